@@ -1,32 +1,57 @@
 //! Build command - generates the static site
 
-use std::{path::Path, time::Instant};
+use std::{
+    fs,
+    path::{Path, PathBuf},
+    time::Instant,
+};
 
-use color_eyre::eyre::{Result, WrapErr};
+use color_eyre::eyre::{Result, WrapErr, bail};
 use typstify_core::Config;
-use typstify_generator::Builder;
+use typstify_generator::{BuildWarning, Builder, WarningKind};
 
 use super::check::quick_validate;
 
+/// Options for the build command.
+#[derive(Debug, Clone, Default)]
+pub struct BuildOptions {
+    /// Include draft posts.
+    pub drafts: bool,
+
+    /// Include pages dated in the future.
+    pub future: bool,
+
+    /// Site host overriding `site.host`.
+    pub host: Option<String>,
+
+    /// Base path overriding `site.base_path`.
+    pub base_path: Option<String>,
+
+    /// Reuse pages unchanged since the last build from the build cache.
+    pub cache: bool,
+
+    /// Fail the build on any warning.
+    pub strict: bool,
+
+    /// File to write the build report (statistics and warnings) to, as
+    /// JSON.
+    pub report: Option<PathBuf>,
+
+    /// Record per-page parse and render times and print the slowest pages.
+    pub timings: bool,
+}
+
+/// Number of pages listed by `--timings`.
+const SLOWEST_PAGES: usize = 20;
+
 /// Run the build command.
 ///
 /// Builds the static site from content files to the output directory.
-pub fn run(
-    config_path: &Path,
-    output: &Path,
-    drafts: bool,
-    host: Option<&str>,
-    base_path: Option<&str>,
-) -> Result<()> {
+/// With `cache`, pages unchanged since the last build are reused from the
+/// build cache. With `strict`, any warning fails the build.
+pub fn run(config_path: &Path, output: &Path, options: &BuildOptions) -> Result<()> {
     let start = Instant::now();
-    tracing::info!(
-        ?config_path,
-        ?output,
-        drafts,
-        ?host,
-        ?base_path,
-        "Starting build"
-    );
+    tracing::info!(?config_path, ?output, ?options, "Starting build");
 
     // Load configuration
     let mut config = Config::load(config_path).wrap_err("Failed to load configuration")?;
@@ -45,17 +70,18 @@ pub fn run(
     // Override output directory if specified
     config.build.output_dir = output.to_string_lossy().to_string();
 
-    // Include drafts if flag is set
-    config.build.drafts = drafts;
+    // Include drafts if flag is set, and future pages if configured or set
+    config.build.drafts = options.drafts;
+    config.build.future |= options.future;
 
     // Override host if specified via CLI
-    if let Some(h) = host {
+    if let Some(h) = &options.host {
         tracing::info!(host = h, "Overriding site host from CLI");
         config.site.host = h.to_string();
     }
 
     // Override base_path if specified via CLI
-    if let Some(bp) = base_path {
+    if let Some(bp) = &options.base_path {
         tracing::info!(base_path = bp, "Overriding site base_path from CLI");
         config.site.base_path = bp.to_string();
     }
@@ -64,7 +90,9 @@ pub fn run(
 
     // Create builder with content and output directories
     let content_dir = Path::new("content");
-    let mut builder = Builder::new(config, content_dir, output);
+    let mut builder = Builder::new(config, content_dir, output)
+        .with_cache(options.cache)
+        .with_timings(options.timings);
 
     // Auto-detect static directory alongside content directory
     let static_dir = Path::new("static");
@@ -73,6 +101,13 @@ pub fn run(
         builder = builder.with_static_dir(static_dir);
     }
 
+    // Site templates override the theme's and the built-in ones
+    let templates_dir = Path::new("templates");
+    if templates_dir.is_dir() {
+        tracing::info!("Found templates directory");
+        builder = builder.with_templates_dir(templates_dir);
+    }
+
     let stats = builder.build().wrap_err("Build failed")?;
 
     let duration = start.elapsed();
@@ -86,12 +121,69 @@ pub fn run(
     println!("  Auto Pages: {}", stats.auto_pages);
     println!("  Redirects:  {}", stats.redirects);
     println!("  Assets:     {}", stats.assets);
+    if stats.cache_hits + stats.cache_misses > 0 {
+        println!(
+            "  Cached:     {} of {} pages",
+            stats.cache_hits,
+            stats.cache_hits + stats.cache_misses
+        );
+    }
+    if !stats.warnings.is_empty() {
+        println!("  Warnings:   {}", stats.warnings.len());
+    }
     println!();
     println!("  Duration:   {:.2}s", duration.as_secs_f64());
+    if let Some(bytes) = stats.peak_rss_bytes {
+        println!("  Peak RSS:   {:.1} MB", bytes as f64 / (1024.0 * 1024.0));
+    }
     println!("  Output:     {}", output.display());
     println!();
 
+    if !stats.warnings.is_empty() {
+        println!("  Warnings:");
+        for warning in &stats.warnings {
+            println!("  ⚠ [{}] {warning}", warning.kind);
+        }
+        println!();
+    }
+
+    if !stats.timings.is_empty() {
+        println!("  Slowest pages:");
+        println!("  {:>11}  {:>11}  {:>11}  Page", "Total", "Parse", "Render");
+        for timing in stats.timings.iter().take(SLOWEST_PAGES) {
+            println!(
+                "  {:>9.1}ms  {:>9.1}ms  {:>9.1}ms  {}",
+                timing.total_ms(),
+                timing.parse_ms,
+                timing.render_ms,
+                timing.url
+            );
+        }
+        println!();
+    }
+
     tracing::info!(?stats, ?duration, "Build completed successfully");
 
+    if let Some(report) = &options.report {
+        let mut report_stats = stats.clone();
+        report_stats.warnings.splice(
+            0..0,
+            warnings.iter().map(|message| BuildWarning {
+                kind: WarningKind::Config,
+                location: None,
+                message: message.clone(),
+            }),
+        );
+        let json =
+            serde_json::to_string_pretty(&report_stats).wrap_err("Failed to serialize report")?;
+        fs::write(report, json)
+            .wrap_err_with(|| format!("Failed to write {}", report.display()))?;
+    }
+
+    let count = warnings.len() + stats.warnings.len();
+    if options.strict && count > 0 {
+        bail!("Build produced {count} warning(s) (--strict)");
+    }
+
     Ok(())
 }
@@ -1,10 +1,27 @@
 //! Check command - validate configuration and content
 
-use std::{collections::HashMap, path::Path};
+use std::{
+    collections::{BTreeMap, HashMap, HashSet},
+    path::{Path, PathBuf},
+    sync::Arc,
+};
 
 use color_eyre::eyre::{Result, bail};
-use typstify_core::Config;
-use typstify_parser::ParserRegistry;
+use typstify_core::{Config, Page, config::Tz};
+use typstify_generator::{
+    ContentCollector, LinkGraph, RobotsGenerator, ShortcodeTemplates, SiteContent, Template,
+    TemplateVariables, audit, shortcodes::SHORTCODES_DIR, themes::THEMES_DIR,
+};
+use typstify_parser::{ParserRegistry, SpellChecker, headings, prose};
+
+/// Project word list accepted by the spelling check.
+const DICTIONARY_FILE: &str = ".typstify-dictionary";
+
+/// Shortest recommended meta description, in characters.
+const MIN_DESCRIPTION_CHARS: usize = 50;
+
+/// Longest meta description shown in full by search engines, in characters.
+const MAX_DESCRIPTION_CHARS: usize = 160;
 
 /// Validation result.
 #[derive(Debug, Default)]
@@ -31,11 +48,34 @@ impl ValidationResult {
     }
 }
 
+/// Options for the check command.
+#[derive(Debug, Clone, Default)]
+pub struct CheckOptions {
+    /// Treat warnings as errors.
+    pub strict: bool,
+
+    /// Audit rendered HTML in the output directory for accessibility issues.
+    pub a11y: bool,
+
+    /// Check rendered HTML in the output directory for well-formedness.
+    pub html: bool,
+
+    /// Check spelling of Markdown content.
+    pub spelling: bool,
+
+    /// Rewrite Markdown files to fix heading structure issues.
+    pub fix: bool,
+
+    /// Report the estimated weight of rendered pages.
+    pub perf: bool,
+}
+
 /// Run the check command.
 ///
 /// Validates configuration and all content files.
-pub fn run(config_path: &Path, strict: bool) -> Result<()> {
-    tracing::info!(?config_path, strict, "Checking configuration and content");
+pub fn run(config_path: &Path, options: &CheckOptions) -> Result<()> {
+    let strict = options.strict;
+    tracing::info!(?config_path, ?options, "Checking configuration and content");
 
     let mut result = ValidationResult::default();
 
@@ -57,7 +97,13 @@ pub fn run(config_path: &Path, strict: bool) -> Result<()> {
     let content_dir = Path::new("content");
     if content_dir.exists() {
         println!("\nChecking content files...");
-        validate_content_files(content_dir, &mut result)?;
+        let timezone = config.as_ref().map_or(Tz::UTC, Config::timezone);
+        validate_content_files(
+            content_dir,
+            timezone,
+            site_shortcodes(config.as_ref()),
+            &mut result,
+        )?;
 
         // Check for multi-language content completeness
         if let Some(ref cfg) = config {
@@ -68,6 +114,13 @@ pub fn run(config_path: &Path, strict: bool) -> Result<()> {
         result.add_warning("Content directory does not exist");
     }
 
+    // Lint the theme's and the site's templates
+    let template_dirs = template_dirs(config.as_ref());
+    if template_dirs.iter().any(|dir| dir.is_dir()) {
+        println!("\nChecking templates...");
+        check_templates(&template_dirs, &mut result);
+    }
+
     // Check required directories
     println!("\nChecking directories...");
     check_directories(&mut result);
@@ -78,6 +131,144 @@ pub fn run(config_path: &Path, strict: bool) -> Result<()> {
         check_config_values(cfg, &mut result);
     }
 
+    // Collect content once for the checks that need rendered page data
+    let site = match config {
+        Some(ref cfg) if content_dir.exists() => {
+            match ContentCollector::new(cfg.clone(), content_dir)
+                .with_shortcodes(site_shortcodes(Some(cfg)))
+                .collect()
+            {
+                Ok(site) => Some(site),
+                Err(e) => {
+                    result.add_warning(format!("Could not collect content: {e}"));
+                    None
+                }
+            }
+        }
+        _ => None,
+    };
+
+    // Check robots.txt rules against the URLs the build would generate
+    if let Some(ref cfg) = config
+        && let Some(ref site) = site
+        && cfg.robots.enabled
+    {
+        println!("\nChecking robots.txt rules...");
+        check_robots_rules(cfg, site, &mut result);
+    }
+
+    // Report orphan and most-linked pages
+    if let Some(ref cfg) = config
+        && let Some(ref site) = site
+    {
+        println!("\nChecking internal links...");
+        check_link_graph(cfg, site, &mut result);
+    }
+
+    // Check titles and meta descriptions
+    if let Some(ref site) = site {
+        println!("\nChecking titles and descriptions...");
+        let pages: Vec<&Page> = site.pages.values().collect();
+        let warnings = check_seo(&pages);
+        if warnings.is_empty() {
+            println!("  ✓ {} pages checked", pages.len());
+        } else {
+            println!("  ⚠ {} title/description issue(s)", warnings.len());
+            for warning in warnings {
+                result.add_warning(warning);
+            }
+        }
+    }
+
+    // Report tags and categories that only differ in case or separators
+    if let Some(ref site) = site {
+        println!("\nChecking taxonomy terms...");
+        let warnings = check_taxonomy_terms(site);
+        if warnings.is_empty() {
+            println!(
+                "  ✓ {} tags and {} categories checked",
+                site.taxonomies.tags.len(),
+                site.taxonomies.categories.len()
+            );
+        } else {
+            println!("  ⚠ {} near-duplicate term group(s)", warnings.len());
+            for warning in warnings {
+                result.add_warning(warning);
+            }
+        }
+    }
+
+    if options.spelling {
+        println!("\nChecking spelling...");
+        match site {
+            Some(ref site) => check_spelling(content_dir, site, &mut result)?,
+            None => result.add_warning("Skipping spelling check: content not collected"),
+        }
+    }
+
+    if let Some(ref cfg) = config
+        && let Some(ref site) = site
+    {
+        println!("\nChecking heading structure...");
+        check_headings(
+            content_dir,
+            site,
+            cfg.check.max_heading_depth,
+            options.fix,
+            &mut result,
+        )?;
+    }
+
+    // Run the configured prose linter
+    if let Some(ref cfg) = config
+        && let Some(ref linter) = cfg.check.prose_linter
+    {
+        println!("\nRunning prose linter...");
+        match site {
+            Some(ref site) => check_prose(linter, content_dir, site, &mut result),
+            None => result.add_warning("Skipping prose linter: content not collected"),
+        }
+    }
+
+    // Audit rendered HTML
+    if options.a11y {
+        println!("\nChecking accessibility of rendered pages...");
+        match config {
+            Some(ref cfg) => audit_rendered_pages(
+                Path::new(&cfg.build.output_dir),
+                "accessibility",
+                audit::check_accessibility,
+                &mut result,
+            ),
+            None => result.add_warning("Skipping accessibility audit: configuration invalid"),
+        }
+    }
+
+    if options.html {
+        println!("\nChecking HTML validity of rendered pages...");
+        match config {
+            Some(ref cfg) => audit_rendered_pages(
+                Path::new(&cfg.build.output_dir),
+                "HTML validity",
+                audit::check_html_validity,
+                &mut result,
+            ),
+            None => result.add_warning("Skipping HTML validity check: configuration invalid"),
+        }
+    }
+
+    if options.perf {
+        println!("\nEstimating page weight of rendered pages...");
+        match config {
+            Some(ref cfg) => check_performance(
+                Path::new(&cfg.build.output_dir),
+                cfg.base_path(),
+                &mut result,
+            ),
+            None => result.add_warning("Skipping performance report: configuration invalid"),
+        }
+    }
+
     // Print summary
     println!();
     println!("Summary:");
@@ -179,9 +370,90 @@ pub fn quick_validate(config: &Config) -> Vec<String> {
     warnings
 }
 
+/// Templates directories of the theme and the site, in override order.
+fn template_dirs(config: Option<&Config>) -> Vec<PathBuf> {
+    let theme = config
+        .and_then(|cfg| cfg.theme.as_ref())
+        .map(|name| Path::new(THEMES_DIR).join(name).join("templates"));
+    theme
+        .into_iter()
+        .chain([PathBuf::from("templates")])
+        .collect()
+}
+
+/// Shortcode templates of the theme and the site, so content using them
+/// is not reported as using unknown shortcodes.
+fn site_shortcodes(config: Option<&Config>) -> ShortcodeTemplates {
+    ShortcodeTemplates::load(&template_dirs(config)).unwrap_or_default()
+}
+
+/// Lint the `*.html` templates in `dirs` and their `shortcodes/`
+/// subdirectories. Syntax problems are errors; variables outside the
+/// documented context of the template name, and documented ones left
+/// unused, are warnings.
+fn check_templates(dirs: &[PathBuf], result: &mut ValidationResult) {
+    let mut files = Vec::new();
+    for dir in dirs {
+        for (dir, shortcode) in [(dir.clone(), false), (dir.join(SHORTCODES_DIR), true)] {
+            let Ok(entries) = std::fs::read_dir(&dir) else {
+                continue;
+            };
+            let mut paths: Vec<PathBuf> = entries
+                .filter_map(|entry| entry.ok().map(|e| e.path()))
+                .filter(|path| path.is_file() && path.extension().is_some_and(|ext| ext == "html"))
+                .collect();
+            paths.sort();
+            files.extend(paths.into_iter().map(|path| (path, shortcode)));
+        }
+    }
+
+    let mut affected = 0;
+    for (path, shortcode) in &files {
+        let Ok(source) = std::fs::read_to_string(path) else {
+            result.add_error(format!("{}: Failed to read file", path.display()));
+            continue;
+        };
+        let name = path.file_stem().unwrap_or_default().to_string_lossy();
+        // Shortcode arguments are whatever the content passes
+        let variables = (!shortcode)
+            .then(|| TemplateVariables::for_template(&name))
+            .flatten();
+        let issues = Template::new(name, source).lint(variables.as_ref());
+        if issues.is_empty() {
+            continue;
+        }
+
+        affected += 1;
+        println!("  ⚠ {}", path.display());
+        for issue in issues {
+            println!("      {issue}");
+            let message = format!("{}: {issue}", path.display());
+            if issue.kind.is_error() {
+                result.add_error(message);
+            } else {
+                result.add_warning(message);
+            }
+        }
+    }
+
+    if affected == 0 {
+        println!("  ✓ All {} templates valid", files.len());
+    } else {
+        println!("  ⚠ {affected}/{} templates have issues", files.len());
+    }
+}
+
 /// Validate all content files in the given directory.
-fn validate_content_files(dir: &Path, result: &mut ValidationResult) -> Result<()> {
-    let registry = ParserRegistry::new();
+fn validate_content_files(
+    dir: &Path,
+    timezone: Tz,
+    shortcodes: ShortcodeTemplates,
+    result: &mut ValidationResult,
+) -> Result<()> {
+    let mut registry = ParserRegistry::new().with_timezone(timezone);
+    if !shortcodes.is_empty() {
+        registry = registry.with_shortcodes(Arc::new(shortcodes));
+    }
     let mut checked = 0;
     let mut failed = 0;
 
@@ -210,7 +482,25 @@ fn validate_content_files(dir: &Path, result: &mut ValidationResult) -> Result<(
             }
         };
 
-        if let Err(e) = registry.parse(&content, path) {
+        // Markdown files also report non-fatal content-quality warnings
+        if ext == "md" {
+            match registry.markdown().parse_with_warnings(&content, path) {
+                Ok((_, warnings)) => {
+                    for warning in warnings {
+                        result.add_warning(format!(
+                            "{}:{}: {}",
+                            path.display(),
+                            warning.line,
+                            warning.message
+                        ));
+                    }
+                }
+                Err(e) => {
+                    result.add_error(format!("{}: Parse error: {e}", path.display()));
+                    failed += 1;
+                }
+            }
+        } else if let Err(e) = registry.parse(&content, path) {
             result.add_error(format!("{}: Parse error: {e}", path.display()));
             failed += 1;
         }
@@ -288,6 +578,558 @@ fn check_config_values(config: &Config, result: &mut ValidationResult) {
     println!("  ✓ Configuration values checked");
 }
 
+/// Validate robots.txt allow/disallow patterns against generated page URLs.
+fn check_robots_rules(config: &Config, content: &SiteContent, result: &mut ValidationResult) {
+    let urls: Vec<&str> = content.pages.keys().map(String::as_str).collect();
+    let warnings = RobotsGenerator::new(config.clone()).validate(&urls);
+
+    if warnings.is_empty() {
+        println!("  ✓ robots.txt rules checked against {} URLs", urls.len());
+    } else {
+        println!("  ⚠ {} robots.txt rule issue(s)", warnings.len());
+        for warning in warnings {
+            result.add_warning(warning);
+        }
+    }
+}
+
+/// Warn about orphan pages and list the most-linked pages.
+fn check_link_graph(config: &Config, content: &SiteContent, result: &mut ValidationResult) {
+    let graph = LinkGraph::build(config, content, |_| true);
+
+    let orphans = graph.orphans(config);
+    if orphans.is_empty() {
+        println!("  ✓ No orphan pages among {} pages", graph.len());
+    } else {
+        println!("  ⚠ {} orphan page(s)", orphans.len());
+        for url in orphans {
+            result.add_warning(format!(
+                "{url}: orphan page (no inbound links and not listed in a section)"
+            ));
+        }
+    }
+
+    let most_linked = graph.most_linked(5);
+    if !most_linked.is_empty() {
+        println!("  Most linked:");
+        for (url, count) in most_linked {
+            println!("    {count:>4}  {url}");
+        }
+    }
+}
+
+/// Find duplicate titles and descriptions (per language) and explicit
+/// descriptions outside the recommended length.
+fn check_seo(pages: &[&Page]) -> Vec<String> {
+    let mut warnings = Vec::new();
+    let mut titles: BTreeMap<(&str, &str), Vec<&str>> = BTreeMap::new();
+    let mut descriptions: BTreeMap<(&str, &str), Vec<&str>> = BTreeMap::new();
+
+    for page in pages {
+        titles
+            .entry((&page.lang, page.title.trim()))
+            .or_default()
+            .push(&page.url);
+
+        let Some(description) = page.description.as_deref().map(str::trim) else {
+            continue;
+        };
+        descriptions
+            .entry((&page.lang, description))
+            .or_default()
+            .push(&page.url);
+
+        let len = description.chars().count();
+        if len < MIN_DESCRIPTION_CHARS {
+            warnings.push(format!(
+                "{}: description is too short ({len} chars, recommended {MIN_DESCRIPTION_CHARS}-{MAX_DESCRIPTION_CHARS})",
+                page.url
+            ));
+        } else if len > MAX_DESCRIPTION_CHARS {
+            warnings.push(format!(
+                "{}: description is too long ({len} chars, recommended {MIN_DESCRIPTION_CHARS}-{MAX_DESCRIPTION_CHARS})",
+                page.url
+            ));
+        }
+    }
+
+    for (kind, groups) in [("title", titles), ("description", descriptions)] {
+        for ((lang, text), mut urls) in groups {
+            if urls.len() > 1 && !text.is_empty() {
+                urls.sort_unstable();
+                warnings.push(format!(
+                    "Duplicate {kind} \"{text}\" ({lang}): {}",
+                    urls.join(", ")
+                ));
+            }
+        }
+    }
+
+    warnings.sort();
+    warnings
+}
+
+/// Find tags (and categories) that differ only in case, spaces, dashes or
+/// underscores (`Rust`, `rust`, `rust-lang`/`rust_lang`), after the
+/// configured aliases are merged.
+fn check_taxonomy_terms(site: &SiteContent) -> Vec<String> {
+    let mut warnings = Vec::new();
+    for (taxonomy, terms) in [
+        ("tags", &site.taxonomies.tags),
+        ("categories", &site.taxonomies.categories),
+    ] {
+        let mut groups: BTreeMap<String, Vec<&str>> = BTreeMap::new();
+        for term in terms.keys() {
+            let key: String = term
+                .chars()
+                .filter(|c| c.is_alphanumeric())
+                .flat_map(char::to_lowercase)
+                .collect();
+            groups.entry(key).or_default().push(term);
+        }
+        for mut group in groups.into_values().filter(|group| group.len() > 1) {
+            group.sort_unstable();
+            warnings.push(format!(
+                "Near-duplicate {taxonomy}: {} (merge them with [taxonomies.{taxonomy}.aliases])",
+                group.join(", ")
+            ));
+        }
+    }
+    warnings
+}
+
+/// Spell check the Markdown sources of English pages.
+///
+/// Words listed in `.typstify-dictionary` (one per line) are accepted in
+/// addition to the bundled dictionary.
+fn check_spelling(
+    content_dir: &Path,
+    site: &SiteContent,
+    result: &mut ValidationResult,
+) -> Result<()> {
+    let mut checker = SpellChecker::new();
+    let dictionary = Path::new(DICTIONARY_FILE);
+    if dictionary.exists() {
+        checker = checker.with_word_list(&std::fs::read_to_string(dictionary)?);
+    }
+
+    let sources = markdown_sources(content_dir, site, true);
+
+    let mut count = 0;
+    for path in &sources {
+        let content = match std::fs::read_to_string(path) {
+            Ok(c) => c,
+            Err(e) => {
+                result.add_warning(format!("{}: Failed to read file: {e}", path.display()));
+                continue;
+            }
+        };
+        for misspelling in checker.check_markdown(&content) {
+            count += 1;
+            result.add_warning(format!(
+                "{}:{}: unknown word '{}'",
+                path.display(),
+                misspelling.line,
+                misspelling.word
+            ));
+        }
+    }
+
+    if count == 0 {
+        println!("  ✓ No spelling issues in {} files", sources.len());
+    } else {
+        println!("  ⚠ {count} unknown word(s); add correct ones to {DICTIONARY_FILE}");
+    }
+
+    Ok(())
+}
+
+/// Markdown source files of the collected pages, optionally limited to
+/// English ones.
+fn markdown_sources(
+    content_dir: &Path,
+    site: &SiteContent,
+    english_only: bool,
+) -> Vec<std::path::PathBuf> {
+    let mut sources: Vec<std::path::PathBuf> = site
+        .pages
+        .values()
+        .filter(|page| !english_only || page.lang == "en" || page.lang.starts_with("en-"))
+        .filter_map(|page| page.source_path.as_deref())
+        .filter(|path| path.extension().is_some_and(|ext| ext == "md"))
+        .map(|path| content_dir.join(path))
+        .collect();
+    sources.sort_unstable();
+    sources.dedup();
+    sources
+}
+
+/// Warn about skipped heading levels, repeated h1s and headings deeper than
+/// `max_depth`, optionally rewriting the files first.
+fn check_headings(
+    content_dir: &Path,
+    site: &SiteContent,
+    max_depth: Option<u8>,
+    fix: bool,
+    result: &mut ValidationResult,
+) -> Result<()> {
+    let sources = markdown_sources(content_dir, site, false);
+    let mut affected = 0;
+    let mut fixed = 0;
+
+    for path in &sources {
+        let Ok(mut content) = std::fs::read_to_string(path) else {
+            continue;
+        };
+        if fix && let Some(rewritten) = headings::fix(&content, max_depth) {
+            std::fs::write(path, &rewritten)?;
+            println!("  ✎ Fixed headings in {}", path.display());
+            content = rewritten;
+            fixed += 1;
+        }
+
+        let issues = headings::lint(&content, max_depth);
+        if !issues.is_empty() {
+            affected += 1;
+        }
+        for issue in issues {
+            result.add_warning(format!(
+                "{}:{}: {}",
+                path.display(),
+                issue.line,
+                issue.message
+            ));
+        }
+    }
+
+    if affected == 0 {
+        println!("  ✓ Heading structure checked in {} files", sources.len());
+    } else {
+        println!("  ⚠ {affected}/{} files have heading issues", sources.len());
+        if !fix {
+            println!("    Run `typstify check --fix` to rewrite them");
+        }
+    }
+    if fixed > 0 {
+        println!("  ✓ Fixed {fixed} file(s)");
+    }
+
+    Ok(())
+}
+
+/// Run the external prose linter over the plain text of every Markdown page.
+fn check_prose(
+    linter: &str,
+    content_dir: &Path,
+    site: &SiteContent,
+    result: &mut ValidationResult,
+) {
+    let sources = markdown_sources(content_dir, site, false);
+    let mut count = 0;
+
+    for path in &sources {
+        let content = match std::fs::read_to_string(path) {
+            Ok(c) => c,
+            Err(e) => {
+                result.add_warning(format!("{}: Failed to read file: {e}", path.display()));
+                continue;
+            }
+        };
+        match run_prose_linter(linter, path, &prose::plain_text(&content)) {
+            Ok(diagnostics) => {
+                for (line, message) in diagnostics {
+                    count += 1;
+                    result.add_warning(format!("{}:{line}: {message}", path.display()));
+                }
+            }
+            Err(e) => {
+                result.add_warning(format!("{}: prose linter failed: {e}", path.display()));
+                // A missing or broken linter fails the same way for every file
+                break;
+            }
+        }
+    }
+
+    if count == 0 {
+        println!("  ✓ No prose issues in {} files", sources.len());
+    } else {
+        println!("  ⚠ {count} prose issue(s)");
+    }
+}
+
+/// Run a prose linter command on `text`, returning `(line, message)` pairs.
+///
+/// Linters commonly exit non-zero when they report issues, so the exit
+/// status only counts as a failure when nothing could be parsed and the
+/// linter wrote an error.
+fn run_prose_linter(
+    command: &str,
+    source: &Path,
+    text: &str,
+) -> std::result::Result<Vec<(usize, String)>, String> {
+    use std::io::Write;
+
+    let input = std::env::temp_dir().join(format!("typstify-prose-{}.txt", std::process::id()));
+    let uses_file = command.contains("{input}");
+    if uses_file {
+        std::fs::write(&input, text)
+            .map_err(|e| format!("failed to write {}: {e}", input.display()))?;
+    }
+
+    let input_str = input.to_string_lossy();
+    let source_str = source.to_string_lossy();
+    let mut args = command.split_whitespace().map(|arg| {
+        arg.replace("{input}", &input_str)
+            .replace("{source}", &source_str)
+    });
+    let program = args.next().ok_or("empty command")?;
+
+    let output = std::process::Command::new(&program)
+        .args(args)
+        .stdin(std::process::Stdio::piped())
+        .stdout(std::process::Stdio::piped())
+        .stderr(std::process::Stdio::piped())
+        .spawn()
+        .and_then(|mut child| {
+            if !uses_file && let Some(mut stdin) = child.stdin.take() {
+                stdin.write_all(text.as_bytes())?;
+            }
+            child.wait_with_output()
+        });
+    if uses_file {
+        let _ = std::fs::remove_file(&input);
+    }
+    let output = output.map_err(|e| format!("failed to run `{program}`: {e}"))?;
+
+    let diagnostics: Vec<_> = String::from_utf8_lossy(&output.stdout)
+        .lines()
+        .filter_map(parse_diagnostic)
+        .collect();
+
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    if diagnostics.is_empty() && !output.status.success() && !stderr.trim().is_empty() {
+        return Err(format!(
+            "`{program}` exited with {}: {}",
+            output.status,
+            stderr.trim()
+        ));
+    }
+
+    Ok(diagnostics)
+}
+
+/// Parse a `[file:]line[:column]:message` diagnostic.
+fn parse_diagnostic(line: &str) -> Option<(usize, String)> {
+    let parts: Vec<&str> = line.split(':').collect();
+    let at = parts
+        .iter()
+        .position(|p| !p.is_empty() && p.bytes().all(|b| b.is_ascii_digit()))?;
+    let number = parts[at].parse().ok()?;
+    let mut rest = &parts[at + 1..];
+    if rest.len() > 1 && rest[0].bytes().all(|b| b.is_ascii_digit()) {
+        rest = &rest[1..];
+    }
+    let message = rest.join(":").trim().to_string();
+    (!message.is_empty()).then_some((number, message))
+}
+
+/// Collect all rendered HTML files under the output directory.
+fn rendered_pages(output_dir: &Path) -> Vec<std::path::PathBuf> {
+    walkdir::WalkDir::new(output_dir)
+        .into_iter()
+        .filter_map(|e| e.ok())
+        .filter(|e| e.file_type().is_file())
+        .filter(|e| e.path().extension().is_some_and(|ext| ext == "html"))
+        .map(|e| e.into_path())
+        .collect()
+}
+
+/// Run an audit over every rendered page in the output directory.
+///
+/// Every issue is reported as a warning, so `--strict` fails the check.
+fn audit_rendered_pages(
+    output_dir: &Path,
+    label: &str,
+    check: fn(&str) -> Vec<audit::AuditIssue>,
+    result: &mut ValidationResult,
+) {
+    if !output_dir.exists() {
+        result.add_warning(format!(
+            "Output directory {} does not exist; run `typstify build` before the {label} check",
+            output_dir.display()
+        ));
+        return;
+    }
+
+    let pages = rendered_pages(output_dir);
+    let mut affected = 0;
+
+    for path in &pages {
+        let Ok(html) = std::fs::read_to_string(path) else {
+            continue;
+        };
+        let issues = check(&html);
+        if issues.is_empty() {
+            continue;
+        }
+
+        affected += 1;
+        println!("  ⚠ {}", path.display());
+        for issue in issues {
+            println!("      {issue}");
+            result.add_warning(format!("{}: {issue}", path.display()));
+        }
+    }
+
+    if affected == 0 {
+        println!("  ✓ No {label} issues in {} pages", pages.len());
+    } else {
+        println!("  ⚠ {affected}/{} pages have {label} issues", pages.len());
+    }
+}
+
+/// Estimated transfer size of a rendered page and the resources it loads.
+#[derive(Debug, Default)]
+struct PageWeight {
+    html: u64,
+    css: u64,
+    js: u64,
+    images: u64,
+    render_blocking: usize,
+    /// Resources on other hosts, which are not measured.
+    external: usize,
+}
+
+impl PageWeight {
+    fn total(&self) -> u64 {
+        self.html + self.css + self.js + self.images
+    }
+}
+
+/// Number of pages listed in the performance report.
+const HEAVIEST_PAGES: usize = 10;
+
+/// Estimate the weight of a rendered page from the sizes of its HTML and the
+/// local stylesheets, scripts and images it references. Each resource is
+/// counted once per page.
+fn page_weight(output_dir: &Path, page: &Path, html: &str, base_path: &str) -> PageWeight {
+    let resources = audit::page_resources(html);
+    let mut seen = HashSet::new();
+    let mut external = 0;
+    let mut measure = |urls: &[String]| -> u64 {
+        urls.iter()
+            .filter(|url| seen.insert(url.to_string()))
+            .filter_map(|url| {
+                let path = resolve_resource(output_dir, page, url, base_path);
+                if path.is_none() && (url.contains("://") || url.starts_with("//")) {
+                    external += 1;
+                }
+                std::fs::metadata(path?).ok().map(|m| m.len())
+            })
+            .sum()
+    };
+
+    let css = measure(&resources.stylesheets);
+    let js = measure(&resources.scripts);
+    let images = measure(&resources.images);
+    PageWeight {
+        html: html.len() as u64,
+        css,
+        js,
+        images,
+        render_blocking: resources.render_blocking.len(),
+        external,
+    }
+}
+
+/// Map a resource URL on a rendered page to its file in the output
+/// directory. External and `data:` URLs resolve to `None`.
+fn resolve_resource(
+    output_dir: &Path,
+    page: &Path,
+    url: &str,
+    base_path: &str,
+) -> Option<std::path::PathBuf> {
+    if url.contains("://") || url.starts_with("//") || url.starts_with("data:") {
+        return None;
+    }
+    let url = url.split(['?', '#']).next().unwrap_or_default();
+    if url.is_empty() {
+        return None;
+    }
+
+    let path = if url.starts_with('/') {
+        let local = url
+            .strip_prefix(base_path)
+            .filter(|rest| rest.starts_with('/'))
+            .unwrap_or(url);
+        output_dir.join(local.trim_start_matches('/'))
+    } else {
+        page.parent()?.join(url)
+    };
+    path.is_file().then_some(path)
+}
+
+/// Format a byte count as KB.
+fn kilobytes(bytes: u64) -> String {
+    format!("{:.1} KB", bytes as f64 / 1024.0)
+}
+
+/// Report the estimated weight and render-blocking resources of every
+/// rendered page, listing the heaviest.
+fn check_performance(output_dir: &Path, base_path: &str, result: &mut ValidationResult) {
+    if !output_dir.exists() {
+        result.add_warning(format!(
+            "Output directory {} does not exist; run `typstify build` before the performance report",
+            output_dir.display()
+        ));
+        return;
+    }
+
+    let mut weights: Vec<(std::path::PathBuf, PageWeight)> = rendered_pages(output_dir)
+        .into_iter()
+        .filter_map(|path| {
+            let html = std::fs::read_to_string(&path).ok()?;
+            let weight = page_weight(output_dir, &path, &html, base_path);
+            Some((path, weight))
+        })
+        .collect();
+    if weights.is_empty() {
+        println!("  ✓ No rendered pages");
+        return;
+    }
+    weights.sort_by(|a, b| b.1.total().cmp(&a.1.total()).then(a.0.cmp(&b.0)));
+
+    let total: u64 = weights.iter().map(|(_, w)| w.total()).sum();
+    let blocking: usize = weights.iter().map(|(_, w)| w.render_blocking).sum();
+    println!(
+        "  {} pages, average {}, {:.1} render-blocking resources per page",
+        weights.len(),
+        kilobytes(total / weights.len() as u64),
+        blocking as f64 / weights.len() as f64
+    );
+
+    println!("  Heaviest pages:");
+    for (path, weight) in weights.iter().take(HEAVIEST_PAGES) {
+        let page = path.strip_prefix(output_dir).unwrap_or(path);
+        let external = if weight.external > 0 {
+            format!(", {} external not measured", weight.external)
+        } else {
+            String::new()
+        };
+        println!(
+            "    {:>10}  {}  (HTML {}, CSS {}, JS {}, images {}, {} render-blocking{external})",
+            kilobytes(weight.total()),
+            page.display(),
+            kilobytes(weight.html),
+            kilobytes(weight.css),
+            kilobytes(weight.js),
+            kilobytes(weight.images),
+            weight.render_blocking
+        );
+    }
+}
+
 /// Validate multi-language content completeness.
 ///
 /// Checks that important content files exist for all configured languages.
@@ -413,3 +1255,176 @@ fn parse_content_file(
     // No language suffix means default language
     Some((without_ext.to_string(), default_lang.to_string()))
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn page(url: &str, lang: &str, title: &str, description: Option<&str>) -> Page {
+        Page {
+            url: url.to_string(),
+            title: title.to_string(),
+            description: description.map(str::to_string),
+            date: None,
+            updated: None,
+            expiry_date: None,
+            draft: false,
+            lang: lang.to_string(),
+            is_default_lang: lang == "en",
+            canonical_id: url.trim_start_matches('/').to_string(),
+            tags: vec![],
+            categories: vec![],
+            content: String::new(),
+            summary: None,
+            reading_time: None,
+            word_count: None,
+            toc: vec![],
+            custom_js: vec![],
+            custom_css: vec![],
+            aliases: vec![],
+            template: None,
+            weight: 0,
+            pinned: false,
+            series: None,
+            content_id: None,
+            extra_meta: std::collections::BTreeMap::new(),
+            structured_data: None,
+            images: vec![],
+            event: None,
+            source_path: None,
+        }
+    }
+
+    #[test]
+    fn test_parse_diagnostic() {
+        assert_eq!(
+            parse_diagnostic("stdin.md:3:7:Vale.Terms:Use 'JavaScript' instead"),
+            Some((3, "Vale.Terms:Use 'JavaScript' instead".to_string()))
+        );
+        assert_eq!(
+            parse_diagnostic("/tmp/page.txt:12: passive voice"),
+            Some((12, "passive voice".to_string()))
+        );
+        assert_eq!(parse_diagnostic("3 issues found"), None);
+        assert_eq!(
+            parse_diagnostic("8:1:too wordy"),
+            Some((8, "too wordy".to_string()))
+        );
+    }
+
+    #[test]
+    fn test_run_prose_linter() {
+        let text = "Fine line.\nTODO: write this.\n";
+
+        let from_file = run_prose_linter("grep -Hn TODO {input}", Path::new("a.md"), text).unwrap();
+        assert_eq!(from_file, vec![(2, "TODO: write this.".to_string())]);
+
+        let from_stdin = run_prose_linter("grep -n TODO", Path::new("a.md"), text).unwrap();
+        assert_eq!(from_stdin, from_file);
+
+        let clean = run_prose_linter("grep -n FIXME", Path::new("a.md"), text).unwrap();
+        assert!(clean.is_empty());
+
+        assert!(run_prose_linter("grep -n TODO /nonexistent", Path::new("a.md"), text).is_err());
+        assert!(run_prose_linter("typstify-no-such-linter", Path::new("a.md"), text).is_err());
+    }
+
+    #[test]
+    fn test_page_weight() {
+        let dir = std::env::temp_dir().join(format!("typstify-perf-{}", std::process::id()));
+        std::fs::create_dir_all(dir.join("posts/hello")).unwrap();
+        std::fs::write(dir.join("style.css"), "a".repeat(300)).unwrap();
+        std::fs::write(dir.join("app.js"), "b".repeat(200)).unwrap();
+        std::fs::write(dir.join("posts/hello/cover.png"), "c".repeat(100)).unwrap();
+
+        let page = dir.join("posts/hello/index.html");
+        let html = r#"<html><head><link rel="stylesheet" href="/blog/style.css?v=1"><script src="/blog/app.js"></script><script src="https://cdn.example.com/x.js" defer></script></head><body><img src="cover.png" alt=""><img src="cover.png" alt=""></body></html>"#;
+        let weight = page_weight(&dir, &page, html, "/blog");
+        std::fs::remove_dir_all(&dir).unwrap();
+
+        assert_eq!(weight.css, 300);
+        assert_eq!(weight.js, 200);
+        assert_eq!(weight.images, 100);
+        assert_eq!(weight.total(), html.len() as u64 + 600);
+        assert_eq!(weight.render_blocking, 2);
+        assert_eq!(weight.external, 1);
+    }
+
+    #[test]
+    fn test_check_templates() {
+        let dir = std::env::temp_dir().join(format!("typstify-templates-{}", std::process::id()));
+        std::fs::create_dir_all(dir.join("shortcodes")).unwrap();
+        std::fs::write(dir.join("post.html"), "<h1>{{ title }}</h1>{{ subtitle }}").unwrap();
+        std::fs::write(dir.join("widget.html"), "<p>{{ title | upper }}</p>").unwrap();
+        std::fs::write(dir.join("shortcodes/note.html"), "<aside>{{ 0 }</aside>").unwrap();
+
+        let mut result = ValidationResult::default();
+        check_templates(std::slice::from_ref(&dir), &mut result);
+        std::fs::remove_dir_all(&dir).unwrap();
+
+        assert_eq!(result.errors.len(), 2, "{:#?}", result.errors);
+        assert!(result.errors[0].contains("widget.html: line 1"));
+        assert!(result.errors[1].contains("note.html: line 1 (offset 7): [unclosed]"));
+        // `subtitle` is unknown, and `content` is never used
+        assert_eq!(result.warnings.len(), 2, "{:#?}", result.warnings);
+        assert!(
+            result
+                .warnings
+                .iter()
+                .all(|w| w.contains("post.html: line "))
+        );
+    }
+
+    #[test]
+    fn test_check_seo() {
+        let good = "A description that is comfortably within the recommended length.";
+        let pages = [
+            page("/a", "en", "Hello", Some(good)),
+            page("/b", "en", "Hello", Some(good)),
+            page("/zh/a", "zh", "Hello", Some("太短")),
+            page("/c", "en", "Unique", Some(&"x".repeat(200))),
+        ];
+        let refs: Vec<&Page> = pages.iter().collect();
+
+        let warnings = check_seo(&refs);
+
+        assert_eq!(warnings.len(), 4, "{warnings:#?}");
+        assert!(warnings.contains(&"Duplicate title \"Hello\" (en): /a, /b".to_string()));
+        assert!(
+            warnings
+                .iter()
+                .any(|w| w.starts_with("Duplicate description"))
+        );
+        assert!(
+            warnings
+                .iter()
+                .any(|w| w.starts_with("/zh/a: description is too short"))
+        );
+        assert!(
+            warnings
+                .iter()
+                .any(|w| w.starts_with("/c: description is too long"))
+        );
+    }
+
+    #[test]
+    fn test_check_taxonomy_terms() {
+        let mut site = SiteContent::default();
+        for tag in ["Rust", "rust", "rust-lang", "rust_lang", "web"] {
+            site.taxonomies
+                .tags
+                .insert(tag.to_string(), vec!["/a".to_string()]);
+        }
+        site.taxonomies
+            .categories
+            .insert("Notes".to_string(), vec!["/a".to_string()]);
+
+        assert_eq!(
+            check_taxonomy_terms(&site),
+            [
+                "Near-duplicate tags: Rust, rust (merge them with [taxonomies.tags.aliases])",
+                "Near-duplicate tags: rust-lang, rust_lang (merge them with [taxonomies.tags.aliases])",
+            ]
+        );
+    }
+}
@@ -0,0 +1,130 @@
+//! Comment command - summarizes a preview build for a pull request comment
+
+use std::{fmt::Write, fs, path::Path};
+
+use color_eyre::eyre::{Result, WrapErr};
+use typstify_generator::{BuildStats, diff::OutputDiff};
+
+/// Pages listed per section of the comment; the rest are counted.
+const MAX_PAGES: usize = 50;
+
+/// Run the comment command.
+///
+/// Compares the pages built into `old` and `new` and prints a Markdown
+/// summary for a pull request comment: the added, changed and removed
+/// pages, linked under `base_url` (the preview's address) when it is given,
+/// and the warnings of the build report `report` written by
+/// `typstify build --report`.
+pub fn run(old: &Path, new: &Path, report: Option<&Path>, base_url: Option<&str>) -> Result<()> {
+    let mut diff = OutputDiff::compare(old, new).wrap_err("Failed to compare the builds")?;
+    if let Some(base_url) = base_url {
+        diff.prefix_urls(base_url);
+    }
+    let stats = report
+        .map(|path| -> Result<BuildStats> {
+            let json = fs::read_to_string(path)
+                .wrap_err_with(|| format!("Failed to read {}", path.display()))?;
+            serde_json::from_str(&json).wrap_err("Failed to parse the build report")
+        })
+        .transpose()?;
+
+    print!("{}", comment_markdown(&diff, stats.as_ref()));
+    Ok(())
+}
+
+/// Markdown summary of `diff` and the build `stats`.
+pub fn comment_markdown(diff: &OutputDiff, stats: Option<&BuildStats>) -> String {
+    let mut markdown = String::from("### Preview\n\n");
+    let plural = |count: usize, noun: &str| {
+        if count == 1 {
+            format!("{count} {noun}")
+        } else {
+            format!("{count} {noun}s")
+        }
+    };
+
+    let mut summary = vec![
+        format!("**{}**", plural(diff.changed.len(), "changed page")),
+        format!("{} added", diff.added.len()),
+        format!("{} removed", diff.removed.len()),
+    ];
+    if let Some(stats) = stats {
+        summary.push(plural(stats.warnings.len(), "warning"));
+    }
+    let _ = writeln!(markdown, "{}\n", summary.join(" · "));
+
+    for (label, urls) in [
+        ("Changed", &diff.changed),
+        ("Added", &diff.added),
+        ("Removed", &diff.removed),
+    ] {
+        if urls.is_empty() {
+            continue;
+        }
+        let _ = writeln!(markdown, "#### {label}\n");
+        for url in urls.iter().take(MAX_PAGES) {
+            // Removed pages are gone from the preview
+            if label == "Removed" || !url.contains("://") {
+                let _ = writeln!(markdown, "- `{url}`");
+            } else {
+                let _ = writeln!(markdown, "- [{url}]({url})");
+            }
+        }
+        if urls.len() > MAX_PAGES {
+            let _ = writeln!(markdown, "- … and {} more", urls.len() - MAX_PAGES);
+        }
+        markdown.push('\n');
+    }
+
+    if let Some(stats) = stats.filter(|stats| !stats.warnings.is_empty()) {
+        let _ = writeln!(
+            markdown,
+            "<details>\n<summary>Warnings ({})</summary>\n",
+            stats.warnings.len()
+        );
+        for warning in &stats.warnings {
+            let _ = writeln!(markdown, "- **{}** {warning}", warning.kind);
+        }
+        markdown.push_str("\n</details>\n");
+    }
+    markdown
+}
+
+#[cfg(test)]
+mod tests {
+    use typstify_generator::{BuildWarning, WarningKind};
+
+    use super::*;
+
+    #[test]
+    fn test_comment_markdown() {
+        let mut diff = OutputDiff {
+            added: vec!["/posts/new/".to_string()],
+            removed: vec!["/posts/old/".to_string()],
+            changed: (0..52).map(|i| format!("/posts/{i}/")).collect(),
+        };
+        diff.prefix_urls("https://pr-1.example.com");
+        let stats = BuildStats {
+            warnings: vec![BuildWarning {
+                kind: WarningKind::Asset,
+                location: Some("/posts/new".to_string()),
+                message: "missing image a.png".to_string(),
+            }],
+            ..BuildStats::default()
+        };
+
+        let markdown = comment_markdown(&diff, Some(&stats));
+        assert!(markdown.starts_with(
+            "### Preview\n\n**52 changed pages** · 1 added · 1 removed · 1 warning\n\n#### Changed\n\n- [https://pr-1.example.com/posts/0/](https://pr-1.example.com/posts/0/)\n"
+        ));
+        assert!(markdown.contains("- … and 2 more\n"));
+        assert!(markdown.contains("#### Removed\n\n- `https://pr-1.example.com/posts/old/`\n"));
+        assert!(markdown.contains("- **asset** /posts/new: missing image a.png\n"));
+
+        let empty = comment_markdown(&OutputDiff::default(), None);
+        assert_eq!(
+            empty,
+            "### Preview\n\n**0 changed pages** · 0 added · 0 removed\n\n"
+        );
+    }
+}
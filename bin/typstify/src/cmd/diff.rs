@@ -0,0 +1,46 @@
+//! Diff command - lists the pages that differ between two builds
+
+use std::path::Path;
+
+use color_eyre::eyre::{Result, WrapErr};
+use typstify_generator::diff::OutputDiff;
+
+/// Run the diff command.
+///
+/// Compares the pages built into `old` and `new`, ignoring formatting, and
+/// prints the added, removed and changed ones, or with `json` the lists as
+/// JSON. URLs are prefixed with `base_url`, such as the preview's address,
+/// when it is given.
+pub fn run(old: &Path, new: &Path, base_url: Option<&str>, json: bool) -> Result<()> {
+    let mut diff = OutputDiff::compare(old, new).wrap_err("Failed to compare the builds")?;
+    if let Some(base_url) = base_url {
+        diff.prefix_urls(base_url);
+    }
+
+    if json {
+        println!(
+            "{}",
+            serde_json::to_string_pretty(&diff).wrap_err("Failed to serialize the diff")?
+        );
+        return Ok(());
+    }
+
+    if diff.is_empty() {
+        println!("  No pages changed");
+        return Ok(());
+    }
+    for (label, urls) in [
+        ("Added", &diff.added),
+        ("Removed", &diff.removed),
+        ("Changed", &diff.changed),
+    ] {
+        if urls.is_empty() {
+            continue;
+        }
+        println!("  {label} ({}):", urls.len());
+        for url in urls {
+            println!("    {url}");
+        }
+    }
+    Ok(())
+}
@@ -0,0 +1,63 @@
+//! Fmt command - normalizes the frontmatter and whitespace of content files
+
+use std::{fs, path::Path};
+
+use color_eyre::eyre::{Result, WrapErr, bail};
+use typstify_core::{Config, ContentType, format};
+
+/// Run the fmt command.
+///
+/// Formats the Markdown and Typst files of the content directory as
+/// configured in `[fmt]`: frontmatter keys in order, dates in one format,
+/// tags recased and trailing whitespace removed. With `check`, files are
+/// left as they are; the ones that are not formatted are listed and the
+/// command fails, for CI.
+pub fn run(config_path: &Path, check: bool) -> Result<()> {
+    let config = Config::load(config_path).wrap_err("Failed to load configuration")?;
+    let content_dir = Path::new("content");
+
+    let mut files = 0;
+    let mut unformatted = Vec::new();
+    for entry in walkdir::WalkDir::new(content_dir)
+        .sort_by_file_name()
+        .into_iter()
+        .filter_map(|e| e.ok())
+        .filter(|e| e.file_type().is_file())
+    {
+        let path = entry.path();
+        let Some(content_type) = path
+            .extension()
+            .and_then(|ext| ContentType::from_extension(&ext.to_string_lossy()))
+        else {
+            continue;
+        };
+        files += 1;
+        let content = fs::read_to_string(path)
+            .wrap_err_with(|| format!("Failed to read {}", path.display()))?;
+        let Some(formatted) = format::format(&content, content_type, &config.fmt) else {
+            continue;
+        };
+
+        if check {
+            println!("  ✗ {}", path.display());
+        } else {
+            fs::write(path, formatted)
+                .wrap_err_with(|| format!("Failed to write {}", path.display()))?;
+            println!("  ✎ Formatted {}", path.display());
+        }
+        unformatted.push(path.to_path_buf());
+    }
+
+    if check && !unformatted.is_empty() {
+        bail!(
+            "{}/{files} files are not formatted; run `typstify fmt` to fix them",
+            unformatted.len()
+        );
+    }
+    if unformatted.is_empty() {
+        println!("  ✓ {files} files already formatted");
+    } else {
+        println!("  ✓ Formatted {}/{files} files", unformatted.len());
+    }
+    Ok(())
+}
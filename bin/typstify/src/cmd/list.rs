@@ -0,0 +1,43 @@
+//! List command - prints the content inventory
+
+use std::path::Path;
+
+use color_eyre::eyre::{Result, WrapErr};
+use typstify_core::Config;
+use typstify_generator::{ContentCollector, inventory};
+
+/// Run the list command.
+///
+/// Prints every page of the site, newest first, with its date, language
+/// and URL, or with `json` the inventory as JSON. Drafts are only listed
+/// with `drafts`.
+pub fn run(config_path: &Path, drafts: bool, json: bool) -> Result<()> {
+    let mut config = Config::load(config_path).wrap_err("Failed to load configuration")?;
+    config.build.drafts = drafts;
+
+    let content = ContentCollector::new(config, "content")
+        .collect()
+        .wrap_err("Failed to collect content")?;
+    let entries = inventory::inventory(&content);
+
+    if json {
+        println!(
+            "{}",
+            serde_json::to_string_pretty(&entries).wrap_err("Failed to serialize inventory")?
+        );
+        return Ok(());
+    }
+
+    for entry in &entries {
+        let date = entry
+            .date
+            .map_or_else(|| " ".repeat(10), |d| d.format("%Y-%m-%d").to_string());
+        let draft = if entry.draft { " (draft)" } else { "" };
+        println!(
+            "  {date}  {:<5} {}{draft}  {}",
+            entry.lang, entry.title, entry.url
+        );
+    }
+    println!("\n  {} pages", entries.len());
+    Ok(())
+}
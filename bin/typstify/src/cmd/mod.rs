@@ -2,5 +2,11 @@
 
 pub mod build;
 pub mod check;
+pub mod comment;
+pub mod diff;
+pub mod fmt;
+pub mod list;
 pub mod new;
+pub mod ping;
+pub mod theme;
 pub mod watch;
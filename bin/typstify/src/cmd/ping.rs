@@ -0,0 +1,50 @@
+//! Ping command - notifies WebSub hubs and search engines after a deploy
+
+use std::path::Path;
+
+use color_eyre::eyre::{Result, WrapErr, bail};
+use typstify_core::Config;
+use typstify_generator::ping::{self, Ping, Pinger};
+
+/// Run the ping command.
+///
+/// Sends the pings configured under `[ping]` for the site built into
+/// `output`: WebSub publish requests for its feeds and sitemap pings. With
+/// `dry_run`, only lists them.
+pub fn run(config_path: &Path, output: &Path, dry_run: bool) -> Result<()> {
+    let config = Config::load(config_path).wrap_err("Failed to load configuration")?;
+    let pings = ping::pings(&config, output);
+    if pings.is_empty() {
+        println!(
+            "No pings to send: configure [ping] and build into {}",
+            output.display()
+        );
+        return Ok(());
+    }
+
+    let pinger = Pinger::new();
+    let mut failed = 0;
+    for ping in &pings {
+        let label = match ping {
+            Ping::WebSub { hub, topic } => format!("WebSub {hub} ({topic})"),
+            Ping::Sitemap { url } => format!("Sitemap {url}"),
+        };
+        if dry_run {
+            println!("  - {label}");
+            continue;
+        }
+        match pinger.send(ping) {
+            Ok(()) => println!("  ✓ {label}"),
+            Err(e) => {
+                failed += 1;
+                tracing::debug!(error = %e, "ping failed");
+                println!("  ✗ {label}: {e}");
+            }
+        }
+    }
+
+    if failed > 0 {
+        bail!("{failed} of {} pings failed", pings.len());
+    }
+    Ok(())
+}
@@ -0,0 +1,54 @@
+//! Theme command - install and list themes
+
+use std::path::Path;
+
+use color_eyre::eyre::{Result, WrapErr};
+use typstify_generator::themes::{self, THEMES_DIR};
+
+/// Install the theme repository at `url` into `themes/`.
+pub fn install(url: &str, name: Option<&str>) -> Result<()> {
+    tracing::info!(url, ?name, "Installing theme");
+
+    let theme = themes::install(url, Path::new(THEMES_DIR), name)
+        .wrap_err_with(|| format!("Failed to install theme from {url}"))?;
+    let dir_name = theme
+        .root()
+        .file_name()
+        .map(|name| name.to_string_lossy().into_owned())
+        .unwrap_or_default();
+
+    println!("Installed: {} ({})", theme.name(), theme.root().display());
+    println!();
+    println!("  Enable it in your config:");
+    println!("  theme = \"{dir_name}\"");
+
+    Ok(())
+}
+
+/// List the themes installed in `themes/`.
+pub fn list() -> Result<()> {
+    let installed = themes::list(Path::new(THEMES_DIR)).wrap_err("Failed to list themes")?;
+    if installed.is_empty() {
+        println!("No themes installed in {THEMES_DIR}/");
+        return Ok(());
+    }
+
+    for theme in &installed {
+        let meta = theme.meta();
+        let version = meta
+            .version
+            .as_deref()
+            .map_or(String::new(), |v| format!(" {v}"));
+        let description = meta
+            .description
+            .as_deref()
+            .map_or(String::new(), |d| format!(" - {d}"));
+        println!(
+            "  {}{version}{description} ({})",
+            theme.name(),
+            theme.root().display()
+        );
+    }
+
+    Ok(())
+}
@@ -10,7 +10,7 @@ use color_eyre::eyre::{Result, WrapErr};
 use notify::{EventKind, RecommendedWatcher, RecursiveMode, Watcher, event::ModifyKind};
 use tokio::{net::TcpListener, sync::mpsc};
 use typstify_core::Config;
-use typstify_generator::{BuildStats, Builder};
+use typstify_generator::{BuildStats, Builder, themes::THEMES_DIR};
 
 use super::check::quick_validate;
 use crate::server::{LIVERELOAD_SCRIPT, ServerState, create_router};
@@ -38,15 +38,18 @@ pub async fn run(config_path: &Path, port: u16, open_browser: bool) -> Result<()
         println!();
     }
 
-    // Enable drafts in development mode
+    // Enable drafts, future posts and search miss recording in development
+    // mode
     config.build.drafts = true;
+    config.build.future = true;
+    config.search.record_misses = true;
 
     let output_dir = Path::new(&config.build.output_dir).to_path_buf();
     let content_dir_path = Path::new("content").to_path_buf();
 
     // Initial build
     tracing::info!("Running initial build...");
-    let mut builder = Builder::new(config.clone(), &content_dir_path, &output_dir);
+    let mut builder = Builder::new(config.clone(), &content_dir_path, &output_dir).with_cache(true);
 
     // Auto-detect static directory alongside content directory
     let static_dir_path = Path::new("static").to_path_buf();
@@ -54,6 +57,10 @@ pub async fn run(config_path: &Path, port: u16, open_browser: bool) -> Result<()
         tracing::info!("Found static directory, will copy to output");
         builder = builder.with_static_dir(&static_dir_path);
     }
+    let templates_dir = Path::new("templates").to_path_buf();
+    if templates_dir.is_dir() {
+        builder = builder.with_templates_dir(&templates_dir);
+    }
 
     let stats = inject_livereload_and_build(&builder, &output_dir)?;
     print_build_stats(&stats);
@@ -66,7 +73,10 @@ pub async fn run(config_path: &Path, port: u16, open_browser: bool) -> Result<()
     let watcher_tx = tx.clone();
 
     let content_dir = Path::new("content").to_path_buf();
-    let templates_dir = Path::new("templates").to_path_buf();
+    let theme_dir = config
+        .theme
+        .as_ref()
+        .map(|name| Path::new(THEMES_DIR).join(name));
     let style_dir = Path::new("style").to_path_buf();
 
     let mut watcher = RecommendedWatcher::new(
@@ -100,12 +110,42 @@ pub async fn run(config_path: &Path, port: u16, open_browser: bool) -> Result<()
             .wrap_err("Failed to watch templates directory")?;
         tracing::debug!("Watching templates directory");
     }
+    if let Some(theme_dir) = theme_dir.as_ref().filter(|dir| dir.exists()) {
+        watcher
+            .watch(theme_dir, RecursiveMode::Recursive)
+            .wrap_err("Failed to watch theme directory")?;
+        tracing::debug!("Watching theme directory");
+    }
     if style_dir.exists() {
         watcher
             .watch(&style_dir, RecursiveMode::Recursive)
             .wrap_err("Failed to watch style directory")?;
         tracing::debug!("Watching style directory");
     }
+    let css_input = Path::new(&config.css.input);
+    if config.css.command(false).is_some()
+        && css_input.exists()
+        && !css_input.starts_with(&style_dir)
+    {
+        watcher
+            .watch(css_input, RecursiveMode::NonRecursive)
+            .wrap_err("Failed to watch css.input")?;
+        tracing::debug!("Watching css.input");
+    }
+    let syntaxes_dir = Path::new(&config.build.syntaxes_dir);
+    if syntaxes_dir.is_dir() {
+        watcher
+            .watch(syntaxes_dir, RecursiveMode::NonRecursive)
+            .wrap_err("Failed to watch syntaxes directory")?;
+        tracing::debug!("Watching syntaxes directory");
+    }
+    let data_dir = Path::new("data");
+    if data_dir.is_dir() {
+        watcher
+            .watch(data_dir, RecursiveMode::Recursive)
+            .wrap_err("Failed to watch data directory")?;
+        tracing::debug!("Watching data directory");
+    }
     if static_dir_path.exists() {
         watcher
             .watch(&static_dir_path, RecursiveMode::Recursive)
@@ -119,6 +159,7 @@ pub async fn run(config_path: &Path, port: u16, open_browser: bool) -> Result<()
     let rebuild_output = output_dir.clone();
     let rebuild_content = content_dir_path.clone();
     let rebuild_static = static_dir_path.clone();
+    let rebuild_templates = templates_dir.clone();
 
     tokio::spawn(async move {
         let mut last_rebuild = Instant::now();
@@ -134,20 +175,27 @@ pub async fn run(config_path: &Path, port: u16, open_browser: bool) -> Result<()
 
             println!();
             println!("  File change detected, rebuilding...");
+            // Remote content was fetched by the initial build
             let mut builder =
-                Builder::new(rebuild_config.clone(), &rebuild_content, &rebuild_output);
+                Builder::new(rebuild_config.clone(), &rebuild_content, &rebuild_output)
+                    .with_remote_fetch(false)
+                    .with_cache(true);
 
             // Include static directory if it exists
             if rebuild_static.exists() && rebuild_static.is_dir() {
                 builder = builder.with_static_dir(&rebuild_static);
             }
+            if rebuild_templates.is_dir() {
+                builder = builder.with_templates_dir(&rebuild_templates);
+            }
 
             match inject_livereload_and_build(&builder, &rebuild_output) {
                 Ok(stats) => {
                     println!(
-                        "  ✓ Rebuilt {} pages in {}ms",
+                        "  ✓ Rebuilt {} pages in {}ms ({} cached)",
                         stats.pages + stats.taxonomy_pages + stats.auto_pages,
-                        stats.duration_ms
+                        stats.duration_ms,
+                        stats.cache_hits
                     );
                     rebuild_state.notify_reload();
                 }
@@ -171,6 +219,7 @@ pub async fn run(config_path: &Path, port: u16, open_browser: bool) -> Result<()
 
     println!();
     println!("  Dev server running at http://{addr}");
+    println!("  Search misses at http://{addr}/__search-misses");
     println!("  Press Ctrl+C to stop");
     println!();
 
@@ -198,6 +247,12 @@ fn print_build_stats(stats: &BuildStats) {
     println!("  Auto Pages:   {:>6}", stats.auto_pages);
     println!("  Redirects:    {:>6}", stats.redirects);
     println!("  Assets:       {:>6}", stats.assets);
+    if stats.cache_hits + stats.cache_misses > 0 {
+        println!("  Cached:       {:>6}", stats.cache_hits);
+    }
+    if !stats.warnings.is_empty() {
+        println!("  Warnings:     {:>6}", stats.warnings.len());
+    }
     println!("  ─────────────────────────────────");
     println!("  Total:        {total_pages:>6} pages");
     println!("  Duration:     {:>6}ms", stats.duration_ms);
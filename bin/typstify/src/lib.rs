@@ -6,7 +6,8 @@
 //!
 //! # Modules
 //!
-//! - [`cmd`] - Command implementations (build, watch, new, check)
+//! - [`cmd`] - Command implementations (build, watch, new, check, fmt, list, diff, comment,
+//!   theme, ping)
 //! - [`server`] - Embedded development server with live reload
 //!
 //! # Example
@@ -14,17 +15,14 @@
 //! ```no_run
 //! use std::path::Path;
 //!
-//! use typstify::cmd;
+//! use typstify::cmd::{self, build::BuildOptions};
 //!
 //! // Build a static site
-//! cmd::build::run(
-//!     Path::new("config.toml"),
-//!     Path::new("public"),
-//!     false,
-//!     None,
-//!     None,
-//! )
-//! .unwrap();
+//! let options = BuildOptions {
+//!     cache: true,
+//!     ..BuildOptions::default()
+//! };
+//! cmd::build::run(Path::new("config.toml"), Path::new("public"), &options).unwrap();
 //! ```
 
 pub mod cmd;
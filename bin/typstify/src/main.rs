@@ -38,12 +38,27 @@ enum Commands {
         /// Include draft posts
         #[arg(long)]
         drafts: bool,
+        /// Include posts dated in the future
+        #[arg(long)]
+        future: bool,
         /// Override site host (e.g., https://example.com)
         #[arg(long)]
         host: Option<String>,
         /// Override site base path (e.g., /my-blog)
         #[arg(long)]
         base_path: Option<String>,
+        /// Render every page again instead of reusing the build cache
+        #[arg(long)]
+        no_cache: bool,
+        /// Fail the build if there are any warnings
+        #[arg(long)]
+        strict: bool,
+        /// Write the build statistics and warnings to a JSON file
+        #[arg(long, value_name = "FILE", num_args = 0..=1, default_missing_value = "build-report.json")]
+        report: Option<std::path::PathBuf>,
+        /// Record per-page parse and render times and print the slowest pages
+        #[arg(long)]
+        timings: bool,
     },
     /// Start development server with live reload
     Watch {
@@ -67,9 +82,98 @@ enum Commands {
         /// Treat warnings as errors
         #[arg(long)]
         strict: bool,
+        /// Audit rendered HTML for accessibility issues
+        #[arg(long)]
+        a11y: bool,
+        /// Check rendered HTML for unclosed tags, duplicate IDs, and invalid nesting
+        #[arg(long)]
+        html: bool,
+        /// Check spelling of Markdown content against the bundled dictionary
+        /// and `.typstify-dictionary`
+        #[arg(long)]
+        spelling: bool,
+        /// Rewrite Markdown headings to fix skipped levels, extra h1s and
+        /// excessive depth
+        #[arg(long)]
+        fix: bool,
+        /// Estimate the weight and render-blocking resources of rendered
+        /// pages and list the heaviest
+        #[arg(long)]
+        perf: bool,
+    },
+    /// Normalize frontmatter and trailing whitespace in content files
+    Fmt {
+        /// List the files that are not formatted and fail, without
+        /// rewriting them
+        #[arg(long)]
+        check: bool,
+    },
+    /// List the site's pages, newest first
+    List {
+        /// Include draft posts
+        #[arg(long)]
+        drafts: bool,
+        /// Print the inventory as JSON
+        #[arg(long)]
+        json: bool,
+    },
+    /// List the pages whose HTML differs between two builds
+    Diff {
+        /// Output directory of the old build
+        old: std::path::PathBuf,
+        /// Output directory of the new build
+        new: std::path::PathBuf,
+        /// Prefix for the listed URLs (e.g., the preview's address)
+        #[arg(long)]
+        base_url: Option<String>,
+        /// Print the differences as JSON
+        #[arg(long)]
+        json: bool,
+    },
+    /// Summarize a preview build as Markdown for a pull request comment
+    Comment {
+        /// Output directory of the old build
+        old: std::path::PathBuf,
+        /// Output directory of the new build
+        new: std::path::PathBuf,
+        /// Build report of the new build (`typstify build --report`)
+        #[arg(long)]
+        report: Option<std::path::PathBuf>,
+        /// Address of the preview, to link the pages
+        #[arg(long)]
+        base_url: Option<String>,
+    },
+    /// Notify WebSub hubs and search engines that the deployed site changed
+    Ping {
+        /// Directory the deployed site was built into
+        #[arg(short, long, default_value = "public")]
+        output: std::path::PathBuf,
+        /// List the pings without sending them
+        #[arg(long)]
+        dry_run: bool,
+    },
+    /// Install and list themes
+    Theme {
+        #[command(subcommand)]
+        command: ThemeCommands,
     },
 }
 
+/// Theme subcommands.
+#[derive(clap::Subcommand)]
+enum ThemeCommands {
+    /// Clone a theme repository into `themes/`
+    Install {
+        /// Git URL of the theme repository
+        url: String,
+        /// Directory name under `themes/` (defaults to the repository name)
+        #[arg(long)]
+        name: Option<String>,
+    },
+    /// List installed themes
+    List,
+}
+
 #[tokio::main]
 async fn main() -> Result<()> {
     color_eyre::install()?;
@@ -81,16 +185,25 @@ async fn main() -> Result<()> {
         Commands::Build {
             output,
             drafts,
+            future,
             host,
             base_path,
+            no_cache,
+            strict,
+            report,
+            timings,
         } => {
-            typstify::cmd::build::run(
-                &cli.config,
-                &output,
+            let options = typstify::cmd::build::BuildOptions {
                 drafts,
-                host.as_deref(),
-                base_path.as_deref(),
-            )?;
+                future,
+                host,
+                base_path,
+                cache: !no_cache,
+                strict,
+                report,
+                timings,
+            };
+            typstify::cmd::build::run(&cli.config, &output, &options)?;
         }
         Commands::Watch { port, open } => {
             typstify::cmd::watch::run(&cli.config, port, open).await?;
@@ -98,9 +211,55 @@ async fn main() -> Result<()> {
         Commands::New { path, template } => {
             typstify::cmd::new::run(&path, &template)?;
         }
-        Commands::Check { strict } => {
-            typstify::cmd::check::run(&cli.config, strict)?;
+        Commands::Check {
+            strict,
+            a11y,
+            html,
+            spelling,
+            fix,
+            perf,
+        } => {
+            let options = typstify::cmd::check::CheckOptions {
+                strict,
+                a11y,
+                html,
+                spelling,
+                fix,
+                perf,
+            };
+            typstify::cmd::check::run(&cli.config, &options)?;
+        }
+        Commands::Fmt { check } => {
+            typstify::cmd::fmt::run(&cli.config, check)?;
+        }
+        Commands::List { drafts, json } => {
+            typstify::cmd::list::run(&cli.config, drafts, json)?;
+        }
+        Commands::Diff {
+            old,
+            new,
+            base_url,
+            json,
+        } => {
+            typstify::cmd::diff::run(&old, &new, base_url.as_deref(), json)?;
+        }
+        Commands::Comment {
+            old,
+            new,
+            report,
+            base_url,
+        } => {
+            typstify::cmd::comment::run(&old, &new, report.as_deref(), base_url.as_deref())?;
+        }
+        Commands::Ping { output, dry_run } => {
+            typstify::cmd::ping::run(&cli.config, &output, dry_run)?;
         }
+        Commands::Theme { command } => match command {
+            ThemeCommands::Install { url, name } => {
+                typstify::cmd::theme::install(&url, name.as_deref())?;
+            }
+            ThemeCommands::List => typstify::cmd::theme::list()?,
+        },
     }
 
     Ok(())
@@ -124,13 +283,23 @@ mod tests {
             Commands::Build {
                 output,
                 drafts,
+                future,
                 host,
                 base_path,
+                no_cache,
+                strict,
+                report,
+                timings,
             } => {
                 assert_eq!(output, std::path::PathBuf::from("dist"));
+                assert!(report.is_none());
                 assert!(!drafts);
+                assert!(!future);
                 assert!(host.is_none());
                 assert!(base_path.is_none());
+                assert!(!no_cache);
+                assert!(!strict);
+                assert!(!timings);
             }
             _ => panic!("Expected Build command"),
         }
@@ -138,12 +307,52 @@ mod tests {
 
     #[test]
     fn test_cli_build_with_drafts() {
-        let args = ["typstify", "build", "--drafts"];
+        let args = ["typstify", "build", "--drafts", "--future"];
         let cli = Cli::parse_from(args);
 
         match cli.command {
-            Commands::Build { drafts, .. } => {
+            Commands::Build { drafts, future, .. } => {
                 assert!(drafts);
+                assert!(future);
+            }
+            _ => panic!("Expected Build command"),
+        }
+    }
+
+    #[test]
+    fn test_cli_build_no_cache() {
+        let args = ["typstify", "build", "--no-cache"];
+        let cli = Cli::parse_from(args);
+
+        match cli.command {
+            Commands::Build { no_cache, .. } => {
+                assert!(no_cache);
+            }
+            _ => panic!("Expected Build command"),
+        }
+    }
+
+    #[test]
+    fn test_cli_build_timings() {
+        let args = ["typstify", "build", "--timings"];
+        let cli = Cli::parse_from(args);
+
+        match cli.command {
+            Commands::Build { timings, .. } => {
+                assert!(timings);
+            }
+            _ => panic!("Expected Build command"),
+        }
+    }
+
+    #[test]
+    fn test_cli_build_strict() {
+        let args = ["typstify", "build", "--strict"];
+        let cli = Cli::parse_from(args);
+
+        match cli.command {
+            Commands::Build { strict, .. } => {
+                assert!(strict);
             }
             _ => panic!("Expected Build command"),
         }
@@ -179,17 +388,146 @@ mod tests {
 
     #[test]
     fn test_cli_check_command_parsing() {
-        let args = ["typstify", "check", "--strict"];
+        let args = ["typstify", "check", "--strict", "--a11y", "--spelling"];
         let cli = Cli::parse_from(args);
 
         match cli.command {
-            Commands::Check { strict } => {
+            Commands::Check {
+                strict,
+                a11y,
+                html,
+                spelling,
+                fix,
+                perf,
+            } => {
                 assert!(strict);
+                assert!(a11y);
+                assert!(!html);
+                assert!(spelling);
+                assert!(!fix);
+                assert!(!perf);
             }
             _ => panic!("Expected Check command"),
         }
     }
 
+    #[test]
+    fn test_cli_fmt_command_parsing() {
+        let cli = Cli::parse_from(["typstify", "fmt", "--check"]);
+        assert!(matches!(cli.command, Commands::Fmt { check: true }));
+        let cli = Cli::parse_from(["typstify", "fmt"]);
+        assert!(matches!(cli.command, Commands::Fmt { check: false }));
+    }
+
+    #[test]
+    fn test_cli_theme_install_parsing() {
+        let args = [
+            "typstify",
+            "theme",
+            "install",
+            "https://github.com/someone/typstify-theme-paper.git",
+            "--name",
+            "paper",
+        ];
+        let cli = Cli::parse_from(args);
+
+        match cli.command {
+            Commands::Theme {
+                command: ThemeCommands::Install { url, name },
+            } => {
+                assert_eq!(url, "https://github.com/someone/typstify-theme-paper.git");
+                assert_eq!(name.as_deref(), Some("paper"));
+            }
+            _ => panic!("Expected Theme install command"),
+        }
+    }
+
+    #[test]
+    fn test_cli_ping() {
+        let cli = Cli::parse_from(["typstify", "ping", "--output", "dist", "--dry-run"]);
+
+        match cli.command {
+            Commands::Ping { output, dry_run } => {
+                assert_eq!(output, std::path::PathBuf::from("dist"));
+                assert!(dry_run);
+            }
+            _ => panic!("Expected Ping command"),
+        }
+    }
+
+    #[test]
+    fn test_cli_list() {
+        let cli = Cli::parse_from(["typstify", "list", "--json"]);
+
+        match cli.command {
+            Commands::List { drafts, json } => {
+                assert!(!drafts);
+                assert!(json);
+            }
+            _ => panic!("Expected List command"),
+        }
+    }
+
+    #[test]
+    fn test_cli_diff() {
+        let cli = Cli::parse_from([
+            "typstify",
+            "diff",
+            "main",
+            "public",
+            "--base-url",
+            "https://preview.example.com",
+        ]);
+
+        match cli.command {
+            Commands::Diff {
+                old,
+                new,
+                base_url,
+                json,
+            } => {
+                assert_eq!(old, std::path::PathBuf::from("main"));
+                assert_eq!(new, std::path::PathBuf::from("public"));
+                assert_eq!(base_url.as_deref(), Some("https://preview.example.com"));
+                assert!(!json);
+            }
+            _ => panic!("Expected Diff command"),
+        }
+    }
+
+    #[test]
+    fn test_cli_build_report() {
+        match Cli::parse_from(["typstify", "build", "--report"]).command {
+            Commands::Build { report, .. } => {
+                assert_eq!(report, Some(std::path::PathBuf::from("build-report.json")));
+            }
+            _ => panic!("Expected Build command"),
+        }
+
+        let cli = Cli::parse_from([
+            "typstify",
+            "comment",
+            "main",
+            "public",
+            "--report",
+            "report.json",
+        ]);
+        match cli.command {
+            Commands::Comment {
+                old,
+                new,
+                report,
+                base_url,
+            } => {
+                assert_eq!(old, std::path::PathBuf::from("main"));
+                assert_eq!(new, std::path::PathBuf::from("public"));
+                assert_eq!(report, Some(std::path::PathBuf::from("report.json")));
+                assert!(base_url.is_none());
+            }
+            _ => panic!("Expected Comment command"),
+        }
+    }
+
     #[test]
     fn test_cli_verbosity_flags() {
         let args = ["typstify", "-vvv", "build"];
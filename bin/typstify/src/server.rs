@@ -4,7 +4,10 @@ use std::{path::Path, sync::Arc, time::Duration};
 
 use axum::{
     Router,
-    response::sse::{Event, Sse},
+    response::{
+        Html,
+        sse::{Event, Sse},
+    },
     routing::get,
 };
 use tokio::sync::broadcast;
@@ -56,6 +59,7 @@ impl Default for ServerState {
 pub fn create_router(output_dir: &Path, state: Arc<ServerState>) -> Router {
     Router::new()
         .route("/__livereload", get(livereload_handler))
+        .route("/__search-misses", get(search_misses_handler))
         .fallback_service(ServeDir::new(output_dir))
         .with_state(state)
 }
@@ -80,6 +84,70 @@ async fn livereload_handler(
     )
 }
 
+/// Dev view of the search queries with no results recorded in this
+/// browser's localStorage.
+async fn search_misses_handler() -> Html<&'static str> {
+    Html(SEARCH_MISSES_PAGE)
+}
+
+/// Page listing recorded search misses, most frequent first.
+const SEARCH_MISSES_PAGE: &str = r#"<!DOCTYPE html>
+<html lang="en">
+<head>
+<meta charset="UTF-8">
+<title>Search misses</title>
+<style>
+body { font: 15px/1.5 system-ui, sans-serif; max-width: 48rem; margin: 2rem auto; padding: 0 1rem; }
+table { width: 100%; border-collapse: collapse; }
+th, td { padding: 0.375rem 0.5rem; border-bottom: 1px solid #ddd; text-align: left; }
+td.count { text-align: right; }
+</style>
+</head>
+<body>
+<h1>Search misses</h1>
+<p>Queries with no results recorded in this browser (<code>search.record_misses</code>).</p>
+<p><button id="clear">Clear</button></p>
+<table>
+<thead><tr><th>Query</th><th>Count</th><th>Last seen</th><th>Page</th></tr></thead>
+<tbody id="misses"></tbody>
+</table>
+<script>
+(function() {
+    const key = 'typstify:search-misses';
+    const body = document.getElementById('misses');
+
+    function render() {
+        const stored = JSON.parse(localStorage.getItem(key) || '{}');
+        const rows = Object.entries(stored).sort((a, b) => b[1].count - a[1].count);
+        body.replaceChildren();
+        if (rows.length === 0) {
+            const tr = body.insertRow();
+            const td = tr.insertCell();
+            td.colSpan = 4;
+            td.textContent = 'No misses recorded yet.';
+            return;
+        }
+        for (const [query, entry] of rows) {
+            const tr = body.insertRow();
+            tr.insertCell().textContent = query;
+            const count = tr.insertCell();
+            count.className = 'count';
+            count.textContent = entry.count;
+            tr.insertCell().textContent = entry.last ? new Date(entry.last).toLocaleString() : '';
+            tr.insertCell().textContent = entry.page || '';
+        }
+    }
+
+    document.getElementById('clear').addEventListener('click', () => {
+        localStorage.removeItem(key);
+        render();
+    });
+    render();
+})();
+</script>
+</body>
+</html>"#;
+
 /// JavaScript snippet to inject for live reload.
 pub const LIVERELOAD_SCRIPT: &str = r#"
 <script>
@@ -2,13 +2,22 @@
 
 use std::{collections::HashMap, path::Path};
 
+use chrono::{DateTime, Datelike, Utc};
+pub use chrono_tz::Tz;
 use serde::{Deserialize, Serialize};
 
-use crate::error::{CoreError, Result};
+use crate::{
+    content::Page,
+    error::{CoreError, Result},
+};
 
 /// Main configuration structure for Typstify.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Config {
+    /// Name of the theme in `themes/` providing templates and static assets.
+    #[serde(default)]
+    pub theme: Option<String>,
+
     /// Site-wide settings.
     pub site: SiteConfig,
 
@@ -32,6 +41,66 @@ pub struct Config {
     #[serde(default)]
     pub taxonomies: TaxonomyConfig,
 
+    /// Date formatting settings.
+    #[serde(default)]
+    pub dates: DatesConfig,
+
+    /// Stylesheet pipeline settings.
+    #[serde(default)]
+    pub css: CssConfig,
+
+    /// Responsive image settings.
+    #[serde(default)]
+    pub images: ImagesConfig,
+
+    /// Markdown rendering settings.
+    #[serde(default)]
+    pub markdown: MarkdownConfig,
+
+    /// Smart typography settings for Markdown content.
+    #[serde(default)]
+    pub typography: TypographyConfig,
+
+    /// Table of contents settings.
+    #[serde(default)]
+    pub toc: TocConfig,
+
+    /// Math rendering settings.
+    #[serde(default)]
+    pub math: MathConfig,
+
+    /// Mermaid diagram settings.
+    #[serde(default)]
+    pub mermaid: MermaidConfig,
+
+    /// Per-section settings, keyed by section path (e.g. "docs").
+    #[serde(default)]
+    pub sections: HashMap<String, SectionConfig>,
+
+    /// `typstify check` settings.
+    #[serde(default)]
+    pub check: CheckConfig,
+
+    /// `typstify fmt` settings.
+    #[serde(default)]
+    pub fmt: FmtConfig,
+
+    /// Webmention endpoints and received mentions.
+    #[serde(default)]
+    pub webmentions: WebmentionsConfig,
+
+    /// Author identity (`rel="me"` profiles, fediverse account).
+    #[serde(default)]
+    pub identity: IdentityConfig,
+
+    /// Content sources besides the content directory.
+    #[serde(default)]
+    pub content: ContentConfig,
+
+    /// Update notifications sent by `typstify ping`.
+    #[serde(default)]
+    pub ping: PingConfig,
+
     /// Language-specific configurations.
     #[serde(default)]
     pub languages: HashMap<String, LanguageConfig>,
@@ -63,6 +132,12 @@ pub struct SiteConfig {
     /// Site author name.
     #[serde(default)]
     pub author: Option<String>,
+
+    /// IANA timezone name (e.g., "Asia/Shanghai") used to interpret
+    /// frontmatter dates without an offset and to display dates.
+    /// Defaults to UTC.
+    #[serde(default)]
+    pub timezone: Option<String>,
 }
 
 /// Configuration for a specific language.
@@ -79,6 +154,27 @@ pub struct LanguageConfig {
     /// Override site description for this language.
     #[serde(default)]
     pub description: Option<String>,
+
+    /// Override the date format for this language (e.g., "%Y年%m月%d日").
+    #[serde(default)]
+    pub date_format: Option<String>,
+
+    /// Month names (January first) used for `%B`/`%b`.
+    #[serde(default)]
+    pub month_names: Option<Vec<String>>,
+
+    /// Weekday names (Monday first) used for `%A`/`%a`.
+    #[serde(default)]
+    pub weekday_names: Option<Vec<String>>,
+
+    /// Label format of weekly archive windows (e.g., "%G年第%-V周"), with
+    /// `%V` the ISO week and `%G` its year. Defaults to "Week %-V, %G".
+    #[serde(default)]
+    pub week_format: Option<String>,
+
+    /// Override the typography settings for this language.
+    #[serde(default)]
+    pub typography: Option<TypographyConfig>,
 }
 
 /// Build configuration.
@@ -92,13 +188,122 @@ pub struct BuildConfig {
     #[serde(default)]
     pub minify: bool,
 
-    /// Syntax highlighting theme name.
+    /// Syntax highlighting theme name, or `auto` for the default light and
+    /// dark themes. A named theme is fixed unless `syntax_theme_dark` is
+    /// set, in which case it is the light theme.
     #[serde(default = "default_syntax_theme")]
     pub syntax_theme: String,
 
+    /// Syntax highlighting theme in dark mode.
+    #[serde(default)]
+    pub syntax_theme_dark: Option<String>,
+
+    /// Directory of extra `.sublime-syntax` syntax definitions and
+    /// `.tmTheme` themes for code highlighting, if it exists.
+    #[serde(default = "default_syntaxes_dir")]
+    pub syntaxes_dir: String,
+
+    /// Whether code blocks get line numbers (`linenos` and `nolinenos`
+    /// override it per block).
+    #[serde(default)]
+    pub line_numbers: bool,
+
     /// Whether to generate drafts.
     #[serde(default)]
     pub drafts: bool,
+
+    /// Whether to generate pages dated in the future.
+    #[serde(default)]
+    pub future: bool,
+
+    /// Whether a page that fails to parse or render fails the build,
+    /// instead of being skipped with a warning.
+    #[serde(default)]
+    pub fail_on_error: bool,
+
+    /// Whether to write the internal link graph as `link-graph.json`.
+    #[serde(default)]
+    pub link_graph: bool,
+
+    /// Whether to write the source path -> URL map as `urlmap.json`.
+    #[serde(default)]
+    pub url_map: bool,
+
+    /// Whether to add a "Linked from" section listing pages that link to
+    /// each page.
+    #[serde(default)]
+    pub backlinks: bool,
+
+    /// Whether pages and posts get a "Print / Save as PDF" button.
+    #[serde(default)]
+    pub print_button: bool,
+
+    /// Whether to generate a `/changelog/` page from the git history of the
+    /// content directory.
+    #[serde(default)]
+    pub changelog: bool,
+
+    /// Directory for data cached between builds (remote content sources,
+    /// `data` shortcode responses).
+    #[serde(default = "default_cache_dir")]
+    pub cache_dir: String,
+
+    /// Seconds a `data` shortcode response is reused before it is fetched
+    /// again.
+    #[serde(default = "default_data_ttl")]
+    pub data_ttl: u64,
+
+    /// Whether to download remote images into `static/remote/` and serve
+    /// the local copies.
+    #[serde(default)]
+    pub localize_images: bool,
+
+    /// Whether to strip EXIF/GPS and other metadata from JPEG and PNG
+    /// static files.
+    #[serde(default = "default_true")]
+    pub strip_metadata: bool,
+
+    /// Static directories (relative to `static/`) whose images keep their
+    /// metadata.
+    #[serde(default)]
+    pub keep_metadata: Vec<String>,
+
+    /// Asset processors keyed by source file extension (without the dot).
+    #[serde(default)]
+    pub processors: HashMap<String, ProcessorConfig>,
+}
+
+/// How static files with a given extension are processed.
+///
+/// Exactly one of `command` or `builtin` must be set.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ProcessorConfig {
+    /// External command, split on whitespace. `{input}` and `{output}` are
+    /// replaced with the source and destination paths.
+    #[serde(default)]
+    pub command: Option<String>,
+
+    /// Built-in processor.
+    #[serde(default)]
+    pub builtin: Option<BuiltinProcessor>,
+
+    /// Extension of the processed file (defaults to the source extension).
+    #[serde(default)]
+    pub output_extension: Option<String>,
+}
+
+/// Built-in asset processors.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum BuiltinProcessor {
+    /// Copy the file unchanged.
+    Copy,
+
+    /// Do not publish the file.
+    Skip,
+
+    /// Strip comments and redundant whitespace from CSS.
+    MinifyCss,
 }
 
 /// Search configuration.
@@ -115,6 +320,54 @@ pub struct SearchConfig {
     /// Chunk size for index splitting (bytes).
     #[serde(default = "default_chunk_size")]
     pub chunk_size: usize,
+
+    /// Resource hint for the search index emitted on every page.
+    #[serde(default)]
+    pub preload: SearchPreload,
+
+    /// Embed the index in each page instead of fetching it when its JSON
+    /// is at most this many bytes (0 disables inlining).
+    #[serde(default)]
+    pub inline_max_bytes: usize,
+
+    /// Endpoint that receives queries with no results as a JSON POST.
+    #[serde(default)]
+    pub miss_endpoint: Option<String>,
+
+    /// Record queries with no results in the browser's localStorage (shown
+    /// at `/__search-misses` by the dev server).
+    #[serde(default)]
+    pub record_misses: bool,
+
+    /// Reduce English words to their stems, so that "building" also finds
+    /// "build".
+    #[serde(default = "default_true")]
+    pub stemming: bool,
+
+    /// Language code -> words left out of its search index and queries.
+    #[serde(default)]
+    pub stopwords: HashMap<String, Vec<String>>,
+
+    /// Also write an index of every page, drafts and protected sections
+    /// included, encrypted with the `TYPSTIFY_SEARCH_KEY` environment
+    /// variable (for preview deployments).
+    #[serde(default)]
+    pub encrypted_preview: bool,
+}
+
+/// Resource hints for the search index.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum SearchPreload {
+    /// No hint; the index is fetched when search opens.
+    #[default]
+    None,
+
+    /// `<link rel="prefetch">`: fetch at idle time.
+    Prefetch,
+
+    /// `<link rel="preload">`: fetch right away.
+    Preload,
 }
 
 /// RSS feed configuration.
@@ -127,6 +380,10 @@ pub struct RssConfig {
     /// Maximum number of items in feed.
     #[serde(default = "default_rss_limit")]
     pub limit: usize,
+
+    /// Leave pinned pages out of the feeds.
+    #[serde(default)]
+    pub exclude_pinned: bool,
 }
 
 /// Robots.txt configuration.
@@ -157,12 +414,511 @@ pub struct TaxonomyConfig {
     pub categories: TaxonomySettings,
 }
 
+/// Date formatting configuration.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DatesConfig {
+    /// Default `strftime`-style format for displayed dates.
+    #[serde(default = "default_date_format")]
+    pub format: String,
+}
+
+/// Stylesheet pipeline configuration.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CssConfig {
+    /// How `assets/style.css` is produced.
+    #[serde(default)]
+    pub pipeline: CssPipeline,
+
+    /// Input stylesheet for external pipelines, relative to the project root.
+    #[serde(default = "default_css_input")]
+    pub input: String,
+
+    /// Command overriding the pipeline default. `{input}` and `{output}` are
+    /// replaced with the stylesheet paths.
+    #[serde(default)]
+    pub command: Option<String>,
+}
+
+/// Responsive image configuration.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ImagesConfig {
+    /// Whether to generate resized variants of local images and wrap them
+    /// in `<picture>` elements.
+    #[serde(default)]
+    pub responsive: bool,
+
+    /// Image widths (in pixels) to generate; widths larger than the
+    /// original are skipped.
+    #[serde(default = "default_breakpoints")]
+    pub breakpoints: Vec<u32>,
+
+    /// Additional formats offered as `<source>` elements, preferred first
+    /// (`avif`, `webp`).
+    #[serde(default = "default_image_formats")]
+    pub formats: Vec<String>,
+
+    /// Value of the `sizes` attribute.
+    #[serde(default = "default_image_sizes")]
+    pub sizes: String,
+}
+
+/// Settings for a content section.
+///
+/// Settings apply to the section's subsections too, unless a subsection has
+/// its own entry.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SectionConfig {
+    /// How pages in the section are laid out.
+    #[serde(default)]
+    pub layout: SectionLayout,
+
+    /// Whether the section's pages are listed in the sitemap.
+    #[serde(default = "default_true")]
+    pub sitemap: bool,
+
+    /// How crawlers may treat the section.
+    #[serde(default)]
+    pub robots: SectionRobots,
+
+    /// How the shorts timeline is split into pages.
+    #[serde(default)]
+    pub paginate_by: PaginateBy,
+
+    /// Whether the deployment serves the section behind a password; its
+    /// pages are left out of the public search index, sitemap and feeds.
+    #[serde(default)]
+    pub protected: bool,
+}
+
+impl Default for SectionConfig {
+    fn default() -> Self {
+        Self {
+            layout: SectionLayout::default(),
+            sitemap: true,
+            robots: SectionRobots::default(),
+            paginate_by: PaginateBy::default(),
+            protected: false,
+        }
+    }
+}
+
+impl SectionConfig {
+    /// Whether the section's pages belong in the sitemap.
+    #[must_use]
+    pub fn in_sitemap(&self) -> bool {
+        self.sitemap && self.robots == SectionRobots::Index && !self.protected
+    }
+}
+
+/// Crawler directives for a section.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum SectionRobots {
+    /// Crawl and index normally.
+    #[default]
+    Index,
+
+    /// Let crawlers fetch pages but mark them `noindex`.
+    Noindex,
+
+    /// Disallow the section in robots.txt.
+    Disallow,
+}
+
+/// How a listing is split into pages.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum PaginateBy {
+    /// A fixed number of items per page (`taxonomies.tags.paginate`).
+    #[default]
+    Count,
+
+    /// One page per day.
+    Day,
+
+    /// One page per ISO week.
+    Week,
+
+    /// One page per month.
+    Month,
+
+    /// One page per year.
+    Year,
+}
+
+/// Section layouts.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum SectionLayout {
+    /// Standalone posts and pages.
+    #[default]
+    Blog,
+
+    /// Documentation with a sidebar tree of the section's pages.
+    Docs,
+}
+
+/// Markdown rendering configuration.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct MarkdownConfig {
+    /// Turn bare `http(s)://` URLs in text into links.
+    #[serde(default)]
+    pub autolink: bool,
+
+    /// Profile URL prefix for `@user` mentions (e.g.
+    /// "https://github.com"); mentions are left as text when unset.
+    #[serde(default)]
+    pub mention_url: Option<String>,
+}
+
+/// Webmention configuration.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct WebmentionsConfig {
+    /// Webmention endpoint advertised with `<link rel="webmention">`
+    /// (e.g. "https://webmention.io/example.com/webmention").
+    #[serde(default)]
+    pub endpoint: Option<String>,
+
+    /// Pingback endpoint advertised with `<link rel="pingback">`.
+    #[serde(default)]
+    pub pingback: Option<String>,
+
+    /// webmention.io JF2 export whose mentions are rendered under posts
+    /// (e.g. "data/webmentions.json").
+    #[serde(default)]
+    pub data: Option<String>,
+}
+
+/// Author identity configuration.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct IdentityConfig {
+    /// Profile URLs linked with `<link rel="me">` from every page (e.g.
+    /// "https://github.com/alice"), so those profiles can verify the site.
+    #[serde(default)]
+    pub me: Vec<String>,
+
+    /// Fediverse account (e.g. "@alice@mastodon.social"). Adds its profile
+    /// as a `rel="me"` link, a `fediverse:creator` meta tag and a
+    /// `/.well-known/webfinger` document resolving to it.
+    #[serde(default)]
+    pub fediverse: Option<String>,
+}
+
+/// Update notification configuration.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PingConfig {
+    /// WebSub hubs notified when the feeds change and advertised in them
+    /// (e.g. "https://pubsubhubbub.appspot.com/").
+    #[serde(default)]
+    pub websub: Vec<String>,
+
+    /// Sitemap ping URLs, with `{sitemap}` replaced by the URL-encoded
+    /// sitemap URL (e.g. "https://www.bing.com/ping?sitemap={sitemap}").
+    #[serde(default)]
+    pub sitemap: Vec<String>,
+}
+
+/// Content source configuration.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ContentConfig {
+    /// Remote sources fetched into the content tree before each build, as a
+    /// single `[content.remote]` table or an array of `[[content.remote]]`.
+    #[serde(default, deserialize_with = "one_or_many")]
+    pub remote: Vec<RemoteContentConfig>,
+}
+
+/// A remote content source: a git repository or an HTTPS tarball.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RemoteContentConfig {
+    /// Git repository URL, or the URL of a `.tar.gz`/`.tgz` archive.
+    pub url: String,
+
+    /// Branch, tag or commit to check out (git only; defaults to the
+    /// remote's default branch).
+    #[serde(default, rename = "ref")]
+    pub git_ref: Option<String>,
+
+    /// Directory inside the source to publish (e.g. "docs"); defaults to
+    /// the whole source.
+    #[serde(default)]
+    pub path: Option<String>,
+
+    /// Directory in the content tree the source is placed in (e.g. "docs"),
+    /// relative to the content directory.
+    pub target: String,
+}
+
+impl RemoteContentConfig {
+    /// Whether the source is a tarball rather than a git repository.
+    #[must_use]
+    pub fn is_archive(&self) -> bool {
+        let path = self.url.split(['?', '#']).next().unwrap_or_default();
+        path.ends_with(".tar.gz") || path.ends_with(".tgz")
+    }
+}
+
+/// Accept either a single table or an array of tables.
+fn one_or_many<'de, D, T>(deserializer: D) -> std::result::Result<Vec<T>, D::Error>
+where
+    D: serde::Deserializer<'de>,
+    T: Deserialize<'de>,
+{
+    #[derive(Deserialize)]
+    #[serde(untagged)]
+    enum OneOrMany<T> {
+        One(T),
+        Many(Vec<T>),
+    }
+
+    Ok(match OneOrMany::deserialize(deserializer)? {
+        OneOrMany::One(one) => vec![one],
+        OneOrMany::Many(many) => many,
+    })
+}
+
+/// `typstify check` configuration.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CheckConfig {
+    /// External prose linter run over the plain text of each Markdown page,
+    /// split on whitespace. `{input}` is replaced with a file holding the
+    /// text (otherwise it is piped to stdin) and `{source}` with the source
+    /// path. Output lines of the form `[file:]line[:column]:message` are
+    /// reported as warnings.
+    #[serde(default)]
+    pub prose_linter: Option<String>,
+
+    /// Deepest heading level allowed in content (e.g. 4 for `####`).
+    #[serde(default)]
+    pub max_heading_depth: Option<u8>,
+}
+
+/// `typstify fmt` configuration.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct FmtConfig {
+    /// Order of frontmatter keys; keys not listed follow in their original
+    /// order.
+    #[serde(default = "default_fmt_key_order")]
+    pub key_order: Vec<String>,
+
+    /// chrono format of dates without a time of day.
+    #[serde(default = "default_fmt_date_format")]
+    pub date_format: String,
+
+    /// chrono format of dates with a time of day but no UTC offset; dates
+    /// with an offset are written as RFC 3339.
+    #[serde(default = "default_fmt_datetime_format")]
+    pub datetime_format: String,
+
+    /// Casing of tags and categories.
+    #[serde(default)]
+    pub tag_case: TagCase,
+}
+
+impl Default for FmtConfig {
+    fn default() -> Self {
+        Self {
+            key_order: default_fmt_key_order(),
+            date_format: default_fmt_date_format(),
+            datetime_format: default_fmt_datetime_format(),
+            tag_case: TagCase::default(),
+        }
+    }
+}
+
+fn default_fmt_key_order() -> Vec<String> {
+    [
+        "title",
+        "date",
+        "updated",
+        "expiry_date",
+        "description",
+        "draft",
+        "tags",
+        "categories",
+    ]
+    .map(String::from)
+    .to_vec()
+}
+
+fn default_fmt_date_format() -> String {
+    "%Y-%m-%d".to_string()
+}
+
+fn default_fmt_datetime_format() -> String {
+    "%Y-%m-%d %H:%M:%S".to_string()
+}
+
+/// Casing of tags and categories written by `typstify fmt`.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum TagCase {
+    /// Left as written.
+    #[default]
+    Preserve,
+    /// `Rust Tips` -> `rust tips`.
+    Lower,
+    /// `Rust Tips` -> `rust-tips`.
+    Kebab,
+}
+
+/// Smart typography configuration.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TypographyConfig {
+    /// Convert straight quotes to typographic quotes.
+    #[serde(default)]
+    pub smart_quotes: bool,
+
+    /// Convert `--` to an en dash and `---` to an em dash.
+    #[serde(default)]
+    pub dashes: bool,
+
+    /// Convert `...` to an ellipsis.
+    #[serde(default)]
+    pub ellipsis: bool,
+
+    /// Quote marks used by `smart_quotes`.
+    #[serde(default)]
+    pub quotes: QuoteStyle,
+}
+
+/// Table of contents configuration.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TocConfig {
+    /// Shallowest heading level listed.
+    #[serde(default = "default_toc_min_depth")]
+    pub min_depth: u8,
+
+    /// Deepest heading level listed.
+    #[serde(default = "default_toc_max_depth")]
+    pub max_depth: u8,
+}
+
+/// Math rendering configuration.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct MathConfig {
+    /// Whether pages load KaTeX to render math.
+    #[serde(default)]
+    pub enabled: bool,
+
+    /// Local KaTeX distribution (the `dist` directory of the `katex` npm
+    /// package) to publish with the site instead of loading KaTeX from a
+    /// CDN.
+    #[serde(default)]
+    pub katex_dir: Option<String>,
+}
+
+/// Mermaid diagram configuration.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct MermaidConfig {
+    /// Render diagrams to inline SVG at build time instead of loading the
+    /// Mermaid script in the browser.
+    #[serde(default)]
+    pub prerender: bool,
+
+    /// Mermaid CLI used to pre-render diagrams.
+    #[serde(default = "default_mermaid_command")]
+    pub command: String,
+}
+
+impl Default for MermaidConfig {
+    fn default() -> Self {
+        Self {
+            prerender: false,
+            command: default_mermaid_command(),
+        }
+    }
+}
+
+fn default_mermaid_command() -> String {
+    "mmdc".to_string()
+}
+
+/// Quotation mark styles.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum QuoteStyle {
+    /// “double” and ‘single’.
+    #[default]
+    English,
+
+    /// « double » and ‹ single › with narrow no-break spaces.
+    French,
+
+    /// „double“ and ‚single‘.
+    German,
+
+    /// 「double」 and 『single』.
+    Japanese,
+}
+
+impl QuoteStyle {
+    /// Opening and closing double quotes, then opening and closing single
+    /// quotes.
+    #[must_use]
+    pub fn marks(self) -> [&'static str; 4] {
+        match self {
+            Self::English => ["\u{201C}", "\u{201D}", "\u{2018}", "\u{2019}"],
+            Self::French => [
+                "\u{AB}\u{202F}",
+                "\u{202F}\u{BB}",
+                "\u{2039}\u{202F}",
+                "\u{202F}\u{203A}",
+            ],
+            Self::German => ["\u{201E}", "\u{201C}", "\u{201A}", "\u{2018}"],
+            Self::Japanese => ["\u{300C}", "\u{300D}", "\u{300E}", "\u{300F}"],
+        }
+    }
+}
+
+/// Stylesheet pipelines.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum CssPipeline {
+    /// Use the embedded default stylesheet.
+    #[default]
+    Builtin,
+
+    /// Compile `input` with the Tailwind CLI.
+    Tailwind,
+
+    /// Compile `input` with the PostCSS CLI.
+    Postcss,
+}
+
 /// Settings for a single taxonomy.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct TaxonomySettings {
     /// Number of items per page.
     #[serde(default = "default_paginate")]
     pub paginate: usize,
+
+    /// Whether to generate an RSS feed for each term.
+    #[serde(default)]
+    pub feed: bool,
+
+    /// Fewest pages a term needs to get a term page; rarer terms are only
+    /// listed on the taxonomy index.
+    #[serde(default = "default_min_pages")]
+    pub min_pages: usize,
+
+    /// Alias -> canonical term (e.g. `rustlang = "rust"`), so that pages
+    /// using either are listed on one term page.
+    #[serde(default)]
+    pub aliases: HashMap<String, String>,
+}
+
+impl TaxonomySettings {
+    /// The canonical term for `term`, resolving aliases.
+    #[must_use]
+    pub fn canonical<'a>(&'a self, term: &'a str) -> &'a str {
+        self.aliases.get(term).map_or(term, String::as_str)
+    }
+
+    /// Whether a term used by `pages` pages gets a term page.
+    #[must_use]
+    pub fn has_term_page(&self, pages: usize) -> bool {
+        pages >= self.min_pages
+    }
 }
 
 // Default value functions
@@ -175,7 +931,19 @@ fn default_output_dir() -> String {
 }
 
 fn default_syntax_theme() -> String {
-    "base16-ocean.dark".to_string()
+    "auto".to_string()
+}
+
+fn default_syntaxes_dir() -> String {
+    "syntaxes".to_string()
+}
+
+fn default_cache_dir() -> String {
+    ".typstify-cache".to_string()
+}
+
+fn default_data_ttl() -> u64 {
+    3600
 }
 
 fn default_true() -> bool {
@@ -198,13 +966,61 @@ fn default_paginate() -> usize {
     10
 }
 
+fn default_min_pages() -> usize {
+    1
+}
+
+fn default_breakpoints() -> Vec<u32> {
+    vec![480, 960, 1600]
+}
+
+fn default_image_formats() -> Vec<String> {
+    vec!["webp".to_string()]
+}
+
+fn default_image_sizes() -> String {
+    "(max-width: 960px) 100vw, 960px".to_string()
+}
+
+fn default_css_input() -> String {
+    "style/input.css".to_string()
+}
+
+fn default_toc_min_depth() -> u8 {
+    1
+}
+
+fn default_toc_max_depth() -> u8 {
+    3
+}
+
+fn default_date_format() -> String {
+    "%B %d, %Y".to_string()
+}
+
 impl Default for BuildConfig {
     fn default() -> Self {
         Self {
             output_dir: default_output_dir(),
             minify: false,
             syntax_theme: default_syntax_theme(),
+            syntax_theme_dark: None,
+            syntaxes_dir: default_syntaxes_dir(),
+            line_numbers: false,
             drafts: false,
+            future: false,
+            fail_on_error: false,
+            link_graph: false,
+            url_map: false,
+            backlinks: false,
+            print_button: false,
+            changelog: false,
+            cache_dir: default_cache_dir(),
+            data_ttl: default_data_ttl(),
+            localize_images: false,
+            strip_metadata: true,
+            keep_metadata: Vec::new(),
+            processors: HashMap::new(),
         }
     }
 }
@@ -215,6 +1031,13 @@ impl Default for SearchConfig {
             enabled: true,
             index_fields: default_index_fields(),
             chunk_size: default_chunk_size(),
+            preload: SearchPreload::default(),
+            inline_max_bytes: 0,
+            miss_endpoint: None,
+            record_misses: false,
+            stemming: true,
+            stopwords: HashMap::new(),
+            encrypted_preview: false,
         }
     }
 }
@@ -224,6 +1047,7 @@ impl Default for RssConfig {
         Self {
             enabled: true,
             limit: default_rss_limit(),
+            exclude_pinned: false,
         }
     }
 }
@@ -242,10 +1066,68 @@ impl Default for TaxonomySettings {
     fn default() -> Self {
         Self {
             paginate: default_paginate(),
+            feed: false,
+            min_pages: default_min_pages(),
+            aliases: HashMap::new(),
+        }
+    }
+}
+
+impl Default for TocConfig {
+    fn default() -> Self {
+        Self {
+            min_depth: default_toc_min_depth(),
+            max_depth: default_toc_max_depth(),
+        }
+    }
+}
+
+impl Default for DatesConfig {
+    fn default() -> Self {
+        Self {
+            format: default_date_format(),
+        }
+    }
+}
+
+impl Default for ImagesConfig {
+    fn default() -> Self {
+        Self {
+            responsive: false,
+            breakpoints: default_breakpoints(),
+            formats: default_image_formats(),
+            sizes: default_image_sizes(),
+        }
+    }
+}
+
+impl Default for CssConfig {
+    fn default() -> Self {
+        Self {
+            pipeline: CssPipeline::default(),
+            input: default_css_input(),
+            command: None,
         }
     }
 }
 
+impl CssConfig {
+    /// Command compiling the stylesheet, or `None` for the builtin pipeline.
+    #[must_use]
+    pub fn command(&self, minify: bool) -> Option<String> {
+        let command = match self.pipeline {
+            CssPipeline::Builtin => return None,
+            _ if self.command.is_some() => return self.command.clone(),
+            CssPipeline::Tailwind if minify => {
+                "tailwindcss --input {input} --output {output} --minify"
+            }
+            CssPipeline::Tailwind => "tailwindcss --input {input} --output {output}",
+            CssPipeline::Postcss => "postcss {input} --output {output}",
+        };
+        Some(command.to_string())
+    }
+}
+
 impl Config {
     /// Load configuration from a TOML file.
     pub fn load(path: &Path) -> Result<Self> {
@@ -295,6 +1177,45 @@ impl Config {
             tracing::warn!("site.host should not have a trailing slash");
         }
 
+        if let Some(tz) = &self.site.timezone
+            && tz.parse::<Tz>().is_err()
+        {
+            return Err(CoreError::config(format!(
+                "site.timezone is not a valid IANA timezone: {tz}"
+            )));
+        }
+
+        for (ext, processor) in &self.build.processors {
+            if processor.command.is_some() == processor.builtin.is_some() {
+                return Err(CoreError::config(format!(
+                    "build.processors.{ext} must set exactly one of `command` or `builtin`"
+                )));
+            }
+        }
+
+        if let Some(theme) = &self.theme
+            && (theme.is_empty() || theme.starts_with('.') || theme.contains(['/', '\\']))
+        {
+            return Err(CoreError::config(format!(
+                "theme must be the name of a directory in themes/: {theme:?}"
+            )));
+        }
+
+        for remote in &self.content.remote {
+            let target = Path::new(&remote.target);
+            if remote.target.is_empty()
+                || target.is_absolute()
+                || target
+                    .components()
+                    .any(|c| !matches!(c, std::path::Component::Normal(_)))
+            {
+                return Err(CoreError::config(format!(
+                    "content.remote target must be a relative path inside the content directory: {:?}",
+                    remote.target
+                )));
+            }
+        }
+
         // Ensure base_path starts with / if not empty
         if !self.site.base_path.is_empty() && !self.site.base_path.starts_with('/') {
             tracing::warn!("site.base_path should start with /");
@@ -361,6 +1282,176 @@ impl Config {
             .or(self.site.description.as_deref())
     }
 
+    /// Get the site timezone, defaulting to UTC.
+    #[must_use]
+    pub fn timezone(&self) -> Tz {
+        self.site
+            .timezone
+            .as_deref()
+            .and_then(|tz| tz.parse().ok())
+            .unwrap_or(Tz::UTC)
+    }
+
+    /// Get the date format for a language, falling back to `dates.format`.
+    #[must_use]
+    pub fn date_format_for_language(&self, lang: &str) -> &str {
+        self.languages
+            .get(lang)
+            .and_then(|lc| lc.date_format.as_deref())
+            .unwrap_or(&self.dates.format)
+    }
+
+    /// Get the label format of weekly archive windows for a language.
+    #[must_use]
+    pub fn week_format_for_language(&self, lang: &str) -> &str {
+        self.languages
+            .get(lang)
+            .and_then(|lc| lc.week_format.as_deref())
+            .unwrap_or("Week %-V, %G")
+    }
+
+    /// Paths of the sections using the docs layout.
+    pub fn docs_sections(&self) -> impl Iterator<Item = &str> {
+        self.sections
+            .iter()
+            .filter(|(_, section)| section.layout == SectionLayout::Docs)
+            .map(|(path, _)| path.trim_matches('/'))
+    }
+
+    /// Whether the page at `url` is in a protected section.
+    #[must_use]
+    pub fn is_protected(&self, url: &str) -> bool {
+        self.section_for_url(url)
+            .is_some_and(|section| section.protected)
+    }
+
+    /// Why `page` is left out of the site at `now`, if it is: drafts and
+    /// pages dated in the future unless `build.drafts` and `build.future`
+    /// include them, and expired pages.
+    #[must_use]
+    pub fn skip_reason(&self, page: &Page, now: DateTime<Utc>) -> Option<&'static str> {
+        if page.draft && !self.build.drafts {
+            Some("draft")
+        } else if page.is_expired(now) {
+            Some("expired")
+        } else if page.is_scheduled(now) && !self.build.future {
+            Some("scheduled")
+        } else {
+            None
+        }
+    }
+
+    /// Whether `page` is published at `now`, and so belongs in feeds and the
+    /// public search index: drafts and future pages (of preview builds),
+    /// expired pages and pages of protected sections aren't.
+    #[must_use]
+    pub fn is_public(&self, page: &Page, now: DateTime<Utc>) -> bool {
+        !page.draft
+            && !page.is_scheduled(now)
+            && !page.is_expired(now)
+            && !self.is_protected(&page.url)
+    }
+
+    /// Whether `page` belongs in the sitemap at `now`: it is public and its
+    /// section is neither excluded from the sitemap nor `noindex`.
+    #[must_use]
+    pub fn in_sitemap(&self, page: &Page, now: DateTime<Utc>) -> bool {
+        self.is_public(page, now)
+            && self
+                .section_for_url(&page.url)
+                .is_none_or(SectionConfig::in_sitemap)
+    }
+
+    /// Settings of the most specific configured section containing `url`
+    /// (a page URL, with or without a language prefix).
+    #[must_use]
+    pub fn section_for_url(&self, url: &str) -> Option<&SectionConfig> {
+        let path = url.trim_matches('/');
+        let path = self
+            .languages
+            .keys()
+            .filter(|lang| **lang != self.site.default_language)
+            .find_map(|lang| {
+                path.strip_prefix(lang.as_str())
+                    .and_then(|rest| rest.strip_prefix('/'))
+            })
+            .unwrap_or(path);
+
+        self.sections
+            .iter()
+            .filter(|(section, _)| {
+                let section = section.trim_matches('/');
+                path.strip_prefix(section)
+                    .is_some_and(|rest| rest.is_empty() || rest.starts_with('/'))
+            })
+            .max_by_key(|(section, _)| section.len())
+            .map(|(_, config)| config)
+    }
+
+    /// Get the typography settings for a language, falling back to
+    /// `[typography]`.
+    #[must_use]
+    pub fn typography_for_language(&self, lang: &str) -> &TypographyConfig {
+        self.languages
+            .get(lang)
+            .and_then(|lc| lc.typography.as_ref())
+            .unwrap_or(&self.typography)
+    }
+
+    /// Format a date for display in the given language.
+    ///
+    /// The date is shown in the site timezone. `%B`/`%b` and `%A`/`%a` use the language's `month_names` and
+    /// `weekday_names` when configured (the short forms take the first three
+    /// characters); everything else follows chrono's `strftime` syntax.
+    #[must_use]
+    pub fn format_date(&self, date: DateTime<Utc>, lang: &str) -> String {
+        self.format_date_as(date, self.date_format_for_language(lang), lang)
+    }
+
+    /// Format a date with `format` instead of the language's date format,
+    /// localized like [`format_date`](Self::format_date).
+    #[must_use]
+    pub fn format_date_as(&self, date: DateTime<Utc>, format: &str, lang: &str) -> String {
+        let date = date.with_timezone(&self.timezone());
+        let lang_config = self.languages.get(lang);
+        let month_names = lang_config.and_then(|lc| lc.month_names.as_deref());
+        let weekday_names = lang_config.and_then(|lc| lc.weekday_names.as_deref());
+
+        let month = month_names.and_then(|n| n.get(date.month0() as usize));
+        let weekday =
+            weekday_names.and_then(|n| n.get(date.weekday().num_days_from_monday() as usize));
+
+        // Substitute localized names, escaping them for the chrono pass
+        let mut resolved = String::with_capacity(format.len());
+        let mut chars = format.chars();
+        while let Some(c) = chars.next() {
+            if c != '%' {
+                resolved.push(c);
+                continue;
+            }
+            let Some(spec) = chars.next() else {
+                resolved.push('%');
+                break;
+            };
+            let name = match spec {
+                'B' => month.map(|m| m.to_string()),
+                'b' => month.map(|m| m.chars().take(3).collect()),
+                'A' => weekday.map(|w| w.to_string()),
+                'a' => weekday.map(|w| w.chars().take(3).collect()),
+                _ => None,
+            };
+            match name {
+                Some(name) => resolved.push_str(&name.replace('%', "%%")),
+                None => {
+                    resolved.push('%');
+                    resolved.push(spec);
+                }
+            }
+        }
+
+        date.format(&resolved).to_string()
+    }
+
     /// Get display name for a language code.
     #[must_use]
     pub fn language_name<'a>(&'a self, lang: &'a str) -> &'a str {
@@ -403,6 +1494,9 @@ limit = 15
 
 [taxonomies.tags]
 paginate = 20
+
+[taxonomies.tags.aliases]
+rustlang = "rust"
 "#
         .to_string()
     }
@@ -433,6 +1527,8 @@ paginate = 20
         assert_eq!(config.search.chunk_size, 32768);
         assert_eq!(config.rss.limit, 15);
         assert_eq!(config.taxonomies.tags.paginate, 20);
+        assert_eq!(config.taxonomies.tags.canonical("rustlang"), "rust");
+        assert_eq!(config.taxonomies.tags.canonical("web"), "web");
     }
 
     #[test]
@@ -451,11 +1547,344 @@ host = "https://example.com"
         assert_eq!(config.site.default_language, "en");
         assert_eq!(config.build.output_dir, "public");
         assert!(!config.build.minify);
+        assert!(!config.build.fail_on_error);
         assert!(config.search.enabled);
         assert_eq!(config.search.chunk_size, 65536);
         assert_eq!(config.rss.limit, 20);
     }
 
+    #[test]
+    fn test_format_date() {
+        let config_content = r#"
+[site]
+title = "Test"
+host = "https://example.com"
+
+[dates]
+format = "%d %B %Y"
+
+[languages.zh]
+date_format = "%Y年%m月%d日 %A"
+weekday_names = ["星期一", "星期二", "星期三", "星期四", "星期五", "星期六", "星期日"]
+
+[languages.fr]
+month_names = ["janvier", "février", "mars", "avril", "mai", "juin", "juillet", "août", "septembre", "octobre", "novembre", "décembre"]
+"#;
+        let config: Config = toml::from_str(config_content).expect("parse");
+        let date = DateTime::parse_from_rfc3339("2026-02-14T10:00:00Z")
+            .expect("date")
+            .with_timezone(&Utc);
+
+        assert_eq!(config.format_date(date, "en"), "14 February 2026");
+        assert_eq!(config.format_date(date, "zh"), "2026年02月14日 星期六");
+        assert_eq!(config.format_date(date, "fr"), "14 février 2026");
+        assert_eq!(config.format_date_as(date, "%B %Y", "fr"), "février 2026");
+
+        let defaults = Config {
+            dates: DatesConfig::default(),
+            ..config
+        };
+        assert_eq!(defaults.format_date(date, "en"), "February 14, 2026");
+    }
+
+    #[test]
+    fn test_timezone() {
+        let mut config: Config = toml::from_str(
+            r#"
+[site]
+title = "Test"
+host = "https://example.com"
+timezone = "Asia/Shanghai"
+"#,
+        )
+        .expect("parse");
+        assert_eq!(config.timezone(), chrono_tz::Asia::Shanghai);
+
+        // Late evening UTC is the next day in Shanghai
+        let date = DateTime::parse_from_rfc3339("2026-01-13T20:00:00Z")
+            .expect("date")
+            .with_timezone(&Utc);
+        assert_eq!(config.format_date(date, "en"), "January 14, 2026");
+
+        config.site.timezone = Some("Mars/Olympus".to_string());
+        assert!(config.validate().is_err());
+        config.site.timezone = None;
+        assert_eq!(config.timezone(), Tz::UTC);
+    }
+
+    #[test]
+    fn test_css_command() {
+        let mut css = CssConfig::default();
+        assert_eq!(css.command(true), None);
+
+        css.pipeline = CssPipeline::Tailwind;
+        assert_eq!(
+            css.command(true).as_deref(),
+            Some("tailwindcss --input {input} --output {output} --minify")
+        );
+
+        css.command = Some("npx @tailwindcss/cli -i {input} -o {output}".to_string());
+        assert_eq!(
+            css.command(false).as_deref(),
+            Some("npx @tailwindcss/cli -i {input} -o {output}")
+        );
+    }
+
+    #[test]
+    fn test_typography_for_language() {
+        let config: Config = toml::from_str(
+            r#"
+[site]
+title = "Test"
+host = "https://example.com"
+
+[typography]
+smart_quotes = true
+dashes = true
+
+[languages.fr.typography]
+smart_quotes = true
+quotes = "french"
+"#,
+        )
+        .unwrap();
+
+        let en = config.typography_for_language("en");
+        assert!(en.dashes);
+        assert_eq!(en.quotes, QuoteStyle::English);
+
+        let fr = config.typography_for_language("fr");
+        assert!(!fr.dashes);
+        assert_eq!(fr.quotes.marks()[0], "\u{AB}\u{202F}");
+    }
+
+    #[test]
+    fn test_section_layouts() {
+        let config: Config = toml::from_str(
+            r#"
+[site]
+title = "Test"
+host = "https://example.com"
+
+[sections.docs]
+layout = "docs"
+
+[sections.posts]
+layout = "blog"
+"#,
+        )
+        .unwrap();
+
+        assert_eq!(config.sections["posts"].layout, SectionLayout::Blog);
+        assert_eq!(config.docs_sections().collect::<Vec<_>>(), vec!["docs"]);
+    }
+
+    #[test]
+    fn test_section_for_url() {
+        let config: Config = toml::from_str(
+            r#"
+[site]
+title = "Test"
+host = "https://example.com"
+
+[languages.zh]
+
+[sections.drafts]
+sitemap = false
+robots = "disallow"
+
+[sections."drafts/public"]
+
+[sections.members]
+protected = true
+"#,
+        )
+        .unwrap();
+
+        let drafts = config.section_for_url("/zh/drafts/idea").unwrap();
+        assert_eq!(drafts.robots, SectionRobots::Disallow);
+        assert!(!drafts.in_sitemap());
+        assert!(
+            config
+                .section_for_url("/drafts/public/post")
+                .unwrap()
+                .in_sitemap()
+        );
+        assert!(config.section_for_url("/drafts-old/post").is_none());
+
+        assert!(config.is_protected("/zh/members/roadmap"));
+        assert!(
+            !config
+                .section_for_url("/members/roadmap")
+                .unwrap()
+                .in_sitemap()
+        );
+        assert!(!config.is_protected("/drafts/idea"));
+    }
+
+    #[test]
+    fn test_publishing() {
+        let mut config: Config = toml::from_str(
+            r#"
+[site]
+title = "Test"
+host = "https://example.com"
+
+[sections.notes]
+robots = "noindex"
+
+[sections.members]
+protected = true
+"#,
+        )
+        .unwrap();
+        let now = Utc::now();
+        let page = |path: &str, frontmatter: crate::Frontmatter| {
+            Page::from_parsed(
+                crate::ParsedContent {
+                    frontmatter,
+                    html: String::new(),
+                    raw: String::new(),
+                    toc: vec![],
+                    dependencies: vec![],
+                },
+                &crate::ContentPath::from_path(Path::new(path), "en").unwrap(),
+            )
+        };
+
+        let post = page("posts/a.md", crate::Frontmatter::default());
+        assert!(config.skip_reason(&post, now).is_none());
+        assert!(config.is_public(&post, now));
+        assert!(config.in_sitemap(&post, now));
+
+        let note = page("notes/a.md", crate::Frontmatter::default());
+        assert!(config.is_public(&note, now));
+        assert!(!config.in_sitemap(&note, now));
+        let member = page("members/a.md", crate::Frontmatter::default());
+        assert!(!config.is_public(&member, now));
+
+        let draft = page(
+            "posts/b.md",
+            crate::Frontmatter {
+                draft: true,
+                ..Default::default()
+            },
+        );
+        let future = page(
+            "posts/c.md",
+            crate::Frontmatter {
+                date: Some(now + chrono::Duration::days(1)),
+                ..Default::default()
+            },
+        );
+        assert_eq!(config.skip_reason(&draft, now), Some("draft"));
+        assert_eq!(config.skip_reason(&future, now), Some("scheduled"));
+
+        // Preview builds include them, but still don't publish them
+        config.build.drafts = true;
+        config.build.future = true;
+        for page in [&draft, &future] {
+            assert!(config.skip_reason(page, now).is_none());
+            assert!(!config.is_public(page, now));
+            assert!(!config.in_sitemap(page, now));
+        }
+    }
+
+    #[test]
+    fn test_remote_content() {
+        let single: Config = toml::from_str(
+            r#"
+[site]
+title = "Test"
+host = "https://example.com"
+
+[content.remote]
+url = "https://github.com/example/project.git"
+ref = "v1.2"
+path = "docs"
+target = "docs"
+"#,
+        )
+        .unwrap();
+        let remote = &single.content.remote[0];
+        assert_eq!(remote.git_ref.as_deref(), Some("v1.2"));
+        assert_eq!(remote.path.as_deref(), Some("docs"));
+        assert!(!remote.is_archive());
+        assert!(single.validate().is_ok());
+
+        let mut many: Config = toml::from_str(
+            r#"
+[site]
+title = "Test"
+host = "https://example.com"
+
+[[content.remote]]
+url = "https://example.com/handbook.tar.gz"
+target = "handbook"
+
+[[content.remote]]
+url = "https://github.com/example/guides.git"
+target = "guides"
+"#,
+        )
+        .unwrap();
+        assert_eq!(many.content.remote.len(), 2);
+        assert!(many.content.remote[0].is_archive());
+
+        many.content.remote[1].target = "../outside".to_string();
+        assert!(many.validate().is_err());
+    }
+
+    #[test]
+    fn test_theme() {
+        let mut config: Config = toml::from_str(
+            r#"
+theme = "paper"
+
+[site]
+title = "Test"
+host = "https://example.com"
+"#,
+        )
+        .unwrap();
+        assert_eq!(config.theme.as_deref(), Some("paper"));
+        assert!(config.validate().is_ok());
+
+        config.theme = Some("../paper".to_string());
+        assert!(config.validate().is_err());
+    }
+
+    #[test]
+    fn test_processors() {
+        let mut config: Config = toml::from_str(
+            r#"
+[site]
+title = "Test"
+host = "https://example.com"
+
+[build.processors]
+scss = { command = "grass {input} {output}", output_extension = "css" }
+css = { builtin = "minify-css" }
+"#,
+        )
+        .expect("parse");
+        assert!(config.validate().is_ok());
+
+        let scss = &config.build.processors["scss"];
+        assert_eq!(scss.command.as_deref(), Some("grass {input} {output}"));
+        assert_eq!(scss.output_extension.as_deref(), Some("css"));
+        assert_eq!(
+            config.build.processors["css"].builtin,
+            Some(BuiltinProcessor::MinifyCss)
+        );
+
+        config
+            .build
+            .processors
+            .insert("ts".to_string(), ProcessorConfig::default());
+        assert!(config.validate().is_err());
+    }
+
     #[test]
     fn test_config_validation_empty_title() {
         let dir = tempfile::tempdir().expect("create temp dir");
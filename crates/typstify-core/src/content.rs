@@ -1,6 +1,9 @@
 //! Content types and structures.
 
-use std::path::{Path, PathBuf};
+use std::{
+    collections::HashSet,
+    path::{Path, PathBuf},
+};
 
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
@@ -67,6 +70,7 @@ impl ContentPath {
     /// - `posts/hello.zh.md` → lang: "zh", canonical_id: "posts/hello", slug: "zh/posts/hello"
     /// - `posts/hello/index.md` → lang: "en" (default), canonical_id: "posts/hello", slug: "posts/hello"
     /// - `posts/hello/index.zh.md` → lang: "zh", canonical_id: "posts/hello", slug: "zh/posts/hello"
+    /// - `docs/guide/_index.md` → lang: "en" (default), canonical_id: "docs/guide", slug: "docs/guide"
     pub fn from_path(path: &Path, default_lang: &str) -> Option<Self> {
         let extension = path.extension()?.to_str()?;
         let content_type = ContentType::from_extension(extension)?;
@@ -95,7 +99,7 @@ impl ContentPath {
 
         // Build the canonical_id (language-neutral) from the path
         let parent = path.parent().unwrap_or(Path::new(""));
-        let canonical_id = if base_stem == "index" {
+        let canonical_id = if base_stem == "index" || base_stem == SECTION_INDEX_STEM {
             // For index files, use the parent directory as the canonical id
             parent.to_string_lossy().to_string()
         } else {
@@ -133,6 +137,19 @@ impl ContentPath {
     }
 }
 
+/// File stem of section index files (`docs/guide/_index.md`), whose
+/// frontmatter describes the section instead of being a page.
+pub const SECTION_INDEX_STEM: &str = "_index";
+
+/// Whether `path` is a section index file, in any language
+/// (`_index.md`, `_index.zh.md`).
+#[must_use]
+pub fn is_section_index(path: &Path) -> bool {
+    path.file_stem()
+        .and_then(|stem| stem.to_str())
+        .is_some_and(|stem| stem.split('.').next() == Some(SECTION_INDEX_STEM))
+}
+
 /// Parsed content with metadata and rendered HTML.
 #[derive(Debug, Clone)]
 pub struct ParsedContent {
@@ -147,10 +164,14 @@ pub struct ParsedContent {
 
     /// Table of contents extracted from headings.
     pub toc: Vec<TocEntry>,
+
+    /// Files besides the source that the content was rendered from
+    /// (Typst imports, images and data files).
+    pub dependencies: Vec<PathBuf>,
 }
 
 /// Table of contents entry.
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub struct TocEntry {
     /// Heading level (1-6).
     pub level: u8,
@@ -160,6 +181,89 @@ pub struct TocEntry {
 
     /// Anchor ID for linking.
     pub id: String,
+
+    /// Deeper headings under this one, in a nested table of contents.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub children: Vec<TocEntry>,
+}
+
+impl TocEntry {
+    /// Create an entry without children.
+    pub fn new(level: u8, text: impl Into<String>, id: impl Into<String>) -> Self {
+        Self {
+            level,
+            text: text.into(),
+            id: id.into(),
+            children: Vec::new(),
+        }
+    }
+
+    /// Nest the headings of `entries` (in document order) under the
+    /// closest preceding shallower heading, keeping levels `min_depth` to
+    /// `max_depth`.
+    pub fn nest(entries: &[TocEntry], min_depth: u8, max_depth: u8) -> Vec<TocEntry> {
+        let mut toc = Vec::new();
+        for entry in entries
+            .iter()
+            .filter(|entry| (min_depth..=max_depth).contains(&entry.level))
+        {
+            insert_nested(&mut toc, TocEntry::new(entry.level, &entry.text, &entry.id));
+        }
+        toc
+    }
+}
+
+fn insert_nested(toc: &mut Vec<TocEntry>, entry: TocEntry) {
+    match toc.last_mut() {
+        Some(last) if last.level < entry.level => insert_nested(&mut last.children, entry),
+        _ => toc.push(entry),
+    }
+}
+
+/// Heading anchors of a page, kept unique by numbering repeats (`setup`,
+/// `setup-1`, ...).
+#[derive(Debug, Default)]
+pub struct HeadingAnchors {
+    used: HashSet<String>,
+}
+
+impl HeadingAnchors {
+    /// Record an anchor the author chose.
+    pub fn reserve(&mut self, id: &str) {
+        self.used.insert(id.to_string());
+    }
+
+    /// `slug`, numbered if it is already taken.
+    pub fn unique(&mut self, slug: &str) -> String {
+        let mut id = slug.to_string();
+        let mut n = 1;
+        while !self.used.insert(id.clone()) {
+            id = format!("{slug}-{n}");
+            n += 1;
+        }
+        id
+    }
+}
+
+/// When and where an event takes place.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct EventInfo {
+    /// Start time.
+    pub start: DateTime<Utc>,
+
+    /// End time, if known.
+    pub end: Option<DateTime<Utc>>,
+
+    /// Venue, address or "Online".
+    pub location: Option<String>,
+}
+
+impl EventInfo {
+    /// Whether the event has not finished at `now`.
+    #[must_use]
+    pub fn is_upcoming(&self, now: DateTime<Utc>) -> bool {
+        self.end.unwrap_or(self.start) >= now
+    }
 }
 
 /// A fully processed page ready for rendering.
@@ -183,6 +287,10 @@ pub struct Page {
     #[serde(default)]
     pub updated: Option<DateTime<Utc>>,
 
+    /// Date from which the page is no longer published.
+    #[serde(default)]
+    pub expiry_date: Option<DateTime<Utc>>,
+
     /// Whether this is a draft.
     #[serde(default)]
     pub draft: bool,
@@ -245,6 +353,35 @@ pub struct Page {
     #[serde(default)]
     pub weight: i32,
 
+    /// Whether the page is pinned to the top of list pages.
+    #[serde(default)]
+    pub pinned: bool,
+
+    /// Series the page is a part of.
+    #[serde(default)]
+    pub series: Option<String>,
+
+    /// Stable content ID from frontmatter, used to redirect old URLs.
+    #[serde(default)]
+    pub content_id: Option<String>,
+
+    /// Extra `<meta>` tags from frontmatter, keyed by name or property.
+    #[serde(default)]
+    pub extra_meta: std::collections::BTreeMap<String, String>,
+
+    /// Whether to add JSON-LD structured data to the page head (default:
+    /// yes).
+    #[serde(default)]
+    pub structured_data: Option<bool>,
+
+    /// Attached images (shorts gallery).
+    #[serde(default)]
+    pub images: Vec<crate::frontmatter::MediaAttachment>,
+
+    /// Event details, for pages with a `start` date.
+    #[serde(default)]
+    pub event: Option<EventInfo>,
+
     /// Source file path.
     #[serde(default)]
     pub source_path: Option<PathBuf>,
@@ -256,14 +393,17 @@ impl Page {
         let fm = &content.frontmatter;
 
         // Calculate word count and reading time
-        let word_count = content.raw.split_whitespace().count() as u32;
+        let word_count = count_words(&content.raw) as u32;
         let reading_time = (word_count / 200).max(1); // Assume 200 WPM
 
-        // Generate summary if not provided
+        // Summary: description, then the excerpt before `<!--more-->`, then
+        // the leading sentences of the content
         let summary = fm.description.clone().or_else(|| {
-            // Take first paragraph or first 160 chars
-            let plain_text = strip_html(&content.html);
-            Some(truncate_at_word_boundary(&plain_text, 160))
+            let summary = match content.html.split_once(SUMMARY_MARKER) {
+                Some((excerpt, _)) => strip_html(excerpt),
+                None => auto_summary(&strip_html(&content.html), SUMMARY_WORDS),
+            };
+            (!summary.is_empty()).then_some(summary)
         });
 
         Self {
@@ -272,6 +412,7 @@ impl Page {
             description: fm.description.clone(),
             date: fm.date,
             updated: fm.updated,
+            expiry_date: fm.expiry_date,
             draft: fm.draft,
             lang: content_path.lang.clone(),
             is_default_lang: content_path.is_default_lang,
@@ -288,26 +429,193 @@ impl Page {
             aliases: fm.aliases.clone(),
             template: fm.template.clone(),
             weight: fm.weight,
+            pinned: fm.pinned,
+            series: fm.series.clone(),
+            content_id: fm.content_id.clone(),
+            extra_meta: fm.extra_meta.clone(),
+            structured_data: fm.structured_data,
+            images: fm.images.clone(),
+            event: fm.start.map(|start| EventInfo {
+                start,
+                end: fm.end,
+                location: fm.location.clone(),
+            }),
             source_path: Some(content_path.path.clone()),
         }
     }
+
+    /// Whether the page is scheduled: dated after `now`.
+    #[must_use]
+    pub fn is_scheduled(&self, now: DateTime<Utc>) -> bool {
+        self.date.is_some_and(|date| date > now)
+    }
+
+    /// Whether the page's `expiry_date` has passed at `now`.
+    #[must_use]
+    pub fn is_expired(&self, now: DateTime<Utc>) -> bool {
+        self.expiry_date.is_some_and(|expiry| expiry <= now)
+    }
 }
 
+/// Marker separating a hand-written excerpt from the rest of the content.
+pub const SUMMARY_MARKER: &str = "<!--more-->";
+
+/// Word budget for auto-generated summaries.
+const SUMMARY_WORDS: usize = 50;
+
+/// Upper bound on auto-generated summary length, in characters.
+const SUMMARY_MAX_CHARS: usize = 300;
+
+/// Elements whose boundaries separate words.
+const BLOCK_TAGS: &[&str] = &[
+    "p",
+    "div",
+    "br",
+    "li",
+    "ul",
+    "ol",
+    "h1",
+    "h2",
+    "h3",
+    "h4",
+    "h5",
+    "h6",
+    "blockquote",
+    "table",
+    "tr",
+    "td",
+    "th",
+    "figure",
+    "figcaption",
+    "section",
+];
+
+/// Elements whose content is skipped entirely.
+const SKIPPED_TAGS: &[&str] = &["pre", "script", "style"];
+
 /// Strip HTML tags from content.
+///
+/// Block boundaries become spaces, entities are decoded, whitespace is
+/// collapsed, and code blocks, scripts and styles are dropped.
 fn strip_html(html: &str) -> String {
     let mut result = String::new();
-    let mut in_tag = false;
-
-    for c in html.chars() {
-        match c {
-            '<' => in_tag = true,
-            '>' => in_tag = false,
-            _ if !in_tag => result.push(c),
-            _ => {}
+    let mut rest = html;
+
+    while let Some(start) = rest.find('<') {
+        result.push_str(&rest[..start]);
+        let Some(end) = rest[start..].find('>') else {
+            rest = "";
+            break;
+        };
+        let tag = &rest[start + 1..start + end];
+        rest = &rest[start + end + 1..];
+
+        let name = tag
+            .trim_start_matches('/')
+            .split(|c: char| c.is_whitespace() || c == '/')
+            .next()
+            .unwrap_or("")
+            .to_ascii_lowercase();
+
+        if !tag.starts_with('/') && SKIPPED_TAGS.contains(&name.as_str()) {
+            let close = format!("</{name}");
+            rest = rest
+                .find(&close)
+                .and_then(|i| rest[i..].find('>').map(|j| &rest[i + j + 1..]))
+                .unwrap_or("");
+            result.push(' ');
+        } else if BLOCK_TAGS.contains(&name.as_str()) {
+            result.push(' ');
         }
     }
+    result.push_str(rest);
 
-    result
+    crate::escape::unescape_html(&result)
+        .split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// Whether a character is written without spaces between words (Chinese,
+/// Japanese); each such character counts as one word.
+fn is_cjk(c: char) -> bool {
+    matches!(
+        c,
+        '\u{3040}'..='\u{30ff}'       // Hiragana, Katakana
+            | '\u{3400}'..='\u{4dbf}' // CJK Extension A
+            | '\u{4e00}'..='\u{9fff}' // CJK Unified Ideographs
+            | '\u{f900}'..='\u{faff}' // CJK Compatibility Ideographs
+            | '\u{20000}'..='\u{2ffff}' // CJK Extensions B and later
+    )
+}
+
+/// Full-width punctuation; separates words but is not one.
+fn is_cjk_punct(c: char) -> bool {
+    matches!(
+        c,
+        '。' | '！' | '？' | '，' | '、' | '；' | '：' | '「' | '」' | '『' | '』' | '（' | '）'
+    )
+}
+
+/// Count words, treating each CJK character as a word.
+pub fn count_words(text: &str) -> usize {
+    word_ends(text).len()
+}
+
+/// Byte offsets just past the end of each word.
+fn word_ends(text: &str) -> Vec<usize> {
+    let mut ends = Vec::new();
+    let mut in_word = false;
+
+    for (i, c) in text.char_indices() {
+        let end = i + c.len_utf8();
+        if c.is_whitespace() || is_cjk_punct(c) {
+            in_word = false;
+        } else if is_cjk(c) {
+            ends.push(end);
+            in_word = false;
+        } else if in_word {
+            // Extend the current word
+            if let Some(last) = ends.last_mut() {
+                *last = end;
+            }
+        } else {
+            ends.push(end);
+            in_word = true;
+        }
+    }
+
+    ends
+}
+
+/// Build a summary from the leading sentences of `text` within `max_words`.
+///
+/// Falls back to cutting mid-sentence (with an ellipsis) when the first
+/// sentence alone exceeds the budget.
+fn auto_summary(text: &str, max_words: usize) -> String {
+    let ends = word_ends(text);
+    if ends.len() <= max_words || max_words == 0 {
+        return truncate_at_word_boundary(text, SUMMARY_MAX_CHARS);
+    }
+
+    // Last sentence end before the first word over budget
+    let limit = ends[max_words];
+    let mut chars = text[..limit].char_indices().peekable();
+    let mut sentence_end = None;
+    while let Some((i, c)) = chars.next() {
+        let ends_sentence = matches!(c, '。' | '！' | '？')
+            || matches!(c, '.' | '!' | '?')
+                && chars.peek().is_some_and(|&(_, next)| next.is_whitespace());
+        if ends_sentence {
+            sentence_end = Some(i + c.len_utf8());
+        }
+    }
+
+    let summary = match sentence_end {
+        Some(end) => text[..end].to_string(),
+        None => format!("{}...", &text[..ends[max_words - 1]]),
+    };
+    truncate_at_word_boundary(&summary, SUMMARY_MAX_CHARS)
 }
 
 /// Truncate text at word boundary, respecting UTF-8 character boundaries.
@@ -412,6 +720,20 @@ mod tests {
         assert_eq!(cp.slug, "zh/posts/hello");
     }
 
+    #[test]
+    fn test_content_path_section_index() {
+        let cp =
+            ContentPath::from_path(Path::new("docs/guide/_index.zh.md"), "en").expect("parse path");
+
+        assert_eq!(cp.lang, "zh");
+        assert_eq!(cp.canonical_id, "docs/guide");
+        assert_eq!(cp.slug, "zh/docs/guide");
+        assert!(is_section_index(&cp.path));
+        assert!(is_section_index(Path::new("_index.md")));
+        assert!(!is_section_index(Path::new("docs/index.md")));
+        assert!(!is_section_index(Path::new("docs/_index-notes.md")));
+    }
+
     #[test]
     fn test_content_path_typst() {
         let path = Path::new("docs/guide.typ");
@@ -433,6 +755,62 @@ mod tests {
         assert_eq!(strip_html("No tags here"), "No tags here");
     }
 
+    #[test]
+    fn test_strip_html_blocks() {
+        assert_eq!(
+            strip_html(
+                "<h2>Intro</h2><p>First.</p>\n<pre><code>let x = 1;</code></pre><p>Second.</p>"
+            ),
+            "Intro First. Second."
+        );
+    }
+
+    #[test]
+    fn test_count_words() {
+        assert_eq!(count_words("Hello, world! Two words."), 4);
+        assert_eq!(count_words("你好，世界。"), 4);
+        assert_eq!(count_words("用 Rust 写博客"), 5);
+    }
+
+    #[test]
+    fn test_auto_summary() {
+        let text = "One two three. Four five six. Seven eight nine.";
+        assert_eq!(auto_summary(text, 100), text);
+        assert_eq!(auto_summary(text, 7), "One two three. Four five six.");
+        assert_eq!(auto_summary(text, 2), "One two...");
+
+        let chinese = "这是第一句。这是第二句。这是第三句。";
+        assert_eq!(auto_summary(chinese, 10), "这是第一句。这是第二句。");
+    }
+
+    #[test]
+    fn test_summary_sources() {
+        let parsed = |html: &str, description: Option<&str>| ParsedContent {
+            frontmatter: Frontmatter {
+                title: "T".to_string(),
+                description: description.map(str::to_string),
+                ..Default::default()
+            },
+            html: html.to_string(),
+            raw: String::new(),
+            toc: Vec::new(),
+            dependencies: Vec::new(),
+        };
+        let path = ContentPath::from_path(Path::new("a.md"), "en").expect("path");
+
+        let page = Page::from_parsed(
+            parsed("<p>Intro.</p>\n<!--more-->\n<p>Rest.</p>", None),
+            &path,
+        );
+        assert_eq!(page.summary.as_deref(), Some("Intro."));
+
+        let page = Page::from_parsed(parsed("<p>Intro.</p>", Some("Desc")), &path);
+        assert_eq!(page.summary.as_deref(), Some("Desc"));
+
+        let page = Page::from_parsed(parsed("<p>Only <em>text</em>.</p>", None), &path);
+        assert_eq!(page.summary.as_deref(), Some("Only text."));
+    }
+
     #[test]
     fn test_truncate_at_word_boundary() {
         let text = "Hello world this is a test";
@@ -452,4 +830,41 @@ mod tests {
         let chinese_text = "你好世界 Hello World";
         assert_eq!(truncate_at_word_boundary(chinese_text, 7), "你好世界...");
     }
+
+    #[test]
+    fn test_nest_toc() {
+        let flat = [
+            TocEntry::new(1, "Title", "title"),
+            TocEntry::new(2, "Install", "install"),
+            TocEntry::new(4, "Deep", "deep"),
+            TocEntry::new(3, "Linux", "linux"),
+            TocEntry::new(2, "Usage", "usage"),
+        ];
+
+        let toc = TocEntry::nest(&flat, 2, 3);
+        assert_eq!(
+            toc,
+            vec![
+                TocEntry {
+                    children: vec![TocEntry::new(3, "Linux", "linux")],
+                    ..TocEntry::new(2, "Install", "install")
+                },
+                TocEntry::new(2, "Usage", "usage"),
+            ]
+        );
+
+        let all = TocEntry::nest(&flat, 1, 6);
+        assert_eq!(all.len(), 1);
+        assert_eq!(all[0].children[0].children[0].id, "deep");
+        assert_eq!(all[0].children[0].children[1].id, "linux");
+    }
+
+    #[test]
+    fn test_heading_anchors() {
+        let mut anchors = HeadingAnchors::default();
+        anchors.reserve("setup-1");
+        assert_eq!(anchors.unique("setup"), "setup");
+        assert_eq!(anchors.unique("setup"), "setup-2");
+        assert_eq!(anchors.unique("usage"), "usage");
+    }
 }
@@ -0,0 +1,430 @@
+//! Content formatting for `typstify fmt`.
+//!
+//! Normalizes the frontmatter of a content file as configured in `[fmt]`:
+//! top-level keys are put in `key_order`, dates are rewritten in one format
+//! and tags and categories are recased. The frontmatter is rewritten line by
+//! line, so comments, quoting and nested values are kept. Trailing
+//! whitespace is removed throughout the file (except for Markdown hard line
+//! breaks) and the file ends with a single newline.
+
+use std::fmt::Write;
+
+use chrono::{DateTime, NaiveDate};
+use chrono_tz::Tz;
+
+use crate::{
+    config::{FmtConfig, TagCase},
+    content::ContentType,
+    frontmatter::{DATE_FIELDS, FrontmatterFormat, parse_local_date},
+};
+
+/// Marker line opening Typst comment frontmatter.
+const TYPST_MARKER: &str = "// typstify:frontmatter";
+
+/// Frontmatter fields holding tags.
+const TAG_FIELDS: [&str; 2] = ["tags", "categories"];
+
+/// Format `content`, a file of type `content_type`.
+///
+/// Returns `None` when the file is already formatted.
+#[must_use]
+pub fn format(content: &str, content_type: ContentType, config: &FmtConfig) -> Option<String> {
+    let newline = if content.contains("\r\n") {
+        "\r\n"
+    } else {
+        "\n"
+    };
+    let lines: Vec<&str> = content.lines().collect();
+    let mut out: Vec<String> = Vec::with_capacity(lines.len());
+
+    let body_start = match frontmatter_range(&lines, content_type) {
+        Some((format, start, end)) => {
+            out.extend(
+                lines[..start]
+                    .iter()
+                    .map(|line| line.trim_end().to_string()),
+            );
+            if content_type == ContentType::Typst {
+                let fm_lines: Vec<&str> = lines[start..end]
+                    .iter()
+                    .map(|line| {
+                        let line = line.trim();
+                        line.strip_prefix("// ").unwrap_or(&line[2..])
+                    })
+                    .collect();
+                out.extend(
+                    format_frontmatter(&fm_lines, format, config)
+                        .into_iter()
+                        .map(|line| {
+                            if line.is_empty() {
+                                "//".to_string()
+                            } else {
+                                format!("// {line}")
+                            }
+                        }),
+                );
+            } else {
+                out.extend(format_frontmatter(&lines[start..end], format, config));
+            }
+            end
+        }
+        None => 0,
+    };
+
+    for (i, line) in lines.iter().enumerate().skip(body_start) {
+        let trimmed = line.trim_end();
+        // Two trailing spaces before more text are a Markdown line break
+        let hard_break = content_type == ContentType::Markdown
+            && line.ends_with("  ")
+            && !trimmed.is_empty()
+            && lines.get(i + 1).is_some_and(|next| !next.trim().is_empty());
+        out.push(if hard_break { line } else { trimmed }.to_string());
+    }
+    while out.last().is_some_and(String::is_empty) {
+        out.pop();
+    }
+
+    let mut formatted = out.join(newline);
+    if !formatted.is_empty() {
+        formatted.push_str(newline);
+    }
+    (formatted != content).then_some(formatted)
+}
+
+/// Format and line range of the frontmatter in `lines`, without its
+/// delimiters.
+fn frontmatter_range(
+    lines: &[&str],
+    content_type: ContentType,
+) -> Option<(FrontmatterFormat, usize, usize)> {
+    match content_type {
+        ContentType::Markdown => {
+            let open = lines.iter().position(|line| !line.trim().is_empty())?;
+            let format = [FrontmatterFormat::Yaml, FrontmatterFormat::Toml]
+                .into_iter()
+                .find(|format| lines[open].trim_end() == format.delimiter())?;
+            let close = lines[open + 1..]
+                .iter()
+                .position(|line| line.trim_end() == format.delimiter())?;
+            Some((format, open + 1, open + 1 + close))
+        }
+        ContentType::Typst => {
+            if lines.first()?.trim() != TYPST_MARKER {
+                return None;
+            }
+            let len = lines[1..]
+                .iter()
+                .take_while(|line| {
+                    let line = line.trim();
+                    line.starts_with("// ") || line == "//"
+                })
+                .count();
+            Some((FrontmatterFormat::Yaml, 1, 1 + len))
+        }
+    }
+}
+
+/// A top-level frontmatter entry and the comments before it.
+struct Entry {
+    key: String,
+    lines: Vec<String>,
+    /// Index of the line holding the key.
+    key_line: usize,
+}
+
+/// Format the frontmatter `lines`.
+fn format_frontmatter(
+    lines: &[&str],
+    format: FrontmatterFormat,
+    config: &FmtConfig,
+) -> Vec<String> {
+    let lines: Vec<&str> = lines.iter().map(|line| line.trim_end()).collect();
+    // TOML tables are left as they are
+    let tables = match format {
+        FrontmatterFormat::Yaml => lines.len(),
+        FrontmatterFormat::Toml => lines
+            .iter()
+            .position(|line| is_toml_table(line))
+            .unwrap_or(lines.len()),
+    };
+
+    let mut entries: Vec<Entry> = Vec::new();
+    let mut pending: Vec<String> = Vec::new();
+    for &line in &lines[..tables] {
+        let trimmed = line.trim_start();
+        if let Some(key) = entry_key(line, format) {
+            let mut lines = std::mem::take(&mut pending);
+            lines.push(line.to_string());
+            entries.push(Entry {
+                key,
+                key_line: lines.len() - 1,
+                lines,
+            });
+        } else if trimmed.is_empty() || trimmed.starts_with('#') {
+            pending.push(line.to_string());
+        } else if let Some(entry) = entries.last_mut() {
+            entry.lines.append(&mut pending);
+            entry.lines.push(line.to_string());
+        } else {
+            pending.push(line.to_string());
+        }
+    }
+
+    let rank = |entry: &Entry| {
+        config
+            .key_order
+            .iter()
+            .position(|key| *key == entry.key)
+            .unwrap_or(config.key_order.len())
+    };
+    entries.sort_by_key(rank);
+
+    let mut out = Vec::with_capacity(lines.len());
+    for mut entry in entries {
+        let key = entry.key.as_str();
+        if DATE_FIELDS.contains(&key) {
+            let line = &mut entry.lines[entry.key_line];
+            let (prefix, value) = split_value(line, format);
+            let (value, comment) = split_comment(value);
+            if let Some(date) = format_date(value, format, config) {
+                *line = format!("{prefix}{date}{comment}");
+            }
+        } else if TAG_FIELDS.contains(&key) && config.tag_case != TagCase::Preserve {
+            for (i, line) in entry.lines.iter_mut().enumerate().skip(entry.key_line) {
+                let (prefix, value) = if i == entry.key_line {
+                    split_value(line, format)
+                } else {
+                    let item = line.trim_start();
+                    let item = match format {
+                        FrontmatterFormat::Yaml => item.strip_prefix("- ").unwrap_or(item),
+                        FrontmatterFormat::Toml => item,
+                    };
+                    line.split_at(line.len() - item.len())
+                };
+                let (value, comment) = split_comment(value);
+                *line = format!("{prefix}{}{comment}", recase_list(value, config.tag_case));
+            }
+        }
+        out.append(&mut entry.lines);
+    }
+    out.append(&mut pending);
+    out.extend(lines[tables..].iter().map(|line| line.to_string()));
+    out
+}
+
+/// Key of the top-level entry starting at `line`, if it starts one.
+fn entry_key(line: &str, format: FrontmatterFormat) -> Option<String> {
+    let first = line.chars().next()?;
+    if first.is_whitespace() || matches!(first, '#' | '-' | '[' | ']' | '{' | '}' | ',') {
+        return None;
+    }
+    let key = match format {
+        FrontmatterFormat::Yaml => line
+            .split_once(": ")
+            .map(|(key, _)| key)
+            .or_else(|| line.strip_suffix(':'))?,
+        FrontmatterFormat::Toml => line.split_once('=')?.0,
+    };
+    let key = unquote(key.trim()).1;
+    let valid = !key.is_empty()
+        && key
+            .chars()
+            .all(|c| c.is_alphanumeric() || matches!(c, '_' | '-' | '.'));
+    valid.then(|| key.to_string())
+}
+
+/// Whether `line` is a TOML table header (`[extra]`, `[[links]]`).
+fn is_toml_table(line: &str) -> bool {
+    line.starts_with('[')
+        && line.ends_with(']')
+        && line
+            .trim_matches(|c| c == '[' || c == ']')
+            .chars()
+            .all(|c| c.is_alphanumeric() || matches!(c, '_' | '-' | '.' | '"' | ' '))
+}
+
+/// Split the key line `line` into the key with its separator, and the value.
+fn split_value(line: &str, format: FrontmatterFormat) -> (&str, &str) {
+    let separator = match format {
+        FrontmatterFormat::Yaml => line.find(':'),
+        FrontmatterFormat::Toml => line.find('='),
+    }
+    .map_or(line.len(), |i| i + 1);
+    let value = line[separator..].trim_start();
+    line.split_at(line.len() - value.len())
+}
+
+/// Split a trailing `# comment` off `value`, keeping the spaces before it
+/// with the comment.
+fn split_comment(value: &str) -> (&str, &str) {
+    let mut quote = None;
+    let mut previous = ' ';
+    for (i, c) in value.char_indices() {
+        match (quote, c) {
+            (None, '"' | '\'') => quote = Some(c),
+            (Some(q), _) if c == q => quote = None,
+            (None, '#') if previous.is_whitespace() => {
+                return value.split_at(value[..i].trim_end().len());
+            }
+            _ => {}
+        }
+        previous = c;
+    }
+    (value, "")
+}
+
+/// The quote around `value` (empty when it is not quoted) and the unquoted
+/// value.
+fn unquote(value: &str) -> (&str, &str) {
+    for quote in ["\"", "'"] {
+        if let Some(inner) = value
+            .strip_prefix(quote)
+            .and_then(|value| value.strip_suffix(quote))
+        {
+            return (quote, inner);
+        }
+    }
+    ("", value)
+}
+
+/// `value`, a date, in the configured format; `None` when it is already
+/// formatted, isn't a date or the format would not read back as the same
+/// time.
+fn format_date(value: &str, format: FrontmatterFormat, config: &FmtConfig) -> Option<String> {
+    let (quote, date) = unquote(value);
+    let formatted = if DateTime::parse_from_rfc3339(date).is_ok() {
+        return None;
+    } else if let Ok(date) = DateTime::parse_from_str(date, "%Y-%m-%d %H:%M:%S%:z") {
+        date.to_rfc3339()
+    } else {
+        let time = parse_local_date(date, Tz::UTC)?;
+        let pattern = if NaiveDate::parse_from_str(date, "%Y-%m-%d").is_ok() {
+            &config.date_format
+        } else {
+            &config.datetime_format
+        };
+        let mut formatted = String::new();
+        write!(formatted, "{}", time.naive_utc().format(pattern)).ok()?;
+        if parse_local_date(&formatted, Tz::UTC) != Some(time) {
+            return None;
+        }
+        formatted
+    };
+    if formatted == date {
+        return None;
+    }
+
+    let formatted = format!("{quote}{formatted}{quote}");
+    // Unquoted TOML dates must stay valid TOML datetimes
+    if format == FrontmatterFormat::Toml
+        && quote.is_empty()
+        && toml::from_str::<toml::Table>(&format!("date = {formatted}")).is_err()
+    {
+        return None;
+    }
+    Some(formatted)
+}
+
+/// Recase the items of `list`, a flow list (`[Rust, "Web Dev"]`), a list
+/// item or a single value; quoting and spacing are kept.
+fn recase_list(list: &str, case: TagCase) -> String {
+    let mut out = String::with_capacity(list.len());
+    let mut item = String::new();
+    let mut quote = None;
+    for c in list.chars() {
+        match (quote, c) {
+            (None, '"' | '\'') => {
+                quote = Some(c);
+                item.push(c);
+            }
+            (Some(q), _) if c == q => {
+                quote = None;
+                item.push(c);
+            }
+            (None, '[' | ']' | ',') => {
+                out.push_str(&recase_item(&item, case));
+                item.clear();
+                out.push(c);
+            }
+            _ => item.push(c),
+        }
+    }
+    out.push_str(&recase_item(&item, case));
+    out
+}
+
+/// Recase the list item `item`, keeping the whitespace and quotes around it.
+fn recase_item(item: &str, case: TagCase) -> String {
+    let trimmed = item.trim();
+    if trimmed.is_empty() {
+        return item.to_string();
+    }
+    let (quote, tag) = unquote(trimmed);
+    let tag = match case {
+        TagCase::Preserve => tag.to_string(),
+        TagCase::Lower => tag.to_lowercase(),
+        TagCase::Kebab => tag
+            .to_lowercase()
+            .split(|c: char| c.is_whitespace() || c == '_')
+            .filter(|word| !word.is_empty())
+            .collect::<Vec<_>>()
+            .join("-"),
+    };
+    let start = item.len() - item.trim_start().len();
+    let end = start + trimmed.len();
+    format!("{}{quote}{tag}{quote}{}", &item[..start], &item[end..])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_format_markdown() {
+        let config = FmtConfig {
+            tag_case: TagCase::Kebab,
+            ..FmtConfig::default()
+        };
+        let content = "---\ntags: [Rust, \"Web Dev\"]  # topics\n# Shown in listings\ntitle: \"Hello\"   \ncustom: 1\ndate: 2024-01-14 10:30\nupdated: \"2024-02-01T08:00:00+01:00\"\ncategories:\n  - Release_Notes\nextra_meta:\n  a: b\n---\n\nLine one  \nline two  \n\nEnd.   \n\n\n";
+        assert_eq!(
+            format(content, ContentType::Markdown, &config).unwrap(),
+            "---\n# Shown in listings\ntitle: \"Hello\"\ndate: 2024-01-14 10:30:00\nupdated: \"2024-02-01T08:00:00+01:00\"\ntags: [rust, \"web-dev\"]  # topics\ncategories:\n  - release-notes\ncustom: 1\nextra_meta:\n  a: b\n---\n\nLine one  \nline two\n\nEnd.\n"
+        );
+
+        let formatted = "---\ntitle: Hello\ndate: 2024-01-14\n---\n\nBody\n";
+        assert_eq!(format(formatted, ContentType::Markdown, &config), None);
+        // A format that loses the time of day is not applied
+        let config = FmtConfig {
+            datetime_format: "%Y-%m-%d".to_string(),
+            ..FmtConfig::default()
+        };
+        assert_eq!(
+            format(
+                "---\ndate: 2024-01-14 10:30\n---\n",
+                ContentType::Markdown,
+                &config
+            ),
+            None
+        );
+    }
+
+    #[test]
+    fn test_format_toml_and_typst() {
+        let config = FmtConfig {
+            tag_case: TagCase::Lower,
+            ..FmtConfig::default()
+        };
+        let toml = "+++\ndate = 2024-01-14T09:00\ntags = [\n  \"Rust\",\n]\ntitle = \"Hello\"\n\n[extra]\ntitle = \"X\"\n+++\nBody\n";
+        assert_eq!(
+            format(toml, ContentType::Markdown, &config).unwrap(),
+            "+++\ntitle = \"Hello\"\ndate = 2024-01-14 09:00:00\ntags = [\n  \"rust\",\n]\n\n[extra]\ntitle = \"X\"\n+++\nBody\n"
+        );
+
+        let typst =
+            "// typstify:frontmatter\n// tags: [\"Typst\"]\n//\n// title: \"Notes\"\n\n= Notes  \n";
+        assert_eq!(
+            format(typst, ContentType::Typst, &config).unwrap(),
+            "// typstify:frontmatter\n//\n// title: \"Notes\"\n// tags: [\"typst\"]\n\n= Notes\n"
+        );
+    }
+}
@@ -2,7 +2,8 @@
 
 use std::path::Path;
 
-use chrono::{DateTime, Utc};
+use chrono::{DateTime, NaiveDate, NaiveDateTime, TimeZone, Utc};
+use chrono_tz::Tz;
 use serde::{Deserialize, Serialize};
 
 use crate::error::{CoreError, Result};
@@ -21,6 +22,10 @@ pub struct Frontmatter {
     #[serde(default)]
     pub updated: Option<DateTime<Utc>>,
 
+    /// Date from which the page is no longer published.
+    #[serde(default)]
+    pub expiry_date: Option<DateTime<Utc>>,
+
     /// Whether this is a draft.
     #[serde(default)]
     pub draft: bool,
@@ -57,11 +62,100 @@ pub struct Frontmatter {
     #[serde(default)]
     pub weight: i32,
 
+    /// Whether to pin the page to the top of list pages.
+    #[serde(default)]
+    pub pinned: bool,
+
+    /// Name of the series the page is a part of.
+    #[serde(default)]
+    pub series: Option<String>,
+
+    /// Stable identifier that survives renames; old URLs redirect to the
+    /// page's new URL.
+    #[serde(default)]
+    pub content_id: Option<String>,
+
+    /// Extra `<meta>` tags for the page head, keyed by name or property
+    /// (e.g. `fediverse:creator`, `google-site-verification`).
+    #[serde(default)]
+    pub extra_meta: std::collections::BTreeMap<String, String>,
+
+    /// Whether to add JSON-LD structured data (`BlogPosting`,
+    /// `BreadcrumbList`, `WebSite`) to the page head; `false` leaves it out.
+    #[serde(default)]
+    pub structured_data: Option<bool>,
+
+    /// Images attached to a short, shown as a gallery below its text.
+    #[serde(default)]
+    pub images: Vec<MediaAttachment>,
+
+    /// Event start; makes the page an event.
+    #[serde(default)]
+    pub start: Option<DateTime<Utc>>,
+
+    /// Event end.
+    #[serde(default)]
+    pub end: Option<DateTime<Utc>>,
+
+    /// Event location (venue, address or "Online").
+    #[serde(default)]
+    pub location: Option<String>,
+
     /// Custom extra fields (for extensibility).
     #[serde(default, flatten)]
     pub extra: std::collections::HashMap<String, serde_yaml::Value>,
 }
 
+/// An image attached to a page, written either as a path or as a table
+/// with `src` and `alt`.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(from = "AttachmentDef")]
+pub struct MediaAttachment {
+    /// Image path, relative to the content file or absolute.
+    pub src: String,
+
+    /// Alternative text.
+    #[serde(default)]
+    pub alt: Option<String>,
+
+    /// Thumbnail URL, set during the build.
+    #[serde(default)]
+    pub thumbnail: Option<String>,
+}
+
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum AttachmentDef {
+    Src(String),
+    Full {
+        src: String,
+        #[serde(default)]
+        alt: Option<String>,
+        #[serde(default)]
+        thumbnail: Option<String>,
+    },
+}
+
+impl From<AttachmentDef> for MediaAttachment {
+    fn from(def: AttachmentDef) -> Self {
+        match def {
+            AttachmentDef::Src(src) => Self {
+                src,
+                ..Default::default()
+            },
+            AttachmentDef::Full {
+                src,
+                alt,
+                thumbnail,
+            } => Self {
+                src,
+                alt,
+                thumbnail,
+            },
+        }
+    }
+}
+
 /// Delimiter types for frontmatter.
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum FrontmatterFormat {
@@ -106,20 +200,103 @@ pub fn split_frontmatter(content: &str) -> Option<(FrontmatterFormat, &str, &str
     Some((format, frontmatter, body))
 }
 
+/// Date fields that accept values without a UTC offset.
+pub(crate) const DATE_FIELDS: [&str; 5] = ["date", "updated", "expiry_date", "start", "end"];
+
+/// Parse a date without an offset (`2024-01-14`, `2024-01-14 10:00[:00]`)
+/// as local time in `timezone`.
+///
+/// Returns `None` for dates that already carry an offset or don't parse.
+pub(crate) fn parse_local_date(value: &str, timezone: Tz) -> Option<DateTime<Utc>> {
+    let value = value.trim();
+    if DateTime::parse_from_rfc3339(value).is_ok() {
+        return None;
+    }
+
+    let naive = [
+        "%Y-%m-%dT%H:%M:%S",
+        "%Y-%m-%d %H:%M:%S",
+        "%Y-%m-%dT%H:%M",
+        "%Y-%m-%d %H:%M",
+    ]
+    .iter()
+    .find_map(|fmt| NaiveDateTime::parse_from_str(value, fmt).ok())
+    .or_else(|| {
+        NaiveDate::parse_from_str(value, "%Y-%m-%d")
+            .ok()
+            .and_then(|d| d.and_hms_opt(0, 0, 0))
+    })?;
+
+    timezone
+        .from_local_datetime(&naive)
+        .earliest()
+        .map(|dt| dt.with_timezone(&Utc))
+}
+
+/// Deserialize YAML frontmatter, resolving offset-less dates in `timezone`.
+fn from_yaml(fm_str: &str, path: &Path, timezone: Tz) -> Result<Frontmatter> {
+    let mut value: serde_yaml::Value =
+        serde_yaml::from_str(fm_str).map_err(|e| CoreError::frontmatter(path, e.to_string()))?;
+
+    if let Some(map) = value.as_mapping_mut() {
+        for field in DATE_FIELDS {
+            if let Some(date) = map.get_mut(field)
+                && let Some(resolved) = date.as_str().and_then(|d| parse_local_date(d, timezone))
+            {
+                *date = serde_yaml::Value::String(resolved.to_rfc3339());
+            }
+        }
+    }
+
+    serde_yaml::from_value(value).map_err(|e| CoreError::frontmatter(path, e.to_string()))
+}
+
+/// Deserialize TOML frontmatter, resolving offset-less dates in `timezone`.
+fn from_toml(fm_str: &str, path: &Path, timezone: Tz) -> Result<Frontmatter> {
+    let mut table: toml::Table =
+        toml::from_str(fm_str).map_err(|e| CoreError::frontmatter(path, e.to_string()))?;
+
+    for field in DATE_FIELDS {
+        // Native TOML datetimes (`date = 2024-01-14`) are passed on as strings
+        let Some(date) = table.get_mut(field) else {
+            continue;
+        };
+        let text = match date {
+            toml::Value::Datetime(dt) => dt.to_string(),
+            toml::Value::String(s) => s.clone(),
+            _ => continue,
+        };
+        let resolved = parse_local_date(&text, timezone).map_or(text, |d| d.to_rfc3339());
+        *date = toml::Value::String(resolved);
+    }
+
+    toml::Value::Table(table)
+        .try_into()
+        .map_err(|e| CoreError::frontmatter(path, e.to_string()))
+}
+
 /// Parse frontmatter from a string.
+///
+/// Dates without an offset are interpreted as UTC.
 pub fn parse_frontmatter(content: &str, path: &Path) -> Result<(Frontmatter, String)> {
+    parse_frontmatter_with_timezone(content, path, Tz::UTC)
+}
+
+/// Parse frontmatter from a string, interpreting dates without an offset as
+/// local time in `timezone`.
+pub fn parse_frontmatter_with_timezone(
+    content: &str,
+    path: &Path,
+    timezone: Tz,
+) -> Result<(Frontmatter, String)> {
     let Some((format, fm_str, body)) = split_frontmatter(content) else {
         // No frontmatter found, return default with full content
         return Ok((Frontmatter::default(), content.to_string()));
     };
 
-    let frontmatter: Frontmatter = match format {
-        FrontmatterFormat::Yaml => {
-            serde_yaml::from_str(fm_str).map_err(|e| CoreError::frontmatter(path, e.to_string()))?
-        }
-        FrontmatterFormat::Toml => {
-            toml::from_str(fm_str).map_err(|e| CoreError::frontmatter(path, e.to_string()))?
-        }
+    let frontmatter = match format {
+        FrontmatterFormat::Yaml => from_yaml(fm_str, path, timezone)?,
+        FrontmatterFormat::Toml => from_toml(fm_str, path, timezone)?,
     };
 
     Ok((frontmatter, body.to_string()))
@@ -135,6 +312,16 @@ pub fn parse_frontmatter(content: &str, path: &Path) -> Result<(Frontmatter, Str
 /// // tags: [rust, typst]
 /// ```
 pub fn parse_typst_frontmatter(content: &str, path: &Path) -> Result<(Frontmatter, String)> {
+    parse_typst_frontmatter_with_timezone(content, path, Tz::UTC)
+}
+
+/// Parse Typst comment frontmatter, interpreting dates without an offset as
+/// local time in `timezone`.
+pub fn parse_typst_frontmatter_with_timezone(
+    content: &str,
+    path: &Path,
+    timezone: Tz,
+) -> Result<(Frontmatter, String)> {
     let mut fm_lines = Vec::new();
     let mut body_start = 0;
     let mut in_frontmatter = false;
@@ -169,8 +356,7 @@ pub fn parse_typst_frontmatter(content: &str, path: &Path) -> Result<(Frontmatte
     }
 
     let fm_str = fm_lines.join("\n");
-    let frontmatter: Frontmatter =
-        serde_yaml::from_str(&fm_str).map_err(|e| CoreError::frontmatter(path, e.to_string()))?;
+    let frontmatter = from_yaml(&fm_str, path, timezone)?;
 
     let body = if body_start < content.len() {
         content[body_start..].trim_start().to_string()
@@ -187,6 +373,14 @@ impl Frontmatter {
         if self.title.is_empty() {
             return Err(CoreError::frontmatter(path, "title is required"));
         }
+        if self.end.is_some() && self.start.is_none() {
+            return Err(CoreError::frontmatter(path, "end requires start"));
+        }
+        if let (Some(start), Some(end)) = (self.start, self.end)
+            && end < start
+        {
+            return Err(CoreError::frontmatter(path, "end is before start"));
+        }
         Ok(())
     }
 }
@@ -289,6 +483,42 @@ Some typst content."#;
         assert!(body.starts_with("= Heading"));
     }
 
+    #[test]
+    fn test_naive_dates_use_timezone() {
+        let expected = DateTime::parse_from_rfc3339("2024-01-13T16:00:00Z")
+            .expect("date")
+            .with_timezone(&Utc);
+        let tz = chrono_tz::Asia::Shanghai;
+
+        let yaml = "---\ntitle: \"T\"\ndate: 2024-01-14\nupdated: 2024-01-14 08:30\n---\n\nBody";
+        let (fm, _) =
+            parse_frontmatter_with_timezone(yaml, Path::new("a.md"), tz).expect("parse yaml");
+        assert_eq!(fm.date, Some(expected));
+        assert_eq!(
+            fm.updated,
+            Some(expected + chrono::Duration::minutes(8 * 60 + 30))
+        );
+
+        let toml = "+++\ntitle = \"T\"\ndate = 2024-01-14\n+++\n\nBody";
+        let (fm, _) =
+            parse_frontmatter_with_timezone(toml, Path::new("a.md"), tz).expect("parse toml");
+        assert_eq!(fm.date, Some(expected));
+
+        let typst = "// typstify:frontmatter\n// title: \"T\"\n// date: 2024-01-14\n\n= Hi";
+        let (fm, _) = parse_typst_frontmatter_with_timezone(typst, Path::new("a.typ"), tz)
+            .expect("parse typst");
+        assert_eq!(fm.date, Some(expected));
+
+        // Explicit offsets are kept as written
+        let explicit = "---\ntitle: \"T\"\ndate: 2024-01-14T00:00:00Z\n---\n";
+        let (fm, _) =
+            parse_frontmatter_with_timezone(explicit, Path::new("a.md"), tz).expect("parse");
+        assert_eq!(
+            fm.date.map(|d| d.to_rfc3339()).as_deref(),
+            Some("2024-01-14T00:00:00+00:00")
+        );
+    }
+
     #[test]
     fn test_frontmatter_with_extra_fields() {
         let content = r#"---
@@ -304,6 +534,28 @@ Body"#;
         assert!(fm.extra.contains_key("custom_field"));
     }
 
+    #[test]
+    fn test_image_attachments() {
+        let content = "---\ntitle: \"Short\"\nimages:\n  - beach.jpg\n  - src: /img/sunset.png\n    alt: \"Sunset\"\n---\n";
+
+        let (fm, _body) = parse_frontmatter(content, Path::new("test.md")).expect("parse");
+
+        assert_eq!(
+            fm.images,
+            vec![
+                MediaAttachment {
+                    src: "beach.jpg".to_string(),
+                    ..Default::default()
+                },
+                MediaAttachment {
+                    src: "/img/sunset.png".to_string(),
+                    alt: Some("Sunset".to_string()),
+                    thumbnail: None,
+                },
+            ]
+        );
+    }
+
     #[test]
     fn test_frontmatter_defaults() {
         let content = r#"---
@@ -5,9 +5,12 @@
 pub mod config;
 pub mod content;
 pub mod error;
+pub mod escape;
+pub mod format;
 pub mod frontmatter;
 
 pub use config::Config;
-pub use content::{ContentPath, ContentType, Page, ParsedContent};
+pub use content::{ContentPath, ContentType, HeadingAnchors, Page, ParsedContent, TocEntry};
 pub use error::{CoreError, Result};
+pub use escape::{escape_html, escape_xml, unescape_html};
 pub use frontmatter::Frontmatter;
@@ -1,16 +1,19 @@
 //! Asset processing and management.
 //!
-//! Handles copying static assets and optional fingerprinting for cache busting.
+//! Handles copying static assets, running per-extension processors and
+//! optional fingerprinting for cache busting.
 
 use std::{
     collections::HashMap,
     fs,
     io::Read,
     path::{Path, PathBuf},
+    process::Command,
 };
 
 use thiserror::Error;
 use tracing::{debug, info};
+use typstify_core::config::{BuiltinProcessor, ProcessorConfig};
 
 /// Asset processing errors.
 #[derive(Debug, Error)]
@@ -22,6 +25,10 @@ pub enum AssetError {
     /// Invalid asset path.
     #[error("invalid asset path: {0}")]
     InvalidPath(PathBuf),
+
+    /// Asset processor failed.
+    #[error("processor failed for {path}: {message}")]
+    Processor { path: PathBuf, message: String },
 }
 
 /// Result type for asset operations.
@@ -82,6 +89,15 @@ pub struct AssetProcessor {
 
     /// File extensions to fingerprint.
     fingerprint_extensions: Vec<String>,
+
+    /// Processors keyed by source file extension.
+    processors: HashMap<String, ProcessorConfig>,
+
+    /// Whether to strip metadata from JPEG and PNG files.
+    strip_metadata: bool,
+
+    /// Directories (relative to the source directory) exempt from stripping.
+    keep_metadata: Vec<PathBuf>,
 }
 
 impl AssetProcessor {
@@ -102,6 +118,9 @@ impl AssetProcessor {
                 "svg".to_string(),
                 "webp".to_string(),
             ],
+            processors: HashMap::new(),
+            strip_metadata: false,
+            keep_metadata: Vec::new(),
         }
     }
 
@@ -112,6 +131,29 @@ impl AssetProcessor {
         self
     }
 
+    /// Set the processors to run, keyed by source file extension.
+    ///
+    /// Files with a processed extension whose name starts with `_` are
+    /// treated as partials (e.g. Sass imports) and not published.
+    #[must_use]
+    pub fn with_processors(mut self, processors: HashMap<String, ProcessorConfig>) -> Self {
+        self.processors = processors;
+        self
+    }
+
+    /// Strip EXIF/GPS and other metadata from JPEG and PNG files, except for
+    /// files under the `keep` directories (relative to the source directory).
+    #[must_use]
+    pub fn with_metadata_stripping(
+        mut self,
+        enabled: bool,
+        keep: impl IntoIterator<Item = impl Into<PathBuf>>,
+    ) -> Self {
+        self.strip_metadata = enabled;
+        self.keep_metadata = keep.into_iter().map(Into::into).collect();
+        self
+    }
+
     /// Process all assets from source to destination directory.
     pub fn process(&self, source_dir: &Path, dest_dir: &Path) -> Result<AssetManifest> {
         info!(
@@ -163,8 +205,9 @@ impl AssetProcessor {
         Ok(())
     }
 
-    /// Process a single file.
-    fn process_file(
+    /// Process a single file under `base_dir` into the same relative location
+    /// under `dest_base`.
+    pub fn process_file(
         &self,
         base_dir: &Path,
         file_path: &Path,
@@ -175,33 +218,81 @@ impl AssetProcessor {
             .strip_prefix(base_dir)
             .map_err(|_| AssetError::InvalidPath(file_path.to_path_buf()))?;
 
-        let should_fingerprint = self.fingerprint
-            && file_path.extension().is_some_and(|ext| {
-                self.fingerprint_extensions
-                    .contains(&ext.to_string_lossy().to_string())
-            });
-
-        let dest_relative = if should_fingerprint {
-            let hash = self.compute_hash(file_path)?;
-            let stem = file_path.file_stem().unwrap_or_default().to_string_lossy();
-            let ext = file_path.extension().unwrap_or_default().to_string_lossy();
+        let processor = file_path
+            .extension()
+            .and_then(|ext| self.processors.get(ext.to_string_lossy().as_ref()));
+
+        let is_partial = file_path
+            .file_name()
+            .is_some_and(|n| n.to_string_lossy().starts_with('_'));
+        if let Some(processor) = processor
+            && (is_partial || processor.builtin == Some(BuiltinProcessor::Skip))
+        {
+            debug!(src = %file_path.display(), "skipped asset");
+            return Ok(());
+        }
 
-            let fingerprinted_name = format!("{stem}.{hash}.{ext}");
-            let parent = relative.parent().unwrap_or(Path::new(""));
-            parent.join(&fingerprinted_name)
-        } else {
-            relative.to_path_buf()
-        };
+        let mut dest_relative = relative.to_path_buf();
+        if let Some(ext) = processor.and_then(|p| p.output_extension.as_deref()) {
+            dest_relative.set_extension(ext);
+        }
 
-        let dest_path = dest_base.join(&dest_relative);
+        let mut dest_path = dest_base.join(&dest_relative);
 
         // Ensure destination directory exists
         if let Some(parent) = dest_path.parent() {
             fs::create_dir_all(parent)?;
         }
 
-        // Copy the file
-        fs::copy(file_path, &dest_path)?;
+        match processor {
+            Some(ProcessorConfig {
+                command: Some(command),
+                ..
+            }) => run_command(command, file_path, &dest_path)?,
+            Some(ProcessorConfig {
+                builtin: Some(BuiltinProcessor::MinifyCss),
+                ..
+            }) => fs::write(&dest_path, minify_css(&fs::read_to_string(file_path)?))?,
+            _ => {
+                let stripped = self
+                    .should_strip(relative)
+                    .then(|| fs::read(file_path))
+                    .transpose()?
+                    .and_then(|bytes| strip_metadata(&bytes));
+                match stripped {
+                    Some(bytes) => fs::write(&dest_path, bytes)?,
+                    None => {
+                        fs::copy(file_path, &dest_path)?;
+                    }
+                }
+            }
+        }
+
+        // Fingerprint the processed output
+        let should_fingerprint = self.fingerprint
+            && dest_relative.extension().is_some_and(|ext| {
+                self.fingerprint_extensions
+                    .contains(&ext.to_string_lossy().to_string())
+            });
+
+        if should_fingerprint {
+            let hash = self.compute_hash(&dest_path)?;
+            let stem = dest_relative
+                .file_stem()
+                .unwrap_or_default()
+                .to_string_lossy();
+            let ext = dest_relative
+                .extension()
+                .unwrap_or_default()
+                .to_string_lossy();
+
+            let fingerprinted_name = format!("{stem}.{hash}.{ext}");
+            let fingerprinted = dest_relative.with_file_name(&fingerprinted_name);
+            let fingerprinted_path = dest_base.join(&fingerprinted);
+            fs::rename(&dest_path, &fingerprinted_path)?;
+            dest_relative = fingerprinted;
+            dest_path = fingerprinted_path;
+        }
 
         // Add to manifest
         let orig_path = format!("/{}", relative.display()).replace('\\', "/");
@@ -211,12 +302,27 @@ impl AssetProcessor {
         debug!(
             src = %file_path.display(),
             dest = %dest_path.display(),
-            "copied asset"
+            "processed asset"
         );
 
         Ok(())
     }
 
+    /// Whether metadata should be stripped from a file (by relative path).
+    fn should_strip(&self, relative: &Path) -> bool {
+        self.strip_metadata
+            && relative.extension().is_some_and(|ext| {
+                matches!(
+                    ext.to_string_lossy().to_ascii_lowercase().as_str(),
+                    "jpg" | "jpeg" | "png"
+                )
+            })
+            && !self
+                .keep_metadata
+                .iter()
+                .any(|dir| relative.starts_with(dir))
+    }
+
     /// Compute a short hash of file contents for fingerprinting.
     fn compute_hash(&self, path: &Path) -> Result<String> {
         let mut file = fs::File::open(path)?;
@@ -252,6 +358,209 @@ impl AssetProcessor {
     }
 }
 
+/// Run an external processor command for one file.
+pub(crate) fn run_command(command: &str, input: &Path, output: &Path) -> Result<()> {
+    let input_str = input.to_string_lossy();
+    let output_str = output.to_string_lossy();
+    let mut args = command.split_whitespace().map(|arg| {
+        arg.replace("{input}", &input_str)
+            .replace("{output}", &output_str)
+    });
+
+    let program = args.next().ok_or_else(|| AssetError::Processor {
+        path: input.to_path_buf(),
+        message: "empty command".to_string(),
+    })?;
+
+    let result = Command::new(&program)
+        .args(args)
+        .output()
+        .map_err(|e| AssetError::Processor {
+            path: input.to_path_buf(),
+            message: format!("failed to run `{program}`: {e}"),
+        })?;
+
+    if !result.status.success() {
+        return Err(AssetError::Processor {
+            path: input.to_path_buf(),
+            message: format!(
+                "`{program}` exited with {}: {}",
+                result.status,
+                String::from_utf8_lossy(&result.stderr).trim()
+            ),
+        });
+    }
+
+    if !output.exists() {
+        return Err(AssetError::Processor {
+            path: input.to_path_buf(),
+            message: format!("`{program}` did not write {}", output.display()),
+        });
+    }
+
+    Ok(())
+}
+
+/// Remove metadata from a JPEG or PNG image.
+///
+/// Returns `None` if the data is neither format, is malformed, or contains
+/// no metadata, in which case the original file should be used.
+#[must_use]
+pub fn strip_metadata(data: &[u8]) -> Option<Vec<u8>> {
+    if data.starts_with(&[0xFF, 0xD8]) {
+        strip_jpeg_metadata(data)
+    } else if data.starts_with(PNG_SIGNATURE) {
+        strip_png_metadata(data)
+    } else {
+        None
+    }
+}
+
+/// PNG file signature.
+const PNG_SIGNATURE: &[u8] = b"\x89PNG\r\n\x1a\n";
+
+/// Drop APP1 (EXIF/XMP), APP13 (IPTC) and comment segments from a JPEG.
+///
+/// Note that this also drops the EXIF orientation flag.
+fn strip_jpeg_metadata(data: &[u8]) -> Option<Vec<u8>> {
+    let mut out = Vec::with_capacity(data.len());
+    out.extend_from_slice(&data[..2]);
+    let mut pos = 2;
+    let mut stripped = false;
+
+    while pos < data.len() {
+        if data[pos] != 0xFF {
+            return None;
+        }
+        let marker = *data.get(pos + 1)?;
+        match marker {
+            // Fill byte
+            0xFF => {
+                pos += 1;
+                continue;
+            }
+            // Standalone markers
+            0x01 | 0xD0..=0xD7 => {
+                out.extend_from_slice(&data[pos..pos + 2]);
+                pos += 2;
+                continue;
+            }
+            // Start of scan: the rest is image data
+            0xDA => {
+                out.extend_from_slice(&data[pos..]);
+                break;
+            }
+            _ => {}
+        }
+
+        let len = usize::from(u16::from_be_bytes([
+            *data.get(pos + 2)?,
+            *data.get(pos + 3)?,
+        ]));
+        let end = pos + 2 + len;
+        if len < 2 || end > data.len() {
+            return None;
+        }
+        if matches!(marker, 0xE1 | 0xED | 0xFE) {
+            stripped = true;
+        } else {
+            out.extend_from_slice(&data[pos..end]);
+        }
+        pos = end;
+    }
+
+    stripped.then_some(out)
+}
+
+/// Drop `eXIf`, text and timestamp chunks from a PNG.
+fn strip_png_metadata(data: &[u8]) -> Option<Vec<u8>> {
+    let mut out = Vec::with_capacity(data.len());
+    out.extend_from_slice(PNG_SIGNATURE);
+    let mut pos = PNG_SIGNATURE.len();
+    let mut stripped = false;
+
+    while pos < data.len() {
+        let len = u32::from_be_bytes(data.get(pos..pos + 4)?.try_into().ok()?) as usize;
+        let kind = data.get(pos + 4..pos + 8)?;
+        let end = pos.checked_add(12 + len)?;
+        if end > data.len() {
+            return None;
+        }
+
+        if matches!(kind, b"eXIf" | b"tEXt" | b"zTXt" | b"iTXt" | b"tIME") {
+            stripped = true;
+        } else {
+            out.extend_from_slice(&data[pos..end]);
+        }
+        pos = end;
+        if kind == b"IEND" {
+            break;
+        }
+    }
+
+    stripped.then_some(out)
+}
+
+/// Strip comments and redundant whitespace from CSS.
+///
+/// String literals are preserved as-is. Whitespace around `{`, `}`, `;` and
+/// `,` is removed, as is the last `;` in a block.
+#[must_use]
+pub fn minify_css(css: &str) -> String {
+    let mut out = String::with_capacity(css.len());
+    let mut chars = css.chars().peekable();
+    let mut pending_space = false;
+
+    while let Some(c) = chars.next() {
+        match c {
+            '/' if chars.peek() == Some(&'*') => {
+                chars.next();
+                let mut prev = '\0';
+                for c in chars.by_ref() {
+                    if prev == '*' && c == '/' {
+                        break;
+                    }
+                    prev = c;
+                }
+            }
+            '"' | '\'' => {
+                if pending_space {
+                    out.push(' ');
+                    pending_space = false;
+                }
+                out.push(c);
+                while let Some(inner) = chars.next() {
+                    out.push(inner);
+                    if inner == '\\' {
+                        if let Some(escaped) = chars.next() {
+                            out.push(escaped);
+                        }
+                    } else if inner == c {
+                        break;
+                    }
+                }
+            }
+            c if c.is_whitespace() => pending_space = !out.is_empty(),
+            '{' | '}' | ';' | ',' => {
+                if c == '}' && out.ends_with(';') {
+                    out.pop();
+                }
+                out.push(c);
+                pending_space = false;
+            }
+            _ => {
+                if pending_space && !out.ends_with(['{', '}', ';', ',']) {
+                    out.push(' ');
+                }
+                pending_space = false;
+                out.push(c);
+            }
+        }
+    }
+
+    out
+}
+
 #[cfg(test)]
 mod tests {
     use std::io::Write;
@@ -327,6 +636,165 @@ mod tests {
         assert!(fingerprinted.len() > "/style.css".len());
     }
 
+    #[test]
+    fn test_builtin_processors() {
+        let source = TempDir::new().unwrap();
+        let dest = TempDir::new().unwrap();
+
+        fs::write(
+            source.path().join("style.css"),
+            "/* theme */\nbody {\n  color: red;\n  font: 1em \"Fira  Sans\";\n}\n",
+        )
+        .unwrap();
+        fs::write(source.path().join("notes.draft"), "todo").unwrap();
+
+        let processors = HashMap::from([
+            (
+                "css".to_string(),
+                ProcessorConfig {
+                    builtin: Some(BuiltinProcessor::MinifyCss),
+                    ..Default::default()
+                },
+            ),
+            (
+                "draft".to_string(),
+                ProcessorConfig {
+                    builtin: Some(BuiltinProcessor::Skip),
+                    ..Default::default()
+                },
+            ),
+        ]);
+        let processor = AssetProcessor::new(true).with_processors(processors);
+        let manifest = processor.process(source.path(), dest.path()).unwrap();
+
+        let output = manifest.get("/style.css").unwrap();
+        let css = fs::read_to_string(dest.path().join(&output[1..])).unwrap();
+        assert_eq!(css, r#"body{color: red;font: 1em "Fira  Sans"}"#);
+
+        assert!(manifest.get("/notes.draft").is_none());
+        assert!(!dest.path().join("notes.draft").exists());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_command_processor() {
+        let source = TempDir::new().unwrap();
+        let dest = TempDir::new().unwrap();
+
+        fs::create_dir(source.path().join("css")).unwrap();
+        fs::write(source.path().join("css/main.scss"), "body { color: red; }").unwrap();
+        fs::write(source.path().join("css/_vars.scss"), "$red: red;").unwrap();
+
+        let processors = HashMap::from([(
+            "scss".to_string(),
+            ProcessorConfig {
+                command: Some("cp {input} {output}".to_string()),
+                output_extension: Some("css".to_string()),
+                ..Default::default()
+            },
+        )]);
+        let processor = AssetProcessor::new(false).with_processors(processors);
+        let manifest = processor.process(source.path(), dest.path()).unwrap();
+
+        assert_eq!(manifest.get("/css/main.scss"), Some("/css/main.css"));
+        assert!(dest.path().join("css/main.css").exists());
+        assert!(!dest.path().join("css/_vars.css").exists());
+        assert_eq!(manifest.assets().len(), 1);
+
+        // Failing commands surface as processor errors
+        let failing = HashMap::from([(
+            "scss".to_string(),
+            ProcessorConfig {
+                command: Some("false".to_string()),
+                ..Default::default()
+            },
+        )]);
+        let result = AssetProcessor::new(false)
+            .with_processors(failing)
+            .process(source.path(), dest.path());
+        assert!(matches!(result, Err(AssetError::Processor { .. })));
+    }
+
+    fn jpeg_segment(marker: u8, payload: &[u8]) -> Vec<u8> {
+        let len = u16::try_from(payload.len() + 2).unwrap().to_be_bytes();
+        [&[0xFF, marker], &len[..], payload].concat()
+    }
+
+    fn png_chunk(kind: &[u8], payload: &[u8]) -> Vec<u8> {
+        let len = u32::try_from(payload.len()).unwrap().to_be_bytes();
+        [&len[..], kind, payload, &[0, 0, 0, 0]].concat()
+    }
+
+    #[test]
+    fn test_strip_jpeg_metadata() {
+        let jfif = jpeg_segment(0xE0, b"JFIF\0");
+        let exif = jpeg_segment(0xE1, b"Exif\0\0GPS");
+        let scan = [0xFF, 0xDA, 0x00, 0x02, 0x12, 0x34, 0xFF, 0xD9];
+        let jpeg = [&[0xFF, 0xD8][..], &jfif, &exif, &scan].concat();
+
+        let stripped = strip_metadata(&jpeg).unwrap();
+        assert_eq!(stripped, [&[0xFF, 0xD8][..], &jfif, &scan].concat());
+
+        // Nothing to strip or not an image
+        assert!(strip_metadata(&stripped).is_none());
+        assert!(strip_metadata(b"GIF89a").is_none());
+    }
+
+    #[test]
+    fn test_strip_png_metadata() {
+        let ihdr = png_chunk(b"IHDR", &[0; 13]);
+        let exif = png_chunk(b"eXIf", b"MM\0*");
+        let text = png_chunk(b"tEXt", b"Author\0me");
+        let iend = png_chunk(b"IEND", &[]);
+        let png = [PNG_SIGNATURE, &ihdr, &exif, &text, &iend].concat();
+
+        let stripped = strip_metadata(&png).unwrap();
+        assert_eq!(stripped, [PNG_SIGNATURE, &ihdr, &iend].concat());
+    }
+
+    #[test]
+    fn test_process_strips_metadata() {
+        let source = TempDir::new().unwrap();
+        let dest = TempDir::new().unwrap();
+
+        let exif = jpeg_segment(0xE1, b"Exif\0\0GPS");
+        let jpeg = [
+            &[0xFF, 0xD8][..],
+            &exif,
+            &[0xFF, 0xDA, 0x00, 0x02, 0xFF, 0xD9],
+        ]
+        .concat();
+        fs::create_dir_all(source.path().join("originals")).unwrap();
+        fs::write(source.path().join("photo.jpg"), &jpeg).unwrap();
+        fs::write(source.path().join("originals/photo.jpg"), &jpeg).unwrap();
+
+        AssetProcessor::new(false)
+            .with_metadata_stripping(true, ["originals"])
+            .process(source.path(), dest.path())
+            .unwrap();
+
+        assert_eq!(
+            fs::read(dest.path().join("photo.jpg")).unwrap(),
+            [0xFF, 0xD8, 0xFF, 0xDA, 0x00, 0x02, 0xFF, 0xD9]
+        );
+        assert_eq!(
+            fs::read(dest.path().join("originals/photo.jpg")).unwrap(),
+            jpeg
+        );
+    }
+
+    #[test]
+    fn test_minify_css() {
+        assert_eq!(
+            minify_css("a ,\n b  >  c { margin : 0 auto ; }\n/* end */"),
+            "a,b > c{margin : 0 auto}"
+        );
+        assert_eq!(
+            minify_css(r#"a::after { content: "/* x */"; }"#),
+            r#"a::after{content: "/* x */"}"#
+        );
+    }
+
     #[test]
     fn test_compute_hash_deterministic() {
         let dir = TempDir::new().unwrap();
@@ -0,0 +1,737 @@
+//! Static audits of rendered HTML.
+//!
+//! Provides a lightweight HTML tag scanner and checks run by `typstify check`
+//! over generated pages.
+
+use std::fmt;
+
+/// Category of an audit issue.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AuditKind {
+    /// Image without an `alt` attribute.
+    MissingAlt,
+    /// Heading level jumps by more than one (e.g. h2 → h4).
+    SkippedHeading,
+    /// Link without any accessible text.
+    EmptyLink,
+    /// `<html>` element without a `lang` attribute.
+    MissingLang,
+    /// Element opened but never closed.
+    UnclosedTag,
+    /// Closing tag without a matching open element.
+    UnexpectedClose,
+    /// The same `id` used more than once.
+    DuplicateId,
+    /// Element placed where HTML does not allow it.
+    InvalidNesting,
+}
+
+impl AuditKind {
+    /// Short identifier used in reports.
+    #[must_use]
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::MissingAlt => "missing-alt",
+            Self::SkippedHeading => "skipped-heading",
+            Self::EmptyLink => "empty-link",
+            Self::MissingLang => "missing-lang",
+            Self::UnclosedTag => "unclosed-tag",
+            Self::UnexpectedClose => "unexpected-close",
+            Self::DuplicateId => "duplicate-id",
+            Self::InvalidNesting => "invalid-nesting",
+        }
+    }
+}
+
+/// A single issue found in an HTML document.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AuditIssue {
+    /// Issue category.
+    pub kind: AuditKind,
+
+    /// 1-based line number in the document.
+    pub line: usize,
+
+    /// Human-readable description.
+    pub message: String,
+}
+
+impl fmt::Display for AuditIssue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "line {}: [{}] {}",
+            self.line,
+            self.kind.as_str(),
+            self.message
+        )
+    }
+}
+
+/// A token produced by [`scan_html`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum HtmlToken {
+    /// Opening (or self-closing) tag.
+    Start {
+        /// Lowercased tag name.
+        name: String,
+        /// Attributes as (lowercased name, value) pairs.
+        attrs: Vec<(String, String)>,
+        /// Whether the tag ended with `/>`.
+        self_closing: bool,
+        /// Byte offset of the `<`.
+        offset: usize,
+    },
+    /// Closing tag.
+    End {
+        /// Lowercased tag name.
+        name: String,
+        /// Byte offset of the `<`.
+        offset: usize,
+    },
+    /// Text between tags.
+    Text {
+        /// Raw text (entities are not decoded).
+        text: String,
+        /// Byte offset of the first character.
+        offset: usize,
+    },
+}
+
+impl HtmlToken {
+    /// Get an attribute value from a start tag.
+    #[must_use]
+    pub fn attr(&self, name: &str) -> Option<&str> {
+        match self {
+            Self::Start { attrs, .. } => attrs
+                .iter()
+                .find(|(k, _)| k == name)
+                .map(|(_, v)| v.as_str()),
+            _ => None,
+        }
+    }
+}
+
+/// Scan HTML into a flat list of tokens.
+///
+/// Comments and doctype declarations are skipped, and the contents of
+/// `<script>` and `<style>` are treated as opaque text.
+#[must_use]
+pub fn scan_html(html: &str) -> Vec<HtmlToken> {
+    let bytes = html.as_bytes();
+    let mut tokens = Vec::new();
+    let mut pos = 0;
+    let mut text_start = 0;
+
+    while pos < bytes.len() {
+        if bytes[pos] != b'<' {
+            pos += 1;
+            continue;
+        }
+
+        let rest = &html[pos..];
+        let skip_to = if rest.starts_with("<!--") {
+            Some(rest.find("-->").map_or(bytes.len(), |i| pos + i + 3))
+        } else if rest.starts_with("<!") || rest.starts_with("<?") {
+            Some(rest.find('>').map_or(bytes.len(), |i| pos + i + 1))
+        } else {
+            None
+        };
+        let tag = if skip_to.is_none() {
+            find_tag_end(rest).filter(|&end| {
+                let inner = rest[1..end].trim_start_matches('/');
+                inner.starts_with(|c: char| c.is_ascii_alphabetic())
+            })
+        } else {
+            None
+        };
+        if skip_to.is_none() && tag.is_none() {
+            // A bare `<` in text
+            pos += 1;
+            continue;
+        }
+
+        // Flush pending text
+        if text_start < pos {
+            tokens.push(HtmlToken::Text {
+                text: html[text_start..pos].to_string(),
+                offset: text_start,
+            });
+        }
+
+        if let Some(next) = skip_to {
+            pos = next;
+            text_start = pos;
+            continue;
+        }
+
+        let end = tag.unwrap_or_default();
+        let inner = &rest[1..end];
+        let tag_offset = pos;
+        pos += end + 1;
+        text_start = pos;
+
+        if let Some(name) = inner.strip_prefix('/') {
+            tokens.push(HtmlToken::End {
+                name: name.trim().to_ascii_lowercase(),
+                offset: tag_offset,
+            });
+            continue;
+        }
+
+        let self_closing = inner.ends_with('/');
+        let inner = inner.trim_end_matches('/');
+        let name_end = inner
+            .find(|c: char| c.is_ascii_whitespace())
+            .unwrap_or(inner.len());
+        let name = inner[..name_end].to_ascii_lowercase();
+        let attrs = parse_attrs(&inner[name_end..]);
+        let raw_text = matches!(name.as_str(), "script" | "style");
+
+        tokens.push(HtmlToken::Start {
+            name: name.clone(),
+            attrs,
+            self_closing,
+            offset: tag_offset,
+        });
+
+        if raw_text && !self_closing {
+            let close = format!("</{name}");
+            let lower = html[pos..].to_ascii_lowercase();
+            let body_end = lower.find(&close).map_or(bytes.len(), |i| pos + i);
+            if body_end > pos {
+                tokens.push(HtmlToken::Text {
+                    text: html[pos..body_end].to_string(),
+                    offset: pos,
+                });
+            }
+            pos = body_end;
+            text_start = body_end;
+        }
+    }
+
+    if text_start < bytes.len() {
+        tokens.push(HtmlToken::Text {
+            text: html[text_start..].to_string(),
+            offset: text_start,
+        });
+    }
+
+    tokens
+}
+
+/// Find the closing `>` of a tag, honoring quoted attribute values.
+fn find_tag_end(s: &str) -> Option<usize> {
+    let mut quote = None;
+    for (i, c) in s.char_indices().skip(1) {
+        match (quote, c) {
+            (Some(q), c) if c == q => quote = None,
+            (Some(_), _) => {}
+            (None, '"' | '\'') => quote = Some(c),
+            (None, '>') => return Some(i),
+            (None, '<') => return None,
+            _ => {}
+        }
+    }
+    None
+}
+
+/// Parse the attribute portion of a start tag.
+fn parse_attrs(s: &str) -> Vec<(String, String)> {
+    let mut attrs = Vec::new();
+    let chars: Vec<char> = s.chars().collect();
+    let mut i = 0;
+
+    while i < chars.len() {
+        while i < chars.len() && chars[i].is_whitespace() {
+            i += 1;
+        }
+        let start = i;
+        while i < chars.len() && !chars[i].is_whitespace() && chars[i] != '=' {
+            i += 1;
+        }
+        if start == i {
+            i += 1;
+            continue;
+        }
+        let name: String = chars[start..i]
+            .iter()
+            .collect::<String>()
+            .to_ascii_lowercase();
+
+        while i < chars.len() && chars[i].is_whitespace() {
+            i += 1;
+        }
+        let mut value = String::new();
+        if i < chars.len() && chars[i] == '=' {
+            i += 1;
+            while i < chars.len() && chars[i].is_whitespace() {
+                i += 1;
+            }
+            if i < chars.len() && (chars[i] == '"' || chars[i] == '\'') {
+                let q = chars[i];
+                i += 1;
+                let vstart = i;
+                while i < chars.len() && chars[i] != q {
+                    i += 1;
+                }
+                value = chars[vstart..i].iter().collect();
+                i += 1;
+            } else {
+                let vstart = i;
+                while i < chars.len() && !chars[i].is_whitespace() {
+                    i += 1;
+                }
+                value = chars[vstart..i].iter().collect();
+            }
+        }
+        attrs.push((name, value));
+    }
+
+    attrs
+}
+
+/// Convert a byte offset into a 1-based line number.
+#[must_use]
+pub fn line_of(html: &str, offset: usize) -> usize {
+    html[..offset.min(html.len())].matches('\n').count() + 1
+}
+
+/// Heading level for `h1`-`h6` tag names.
+fn heading_level(name: &str) -> Option<u8> {
+    match name {
+        "h1" => Some(1),
+        "h2" => Some(2),
+        "h3" => Some(3),
+        "h4" => Some(4),
+        "h5" => Some(5),
+        "h6" => Some(6),
+        _ => None,
+    }
+}
+
+/// Run accessibility checks over a rendered HTML document.
+///
+/// Reports images without `alt`, skipped heading levels, links without
+/// accessible text, and a missing `lang` attribute on `<html>`.
+#[must_use]
+pub fn check_accessibility(html: &str) -> Vec<AuditIssue> {
+    let mut issues = Vec::new();
+    let mut last_heading: Option<u8> = None;
+    // (offset, has accessible text) for the currently open link
+    let mut open_link: Option<(usize, bool)> = None;
+
+    for token in scan_html(html) {
+        match &token {
+            HtmlToken::Start {
+                name,
+                self_closing,
+                offset,
+                ..
+            } => {
+                let line = line_of(html, *offset);
+                match name.as_str() {
+                    "html" if token.attr("lang").is_none_or(str::is_empty) => {
+                        issues.push(AuditIssue {
+                            kind: AuditKind::MissingLang,
+                            line,
+                            message: "<html> element has no lang attribute".to_string(),
+                        });
+                    }
+                    "img" => {
+                        let alt = token.attr("alt");
+                        if alt.is_none() {
+                            let src = token.attr("src").unwrap_or("");
+                            issues.push(AuditIssue {
+                                kind: AuditKind::MissingAlt,
+                                line,
+                                message: format!("image '{src}' has no alt text"),
+                            });
+                        }
+                        if let Some((_, has_text)) = open_link.as_mut()
+                            && alt.is_some_and(|a| !a.trim().is_empty())
+                        {
+                            *has_text = true;
+                        }
+                    }
+                    "a" if token.attr("href").is_some() && !self_closing => {
+                        let labelled = token
+                            .attr("aria-label")
+                            .or_else(|| token.attr("title"))
+                            .is_some_and(|l| !l.trim().is_empty());
+                        open_link = Some((*offset, labelled));
+                    }
+                    _ => {
+                        if let Some(level) = heading_level(name) {
+                            if let Some(prev) = last_heading
+                                && level > prev + 1
+                            {
+                                issues.push(AuditIssue {
+                                    kind: AuditKind::SkippedHeading,
+                                    line,
+                                    message: format!("heading level skipped: h{prev} → h{level}"),
+                                });
+                            }
+                            last_heading = Some(level);
+                        }
+                    }
+                }
+            }
+            HtmlToken::Text { text, .. } => {
+                if let Some((_, has_text)) = open_link.as_mut()
+                    && !text.trim().is_empty()
+                {
+                    *has_text = true;
+                }
+            }
+            HtmlToken::End { name, .. } => {
+                if name == "a"
+                    && let Some((offset, has_text)) = open_link.take()
+                    && !has_text
+                {
+                    issues.push(AuditIssue {
+                        kind: AuditKind::EmptyLink,
+                        line: line_of(html, offset),
+                        message: "link has no text or aria-label".to_string(),
+                    });
+                }
+            }
+        }
+    }
+
+    issues
+}
+
+/// Elements that never have content or a closing tag.
+const VOID_ELEMENTS: &[&str] = &[
+    "area", "base", "br", "col", "embed", "hr", "img", "input", "link", "meta", "source", "track",
+    "wbr",
+];
+
+/// Elements whose closing tag may be omitted.
+const OPTIONAL_END_ELEMENTS: &[&str] = &[
+    "html", "head", "body", "p", "li", "dt", "dd", "tr", "td", "th", "thead", "tbody", "tfoot",
+    "option", "optgroup", "colgroup", "caption", "rt", "rp",
+];
+
+/// Block-level elements that implicitly close an open `<p>`.
+const BLOCK_ELEMENTS: &[&str] = &[
+    "address",
+    "article",
+    "aside",
+    "blockquote",
+    "details",
+    "div",
+    "dl",
+    "fieldset",
+    "figcaption",
+    "figure",
+    "footer",
+    "form",
+    "h1",
+    "h2",
+    "h3",
+    "h4",
+    "h5",
+    "h6",
+    "header",
+    "hr",
+    "main",
+    "nav",
+    "ol",
+    "p",
+    "pre",
+    "section",
+    "table",
+    "ul",
+];
+
+/// Check a rendered HTML document for well-formedness.
+///
+/// Reports unclosed elements, stray closing tags, duplicate `id`s, and
+/// common invalid nesting (block elements inside `<p>`, nested links,
+/// `<li>` outside a list).
+#[must_use]
+pub fn check_html_validity(html: &str) -> Vec<AuditIssue> {
+    let mut issues = Vec::new();
+    let mut stack: Vec<(String, usize)> = Vec::new();
+    let mut ids: std::collections::HashMap<String, usize> = std::collections::HashMap::new();
+
+    for token in scan_html(html) {
+        match &token {
+            HtmlToken::Start {
+                name,
+                self_closing,
+                offset,
+                ..
+            } => {
+                let line = line_of(html, *offset);
+
+                if let Some(id) = token.attr("id").filter(|id| !id.is_empty()) {
+                    if let Some(first) = ids.get(id) {
+                        issues.push(AuditIssue {
+                            kind: AuditKind::DuplicateId,
+                            line,
+                            message: format!("duplicate id '{id}' (first used on line {first})"),
+                        });
+                    } else {
+                        ids.insert(id.to_string(), line);
+                    }
+                }
+
+                let parent = stack.last().map(|(n, _)| n.as_str());
+                if parent == Some("p") && BLOCK_ELEMENTS.contains(&name.as_str()) {
+                    if name != "p" {
+                        issues.push(AuditIssue {
+                            kind: AuditKind::InvalidNesting,
+                            line,
+                            message: format!("<{name}> is not allowed inside <p>"),
+                        });
+                    }
+                    stack.pop();
+                } else if name == "li" && parent == Some("li") {
+                    stack.pop();
+                } else if name == "a" && stack.iter().any(|(n, _)| n == "a") {
+                    issues.push(AuditIssue {
+                        kind: AuditKind::InvalidNesting,
+                        line,
+                        message: "<a> is not allowed inside another <a>".to_string(),
+                    });
+                }
+
+                if name == "li"
+                    && !matches!(
+                        stack.last().map(|(n, _)| n.as_str()),
+                        Some("ul" | "ol" | "menu")
+                    )
+                {
+                    issues.push(AuditIssue {
+                        kind: AuditKind::InvalidNesting,
+                        line,
+                        message: "<li> must be inside <ul>, <ol>, or <menu>".to_string(),
+                    });
+                }
+
+                if !self_closing && !VOID_ELEMENTS.contains(&name.as_str()) {
+                    stack.push((name.clone(), line));
+                }
+            }
+            HtmlToken::End { name, offset } => {
+                if VOID_ELEMENTS.contains(&name.as_str()) {
+                    continue;
+                }
+                let line = line_of(html, *offset);
+                let Some(pos) = stack.iter().rposition(|(n, _)| n == name) else {
+                    issues.push(AuditIssue {
+                        kind: AuditKind::UnexpectedClose,
+                        line,
+                        message: format!("closing </{name}> has no matching open element"),
+                    });
+                    continue;
+                };
+                for (open, open_line) in stack.drain(pos + 1..) {
+                    if !OPTIONAL_END_ELEMENTS.contains(&open.as_str()) {
+                        issues.push(AuditIssue {
+                            kind: AuditKind::UnclosedTag,
+                            line: open_line,
+                            message: format!("<{open}> is not closed before </{name}>"),
+                        });
+                    }
+                }
+                stack.pop();
+            }
+            HtmlToken::Text { .. } => {}
+        }
+    }
+
+    for (open, open_line) in stack {
+        if !OPTIONAL_END_ELEMENTS.contains(&open.as_str()) {
+            issues.push(AuditIssue {
+                kind: AuditKind::UnclosedTag,
+                line: open_line,
+                message: format!("<{open}> is never closed"),
+            });
+        }
+    }
+
+    issues
+}
+
+/// External resources referenced by a rendered page.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct PageResources {
+    /// Stylesheet URLs (`<link rel="stylesheet">`).
+    pub stylesheets: Vec<String>,
+
+    /// Script URLs (`<script src>`).
+    pub scripts: Vec<String>,
+
+    /// Image URLs (`<img src>`, `<video poster>`).
+    pub images: Vec<String>,
+
+    /// Stylesheets and synchronous scripts in `<head>` that block the first
+    /// render.
+    pub render_blocking: Vec<String>,
+}
+
+/// Find the stylesheets, scripts and images a rendered page loads.
+///
+/// A stylesheet is render-blocking unless it only applies to `print`; a
+/// script is render-blocking unless it is `async`, `defer` or a module.
+/// Only resources in `<head>` count as blocking.
+#[must_use]
+pub fn page_resources(html: &str) -> PageResources {
+    let mut resources = PageResources::default();
+    let mut in_head = false;
+
+    for token in scan_html(html) {
+        match &token {
+            HtmlToken::Start { name, .. } => match name.as_str() {
+                "head" => in_head = true,
+                "body" => in_head = false,
+                "link" => {
+                    let is_stylesheet = token.attr("rel").is_some_and(|rel| {
+                        rel.split_whitespace()
+                            .any(|r| r.eq_ignore_ascii_case("stylesheet"))
+                    });
+                    if let Some(href) = token.attr("href").filter(|_| is_stylesheet) {
+                        resources.stylesheets.push(href.to_string());
+                        let print_only = token
+                            .attr("media")
+                            .is_some_and(|media| media.trim().eq_ignore_ascii_case("print"));
+                        if in_head && !print_only {
+                            resources.render_blocking.push(href.to_string());
+                        }
+                    }
+                }
+                "script" => {
+                    if let Some(src) = token.attr("src") {
+                        resources.scripts.push(src.to_string());
+                        let deferred = token.attr("async").is_some()
+                            || token.attr("defer").is_some()
+                            || token.attr("type") == Some("module");
+                        if in_head && !deferred {
+                            resources.render_blocking.push(src.to_string());
+                        }
+                    }
+                }
+                "img" => {
+                    if let Some(src) = token.attr("src") {
+                        resources.images.push(src.to_string());
+                    }
+                }
+                "video" => {
+                    if let Some(poster) = token.attr("poster") {
+                        resources.images.push(poster.to_string());
+                    }
+                }
+                _ => {}
+            },
+            HtmlToken::End { name, .. } if name == "head" => in_head = false,
+            _ => {}
+        }
+    }
+
+    resources
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_scan_html() {
+        let tokens =
+            scan_html(r#"<!-- c --><p class="a b">Hi<br/></p><script>if (a<b) {}</script>"#);
+
+        assert_eq!(tokens[0].attr("class"), Some("a b"));
+        assert!(matches!(&tokens[1], HtmlToken::Text { text, .. } if text == "Hi"));
+        assert!(
+            matches!(&tokens[2], HtmlToken::Start { name, self_closing: true, .. } if name == "br")
+        );
+        assert!(matches!(&tokens[3], HtmlToken::End { name, .. } if name == "p"));
+        assert!(matches!(&tokens[4], HtmlToken::Start { name, .. } if name == "script"));
+        assert!(matches!(&tokens[5], HtmlToken::Text { text, .. } if text == "if (a<b) {}"));
+    }
+
+    #[test]
+    fn test_accessible_document() {
+        let html = r#"<html lang="en"><body><h1>T</h1><h2>S</h2>
+<img src="a.png" alt=""><a href="/">Home</a><a href="/x" aria-label="X"></a>
+<a href="/y"><img src="y.png" alt="Y"></a></body></html>"#;
+        assert!(check_accessibility(html).is_empty());
+    }
+
+    #[test]
+    fn test_accessibility_issues() {
+        let html =
+            "<html>\n<h1>T</h1>\n<h3>S</h3>\n<img src=\"a.png\">\n<a href=\"/\"> </a>\n</html>";
+        let issues = check_accessibility(html);
+        let kinds: Vec<_> = issues.iter().map(|i| (i.kind, i.line)).collect();
+
+        assert_eq!(
+            kinds,
+            vec![
+                (AuditKind::MissingLang, 1),
+                (AuditKind::SkippedHeading, 3),
+                (AuditKind::MissingAlt, 4),
+                (AuditKind::EmptyLink, 5),
+            ]
+        );
+        assert!(issues[1].to_string().contains("h1 → h3"));
+    }
+
+    #[test]
+    fn test_valid_html() {
+        let html = r#"<!DOCTYPE html>
+<html lang="en"><head><meta charset="utf-8"><title>T</title></head>
+<body><p>One<p>Two</p><ul><li>a<li>b</ul><img src="x.png"><br/>
+<table><tr><td>1<td>2</tr></table></body></html>"#;
+        assert!(check_html_validity(html).is_empty());
+    }
+
+    #[test]
+    fn test_invalid_html() {
+        let html = "<div id=\"a\">\n<p><div>x</div></p>\n<span id=\"a\">\n<a href=\"/\"><a href=\"/b\">b</a></a>\n<li>x</li>\n</div>";
+        let kinds: Vec<_> = check_html_validity(html)
+            .into_iter()
+            .map(|i| (i.kind, i.line))
+            .collect();
+
+        assert_eq!(
+            kinds,
+            vec![
+                (AuditKind::InvalidNesting, 2),
+                (AuditKind::UnexpectedClose, 2),
+                (AuditKind::DuplicateId, 3),
+                (AuditKind::InvalidNesting, 4),
+                (AuditKind::InvalidNesting, 5),
+                (AuditKind::UnclosedTag, 3),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_page_resources() {
+        let html = r#"<html><head>
+<link rel="stylesheet" href="/style.css">
+<link rel="stylesheet" href="/print.css" media="print">
+<link rel="icon" href="/favicon.ico">
+<script src="/app.js"></script>
+<script src="/search.js" defer></script>
+<script type="module" src="/wasm.js"></script>
+</head><body>
+<img src="/a.png" alt="A"><video poster="/p.jpg"></video>
+<script src="/late.js"></script>
+</body></html>"#;
+        let resources = page_resources(html);
+
+        assert_eq!(resources.stylesheets, vec!["/style.css", "/print.css"]);
+        assert_eq!(
+            resources.scripts,
+            vec!["/app.js", "/search.js", "/wasm.js", "/late.js"]
+        );
+        assert_eq!(resources.images, vec!["/a.png", "/p.jpg"]);
+        assert_eq!(resources.render_blocking, vec!["/style.css", "/app.js"]);
+    }
+}
@@ -0,0 +1,310 @@
+//! Blogroll page and OPML export.
+//!
+//! Reads the sites listed in `data/blogroll.toml`:
+//!
+//! ```toml
+//! title = "Blogroll"
+//! description = "Sites I read"
+//!
+//! [[feeds]]
+//! name = "Example Blog"
+//! url = "https://example.com"
+//! feed = "https://example.com/feed.xml"
+//! description = "Notes on Rust"
+//! category = "Rust"
+//! ```
+//!
+//! and renders them as a `/blogroll/` page, grouped by category, and as
+//! `blogroll.opml` for importing into a feed reader.
+
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
+
+use chrono::Utc;
+use serde::Deserialize;
+use thiserror::Error;
+use typstify_core::{escape_html, escape_xml};
+
+/// Default path (relative to the site root) of the blogroll data file.
+pub const BLOGROLL_FILE: &str = "data/blogroll.toml";
+
+/// OPML file name, written at the root of the output directory.
+pub const OPML_FILE: &str = "blogroll.opml";
+
+/// Blogroll errors.
+#[derive(Debug, Error)]
+pub enum BlogrollError {
+    /// IO error.
+    #[error("IO error: {0}")]
+    Io(#[from] std::io::Error),
+
+    /// Invalid blogroll data file.
+    #[error("invalid {}: {message}", .path.display())]
+    Parse {
+        /// Path of the data file.
+        path: PathBuf,
+        /// Error message.
+        message: String,
+    },
+}
+
+/// Result type for blogroll operations.
+pub type Result<T> = std::result::Result<T, BlogrollError>;
+
+/// A site in the blogroll.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+pub struct BlogrollEntry {
+    /// Site name.
+    pub name: String,
+
+    /// Homepage URL.
+    pub url: String,
+
+    /// Feed URL (RSS or Atom).
+    pub feed: String,
+
+    /// Short description.
+    #[serde(default)]
+    pub description: Option<String>,
+
+    /// Category the site is listed under.
+    #[serde(default)]
+    pub category: Option<String>,
+}
+
+/// Contents of the blogroll data file.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Deserialize)]
+pub struct Blogroll {
+    /// Page and OPML title (default: "Blogroll").
+    #[serde(default)]
+    pub title: Option<String>,
+
+    /// Introduction shown above the list.
+    #[serde(default)]
+    pub description: Option<String>,
+
+    /// Listed sites, in display order.
+    #[serde(default)]
+    pub feeds: Vec<BlogrollEntry>,
+}
+
+impl Blogroll {
+    /// Load the blogroll data file at `path`.
+    pub fn load(path: &Path) -> Result<Self> {
+        let text = fs::read_to_string(path)?;
+        toml::from_str(&text).map_err(|e| BlogrollError::Parse {
+            path: path.to_path_buf(),
+            message: e.message().to_string(),
+        })
+    }
+
+    /// Page and OPML title.
+    #[must_use]
+    pub fn title(&self) -> &str {
+        self.title.as_deref().unwrap_or("Blogroll")
+    }
+
+    /// Entries grouped by category, in order of first appearance; entries
+    /// without a category come first, under `None`.
+    #[must_use]
+    pub fn categories(&self) -> Vec<(Option<&str>, Vec<&BlogrollEntry>)> {
+        let mut groups: Vec<(Option<&str>, Vec<&BlogrollEntry>)> = Vec::new();
+        if self.feeds.iter().any(|entry| entry.category.is_none()) {
+            groups.push((None, Vec::new()));
+        }
+        for entry in &self.feeds {
+            let category = entry.category.as_deref();
+            match groups.iter_mut().find(|(name, _)| *name == category) {
+                Some((_, entries)) => entries.push(entry),
+                None => groups.push((category, vec![entry])),
+            }
+        }
+        groups
+    }
+
+    /// The blogroll as an OPML 2.0 document, with a folder per category.
+    #[must_use]
+    pub fn opml(&self, owner: Option<&str>) -> String {
+        let mut xml = String::from("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n");
+        xml.push_str("<opml version=\"2.0\">\n  <head>\n");
+        xml.push_str(&format!(
+            "    <title>{}</title>\n",
+            escape_xml(self.title())
+        ));
+        xml.push_str(&format!(
+            "    <dateCreated>{}</dateCreated>\n",
+            Utc::now().to_rfc2822()
+        ));
+        if let Some(owner) = owner.filter(|owner| !owner.is_empty()) {
+            xml.push_str(&format!(
+                "    <ownerName>{}</ownerName>\n",
+                escape_xml(owner)
+            ));
+        }
+        xml.push_str("  </head>\n  <body>\n");
+
+        for (category, entries) in self.categories() {
+            let indent = if let Some(category) = category {
+                let category = escape_xml(category);
+                xml.push_str(&format!(
+                    "    <outline text=\"{category}\" title=\"{category}\">\n"
+                ));
+                "      "
+            } else {
+                "    "
+            };
+            for entry in entries {
+                let name = escape_xml(&entry.name);
+                xml.push_str(&format!(
+                    "{indent}<outline type=\"rss\" text=\"{name}\" title=\"{name}\" xmlUrl=\"{}\" htmlUrl=\"{}\"",
+                    escape_xml(&entry.feed),
+                    escape_xml(&entry.url)
+                ));
+                if let Some(description) = &entry.description {
+                    xml.push_str(&format!(" description=\"{}\"", escape_xml(description)));
+                }
+                xml.push_str("/>\n");
+            }
+            if category.is_some() {
+                xml.push_str("    </outline>\n");
+            }
+        }
+
+        xml.push_str("  </body>\n</opml>\n");
+        xml
+    }
+
+    /// The list of sites for the `/blogroll/` page, with a link to the
+    /// OPML file at `opml_url`.
+    #[must_use]
+    pub fn html(&self, opml_url: &str) -> String {
+        let mut html = String::new();
+        if let Some(description) = &self.description {
+            html.push_str(&format!(
+                "<p class=\"blogroll-description\">{}</p>\n",
+                escape_html(description)
+            ));
+        }
+
+        for (category, entries) in self.categories() {
+            if let Some(category) = category {
+                html.push_str(&format!("<h2>{}</h2>\n", escape_html(category)));
+            }
+            html.push_str("<ul class=\"blogroll-list\">\n");
+            for entry in entries {
+                html.push_str(&format!(
+                    "<li><a href=\"{}\">{}</a> <a class=\"blogroll-feed\" href=\"{}\">feed</a>",
+                    escape_html(&entry.url),
+                    escape_html(&entry.name),
+                    escape_html(&entry.feed)
+                ));
+                if let Some(description) = &entry.description {
+                    html.push_str(&format!(
+                        "<p class=\"blogroll-site-description\">{}</p>",
+                        escape_html(description)
+                    ));
+                }
+                html.push_str("</li>\n");
+            }
+            html.push_str("</ul>\n");
+        }
+
+        html.push_str(&format!(
+            "<p class=\"blogroll-opml\"><a href=\"{}\" type=\"text/x-opml\">Download as OPML</a></p>",
+            escape_html(opml_url)
+        ));
+        html
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn blogroll() -> Blogroll {
+        toml::from_str(
+            r#"
+description = "Sites I read"
+
+[[feeds]]
+name = "Rust & Friends"
+url = "https://rust.example.com"
+feed = "https://rust.example.com/feed.xml"
+category = "Rust"
+
+[[feeds]]
+name = "Daily Notes"
+url = "https://notes.example.com"
+feed = "https://notes.example.com/rss"
+description = "Short notes"
+
+[[feeds]]
+name = "Ferris"
+url = "https://ferris.example.com"
+feed = "https://ferris.example.com/atom.xml"
+category = "Rust"
+"#,
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn test_categories() {
+        let blogroll = blogroll();
+        let groups: Vec<_> = blogroll
+            .categories()
+            .into_iter()
+            .map(|(category, entries)| (category, entries.len()))
+            .collect();
+        assert_eq!(groups, vec![(None, 1), (Some("Rust"), 2)]);
+        assert_eq!(blogroll.title(), "Blogroll");
+    }
+
+    #[test]
+    fn test_opml() {
+        let opml = blogroll().opml(Some("Jane"));
+
+        assert!(
+            opml.starts_with("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n<opml version=\"2.0\">")
+        );
+        assert!(opml.contains("<title>Blogroll</title>"));
+        assert!(opml.contains("<ownerName>Jane</ownerName>"));
+        assert!(opml.contains(
+            r#"    <outline type="rss" text="Daily Notes" title="Daily Notes" xmlUrl="https://notes.example.com/rss" htmlUrl="https://notes.example.com" description="Short notes"/>"#
+        ));
+        assert!(opml.contains(
+            "    <outline text=\"Rust\" title=\"Rust\">\n      <outline type=\"rss\" text=\"Rust &amp; Friends\""
+        ));
+        assert_eq!(opml.matches("</outline>").count(), 1);
+    }
+
+    #[test]
+    fn test_html() {
+        let html = blogroll().html("/blogroll.opml");
+
+        assert!(html.starts_with("<p class=\"blogroll-description\">Sites I read</p>"));
+        assert!(html.contains("<h2>Rust</h2>"));
+        assert!(html.contains(
+            r#"<li><a href="https://rust.example.com">Rust &amp; Friends</a> <a class="blogroll-feed" href="https://rust.example.com/feed.xml">feed</a></li>"#
+        ));
+        assert!(
+            html.ends_with(
+                r#"<a href="/blogroll.opml" type="text/x-opml">Download as OPML</a></p>"#
+            )
+        );
+    }
+
+    #[test]
+    fn test_load_error() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let path = dir.path().join("blogroll.toml");
+        fs::write(&path, "[[feeds]]\nname = \"No URL\"\n").unwrap();
+
+        assert!(matches!(
+            Blogroll::load(&path),
+            Err(BlogrollError::Parse { .. })
+        ));
+    }
+}
@@ -3,28 +3,67 @@
 //! Coordinates the full site build process.
 
 use std::{
-    fs,
+    collections::{BTreeSet, HashMap, HashSet},
+    fmt, fs,
     path::{Path, PathBuf},
-    time::Instant,
+    sync::Mutex,
+    time::{Duration, Instant},
 };
 
+use chrono::Utc;
 use rayon::prelude::*;
+use serde::{Deserialize, Serialize};
 use thiserror::Error;
 use tracing::{debug, info, warn};
-use typstify_core::{Config, Page};
-use typstify_search::SimpleSearchIndex;
+use typstify_core::{
+    Config, Page,
+    config::{PaginateBy, TaxonomySettings},
+};
+use typstify_parser::{AUTO_THEME, SyntaxHighlighter};
+use typstify_search::{
+    Analyzer, CHUNKED_INDEX_DIR, ChunkerConfig, ENCRYPTED_INDEX_FILE, EncryptedIndex,
+    INDEX_MANIFEST_FILE, IndexChunker, IndexerConfig, MAX_SIMPLE_INDEX_SIZE, SearchIndexer,
+    SimpleSearchIndex,
+};
 
 use crate::{
-    assets::{AssetError, AssetManifest, AssetProcessor},
-    collector::{CollectorError, ContentCollector, SiteContent, paginate},
+    assets::{AssetError, AssetManifest, AssetProcessor, run_command},
+    audit::{HtmlToken, scan_html},
+    blogroll::{BLOGROLL_FILE, Blogroll, BlogrollError, OPML_FILE},
+    cache::{BuildCache, CACHE_FILE, CacheError, store_rendered},
+    changelog::Changelog,
+    collector::{
+        CollectorError, ContentCollector, SiteContent, listing_order, paginate, paginate_by_window,
+    },
+    data::{self, DATA_DIR, DataFetcher},
+    events,
+    gallery::{self, GalleryImage},
     html::{
-        HtmlError, HtmlGenerator, list_item_html, pagination_html, shorts_with_separators_html,
+        HtmlError, HtmlGenerator, archive_picker_html, list_item_html, pagination_html,
+        shorts_with_separators_html, slug_from_str, window_pagination_html,
     },
+    images::{ImagePipeline, ImageVariants, THUMBNAIL_SIZE, rewrite_img_tags},
+    links::{LINK_GRAPH_FILE, LinkGraph},
+    manifest::{BuildManifest, MANIFEST_FILE, ManifestError},
+    math::{self, MathError},
+    mermaid::{self, MERMAID_DIR, Prerenderer},
+    remote::{REMOTE_DIR, RemoteError, RemoteImages},
     robots::{RobotsError, RobotsGenerator},
     rss::{RssError, RssGenerator},
+    sections::{SITE_STRUCTURE_FILE, SectionTree, SiteStructure, sidebar_html, subsections_html},
+    shortcodes::ShortcodeTemplates,
     sitemap::{SitemapError, SitemapGenerator},
+    sources::{self, SourceError},
+    template::TemplateRegistry,
+    themes::{THEMES_DIR, Theme, ThemeError},
+    urlmap::{URL_MAP_FILE, UrlMap},
+    webmentions::{WebmentionError, Webmentions},
 };
 
+/// Environment variable holding the key of the encrypted preview search
+/// index (`search.encrypted_preview`).
+pub const SEARCH_KEY_ENV: &str = "TYPSTIFY_SEARCH_KEY";
+
 /// Build errors.
 #[derive(Debug, Error)]
 pub enum BuildError {
@@ -56,6 +95,51 @@ pub enum BuildError {
     #[error("asset error: {0}")]
     Asset(#[from] AssetError),
 
+    /// Build manifest error.
+    #[error("manifest error: {0}")]
+    Manifest(#[from] ManifestError),
+
+    /// JSON serialization error.
+    #[error("JSON error: {0}")]
+    Json(#[from] serde_json::Error),
+
+    /// Remote image error.
+    #[error("remote image error: {0}")]
+    Remote(#[from] RemoteError),
+
+    /// Remote content source error.
+    #[error("remote content error: {0}")]
+    Source(#[from] SourceError),
+
+    /// Webmention export error.
+    #[error("webmention error: {0}")]
+    Webmention(#[from] WebmentionError),
+
+    /// Theme error.
+    #[error("theme error: {0}")]
+    Theme(#[from] ThemeError),
+
+    /// Build cache error.
+    #[error("build cache error: {0}")]
+    Cache(#[from] CacheError),
+
+    /// Blogroll error.
+    #[error("blogroll error: {0}")]
+    Blogroll(#[from] BlogrollError),
+
+    /// Math asset error.
+    #[error("math error: {0}")]
+    Math(#[from] MathError),
+
+    /// A page failed with `build.fail_on_error` set.
+    #[error("failed to build {location}: {message}")]
+    Page {
+        /// Page URL or source file path.
+        location: String,
+        /// Error message.
+        message: String,
+    },
+
     /// Configuration error.
     #[error("config error: {0}")]
     Config(String),
@@ -64,8 +148,9 @@ pub enum BuildError {
 /// Result type for build operations.
 pub type Result<T> = std::result::Result<T, BuildError>;
 
-/// Build statistics.
-#[derive(Debug, Clone, Default)]
+/// Build statistics, serialized as the build report of `typstify build
+/// --report`.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct BuildStats {
     /// Number of pages generated.
     pub pages: usize,
@@ -82,8 +167,108 @@ pub struct BuildStats {
     /// Number of assets processed.
     pub assets: usize,
 
+    /// Number of pages taken from the build cache instead of being rendered.
+    pub cache_hits: usize,
+
+    /// Number of pages rendered because they were not in the build cache or
+    /// their inputs changed (0 when the cache is disabled).
+    pub cache_misses: usize,
+
+    /// Problems that did not stop the build, in the order they were found.
+    pub warnings: Vec<BuildWarning>,
+
+    /// Parse and render times of each page, slowest first (empty unless
+    /// [`Builder::with_timings`] is set).
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub timings: Vec<PageTiming>,
+
     /// Build duration in milliseconds.
     pub duration_ms: u64,
+
+    /// Peak resident set size of the process in bytes, where the platform
+    /// reports it (Linux).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub peak_rss_bytes: Option<u64>,
+}
+
+/// Time spent building one page.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PageTiming {
+    /// Page URL.
+    pub url: String,
+
+    /// Milliseconds spent reading and parsing the source file.
+    pub parse_ms: f64,
+
+    /// Milliseconds spent rendering the page's HTML (0 when it came from the
+    /// build cache).
+    pub render_ms: f64,
+}
+
+impl PageTiming {
+    /// Total milliseconds spent on the page.
+    #[must_use]
+    pub fn total_ms(&self) -> f64 {
+        self.parse_ms + self.render_ms
+    }
+}
+
+/// What a [`BuildWarning`] is about.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum WarningKind {
+    /// A content file failed to parse and was left out.
+    Parse,
+
+    /// A page failed to render and was left out.
+    Page,
+
+    /// A referenced file, image or gallery was missing or not processed.
+    Asset,
+
+    /// Build-time data could not be fetched.
+    Data,
+
+    /// A search index is larger than recommended.
+    Search,
+
+    /// A setting could not be applied.
+    Config,
+}
+
+impl fmt::Display for WarningKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Self::Parse => "parse",
+            Self::Page => "page",
+            Self::Asset => "asset",
+            Self::Data => "data",
+            Self::Search => "search",
+            Self::Config => "config",
+        })
+    }
+}
+
+/// A problem that did not stop the build.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BuildWarning {
+    /// What the warning is about.
+    pub kind: WarningKind,
+
+    /// Page URL or file path the warning is about, if any.
+    pub location: Option<String>,
+
+    /// Description of the problem.
+    pub message: String,
+}
+
+impl fmt::Display for BuildWarning {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self.location {
+            Some(location) => write!(f, "{location}: {}", self.message),
+            None => f.write_str(&self.message),
+        }
+    }
 }
 
 /// Site builder that orchestrates the build process.
@@ -93,6 +278,15 @@ pub struct Builder {
     content_dir: PathBuf,
     output_dir: PathBuf,
     static_dir: Option<PathBuf>,
+    templates_dir: Option<PathBuf>,
+    themes_dir: PathBuf,
+    blogroll_file: PathBuf,
+    fetch_remote: bool,
+    cache: bool,
+    timings: bool,
+    search_key: Option<String>,
+    /// Warnings of the running build.
+    warnings: Mutex<Vec<BuildWarning>>,
 }
 
 impl Builder {
@@ -108,6 +302,16 @@ impl Builder {
             content_dir: content_dir.into(),
             output_dir: output_dir.into(),
             static_dir: None,
+            templates_dir: None,
+            themes_dir: PathBuf::from(THEMES_DIR),
+            blogroll_file: PathBuf::from(BLOGROLL_FILE),
+            fetch_remote: true,
+            cache: false,
+            timings: false,
+            search_key: std::env::var(SEARCH_KEY_ENV)
+                .ok()
+                .filter(|key| !key.is_empty()),
+            warnings: Mutex::new(Vec::new()),
         }
     }
 
@@ -118,10 +322,70 @@ impl Builder {
         self
     }
 
+    /// Set the site templates directory. Its templates replace the theme's
+    /// and the built-in templates of the same name.
+    #[must_use]
+    pub fn with_templates_dir(mut self, dir: impl Into<PathBuf>) -> Self {
+        self.templates_dir = Some(dir.into());
+        self
+    }
+
+    /// Set the directory the `theme` setting is resolved in (default:
+    /// `themes`).
+    #[must_use]
+    pub fn with_themes_dir(mut self, dir: impl Into<PathBuf>) -> Self {
+        self.themes_dir = dir.into();
+        self
+    }
+
+    /// Set the blogroll data file (default: `data/blogroll.toml`). The
+    /// blogroll is generated when it exists.
+    #[must_use]
+    pub fn with_blogroll_file(mut self, path: impl Into<PathBuf>) -> Self {
+        self.blogroll_file = path.into();
+        self
+    }
+
+    /// Set whether `[content.remote]` sources are fetched before collecting
+    /// content (default: true). When disabled, the copies already in the
+    /// content tree are used.
+    #[must_use]
+    pub fn with_remote_fetch(mut self, fetch: bool) -> Self {
+        self.fetch_remote = fetch;
+        self
+    }
+
+    /// Set whether parsed and rendered pages are kept in `build.cache_dir`
+    /// between builds (default: false). With the cache, pages whose source,
+    /// templates and configuration are unchanged are not parsed or rendered
+    /// again.
+    #[must_use]
+    pub fn with_cache(mut self, cache: bool) -> Self {
+        self.cache = cache;
+        self
+    }
+
+    /// Set whether per-page parse and render times are recorded in
+    /// [`BuildStats::timings`] (default: false).
+    #[must_use]
+    pub fn with_timings(mut self, timings: bool) -> Self {
+        self.timings = timings;
+        self
+    }
+
+    /// Set the key of the encrypted preview search index
+    /// (`search.encrypted_preview`; default: `TYPSTIFY_SEARCH_KEY`).
+    #[must_use]
+    pub fn with_search_key(mut self, key: impl Into<String>) -> Self {
+        self.search_key = Some(key.into());
+        self
+    }
+
     /// Execute the full build process.
     pub fn build(&self) -> Result<BuildStats> {
         let start = Instant::now();
         let mut stats = BuildStats::default();
+        self.take_warnings();
 
         info!(
             content = %self.content_dir.display(),
@@ -129,55 +393,156 @@ impl Builder {
             "starting build"
         );
 
-        // 1. Clean output directory
+        // 1. Resolve the theme, then clean output directory, keeping the
+        //    previous manifest
+        let theme = self.theme()?;
+        let cache_dir = Path::new(&self.config.build.cache_dir);
+        let cache_path = cache_dir.join(CACHE_FILE);
+        let cache = if self.cache {
+            BuildCache::load(&cache_path, &self.config)
+        } else {
+            BuildCache::new(&self.config)
+        };
         self.clean_output()?;
 
-        // 2. Collect content
-        let collector = ContentCollector::new(self.config.clone(), &self.content_dir);
-        let content = collector.collect()?;
+        // 2. Fetch remote content sources, then collect content
+        if self.fetch_remote {
+            self.sync_remote_content()?;
+        }
+        let collector = ContentCollector::new(self.config.clone(), &self.content_dir)
+            .with_shortcodes(self.shortcodes(theme.as_ref())?);
+        let previous = collector.previous_manifest();
+        let (mut content, mut next_cache) = collector.collect_cached(&cache)?;
+        if self.config.build.fail_on_error
+            && let Some((path, message)) = content.failed.first()
+        {
+            return Err(BuildError::Page {
+                location: path.display().to_string(),
+                message: message.clone(),
+            });
+        }
+        for (path, message) in &content.failed {
+            self.warn(
+                WarningKind::Parse,
+                Some(&path.display().to_string()),
+                format!("failed to parse: {message}"),
+            );
+        }
+        let mut manifest = collector.manifest()?;
+        self.redirect_moved_content(&mut content, &mut manifest, &previous);
+        stats.assets = self.copy_page_assets(&mut content)?;
+        stats.assets += self.attach_images(&mut content)?;
+        if self.config.build.localize_images {
+            stats.assets += self.localize_remote_images(&mut content)?;
+        }
+        if self.config.images.responsive {
+            stats.assets += self.generate_responsive_images(&mut content);
+        }
+        stats.assets += self.expand_galleries(&mut content)?;
+        self.expand_data(&mut content)?;
+        if self.config.mermaid.prerender {
+            self.prerender_diagrams(&mut content);
+        }
+
+        // 3. Extract sections for dynamic navigation, sorted so the navigation
+        //    (and the build cache) is the same from build to build
+        let mut sections: Vec<String> = content.sections.keys().cloned().collect();
+        sections.sort();
 
-        // 3. Extract sections for dynamic navigation
-        let sections: Vec<String> = content.sections.keys().cloned().collect();
+        // 4. Generate search indexes (per language), before the pages that
+        //    point at the chunked ones
+        if self.config.search.enabled {
+            self.generate_search_indexes(&content)?;
+        }
 
-        // 4. Generate HTML pages
-        stats.pages = self.generate_pages(&content, &sections)?;
+        // 5. Generate HTML pages
+        let (pages, hits, render_times) =
+            self.generate_pages(&content, &sections, &cache, &mut next_cache)?;
+        stats.pages = pages;
+        if self.cache {
+            stats.cache_hits = hits;
+            stats.cache_misses = pages - hits;
+        }
+        if self.timings {
+            stats.timings = page_timings(&content.parse_times, &render_times);
+        }
 
-        // 5. Generate taxonomy pages
+        // 6. Generate taxonomy pages
         stats.taxonomy_pages = self.generate_taxonomy_pages(&content, &sections)?;
 
-        // 6. Generate auto-generated index pages (archives, tags index, section indices)
+        // 7. Generate auto-generated index pages (archives, tags index, section indices)
         stats.auto_pages = self.generate_auto_pages(&content, &sections)?;
 
-        // 6. Generate redirects
+        if self.config.build.changelog {
+            stats.auto_pages += self.generate_changelog(&content, &sections)?;
+        }
+        stats.auto_pages += self.generate_blogroll(&content, &sections)?;
+
+        // 8. Generate redirects
         stats.redirects = self.generate_redirects(&content)?;
 
-        // 7. Generate RSS feed
+        // 9. Generate RSS feed
         if self.config.rss.enabled {
             self.generate_rss(&content)?;
         }
 
-        // 8. Generate sitemap
-        self.generate_sitemap(&content)?;
+        // 10. Generate sitemap
+        let sitemaps = self.generate_sitemap(&content)?;
 
-        // 9. Generate robots.txt
-        self.generate_robots()?;
+        // 11. Generate robots.txt and the webfinger document
+        self.generate_robots(&sitemaps)?;
+        self.generate_webfinger()?;
 
-        // 10. Generate search index (per language)
-        if self.config.search.enabled {
-            self.generate_search_indexes(&content)?;
-        }
+        // 12. Generate site structure (per language)
+        self.generate_site_structure(&content)?;
 
-        // 11. Generate static CSS/JS assets for better caching
-        crate::static_assets::generate_static_assets(&self.output_dir)
+        // 13. Generate static CSS/JS assets for better caching
+        let syntax_css = self.syntax_highlighter().css();
+        crate::static_assets::generate_static_assets(&self.output_dir, syntax_css.as_deref())
             .map_err(|e| BuildError::Io(std::io::Error::other(e.to_string())))?;
+        self.compile_stylesheet()?;
+        if self.config.math.enabled
+            && let Some(katex_dir) = &self.config.math.katex_dir
+        {
+            let copied = math::copy_katex(Path::new(katex_dir), &self.output_dir)?;
+            debug!(files = copied, "published KaTeX");
+        }
+
+        // 14. Process theme and user-provided assets
+        let static_dirs: Vec<PathBuf> = theme
+            .iter()
+            .map(Theme::static_dir)
+            .chain(self.static_dir.clone())
+            .collect();
+        if !static_dirs.is_empty() {
+            let manifest = self.process_assets(&static_dirs)?;
+            stats.assets += manifest.assets().len();
+        }
 
-        // 12. Process user-provided assets
-        if let Some(ref static_dir) = self.static_dir {
-            let manifest = self.process_assets(static_dir)?;
-            stats.assets = manifest.assets().len();
+        // 15. Record source hashes and content IDs for the next build
+        manifest.write_to(&cache_dir.join(MANIFEST_FILE))?;
+
+        // 16. Write the internal link graph and the URL map
+        if self.config.build.link_graph {
+            let graph = LinkGraph::build(&self.config, &content, |page| self.is_public(page));
+            fs::write(self.output_dir.join(LINK_GRAPH_FILE), graph.to_json()?)?;
+        }
+        if self.config.build.url_map {
+            let map = UrlMap::build(&self.config, &content, |page| self.is_public(page));
+            let json = map
+                .to_json()
+                .map_err(|e| BuildError::Config(e.to_string()))?;
+            fs::write(self.output_dir.join(URL_MAP_FILE), json)?;
+        }
+
+        // 17. Keep parsed and rendered pages for the next build
+        if self.cache {
+            next_cache.write_to(&cache_path)?;
         }
 
+        stats.warnings = self.take_warnings();
         stats.duration_ms = start.elapsed().as_millis() as u64;
+        stats.peak_rss_bytes = peak_rss_bytes();
 
         info!(
             pages = stats.pages,
@@ -185,13 +550,40 @@ impl Builder {
             auto_pages = stats.auto_pages,
             redirects = stats.redirects,
             assets = stats.assets,
+            cache_hits = stats.cache_hits,
+            warnings = stats.warnings.len(),
             duration_ms = stats.duration_ms,
+            peak_rss_bytes = stats.peak_rss_bytes,
             "build complete"
         );
 
         Ok(stats)
     }
 
+    /// Log a warning and record it for [`BuildStats::warnings`].
+    fn warn(&self, kind: WarningKind, location: Option<&str>, message: impl Into<String>) {
+        let warning = BuildWarning {
+            kind,
+            location: location.map(str::to_string),
+            message: message.into(),
+        };
+        warn!(%kind, "{warning}");
+        self.warnings
+            .lock()
+            .unwrap_or_else(std::sync::PoisonError::into_inner)
+            .push(warning);
+    }
+
+    /// The recorded warnings, leaving none.
+    fn take_warnings(&self) -> Vec<BuildWarning> {
+        std::mem::take(
+            &mut *self
+                .warnings
+                .lock()
+                .unwrap_or_else(std::sync::PoisonError::into_inner),
+        )
+    }
+
     /// Clean the output directory.
     fn clean_output(&self) -> Result<()> {
         if self.output_dir.exists() {
@@ -202,99 +594,328 @@ impl Builder {
         Ok(())
     }
 
+    /// Add aliases from the previous URLs of pages with a `content_id`, so
+    /// renamed pages keep redirecting from where they used to live.
+    ///
+    /// URLs now used by another page are not redirected.
+    fn redirect_moved_content(
+        &self,
+        content: &mut SiteContent,
+        manifest: &mut BuildManifest,
+        previous: &BuildManifest,
+    ) {
+        let urls: BTreeSet<String> = content.pages.keys().cloned().collect();
+
+        for page in content.pages.values_mut() {
+            let Some(id) = &page.content_id else {
+                continue;
+            };
+            let key = format!("{}:{id}", page.lang);
+            for old_url in manifest.track_content_id(&key, &page.url, previous) {
+                if urls.contains(&old_url) || page.aliases.contains(&old_url) {
+                    continue;
+                }
+                info!(from = %old_url, to = %page.url, "redirecting moved page");
+                page.aliases.push(old_url);
+            }
+        }
+    }
+
+    /// Load the received webmentions configured in `webmentions.data`.
+    ///
+    /// A missing export only logs a warning, so builds work before the first
+    /// export has been fetched.
+    fn load_webmentions(&self) -> Result<Option<Webmentions>> {
+        let Some(data) = &self.config.webmentions.data else {
+            return Ok(None);
+        };
+        let path = Path::new(data);
+        if !path.exists() {
+            self.warn(
+                WarningKind::Data,
+                Some(&path.display().to_string()),
+                "webmentions data not found",
+            );
+            return Ok(None);
+        }
+        Ok(Some(Webmentions::load(path)?))
+    }
+
     /// Generate HTML pages for all content.
-    fn generate_pages(&self, content: &SiteContent, sections: &[String]) -> Result<usize> {
-        let generator = HtmlGenerator::new(self.config.clone()).with_sections(sections.to_vec());
+    ///
+    /// Pages are written as they are rendered, and with the cache enabled
+    /// also stored in the cache directory, so no page's HTML is kept past
+    /// writing it. Pages whose digest matches an entry of `cache` are written
+    /// from it instead of being rendered; with the cache enabled, every
+    /// written page is recorded in `next`.
+    /// Returns the number of pages written, how many came from the cache and
+    /// the time spent rendering each.
+    fn generate_pages(
+        &self,
+        content: &SiteContent,
+        sections: &[String],
+        cache: &BuildCache,
+        next: &mut BuildCache,
+    ) -> Result<(usize, usize, HashMap<String, Duration>)> {
+        let trees = self.section_trees(content);
+        let titles = content
+            .pages
+            .values()
+            .map(|p| (p.url.clone(), p.title.clone()))
+            .collect();
+        let mut generator = self
+            .html_generator(content)?
+            .with_sections(sections.to_vec())
+            .with_sidebars(self.docs_sidebars(content, &trees))
+            .with_neighbors(content.neighbors.clone(), titles)
+            .with_inline_search(self.inline_search_indexes(content)?);
+        if self.config.build.backlinks {
+            generator =
+                generator.with_link_graph(LinkGraph::build(&self.config, content, |page| {
+                    self.is_public(page)
+                }));
+        }
+        if let Some(webmentions) = self.load_webmentions()? {
+            generator = generator.with_webmentions(webmentions);
+        }
         let pages: Vec<_> = content.pages.values().collect();
+        let digest = generator.digest();
+        let cache_dir = Path::new(&self.config.build.cache_dir);
 
         info!(count = pages.len(), "generating HTML pages");
 
-        // Generate pages in parallel
-        let results: Vec<_> = pages
-            .par_iter()
-            .map(|page| {
-                // Collect alternate language versions
-                let mut alternates = Vec::new();
-                if let Some(slugs) = content.translations.get(&page.canonical_id) {
-                    for slug in slugs {
-                        if let Some(alt_page) = content.pages.get(slug) {
-                            alternates.push((alt_page.lang.as_str(), alt_page.url.as_str()));
-                        }
+        let render = |page: &Page| {
+            // Collect alternate language versions
+            let mut alternates = Vec::new();
+            if let Some(slugs) = content.translations.get(&page.canonical_id) {
+                for slug in slugs {
+                    if let Some(alt_page) = content.pages.get(slug) {
+                        alternates.push((alt_page.lang.as_str(), alt_page.url.as_str()));
                     }
                 }
+            }
 
-                let html = generator.generate_page(page, &alternates)?;
-                let output_path = generator.output_path(page, &self.output_dir);
+            let page_digest = generator.page_digest(&digest, page, &alternates);
+            let cached = cache.rendered(cache_dir, &page.url, &page_digest);
+            let start = Instant::now();
+            let hit = cached.is_some();
+            let html = match cached {
+                Some(html) => html,
+                None => generator.generate_page(page, &alternates)?,
+            };
+            let elapsed = start.elapsed();
+            let output_path = generator.output_path(page, &self.output_dir);
 
-                // Write HTML file
-                if let Some(parent) = output_path.parent() {
-                    fs::create_dir_all(parent)?;
-                }
-                fs::write(&output_path, &html)?;
+            // Write HTML file
+            if let Some(parent) = output_path.parent() {
+                fs::create_dir_all(parent)?;
+            }
+            fs::write(&output_path, html.as_bytes())?;
 
-                debug!(path = %output_path.display(), "wrote page");
-                Ok::<_, BuildError>(())
-            })
+            if self.cache && !hit {
+                store_rendered(cache_dir, &page.url, &page_digest, &html)?;
+            }
+
+            debug!(path = %output_path.display(), cached = hit, "wrote page");
+            Ok::<_, BuildError>((page_digest, hit, elapsed))
+        };
+
+        // Generate pages in parallel
+        let results: Vec<_> = pages
+            .par_iter()
+            .map(|page| (page.url.as_str(), render(page)))
             .collect();
 
         // Check for errors
         let mut count = 0;
-        for result in results {
+        let mut hits = 0;
+        let mut render_times = HashMap::with_capacity(results.len());
+        for (url, result) in results {
             match result {
-                Ok(()) => count += 1,
-                Err(e) => warn!(error = %e, "failed to generate page"),
+                Ok((page_digest, cached, elapsed)) => {
+                    count += 1;
+                    hits += usize::from(cached);
+                    render_times.insert(url.to_string(), elapsed);
+                    if self.cache {
+                        next.insert_rendered(url, page_digest);
+                    }
+                }
+                Err(e) if self.config.build.fail_on_error => {
+                    return Err(BuildError::Page {
+                        location: url.to_string(),
+                        message: e.to_string(),
+                    });
+                }
+                Err(e) => self.warn(
+                    WarningKind::Page,
+                    Some(url),
+                    format!("failed to generate page: {e}"),
+                ),
             }
         }
 
-        Ok(count)
+        Ok((count, hits, render_times))
+    }
+
+    /// Section tree of each language with its URL prefix ("" for the default
+    /// language, "/zh" otherwise).
+    fn section_trees(&self, content: &SiteContent) -> Vec<(String, SectionTree)> {
+        self.config
+            .all_languages()
+            .into_iter()
+            .map(|lang| {
+                let lang_prefix = if lang == self.config.site.default_language {
+                    String::new()
+                } else {
+                    format!("/{lang}")
+                };
+                let tree = SectionTree::build(
+                    content.pages.values().filter(|p| p.lang == lang),
+                    &lang_prefix,
+                )
+                .with_meta(
+                    content.section_meta.iter().filter(|(_, m)| m.lang == lang),
+                    &lang_prefix,
+                );
+                (lang_prefix, tree)
+            })
+            .collect()
+    }
+
+    /// Render the sidebar of each docs-layout section, per language, keyed by
+    /// section URL.
+    fn docs_sidebars(
+        &self,
+        content: &SiteContent,
+        trees: &[(String, SectionTree)],
+    ) -> HashMap<String, String> {
+        let base_path = self.config.base_path();
+        let mut sidebars = HashMap::new();
+        for (lang_prefix, tree) in trees {
+            for root in self.config.docs_sections() {
+                if let Some(sidebar) =
+                    sidebar_html(tree, root, base_path, lang_prefix, &content.pages)
+                {
+                    sidebars.insert(format!("{lang_prefix}/{root}"), sidebar);
+                }
+            }
+        }
+        sidebars
     }
 
-    /// Generate taxonomy (tag/category) pages.
+    /// Generate taxonomy (tag/category) and series pages.
     fn generate_taxonomy_pages(&self, content: &SiteContent, sections: &[String]) -> Result<usize> {
-        let generator = HtmlGenerator::new(self.config.clone()).with_sections(sections.to_vec());
-        let per_page = self.config.taxonomies.tags.paginate;
+        let generator = self
+            .html_generator(content)?
+            .with_sections(sections.to_vec())
+            .with_inline_search(self.inline_search_indexes(content)?);
+        let taxonomies = &self.config.taxonomies;
         let mut count = 0;
 
-        // Generate tag pages
-        for (tag, slugs) in &content.taxonomies.tags {
-            let pages: Vec<_> = slugs.iter().filter_map(|s| content.pages.get(s)).collect();
-            count += self
-                .generate_taxonomy_term_pages(&generator, "Tags", tag, &pages, per_page, "tags")?;
+        // Generate tag pages; rare terms are only listed on the index
+        for tag in content.taxonomies.tags.keys() {
+            let pages = ContentCollector::taxonomy_pages(content, "tags", tag);
+            if !taxonomies.tags.has_term_page(pages.len()) {
+                continue;
+            }
+            count += self.generate_taxonomy_term_pages(
+                &generator,
+                "Tags",
+                tag,
+                &pages,
+                &taxonomies.tags,
+                "tags",
+            )?;
         }
 
         // Generate category pages
-        for (category, slugs) in &content.taxonomies.categories {
-            let pages: Vec<_> = slugs.iter().filter_map(|s| content.pages.get(s)).collect();
+        for category in content.taxonomies.categories.keys() {
+            let pages = ContentCollector::taxonomy_pages(content, "categories", category);
+            if !taxonomies.categories.has_term_page(pages.len()) {
+                continue;
+            }
             count += self.generate_taxonomy_term_pages(
                 &generator,
                 "Categories",
                 category,
                 &pages,
-                per_page,
+                &taxonomies.categories,
                 "categories",
             )?;
         }
 
+        // Generate series pages, listing the parts in reading order
+        for (name, urls) in &content.series {
+            let items_html: String = urls
+                .iter()
+                .filter_map(|url| content.pages.get(url))
+                .map(list_item_html)
+                .collect();
+            let html = generator.generate_taxonomy_page("Series", name, &items_html, None, None)?;
+            let output_path = self
+                .output_dir
+                .join("series")
+                .join(slug_from_str(name))
+                .join("index.html");
+            if let Some(parent) = output_path.parent() {
+                fs::create_dir_all(parent)?;
+            }
+            fs::write(&output_path, &html)?;
+            count += 1;
+        }
+
         Ok(count)
     }
 
     /// Generate paginated pages for a taxonomy term.
+    ///
+    /// When the taxonomy has `feed` enabled (and RSS is enabled), also writes
+    /// the term's `rss.xml` and links it from the term pages.
     fn generate_taxonomy_term_pages(
         &self,
         generator: &HtmlGenerator,
         taxonomy_name: &str,
         term: &str,
         pages: &[&typstify_core::Page],
-        per_page: usize,
+        settings: &TaxonomySettings,
         url_prefix: &str,
     ) -> Result<usize> {
         use crate::collector::paginate;
 
+        let per_page = settings.paginate;
         let term_slug = term.to_lowercase().replace(' ', "-");
         let base_url = format!("/{url_prefix}/{term_slug}");
         let total_pages = (pages.len() + per_page - 1).max(1) / per_page.max(1);
         let mut count = 0;
 
+        // Generate the term feed
+        let feed_url = if settings.feed && self.config.rss.enabled {
+            let mut posts: Vec<_> = pages
+                .iter()
+                .copied()
+                .filter(|p| {
+                    p.date.is_some()
+                        && !(p.pinned && self.config.rss.exclude_pinned)
+                        && self.is_public(p)
+                })
+                .collect();
+            posts.sort_by_key(|p| std::cmp::Reverse(p.date));
+
+            let xml = RssGenerator::new(self.config.clone()).generate_for_term(
+                &posts,
+                taxonomy_name,
+                term,
+                &base_url,
+            )?;
+            let feed_dir = self.output_dir.join(url_prefix).join(&term_slug);
+            fs::create_dir_all(&feed_dir)?;
+            fs::write(feed_dir.join("rss.xml"), xml)?;
+
+            Some(format!("{}{base_url}/rss.xml", self.config.base_path()))
+        } else {
+            None
+        };
+
         for page_num in 1..=total_pages.max(1) {
             let (page_items, _) = paginate(pages, page_num, per_page);
 
@@ -307,6 +928,7 @@ impl Builder {
                 term,
                 &items_html,
                 pagination.as_deref(),
+                feed_url.as_deref(),
             )?;
 
             // Determine output path
@@ -337,7 +959,12 @@ impl Builder {
     /// Generate auto-generated index pages: archives, tags index, categories index, section indices.
     /// Generates per-language versions when multiple languages are configured.
     fn generate_auto_pages(&self, content: &SiteContent, sections: &[String]) -> Result<usize> {
-        let generator = HtmlGenerator::new(self.config.clone()).with_sections(sections.to_vec());
+        let trees = self.section_trees(content);
+        let generator = self
+            .html_generator(content)?
+            .with_sections(sections.to_vec())
+            .with_sidebars(self.docs_sidebars(content, &trees))
+            .with_inline_search(self.inline_search_indexes(content)?);
         let mut count = 0;
 
         // Get all languages
@@ -416,7 +1043,7 @@ impl Builder {
                 .filter(|p| p.date.is_some())
                 .copied()
                 .collect();
-            lang_posts.sort_by(|a, b| b.date.cmp(&a.date));
+            lang_posts.sort_by_key(|p| std::cmp::Reverse(p.date));
 
             if !lang_posts.is_empty() {
                 let html = generator.generate_archives_page(&lang_posts, lang)?;
@@ -436,42 +1063,91 @@ impl Builder {
                 info!(path = %output_path.display(), lang = lang, "generated archives page");
             }
 
-            // 4. Generate section index pages (e.g., /posts/, /{lang}/posts/)
-            // Group pages by section within this language
-            let mut sections: std::collections::HashMap<String, Vec<&Page>> =
-                std::collections::HashMap::new();
-            for page in lang_pages.iter().copied() {
-                // Extract section from URL (first path segment after lang prefix if any)
-                let url = page.url.trim_start_matches('/');
-                let section = if is_default {
-                    url.split('/').next().unwrap_or("")
-                } else {
-                    // For non-default lang, URL starts with /{lang}/section/...
-                    url.split('/').nth(1).unwrap_or("")
-                };
+            // 4. Generate section index pages at every level of the section
+            // hierarchy (e.g., /posts/, /docs/guides/, /{lang}/posts/)
+            let url_prefix = if is_default {
+                String::new()
+            } else {
+                format!("/{lang}")
+            };
 
-                if !section.is_empty() && section != "index.html" {
-                    sections.entry(section.to_string()).or_default().push(page);
-                }
-            }
+            // Events page and calendar replace the plain `events` section
+            // index
+            let events_page = self.generate_events(&generator, &lang_pages, &url_prefix, lang)?;
+            count += usize::from(events_page);
+            let tree = SectionTree::build(lang_pages.iter().copied(), &url_prefix).with_meta(
+                content.section_meta.iter().filter(|(_, m)| m.lang == *lang),
+                &url_prefix,
+            );
+            let link_prefix = format!("{}{url_prefix}", self.config.base_path());
 
-            for (section, mut section_pages) in sections {
-                // Sort by date (newest first) or by title
-                section_pages.sort_by(|a, b| match (&b.date, &a.date) {
-                    (Some(b_date), Some(a_date)) => b_date.cmp(a_date),
-                    (Some(_), None) => std::cmp::Ordering::Less,
-                    (None, Some(_)) => std::cmp::Ordering::Greater,
-                    (None, None) => a.title.cmp(&b.title),
-                });
+            for (section, node) in tree.iter() {
+                if events_page && section == "events" {
+                    continue;
+                }
+                let mut section_pages: Vec<&Page> = node
+                    .pages
+                    .iter()
+                    .filter_map(|url| content.pages.get(url))
+                    .collect();
+                let subsections = subsections_html(&link_prefix, &tree, node);
+                let section_url = format!("{url_prefix}/{section}");
 
-                // Generate paginated section index
-                let per_page = self.config.taxonomies.tags.paginate;
-                let total_pages = section_pages.len().div_ceil(per_page).max(1);
+                section_pages.sort_by(|a, b| listing_order(a, b));
 
                 // Use shorts-specific template for shorts section
                 let is_shorts = section == "shorts";
                 let author = self.config.site.author.as_deref().unwrap_or("Author");
 
+                // Shorts timeline split by time window, newest window at the
+                // section URL and older ones under `archive/{window}`
+                let paginate_by = self
+                    .config
+                    .section_for_url(&section_url)
+                    .map_or(PaginateBy::Count, |s| s.paginate_by);
+                if is_shorts && paginate_by != PaginateBy::Count {
+                    let windows =
+                        paginate_by_window(&section_pages, paginate_by, &self.config, lang);
+                    let window_base = format!("{}{section_url}", self.config.base_path());
+                    let section_dir = self.output_dir.join(section_url.trim_start_matches('/'));
+
+                    for (i, window) in windows.iter().enumerate() {
+                        let output_path = if i == 0 {
+                            if content.pages.contains_key(&section_url) {
+                                debug!(url = %section_url, "content page replaces section index");
+                                continue;
+                            }
+                            section_dir.join("index.html")
+                        } else {
+                            section_dir
+                                .join("archive")
+                                .join(&window.key)
+                                .join("index.html")
+                        };
+
+                        let html = generator.generate_shorts_page(
+                            section,
+                            None, // description
+                            &shorts_with_separators_html(&window.pages, author),
+                            window_pagination_html(&windows, i, &window_base).as_deref(),
+                            archive_picker_html(&windows, i, &window_base).as_deref(),
+                            lang,
+                        )?;
+                        if let Some(parent) = output_path.parent() {
+                            fs::create_dir_all(parent)?;
+                        }
+                        fs::write(&output_path, &html)?;
+                        count += 1;
+                    }
+
+                    info!(section = %section, lang = %lang, windows = windows.len(), "generated shorts archive");
+                    continue;
+                }
+
+                // Generate paginated section index
+                let per_page = self.config.taxonomies.tags.paginate;
+                let total_pages = section_pages.len().div_ceil(per_page).max(1);
+
                 for page_num in 1..=total_pages {
                     let (page_items, _) = paginate(&section_pages, page_num, per_page);
 
@@ -482,51 +1158,40 @@ impl Builder {
                         page_items.iter().map(|p| list_item_html(p)).collect()
                     };
 
-                    let base_url = if is_default {
-                        format!("/{section}")
-                    } else {
-                        format!("/{lang}/{section}")
-                    };
-                    let pagination = pagination_html(page_num, total_pages, &base_url);
+                    let pagination = pagination_html(page_num, total_pages, &section_url);
 
                     // Use shorts template for shorts section
                     let html = if is_shorts {
                         generator.generate_shorts_page(
-                            &section,
+                            section,
                             None, // description
                             &items_html,
                             pagination.as_deref(),
+                            None, // archive picker
                             lang,
                         )?
                     } else {
                         generator.generate_section_page(
-                            &section,
+                            section,
                             None, // description
                             &items_html,
+                            subsections.as_deref(),
                             pagination.as_deref(),
                             lang,
                         )?
                     };
 
+                    let section_dir = self.output_dir.join(section_url.trim_start_matches('/'));
                     let output_path = if page_num == 1 {
-                        if is_default {
-                            self.output_dir.join(&section).join("index.html")
-                        } else {
-                            self.output_dir
-                                .join(&lang_prefix)
-                                .join(&section)
-                                .join("index.html")
+                        // Don't overwrite a content page at the section URL
+                        // (e.g. `docs/index.md`)
+                        if content.pages.contains_key(&section_url) {
+                            debug!(url = %section_url, "content page replaces section index");
+                            continue;
                         }
-                    } else if is_default {
-                        self.output_dir
-                            .join(&section)
-                            .join("page")
-                            .join(page_num.to_string())
-                            .join("index.html")
+                        section_dir.join("index.html")
                     } else {
-                        self.output_dir
-                            .join(&lang_prefix)
-                            .join(&section)
+                        section_dir
                             .join("page")
                             .join(page_num.to_string())
                             .join("index.html")
@@ -548,7 +1213,7 @@ impl Builder {
 
     /// Generate redirect pages for URL aliases.
     fn generate_redirects(&self, content: &SiteContent) -> Result<usize> {
-        let generator = HtmlGenerator::new(self.config.clone());
+        let generator = self.html_generator(content)?;
         let mut count = 0;
 
         for page in content.pages.values() {
@@ -577,8 +1242,15 @@ impl Builder {
         let generator = RssGenerator::new(self.config.clone());
         let pages = ContentCollector::pages_by_date(content);
 
-        // Filter to only posts (pages with dates)
-        let posts: Vec<_> = pages.into_iter().filter(|p| p.date.is_some()).collect();
+        // Filter to only public posts (pages with dates)
+        let posts: Vec<_> = pages
+            .into_iter()
+            .filter(|p| {
+                p.date.is_some()
+                    && !(p.pinned && self.config.rss.exclude_pinned)
+                    && self.is_public(p)
+            })
+            .collect();
 
         // Generate main RSS feed with all languages
         let xml = generator.generate(&posts)?;
@@ -621,15 +1293,28 @@ impl Builder {
         Ok(())
     }
 
-    /// Generate sitemap.
-    fn generate_sitemap(&self, content: &SiteContent) -> Result<()> {
+    /// Generate sitemaps.
+    ///
+    /// Returns the generated sitemap paths relative to the output root.
+    fn generate_sitemap(&self, content: &SiteContent) -> Result<Vec<String>> {
         let generator = SitemapGenerator::new(self.config.clone());
-        let pages: Vec<_> = content.pages.values().collect();
+        let mut pages: Vec<_> = content
+            .pages
+            .values()
+            .filter(|p| self.config.in_sitemap(p, Utc::now()))
+            .collect();
+        pages.sort_by(|a, b| a.url.cmp(&b.url));
 
-        let xml = generator.generate(&pages)?;
-        let output_path = self.output_dir.join("sitemap.xml");
-        fs::write(&output_path, xml)?;
-        info!(path = %output_path.display(), "generated sitemap");
+        let mut paths = Vec::new();
+        for file in generator.generate_files(&pages)? {
+            let output_path = self.output_dir.join(&file.path);
+            if let Some(parent) = output_path.parent() {
+                fs::create_dir_all(parent)?;
+            }
+            fs::write(&output_path, &file.xml)?;
+            info!(path = %output_path.display(), "generated sitemap");
+            paths.push(file.path);
+        }
 
         // Generate XSLT stylesheet for sitemap
         let xsl = crate::sitemap::generate_sitemap_xsl();
@@ -637,51 +1322,363 @@ impl Builder {
         fs::write(&xsl_path, xsl)?;
         info!(path = %xsl_path.display(), "generated sitemap stylesheet");
 
-        Ok(())
+        Ok(paths)
     }
 
-    /// Generate robots.txt.
-    fn generate_robots(&self) -> Result<()> {
+    /// Generate robots.txt referencing every generated sitemap.
+    fn generate_robots(&self, sitemaps: &[String]) -> Result<()> {
         let generator = RobotsGenerator::new(self.config.clone());
-        generator.generate(&self.output_dir)?;
+        generator.generate(&self.output_dir, sitemaps)?;
         Ok(())
     }
 
-    /// Generate search indexes per language.
+    /// Write `events.ics` and, unless a content page takes its URL, the
+    /// `/events/` page for the events among `pages` (one language).
     ///
-    /// Creates a `search-index.json` for default language at root,
-    /// and `/{lang}/search-index.json` for non-default languages.
-    fn generate_search_indexes(&self, content: &SiteContent) -> Result<()> {
-        let all_languages = self.config.all_languages();
-        let default_lang = &self.config.site.default_language;
+    /// Returns whether the events page was written.
+    fn generate_events(
+        &self,
+        generator: &HtmlGenerator,
+        pages: &[&Page],
+        url_prefix: &str,
+        lang: &str,
+    ) -> Result<bool> {
+        let (upcoming, past) = events::split_events(pages.iter().copied(), Utc::now());
+        if upcoming.is_empty() && past.is_empty() {
+            return Ok(false);
+        }
 
-        for lang in &all_languages {
-            // Filter pages by language
-            let lang_pages: Vec<_> = content.pages.values().filter(|p| p.lang == *lang).collect();
+        let lang_dir = self.output_dir.join(url_prefix.trim_start_matches('/'));
+        fs::create_dir_all(&lang_dir)?;
+        let calendar_name = format!("{} events", self.config.title_for_language(lang));
+        let published: Vec<&Page> = upcoming
+            .iter()
+            .chain(&past)
+            .copied()
+            .filter(|page| self.is_public(page))
+            .collect();
+        fs::write(
+            lang_dir.join(events::ICS_FILE),
+            events::ics(&self.config, &published, &calendar_name),
+        )?;
+
+        let events_url = format!("{url_prefix}/events");
+        if pages.iter().any(|page| page.url == events_url) {
+            debug!(url = %events_url, "content page replaces events page");
+            return Ok(false);
+        }
 
-            if lang_pages.is_empty() {
-                continue;
+        let calendar_url = format!(
+            "{}{url_prefix}/{}",
+            self.config.base_path(),
+            events::ICS_FILE
+        );
+        let items = events::events_html(&self.config, &upcoming, &past, &calendar_url);
+        let html = generator.generate_events_page(&items, &calendar_url, lang)?;
+        let output_path = lang_dir.join("events").join("index.html");
+        fs::create_dir_all(lang_dir.join("events"))?;
+        fs::write(&output_path, html)?;
+        info!(
+            path = %output_path.display(),
+            upcoming = upcoming.len(),
+            past = past.len(),
+            "generated events page"
+        );
+        Ok(true)
+    }
+
+    /// Generate `/changelog/` from the git history of the content
+    /// directory. Skipped with a warning outside a git repository.
+    fn generate_changelog(&self, content: &SiteContent, sections: &[String]) -> Result<usize> {
+        if content.pages.contains_key("/changelog") {
+            debug!("content page replaces changelog");
+            return Ok(0);
+        }
+        let changelog = match Changelog::from_git(&self.content_dir) {
+            Ok(changelog) => changelog,
+            Err(e) => {
+                self.warn(WarningKind::Data, None, format!("skipping changelog: {e}"));
+                return Ok(0);
             }
+        };
 
-            // Build simple search index
-            let index = SimpleSearchIndex::from_pages(&lang_pages);
+        let items = changelog.html(content.pages.values(), self.config.base_path());
+        let html = self
+            .html_generator(content)?
+            .with_sections(sections.to_vec())
+            .generate_changelog_page(&items)?;
 
-            // Determine output path
-            let output_path = if *lang == default_lang.as_str() {
-                self.output_dir.join("search-index.json")
-            } else {
-                self.output_dir.join(lang).join("search-index.json")
-            };
+        let output_path = self.output_dir.join("changelog").join("index.html");
+        if let Some(parent) = output_path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        fs::write(&output_path, html)?;
+        info!(path = %output_path.display(), commits = changelog.commits().len(), "generated changelog");
+        Ok(1)
+    }
 
-            // Create parent directories if needed
-            if let Some(parent) = output_path.parent() {
-                fs::create_dir_all(parent)?;
+    /// Generate `blogroll.opml` and `/blogroll/` from the blogroll data file,
+    /// if there is one. A `blogroll` content page replaces the generated
+    /// page, but the OPML file is still written.
+    fn generate_blogroll(&self, content: &SiteContent, sections: &[String]) -> Result<usize> {
+        if !self.blogroll_file.is_file() {
+            return Ok(0);
+        }
+        let blogroll = Blogroll::load(&self.blogroll_file)?;
+
+        let opml = blogroll.opml(self.config.site.author.as_deref());
+        fs::write(self.output_dir.join(OPML_FILE), opml)?;
+        info!(sites = blogroll.feeds.len(), "generated blogroll OPML");
+
+        if content.pages.contains_key("/blogroll") {
+            debug!("content page replaces blogroll");
+            return Ok(0);
+        }
+
+        let opml_url = format!("{}/{OPML_FILE}", self.config.base_path());
+        let html = self
+            .html_generator(content)?
+            .with_sections(sections.to_vec())
+            .generate_blogroll_page(blogroll.title(), &blogroll.html(&opml_url))?;
+
+        let output_path = self.output_dir.join("blogroll").join("index.html");
+        if let Some(parent) = output_path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        fs::write(&output_path, html)?;
+        info!(path = %output_path.display(), "generated blogroll");
+        Ok(1)
+    }
+
+    /// Write `/.well-known/webfinger` for the configured fediverse account.
+    fn generate_webfinger(&self) -> Result<()> {
+        let Some(json) = crate::identity::webfinger(&self.config) else {
+            if let Some(account) = &self.config.identity.fediverse {
+                self.warn(
+                    WarningKind::Config,
+                    Some("identity.fediverse"),
+                    format!("invalid fediverse account {account}, expected @user@instance"),
+                );
+            }
+            return Ok(());
+        };
+        if !self.config.base_path().is_empty() {
+            self.warn(
+                WarningKind::Config,
+                Some("identity.fediverse"),
+                "webfinger is only discovered at the domain root, not under base_url's path",
+            );
+        }
+
+        let path = self.output_dir.join(crate::identity::WEBFINGER_FILE);
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        fs::write(&path, json)?;
+        info!(path = %path.display(), "generated webfinger");
+        Ok(())
+    }
+
+    /// Generate the section hierarchy and page titles of each language
+    /// ([`SITE_STRUCTURE_FILE`]) for client-side breadcrumbs, at the root for
+    /// the default language and under `/{lang}/` for the others. Only public
+    /// pages are listed.
+    fn generate_site_structure(&self, content: &SiteContent) -> Result<()> {
+        let default_lang = &self.config.site.default_language;
+
+        for lang in self.config.all_languages() {
+            let lang_prefix = if lang == default_lang.as_str() {
+                String::new()
+            } else {
+                format!("/{lang}")
+            };
+            let tree = SectionTree::build(
+                content
+                    .pages
+                    .values()
+                    .filter(|p| p.lang == lang && self.is_public(p)),
+                &lang_prefix,
+            )
+            .with_meta(
+                content.section_meta.iter().filter(|(_, m)| m.lang == lang),
+                &lang_prefix,
+            );
+            if tree.is_empty() {
+                continue;
+            }
+
+            let structure =
+                SiteStructure::build(&tree, &content.pages, self.config.base_path(), &lang_prefix);
+            let dir = self.output_dir.join(lang_prefix.trim_start_matches('/'));
+            fs::create_dir_all(&dir)?;
+            let json =
+                serde_json::to_string(&structure).map_err(|e| BuildError::Config(e.to_string()))?;
+            fs::write(dir.join(SITE_STRUCTURE_FILE), json)?;
+        }
+        Ok(())
+    }
+
+    /// The highlighter of `build.syntax_theme`, warning about unknown
+    /// themes and a syntaxes directory that fails to load.
+    fn syntax_highlighter(&self) -> SyntaxHighlighter {
+        let build = &self.config.build;
+        let highlighter = SyntaxHighlighter::try_from_config(build).unwrap_or_else(|e| {
+            self.warn(
+                WarningKind::Config,
+                Some("build.syntaxes_dir"),
+                e.to_string(),
+            );
+            SyntaxHighlighter::from_config(build)
+        });
+        for (key, theme) in [
+            ("build.syntax_theme", Some(&build.syntax_theme)),
+            ("build.syntax_theme_dark", build.syntax_theme_dark.as_ref()),
+        ] {
+            if let Some(theme) = theme
+                && theme != AUTO_THEME
+                && !highlighter.has_theme(theme)
+            {
+                self.warn(
+                    WarningKind::Config,
+                    Some(key),
+                    format!(
+                        "unknown syntax theme {theme:?}, expected {AUTO_THEME:?} or one of: {}",
+                        highlighter.available_themes().join(", ")
+                    ),
+                );
+            }
+        }
+        highlighter
+    }
+
+    /// Whether `page` belongs in the public search index and feeds (see
+    /// [`Config::is_public`]).
+    fn is_public(&self, page: &Page) -> bool {
+        self.config.is_public(page, Utc::now())
+    }
+
+    /// Simple search index of `pages` in the language `lang`, analyzed with
+    /// the language's stopwords and stemming (`search.stopwords`,
+    /// `search.stemming`).
+    fn search_index(&self, lang: &str, pages: &[&Page]) -> SimpleSearchIndex {
+        let search = &self.config.search;
+        SimpleSearchIndex::from_pages_with(
+            pages,
+            Analyzer::for_language(lang, search.stemming, &search.stopwords),
+        )
+    }
+
+    /// Search index JSON of each language small enough to embed in pages
+    /// (`search.inline_max_bytes`).
+    fn inline_search_indexes(&self, content: &SiteContent) -> Result<HashMap<String, String>> {
+        let max_bytes = self.config.search.inline_max_bytes;
+        let mut indexes = HashMap::new();
+        if !self.config.search.enabled || max_bytes == 0 {
+            return Ok(indexes);
+        }
+
+        for lang in self.config.all_languages() {
+            let lang_pages: Vec<_> = content
+                .pages
+                .values()
+                .filter(|p| p.lang == lang && self.is_public(p))
+                .collect();
+            if lang_pages.is_empty() {
+                continue;
+            }
+
+            let json = self
+                .search_index(lang, &lang_pages)
+                .to_json()
+                .map_err(|e| BuildError::Config(e.to_string()))?;
+            if json.len() <= max_bytes {
+                indexes.insert(lang.to_string(), json);
+            } else {
+                debug!(lang, bytes = json.len(), "search index too large to inline");
+            }
+        }
+        Ok(indexes)
+    }
+
+    /// Generate search indexes per language.
+    ///
+    /// Creates a `search-index.json` for default language at root,
+    /// and `/{lang}/search-index.json` for non-default languages, of the
+    /// public pages. Languages whose index is larger than
+    /// [`MAX_SIMPLE_INDEX_SIZE`] also get a chunked Tantivy index. With
+    /// `search.encrypted_preview`, an encrypted index of all pages is written
+    /// next to each.
+    fn generate_search_indexes(&self, content: &SiteContent) -> Result<()> {
+        let all_languages = self.config.all_languages();
+        let default_lang = &self.config.site.default_language;
+
+        let preview_key = if self.config.search.encrypted_preview {
+            if self.search_key.is_none() {
+                self.warn(
+                    WarningKind::Search,
+                    None,
+                    format!(
+                        "search.encrypted_preview is set but {SEARCH_KEY_ENV} is not; no preview index written"
+                    ),
+                );
+            }
+            self.search_key.as_deref()
+        } else {
+            None
+        };
+
+        for lang in &all_languages {
+            // Filter pages by language
+            let all_pages: Vec<_> = content.pages.values().filter(|p| p.lang == *lang).collect();
+            let lang_pages: Vec<_> = all_pages
+                .iter()
+                .copied()
+                .filter(|p| self.is_public(p))
+                .collect();
+
+            let dir = if *lang == default_lang.as_str() {
+                self.output_dir.clone()
+            } else {
+                self.output_dir.join(lang)
+            };
+            if !all_pages.is_empty() {
+                fs::create_dir_all(&dir)?;
+            }
+
+            if let Some(key) = preview_key
+                && !all_pages.is_empty()
+            {
+                let json = self
+                    .search_index(lang, &all_pages)
+                    .to_json()
+                    .and_then(|json| EncryptedIndex::seal(&json, key)?.to_json())
+                    .map_err(|e| BuildError::Config(e.to_string()))?;
+                let preview_path = dir.join(ENCRYPTED_INDEX_FILE);
+                fs::write(&preview_path, json)?;
+                info!(
+                    path = %preview_path.display(),
+                    lang = lang,
+                    documents = all_pages.len(),
+                    "generated encrypted preview search index"
+                );
+            }
+
+            if lang_pages.is_empty() {
+                continue;
             }
 
+            // Build simple search index
+            let index = self.search_index(lang, &lang_pages);
+            let output_path = dir.join("search-index.json");
+
             // Write the index
             index
                 .write_to_file(&output_path)
                 .map_err(|e| BuildError::Config(e.to_string()))?;
+            let size = fs::metadata(&output_path)?.len();
+            if size > MAX_SIMPLE_INDEX_SIZE as u64 {
+                self.generate_chunked_index(lang, &lang_pages, &dir)?;
+            }
 
             info!(
                 path = %output_path.display(),
@@ -694,10 +1691,606 @@ impl Builder {
         Ok(())
     }
 
-    /// Process static assets.
-    fn process_assets(&self, static_dir: &Path) -> Result<AssetManifest> {
-        let processor = AssetProcessor::new(self.config.build.minify);
-        let manifest = processor.process(static_dir, &self.output_dir)?;
+    /// Write a chunked Tantivy index of `pages`, in the language `lang`, to
+    /// `search/` in `dir` for the search runtime.
+    ///
+    /// The index is built in `build.cache_dir` and split into chunks of
+    /// `search.chunk_size` bytes.
+    fn generate_chunked_index(&self, lang: &str, pages: &[&Page], dir: &Path) -> Result<()> {
+        let search = &self.config.search;
+        let index_dir = Path::new(&self.config.build.cache_dir)
+            .join(CHUNKED_INDEX_DIR)
+            .join(lang);
+        // Start from an empty index, not the pages of the last build
+        if index_dir.exists() {
+            fs::remove_dir_all(&index_dir)?;
+        }
+        let output_dir = dir.join(CHUNKED_INDEX_DIR);
+        let config = IndexerConfig {
+            default_lang: lang.to_string(),
+            analyzer: Analyzer::for_language(lang, search.stemming, &search.stopwords),
+            ..IndexerConfig::default()
+        };
+        let chunker = IndexChunker::new(ChunkerConfig {
+            chunk_size: search.chunk_size,
+            ..ChunkerConfig::default()
+        });
+        let manifest = SearchIndexer::new(&index_dir, config)
+            .and_then(|indexer| {
+                indexer.index_pages(pages)?;
+                indexer.optimize()
+            })
+            .and_then(|()| chunker.chunk_directory(&index_dir, &output_dir))
+            .and_then(|manifest| {
+                IndexChunker::write_manifest(&manifest, &output_dir.join(INDEX_MANIFEST_FILE))?;
+                Ok(manifest)
+            })
+            .map_err(|e| BuildError::Config(e.to_string()))?;
+
+        info!(
+            path = %output_dir.display(),
+            lang,
+            documents = pages.len(),
+            bytes = manifest.total_size,
+            "generated chunked search index"
+        );
+        Ok(())
+    }
+
+    /// Languages with a chunked search index in the output directory.
+    fn chunked_search_langs(&self) -> HashSet<String> {
+        let default_lang = &self.config.site.default_language;
+        self.config
+            .all_languages()
+            .into_iter()
+            .filter(|lang| {
+                let dir = if *lang == default_lang.as_str() {
+                    self.output_dir.clone()
+                } else {
+                    self.output_dir.join(lang)
+                };
+                dir.join(CHUNKED_INDEX_DIR)
+                    .join(INDEX_MANIFEST_FILE)
+                    .is_file()
+            })
+            .map(str::to_string)
+            .collect()
+    }
+
+    /// Copy page-local CSS/JS referenced from frontmatter into the output
+    /// directory and point the page at the fingerprinted copies.
+    ///
+    /// References are resolved relative to the content file; absolute paths
+    /// and URLs are left untouched. Local media embedded in the content
+    /// (`<img>`, `<video>`, `<audio>`, `<source>`, `<track>`) is copied next
+    /// to the page's `index.html` so relative URLs keep working.
+    fn copy_page_assets(&self, content: &mut SiteContent) -> Result<usize> {
+        let processor =
+            AssetProcessor::new(true).with_processors(self.config.build.processors.clone());
+        let mut manifest = AssetManifest::new();
+        let mut media = 0;
+        let base_path = self.config.base_path();
+
+        for page in content.pages.values_mut() {
+            let Some(source_dir) = page.source_path.as_ref().and_then(|p| p.parent()) else {
+                continue;
+            };
+
+            for reference in page.custom_css.iter_mut().chain(page.custom_js.iter_mut()) {
+                if !is_local_reference(reference) {
+                    continue;
+                }
+                let Some(relative) = normalize_relative(&source_dir.join(&*reference)) else {
+                    self.warn(
+                        WarningKind::Asset,
+                        Some(&page.url),
+                        format!("page asset {reference} outside content directory"),
+                    );
+                    continue;
+                };
+                let file_path = self.content_dir.join(&relative);
+                if !file_path.is_file() {
+                    self.warn(
+                        WarningKind::Asset,
+                        Some(&page.url),
+                        format!("page asset {reference} not found"),
+                    );
+                    continue;
+                }
+
+                let key = format!("/{}", relative.display()).replace('\\', "/");
+                if manifest.get(&key).is_none() {
+                    processor.process_file(
+                        &self.content_dir,
+                        &file_path,
+                        &self.output_dir,
+                        &mut manifest,
+                    )?;
+                }
+                if let Some(output) = manifest.get(&key) {
+                    *reference = format!("{base_path}{output}");
+                }
+            }
+
+            let page_dir = self.output_dir.join(page.url.trim_start_matches('/'));
+            for reference in page_media(&page.content) {
+                let Some(relative) = normalize_relative(Path::new(&reference)) else {
+                    continue;
+                };
+                let file_path = self.content_dir.join(source_dir).join(&relative);
+                if !file_path.is_file() {
+                    self.warn(
+                        WarningKind::Asset,
+                        Some(&page.url),
+                        format!("page media {reference} not found"),
+                    );
+                    continue;
+                }
+                AssetProcessor::copy_file(&file_path, &page_dir.join(&relative))?;
+                media += 1;
+            }
+        }
+
+        Ok(manifest.assets().len() + media)
+    }
+
+    /// Publish the images attached to pages (`images` frontmatter) and
+    /// generate their gallery thumbnails. Relative attachments are copied
+    /// next to the page and their `src` made absolute, so galleries also
+    /// work on list pages. Returns the number of files written.
+    fn attach_images(&self, content: &mut SiteContent) -> Result<usize> {
+        let base_path = self.config.base_path();
+        let mut count = 0;
+
+        for page in content.pages.values_mut() {
+            let mut images = std::mem::take(&mut page.images);
+            for image in &mut images {
+                let paths = self.image_paths(page, &image.src);
+
+                if is_local_reference(&image.src) {
+                    let Some(relative) = normalize_relative(Path::new(&image.src)) else {
+                        self.warn(
+                            WarningKind::Asset,
+                            Some(&page.url),
+                            format!("attached image {} outside content directory", image.src),
+                        );
+                        continue;
+                    };
+                    let Some(source_dir) = page.source_path.as_ref().and_then(|p| p.parent())
+                    else {
+                        continue;
+                    };
+                    let file_path = self.content_dir.join(source_dir).join(&relative);
+                    if !file_path.is_file() {
+                        self.warn(
+                            WarningKind::Asset,
+                            Some(&page.url),
+                            format!("attached image {} not found", image.src),
+                        );
+                        continue;
+                    }
+                    AssetProcessor::copy_file(
+                        &file_path,
+                        &self
+                            .output_dir
+                            .join(page.url.trim_start_matches('/'))
+                            .join(&relative),
+                    )?;
+                    count += 1;
+                    image.src = format!(
+                        "{base_path}{}/{}",
+                        page.url.trim_end_matches('/'),
+                        relative.display()
+                    )
+                    .replace('\\', "/");
+                }
+
+                if let Some((source, dest_dir)) = paths {
+                    match ImagePipeline::thumbnail(&source, &dest_dir, THUMBNAIL_SIZE) {
+                        Ok(name) => {
+                            let url_dir = &image.src[..image.src.rfind('/').map_or(0, |i| i + 1)];
+                            image.thumbnail = Some(format!("{url_dir}{name}"));
+                            count += 1;
+                        }
+                        Err(e) => {
+                            self.warn(
+                                WarningKind::Asset,
+                                Some(&source.display().to_string()),
+                                format!("failed to generate thumbnail: {e}"),
+                            );
+                        }
+                    }
+                }
+            }
+            page.images = images;
+        }
+
+        Ok(count)
+    }
+
+    /// Replace `data` shortcode placeholders with their templates, rendered
+    /// with JSON fetched at build time. Failed fetches render nothing.
+    fn expand_data(&self, content: &mut SiteContent) -> Result<()> {
+        if !content
+            .pages
+            .values()
+            .any(|page| page.content.contains(data::MARKER))
+        {
+            return Ok(());
+        }
+
+        let templates = self.templates()?;
+        let fetcher = DataFetcher::new(
+            Path::new(&self.config.build.cache_dir).join(DATA_DIR),
+            Duration::from_secs(self.config.build.data_ttl),
+        );
+        for page in content.pages.values_mut() {
+            if !page.content.contains(data::MARKER) {
+                continue;
+            }
+            let expanded = data::expand(&page.content, |url, template| -> Result<String> {
+                let value = match fetcher.fetch(url) {
+                    Ok(value) => value,
+                    Err(e) => {
+                        self.warn(
+                            WarningKind::Data,
+                            Some(&page.url),
+                            format!("data shortcode not rendered: {e}"),
+                        );
+                        return Ok(String::new());
+                    }
+                };
+                Ok(templates
+                    .render(template, &data::context(&value))
+                    .map_err(HtmlError::from)?)
+            })?;
+            page.content = expanded;
+        }
+        Ok(())
+    }
+
+    /// Replace the Mermaid diagrams of pages with SVGs rendered by
+    /// `mermaid.command`. Pages whose diagrams fail to render keep them for
+    /// the Mermaid script.
+    fn prerender_diagrams(&self, content: &mut SiteContent) {
+        let prerenderer = Prerenderer::new(
+            &self.config.mermaid.command,
+            Path::new(&self.config.build.cache_dir).join(MERMAID_DIR),
+        );
+        for page in content.pages.values_mut() {
+            if !mermaid::has_diagrams(&page.content) {
+                continue;
+            }
+            match prerenderer.prerender(&page.content) {
+                Ok(html) => page.content = html,
+                Err(e) => self.warn(
+                    WarningKind::Page,
+                    Some(&page.url),
+                    format!("diagrams not pre-rendered: {e}"),
+                ),
+            }
+        }
+    }
+
+    /// Fill the `gallery` shortcode placeholders with the images of their
+    /// directories, copied next to the page with thumbnails. Returns the
+    /// number of files written.
+    fn expand_galleries(&self, content: &mut SiteContent) -> Result<usize> {
+        let base_path = self.config.base_path();
+        let mut count = 0;
+
+        for page in content.pages.values_mut() {
+            if !page.content.contains(gallery::MARKER) {
+                continue;
+            }
+            let Some(source_dir) = page.source_path.as_ref().and_then(|p| p.parent()) else {
+                continue;
+            };
+            let page_dir = self.output_dir.join(page.url.trim_start_matches('/'));
+            let page_url = format!("{base_path}{}", page.url.trim_end_matches('/'));
+
+            let expanded = gallery::expand(&page.content, |dir, exif| -> Result<String> {
+                let Some(relative) = normalize_relative(Path::new(dir)) else {
+                    self.warn(
+                        WarningKind::Asset,
+                        Some(&page.url),
+                        format!("gallery {dir} outside content directory"),
+                    );
+                    return Ok(String::new());
+                };
+                let files = match gallery::image_files(
+                    &self.content_dir.join(source_dir).join(&relative),
+                ) {
+                    Ok(files) => files,
+                    Err(e) => {
+                        self.warn(
+                            WarningKind::Asset,
+                            Some(&page.url),
+                            format!("gallery directory {dir} not readable: {e}"),
+                        );
+                        return Ok(String::new());
+                    }
+                };
+
+                let dest_dir = page_dir.join(&relative);
+                let url_dir = format!("{page_url}/{}", relative.display()).replace('\\', "/");
+                let mut images = Vec::with_capacity(files.len());
+                for file in files {
+                    let name = file.file_name().unwrap_or_default().to_string_lossy();
+                    AssetProcessor::copy_file(&file, &dest_dir.join(name.as_ref()))?;
+                    count += 1;
+
+                    let src = format!("{url_dir}/{name}");
+                    let thumbnail = match ImagePipeline::thumbnail(&file, &dest_dir, THUMBNAIL_SIZE)
+                    {
+                        Ok(thumb) => {
+                            count += 1;
+                            format!("{url_dir}/{thumb}")
+                        }
+                        Err(e) => {
+                            self.warn(
+                                WarningKind::Asset,
+                                Some(&file.display().to_string()),
+                                format!("failed to generate thumbnail: {e}"),
+                            );
+                            src.clone()
+                        }
+                    };
+                    let stem = file.file_stem().unwrap_or_default().to_string_lossy();
+                    let caption = if exif {
+                        gallery::exif_caption(&file)
+                    } else {
+                        None
+                    };
+                    images.push(GalleryImage {
+                        alt: caption
+                            .clone()
+                            .unwrap_or_else(|| stem.replace(['-', '_'], " ")),
+                        src,
+                        thumbnail,
+                        caption,
+                    });
+                }
+                Ok(gallery::grid_html(&images))
+            })?;
+            page.content = expanded;
+        }
+
+        Ok(count)
+    }
+
+    /// Fetch the `[content.remote]` sources and copy them into the content
+    /// tree.
+    fn sync_remote_content(&self) -> Result<()> {
+        let cache_dir = Path::new(&self.config.build.cache_dir);
+        for source in &self.config.content.remote {
+            let files = sources::sync(source, cache_dir, &self.content_dir)?;
+            info!(url = %source.url, target = %source.target, files, "synced remote content");
+        }
+        Ok(())
+    }
+
+    /// Point remote images at local copies cached in `static/remote/` and
+    /// publish the cache under `/remote/`.
+    fn localize_remote_images(&self, content: &mut SiteContent) -> Result<usize> {
+        let static_dir = self
+            .static_dir
+            .clone()
+            .unwrap_or_else(|| PathBuf::from("static"));
+        let cache_dir = static_dir.join(REMOTE_DIR);
+        let url_prefix = format!("{}/{REMOTE_DIR}", self.config.base_path());
+
+        let mut remote = RemoteImages::new(&cache_dir)?;
+        for page in content.pages.values_mut() {
+            page.content = remote.localize(&page.content, &url_prefix)?;
+            for (url, message) in remote.take_failed() {
+                self.warn(
+                    WarningKind::Asset,
+                    Some(&page.url),
+                    format!("remote image {url} not localized: {message}"),
+                );
+            }
+        }
+
+        // Copied directly so the URLs hold even when static files are
+        // fingerprinted or no static directory is configured
+        let mut count = 0;
+        if cache_dir.is_dir() {
+            for entry in fs::read_dir(&cache_dir)? {
+                let path = entry?.path();
+                if path.is_file()
+                    && let Some(name) = path.file_name()
+                {
+                    AssetProcessor::copy_file(&path, &self.output_dir.join(REMOTE_DIR).join(name))?;
+                    count += 1;
+                }
+            }
+        }
+
+        info!(count, "localized remote images");
+        Ok(count)
+    }
+
+    /// Generate resized variants of local images and wrap them in
+    /// `<picture>` elements. Returns the number of files written.
+    ///
+    /// Absolute sources resolve against the static directory, relative ones
+    /// against the content file. Images that fail to process are left as-is.
+    fn generate_responsive_images(&self, content: &mut SiteContent) -> usize {
+        let pipeline = ImagePipeline::new(&self.config.images);
+
+        // Collect every image first so they can be processed in parallel
+        let mut jobs = BTreeSet::new();
+        for page in content.pages.values() {
+            rewrite_img_tags(&page.content, |_, src| {
+                jobs.extend(self.image_paths(page, src));
+                None
+            });
+        }
+
+        let variants: HashMap<(PathBuf, PathBuf), ImageVariants> = jobs
+            .into_par_iter()
+            .filter_map(
+                |(source, dest_dir)| match pipeline.process(&source, &dest_dir) {
+                    Ok(variants) => Some(((source, dest_dir), variants)),
+                    Err(e) => {
+                        self.warn(
+                            WarningKind::Asset,
+                            Some(&source.display().to_string()),
+                            format!("failed to process image: {e}"),
+                        );
+                        None
+                    }
+                },
+            )
+            .collect();
+
+        for page in content.pages.values_mut() {
+            let html = rewrite_img_tags(&page.content, |tag, src| {
+                let key = self.image_paths(page, src)?;
+                let url_dir = &src[..src.rfind('/').map_or(0, |i| i + 1)];
+                Some(pipeline.picture_html(tag, url_dir, variants.get(&key)?))
+            });
+            page.content = html;
+        }
+
+        let files = variants
+            .values()
+            .map(|v| {
+                v.fallback.files.len() + v.sources.iter().map(|s| s.files.len()).sum::<usize>()
+            })
+            .sum();
+        info!(
+            images = variants.len(),
+            files, "generated responsive images"
+        );
+        files
+    }
+
+    /// Source file and output directory for an image referenced by `src`
+    /// on `page`, if it is a local image the pipeline can process.
+    fn image_paths(&self, page: &Page, src: &str) -> Option<(PathBuf, PathBuf)> {
+        let path = src.split(['?', '#']).next().unwrap_or(src);
+
+        let (source, dest) = if let Some(absolute) = path.strip_prefix('/') {
+            let base_path = self.config.base_path().trim_start_matches('/');
+            let relative = if base_path.is_empty() {
+                absolute
+            } else {
+                absolute.strip_prefix(base_path)?.strip_prefix('/')?
+            };
+            let relative = normalize_relative(Path::new(relative))?;
+            (
+                self.static_dir.as_ref()?.join(&relative),
+                self.output_dir.join(&relative),
+            )
+        } else if is_local_reference(path) {
+            let relative = normalize_relative(Path::new(path))?;
+            let source_dir = page.source_path.as_ref()?.parent()?;
+            (
+                self.content_dir.join(source_dir).join(&relative),
+                self.output_dir
+                    .join(page.url.trim_start_matches('/'))
+                    .join(&relative),
+            )
+        } else {
+            return None;
+        };
+
+        if !ImagePipeline::supports(&source) || !source.is_file() {
+            return None;
+        }
+        Some((source, dest.parent()?.to_path_buf()))
+    }
+
+    /// Replace the default stylesheet with the output of the configured CSS
+    /// pipeline, if any.
+    fn compile_stylesheet(&self) -> Result<()> {
+        let Some(command) = self.config.css.command(self.config.build.minify) else {
+            return Ok(());
+        };
+
+        let input = Path::new(&self.config.css.input);
+        if !input.exists() {
+            return Err(BuildError::Config(format!(
+                "css.input not found: {}",
+                input.display()
+            )));
+        }
+
+        let output = self.output_dir.join("assets/style.css");
+        fs::remove_file(&output)?;
+        run_command(&command, input, &output)?;
+
+        info!(input = %input.display(), "compiled stylesheet");
+        Ok(())
+    }
+
+    /// The configured theme, if any.
+    fn theme(&self) -> Result<Option<Theme>> {
+        let Some(name) = &self.config.theme else {
+            return Ok(None);
+        };
+        let theme = Theme::find(&self.themes_dir, name)?;
+        debug!(theme = theme.name(), "using theme");
+        Ok(Some(theme))
+    }
+
+    /// Templates resolved site → theme → built-in.
+    fn templates(&self) -> Result<TemplateRegistry> {
+        let mut templates = TemplateRegistry::new();
+        if let Some(theme) = self.theme()? {
+            templates.load_dir(&theme.templates_dir())?;
+        }
+        if let Some(dir) = &self.templates_dir {
+            templates.load_dir(dir)?;
+        }
+        Ok(templates)
+    }
+
+    /// Shortcode templates resolved site → theme.
+    fn shortcodes(&self, theme: Option<&Theme>) -> Result<ShortcodeTemplates> {
+        let dirs: Vec<PathBuf> = theme
+            .map(Theme::templates_dir)
+            .into_iter()
+            .chain(self.templates_dir.clone())
+            .collect();
+        Ok(ShortcodeTemplates::load(&dirs)?)
+    }
+
+    /// An HTML generator using the resolved templates, with `content`
+    /// available to Jinja templates.
+    fn html_generator(&self, content: &SiteContent) -> Result<HtmlGenerator> {
+        #[cfg_attr(not(feature = "minijinja"), allow(unused_mut))]
+        let mut templates = self.templates()?;
+        #[cfg(feature = "minijinja")]
+        templates.set_site(&self.config, content);
+        Ok(
+            HtmlGenerator::with_templates(self.config.clone(), templates)
+                .with_taxonomies(&content.taxonomies)
+                .with_series(content)
+                .with_section_meta(content.section_meta.clone())
+                .with_chunked_search(self.chunked_search_langs()),
+        )
+    }
+
+    /// Process static assets. Files of later directories replace those of
+    /// earlier ones at the same path.
+    fn process_assets(&self, static_dirs: &[PathBuf]) -> Result<AssetManifest> {
+        let processor = AssetProcessor::new(self.config.build.minify)
+            .with_processors(self.config.build.processors.clone())
+            .with_metadata_stripping(
+                self.config.build.strip_metadata,
+                &self.config.build.keep_metadata,
+            );
+        let mut manifest = AssetManifest::new();
+        for static_dir in static_dirs {
+            for (original, fingerprinted) in
+                processor.process(static_dir, &self.output_dir)?.assets()
+            {
+                manifest.add(original.clone(), fingerprinted.clone());
+            }
+        }
 
         // Write manifest
         let manifest_path = self.output_dir.join("asset-manifest.json");
@@ -707,6 +2300,95 @@ impl Builder {
     }
 }
 
+/// Whether a frontmatter asset reference points at a file next to the content.
+fn is_local_reference(reference: &str) -> bool {
+    !reference.is_empty()
+        && !reference.starts_with('/')
+        && !reference.contains("://")
+        && !reference.starts_with("data:")
+}
+
+/// Local media files referenced from rendered page content.
+fn page_media(html: &str) -> BTreeSet<String> {
+    scan_html(html)
+        .iter()
+        .filter(|token| {
+            matches!(token, HtmlToken::Start { name, .. }
+                if matches!(name.as_str(), "img" | "video" | "audio" | "source" | "track"))
+        })
+        .flat_map(|token| [token.attr("src"), token.attr("poster")])
+        .flatten()
+        .map(|src| src.split(['?', '#']).next().unwrap_or(src))
+        .filter(|src| is_local_reference(src))
+        .map(str::to_string)
+        .collect()
+}
+
+/// Resolve `.` and `..` components, rejecting paths that escape the root.
+fn normalize_relative(path: &Path) -> Option<PathBuf> {
+    let mut normalized = PathBuf::new();
+    for component in path.components() {
+        match component {
+            std::path::Component::Normal(part) => normalized.push(part),
+            std::path::Component::CurDir => {}
+            std::path::Component::ParentDir => {
+                if !normalized.pop() {
+                    return None;
+                }
+            }
+            _ => return None,
+        }
+    }
+    Some(normalized)
+}
+
+/// Peak resident set size of the process in bytes, read from
+/// `/proc/self/status` where available.
+fn peak_rss_bytes() -> Option<u64> {
+    parse_peak_rss(&fs::read_to_string("/proc/self/status").ok()?)
+}
+
+/// The `VmHWM` (peak resident set size) line of a `/proc/*/status` file, in
+/// bytes.
+fn parse_peak_rss(status: &str) -> Option<u64> {
+    let kb = status
+        .lines()
+        .find_map(|line| line.strip_prefix("VmHWM:"))?
+        .trim()
+        .strip_suffix("kB")?
+        .trim()
+        .parse::<u64>()
+        .ok()?;
+    Some(kb * 1024)
+}
+
+/// Per-page timings from the parse and render times of each page URL,
+/// slowest first.
+fn page_timings(
+    parse_times: &HashMap<String, Duration>,
+    render_times: &HashMap<String, Duration>,
+) -> Vec<PageTiming> {
+    let ms = |times: &HashMap<String, Duration>, url: &str| {
+        times
+            .get(url)
+            .map_or(0.0, |elapsed| elapsed.as_secs_f64() * 1000.0)
+    };
+    let mut timings: Vec<PageTiming> = render_times
+        .keys()
+        .map(|url| PageTiming {
+            url: url.clone(),
+            parse_ms: ms(parse_times, url),
+            render_ms: ms(render_times, url),
+        })
+        .collect();
+    timings.sort_by(|a, b| {
+        b.total_ms()
+            .total_cmp(&a.total_ms())
+            .then_with(|| a.url.cmp(&b.url))
+    });
+    timings
+}
+
 #[cfg(test)]
 mod tests {
     use std::collections::HashMap;
@@ -714,9 +2396,14 @@ mod tests {
     use tempfile::TempDir;
 
     use super::*;
-
-    fn test_config() -> Config {
-        Config {
+    use crate::cache::RENDERED_DIR;
+
+    /// A test configuration, with `build.cache_dir` in the returned
+    /// directory so the build manifest stays out of the working directory.
+    fn test_config() -> (Config, TempDir) {
+        let cache_dir = TempDir::new().unwrap();
+        let config = Config {
+            theme: None,
             site: typstify_core::config::SiteConfig {
                 title: "Test Site".to_string(),
                 host: "https://example.com".to_string(),
@@ -724,17 +2411,38 @@ mod tests {
                 default_language: "en".to_string(),
                 description: None,
                 author: None,
+                timezone: None,
             },
             languages: HashMap::new(),
-            build: typstify_core::config::BuildConfig::default(),
+            build: typstify_core::config::BuildConfig {
+                cache_dir: cache_dir.path().to_string_lossy().into_owned(),
+                ..Default::default()
+            },
             search: typstify_core::config::SearchConfig::default(),
             rss: typstify_core::config::RssConfig {
                 enabled: true,
                 limit: 20,
+                exclude_pinned: false,
             },
             robots: typstify_core::config::RobotsConfig::default(),
             taxonomies: typstify_core::config::TaxonomyConfig::default(),
-        }
+            dates: typstify_core::config::DatesConfig::default(),
+            css: typstify_core::config::CssConfig::default(),
+            images: typstify_core::config::ImagesConfig::default(),
+            markdown: typstify_core::config::MarkdownConfig::default(),
+            typography: typstify_core::config::TypographyConfig::default(),
+            sections: std::collections::HashMap::new(),
+            check: typstify_core::config::CheckConfig::default(),
+            webmentions: typstify_core::config::WebmentionsConfig::default(),
+            identity: typstify_core::config::IdentityConfig::default(),
+            content: typstify_core::config::ContentConfig::default(),
+            ping: typstify_core::config::PingConfig::default(),
+            toc: typstify_core::config::TocConfig::default(),
+            math: typstify_core::config::MathConfig::default(),
+            mermaid: typstify_core::config::MermaidConfig::default(),
+            fmt: typstify_core::config::FmtConfig::default(),
+        };
+        (config, cache_dir)
     }
 
     #[test]
@@ -742,13 +2450,19 @@ mod tests {
         let content_dir = TempDir::new().unwrap();
         let output_dir = TempDir::new().unwrap();
 
-        let builder = Builder::new(test_config(), content_dir.path(), output_dir.path());
+        let (config, cache_dir) = test_config();
+        let builder = Builder::new(config, content_dir.path(), output_dir.path());
 
         let stats = builder.build().unwrap();
 
         assert_eq!(stats.pages, 0);
+        assert!(stats.warnings.is_empty());
         assert!(output_dir.path().join("sitemap.xml").exists());
         assert!(output_dir.path().join("rss.xml").exists());
+        // The manifest is kept with the cache, not published
+        assert!(cache_dir.path().join(MANIFEST_FILE).exists());
+        assert!(!output_dir.path().join(MANIFEST_FILE).exists());
+        assert!(!output_dir.path().join(LINK_GRAPH_FILE).exists());
     }
 
     #[test]
@@ -776,7 +2490,8 @@ Hello, world!
         // Verify file was created
         assert!(post_path.exists());
 
-        let builder = Builder::new(test_config(), content_dir.path(), output_dir.path());
+        let (config, _cache_dir) = test_config();
+        let builder = Builder::new(config, content_dir.path(), output_dir.path());
 
         let stats = builder.build().unwrap();
 
@@ -804,6 +2519,142 @@ Hello, world!
         assert!(tags_web.exists(), "tags/web should exist");
     }
 
+    #[test]
+    fn test_build_taxonomy_feeds() {
+        let content_dir = TempDir::new().unwrap();
+        let output_dir = TempDir::new().unwrap();
+
+        fs::write(
+            content_dir.path().join("post.md"),
+            "---\ntitle: \"Post\"\ndate: 2026-01-14T00:00:00Z\ntags:\n  - rust\n---\n\nBody\n",
+        )
+        .unwrap();
+
+        let (mut config, _cache_dir) = test_config();
+        config.taxonomies.tags.feed = true;
+        Builder::new(config, content_dir.path(), output_dir.path())
+            .build()
+            .unwrap();
+
+        let feed = fs::read_to_string(output_dir.path().join("tags/rust/rss.xml")).unwrap();
+        assert!(feed.contains("<title>Post</title>"));
+
+        let term_page = fs::read_to_string(output_dir.path().join("tags/rust/index.html")).unwrap();
+        assert!(
+            term_page.contains(r#"type="application/rss+xml" title="Test Site" href="/rss.xml""#)
+        );
+        assert!(term_page.contains(r#"href="/tags/rust/rss.xml""#));
+
+        let post = fs::read_to_string(output_dir.path().join("post/index.html")).unwrap();
+        assert!(post.contains(r#"href="/rss.xml""#));
+        assert!(!post.contains("/tags/rust/rss.xml"));
+    }
+
+    #[test]
+    fn test_build_taxonomy_min_pages() {
+        let content_dir = TempDir::new().unwrap();
+        let output_dir = TempDir::new().unwrap();
+
+        for (name, tags) in [("a", "[rust, misc]"), ("b", "[rust]")] {
+            fs::write(
+                content_dir.path().join(format!("{name}.md")),
+                format!("---\ntitle: \"{name}\"\ndate: 2026-01-14T00:00:00Z\ntags: {tags}\n---\n\nBody\n"),
+            )
+            .unwrap();
+        }
+
+        let (mut config, _cache_dir) = test_config();
+        config.taxonomies.tags.min_pages = 2;
+        Builder::new(config, content_dir.path(), output_dir.path())
+            .build()
+            .unwrap();
+
+        assert!(output_dir.path().join("tags/rust/index.html").exists());
+        assert!(!output_dir.path().join("tags/misc").exists());
+
+        let index = fs::read_to_string(output_dir.path().join("tags/index.html")).unwrap();
+        assert!(index.contains(r#"href="/tags/rust" class="tag-item""#));
+        assert!(index.contains(r#"<span class="tag-item"><span class="tag-name">misc</span>"#));
+        let post = fs::read_to_string(output_dir.path().join("a/index.html")).unwrap();
+        assert!(post.contains(r#"<a href="/tags/rust" rel="tag">rust</a> <span>misc</span>"#));
+    }
+
+    #[test]
+    fn test_build_series() {
+        let content_dir = TempDir::new().unwrap();
+        let output_dir = TempDir::new().unwrap();
+
+        for (name, day) in [("part-two", 2), ("part-one", 1)] {
+            fs::write(
+                content_dir.path().join(format!("{name}.md")),
+                format!("---\ntitle: \"{name}\"\ndate: 2026-01-0{day}T00:00:00Z\nseries: Rust Basics\n---\n\nBody\n"),
+            )
+            .unwrap();
+        }
+
+        let (config, _cache_dir) = test_config();
+        Builder::new(config, content_dir.path(), output_dir.path())
+            .build()
+            .unwrap();
+
+        let post = fs::read_to_string(output_dir.path().join("part-two/index.html")).unwrap();
+        assert!(post.contains(r#"Part 2 of 2 in <a href="/series/rust-basics">Rust Basics</a>"#));
+        assert!(post.contains(
+            r#"<li><a href="/part-one">part-one</a></li><li aria-current="page">part-two</li>"#
+        ));
+
+        let index =
+            fs::read_to_string(output_dir.path().join("series/rust-basics/index.html")).unwrap();
+        let one = index.find(r#"href="/part-one""#).unwrap();
+        let two = index.find(r#"href="/part-two""#).unwrap();
+        assert!(one < two);
+    }
+
+    #[test]
+    fn test_parse_peak_rss() {
+        let status =
+            "Name:\ttypstify\nVmPeak:\t  300000 kB\nVmHWM:\t   12345 kB\nVmRSS:\t   10000 kB\n";
+        assert_eq!(parse_peak_rss(status), Some(12345 * 1024));
+        assert_eq!(parse_peak_rss("Name:\ttypstify\n"), None);
+    }
+
+    #[test]
+    fn test_page_timings() {
+        let parse_times = HashMap::from([
+            ("/a".to_string(), Duration::from_millis(5)),
+            ("/b".to_string(), Duration::from_millis(40)),
+        ]);
+        let render_times = HashMap::from([
+            ("/a".to_string(), Duration::from_millis(10)),
+            ("/b".to_string(), Duration::from_millis(2)),
+        ]);
+
+        let timings = page_timings(&parse_times, &render_times);
+        assert_eq!(timings.len(), 2);
+        assert_eq!(timings[0].url, "/b");
+        assert!((timings[0].parse_ms - 40.0).abs() < 1e-9);
+        assert!((timings[1].total_ms() - 15.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_build_timings() {
+        let content_dir = TempDir::new().unwrap();
+        let output_dir = TempDir::new().unwrap();
+        fs::write(
+            content_dir.path().join("hello.md"),
+            "---\ntitle: Hello\n---\n\nBody\n",
+        )
+        .unwrap();
+
+        let (config, _cache_dir) = test_config();
+        let builder = Builder::new(config, content_dir.path(), output_dir.path());
+        assert!(builder.build().unwrap().timings.is_empty());
+
+        let stats = builder.with_timings(true).build().unwrap();
+        assert_eq!(stats.timings.len(), 1);
+        assert_eq!(stats.timings[0].url, "/hello");
+    }
+
     #[test]
     fn test_build_stats() {
         let stats = BuildStats::default();
@@ -820,7 +2671,8 @@ Hello, world!
         // Create a static file
         fs::write(static_dir.path().join("style.css"), "body {}").unwrap();
 
-        let builder = Builder::new(test_config(), content_dir.path(), output_dir.path())
+        let (config, _cache_dir) = test_config();
+        let builder = Builder::new(config, content_dir.path(), output_dir.path())
             .with_static_dir(static_dir.path());
 
         let stats = builder.build().unwrap();
@@ -828,4 +2680,1316 @@ Hello, world!
         assert_eq!(stats.assets, 1);
         assert!(output_dir.path().join("style.css").exists());
     }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_build_css_pipeline() {
+        let content_dir = TempDir::new().unwrap();
+        let output_dir = TempDir::new().unwrap();
+        let style_dir = TempDir::new().unwrap();
+
+        let input = style_dir.path().join("input.css");
+        fs::write(&input, "@tailwind base;").unwrap();
+
+        let (mut config, _cache_dir) = test_config();
+        config.css.pipeline = typstify_core::config::CssPipeline::Tailwind;
+        config.css.input = input.to_string_lossy().into_owned();
+        config.css.command = Some("cp {input} {output}".to_string());
+
+        Builder::new(config.clone(), content_dir.path(), output_dir.path())
+            .build()
+            .unwrap();
+        let css = fs::read_to_string(output_dir.path().join("assets/style.css")).unwrap();
+        assert_eq!(css, "@tailwind base;");
+
+        config.css.input = style_dir
+            .path()
+            .join("missing.css")
+            .to_string_lossy()
+            .into_owned();
+        let result = Builder::new(config, content_dir.path(), output_dir.path()).build();
+        assert!(matches!(result, Err(BuildError::Config(_))));
+    }
+
+    #[test]
+    fn test_build_page_assets() {
+        let content_dir = TempDir::new().unwrap();
+        let output_dir = TempDir::new().unwrap();
+
+        fs::create_dir_all(content_dir.path().join("posts")).unwrap();
+        fs::create_dir_all(content_dir.path().join("shared")).unwrap();
+        fs::write(content_dir.path().join("posts/chart.css"), ".chart {}").unwrap();
+        fs::write(content_dir.path().join("shared/chart.js"), "draw();").unwrap();
+        fs::write(
+            content_dir.path().join("posts/hello.md"),
+            r#"---
+title: "Hello"
+custom_css:
+  - chart.css
+  - /assets/site.css
+custom_js:
+  - ../shared/chart.js
+  - https://cdn.example.com/lib.js
+  - ../../outside.js
+---
+
+Hello
+"#,
+        )
+        .unwrap();
+
+        let (config, _cache_dir) = test_config();
+        let stats = Builder::new(config, content_dir.path(), output_dir.path())
+            .build()
+            .unwrap();
+        assert_eq!(stats.assets, 2);
+
+        let html = fs::read_to_string(output_dir.path().join("posts/hello/index.html")).unwrap();
+        let css_href = html
+            .split(r#"<link rel="stylesheet" href=""#)
+            .find_map(|s| s.strip_prefix("/posts/chart."))
+            .and_then(|s| s.split('"').next())
+            .unwrap();
+        assert!(css_href.ends_with(".css"));
+        assert!(
+            output_dir
+                .path()
+                .join(format!("posts/chart.{css_href}"))
+                .exists()
+        );
+        assert!(html.contains(r#"<script src="/shared/chart."#));
+        assert!(html.contains(r#"href="/assets/site.css""#));
+        assert!(html.contains(r#"src="https://cdn.example.com/lib.js""#));
+        assert!(html.contains(r#"src="../../outside.js""#));
+    }
+
+    #[test]
+    fn test_build_warnings() {
+        let content_dir = TempDir::new().unwrap();
+        let output_dir = TempDir::new().unwrap();
+
+        fs::write(
+            content_dir.path().join("hello.md"),
+            "---\ntitle: \"Hello\"\ncustom_css:\n  - missing.css\n---\n\nHello\n",
+        )
+        .unwrap();
+        fs::write(
+            content_dir.path().join("broken.md"),
+            "---\ntitle: [unclosed\n---\n\nBroken\n",
+        )
+        .unwrap();
+
+        let (config, _cache_dir) = test_config();
+        let builder = Builder::new(config, content_dir.path(), output_dir.path());
+        let stats = builder.build().unwrap();
+
+        assert_eq!(stats.pages, 1);
+        let kinds: Vec<_> = stats.warnings.iter().map(|w| w.kind).collect();
+        assert_eq!(kinds, vec![WarningKind::Parse, WarningKind::Asset]);
+        assert!(
+            stats.warnings[0]
+                .location
+                .as_deref()
+                .is_some_and(|location| location.ends_with("broken.md"))
+        );
+        assert_eq!(
+            stats.warnings[1].to_string(),
+            "/hello: page asset missing.css not found"
+        );
+
+        // Warnings are per build
+        fs::remove_file(content_dir.path().join("broken.md")).unwrap();
+        assert_eq!(builder.build().unwrap().warnings.len(), 1);
+    }
+
+    #[test]
+    fn test_build_fail_on_error() {
+        let content_dir = TempDir::new().unwrap();
+        let output_dir = TempDir::new().unwrap();
+        fs::write(
+            content_dir.path().join("hello.md"),
+            "---\ntitle: Hello\n---\n\nHello\n",
+        )
+        .unwrap();
+        fs::write(
+            content_dir.path().join("broken.md"),
+            "---\ntitle: [unclosed\n---\n\nBroken\n",
+        )
+        .unwrap();
+
+        let (mut config, _cache_dir) = test_config();
+        config.build.fail_on_error = true;
+        let builder = Builder::new(config, content_dir.path(), output_dir.path());
+
+        assert!(matches!(
+            builder.build(),
+            Err(BuildError::Page { location, .. }) if location.ends_with("broken.md")
+        ));
+        assert!(!output_dir.path().join("hello/index.html").exists());
+    }
+
+    #[test]
+    fn test_build_nested_sections() {
+        let content_dir = TempDir::new().unwrap();
+        let output_dir = TempDir::new().unwrap();
+
+        fs::create_dir_all(content_dir.path().join("docs/guides")).unwrap();
+        for (path, title) in [
+            ("about.md", "About me"),
+            ("docs/intro.md", "Intro"),
+            ("docs/guides/install.md", "Install"),
+        ] {
+            fs::write(
+                content_dir.path().join(path),
+                format!("---\ntitle: \"{title}\"\n---\n\nBody\n"),
+            )
+            .unwrap();
+        }
+
+        let (config, _cache_dir) = test_config();
+        Builder::new(config, content_dir.path(), output_dir.path())
+            .build()
+            .unwrap();
+
+        let docs = fs::read_to_string(output_dir.path().join("docs/index.html")).unwrap();
+        assert!(docs.contains(r#"<li><a href="/docs/guides">Guides</a></li>"#));
+        assert!(docs.contains("Intro"));
+        assert!(!docs.contains("Install"));
+
+        let guides = fs::read_to_string(output_dir.path().join("docs/guides/index.html")).unwrap();
+        assert!(guides.contains("Install"));
+        assert!(
+            guides.contains(
+                r#"<li><a href="/docs">Docs</a></li><li aria-current="page">Guides</li>"#
+            )
+        );
+
+        // Top-level pages are not sections
+        let about = fs::read_to_string(output_dir.path().join("about/index.html")).unwrap();
+        assert!(about.contains("About me"));
+        assert!(!about.contains("section-list"));
+
+        let structure: serde_json::Value = serde_json::from_str(
+            &fs::read_to_string(output_dir.path().join(SITE_STRUCTURE_FILE)).unwrap(),
+        )
+        .unwrap();
+        assert_eq!(structure["home"], "/");
+        assert_eq!(structure["sections"]["docs/guides"]["url"], "/docs/guides");
+        assert_eq!(structure["pages"]["/docs/guides/install"], "Install");
+    }
+
+    #[test]
+    fn test_build_section_index_files() {
+        let content_dir = TempDir::new().unwrap();
+        let output_dir = TempDir::new().unwrap();
+        let templates_dir = TempDir::new().unwrap();
+
+        fs::create_dir_all(content_dir.path().join("docs/guide/advanced")).unwrap();
+        fs::create_dir_all(content_dir.path().join("docs/api")).unwrap();
+        for (path, source) in [
+            ("docs/api/core.md", "---\ntitle: Core\n---\n\nBody\n"),
+            (
+                "docs/guide/advanced/tuning.md",
+                "---\ntitle: Tuning\n---\n\nBody\n",
+            ),
+            (
+                "docs/guide/_index.md",
+                "---\ntitle: User Guide\ndescription: Start here\nweight: -1\n---\n",
+            ),
+            (
+                "docs/guide/advanced/_index.md",
+                "---\ntitle: Advanced\ntemplate: chapter\n---\n",
+            ),
+        ] {
+            fs::write(content_dir.path().join(path), source).unwrap();
+        }
+        fs::write(
+            templates_dir.path().join("chapter.html"),
+            "<div class=\"chapter\">{{ title }}</div>{{ items | safe }}",
+        )
+        .unwrap();
+
+        let (config, _cache_dir) = test_config();
+        Builder::new(config, content_dir.path(), output_dir.path())
+            .with_templates_dir(templates_dir.path())
+            .build()
+            .unwrap();
+
+        // `_index` files are not pages
+        assert!(!output_dir.path().join("docs/guide/_index").exists());
+
+        // Weighted subsections come first, with their `_index` titles
+        let docs = fs::read_to_string(output_dir.path().join("docs/index.html")).unwrap();
+        assert!(docs.contains(
+            r#"<li><a href="/docs/guide">User Guide</a></li><li><a href="/docs/api">Api</a></li>"#
+        ));
+
+        // A section with only subsections still gets an index page
+        let guide = fs::read_to_string(output_dir.path().join("docs/guide/index.html")).unwrap();
+        assert!(guide.contains("Start here"));
+        assert!(guide.contains(r#"<li><a href="/docs/guide/advanced">Advanced</a></li>"#));
+
+        let advanced =
+            fs::read_to_string(output_dir.path().join("docs/guide/advanced/index.html")).unwrap();
+        assert!(advanced.contains(r#"<div class="chapter">Advanced</div>"#));
+
+        let tuning = fs::read_to_string(
+            output_dir
+                .path()
+                .join("docs/guide/advanced/tuning/index.html"),
+        )
+        .unwrap();
+        assert!(tuning.contains(r#"<li><a href="/docs/guide">User Guide</a></li>"#));
+        assert!(tuning.contains(r#""name":"User Guide""#));
+    }
+
+    #[test]
+    fn test_build_redirects_moved_content() {
+        let content_dir = TempDir::new().unwrap();
+        let output_dir = TempDir::new().unwrap();
+        let source = "---\ntitle: \"Hello\"\ncontent_id: hello-1\n---\n\nBody\n";
+
+        fs::create_dir_all(content_dir.path().join("posts")).unwrap();
+        fs::write(content_dir.path().join("posts/hello.md"), source).unwrap();
+        let (config, _cache_dir) = test_config();
+        let builder = Builder::new(config, content_dir.path(), output_dir.path());
+        assert_eq!(builder.build().unwrap().redirects, 0);
+
+        fs::rename(
+            content_dir.path().join("posts/hello.md"),
+            content_dir.path().join("posts/hello-world.md"),
+        )
+        .unwrap();
+        assert_eq!(builder.build().unwrap().redirects, 1);
+        let redirect =
+            fs::read_to_string(output_dir.path().join("posts/hello/index.html")).unwrap();
+        assert!(redirect.contains("https://example.com/posts/hello-world"));
+
+        // The redirect survives later builds
+        assert_eq!(builder.build().unwrap().redirects, 1);
+    }
+
+    #[test]
+    fn test_build_pinned_posts() {
+        let content_dir = TempDir::new().unwrap();
+        let output_dir = TempDir::new().unwrap();
+
+        fs::create_dir_all(content_dir.path().join("posts")).unwrap();
+        for (name, date, extra) in [
+            ("old", "2024-01-01", "pinned: true\n"),
+            ("mid", "2024-02-01", "weight: 1\n"),
+            ("new", "2024-03-01", ""),
+        ] {
+            fs::write(
+                content_dir.path().join(format!("posts/{name}.md")),
+                format!("---\ntitle: \"{name}\"\ndate: {date}\n{extra}---\n\nBody\n"),
+            )
+            .unwrap();
+        }
+
+        let (mut config, _cache_dir) = test_config();
+        config.rss.exclude_pinned = true;
+        Builder::new(config, content_dir.path(), output_dir.path())
+            .build()
+            .unwrap();
+
+        let index = fs::read_to_string(output_dir.path().join("posts/index.html")).unwrap();
+        let position = |name: &str| index.find(&format!(r#"href="/posts/{name}""#)).unwrap();
+        assert!(position("old") < position("new"));
+        assert!(position("new") < position("mid"));
+
+        let rss = fs::read_to_string(output_dir.path().join("rss.xml")).unwrap();
+        assert!(rss.contains("/posts/new"));
+        assert!(!rss.contains("/posts/old"));
+    }
+
+    #[test]
+    fn test_build_webmentions() {
+        let content_dir = TempDir::new().unwrap();
+        let output_dir = TempDir::new().unwrap();
+        let data_dir = TempDir::new().unwrap();
+
+        fs::create_dir_all(content_dir.path().join("posts")).unwrap();
+        fs::write(
+            content_dir.path().join("posts/hello.md"),
+            "---\ntitle: \"Hello\"\ndate: 2024-01-01\n---\n\nBody\n",
+        )
+        .unwrap();
+        let data = data_dir.path().join("webmentions.json");
+        fs::write(
+            &data,
+            r#"{"type": "feed", "children": [{"author": {"name": "Bob"}, "wm-target": "https://example.com/posts/hello/", "wm-property": "in-reply-to", "content": {"text": "Nice"}}]}"#,
+        )
+        .unwrap();
+
+        let (mut config, _cache_dir) = test_config();
+        config.webmentions.endpoint =
+            Some("https://webmention.io/example.com/webmention".to_string());
+        config.webmentions.data = Some(data.to_string_lossy().into_owned());
+        Builder::new(config, content_dir.path(), output_dir.path())
+            .build()
+            .unwrap();
+
+        let post = fs::read_to_string(output_dir.path().join("posts/hello/index.html")).unwrap();
+        assert!(post.contains(
+            r#"<link rel="webmention" href="https://webmention.io/example.com/webmention">"#
+        ));
+        assert!(post.contains(r#"<section class="webmentions""#));
+        assert!(post.contains("<p>Nice</p>"));
+
+        let index = fs::read_to_string(output_dir.path().join("posts/index.html")).unwrap();
+        assert!(index.contains(r#"rel="webmention""#));
+        assert!(!index.contains(r#"<section class="webmentions""#));
+    }
+
+    #[test]
+    fn test_build_changelog() {
+        let content_dir = TempDir::new().unwrap();
+        let output_dir = TempDir::new().unwrap();
+
+        fs::create_dir_all(content_dir.path().join("posts")).unwrap();
+        fs::write(
+            content_dir.path().join("posts/hello.md"),
+            "---\ntitle: \"Hello\"\ndate: 2024-01-01\n---\n\nBody\n",
+        )
+        .unwrap();
+        let git = |args: &[&str]| {
+            let status = std::process::Command::new("git")
+                .arg("-C")
+                .arg(content_dir.path())
+                .args([
+                    "-c",
+                    "user.name=Alice",
+                    "-c",
+                    "user.email=alice@example.com",
+                ])
+                .args(args)
+                .env("GIT_AUTHOR_DATE", "2024-03-05T10:00:00+00:00")
+                .output()
+                .unwrap()
+                .status;
+            assert!(status.success(), "git {args:?}");
+        };
+        git(&["init", "-q"]);
+        git(&["add", "."]);
+        git(&["commit", "-q", "-m", "Add hello post"]);
+
+        let (mut config, _cache_dir) = test_config();
+        config.build.changelog = true;
+        Builder::new(config, content_dir.path(), output_dir.path())
+            .build()
+            .unwrap();
+
+        let changelog = fs::read_to_string(output_dir.path().join("changelog/index.html")).unwrap();
+        assert!(changelog.contains("<h2>March 2024</h2>"));
+        assert!(changelog.contains(r#"<span class="changelog-subject">Add hello post</span>"#));
+        assert!(changelog.contains(r#"<a href="/posts/hello">Hello</a>"#));
+    }
+
+    #[test]
+    fn test_build_gallery() {
+        let content_dir = TempDir::new().unwrap();
+        let output_dir = TempDir::new().unwrap();
+
+        let photos = content_dir.path().join("posts/photos");
+        fs::create_dir_all(&photos).unwrap();
+        image::RgbImage::new(400, 300)
+            .save(photos.join("b-lake.jpg"))
+            .unwrap();
+        image::RgbaImage::new(40, 40)
+            .save(photos.join("a-hill.png"))
+            .unwrap();
+        fs::write(photos.join("notes.txt"), "skipped").unwrap();
+        fs::write(
+            content_dir.path().join("posts/trip.md"),
+            "---\ntitle: \"Trip\"\n---\n\n{{< gallery photos caption=\"Summer\" >}}\n",
+        )
+        .unwrap();
+
+        let (config, _cache_dir) = test_config();
+        Builder::new(config, content_dir.path(), output_dir.path())
+            .build()
+            .unwrap();
+
+        let out = output_dir.path().join("posts/trip");
+        assert!(out.join("photos/a-hill.png").is_file());
+        assert!(out.join("photos/b-lake-thumb.jpg").is_file());
+        assert!(!out.join("photos/notes.txt").exists());
+
+        let html = fs::read_to_string(out.join("index.html")).unwrap();
+        assert!(html.contains(
+            r#"<figure class="gallery" data-gallery="photos"><div class="gallery-grid"><a class="gallery-item" href="/posts/trip/photos/a-hill.png"><img src="/posts/trip/photos/a-hill-thumb.png" alt="a hill""#
+        ));
+        assert!(html.contains(r#"href="/posts/trip/photos/b-lake.jpg""#));
+        assert!(html.contains("</div><figcaption>Summer</figcaption></figure>"));
+    }
+
+    #[test]
+    fn test_build_events() {
+        let content_dir = TempDir::new().unwrap();
+        let output_dir = TempDir::new().unwrap();
+
+        fs::create_dir_all(content_dir.path().join("events")).unwrap();
+        fs::write(
+            content_dir.path().join("events/meetup.md"),
+            "---\ntitle: \"Rust Meetup\"\nstart: 2999-05-01 18:00\nend: 2999-05-01 20:00\nlocation: \"Room <1>\"\n---\n\nTalks\n",
+        )
+        .unwrap();
+        fs::write(
+            content_dir.path().join("events/workshop.md"),
+            "---\ntitle: \"Workshop\"\nstart: 2001-02-03 09:00\n---\n\nPast\n",
+        )
+        .unwrap();
+
+        let (config, _cache_dir) = test_config();
+        Builder::new(config, content_dir.path(), output_dir.path())
+            .build()
+            .unwrap();
+
+        let page = fs::read_to_string(output_dir.path().join("events/meetup/index.html")).unwrap();
+        assert!(page.contains(r#"<article class="event">"#));
+        assert!(page.contains(r#"<p class="event-location">Room &lt;1&gt;</p>"#));
+
+        let events = fs::read_to_string(output_dir.path().join("events/index.html")).unwrap();
+        let upcoming = events.find("Rust Meetup").unwrap();
+        let past = events.find("<h2>Past events</h2>").unwrap();
+        assert!(upcoming < past);
+        assert!(events[past..].contains("Workshop"));
+        assert!(events.contains(r#"type="text/calendar""#));
+
+        let ics = fs::read_to_string(output_dir.path().join("events.ics")).unwrap();
+        assert!(ics.contains("DTSTART:29990501T180000Z\r\n"));
+        assert!(ics.contains("LOCATION:Room <1>\r\n"));
+        assert_eq!(ics.matches("BEGIN:VEVENT").count(), 2);
+    }
+
+    #[test]
+    fn test_build_theme() {
+        let content_dir = TempDir::new().unwrap();
+        let output_dir = TempDir::new().unwrap();
+        let site_dir = TempDir::new().unwrap();
+
+        fs::create_dir_all(content_dir.path().join("posts")).unwrap();
+        fs::write(
+            content_dir.path().join("about.md"),
+            "---\ntitle: \"About\"\n---\n\nAbout me\n",
+        )
+        .unwrap();
+        fs::write(
+            content_dir.path().join("posts/hello.md"),
+            "---\ntitle: \"Hello\"\ndate: 2024-01-01\n---\n\nHello\n",
+        )
+        .unwrap();
+
+        let theme = site_dir.path().join("themes/paper");
+        for dir in ["templates", "static"] {
+            fs::create_dir_all(theme.join(dir)).unwrap();
+        }
+        fs::write(theme.join("theme.toml"), "name = \"Paper\"\n").unwrap();
+        fs::write(
+            theme.join("templates/page.html"),
+            r#"<div class="paper-page">{{ content | safe }}</div>"#,
+        )
+        .unwrap();
+        fs::write(
+            theme.join("templates/post.html"),
+            r#"<div class="paper-post">{{ content | safe }}</div>"#,
+        )
+        .unwrap();
+        fs::write(theme.join("static/paper.txt"), "theme").unwrap();
+        fs::write(theme.join("static/notes.txt"), "theme").unwrap();
+
+        let templates = site_dir.path().join("templates");
+        let static_dir = site_dir.path().join("static");
+        fs::create_dir_all(&templates).unwrap();
+        fs::create_dir_all(&static_dir).unwrap();
+        fs::write(
+            templates.join("post.html"),
+            r#"<div class="site-post">{{ content | safe }}</div>"#,
+        )
+        .unwrap();
+        fs::write(static_dir.join("notes.txt"), "site").unwrap();
+
+        let (mut config, _cache_dir) = test_config();
+        config.theme = Some("paper".to_string());
+        let builder = |config: Config| {
+            Builder::new(config, content_dir.path(), output_dir.path())
+                .with_themes_dir(site_dir.path().join("themes"))
+                .with_templates_dir(&templates)
+                .with_static_dir(&static_dir)
+                .with_remote_fetch(false)
+        };
+        builder(config.clone()).build().unwrap();
+
+        let about = fs::read_to_string(output_dir.path().join("about/index.html")).unwrap();
+        assert!(about.contains(r#"<div class="paper-page">"#));
+        let post = fs::read_to_string(output_dir.path().join("posts/hello/index.html")).unwrap();
+        assert!(post.contains(r#"<div class="site-post">"#));
+        assert!(!post.contains("paper-post"));
+
+        let read = |name: &str| fs::read_to_string(output_dir.path().join(name)).unwrap();
+        assert_eq!(read("paper.txt"), "theme");
+        assert_eq!(read("notes.txt"), "site");
+
+        config.theme = Some("missing".to_string());
+        assert!(matches!(
+            builder(config).build(),
+            Err(BuildError::Theme(ThemeError::NotFound { .. }))
+        ));
+    }
+
+    #[test]
+    fn test_build_blogroll() {
+        let content_dir = TempDir::new().unwrap();
+        let output_dir = TempDir::new().unwrap();
+        let data_dir = TempDir::new().unwrap();
+
+        let blogroll = data_dir.path().join("blogroll.toml");
+        fs::write(
+            &blogroll,
+            "title = \"Sites I Read\"\n\n[[feeds]]\nname = \"Ferris\"\nurl = \"https://ferris.example.com\"\nfeed = \"https://ferris.example.com/feed.xml\"\ncategory = \"Rust\"\n",
+        )
+        .unwrap();
+
+        let (config, _cache_dir) = test_config();
+        let stats = Builder::new(config, content_dir.path(), output_dir.path())
+            .with_blogroll_file(&blogroll)
+            .build()
+            .unwrap();
+
+        let page = fs::read_to_string(output_dir.path().join("blogroll/index.html")).unwrap();
+        assert!(page.contains("<h1>Sites I Read</h1>"));
+        assert!(page.contains(r#"<a href="https://ferris.example.com">Ferris</a>"#));
+        assert!(page.contains(r#"href="/blogroll.opml""#));
+        let opml = fs::read_to_string(output_dir.path().join(OPML_FILE)).unwrap();
+        assert!(opml.contains(r#"xmlUrl="https://ferris.example.com/feed.xml""#));
+        assert!(stats.auto_pages >= 1);
+
+        // A content page takes the place of the generated one
+        fs::write(
+            content_dir.path().join("blogroll.md"),
+            "---\ntitle: \"My Blogroll\"\n---\n\nHand-written\n",
+        )
+        .unwrap();
+        let (config, _cache_dir) = test_config();
+        Builder::new(config, content_dir.path(), output_dir.path())
+            .with_blogroll_file(&blogroll)
+            .build()
+            .unwrap();
+        let page = fs::read_to_string(output_dir.path().join("blogroll/index.html")).unwrap();
+        assert!(page.contains("Hand-written"));
+        assert!(output_dir.path().join(OPML_FILE).is_file());
+    }
+
+    #[test]
+    fn test_build_shortcodes() {
+        let content_dir = TempDir::new().unwrap();
+        let output_dir = TempDir::new().unwrap();
+        let templates = TempDir::new().unwrap();
+
+        fs::write(
+            content_dir.path().join("about.md"),
+            "---\ntitle: \"About\"\n---\n\n{{< callout \"Read <this>\" kind=note >}}\n",
+        )
+        .unwrap();
+        fs::write(
+            content_dir.path().join("notes.typ"),
+            "// typstify:frontmatter\n// title: \"Notes\"\n\n{{< callout Typst kind=tip >}}\n",
+        )
+        .unwrap();
+        fs::create_dir_all(templates.path().join("shortcodes")).unwrap();
+        fs::write(
+            templates.path().join("shortcodes/callout.html"),
+            "<aside class=\"callout {{ kind }}\">\n    {{ 0 }}\n</aside>\n",
+        )
+        .unwrap();
+
+        let (config, _cache_dir) = test_config();
+        Builder::new(config, content_dir.path(), output_dir.path())
+            .with_templates_dir(templates.path())
+            .build()
+            .unwrap();
+
+        let about = fs::read_to_string(output_dir.path().join("about/index.html")).unwrap();
+        assert!(about.contains(r#"<aside class="callout note"> Read &lt;this&gt; </aside>"#));
+        let notes = fs::read_to_string(output_dir.path().join("notes/index.html")).unwrap();
+        assert!(notes.contains(r#"<aside class="callout tip"> Typst </aside>"#));
+    }
+
+    #[cfg(feature = "minijinja")]
+    #[test]
+    fn test_build_jinja_templates() {
+        let content_dir = TempDir::new().unwrap();
+        let output_dir = TempDir::new().unwrap();
+        let templates = TempDir::new().unwrap();
+
+        fs::create_dir_all(content_dir.path().join("posts")).unwrap();
+        for (name, date) in [("first", "2026-01-01"), ("second", "2026-02-01")] {
+            fs::write(
+                content_dir.path().join(format!("posts/{name}.md")),
+                format!("---\ntitle: \"Post {name}\"\ndate: {date}\ntags: [rust]\n---\n\nBody\n"),
+            )
+            .unwrap();
+        }
+        fs::write(
+            templates.path().join("post.jinja"),
+            "<article><h1>{{ page.title }}</h1>{% for tag in page.tags %}<em>{{ tag }}</em>{% endfor %}\
+             <ul>{% for p in taxonomies.tags.rust if p.url != url %}<li>{{ p.title }}</li>{% endfor %}</ul></article>",
+        )
+        .unwrap();
+
+        let (config, _cache_dir) = test_config();
+        Builder::new(config, content_dir.path(), output_dir.path())
+            .with_templates_dir(templates.path())
+            .build()
+            .unwrap();
+
+        let first = fs::read_to_string(output_dir.path().join("posts/first/index.html")).unwrap();
+        assert!(first.contains(
+            "<article><h1>Post first</h1><em>rust</em><ul><li>Post second</li></ul></article>"
+        ));
+        // The simple base template still wraps it
+        assert!(first.contains("<!DOCTYPE html>"));
+    }
+
+    #[test]
+    fn test_build_data() {
+        let content_dir = TempDir::new().unwrap();
+        let output_dir = TempDir::new().unwrap();
+        let site_dir = TempDir::new().unwrap();
+
+        let url = "https://api.example.com/repos/a/b";
+        fs::write(
+            content_dir.path().join("about.md"),
+            format!(
+                "---\ntitle: \"About\"\n---\n\nStars: {{{{< data url=\"{url}\" template=\"stars\" >}}}}\n\n{{{{< data url=\"http://127.0.0.1:9/down.json\" template=\"stars\" >}}}}\n\n{{{{< github \"a/b\" >}}}}\n"
+            ),
+        )
+        .unwrap();
+
+        let cache_dir = site_dir.path().join("cache");
+        fs::create_dir_all(cache_dir.join(DATA_DIR)).unwrap();
+        fs::write(
+            cache_dir
+                .join(DATA_DIR)
+                .join(format!("{}.json", crate::remote::url_hash(url))),
+            r#"{"stargazers_count": 1234, "owner": {"login": "<a>"}}"#,
+        )
+        .unwrap();
+        fs::write(
+            cache_dir.join(DATA_DIR).join(format!(
+                "{}.json",
+                crate::remote::url_hash("https://api.github.com/repos/a/b")
+            )),
+            r#"{"full_name": "a/b", "html_url": "https://github.com/a/b", "description": null,
+                "language": "Rust", "stargazers_count": 7, "forks_count": 2}"#,
+        )
+        .unwrap();
+        let templates = site_dir.path().join("templates");
+        fs::create_dir_all(&templates).unwrap();
+        fs::write(
+            templates.join("stars.html"),
+            r#"<span class="stars">{{ stargazers_count }} ({{ owner.login }})</span>"#,
+        )
+        .unwrap();
+
+        let (mut config, _cache_dir) = test_config();
+        config.build.cache_dir = cache_dir.to_string_lossy().into_owned();
+        Builder::new(config, content_dir.path(), output_dir.path())
+            .with_templates_dir(&templates)
+            .build()
+            .unwrap();
+
+        let about = fs::read_to_string(output_dir.path().join("about/index.html")).unwrap();
+        assert!(about.contains(r#"Stars: <span class="stars">1234 (&lt;a&gt;)</span>"#));
+        assert!(
+            about.contains(r#"<a class="github-card-name" href="https://github.com/a/b">a/b</a>"#)
+        );
+        assert!(about.contains(r#"<span class="github-card-language">Rust</span>"#));
+        assert!(about.contains("★ 7"));
+        assert!(!about.contains("data-url"));
+    }
+
+    #[test]
+    fn test_build_remote_content() {
+        let content_dir = TempDir::new().unwrap();
+        let output_dir = TempDir::new().unwrap();
+        let repo = TempDir::new().unwrap();
+        let cache_dir = TempDir::new().unwrap();
+
+        fs::create_dir_all(repo.path().join("docs")).unwrap();
+        fs::write(
+            repo.path().join("docs/guide.md"),
+            "---\ntitle: \"Guide\"\n---\n\nFrom another repository\n",
+        )
+        .unwrap();
+        fs::write(repo.path().join("README.md"), "Not published").unwrap();
+        for args in [
+            &["init", "-q"][..],
+            &["add", "."],
+            &[
+                "-c",
+                "user.name=Alice",
+                "-c",
+                "user.email=alice@example.com",
+                "commit",
+                "-q",
+                "-m",
+                "Add guide",
+            ],
+        ] {
+            let status = std::process::Command::new("git")
+                .arg("-C")
+                .arg(repo.path())
+                .args(args)
+                .status()
+                .unwrap();
+            assert!(status.success(), "git {args:?}");
+        }
+
+        let (mut config, _cache_dir) = test_config();
+        config.build.cache_dir = cache_dir.path().to_string_lossy().into_owned();
+        config.content.remote = vec![typstify_core::config::RemoteContentConfig {
+            url: repo.path().to_string_lossy().into_owned(),
+            git_ref: None,
+            path: Some("docs".to_string()),
+            target: "handbook".to_string(),
+        }];
+        Builder::new(config, content_dir.path(), output_dir.path())
+            .build()
+            .unwrap();
+
+        assert!(content_dir.path().join("handbook/guide.md").is_file());
+        assert!(!content_dir.path().join("handbook/README.md").exists());
+        let html = fs::read_to_string(output_dir.path().join("handbook/guide/index.html")).unwrap();
+        assert!(html.contains("From another repository"));
+    }
+
+    #[test]
+    fn test_build_identity() {
+        let content_dir = TempDir::new().unwrap();
+        let output_dir = TempDir::new().unwrap();
+
+        fs::create_dir_all(content_dir.path().join("posts")).unwrap();
+        fs::write(
+            content_dir.path().join("posts/hello.md"),
+            "---\ntitle: \"Hello\"\ndate: 2024-01-01\n---\n\nBody\n",
+        )
+        .unwrap();
+
+        let (mut config, _cache_dir) = test_config();
+        config.identity.me = vec!["https://github.com/alice".to_string()];
+        config.identity.fediverse = Some("@alice@mastodon.social".to_string());
+        Builder::new(config, content_dir.path(), output_dir.path())
+            .build()
+            .unwrap();
+
+        let post = fs::read_to_string(output_dir.path().join("posts/hello/index.html")).unwrap();
+        assert!(post.contains(r#"<link rel="me" href="https://github.com/alice">"#));
+        assert!(post.contains(r#"<link rel="me" href="https://mastodon.social/@alice">"#));
+
+        let webfinger =
+            fs::read_to_string(output_dir.path().join(".well-known/webfinger")).unwrap();
+        assert!(webfinger.contains(r#""subject": "acct:alice@mastodon.social""#));
+    }
+
+    #[test]
+    fn test_build_hidden_sections() {
+        let content_dir = TempDir::new().unwrap();
+        let output_dir = TempDir::new().unwrap();
+
+        for dir in ["posts", "notes", "internal"] {
+            fs::create_dir_all(content_dir.path().join(dir)).unwrap();
+            fs::write(
+                content_dir.path().join(format!("{dir}/page.md")),
+                "---\ntitle: \"Page\"\n---\n\nBody\n",
+            )
+            .unwrap();
+        }
+
+        let (mut config, _cache_dir) = test_config();
+        config.sections.insert(
+            "notes".to_string(),
+            typstify_core::config::SectionConfig {
+                robots: typstify_core::config::SectionRobots::Noindex,
+                ..Default::default()
+            },
+        );
+        config.sections.insert(
+            "internal".to_string(),
+            typstify_core::config::SectionConfig {
+                sitemap: false,
+                ..Default::default()
+            },
+        );
+        Builder::new(config, content_dir.path(), output_dir.path())
+            .build()
+            .unwrap();
+
+        let sitemap = fs::read_to_string(output_dir.path().join("sitemap.xml")).unwrap();
+        assert!(sitemap.contains("/posts/page"));
+        assert!(!sitemap.contains("/notes/page"));
+        assert!(!sitemap.contains("/internal/page"));
+
+        let noindex = r#"<meta name="robots" content="noindex">"#;
+        let note = fs::read_to_string(output_dir.path().join("notes/page/index.html")).unwrap();
+        assert!(note.contains(noindex));
+        let notes = fs::read_to_string(output_dir.path().join("notes/index.html")).unwrap();
+        assert!(notes.contains(noindex));
+        let internal =
+            fs::read_to_string(output_dir.path().join("internal/page/index.html")).unwrap();
+        assert!(!internal.contains(noindex));
+    }
+
+    #[test]
+    fn test_build_syntax_theme() {
+        let content_dir = TempDir::new().unwrap();
+        let output_dir = TempDir::new().unwrap();
+        fs::write(
+            content_dir.path().join("code.md"),
+            "---\ntitle: \"Code\"\n---\n\n```rust {2 linenos}\nlet a = 1;\nlet b = 2;\n```\n",
+        )
+        .unwrap();
+
+        let (mut config, _cache_dir) = test_config();
+        config.build.syntax_theme = "InspiredGitHub".to_string();
+        config.build.syntax_theme_dark = Some("Nope".to_string());
+        let stats = Builder::new(config, content_dir.path(), output_dir.path())
+            .build()
+            .unwrap();
+
+        let warning = stats
+            .warnings
+            .iter()
+            .find(|w| w.kind == WarningKind::Config)
+            .unwrap();
+        assert_eq!(warning.location.as_deref(), Some("build.syntax_theme_dark"));
+        assert!(warning.message.starts_with("unknown syntax theme \"Nope\""));
+        assert!(warning.message.contains("InspiredGitHub"));
+
+        // The unknown dark theme is ignored: InspiredGitHub, fixed
+        let page = fs::read_to_string(output_dir.path().join("code/index.html")).unwrap();
+        assert!(page.contains("<pre class=\"line-numbers\" style=\"background-color:#ffffff;\">"));
+        assert!(page.contains("<span class=\"line highlighted\">"));
+        let css = fs::read_to_string(output_dir.path().join("assets/syntax.css")).unwrap();
+        assert!(css.is_empty());
+    }
+
+    #[test]
+    fn test_build_chunked_search_index() {
+        let content_dir = TempDir::new().unwrap();
+        let output_dir = TempDir::new().unwrap();
+        let cache_dir = TempDir::new().unwrap();
+
+        // Enough distinct words for a simple index over MAX_SIMPLE_INDEX_SIZE
+        let posts = content_dir.path().join("posts");
+        fs::create_dir_all(&posts).unwrap();
+        for i in 0..40 {
+            let words: Vec<_> = (0..3000).map(|j| format!("term{i}x{j}")).collect();
+            fs::write(
+                posts.join(format!("post-{i}.md")),
+                format!("---\ntitle: \"Post {i}\"\n---\n\n{}\n", words.join(" ")),
+            )
+            .unwrap();
+        }
+
+        let (mut config, _cache_dir) = test_config();
+        config.build.cache_dir = cache_dir.path().to_string_lossy().into_owned();
+        Builder::new(config, content_dir.path(), output_dir.path())
+            .with_remote_fetch(false)
+            .build()
+            .unwrap();
+
+        let simple = fs::metadata(output_dir.path().join("search-index.json")).unwrap();
+        assert!(simple.len() > MAX_SIMPLE_INDEX_SIZE as u64);
+        let chunked = output_dir.path().join(CHUNKED_INDEX_DIR);
+        let manifest = typstify_search::IndexManifest::from_json(
+            &fs::read_to_string(chunked.join(INDEX_MANIFEST_FILE)).unwrap(),
+        )
+        .unwrap();
+        assert!(manifest.files.contains_key("meta.json"));
+        for chunk in manifest.files.values().flat_map(|file| &file.chunks) {
+            assert!(chunked.join(chunk).is_file(), "{chunk}");
+        }
+
+        // Pages point the search script at the index and the runtime
+        let page = fs::read_to_string(output_dir.path().join("posts/post-0/index.html")).unwrap();
+        assert!(page.contains(
+            r#"<script type="application/json" id="search-engine-config">{"index":"/search","runtime":"/pkg/typstify_search_wasm.js"}</script>"#
+        ));
+    }
+
+    #[test]
+    fn test_build_protected_sections() {
+        let content_dir = TempDir::new().unwrap();
+        let output_dir = TempDir::new().unwrap();
+
+        for (path, draft) in [
+            ("posts/public.md", false),
+            ("posts/wip.md", true),
+            ("members/roadmap.md", false),
+        ] {
+            let path = content_dir.path().join(path);
+            fs::create_dir_all(path.parent().unwrap()).unwrap();
+            fs::write(
+                path,
+                format!(
+                    "---\ntitle: \"Page\"\ndate: 2026-01-01T00:00:00Z\ndraft: {draft}\n---\n\nBody\n"
+                ),
+            )
+            .unwrap();
+        }
+
+        let (mut config, _cache_dir) = test_config();
+        config.build.drafts = true;
+        config.search.encrypted_preview = true;
+        config.build.url_map = true;
+        config.build.link_graph = true;
+        config.sections.insert(
+            "members".to_string(),
+            typstify_core::config::SectionConfig {
+                protected: true,
+                ..Default::default()
+            },
+        );
+        Builder::new(config, content_dir.path(), output_dir.path())
+            .with_search_key("preview")
+            .build()
+            .unwrap();
+
+        for file in [
+            "search-index.json",
+            "sitemap.xml",
+            "rss.xml",
+            URL_MAP_FILE,
+            SITE_STRUCTURE_FILE,
+            LINK_GRAPH_FILE,
+        ] {
+            let public = fs::read_to_string(output_dir.path().join(file)).unwrap();
+            assert!(public.contains("/posts/public"), "{file}");
+            assert!(!public.contains("/posts/wip"), "{file}");
+            assert!(!public.contains("/members/roadmap"), "{file}");
+        }
+
+        // Small indexes aren't chunked
+        assert!(!output_dir.path().join(CHUNKED_INDEX_DIR).exists());
+
+        let preview = fs::read_to_string(output_dir.path().join(ENCRYPTED_INDEX_FILE)).unwrap();
+        assert!(!preview.contains("/members/roadmap"));
+        let index = EncryptedIndex::from_json(&preview)
+            .unwrap()
+            .open("preview")
+            .unwrap();
+        for url in ["/posts/public", "/posts/wip", "/members/roadmap"] {
+            assert!(index.contains(url), "{url}");
+        }
+    }
+
+    #[test]
+    fn test_build_docs_layout() {
+        let content_dir = TempDir::new().unwrap();
+        let output_dir = TempDir::new().unwrap();
+
+        fs::create_dir_all(content_dir.path().join("docs/guides")).unwrap();
+        fs::create_dir_all(content_dir.path().join("posts")).unwrap();
+        for (path, title) in [
+            ("docs/intro.md", "Intro"),
+            ("docs/guides/install.md", "Install"),
+            ("posts/hello.md", "Hello"),
+        ] {
+            fs::write(
+                content_dir.path().join(path),
+                format!("---\ntitle: \"{title}\"\n---\n\nBody\n"),
+            )
+            .unwrap();
+        }
+
+        let (mut config, _cache_dir) = test_config();
+        config.sections.insert(
+            "docs".to_string(),
+            typstify_core::config::SectionConfig {
+                layout: typstify_core::config::SectionLayout::Docs,
+                ..Default::default()
+            },
+        );
+        Builder::new(config, content_dir.path(), output_dir.path())
+            .build()
+            .unwrap();
+
+        let install =
+            fs::read_to_string(output_dir.path().join("docs/guides/install/index.html")).unwrap();
+        assert!(install.contains(r#"<div class="docs-layout">"#));
+        assert!(install.contains(r#"<a href="/docs/intro">Intro</a>"#));
+        assert!(install.contains(r#"<a href="/docs/guides/install" aria-current="page">"#));
+
+        let guides = fs::read_to_string(output_dir.path().join("docs/guides/index.html")).unwrap();
+        assert!(guides.contains(r#"<a href="/docs/guides" aria-current="page">Guides</a>"#));
+
+        let hello = fs::read_to_string(output_dir.path().join("posts/hello/index.html")).unwrap();
+        assert!(!hello.contains("docs-layout"));
+    }
+
+    #[test]
+    fn test_build_page_media() {
+        let content_dir = TempDir::new().unwrap();
+        let output_dir = TempDir::new().unwrap();
+
+        fs::create_dir_all(content_dir.path().join("posts/media")).unwrap();
+        fs::write(content_dir.path().join("posts/media/clip.mp4"), "mp4").unwrap();
+        fs::write(content_dir.path().join("posts/poster.jpg"), "jpg").unwrap();
+        fs::write(
+            content_dir.path().join("posts/demo.md"),
+            r#"---
+title: "Demo"
+---
+
+{{< video src="media/clip.mp4" poster="poster.jpg" caption="Demo" >}}
+
+{{< audio https://cdn.example.com/a.mp3 >}}
+"#,
+        )
+        .unwrap();
+
+        let (config, _cache_dir) = test_config();
+        let stats = Builder::new(config, content_dir.path(), output_dir.path())
+            .build()
+            .unwrap();
+        assert_eq!(stats.assets, 2);
+
+        let page_dir = output_dir.path().join("posts/demo");
+        assert!(page_dir.join("media/clip.mp4").exists());
+        assert!(page_dir.join("poster.jpg").exists());
+
+        let html = fs::read_to_string(page_dir.join("index.html")).unwrap();
+        assert!(html.contains(r#"<source src="media/clip.mp4" type="video/mp4">"#));
+        assert!(html.contains("<figcaption>Demo</figcaption>"));
+    }
+
+    #[test]
+    fn test_build_localize_images() {
+        let content_dir = TempDir::new().unwrap();
+        let output_dir = TempDir::new().unwrap();
+        let static_dir = TempDir::new().unwrap();
+
+        let cache_dir = static_dir.path().join(REMOTE_DIR);
+        fs::create_dir_all(&cache_dir).unwrap();
+        fs::write(cache_dir.join("0123456789abcdef.png"), "old").unwrap();
+        fs::create_dir_all(content_dir.path().join("posts")).unwrap();
+        fs::write(
+            content_dir.path().join("posts/hello.md"),
+            "---\ntitle: \"Hello\"\n---\n\n![Local](/img/a.png)\n",
+        )
+        .unwrap();
+
+        let (mut config, _cache_dir) = test_config();
+        config.build.localize_images = true;
+        Builder::new(config, content_dir.path(), output_dir.path())
+            .with_static_dir(static_dir.path())
+            .build()
+            .unwrap();
+
+        assert!(
+            output_dir
+                .path()
+                .join("remote/0123456789abcdef.png")
+                .exists()
+        );
+        let html = fs::read_to_string(output_dir.path().join("posts/hello/index.html")).unwrap();
+        assert!(html.contains(r#"src="/img/a.png""#));
+    }
+
+    #[test]
+    fn test_build_responsive_images() {
+        let content_dir = TempDir::new().unwrap();
+        let output_dir = TempDir::new().unwrap();
+        let static_dir = TempDir::new().unwrap();
+
+        fs::create_dir_all(static_dir.path().join("img")).unwrap();
+        image::RgbImage::new(640, 320)
+            .save(static_dir.path().join("img/hero.jpg"))
+            .unwrap();
+        fs::create_dir_all(content_dir.path().join("posts")).unwrap();
+        image::RgbImage::new(300, 300)
+            .save(content_dir.path().join("posts/shot.png"))
+            .unwrap();
+        fs::write(
+            content_dir.path().join("posts/hello.md"),
+            "---\ntitle: \"Hello\"\n---\n\n![Hero](/img/hero.jpg)\n\n![Shot](shot.png)\n",
+        )
+        .unwrap();
+
+        let (mut config, _cache_dir) = test_config();
+        config.images.responsive = true;
+        config.images.breakpoints = vec![480];
+        Builder::new(config, content_dir.path(), output_dir.path())
+            .with_static_dir(static_dir.path())
+            .build()
+            .unwrap();
+
+        let out = output_dir.path();
+        assert!(out.join("img/hero-480.jpg").exists());
+        assert!(out.join("img/hero-640.webp").exists());
+        assert!(out.join("posts/hello/shot.png").exists());
+        assert!(out.join("posts/hello/shot-300.webp").exists());
+
+        let html = fs::read_to_string(out.join("posts/hello/index.html")).unwrap();
+        assert!(html.contains(
+            r#"<picture><source type="image/webp" srcset="/img/hero-480.webp 480w, /img/hero-640.webp 640w""#
+        ));
+        assert!(html.contains(r#"srcset="shot-300.png 300w""#));
+        assert!(html.contains(r#"width="640" height="320" src="/img/hero.jpg""#));
+    }
+
+    #[test]
+    fn test_build_short_images() {
+        let content_dir = TempDir::new().unwrap();
+        let output_dir = TempDir::new().unwrap();
+
+        fs::create_dir_all(content_dir.path().join("shorts")).unwrap();
+        image::RgbImage::new(640, 480)
+            .save(content_dir.path().join("shorts/beach.jpg"))
+            .unwrap();
+        fs::write(
+            content_dir.path().join("shorts/trip.md"),
+            "---\ntitle: \"Trip\"\ndate: 2024-01-01\ntemplate: short\nimages:\n  - src: beach.jpg\n    alt: \"The beach\"\n---\n\nAt the beach.\n",
+        )
+        .unwrap();
+
+        let (config, _cache_dir) = test_config();
+        Builder::new(config, content_dir.path(), output_dir.path())
+            .build()
+            .unwrap();
+
+        let out = output_dir.path();
+        assert!(out.join("shorts/trip/beach.jpg").exists());
+        assert!(out.join("shorts/trip/beach-thumb.jpg").exists());
+
+        let gallery = r#"<div class="short-gallery" data-count="1"><a href="/shorts/trip/beach.jpg"><img src="/shorts/trip/beach-thumb.jpg" alt="The beach" loading="lazy"></a></div>"#;
+        let timeline = fs::read_to_string(out.join("shorts/index.html")).unwrap();
+        assert!(timeline.contains(gallery));
+        let short = fs::read_to_string(out.join("shorts/trip/index.html")).unwrap();
+        assert!(short.contains(gallery));
+    }
+
+    #[test]
+    fn test_build_shorts_by_month() {
+        let content_dir = TempDir::new().unwrap();
+        let output_dir = TempDir::new().unwrap();
+
+        fs::create_dir_all(content_dir.path().join("shorts")).unwrap();
+        for (name, date) in [
+            ("a", "2024-01-05"),
+            ("b", "2024-01-20"),
+            ("c", "2024-03-02"),
+        ] {
+            fs::write(
+                content_dir.path().join(format!("shorts/{name}.md")),
+                format!(
+                    "---\ntitle: \"{name}\"\ndate: {date}\ntemplate: short\n---\n\nShort {name}\n"
+                ),
+            )
+            .unwrap();
+        }
+
+        let (mut config, _cache_dir) = test_config();
+        config.sections.insert(
+            "shorts".to_string(),
+            typstify_core::config::SectionConfig {
+                paginate_by: PaginateBy::Month,
+                ..Default::default()
+            },
+        );
+        Builder::new(config, content_dir.path(), output_dir.path())
+            .build()
+            .unwrap();
+
+        let newest = fs::read_to_string(output_dir.path().join("shorts/index.html")).unwrap();
+        assert!(newest.contains("Short c"));
+        assert!(!newest.contains("Short a"));
+        assert!(
+            newest.contains(r#"<a href="/shorts/archive/2024-01" rel="next">January 2024 →</a>"#)
+        );
+        assert!(newest.contains(
+            r#"<li><a href="/shorts" aria-current="page">March 2024</a> <span class="archive-count">1</span></li>"#
+        ));
+
+        let january =
+            fs::read_to_string(output_dir.path().join("shorts/archive/2024-01/index.html"))
+                .unwrap();
+        assert!(january.contains("Short a") && january.contains("Short b"));
+        assert!(january.contains(r#"<a href="/shorts" rel="prev">← March 2024</a>"#));
+        assert!(!output_dir.path().join("shorts/page/2").exists());
+    }
+
+    #[test]
+    fn test_build_cache() {
+        let content_dir = TempDir::new().unwrap();
+        let output_dir = TempDir::new().unwrap();
+        let site_dir = TempDir::new().unwrap();
+
+        fs::create_dir_all(content_dir.path().join("posts")).unwrap();
+        fs::write(
+            content_dir.path().join("about.md"),
+            "---\ntitle: \"About\"\n---\n\nAbout me\n",
+        )
+        .unwrap();
+        let hello = content_dir.path().join("posts/hello.md");
+        fs::write(
+            &hello,
+            "---\ntitle: \"Hello\"\ndate: 2024-01-01\n---\n\nHello\n",
+        )
+        .unwrap();
+        let templates = site_dir.path().join("templates");
+        fs::create_dir_all(&templates).unwrap();
+
+        let (mut config, _cache_dir) = test_config();
+        config.build.cache_dir = site_dir.path().join("cache").to_string_lossy().into_owned();
+        let build = || {
+            Builder::new(config.clone(), content_dir.path(), output_dir.path())
+                .with_templates_dir(&templates)
+                .with_remote_fetch(false)
+                .with_cache(true)
+                .build()
+                .unwrap()
+        };
+
+        let first = build();
+        assert_eq!((first.cache_hits, first.cache_misses), (0, 2));
+        assert!(site_dir.path().join("cache").join(CACHE_FILE).is_file());
+        let rendered = site_dir.path().join("cache").join(RENDERED_DIR);
+        assert_eq!(fs::read_dir(&rendered).unwrap().count(), 2);
+
+        let second = build();
+        assert_eq!((second.cache_hits, second.cache_misses), (2, 0));
+        let post = fs::read_to_string(output_dir.path().join("posts/hello/index.html")).unwrap();
+        assert!(post.contains("<p>Hello</p>"));
+
+        // Only the edited page is rendered again
+        fs::write(
+            &hello,
+            "---\ntitle: \"Hello\"\ndate: 2024-01-01\n---\n\nHello again\n",
+        )
+        .unwrap();
+        let edited = build();
+        assert_eq!((edited.cache_hits, edited.cache_misses), (1, 1));
+        assert_eq!(fs::read_dir(&rendered).unwrap().count(), 2);
+        let post = fs::read_to_string(output_dir.path().join("posts/hello/index.html")).unwrap();
+        assert!(post.contains("<p>Hello again</p>"));
+
+        // A template change renders every page again
+        fs::write(
+            templates.join("page.html"),
+            r#"<div class="site-page">{{ content | safe }}</div>"#,
+        )
+        .unwrap();
+        let restyled = build();
+        assert_eq!((restyled.cache_hits, restyled.cache_misses), (0, 2));
+
+        // Without the cache nothing is counted
+        let uncached = Builder::new(config.clone(), content_dir.path(), output_dir.path())
+            .with_remote_fetch(false)
+            .build()
+            .unwrap();
+        assert_eq!((uncached.cache_hits, uncached.cache_misses), (0, 0));
+    }
 }
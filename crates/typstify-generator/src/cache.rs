@@ -0,0 +1,326 @@
+//! Build cache for incremental rebuilds.
+//!
+//! Kept in `build.cache_dir` between builds, it holds the parsed page of
+//! every content file, keyed by the hash of its source, and the rendered
+//! HTML of every page, keyed by a digest of everything the page is rendered
+//! from (see [`HtmlGenerator::page_digest`](crate::html::HtmlGenerator::page_digest)).
+//! Rendered HTML is stored one file per page under [`RENDERED_DIR`], so a
+//! build can write each page to the cache as it renders it.
+//! Unchanged files are not parsed again and unchanged pages are not
+//! rendered again. The whole cache is dropped when the configuration or the
+//! typstify version changes.
+
+use std::{
+    collections::{BTreeMap, HashSet},
+    fs,
+    path::{Path, PathBuf},
+};
+
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+use tracing::debug;
+use typstify_core::{Config, Page};
+
+use crate::manifest::content_hash;
+
+/// Current cache format version.
+pub const CACHE_VERSION: u32 = 4;
+
+/// Cache file name, written into `build.cache_dir`.
+pub const CACHE_FILE: &str = "build-cache.json";
+
+/// Directory of rendered pages, next to [`CACHE_FILE`].
+pub const RENDERED_DIR: &str = "rendered";
+
+/// Build cache errors.
+#[derive(Debug, Error)]
+pub enum CacheError {
+    /// IO error.
+    #[error("IO error: {0}")]
+    Io(#[from] std::io::Error),
+
+    /// Serialization error.
+    #[error("cache serialization error: {0}")]
+    Serialization(#[from] serde_json::Error),
+}
+
+/// Result type for cache operations.
+pub type Result<T> = std::result::Result<T, CacheError>;
+
+/// A parsed content file.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CachedPage {
+    /// Content hash of the source file and its dependencies.
+    pub hash: String,
+
+    /// Files besides the source that the page was parsed from.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub dependencies: Vec<PathBuf>,
+
+    /// The page parsed from it.
+    pub page: Page,
+}
+
+/// A rendered page, whose HTML is in [`rendered_path`].
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CachedHtml {
+    /// Digest of the inputs the page was rendered from.
+    pub digest: String,
+}
+
+/// Parsed and rendered pages from a build.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BuildCache {
+    /// Cache format version.
+    pub version: u32,
+
+    /// Digest of the configuration and typstify version the entries were
+    /// made with.
+    pub config: String,
+
+    /// Source path (relative to the content directory) -> parsed page.
+    #[serde(default)]
+    pub parsed: BTreeMap<String, CachedPage>,
+
+    /// Page URL -> rendered page.
+    #[serde(default)]
+    pub rendered: BTreeMap<String, CachedHtml>,
+}
+
+impl BuildCache {
+    /// Create an empty cache for `config`.
+    #[must_use]
+    pub fn new(config: &Config) -> Self {
+        Self {
+            version: CACHE_VERSION,
+            config: config_digest(config),
+            parsed: BTreeMap::new(),
+            rendered: BTreeMap::new(),
+        }
+    }
+
+    /// Load the cache at `path` if it was written for `config`; otherwise
+    /// (missing, unreadable, other version or configuration) an empty one.
+    #[must_use]
+    pub fn load(path: &Path, config: &Config) -> Self {
+        let digest = config_digest(config);
+        match fs::read_to_string(path)
+            .ok()
+            .and_then(|json| serde_json::from_str::<Self>(&json).ok())
+        {
+            Some(cache) if cache.version == CACHE_VERSION && cache.config == digest => cache,
+            Some(_) => {
+                debug!(path = %path.display(), "build cache is stale, starting fresh");
+                Self::new(config)
+            }
+            None => Self::new(config),
+        }
+    }
+
+    /// The parsed page of the file at `key`, if its source hash is `hash`.
+    #[must_use]
+    pub fn parsed(&self, key: &str, hash: &str) -> Option<&Page> {
+        self.parsed
+            .get(key)
+            .filter(|entry| entry.hash == hash)
+            .map(|entry| &entry.page)
+    }
+
+    /// The files besides its source that the cached page of the file at
+    /// `key` was parsed from, whose contents are part of its hash.
+    #[must_use]
+    pub fn dependencies(&self, key: &str) -> &[PathBuf] {
+        self.parsed
+            .get(key)
+            .map_or(&[], |entry| entry.dependencies.as_slice())
+    }
+
+    /// Record the page parsed from the file at `key` and `dependencies`.
+    pub fn insert_parsed(
+        &mut self,
+        key: impl Into<String>,
+        hash: impl Into<String>,
+        dependencies: Vec<PathBuf>,
+        page: Page,
+    ) {
+        self.parsed.insert(
+            key.into(),
+            CachedPage {
+                hash: hash.into(),
+                dependencies,
+                page,
+            },
+        );
+    }
+
+    /// The rendered HTML of the page at `url`, if it was rendered from
+    /// inputs with `digest` and stored in the cache directory `dir`.
+    #[must_use]
+    pub fn rendered(&self, dir: &Path, url: &str, digest: &str) -> Option<String> {
+        self.rendered
+            .get(url)
+            .filter(|entry| entry.digest == digest)
+            .and_then(|_| fs::read_to_string(rendered_path(dir, url, digest)).ok())
+    }
+
+    /// Record that the page at `url` was rendered from inputs with `digest`,
+    /// its HTML having been stored with [`store_rendered`].
+    pub fn insert_rendered(&mut self, url: impl Into<String>, digest: impl Into<String>) {
+        self.rendered.insert(
+            url.into(),
+            CachedHtml {
+                digest: digest.into(),
+            },
+        );
+    }
+
+    /// Write the cache to `path`, creating its directory, and remove the
+    /// stored HTML of pages it no longer records.
+    pub fn write_to(&self, path: &Path) -> Result<()> {
+        let dir = path.parent().unwrap_or(Path::new(""));
+        fs::create_dir_all(dir)?;
+        fs::write(path, serde_json::to_string(self)?)?;
+
+        let Ok(entries) = fs::read_dir(dir.join(RENDERED_DIR)) else {
+            return Ok(());
+        };
+        let keep: HashSet<_> = self
+            .rendered
+            .iter()
+            .map(|(url, entry)| rendered_path(dir, url, &entry.digest))
+            .collect();
+        for entry in entries.flatten() {
+            let file = entry.path();
+            if !keep.contains(&file) {
+                fs::remove_file(&file)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Path of the HTML of the page at `url` rendered from inputs with `digest`,
+/// in the cache directory `dir`.
+#[must_use]
+pub fn rendered_path(dir: &Path, url: &str, digest: &str) -> PathBuf {
+    let name = content_hash(format!("{url}\0{digest}").as_bytes());
+    dir.join(RENDERED_DIR).join(format!("{name}.html"))
+}
+
+/// Store the HTML of the page at `url` rendered from inputs with `digest` in
+/// the cache directory `dir`. The page is only part of the cache once it is
+/// also recorded with [`BuildCache::insert_rendered`].
+pub fn store_rendered(dir: &Path, url: &str, digest: &str, html: &str) -> Result<()> {
+    let path = rendered_path(dir, url, digest);
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    fs::write(path, html)?;
+    Ok(())
+}
+
+/// Digest of `config` and the typstify version.
+#[must_use]
+pub fn config_digest(config: &Config) -> String {
+    // Via `Value`, whose maps are sorted, so the digest is stable
+    let config = serde_json::to_value(config)
+        .map(|value| value.to_string())
+        .unwrap_or_default();
+    content_hash(format!("{}\0{config}", env!("CARGO_PKG_VERSION")).as_bytes())
+}
+
+#[cfg(test)]
+mod tests {
+    use tempfile::TempDir;
+    use typstify_core::{
+        content::{ContentPath, ParsedContent},
+        frontmatter::Frontmatter,
+    };
+
+    use super::*;
+
+    fn config() -> Config {
+        toml::from_str("[site]\ntitle = \"Test\"\nhost = \"https://example.com\"\n").unwrap()
+    }
+
+    fn page() -> Page {
+        let path = ContentPath::from_path(Path::new("posts/hello.md"), "en").unwrap();
+        Page::from_parsed(
+            ParsedContent {
+                frontmatter: Frontmatter {
+                    title: "Hello".to_string(),
+                    ..Frontmatter::default()
+                },
+                html: "<p>Hello</p>".to_string(),
+                raw: "Hello".to_string(),
+                toc: vec![],
+                dependencies: vec![],
+            },
+            &path,
+        )
+    }
+
+    #[test]
+    fn test_roundtrip() {
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join("cache").join(CACHE_FILE);
+        let config = config();
+
+        let cache_dir = path.parent().unwrap();
+
+        let mut cache = BuildCache::new(&config);
+        cache.insert_parsed("posts/hello.md", "abc", vec![], page());
+        store_rendered(cache_dir, "/posts/hello", "d1", "<html></html>").unwrap();
+        cache.insert_rendered("/posts/hello", "d1");
+        cache.write_to(&path).unwrap();
+
+        let loaded = BuildCache::load(&path, &config);
+        assert_eq!(
+            loaded.parsed("posts/hello.md", "abc").unwrap().content,
+            "<p>Hello</p>"
+        );
+        assert!(loaded.parsed("posts/hello.md", "changed").is_none());
+        assert_eq!(
+            loaded.rendered(cache_dir, "/posts/hello", "d1").as_deref(),
+            Some("<html></html>")
+        );
+        assert_eq!(loaded.rendered(cache_dir, "/posts/hello", "d2"), None);
+    }
+
+    #[test]
+    fn test_write_removes_unrecorded_pages() {
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join(CACHE_FILE);
+
+        let mut cache = BuildCache::new(&config());
+        store_rendered(dir.path(), "/a", "d1", "<p>old</p>").unwrap();
+        store_rendered(dir.path(), "/a", "d2", "<p>new</p>").unwrap();
+        store_rendered(dir.path(), "/b", "d1", "<p>gone</p>").unwrap();
+        cache.insert_rendered("/a", "d2");
+        cache.write_to(&path).unwrap();
+
+        assert!(rendered_path(dir.path(), "/a", "d2").exists());
+        assert!(!rendered_path(dir.path(), "/a", "d1").exists());
+        assert!(!rendered_path(dir.path(), "/b", "d1").exists());
+    }
+
+    #[test]
+    fn test_load_discards_other_config() {
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join(CACHE_FILE);
+
+        let mut cache = BuildCache::new(&config());
+        cache.insert_parsed("a.md", "abc", vec![], page());
+        cache.write_to(&path).unwrap();
+
+        let mut changed = config();
+        changed.site.title = "Renamed".to_string();
+        assert!(BuildCache::load(&path, &changed).parsed.is_empty());
+        assert!(
+            BuildCache::load(&dir.path().join("missing.json"), &config())
+                .parsed
+                .is_empty()
+        );
+        assert_eq!(config_digest(&config()), config_digest(&config()));
+    }
+}
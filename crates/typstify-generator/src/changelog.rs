@@ -0,0 +1,287 @@
+//! Content changelog from git history.
+//!
+//! Reads the commits touching the content directory and renders them as a
+//! `/changelog/` page grouped by month, with each commit's author and links
+//! to the pages it changed.
+
+use std::{
+    collections::{BTreeMap, HashMap},
+    path::{Path, PathBuf},
+    process::Command,
+};
+
+use chrono::{DateTime, Datelike, FixedOffset};
+use thiserror::Error;
+use typstify_core::{Page, escape_html};
+
+/// Changelog errors.
+#[derive(Debug, Error)]
+pub enum ChangelogError {
+    /// `git` could not be run.
+    #[error("failed to run git: {0}")]
+    Io(#[from] std::io::Error),
+
+    /// `git log` failed (e.g. not a git repository).
+    #[error("git log failed: {0}")]
+    Git(String),
+}
+
+/// Result type for changelog operations.
+pub type Result<T> = std::result::Result<T, ChangelogError>;
+
+/// Field and record separators of the `git log` format.
+const FIELD: char = '\u{1f}';
+const RECORD: char = '\u{1e}';
+
+/// A commit touching the content directory.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ContentCommit {
+    /// Abbreviated commit hash.
+    pub hash: String,
+
+    /// Author name.
+    pub author: String,
+
+    /// Author date.
+    pub date: DateTime<FixedOffset>,
+
+    /// First line of the commit message.
+    pub subject: String,
+
+    /// Changed files, relative to the content directory.
+    pub files: Vec<PathBuf>,
+}
+
+/// Commits touching the content directory, newest first.
+#[derive(Debug, Clone, Default)]
+pub struct Changelog {
+    commits: Vec<ContentCommit>,
+}
+
+impl Changelog {
+    /// Read the history of `content_dir` with `git log`.
+    pub fn from_git(content_dir: &Path) -> Result<Self> {
+        let output = Command::new("git")
+            .arg("-C")
+            .arg(content_dir)
+            .args([
+                "log",
+                "--no-merges",
+                "--relative",
+                "--name-only",
+                &format!("--format={RECORD}%h{FIELD}%an{FIELD}%aI{FIELD}%s"),
+                "--",
+                ".",
+            ])
+            .output()?;
+
+        if !output.status.success() {
+            return Err(ChangelogError::Git(
+                String::from_utf8_lossy(&output.stderr).trim().to_string(),
+            ));
+        }
+        Ok(Self::parse(&String::from_utf8_lossy(&output.stdout)))
+    }
+
+    /// Parse `git log` output in the format used by [`Changelog::from_git`].
+    #[must_use]
+    pub fn parse(log: &str) -> Self {
+        let commits = log
+            .split(RECORD)
+            .filter_map(|record| {
+                let mut lines = record.lines();
+                let mut fields = lines.next()?.splitn(4, FIELD);
+                let hash = fields.next()?.to_string();
+                let author = fields.next()?.to_string();
+                let date = DateTime::parse_from_rfc3339(fields.next()?).ok()?;
+                let subject = fields.next().unwrap_or_default().to_string();
+                let files = lines
+                    .map(str::trim)
+                    .filter(|line| !line.is_empty())
+                    .map(PathBuf::from)
+                    .collect();
+                Some(ContentCommit {
+                    hash,
+                    author,
+                    date,
+                    subject,
+                    files,
+                })
+            })
+            .collect();
+
+        Self { commits }
+    }
+
+    /// The commits, newest first.
+    #[must_use]
+    pub fn commits(&self) -> &[ContentCommit] {
+        &self.commits
+    }
+
+    /// Whether there are no commits.
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.commits.is_empty()
+    }
+
+    /// Contributors with their commit counts, most active first.
+    #[must_use]
+    pub fn contributors(&self) -> Vec<(&str, usize)> {
+        let mut counts: HashMap<&str, usize> = HashMap::new();
+        for commit in &self.commits {
+            *counts.entry(commit.author.as_str()).or_default() += 1;
+        }
+        let mut contributors: Vec<_> = counts.into_iter().collect();
+        contributors.sort_by(|a, b| b.1.cmp(&a.1).then(a.0.cmp(b.0)));
+        contributors
+    }
+
+    /// Render the changelog grouped by month (newest first). Changed files
+    /// are linked to the pages built from them; other files are not listed.
+    #[must_use]
+    pub fn html<'a>(&self, pages: impl IntoIterator<Item = &'a Page>, base_path: &str) -> String {
+        let by_source: HashMap<&Path, &Page> = pages
+            .into_iter()
+            .filter_map(|page| page.source_path.as_deref().map(|path| (path, page)))
+            .collect();
+
+        let mut by_month: BTreeMap<(i32, u32), Vec<&ContentCommit>> = BTreeMap::new();
+        for commit in &self.commits {
+            by_month
+                .entry((commit.date.year(), commit.date.month()))
+                .or_default()
+                .push(commit);
+        }
+
+        let contributors = self
+            .contributors()
+            .iter()
+            .map(|(name, count)| {
+                format!(
+                    r#"<li>{} <span class="changelog-count">{count}</span></li>"#,
+                    escape_html(name)
+                )
+            })
+            .collect::<String>();
+        let mut html = format!(
+            r#"<div class="changelog-contributors"><h2>Contributors</h2><ul>{contributors}</ul></div>"#
+        );
+
+        for commits in by_month.values().rev() {
+            let month = commits[0].date.format("%B %Y");
+            html.push_str(&format!(
+                r#"<div class="changelog-month"><h2>{month}</h2><ul>"#
+            ));
+            for commit in commits {
+                let links = commit
+                    .files
+                    .iter()
+                    .filter_map(|file| by_source.get(file.as_path()))
+                    .map(|page| {
+                        format!(
+                            r#"<a href="{base_path}{}">{}</a>"#,
+                            page.url,
+                            escape_html(&page.title)
+                        )
+                    })
+                    .collect::<Vec<_>>();
+                let pages = if links.is_empty() {
+                    String::new()
+                } else {
+                    format!(
+                        r#" <span class="changelog-pages">{}</span>"#,
+                        links.join(", ")
+                    )
+                };
+                html.push_str(&format!(
+                    r#"<li><time class="changelog-date" datetime="{}">{}</time> <span class="changelog-subject">{}</span> <span class="changelog-author">{}</span>{pages}</li>"#,
+                    commit.date.format("%Y-%m-%d"),
+                    commit.date.format("%m-%d"),
+                    escape_html(&commit.subject),
+                    escape_html(&commit.author),
+                ));
+            }
+            html.push_str("</ul></div>");
+        }
+
+        html
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn log() -> String {
+        [
+            "\u{1e}c3\u{1f}Alice\u{1f}2024-03-05T10:00:00+01:00\u{1f}Fix <typo>\n\nposts/hello.md\nposts/hello/cover.png\n",
+            "\u{1e}b2\u{1f}Bob\u{1f}2024-03-01T09:00:00+00:00\u{1f}Add about page\n\nabout.md\n",
+            "\u{1e}a1\u{1f}Alice\u{1f}2024-02-10T08:00:00+00:00\u{1f}First post\n\nposts/hello.md\n",
+        ]
+        .concat()
+    }
+
+    #[test]
+    fn test_parse() {
+        let changelog = Changelog::parse(&log());
+        let commits = changelog.commits();
+
+        assert_eq!(commits.len(), 3);
+        assert_eq!(commits[0].hash, "c3");
+        assert_eq!(commits[0].subject, "Fix <typo>");
+        assert_eq!(
+            commits[0].files,
+            vec![
+                PathBuf::from("posts/hello.md"),
+                PathBuf::from("posts/hello/cover.png")
+            ]
+        );
+        assert_eq!(changelog.contributors(), vec![("Alice", 2), ("Bob", 1)]);
+    }
+
+    #[test]
+    fn test_html() {
+        let page = Page {
+            url: "/posts/hello".to_string(),
+            title: "Hello".to_string(),
+            description: None,
+            date: None,
+            updated: None,
+            expiry_date: None,
+            draft: false,
+            lang: "en".to_string(),
+            is_default_lang: true,
+            canonical_id: "posts/hello".to_string(),
+            tags: vec![],
+            categories: vec![],
+            content: String::new(),
+            summary: None,
+            reading_time: None,
+            word_count: None,
+            toc: vec![],
+            custom_js: vec![],
+            custom_css: vec![],
+            aliases: vec![],
+            template: None,
+            weight: 0,
+            pinned: false,
+            series: None,
+            content_id: None,
+            extra_meta: std::collections::BTreeMap::new(),
+            structured_data: None,
+            images: vec![],
+            event: None,
+            source_path: Some(PathBuf::from("posts/hello.md")),
+        };
+        let html = Changelog::parse(&log()).html([&page], "/blog");
+
+        let march = html.find("<h2>March 2024</h2>").unwrap();
+        let february = html.find("<h2>February 2024</h2>").unwrap();
+        assert!(march < february);
+        assert!(html.contains(
+            r#"<span class="changelog-subject">Fix &lt;typo&gt;</span> <span class="changelog-author">Alice</span> <span class="changelog-pages"><a href="/blog/posts/hello">Hello</a></span>"#
+        ));
+        assert!(html.contains(r#"<span class="changelog-subject">Add about page</span> <span class="changelog-author">Bob</span></li>"#));
+    }
+}
@@ -3,16 +3,28 @@
 //! Walks the content directory and collects all pages into a structured hierarchy.
 
 use std::{
-    collections::HashMap,
-    fs,
+    collections::{BTreeMap, HashMap},
+    fs, io,
     path::{Path, PathBuf},
+    sync::Arc,
+    time::{Duration, Instant},
 };
 
+use chrono::Utc;
 use rayon::prelude::*;
 use thiserror::Error;
 use tracing::{debug, info, warn};
-use typstify_core::{Config, ContentPath, ContentType, Page};
-use typstify_parser::ParserRegistry;
+use typstify_core::{
+    Config, ContentPath, ContentType, Page, config::PaginateBy, content::is_section_index,
+};
+use typstify_parser::{ParserRegistry, SyntaxHighlighter, shortcode};
+
+use crate::{
+    cache::BuildCache,
+    manifest::{BuildManifest, MANIFEST_FILE, content_hash},
+    sections::{Neighbors, SectionMeta, SectionTree},
+    shortcodes::ShortcodeTemplates,
+};
 
 /// Content collection errors.
 #[derive(Debug, Error)]
@@ -47,6 +59,22 @@ pub struct SiteContent {
 
     /// Translation groups (canonical_id -> [slugs]).
     pub translations: HashMap<String, Vec<String>>,
+
+    /// Series name -> page URLs, in reading order.
+    pub series: HashMap<String, Vec<String>>,
+
+    /// Metadata from `_index` files, by section URL (e.g. "/docs/guides",
+    /// "/zh/docs").
+    pub section_meta: HashMap<String, SectionMeta>,
+
+    /// Previous/next page of each page within its section.
+    pub neighbors: HashMap<String, Neighbors>,
+
+    /// Time spent reading and parsing each page, by URL.
+    pub parse_times: HashMap<String, Duration>,
+
+    /// Content files that failed to parse, with the error message.
+    pub failed: Vec<(PathBuf, String)>,
 }
 
 /// Index of taxonomy terms.
@@ -59,27 +87,90 @@ pub struct TaxonomyIndex {
     pub categories: HashMap<String, Vec<String>>,
 }
 
+/// Pages that differ from a previous build manifest.
+#[derive(Debug, Default)]
+pub struct ChangeSet {
+    /// Pages whose source file is new since the previous build.
+    pub added: Vec<Page>,
+
+    /// Pages whose source file content changed.
+    pub changed: Vec<Page>,
+
+    /// URLs of pages whose source file was removed (or is now a filtered draft).
+    pub removed: Vec<String>,
+
+    /// Manifest describing the current state of the content directory.
+    pub manifest: BuildManifest,
+}
+
+impl ChangeSet {
+    /// Whether nothing changed since the previous build.
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.added.is_empty() && self.changed.is_empty() && self.removed.is_empty()
+    }
+}
+
 /// Content collector that walks directories and parses files.
 #[derive(Debug)]
 pub struct ContentCollector {
     config: Config,
     parser: ParserRegistry,
     content_dir: PathBuf,
+    site_root: PathBuf,
+    shortcodes_digest: Option<String>,
+    syntaxes_digest: Option<String>,
 }
 
 impl ContentCollector {
     /// Create a new content collector.
+    ///
+    /// The site root, which `include` shortcode paths are relative to, is
+    /// the directory holding `content_dir`.
     #[must_use]
     pub fn new(config: Config, content_dir: impl Into<PathBuf>) -> Self {
+        let content_dir = content_dir.into();
+        let site_root = match content_dir.parent() {
+            Some(parent) if !parent.as_os_str().is_empty() => parent.to_path_buf(),
+            _ => PathBuf::from("."),
+        };
         Self {
+            parser: ParserRegistry::new()
+                .with_highlighter(SyntaxHighlighter::from_config(&config.build))
+                .with_timezone(config.timezone())
+                .with_markdown_config(config.markdown.clone())
+                .with_include_root(&site_root),
+            syntaxes_digest: syntaxes_digest(Path::new(&config.build.syntaxes_dir)),
             config,
-            parser: ParserRegistry::new(),
-            content_dir: content_dir.into(),
+            content_dir,
+            site_root,
+            shortcodes_digest: None,
         }
     }
 
+    /// Set the site-defined shortcodes available to content.
+    #[must_use]
+    pub fn with_shortcodes(mut self, shortcodes: ShortcodeTemplates) -> Self {
+        if shortcodes.is_empty() {
+            return self;
+        }
+        self.shortcodes_digest = Some(shortcodes.digest());
+        self.parser = std::mem::take(&mut self.parser).with_shortcodes(Arc::new(shortcodes));
+        self
+    }
+
     /// Collect all content from the content directory.
     pub fn collect(&self) -> Result<SiteContent> {
+        let (content, _) = self.collect_cached(&BuildCache::new(&self.config))?;
+        Ok(content)
+    }
+
+    /// Collect all content, reusing the pages in `cache` whose source is
+    /// unchanged instead of parsing them again.
+    ///
+    /// Also returns the parse cache for the next build: the pages used in
+    /// this one, so entries of deleted files are dropped.
+    pub fn collect_cached(&self, cache: &BuildCache) -> Result<(SiteContent, BuildCache)> {
         info!(dir = %self.content_dir.display(), "collecting content");
 
         // Find all content files
@@ -87,29 +178,57 @@ impl ContentCollector {
         info!(count = files.len(), "found content files");
 
         // Parse files in parallel
-        let pages: Vec<_> = files
+        let results: Vec<_> = files
             .par_iter()
-            .filter_map(|path| {
-                match self.parse_file(path) {
-                    Ok(page) => {
-                        // Filter drafts unless configured to include them
-                        if page.draft && !self.config.build.drafts {
-                            debug!(url = %page.url, "skipping draft");
-                            None
-                        } else {
-                            Some(page)
-                        }
-                    }
-                    Err(e) => {
-                        warn!(path = %path.display(), error = %e, "failed to parse file");
-                        None
-                    }
-                }
+            .map(|path| {
+                let start = Instant::now();
+                let result = self.parse_cached(path, cache);
+                (path, result, start.elapsed())
             })
             .collect();
 
+        let mut failed = Vec::new();
+        let mut parsed = Vec::with_capacity(results.len());
+        for (path, result, elapsed) in results {
+            match result {
+                Ok(entry) => parsed.push((entry, elapsed)),
+                Err(e) => {
+                    warn!(path = %path.display(), error = %e, "failed to parse file");
+                    let message = match e {
+                        CollectorError::Parse { message, .. } => message,
+                        e => e.to_string(),
+                    };
+                    failed.push((path.clone(), message));
+                }
+            }
+        }
+
+        let now = Utc::now();
+        let mut next = BuildCache::new(&self.config);
+        let mut pages = Vec::with_capacity(parsed.len());
+        let mut parse_times = HashMap::with_capacity(parsed.len());
+        let mut section_meta = HashMap::new();
+        for ((key, hash, dependencies, page), elapsed) in parsed {
+            if let Some(reason) = self.config.skip_reason(&page, now) {
+                debug!(url = %page.url, reason, "skipping page");
+            } else if page.source_path.as_deref().is_some_and(is_section_index) {
+                section_meta.insert(page.url.clone(), SectionMeta::from_page(&page));
+            } else {
+                let mut page = page.clone();
+                self.merge_terms(&mut page);
+                parse_times.insert(page.url.clone(), elapsed);
+                pages.push(page);
+            }
+            next.insert_parsed(key, hash, dependencies, page);
+        }
+
         // Build site content structure
-        let mut content = SiteContent::default();
+        let mut content = SiteContent {
+            failed,
+            parse_times,
+            section_meta,
+            ..SiteContent::default()
+        };
 
         for page in pages {
             let url = page.url.clone();
@@ -143,6 +262,15 @@ impl ContentCollector {
                     .push(url.clone());
             }
 
+            // Index series
+            if let Some(series) = &page.series {
+                content
+                    .series
+                    .entry(series.clone())
+                    .or_default()
+                    .push(url.clone());
+            }
+
             // Index translations
             if !page.canonical_id.is_empty() {
                 content
@@ -155,6 +283,15 @@ impl ContentCollector {
             content.pages.insert(url, page);
         }
 
+        for urls in content.series.values_mut() {
+            urls.sort_by(|a, b| series_order(&content.pages[a], &content.pages[b]));
+        }
+
+        // Sections are keyed by parent path, which includes the language
+        // prefix, so one tree links pages of every language
+        content.neighbors =
+            SectionTree::build(content.pages.values(), "").neighbors(&content.pages);
+
         info!(
             pages = content.pages.len(),
             sections = content.sections.len(),
@@ -163,7 +300,7 @@ impl ContentCollector {
             "content collection complete"
         );
 
-        Ok(content)
+        Ok((content, next))
     }
 
     /// Find all content files recursively.
@@ -205,12 +342,169 @@ impl ContentCollector {
         Ok(())
     }
 
-    /// Parse a single content file into a Page.
-    fn parse_file(&self, path: &Path) -> Result<Page> {
-        debug!(path = %path.display(), "parsing file");
+    /// Compute the build manifest for the current content directory.
+    ///
+    /// Hashes every content file without parsing it.
+    pub fn manifest(&self) -> Result<BuildManifest> {
+        let mut manifest = BuildManifest::new();
 
-        // Read file content
-        let content = fs::read_to_string(path)?;
+        for path in self.find_content_files()? {
+            let bytes = fs::read(&path)?;
+            let (key, url) = self.manifest_key(&path)?;
+            manifest.insert(key, content_hash(&bytes), url);
+        }
+
+        Ok(manifest)
+    }
+
+    /// The manifest the last build recorded in `build.cache_dir`, empty if
+    /// there is none; what [`collect_changed`](Self::collect_changed)
+    /// compares against.
+    #[must_use]
+    pub fn previous_manifest(&self) -> BuildManifest {
+        BuildManifest::load(&Path::new(&self.config.build.cache_dir).join(MANIFEST_FILE))
+    }
+
+    /// Collect only the pages that changed since `previous`.
+    ///
+    /// Files whose hash matches the previous manifest are not parsed. Files
+    /// that fail to parse are left out of the returned manifest so they are
+    /// retried on the next build.
+    pub fn collect_changed(&self, previous: &BuildManifest) -> Result<ChangeSet> {
+        let files = self.find_content_files()?;
+        let now = Utc::now();
+        let mut changes = ChangeSet::default();
+
+        for path in files {
+            let bytes = fs::read(&path)?;
+            let hash = content_hash(&bytes);
+            let (key, url) = self.manifest_key(&path)?;
+
+            let prev = previous.get(&key);
+            if prev.is_some_and(|entry| entry.hash == hash) {
+                changes.manifest.insert(key, hash, url);
+                continue;
+            }
+
+            let source = String::from_utf8_lossy(&bytes);
+            let mut page = match self.parse_source(&source, &path) {
+                Ok((page, _)) => page,
+                Err(e) => {
+                    warn!(path = %path.display(), error = %e, "failed to parse file");
+                    continue;
+                }
+            };
+            changes.manifest.insert(key, hash, url);
+            if is_section_index(&path) {
+                // Section metadata, not a page
+                continue;
+            }
+            self.merge_terms(&mut page);
+
+            if let Some(reason) = self.config.skip_reason(&page, now) {
+                debug!(url = %page.url, reason, "skipping page");
+                if let Some(entry) = prev {
+                    changes.removed.push(entry.url.clone());
+                }
+            } else if prev.is_some() {
+                changes.changed.push(page);
+            } else {
+                changes.added.push(page);
+            }
+        }
+
+        for (key, entry) in &previous.files {
+            if !changes.manifest.files.contains_key(key) {
+                changes.removed.push(entry.url.clone());
+            }
+        }
+
+        info!(
+            added = changes.added.len(),
+            changed = changes.changed.len(),
+            removed = changes.removed.len(),
+            "change detection complete"
+        );
+
+        Ok(changes)
+    }
+
+    /// Replace the tag and category aliases of `page` with their canonical
+    /// terms, dropping the duplicates this creates.
+    fn merge_terms(&self, page: &mut Page) {
+        let taxonomies = &self.config.taxonomies;
+        for (terms, settings) in [
+            (&mut page.tags, &taxonomies.tags),
+            (&mut page.categories, &taxonomies.categories),
+        ] {
+            if settings.aliases.is_empty() {
+                continue;
+            }
+            let mut merged: Vec<String> = Vec::with_capacity(terms.len());
+            for term in terms.drain(..) {
+                let term = settings.canonical(&term).to_string();
+                if !merged.contains(&term) {
+                    merged.push(term);
+                }
+            }
+            *terms = merged;
+        }
+    }
+
+    /// Manifest key (content-relative path) and URL for a content file.
+    fn manifest_key(&self, path: &Path) -> Result<(String, String)> {
+        let relative_path = path.strip_prefix(&self.content_dir).unwrap_or(path);
+        let content_path =
+            ContentPath::from_path(relative_path, &self.config.site.default_language)
+                .ok_or_else(|| CollectorError::InvalidPath(path.to_path_buf()))?;
+        let key = relative_path.to_string_lossy().replace('\\', "/");
+        Ok((key, content_path.url_path()))
+    }
+
+    /// Parse a single content file into a Page, or take it from `cache` if
+    /// the file and the files it was parsed from are unchanged. Returns the
+    /// file's cache key, hash and dependencies with it.
+    fn parse_cached(
+        &self,
+        path: &Path,
+        cache: &BuildCache,
+    ) -> Result<(String, String, Vec<PathBuf>, Page)> {
+        let bytes = fs::read(path)?;
+        // Pages are parsed with the shortcodes and the custom syntaxes, so
+        // they are part of the hash
+        let mut input = bytes.clone();
+        for digest in [&self.shortcodes_digest, &self.syntaxes_digest]
+            .into_iter()
+            .flatten()
+        {
+            input.extend_from_slice(digest.as_bytes());
+        }
+        // So are the files it includes: editing one reparses the page
+        input.extend_from_slice(
+            includes_digest(&String::from_utf8_lossy(&bytes), &self.site_root).as_bytes(),
+        );
+        let source_hash = content_hash(&input);
+        let (key, _) = self.manifest_key(path)?;
+
+        // And the files Typst read when it was last compiled
+        let hash = dependencies_hash(&source_hash, cache.dependencies(&key));
+        if let Some(page) = cache.parsed(&key, &hash) {
+            debug!(path = %path.display(), "using cached page");
+            let dependencies = cache.dependencies(&key).to_vec();
+            return Ok((key, hash, dependencies, page.clone()));
+        }
+
+        let source =
+            String::from_utf8(bytes).map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?;
+        let (page, dependencies) = self.parse_source(&source, path)?;
+        let hash = dependencies_hash(&source_hash, &dependencies);
+        Ok((key, hash, dependencies, page))
+    }
+
+    /// Parse already-read source content of a file into a Page, returning
+    /// the other files it was parsed from with it.
+    fn parse_source(&self, content: &str, path: &Path) -> Result<(Page, Vec<PathBuf>)> {
+        debug!(path = %path.display(), "parsing file");
 
         // Parse content path to extract slug and language
         let relative_path = path.strip_prefix(&self.content_dir).unwrap_or(path);
@@ -219,15 +513,17 @@ impl ContentCollector {
                 .ok_or_else(|| CollectorError::InvalidPath(path.to_path_buf()))?;
 
         // Parse content using appropriate parser
+        let typography = self.config.typography_for_language(&content_path.lang);
         let parsed = self
             .parser
-            .parse(&content, path)
+            .parse_with_typography(content, path, typography)
             .map_err(|e| CollectorError::Parse {
                 path: path.to_path_buf(),
                 message: e.to_string(),
             })?;
 
-        Ok(Page::from_parsed(parsed, &content_path))
+        let dependencies = parsed.dependencies.clone();
+        Ok((Page::from_parsed(parsed, &content_path), dependencies))
     }
 
     /// Get pages sorted by date (newest first).
@@ -242,7 +538,7 @@ impl ContentCollector {
         pages
     }
 
-    /// Get pages for a specific section, sorted by date.
+    /// Get pages for a specific section, in list order.
     pub fn section_pages<'a>(content: &'a SiteContent, section: &str) -> Vec<&'a Page> {
         let mut pages: Vec<_> = content
             .sections
@@ -250,16 +546,11 @@ impl ContentCollector {
             .map(|urls| urls.iter().filter_map(|u| content.pages.get(u)).collect())
             .unwrap_or_default();
 
-        pages.sort_by(|a, b| match (&b.date, &a.date) {
-            (Some(b_date), Some(a_date)) => b_date.cmp(a_date),
-            (Some(_), None) => std::cmp::Ordering::Less,
-            (None, Some(_)) => std::cmp::Ordering::Greater,
-            (None, None) => a.title.cmp(&b.title),
-        });
+        pages.sort_by(|a, b| listing_order(a, b));
         pages
     }
 
-    /// Get pages for a taxonomy term, sorted by date.
+    /// Get pages for a taxonomy term, in list order.
     pub fn taxonomy_pages<'a>(
         content: &'a SiteContent,
         taxonomy: &str,
@@ -275,14 +566,90 @@ impl ContentCollector {
             .map(|u| u.iter().filter_map(|url| content.pages.get(url)).collect())
             .unwrap_or_default();
 
-        pages.sort_by(|a, b| match (&b.date, &a.date) {
+        pages.sort_by(|a, b| listing_order(a, b));
+        pages
+    }
+}
+
+/// Digest of the files in the syntaxes directory `dir`, if it exists.
+fn syntaxes_digest(dir: &Path) -> Option<String> {
+    let mut paths: Vec<PathBuf> = fs::read_dir(dir)
+        .ok()?
+        .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+        .collect();
+    paths.sort();
+    let mut input = Vec::new();
+    for path in paths {
+        if let Ok(bytes) = fs::read(&path) {
+            input.extend_from_slice(path.file_name().unwrap_or_default().as_encoded_bytes());
+            input.extend_from_slice(&bytes);
+        }
+    }
+    Some(content_hash(&input))
+}
+
+/// Digest of the files included by the `include` shortcodes in `source`,
+/// as resolved against the site root `root` when the page is parsed.
+/// Missing and rejected files are hashed as absent, so the page is parsed
+/// again once they appear.
+fn includes_digest(source: &str, root: &Path) -> String {
+    let mut input = Vec::new();
+    for file in shortcode::includes(source) {
+        input.extend_from_slice(file.as_bytes());
+        input.push(0);
+        if let Ok(bytes) = shortcode::resolve_include(root, &file)
+            .and_then(|path| fs::read(path).map_err(|e| e.to_string()))
+        {
+            input.push(1);
+            input.extend_from_slice(&bytes);
+        }
+        input.push(0);
+    }
+    content_hash(&input)
+}
+
+/// Hash of a page source hashed as `source_hash` and the files in
+/// `dependencies`. Missing files are hashed as absent, so the page is parsed
+/// again once they appear.
+fn dependencies_hash(source_hash: &str, dependencies: &[PathBuf]) -> String {
+    if dependencies.is_empty() {
+        return source_hash.to_string();
+    }
+    let mut input = source_hash.as_bytes().to_vec();
+    for path in dependencies {
+        input.extend_from_slice(path.as_os_str().as_encoded_bytes());
+        input.push(0);
+        if let Ok(bytes) = fs::read(path) {
+            input.push(1);
+            input.extend_from_slice(&bytes);
+        }
+        input.push(0);
+    }
+    content_hash(&input)
+}
+
+/// Order of pages on list pages: pinned pages first, then by weight
+/// (lowest first), then by date (newest first, undated last), then by title.
+pub fn listing_order(a: &Page, b: &Page) -> std::cmp::Ordering {
+    b.pinned
+        .cmp(&a.pinned)
+        .then_with(|| a.weight.cmp(&b.weight))
+        .then_with(|| match (&b.date, &a.date) {
             (Some(b_date), Some(a_date)) => b_date.cmp(a_date),
             (Some(_), None) => std::cmp::Ordering::Less,
             (None, Some(_)) => std::cmp::Ordering::Greater,
-            (None, None) => a.title.cmp(&b.title),
-        });
-        pages
-    }
+            (None, None) => std::cmp::Ordering::Equal,
+        })
+        .then_with(|| a.title.cmp(&b.title))
+}
+
+/// Order of the parts of a series: by weight (lowest first), then by date
+/// (oldest first), then by title.
+pub fn series_order(a: &Page, b: &Page) -> std::cmp::Ordering {
+    a.weight
+        .cmp(&b.weight)
+        .then_with(|| a.date.cmp(&b.date))
+        .then_with(|| a.title.cmp(&b.title))
 }
 
 /// Paginate a slice of items.
@@ -298,15 +665,87 @@ pub fn paginate<T>(items: &[T], page: usize, per_page: usize) -> (&[T], usize) {
     }
 }
 
+/// A page of a listing split by time window.
+#[derive(Debug, Clone)]
+pub struct TimeWindow<'a> {
+    /// URL segment of the window (e.g. `2024-03`, `2024-W09`).
+    pub key: String,
+
+    /// Display label (e.g. "March 2024").
+    pub label: String,
+
+    /// Pages in the window, in their original order.
+    pub pages: Vec<&'a Page>,
+}
+
+/// Split pages into time windows (in the site timezone), newest first,
+/// labelled in `lang`. Undated pages join the newest window.
+/// [`PaginateBy::Count`] yields a single window.
+pub fn paginate_by_window<'a>(
+    pages: &[&'a Page],
+    by: PaginateBy,
+    config: &Config,
+    lang: &str,
+) -> Vec<TimeWindow<'a>> {
+    let tz = config.timezone();
+    let mut windows: BTreeMap<String, TimeWindow<'a>> = BTreeMap::new();
+    let mut undated = Vec::new();
+
+    for page in pages {
+        let Some(date) = page.date else {
+            undated.push(*page);
+            continue;
+        };
+        let day = date.with_timezone(&tz).date_naive();
+        let (key, label) = match by {
+            PaginateBy::Count => (String::new(), String::new()),
+            PaginateBy::Day => (
+                day.format("%Y-%m-%d").to_string(),
+                config.format_date(date, lang),
+            ),
+            PaginateBy::Week => (
+                day.format("%G-W%V").to_string(),
+                config.format_date_as(date, config.week_format_for_language(lang), lang),
+            ),
+            PaginateBy::Month => (
+                day.format("%Y-%m").to_string(),
+                config.format_date_as(date, "%B %Y", lang),
+            ),
+            PaginateBy::Year => (day.format("%Y").to_string(), day.format("%Y").to_string()),
+        };
+        windows
+            .entry(key.clone())
+            .or_insert_with(|| TimeWindow {
+                key,
+                label,
+                pages: Vec::new(),
+            })
+            .pages
+            .push(page);
+    }
+
+    let mut windows: Vec<_> = windows.into_values().rev().collect();
+    match windows.first_mut() {
+        Some(newest) => newest.pages.extend(undated),
+        None if !undated.is_empty() => windows.push(TimeWindow {
+            key: String::new(),
+            label: String::new(),
+            pages: undated,
+        }),
+        None => {}
+    }
+    windows
+}
+
 #[cfg(test)]
 mod tests {
     use std::collections::HashMap;
 
     use super::*;
 
-    #[allow(dead_code)]
     fn test_config() -> Config {
         Config {
+            theme: None,
             site: typstify_core::config::SiteConfig {
                 title: "Test Site".to_string(),
                 host: "https://example.com".to_string(),
@@ -314,6 +753,7 @@ mod tests {
                 default_language: "en".to_string(),
                 description: None,
                 author: None,
+                timezone: None,
             },
             languages: HashMap::new(),
             build: typstify_core::config::BuildConfig {
@@ -324,6 +764,21 @@ mod tests {
             rss: typstify_core::config::RssConfig::default(),
             robots: typstify_core::config::RobotsConfig::default(),
             taxonomies: typstify_core::config::TaxonomyConfig::default(),
+            dates: typstify_core::config::DatesConfig::default(),
+            css: typstify_core::config::CssConfig::default(),
+            images: typstify_core::config::ImagesConfig::default(),
+            markdown: typstify_core::config::MarkdownConfig::default(),
+            typography: typstify_core::config::TypographyConfig::default(),
+            sections: std::collections::HashMap::new(),
+            check: typstify_core::config::CheckConfig::default(),
+            webmentions: typstify_core::config::WebmentionsConfig::default(),
+            identity: typstify_core::config::IdentityConfig::default(),
+            content: typstify_core::config::ContentConfig::default(),
+            ping: typstify_core::config::PingConfig::default(),
+            toc: typstify_core::config::TocConfig::default(),
+            math: typstify_core::config::MathConfig::default(),
+            mermaid: typstify_core::config::MermaidConfig::default(),
+            fmt: typstify_core::config::FmtConfig::default(),
         }
     }
 
@@ -345,6 +800,67 @@ mod tests {
         assert!(page5.is_empty());
     }
 
+    #[test]
+    fn test_paginate_by_window_labels() {
+        let path = ContentPath::from_path(Path::new("shorts/a.md"), "en").unwrap();
+        let mut page = Page::from_parsed(
+            typstify_core::content::ParsedContent {
+                frontmatter: typstify_core::Frontmatter::default(),
+                html: String::new(),
+                raw: String::new(),
+                toc: vec![],
+                dependencies: vec![],
+            },
+            &path,
+        );
+        page.date = Some("2024-03-01T10:00:00Z".parse().unwrap());
+        let pages = [&page];
+
+        let mut config = test_config();
+        config.languages.insert(
+            "fr".to_string(),
+            typstify_core::config::LanguageConfig {
+                date_format: Some("%-d %B %Y".to_string()),
+                month_names: Some(
+                    [
+                        "janvier",
+                        "février",
+                        "mars",
+                        "avril",
+                        "mai",
+                        "juin",
+                        "juillet",
+                        "août",
+                        "septembre",
+                        "octobre",
+                        "novembre",
+                        "décembre",
+                    ]
+                    .map(String::from)
+                    .to_vec(),
+                ),
+                week_format: Some("Semaine %V, %G".to_string()),
+                ..Default::default()
+            },
+        );
+        let label = |by, lang| {
+            let windows = paginate_by_window(&pages, by, &config, lang);
+            (windows[0].key.clone(), windows[0].label.clone())
+        };
+
+        assert_eq!(
+            label(PaginateBy::Day, "en"),
+            ("2024-03-01".to_string(), "March 01, 2024".to_string())
+        );
+        assert_eq!(label(PaginateBy::Day, "fr").1, "1 mars 2024");
+        assert_eq!(
+            label(PaginateBy::Week, "en"),
+            ("2024-W09".to_string(), "Week 9, 2024".to_string())
+        );
+        assert_eq!(label(PaginateBy::Week, "fr").1, "Semaine 09, 2024");
+        assert_eq!(label(PaginateBy::Month, "fr").1, "mars 2024");
+    }
+
     #[test]
     fn test_taxonomy_index() {
         let mut index = TaxonomyIndex::default();
@@ -361,6 +877,263 @@ mod tests {
         assert!(!index.tags.contains_key("python"));
     }
 
+    #[test]
+    fn test_collect_changed() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let write = |name: &str, title: &str| {
+            fs::write(
+                dir.path().join(name),
+                format!("---\ntitle: \"{title}\"\n---\n\nBody"),
+            )
+            .unwrap();
+        };
+        write("a.md", "A");
+        write("b.md", "B");
+
+        let cache_dir = tempfile::TempDir::new().unwrap();
+        let mut config = test_config();
+        config.build.cache_dir = cache_dir.path().to_string_lossy().into_owned();
+        let collector = ContentCollector::new(config, dir.path());
+
+        // Everything is new without a previous build
+        let first = collector
+            .collect_changed(&collector.previous_manifest())
+            .unwrap();
+        assert_eq!(first.added.len(), 2);
+        assert!(first.changed.is_empty());
+        assert!(first.removed.is_empty());
+        assert_eq!(first.manifest, collector.manifest().unwrap());
+
+        // Nothing changed since the manifest the build records
+        first
+            .manifest
+            .write_to(&cache_dir.path().join(MANIFEST_FILE))
+            .unwrap();
+        let unchanged = collector
+            .collect_changed(&collector.previous_manifest())
+            .unwrap();
+        assert!(unchanged.is_empty());
+
+        // Modify one, remove one, add one
+        write("a.md", "A2");
+        fs::remove_file(dir.path().join("b.md")).unwrap();
+        write("c.md", "C");
+
+        let changes = collector
+            .collect_changed(&collector.previous_manifest())
+            .unwrap();
+        assert_eq!(changes.changed.len(), 1);
+        assert_eq!(changes.changed[0].title, "A2");
+        assert_eq!(changes.added.len(), 1);
+        assert_eq!(changes.added[0].url, "/c");
+        assert_eq!(changes.removed, vec!["/b".to_string()]);
+        assert!(changes.manifest.get("b.md").is_none());
+    }
+
+    #[test]
+    fn test_collect_scheduled_and_expired() {
+        let dir = tempfile::TempDir::new().unwrap();
+        for (name, frontmatter) in [
+            ("past.md", "date: 2001-01-01"),
+            ("future.md", "date: 2999-01-01"),
+            ("expired.md", "date: 2001-01-01\nexpiry_date: 2002-01-01"),
+            ("expiring.md", "expiry_date: 2999-01-01"),
+        ] {
+            fs::write(
+                dir.path().join(name),
+                format!("---\ntitle: \"{name}\"\n{frontmatter}\n---\n\nBody"),
+            )
+            .unwrap();
+        }
+        let urls = |config: Config| {
+            let content = ContentCollector::new(config, dir.path()).collect().unwrap();
+            let mut urls: Vec<String> = content.pages.into_keys().collect();
+            urls.sort();
+            urls
+        };
+
+        assert_eq!(urls(test_config()), ["/expiring", "/past"]);
+        let mut config = test_config();
+        config.build.future = true;
+        assert_eq!(urls(config), ["/expiring", "/future", "/past"]);
+
+        let changes = ContentCollector::new(test_config(), dir.path())
+            .collect_changed(&BuildManifest::new())
+            .unwrap();
+        assert_eq!(changes.added.len(), 2);
+    }
+
+    #[test]
+    fn test_collect_merges_tag_aliases() {
+        let dir = tempfile::TempDir::new().unwrap();
+        fs::write(
+            dir.path().join("a.md"),
+            "---\ntitle: A\ntags: [rustlang, rust, web]\n---\n\nBody",
+        )
+        .unwrap();
+        fs::write(
+            dir.path().join("b.md"),
+            "---\ntitle: B\ntags: [rustlang]\n---\n\nBody",
+        )
+        .unwrap();
+        let mut config = test_config();
+        config
+            .taxonomies
+            .tags
+            .aliases
+            .insert("rustlang".to_string(), "rust".to_string());
+
+        let content = ContentCollector::new(config, dir.path()).collect().unwrap();
+        assert_eq!(content.pages["/a"].tags, ["rust", "web"]);
+        let mut rust = content.taxonomies.tags["rust"].clone();
+        rust.sort();
+        assert_eq!(rust, ["/a", "/b"]);
+        assert!(!content.taxonomies.tags.contains_key("rustlang"));
+    }
+
+    #[test]
+    fn test_collect_series() {
+        let dir = tempfile::TempDir::new().unwrap();
+        for (name, date) in [
+            ("two", "2026-01-02"),
+            ("one", "2026-01-01"),
+            ("three", "2026-01-03"),
+        ] {
+            fs::write(
+                dir.path().join(format!("{name}.md")),
+                format!("---\ntitle: {name}\ndate: {date}\nseries: Intro\n---\n\nBody"),
+            )
+            .unwrap();
+        }
+        fs::write(
+            dir.path().join("other.md"),
+            "---\ntitle: Other\n---\n\nBody",
+        )
+        .unwrap();
+
+        let content = ContentCollector::new(test_config(), dir.path())
+            .collect()
+            .unwrap();
+        assert_eq!(content.series.len(), 1);
+        assert_eq!(content.series["Intro"], ["/one", "/two", "/three"]);
+        assert_eq!(content.pages["/one"].series.as_deref(), Some("Intro"));
+    }
+
+    #[test]
+    fn test_collect_neighbors() {
+        let dir = tempfile::TempDir::new().unwrap();
+        for (path, date) in [
+            ("posts/b.md", "2026-01-02"),
+            ("posts/a.md", "2026-01-01"),
+            ("notes/c.md", "2026-01-03"),
+        ] {
+            let path = dir.path().join(path);
+            fs::create_dir_all(path.parent().unwrap()).unwrap();
+            fs::write(path, format!("---\ntitle: T\ndate: {date}\n---\n\nBody")).unwrap();
+        }
+
+        let content = ContentCollector::new(test_config(), dir.path())
+            .collect()
+            .unwrap();
+        assert_eq!(content.neighbors["/posts/a"].prev, None);
+        assert_eq!(
+            content.neighbors["/posts/a"].next.as_deref(),
+            Some("/posts/b")
+        );
+        assert_eq!(content.neighbors["/posts/b"].next, None);
+        assert_eq!(content.neighbors["/notes/c"], Neighbors::default());
+    }
+
+    #[test]
+    fn test_collect_section_meta() {
+        let dir = tempfile::TempDir::new().unwrap();
+        for (path, source) in [
+            ("docs/guide/intro.md", "---\ntitle: Intro\n---\n\nBody"),
+            (
+                "docs/guide/_index.md",
+                "---\ntitle: User Guide\ndescription: Start here\nweight: 2\ntemplate: guide\n---\n",
+            ),
+            ("docs/guide/_index.zh.md", "---\ntitle: 指南\n---\n"),
+        ] {
+            let path = dir.path().join(path);
+            fs::create_dir_all(path.parent().unwrap()).unwrap();
+            fs::write(path, source).unwrap();
+        }
+
+        let content = ContentCollector::new(test_config(), dir.path())
+            .collect()
+            .unwrap();
+        assert_eq!(
+            content.pages.keys().collect::<Vec<_>>(),
+            vec!["/docs/guide/intro"]
+        );
+        assert_eq!(
+            content.section_meta["/docs/guide"],
+            SectionMeta {
+                lang: "en".to_string(),
+                title: Some("User Guide".to_string()),
+                description: Some("Start here".to_string()),
+                weight: 2,
+                template: Some("guide".to_string()),
+            }
+        );
+        assert_eq!(content.section_meta["/zh/docs/guide"].lang, "zh");
+    }
+
+    #[test]
+    fn test_collect_cached_tracks_includes() {
+        let site = tempfile::tempdir().unwrap();
+        let content_dir = site.path().join("content");
+        fs::create_dir(&content_dir).unwrap();
+        fs::write(site.path().join("snippet.rs"), "fn one() {}\n").unwrap();
+        fs::write(
+            content_dir.join("code.md"),
+            "---\ntitle: Code\n---\n\n{{< include file=\"snippet.rs\" >}}\n",
+        )
+        .unwrap();
+
+        let collector = ContentCollector::new(test_config(), &content_dir);
+        let (content, cache) = collector
+            .collect_cached(&BuildCache::new(&test_config()))
+            .unwrap();
+        assert!(content.pages["/code"].content.contains("one"));
+
+        // Editing only the included file parses the page again
+        fs::write(site.path().join("snippet.rs"), "fn two() {}\n").unwrap();
+        let (content, _) = collector.collect_cached(&cache).unwrap();
+        assert!(content.pages["/code"].content.contains("two"));
+    }
+
+    #[test]
+    fn test_collect_cached_tracks_typst_imports() {
+        let dir = tempfile::TempDir::new().unwrap();
+        fs::create_dir(dir.path().join("lib")).unwrap();
+        fs::write(dir.path().join("lib/answer.typ"), "#let answer = 42").unwrap();
+        fs::write(
+            dir.path().join("post.typ"),
+            "// typstify:frontmatter\n// title: \"Post\"\n\n#import \"lib/answer.typ\": answer\nThe answer is #answer.\n",
+        )
+        .unwrap();
+
+        let collector = ContentCollector::new(test_config(), dir.path());
+        let (content, cache) = collector
+            .collect_cached(&BuildCache::new(&test_config()))
+            .unwrap();
+        assert!(content.pages["/post"].content.contains("The answer is 42."));
+
+        // Unchanged, the page comes from the cache
+        let (_, cache) = collector.collect_cached(&cache).unwrap();
+        assert_eq!(
+            cache.dependencies("post.typ"),
+            [dir.path().join("lib/answer.typ")]
+        );
+
+        // Editing only the imported file parses the page again
+        fs::write(dir.path().join("lib/answer.typ"), "#let answer = 43").unwrap();
+        let (content, _) = collector.collect_cached(&cache).unwrap();
+        assert!(content.pages["/post"].content.contains("The answer is 43."));
+    }
+
     #[test]
     fn test_site_content_default() {
         let content = SiteContent::default();
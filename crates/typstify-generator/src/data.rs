@@ -0,0 +1,319 @@
+//! Build-time JSON data from the `data` shortcode.
+//!
+//! The shortcode leaves a `<div class="data" data-url=".." data-template="..">`
+//! placeholder in the page. At build time the URL is fetched, or read from
+//! the cache under `build.cache_dir` while younger than `build.data_ttl`, and
+//! the placeholder is replaced by the named template rendered with the JSON
+//! as variables: `{"owner": {"login": "x"}}` becomes `owner.login` and array
+//! items are numbered (`0.tag_name`). When a fetch fails the cached copy is
+//! used regardless of its age.
+
+use std::{
+    fs,
+    path::{Path, PathBuf},
+    time::{Duration, SystemTime},
+};
+
+use serde_json::Value;
+use thiserror::Error;
+use tracing::{debug, warn};
+use typstify_core::unescape_html;
+
+use crate::{remote::url_hash, template::TemplateContext};
+
+/// Start of a data placeholder left by the shortcode.
+pub const MARKER: &str = r#"<div class="data" data-url=""#;
+
+/// Directory (relative to the build cache) holding fetched responses.
+pub const DATA_DIR: &str = "data";
+
+/// End of a data placeholder.
+const END: &str = "></div>";
+
+/// Request timeout.
+const TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Largest response that will be downloaded.
+const MAX_SIZE: u64 = 5 * 1024 * 1024;
+
+/// Data fetching errors.
+#[derive(Debug, Error)]
+pub enum DataError {
+    /// IO error.
+    #[error("IO error: {0}")]
+    Io(#[from] std::io::Error),
+
+    /// The URL could not be fetched and nothing is cached.
+    #[error("failed to fetch {url}: {message}")]
+    Fetch {
+        /// Requested URL.
+        url: String,
+        /// Error message.
+        message: String,
+    },
+}
+
+/// Result type for data operations.
+pub type Result<T> = std::result::Result<T, DataError>;
+
+/// Fetches JSON documents, caching them on disk.
+#[derive(Debug)]
+pub struct DataFetcher {
+    /// Cache directory for responses.
+    cache_dir: PathBuf,
+
+    /// How long a cached response is reused.
+    ttl: Duration,
+
+    /// HTTP client.
+    agent: ureq::Agent,
+}
+
+impl DataFetcher {
+    /// Create a fetcher caching responses in `cache_dir` for `ttl`.
+    pub fn new(cache_dir: impl Into<PathBuf>, ttl: Duration) -> Self {
+        let agent = ureq::Agent::config_builder()
+            .timeout_global(Some(TIMEOUT))
+            .build()
+            .into();
+
+        Self {
+            cache_dir: cache_dir.into(),
+            ttl,
+            agent,
+        }
+    }
+
+    /// The JSON document at `url`, from the cache while it is fresh.
+    pub fn fetch(&self, url: &str) -> Result<Value> {
+        let path = self.cache_dir.join(format!("{}.json", url_hash(url)));
+        if is_fresh(&path, self.ttl)
+            && let Some(value) = read_cached(&path)
+        {
+            debug!(url, "using cached data");
+            return Ok(value);
+        }
+
+        match self.download(url) {
+            Ok((bytes, value)) => {
+                fs::create_dir_all(&self.cache_dir)?;
+                fs::write(&path, bytes)?;
+                debug!(url, "fetched data");
+                Ok(value)
+            }
+            Err(message) => match read_cached(&path) {
+                Some(value) => {
+                    warn!(url, error = %message, "failed to fetch data, using cached copy");
+                    Ok(value)
+                }
+                None => Err(DataError::Fetch {
+                    url: url.to_string(),
+                    message,
+                }),
+            },
+        }
+    }
+
+    /// Download and parse `url`, returning the body and its JSON.
+    ///
+    /// GitHub API requests are authenticated with `GITHUB_TOKEN` when it is
+    /// set, for the higher rate limit.
+    fn download(&self, url: &str) -> std::result::Result<(Vec<u8>, Value), String> {
+        let mut request = self.agent.get(url).header("Accept", "application/json");
+        if url.starts_with("https://api.github.com/")
+            && let Ok(token) = std::env::var("GITHUB_TOKEN")
+            && !token.is_empty()
+        {
+            request = request.header("Authorization", format!("Bearer {token}"));
+        }
+        let bytes = request
+            .call()
+            .map_err(|e| e.to_string())?
+            .body_mut()
+            .with_config()
+            .limit(MAX_SIZE)
+            .read_to_vec()
+            .map_err(|e| e.to_string())?;
+        let value = serde_json::from_slice(&bytes).map_err(|e| format!("invalid JSON: {e}"))?;
+        Ok((bytes, value))
+    }
+}
+
+/// Whether the file at `path` was written less than `ttl` ago.
+fn is_fresh(path: &Path, ttl: Duration) -> bool {
+    fs::metadata(path)
+        .and_then(|meta| meta.modified())
+        .ok()
+        .and_then(|modified| SystemTime::now().duration_since(modified).ok())
+        .is_some_and(|age| age < ttl)
+}
+
+/// The cached JSON document at `path`, if readable.
+fn read_cached(path: &Path) -> Option<Value> {
+    serde_json::from_slice(&fs::read(path).ok()?).ok()
+}
+
+/// Replace every data placeholder in `html` with the output of `render`,
+/// called with the URL and template name.
+pub fn expand<E>(
+    html: &str,
+    mut render: impl FnMut(&str, &str) -> std::result::Result<String, E>,
+) -> std::result::Result<String, E> {
+    let mut output = String::with_capacity(html.len());
+    let mut rest = html;
+
+    while let Some(start) = rest.find(MARKER) {
+        let (before, placeholder) = rest.split_at(start);
+        output.push_str(before);
+
+        let attrs = &placeholder[MARKER.len()..];
+        let parsed = attrs.find(END).and_then(|end| {
+            let (url, template) = attrs[..end].split_once(r#"" data-template=""#)?;
+            Some((url, template.strip_suffix('"')?, end))
+        });
+        let Some((url, template, end)) = parsed else {
+            output.push_str(MARKER);
+            rest = attrs;
+            continue;
+        };
+
+        output.push_str(&render(&unescape_html(url), &unescape_html(template))?);
+        rest = &attrs[end + END.len()..];
+    }
+    output.push_str(rest);
+
+    Ok(output)
+}
+
+/// Template variables for a JSON document: object keys joined with `.`,
+/// array items by index, and a scalar document as `value`. `null`s are left
+/// out, so templates can mark them optional.
+#[must_use]
+pub fn context(value: &Value) -> TemplateContext {
+    let mut context = TemplateContext::new();
+    match value {
+        Value::Object(_) | Value::Array(_) => flatten(value, "", &mut context),
+        _ => flatten(value, "value", &mut context),
+    }
+    context
+}
+
+/// Insert `value` and its members under `prefix`.
+fn flatten(value: &Value, prefix: &str, context: &mut TemplateContext) {
+    let key = |name: &str| {
+        if prefix.is_empty() {
+            name.to_string()
+        } else {
+            format!("{prefix}.{name}")
+        }
+    };
+    match value {
+        Value::Null => {}
+        Value::Bool(b) => context.insert(prefix, b.to_string()),
+        Value::Number(n) => context.insert(prefix, n.to_string()),
+        Value::String(s) => context.insert(prefix, s.as_str()),
+        Value::Array(items) => {
+            for (i, item) in items.iter().enumerate() {
+                flatten(item, &key(&i.to_string()), context);
+            }
+        }
+        Value::Object(members) => {
+            for (name, member) in members {
+                flatten(member, &key(name), context);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        io::{Read, Write},
+        net::TcpListener,
+        thread,
+    };
+
+    use tempfile::TempDir;
+
+    use super::*;
+
+    /// Serve `body` as JSON to a single request, returning the URL.
+    fn serve_once(body: &'static str) -> String {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let url = format!("http://{}/repo.json", listener.local_addr().unwrap());
+        thread::spawn(move || {
+            let (mut stream, _) = listener.accept().unwrap();
+            let mut request = [0; 1024];
+            let _ = stream.read(&mut request);
+            let response = format!(
+                "HTTP/1.1 200 OK\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{body}",
+                body.len()
+            );
+            stream.write_all(response.as_bytes()).unwrap();
+        });
+        url
+    }
+
+    #[test]
+    fn test_fetch_caches() {
+        let dir = TempDir::new().unwrap();
+        let url = serve_once(r#"{"stargazers_count": 42}"#);
+
+        let fetcher = DataFetcher::new(dir.path(), Duration::from_secs(3600));
+        assert_eq!(fetcher.fetch(&url).unwrap()["stargazers_count"], 42);
+        // The server is gone: a fresh cache entry is used
+        assert_eq!(fetcher.fetch(&url).unwrap()["stargazers_count"], 42);
+
+        // A stale entry is used as a fallback when the fetch fails
+        let stale = DataFetcher::new(dir.path(), Duration::ZERO);
+        assert_eq!(stale.fetch(&url).unwrap()["stargazers_count"], 42);
+
+        let missing = format!("{url}?uncached");
+        assert!(matches!(
+            stale.fetch(&missing),
+            Err(DataError::Fetch { .. })
+        ));
+    }
+
+    #[test]
+    fn test_expand() {
+        let html = r#"<p>Stars:</p><div class="data" data-url="https://example.com/a?x=1&amp;y=2" data-template="stars"></div><p>end</p>"#;
+
+        let mut calls = Vec::new();
+        let expanded = expand(html, |url, template| {
+            calls.push((url.to_string(), template.to_string()));
+            Ok::<_, ()>("<b>42</b>".to_string())
+        })
+        .unwrap();
+
+        assert_eq!(
+            calls,
+            vec![(
+                "https://example.com/a?x=1&y=2".to_string(),
+                "stars".to_string()
+            )]
+        );
+        assert_eq!(expanded, "<p>Stars:</p><b>42</b><p>end</p>");
+    }
+
+    #[test]
+    fn test_context() {
+        let value: Value = serde_json::from_str(
+            r#"{"name": "typstify", "stars": 42, "archived": false, "license": null,
+                "owner": {"login": "longcipher"}, "topics": ["ssg", "typst"]}"#,
+        )
+        .unwrap();
+        let context = context(&value);
+
+        assert_eq!(context.get("name"), Some("typstify"));
+        assert_eq!(context.get("stars"), Some("42"));
+        assert_eq!(context.get("archived"), Some("false"));
+        assert_eq!(context.get("owner.login"), Some("longcipher"));
+        assert_eq!(context.get("topics.1"), Some("typst"));
+        assert!(!context.contains("license"));
+
+        let releases: Value = serde_json::from_str(r#"[{"tag_name": "v0.2.0"}]"#).unwrap();
+        assert_eq!(super::context(&releases).get("0.tag_name"), Some("v0.2.0"));
+        assert_eq!(super::context(&Value::from(7)).get("value"), Some("7"));
+    }
+}
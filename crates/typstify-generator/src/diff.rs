@@ -0,0 +1,269 @@
+//! Differences between two builds.
+//!
+//! Compares the HTML pages of two output directories, so that a preview of
+//! a change can list the pages it affects. Pages are compared semantically:
+//! whitespace between and within text, comments, attribute order, class
+//! order and `<br/>` versus `<br>` are ignored, so minifying or reformatting
+//! templates changes nothing; the contents of `<pre>`, `<textarea>`,
+//! `<script>` and `<style>` are compared as they are.
+
+use std::{
+    collections::BTreeSet,
+    fs,
+    path::{Path, PathBuf},
+};
+
+use serde::Serialize;
+use thiserror::Error;
+
+use crate::audit::{HtmlToken, scan_html};
+
+/// Diff errors.
+#[derive(Debug, Error)]
+pub enum DiffError {
+    /// An output directory could not be read.
+    #[error("failed to read {0}: {1}")]
+    Io(String, std::io::Error),
+}
+
+/// Result type for diff operations.
+pub type Result<T> = std::result::Result<T, DiffError>;
+
+/// Pages that differ between two builds, by URL path (relative to the
+/// output directory: `/posts/hello/` for `posts/hello/index.html`).
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+pub struct OutputDiff {
+    /// Pages only in the new build.
+    pub added: Vec<String>,
+
+    /// Pages only in the old build.
+    pub removed: Vec<String>,
+
+    /// Pages in both builds whose HTML differs.
+    pub changed: Vec<String>,
+}
+
+impl OutputDiff {
+    /// Compare the pages of the output directories `old` and `new`.
+    pub fn compare(old: &Path, new: &Path) -> Result<Self> {
+        let old_pages = html_files(old)?;
+        let new_pages = html_files(new)?;
+
+        let mut diff = Self::default();
+        for page in old_pages.difference(&new_pages) {
+            diff.removed.push(url_path(page));
+        }
+        for page in &new_pages {
+            if !old_pages.contains(page) {
+                diff.added.push(url_path(page));
+                continue;
+            }
+            let read = |dir: &Path| {
+                let path = dir.join(page);
+                fs::read_to_string(&path).map_err(|e| DiffError::Io(path.display().to_string(), e))
+            };
+            if !html_equivalent(&read(old)?, &read(new)?) {
+                diff.changed.push(url_path(page));
+            }
+        }
+        Ok(diff)
+    }
+
+    /// Prefix every URL with `base_url`, such as the address of a preview
+    /// deployment.
+    pub fn prefix_urls(&mut self, base_url: &str) {
+        let base_url = base_url.trim_end_matches('/');
+        for url in self
+            .added
+            .iter_mut()
+            .chain(&mut self.removed)
+            .chain(&mut self.changed)
+        {
+            url.insert_str(0, base_url);
+        }
+    }
+
+    /// Whether the builds have the same pages.
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.added.is_empty() && self.removed.is_empty() && self.changed.is_empty()
+    }
+}
+
+/// Whether the HTML documents `a` and `b` are the same, ignoring
+/// formatting.
+#[must_use]
+pub fn html_equivalent(a: &str, b: &str) -> bool {
+    normalize(a) == normalize(b)
+}
+
+/// A token of normalized HTML.
+#[derive(Debug, PartialEq, Eq)]
+enum Token {
+    Start(String, Vec<(String, String)>),
+    End(String),
+    Text(String),
+}
+
+/// The tokens of `html`, without formatting.
+fn normalize(html: &str) -> Vec<Token> {
+    let mut tokens = Vec::new();
+    // Open elements whose whitespace is significant
+    let mut verbatim = 0usize;
+
+    for token in scan_html(html) {
+        match token {
+            HtmlToken::Start {
+                name,
+                mut attrs,
+                self_closing,
+                ..
+            } => {
+                for (key, value) in &mut attrs {
+                    if key == "class" {
+                        let mut classes: Vec<&str> = value.split_whitespace().collect();
+                        classes.sort_unstable();
+                        *value = classes.join(" ");
+                    }
+                }
+                attrs.sort();
+                if is_verbatim(&name) && !self_closing {
+                    verbatim += 1;
+                }
+                tokens.push(Token::Start(name, attrs));
+            }
+            HtmlToken::End { name, .. } => {
+                if is_verbatim(&name) {
+                    verbatim = verbatim.saturating_sub(1);
+                }
+                tokens.push(Token::End(name));
+            }
+            HtmlToken::Text { text, .. } if verbatim > 0 => tokens.push(Token::Text(text)),
+            HtmlToken::Text { text, .. } => {
+                let text = text.split_whitespace().collect::<Vec<_>>().join(" ");
+                if text.is_empty() {
+                    continue;
+                }
+                // Text split by a skipped comment
+                if let Some(Token::Text(previous)) = tokens.last_mut() {
+                    previous.push(' ');
+                    previous.push_str(&text);
+                } else {
+                    tokens.push(Token::Text(text));
+                }
+            }
+        }
+    }
+    tokens
+}
+
+/// Whether the whitespace in elements named `name` is significant.
+fn is_verbatim(name: &str) -> bool {
+    matches!(name, "pre" | "textarea" | "script" | "style")
+}
+
+/// The `.html` files under `dir`, relative to it.
+fn html_files(dir: &Path) -> Result<BTreeSet<PathBuf>> {
+    let mut files = BTreeSet::new();
+    let mut dirs = vec![dir.to_path_buf()];
+    while let Some(current) = dirs.pop() {
+        let entries =
+            fs::read_dir(&current).map_err(|e| DiffError::Io(current.display().to_string(), e))?;
+        for entry in entries {
+            let path = entry
+                .map_err(|e| DiffError::Io(current.display().to_string(), e))?
+                .path();
+            if path.is_dir() {
+                dirs.push(path);
+            } else if path.extension().is_some_and(|ext| ext == "html")
+                && let Ok(relative) = path.strip_prefix(dir)
+            {
+                files.insert(relative.to_path_buf());
+            }
+        }
+    }
+    Ok(files)
+}
+
+/// URL path of the page at `path` in the output directory.
+fn url_path(path: &Path) -> String {
+    let path = path.to_string_lossy().replace('\\', "/");
+    match path.strip_suffix("index.html") {
+        Some(dir) => format!("/{dir}"),
+        None => format!("/{path}"),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use tempfile::TempDir;
+
+    use super::*;
+
+    #[test]
+    fn test_html_equivalent() {
+        assert!(html_equivalent(
+            "<p class=\"a b\" id=\"x\">Hello\n  <em>world</em></p><br/>",
+            "<!-- built --><p id=\"x\" class=\"b a\">Hello <em>world</em></p>\n<br>"
+        ));
+        assert!(!html_equivalent("<p>Hello</p>", "<p>Hello!</p>"));
+        assert!(!html_equivalent("<p id=\"a\">x</p>", "<p id=\"b\">x</p>"));
+        assert!(!html_equivalent(
+            "<pre><code>a\n  b</code></pre>",
+            "<pre><code>a\nb</code></pre>"
+        ));
+    }
+
+    #[test]
+    fn test_compare() {
+        let old = TempDir::new().unwrap();
+        let new = TempDir::new().unwrap();
+        for (dir, files) in [
+            (
+                &old,
+                [
+                    ("index.html", "<h1>Home</h1>"),
+                    ("posts/a/index.html", "<p>A</p>"),
+                    ("posts/b/index.html", "<p>B</p>"),
+                ],
+            ),
+            (
+                &new,
+                [
+                    ("index.html", "<h1>Home</h1>\n"),
+                    ("posts/a/index.html", "<p>A, edited</p>"),
+                    ("404.html", "<p>Not found</p>"),
+                ],
+            ),
+        ] {
+            for (path, html) in files {
+                let path = dir.path().join(path);
+                fs::create_dir_all(path.parent().unwrap()).unwrap();
+                fs::write(path, html).unwrap();
+            }
+        }
+        fs::write(new.path().join("sitemap.xml"), "<urlset/>").unwrap();
+
+        let diff = OutputDiff::compare(old.path(), new.path()).unwrap();
+        assert_eq!(
+            diff,
+            OutputDiff {
+                added: vec!["/404.html".to_string()],
+                removed: vec!["/posts/b/".to_string()],
+                changed: vec!["/posts/a/".to_string()],
+            }
+        );
+        let mut prefixed = diff.clone();
+        prefixed.prefix_urls("https://preview.example.com/");
+        assert_eq!(prefixed.changed, ["https://preview.example.com/posts/a/"]);
+        assert!(
+            OutputDiff::compare(old.path(), old.path())
+                .unwrap()
+                .is_empty()
+        );
+        assert!(matches!(
+            OutputDiff::compare(&old.path().join("missing"), new.path()),
+            Err(DiffError::Io(..))
+        ));
+    }
+}
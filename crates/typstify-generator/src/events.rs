@@ -0,0 +1,335 @@
+//! Events: the upcoming-events list and the iCalendar feed.
+//!
+//! Pages with a `start` date in their frontmatter are events. They are
+//! listed at `/events/` (upcoming first, soonest first, then past events)
+//! and exported as an `events.ics` feed calendar apps can subscribe to.
+
+use chrono::{DateTime, Utc};
+use typstify_core::{Config, Page, escape_html};
+
+/// Path of the iCalendar feed, relative to the (language) output root.
+pub const ICS_FILE: &str = "events.ics";
+
+/// Longest content line in octets, excluding the line break (RFC 5545).
+const MAX_LINE: usize = 75;
+
+/// Events split into upcoming (soonest first) and past (latest first).
+#[must_use]
+pub fn split_events<'a>(
+    pages: impl IntoIterator<Item = &'a Page>,
+    now: DateTime<Utc>,
+) -> (Vec<&'a Page>, Vec<&'a Page>) {
+    let (mut upcoming, mut past): (Vec<&Page>, Vec<&Page>) = pages
+        .into_iter()
+        .filter(|page| page.event.is_some())
+        .partition(|page| page.event.as_ref().is_some_and(|e| e.is_upcoming(now)));
+
+    let start = |page: &&Page| page.event.as_ref().map(|e| e.start);
+    upcoming.sort_by(|a, b| start(a).cmp(&start(b)).then(a.url.cmp(&b.url)));
+    past.sort_by(|a, b| start(b).cmp(&start(a)).then(a.url.cmp(&b.url)));
+    (upcoming, past)
+}
+
+/// "When" of an event for display, in the site timezone: the start, plus
+/// the end time (same day) or end date.
+#[must_use]
+pub fn event_when(config: &Config, page: &Page) -> Option<String> {
+    let event = page.event.as_ref()?;
+    let tz = config.timezone();
+    let start = event.start.with_timezone(&tz);
+    let mut when = format!(
+        "{} {}",
+        config.format_date(event.start, &page.lang),
+        start.format("%H:%M")
+    );
+    if let Some(end) = event.end.map(|end| end.with_timezone(&tz)) {
+        if end.date_naive() == start.date_naive() {
+            when.push_str(&format!("–{}", end.format("%H:%M")));
+        } else {
+            when.push_str(&format!(
+                " – {} {}",
+                config.format_date(end.with_timezone(&Utc), &page.lang),
+                end.format("%H:%M")
+            ));
+        }
+    }
+    Some(when)
+}
+
+/// List item for an event on the events page.
+#[must_use]
+pub fn event_item_html(config: &Config, page: &Page) -> String {
+    let Some(event) = page.event.as_ref() else {
+        return String::new();
+    };
+    let when = event_when(config, page).unwrap_or_default();
+    let location = event.location.as_deref().map_or(String::new(), |location| {
+        format!(
+            r#" <span class="event-location">{}</span>"#,
+            escape_html(location)
+        )
+    });
+
+    format!(
+        r#"<li class="event-item"><time datetime="{}">{}</time> <a href="{}{}">{}</a>{location}</li>"#,
+        event.start.to_rfc3339(),
+        escape_html(&when),
+        config.base_path(),
+        page.url,
+        escape_html(&page.title)
+    )
+}
+
+/// The events page body: upcoming events, then past ones.
+#[must_use]
+pub fn events_html(
+    config: &Config,
+    upcoming: &[&Page],
+    past: &[&Page],
+    calendar_url: &str,
+) -> String {
+    let list = |pages: &[&Page]| {
+        pages
+            .iter()
+            .map(|page| event_item_html(config, page))
+            .collect::<String>()
+    };
+
+    let mut html = format!(
+        r#"<p class="events-subscribe"><a href="{calendar_url}">Subscribe (iCalendar)</a></p><h2>Upcoming</h2>"#
+    );
+    if upcoming.is_empty() {
+        html.push_str(r#"<p class="events-empty">No upcoming events.</p>"#);
+    } else {
+        html.push_str(&format!(
+            r#"<ul class="event-list">{}</ul>"#,
+            list(upcoming)
+        ));
+    }
+    if !past.is_empty() {
+        html.push_str(&format!(
+            r#"<h2>Past events</h2><ul class="event-list event-list-past">{}</ul>"#,
+            list(past)
+        ));
+    }
+    html
+}
+
+/// iCalendar feed of `pages` that are events.
+#[must_use]
+pub fn ics(config: &Config, pages: &[&Page], calendar_name: &str) -> String {
+    let host = config
+        .site
+        .host
+        .split_once("://")
+        .map_or(config.site.host.as_str(), |(_, rest)| rest)
+        .trim_end_matches('/');
+
+    let mut lines = vec![
+        "BEGIN:VCALENDAR".to_string(),
+        "VERSION:2.0".to_string(),
+        format!(
+            "PRODID:-//Typstify//{}//EN",
+            escape_text(&config.site.title)
+        ),
+        "CALSCALE:GREGORIAN".to_string(),
+        "METHOD:PUBLISH".to_string(),
+        format!("X-WR-CALNAME:{}", escape_text(calendar_name)),
+    ];
+
+    for page in pages {
+        let Some(event) = page.event.as_ref() else {
+            continue;
+        };
+        let stamp = page.updated.or(page.date).unwrap_or(event.start);
+        lines.push("BEGIN:VEVENT".to_string());
+        lines.push(format!("UID:{}@{host}", page.url.trim_matches('/')));
+        lines.push(format!("DTSTAMP:{}", ics_time(stamp)));
+        lines.push(format!("DTSTART:{}", ics_time(event.start)));
+        if let Some(end) = event.end {
+            lines.push(format!("DTEND:{}", ics_time(end)));
+        }
+        lines.push(format!("SUMMARY:{}", escape_text(&page.title)));
+        if let Some(description) = page.description.as_ref().or(page.summary.as_ref()) {
+            lines.push(format!("DESCRIPTION:{}", escape_text(description)));
+        }
+        if let Some(location) = &event.location {
+            lines.push(format!("LOCATION:{}", escape_text(location)));
+        }
+        lines.push(format!("URL:{}{}", config.base_url(), page.url));
+        lines.push("END:VEVENT".to_string());
+    }
+    lines.push("END:VCALENDAR".to_string());
+
+    lines.iter().map(|line| fold(line) + "\r\n").collect()
+}
+
+/// UTC date-time in iCalendar form (`20240305T100000Z`).
+fn ics_time(time: DateTime<Utc>) -> String {
+    time.format("%Y%m%dT%H%M%SZ").to_string()
+}
+
+/// Escape a TEXT value.
+fn escape_text(text: &str) -> String {
+    text.replace('\\', "\\\\")
+        .replace(';', "\\;")
+        .replace(',', "\\,")
+        .replace("\r\n", "\\n")
+        .replace('\n', "\\n")
+}
+
+/// Fold a content line longer than 75 octets, without splitting characters.
+fn fold(line: &str) -> String {
+    let mut folded = String::with_capacity(line.len() + line.len() / MAX_LINE * 3);
+    let mut width = 0;
+    for c in line.chars() {
+        if width + c.len_utf8() > MAX_LINE {
+            folded.push_str("\r\n ");
+            // The leading space counts toward the next line
+            width = 1;
+        }
+        folded.push(c);
+        width += c.len_utf8();
+    }
+    folded
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::TimeZone;
+    use typstify_core::content::EventInfo;
+
+    use super::*;
+
+    fn event(url: &str, start: DateTime<Utc>, end: Option<DateTime<Utc>>) -> Page {
+        Page {
+            url: url.to_string(),
+            title: format!("Meetup {url}"),
+            description: Some("Talks; pizza, drinks".to_string()),
+            date: None,
+            updated: None,
+            expiry_date: None,
+            draft: false,
+            lang: "en".to_string(),
+            is_default_lang: true,
+            canonical_id: url.trim_start_matches('/').to_string(),
+            tags: vec![],
+            categories: vec![],
+            content: String::new(),
+            summary: None,
+            reading_time: None,
+            word_count: None,
+            toc: vec![],
+            custom_js: vec![],
+            custom_css: vec![],
+            aliases: vec![],
+            template: None,
+            weight: 0,
+            pinned: false,
+            series: None,
+            content_id: None,
+            extra_meta: std::collections::BTreeMap::new(),
+            structured_data: None,
+            images: vec![],
+            event: Some(EventInfo {
+                start,
+                end,
+                location: Some("Room 1".to_string()),
+            }),
+            source_path: None,
+        }
+    }
+
+    fn config() -> Config {
+        Config {
+            theme: None,
+            site: typstify_core::config::SiteConfig {
+                title: "Test Site".to_string(),
+                host: "https://example.com".to_string(),
+                base_path: String::new(),
+                default_language: "en".to_string(),
+                description: None,
+                author: None,
+                timezone: None,
+            },
+            languages: std::collections::HashMap::new(),
+            build: typstify_core::config::BuildConfig::default(),
+            search: typstify_core::config::SearchConfig::default(),
+            rss: typstify_core::config::RssConfig::default(),
+            robots: typstify_core::config::RobotsConfig::default(),
+            taxonomies: typstify_core::config::TaxonomyConfig::default(),
+            dates: typstify_core::config::DatesConfig::default(),
+            css: typstify_core::config::CssConfig::default(),
+            images: typstify_core::config::ImagesConfig::default(),
+            markdown: typstify_core::config::MarkdownConfig::default(),
+            typography: typstify_core::config::TypographyConfig::default(),
+            sections: std::collections::HashMap::new(),
+            check: typstify_core::config::CheckConfig::default(),
+            webmentions: typstify_core::config::WebmentionsConfig::default(),
+            identity: typstify_core::config::IdentityConfig::default(),
+            content: typstify_core::config::ContentConfig::default(),
+            ping: typstify_core::config::PingConfig::default(),
+            toc: typstify_core::config::TocConfig::default(),
+            math: typstify_core::config::MathConfig::default(),
+            mermaid: typstify_core::config::MermaidConfig::default(),
+            fmt: typstify_core::config::FmtConfig::default(),
+        }
+    }
+
+    #[test]
+    fn test_split_events() {
+        let at = |day| Utc.with_ymd_and_hms(2024, 3, day, 18, 0, 0).unwrap();
+        let pages = [
+            event("/events/a", at(1), None),
+            event("/events/b", at(20), None),
+            event("/events/c", at(10), Some(at(16))),
+            event("/events/d", at(5), None),
+        ];
+
+        let (upcoming, past) = split_events(&pages, at(15));
+        let urls = |pages: &[&Page]| pages.iter().map(|p| p.url.clone()).collect::<Vec<_>>();
+        assert_eq!(urls(&upcoming), vec!["/events/c", "/events/b"]);
+        assert_eq!(urls(&past), vec!["/events/d", "/events/a"]);
+    }
+
+    #[test]
+    fn test_event_when() {
+        let start = Utc.with_ymd_and_hms(2024, 3, 5, 18, 0, 0).unwrap();
+        let page = event("/events/a", start, Some(start + chrono::Duration::hours(2)));
+        assert_eq!(
+            event_when(&config(), &page).unwrap(),
+            format!("{} 18:00–20:00", config().format_date(start, "en"))
+        );
+    }
+
+    #[test]
+    fn test_ics() {
+        let start = Utc.with_ymd_and_hms(2024, 3, 5, 18, 0, 0).unwrap();
+        let mut page = event(
+            "/events/rust-meetup",
+            start,
+            Some(start + chrono::Duration::hours(2)),
+        );
+        page.title =
+            "A very long event title that certainly needs folding across several lines".to_string();
+        let ics = ics(&config(), &[&page], "Test Site events");
+
+        assert!(ics.starts_with("BEGIN:VCALENDAR\r\nVERSION:2.0\r\n"));
+        assert!(ics.contains("UID:events/rust-meetup@example.com\r\n"));
+        assert!(ics.contains("DTSTART:20240305T180000Z\r\nDTEND:20240305T200000Z\r\n"));
+        assert!(ics.contains("DESCRIPTION:Talks\\; pizza\\, drinks\r\n"));
+        assert!(ics.contains("LOCATION:Room 1\r\n"));
+        assert!(ics.contains("URL:https://example.com/events/rust-meetup\r\n"));
+        assert!(ics.ends_with("END:VEVENT\r\nEND:VCALENDAR\r\n"));
+        assert!(ics.lines().all(|line| line.len() <= MAX_LINE));
+        assert!(ics.contains("\r\n "));
+    }
+
+    #[test]
+    fn test_fold_keeps_characters() {
+        let line = format!("SUMMARY:{}", "é".repeat(60));
+        let folded = fold(&line);
+        assert!(folded.split("\r\n").all(|l| l.len() <= MAX_LINE));
+        assert_eq!(folded.replace("\r\n ", ""), line);
+    }
+}
@@ -0,0 +1,215 @@
+//! Photo galleries from the `gallery` shortcode.
+//!
+//! The shortcode leaves a `<figure class="gallery" data-gallery="dir">`
+//! placeholder in the page. At build time the images in `dir` are published
+//! next to the page and the placeholder is filled with a grid of thumbnails
+//! linking to the full images, which `main.js` opens in a lightbox.
+
+use std::{
+    fs,
+    io::BufReader,
+    path::{Path, PathBuf},
+};
+
+use exif::{In, Reader, Tag, Value};
+use typstify_core::{escape_html, unescape_html};
+
+use crate::images::{ImagePipeline, THUMBNAIL_SIZE};
+
+/// Start of a gallery placeholder left by the shortcode.
+pub const MARKER: &str = r#"<figure class="gallery" data-gallery=""#;
+
+/// An image of a gallery.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct GalleryImage {
+    /// URL of the full image.
+    pub src: String,
+
+    /// URL of the thumbnail.
+    pub thumbnail: String,
+
+    /// Alternative text.
+    pub alt: String,
+
+    /// Caption shown in the lightbox.
+    pub caption: Option<String>,
+}
+
+/// Fill every gallery placeholder in `html` with the grid returned by
+/// `render`, called with the gallery's directory and whether EXIF captions
+/// were requested.
+pub fn expand<E>(
+    html: &str,
+    mut render: impl FnMut(&str, bool) -> Result<String, E>,
+) -> Result<String, E> {
+    let mut output = String::with_capacity(html.len());
+    let mut rest = html;
+
+    while let Some(start) = rest.find(MARKER) {
+        let (before, placeholder) = rest.split_at(start);
+        output.push_str(before);
+
+        let attrs = &placeholder[MARKER.len()..];
+        let (Some(dir_end), Some(tag_end)) = (attrs.find('"'), attrs.find('>')) else {
+            output.push_str(MARKER);
+            rest = attrs;
+            continue;
+        };
+        let dir = unescape_html(&attrs[..dir_end]);
+        let exif = attrs[dir_end..tag_end].contains(" data-exif");
+
+        output.push_str(&placeholder[..MARKER.len() + tag_end + 1]);
+        output.push_str(&render(&dir, exif)?);
+        rest = &attrs[tag_end + 1..];
+    }
+    output.push_str(rest);
+
+    Ok(output)
+}
+
+/// The images in `dir` the image pipeline can process, sorted by name.
+pub fn image_files(dir: &Path) -> std::io::Result<Vec<PathBuf>> {
+    let mut files: Vec<PathBuf> = fs::read_dir(dir)?
+        .filter_map(|entry| entry.ok().map(|e| e.path()))
+        .filter(|path| path.is_file() && ImagePipeline::supports(path))
+        .collect();
+    files.sort();
+    Ok(files)
+}
+
+/// The EXIF image description of the file at `path`, if it has one.
+#[must_use]
+pub fn exif_caption(path: &Path) -> Option<String> {
+    let file = fs::File::open(path).ok()?;
+    let exif = Reader::new()
+        .read_from_container(&mut BufReader::new(file))
+        .ok()?;
+    let field = exif.get_field(Tag::ImageDescription, In::PRIMARY)?;
+    let Value::Ascii(values) = &field.value else {
+        return None;
+    };
+    let caption = values
+        .iter()
+        .map(|value| String::from_utf8_lossy(value).trim().to_string())
+        .find(|value| !value.is_empty())?;
+    Some(caption)
+}
+
+/// The thumbnail grid of a gallery.
+#[must_use]
+pub fn grid_html(images: &[GalleryImage]) -> String {
+    let items: String = images
+        .iter()
+        .map(|image| {
+            let caption = image.caption.as_ref().map_or(String::new(), |caption| {
+                format!(r#" data-caption="{}""#, escape_html(caption))
+            });
+            format!(
+                r#"<a class="gallery-item" href="{}"{caption}><img src="{}" alt="{}" width="{THUMBNAIL_SIZE}" height="{THUMBNAIL_SIZE}" loading="lazy"></a>"#,
+                escape_html(&image.src),
+                escape_html(&image.thumbnail),
+                escape_html(&image.alt)
+            )
+        })
+        .collect();
+
+    format!(r#"<div class="gallery-grid">{items}</div>"#)
+}
+
+#[cfg(test)]
+mod tests {
+    use tempfile::TempDir;
+
+    use super::*;
+
+    /// A 1x1 JPEG whose EXIF data has the given image description.
+    fn jpeg_with_description(description: &str) -> Vec<u8> {
+        let mut jpeg = Vec::new();
+        image::RgbImage::new(1, 1)
+            .write_to(
+                &mut std::io::Cursor::new(&mut jpeg),
+                image::ImageFormat::Jpeg,
+            )
+            .unwrap();
+
+        // Little-endian TIFF with one IFD entry: ImageDescription (ASCII),
+        // stored after the IFD (so longer than 4 bytes)
+        let text = [description.as_bytes(), b"\0"].concat();
+        let mut tiff = b"II*\0\x08\0\0\0\x01\0\x0e\x01\x02\0".to_vec();
+        tiff.extend((text.len() as u32).to_le_bytes());
+        tiff.extend(26u32.to_le_bytes());
+        tiff.extend(0u32.to_le_bytes());
+        tiff.extend(&text);
+
+        let mut app1 = b"Exif\0\0".to_vec();
+        app1.extend(tiff);
+        let mut segment = vec![0xff, 0xe1];
+        segment.extend(((app1.len() + 2) as u16).to_be_bytes());
+        segment.extend(app1);
+
+        // Right after the SOI marker
+        jpeg.splice(2..2, segment);
+        jpeg
+    }
+
+    #[test]
+    fn test_expand() {
+        let html = r#"<p>Trip</p><figure class="gallery" data-gallery="photos/a&amp;b" data-exif><figcaption>Alps</figcaption></figure><figure class="gallery" data-gallery="more"></figure>"#;
+
+        let mut calls = Vec::new();
+        let expanded = expand(html, |dir, exif| {
+            calls.push((dir.to_string(), exif));
+            Ok::<_, ()>(format!("[{dir}]"))
+        })
+        .unwrap();
+
+        assert_eq!(
+            calls,
+            vec![
+                ("photos/a&b".to_string(), true),
+                ("more".to_string(), false)
+            ]
+        );
+        assert_eq!(
+            expanded,
+            r#"<p>Trip</p><figure class="gallery" data-gallery="photos/a&amp;b" data-exif>[photos/a&b]<figcaption>Alps</figcaption></figure><figure class="gallery" data-gallery="more">[more]</figure>"#
+        );
+    }
+
+    #[test]
+    fn test_image_files_and_exif_caption() {
+        let dir = TempDir::new().unwrap();
+        fs::write(
+            dir.path().join("b.jpg"),
+            jpeg_with_description("Lake at dawn"),
+        )
+        .unwrap();
+        fs::write(dir.path().join("a.jpg"), jpeg_with_description("      ")).unwrap();
+        fs::write(dir.path().join("notes.txt"), "not an image").unwrap();
+
+        let files = image_files(dir.path()).unwrap();
+        let names: Vec<_> = files
+            .iter()
+            .map(|f| f.file_name().unwrap().to_string_lossy().into_owned())
+            .collect();
+        assert_eq!(names, vec!["a.jpg", "b.jpg"]);
+
+        assert_eq!(exif_caption(&files[0]), None);
+        assert_eq!(exif_caption(&files[1]).as_deref(), Some("Lake at dawn"));
+    }
+
+    #[test]
+    fn test_grid_html() {
+        let html = grid_html(&[GalleryImage {
+            src: "/trip/photos/a.jpg".to_string(),
+            thumbnail: "/trip/photos/a-thumb.jpg".to_string(),
+            alt: "a".to_string(),
+            caption: Some("Lake \"Blue\"".to_string()),
+        }]);
+
+        assert_eq!(
+            html,
+            r#"<div class="gallery-grid"><a class="gallery-item" href="/trip/photos/a.jpg" data-caption="Lake &quot;Blue&quot;"><img src="/trip/photos/a-thumb.jpg" alt="a" width="320" height="320" loading="lazy"></a></div>"#
+        );
+    }
+}
@@ -2,14 +2,33 @@
 //!
 //! Converts parsed content into final HTML pages using templates.
 
-use std::path::{Path, PathBuf};
+use std::{
+    collections::{HashMap, HashSet},
+    path::{Path, PathBuf},
+};
 
 use chrono::{Datelike, Utc};
 use thiserror::Error;
 use tracing::debug;
-use typstify_core::{Config, Page};
-
-use crate::template::{Template, TemplateContext, TemplateError, TemplateRegistry};
+use typstify_core::{
+    Config, Page, TocEntry,
+    config::{SearchPreload, SectionRobots, TaxonomySettings},
+    escape_html,
+};
+use typstify_search::CHUNKED_INDEX_DIR;
+
+use crate::{
+    cache::config_digest,
+    collector::{SiteContent, TaxonomyIndex, TimeWindow},
+    events, identity,
+    links::LinkGraph,
+    manifest::content_hash,
+    math, mermaid,
+    sections::{Neighbors, SectionMeta, breadcrumbs_html, section_title},
+    structured_data,
+    template::{Template, TemplateContext, TemplateError, TemplateRegistry},
+    webmentions::Webmentions,
+};
 
 /// HTML generation errors.
 #[derive(Debug, Error)]
@@ -30,6 +49,17 @@ pub enum HtmlError {
 /// Result type for HTML generation.
 pub type Result<T> = std::result::Result<T, HtmlError>;
 
+/// Button printing the page, shown with `build.print_button`.
+const PRINT_BUTTON: &str =
+    r#"<button type="button" class="print-button">Print / Save as PDF</button>"#;
+
+/// Banner shown on draft pages when drafts are included in the build.
+/// Where pages load the search runtime for chunked indexes from: the
+/// `wasm-pack` build of typstify-search-wasm, deployed from `static/pkg/`.
+pub const SEARCH_RUNTIME: &str = "/pkg/typstify_search_wasm.js";
+
+const DRAFT_BANNER: &str = r#"<div class="draft-banner" role="status">DRAFT — not published</div>"#;
+
 /// HTML page generator.
 #[derive(Debug)]
 pub struct HtmlGenerator {
@@ -37,6 +67,28 @@ pub struct HtmlGenerator {
     config: Config,
     /// Content sections for dynamic navigation (e.g., "posts", "shorts").
     sections: Vec<String>,
+    /// Link graph used to render backlinks, if enabled.
+    link_graph: Option<LinkGraph>,
+    /// Docs sidebars keyed by section URL (e.g., "/docs", "/zh/docs").
+    sidebars: HashMap<String, String>,
+    /// Previous/next pages keyed by page URL.
+    neighbors: HashMap<String, Neighbors>,
+    /// Page titles keyed by URL, for previous/next links.
+    titles: HashMap<String, String>,
+    /// Search index JSON to embed in pages, keyed by language.
+    inline_search: HashMap<String, String>,
+    /// Languages with a chunked search index for the search runtime.
+    chunked_search: HashSet<String>,
+    /// Received webmentions rendered under posts.
+    webmentions: Option<Webmentions>,
+    /// Tags without a term page, used by fewer than `min_pages` pages.
+    thin_tags: HashSet<String>,
+    /// Categories without a term page.
+    thin_categories: HashSet<String>,
+    /// Parts (URL and title) of each series, in reading order.
+    series: HashMap<String, Vec<(String, String)>>,
+    /// Metadata from `_index` files, keyed by section URL.
+    section_meta: HashMap<String, SectionMeta>,
 }
 
 impl HtmlGenerator {
@@ -47,6 +99,17 @@ impl HtmlGenerator {
             templates: TemplateRegistry::new(),
             config,
             sections: Vec::new(),
+            link_graph: None,
+            sidebars: HashMap::new(),
+            neighbors: HashMap::new(),
+            titles: HashMap::new(),
+            inline_search: HashMap::new(),
+            chunked_search: HashSet::new(),
+            webmentions: None,
+            thin_tags: HashSet::new(),
+            thin_categories: HashSet::new(),
+            series: HashMap::new(),
+            section_meta: HashMap::new(),
         }
     }
 
@@ -57,6 +120,17 @@ impl HtmlGenerator {
             templates,
             config,
             sections: Vec::new(),
+            link_graph: None,
+            sidebars: HashMap::new(),
+            neighbors: HashMap::new(),
+            titles: HashMap::new(),
+            inline_search: HashMap::new(),
+            chunked_search: HashSet::new(),
+            webmentions: None,
+            thin_tags: HashSet::new(),
+            thin_categories: HashSet::new(),
+            series: HashMap::new(),
+            section_meta: HashMap::new(),
         }
     }
 
@@ -67,6 +141,310 @@ impl HtmlGenerator {
         self
     }
 
+    /// Set the link graph used to list backlinks on each page.
+    #[must_use]
+    pub fn with_link_graph(mut self, graph: LinkGraph) -> Self {
+        self.link_graph = Some(graph);
+        self
+    }
+
+    /// Set the docs sidebars, keyed by section URL.
+    ///
+    /// Pages and section indices under a key are wrapped in the "docs"
+    /// template with the matching sidebar.
+    #[must_use]
+    pub fn with_sidebars(mut self, sidebars: HashMap<String, String>) -> Self {
+        self.sidebars = sidebars;
+        self
+    }
+
+    /// Set the previous/next pages of each page and the titles to link them
+    /// with.
+    #[must_use]
+    pub fn with_neighbors(
+        mut self,
+        neighbors: HashMap<String, Neighbors>,
+        titles: HashMap<String, String>,
+    ) -> Self {
+        self.neighbors = neighbors;
+        self.titles = titles;
+        self
+    }
+
+    /// Set the search index JSON to embed in pages, keyed by language.
+    ///
+    /// Languages without an entry get a resource hint for
+    /// `search-index.json` instead, according to `search.preload`.
+    #[must_use]
+    pub fn with_inline_search(mut self, indexes: HashMap<String, String>) -> Self {
+        self.inline_search = indexes;
+        self
+    }
+
+    /// Set the languages with a chunked search index, whose pages load the
+    /// search runtime ([`SEARCH_RUNTIME`]) to search it.
+    #[must_use]
+    pub fn with_chunked_search(mut self, langs: HashSet<String>) -> Self {
+        self.chunked_search = langs;
+        self
+    }
+
+    /// Set the site's taxonomy terms, so that terms too rare for a term page
+    /// (see `min_pages`) are not linked.
+    #[must_use]
+    pub fn with_taxonomies(mut self, taxonomies: &TaxonomyIndex) -> Self {
+        let thin = |terms: &HashMap<String, Vec<String>>, settings: &TaxonomySettings| {
+            terms
+                .iter()
+                .filter(|(_, pages)| !settings.has_term_page(pages.len()))
+                .map(|(term, _)| term.clone())
+                .collect()
+        };
+        self.thin_tags = thin(&taxonomies.tags, &self.config.taxonomies.tags);
+        self.thin_categories = thin(&taxonomies.categories, &self.config.taxonomies.categories);
+        self
+    }
+
+    /// Set the site's series, so that each part links to the others.
+    #[must_use]
+    pub fn with_series(mut self, content: &SiteContent) -> Self {
+        self.series = content
+            .series
+            .iter()
+            .map(|(name, urls)| {
+                let parts = urls
+                    .iter()
+                    .filter_map(|url| content.pages.get(url))
+                    .map(|p| (p.url.clone(), p.title.clone()))
+                    .collect();
+                (name.clone(), parts)
+            })
+            .collect();
+        self
+    }
+
+    /// Set the metadata from `_index` files, keyed by section URL, for
+    /// section titles, descriptions and templates.
+    #[must_use]
+    pub fn with_section_meta(mut self, section_meta: HashMap<String, SectionMeta>) -> Self {
+        self.section_meta = section_meta;
+        self
+    }
+
+    /// Display title of `section` (a path without the language prefix) in
+    /// the language of `lang_prefix`.
+    fn section_display_title(&self, lang_prefix: &str, section: &str) -> String {
+        self.section_meta
+            .get(&format!("{lang_prefix}/{section}"))
+            .and_then(|meta| meta.title.clone())
+            .unwrap_or_else(|| section_title(section))
+    }
+
+    /// Set the received webmentions to render under posts.
+    #[must_use]
+    pub fn with_webmentions(mut self, webmentions: Webmentions) -> Self {
+        self.webmentions = Some(webmentions);
+        self
+    }
+
+    /// Add the search client settings for `lang`: where to report queries
+    /// with no results, the chunked index and search runtime, and the
+    /// embedded index or a resource hint for it.
+    fn add_search_index(&self, ctx: &mut TemplateContext, lang: &str) {
+        let search = &self.config.search;
+        if !search.enabled {
+            return;
+        }
+
+        if search.miss_endpoint.is_some() || search.record_misses {
+            let settings = serde_json::json!({
+                "endpoint": search.miss_endpoint,
+                "local": search.record_misses,
+            });
+            ctx.insert(
+                "search_misses",
+                format!(
+                    r#"<script type="application/json" id="search-misses-config">{}</script>"#,
+                    settings.to_string().replace("</", r"<\/")
+                ),
+            );
+        }
+
+        let lang_prefix = if lang == self.config.site.default_language {
+            String::new()
+        } else {
+            format!("/{lang}")
+        };
+        if self.chunked_search.contains(lang) {
+            let base_path = self.config.base_path();
+            let settings = serde_json::json!({
+                "index": format!("{base_path}{lang_prefix}/{CHUNKED_INDEX_DIR}"),
+                "runtime": format!("{base_path}{SEARCH_RUNTIME}"),
+            });
+            ctx.insert(
+                "search_engine",
+                format!(
+                    r#"<script type="application/json" id="search-engine-config">{}</script>"#,
+                    settings.to_string().replace("</", r"<\/")
+                ),
+            );
+        }
+
+        if let Some(json) = self.inline_search.get(lang) {
+            ctx.insert(
+                "search_data",
+                format!(
+                    r#"<script type="application/json" id="search-index-data">{}</script>"#,
+                    json.replace("</", r"<\/")
+                ),
+            );
+            return;
+        }
+
+        let rel = match search.preload {
+            SearchPreload::None => return,
+            SearchPreload::Prefetch => "prefetch",
+            SearchPreload::Preload => "preload",
+        };
+        ctx.insert(
+            "search_hint",
+            format!(
+                r#"<link rel="{rel}" href="{}{lang_prefix}/search-index.json" as="fetch" crossorigin data-search-index>"#,
+                self.config.base_path()
+            ),
+        );
+    }
+
+    /// Previous and next links for a page, if any.
+    fn neighbor_links(&self, url: &str) -> (Option<String>, Option<String>) {
+        let Some(neighbors) = self.neighbors.get(url) else {
+            return (None, None);
+        };
+        let base_path = self.config.base_path();
+        let link = |url: &Option<String>, rel: &str, label: &str| {
+            url.as_ref().map(|url| {
+                let title = escape_html(self.titles.get(url).map_or(url.as_str(), String::as_str));
+                format!(
+                    r#"<a class="post-nav-{rel}" href="{base_path}{url}" rel="{rel}"><span>{label}</span>{title}</a>"#
+                )
+            })
+        };
+        (
+            link(&neighbors.prev, "prev", "← Previous"),
+            link(&neighbors.next, "next", "Next →"),
+        )
+    }
+
+    /// "Part N of M" box listing every part of the page's series, if any.
+    fn series_html(&self, page: &Page) -> Option<String> {
+        let name = page.series.as_ref()?;
+        let parts = self.series.get(name)?;
+        let part = parts.iter().position(|(url, _)| *url == page.url)? + 1;
+        let base_path = self.config.base_path();
+
+        let items: String = parts
+            .iter()
+            .map(|(url, title)| {
+                if *url == page.url {
+                    format!(r#"<li aria-current="page">{}</li>"#, escape_html(title))
+                } else {
+                    format!(
+                        r#"<li><a href="{base_path}{url}">{}</a></li>"#,
+                        escape_html(title)
+                    )
+                }
+            })
+            .collect();
+        Some(format!(
+            r#"<aside class="series"><p>Part {part} of {} in <a href="{base_path}/series/{}">{}</a></p><ol>{items}</ol></aside>"#,
+            parts.len(),
+            slug_from_str(name),
+            escape_html(name)
+        ))
+    }
+
+    /// Breadcrumb trail from the home page through the sections containing
+    /// `page` to the page itself, or `None` for the home page.
+    fn page_breadcrumbs(&self, page: &Page) -> Option<String> {
+        let lang_prefix = if page.is_default_lang {
+            String::new()
+        } else {
+            format!("/{}", page.lang)
+        };
+        let path = page
+            .url
+            .strip_prefix(&lang_prefix)
+            .unwrap_or(&page.url)
+            .trim_matches('/');
+        if path.is_empty() {
+            return None;
+        }
+        let section = path.rsplit_once('/').map_or("", |(section, _)| section);
+        Some(breadcrumbs_html(
+            &format!("{}{lang_prefix}", self.config.base_path()),
+            section,
+            Some(&page.title),
+            |section| self.section_display_title(&lang_prefix, section),
+        ))
+    }
+
+    /// Wrap rendered content in the docs layout if `url` is in a docs
+    /// section, marking the current link in the sidebar.
+    fn wrap_docs(&self, url: &str, inner_html: String) -> Result<String> {
+        let Some(sidebar) = self
+            .sidebars
+            .iter()
+            .filter(|(key, _)| {
+                url.strip_prefix(key.as_str())
+                    .is_some_and(|rest| rest.is_empty() || rest.starts_with('/'))
+            })
+            .max_by_key(|(key, _)| key.len())
+            .map(|(_, sidebar)| sidebar)
+        else {
+            return Ok(inner_html);
+        };
+
+        let href = format!(r#"href="{}{url}""#, self.config.base_path());
+        let sidebar = sidebar.replacen(&href, &format!(r#"{href} aria-current="page""#), 1);
+        let ctx = TemplateContext::new()
+            .with_var("sidebar", sidebar)
+            .with_var("content", inner_html);
+        Ok(self.templates.render("docs", &ctx)?)
+    }
+
+    /// Robots meta tag for pages in a section configured with
+    /// `robots = "noindex"`.
+    fn section_robots_meta(&self, url: &str) -> Option<&'static str> {
+        self.config
+            .section_for_url(url)
+            .filter(|section| section.robots == SectionRobots::Noindex)
+            .map(|_| r#"<meta name="robots" content="noindex">"#)
+    }
+
+    /// Generate the "Linked from" section for a page.
+    fn generate_backlinks(&self, url: &str) -> Option<String> {
+        let graph = self.link_graph.as_ref()?;
+        let base_path = self.config.base_path();
+
+        let items: Vec<String> = graph
+            .inbound(url)
+            .map(|source| {
+                format!(
+                    r#"<li><a href="{base_path}{source}">{}</a></li>"#,
+                    escape_html(graph.title(source).unwrap_or(source))
+                )
+            })
+            .collect();
+        if items.is_empty() {
+            return None;
+        }
+
+        Some(format!(
+            "<aside class=\"backlinks\">\n<h2>Linked from</h2>\n<ul>\n{}\n</ul>\n</aside>",
+            items.join("\n")
+        ))
+    }
+
     /// Generate navigation HTML for content sections.
     fn generate_section_nav(&self, base_path: &str, lang_prefix: &str) -> String {
         if self.sections.is_empty() {
@@ -113,6 +491,60 @@ impl HtmlGenerator {
         self.templates.register(template);
     }
 
+    /// Digest of the state shared by all pages: configuration, templates,
+    /// navigation, docs sidebars, inline and chunked search indexes and
+    /// section titles.
+    #[must_use]
+    pub fn digest(&self) -> String {
+        let mut sidebars: Vec<_> = self.sidebars.iter().collect();
+        sidebars.sort();
+        let mut inline_search: Vec<_> = self.inline_search.iter().collect();
+        inline_search.sort();
+        let mut chunked_search: Vec<_> = self.chunked_search.iter().collect();
+        chunked_search.sort();
+        let mut section_titles: Vec<_> = self
+            .section_meta
+            .iter()
+            .map(|(url, meta)| (url, &meta.title))
+            .collect();
+        section_titles.sort();
+
+        let state = format!(
+            "{}\0{}\0{:?}\0{sidebars:?}\0{inline_search:?}\0{chunked_search:?}\0{section_titles:?}\0{}",
+            config_digest(&self.config),
+            self.templates.digest(),
+            self.sections,
+            Utc::now().year()
+        );
+        content_hash(state.as_bytes())
+    }
+
+    /// Digest of everything [`generate_page`](Self::generate_page) renders
+    /// `page` from, given the generator's [`digest`](Self::digest). Pages
+    /// with the same digest render to the same HTML.
+    #[must_use]
+    pub fn page_digest(&self, digest: &str, page: &Page, alternates: &[(&str, &str)]) -> String {
+        let page_json = serde_json::to_string(page).unwrap_or_default();
+        let webmentions = self
+            .webmentions
+            .as_ref()
+            .and_then(|w| w.html(&format!("{}{}", self.config.base_path(), page.url)));
+
+        let thin_tags: Vec<&String> = page
+            .tags
+            .iter()
+            .filter(|tag| self.thin_tags.contains(*tag))
+            .collect();
+
+        let state = format!(
+            "{digest}\0{page_json}\0{alternates:?}\0{:?}\0{:?}\0{webmentions:?}\0{thin_tags:?}\0{:?}",
+            self.neighbor_links(&page.url),
+            self.generate_backlinks(&page.url),
+            self.series_html(page),
+        );
+        content_hash(state.as_bytes())
+    }
+
     /// Generate HTML for a page.
     pub fn generate_page(&self, page: &Page, alternates: &[(&str, &str)]) -> Result<String> {
         debug!(url = %page.url, "generating HTML for page");
@@ -120,7 +552,13 @@ impl HtmlGenerator {
         // Determine which template to use
         let template_name = page.template.as_deref().map_or_else(
             || {
-                if page.date.is_some() { "post" } else { "page" }
+                if page.event.is_some() {
+                    "event"
+                } else if page.date.is_some() {
+                    "post"
+                } else {
+                    "page"
+                }
             },
             |t| {
                 // Normalize "shorts" to "short" for individual pages
@@ -131,15 +569,18 @@ impl HtmlGenerator {
         // Build inner content context
         let inner_ctx = self.build_page_context(page)?;
         let inner_html = self.templates.render(template_name, &inner_ctx)?;
+        let inner_html = self.wrap_docs(&page.url, inner_html)?;
 
         // Build outer (base) context
         let base_ctx = self.build_base_context(page, &inner_html, alternates)?;
-        Ok(self.templates.render("base", &base_ctx)?)
+        self.render_base(base_ctx)
     }
 
     /// Generate redirect HTML for URL aliases.
     pub fn generate_redirect(&self, redirect_url: &str) -> Result<String> {
-        let ctx = TemplateContext::new().with_var("redirect_url", redirect_url);
+        let ctx = TemplateContext::new()
+            .with_var("redirect_url", redirect_url)
+            .with_var("lang", &self.config.site.default_language);
         self.templates
             .render("redirect", &ctx)
             .map_err(HtmlError::from)
@@ -185,7 +626,7 @@ impl HtmlGenerator {
             .with_var("nav_about_url", format!("{base_path}/about"))
             .with_var("section_nav", self.generate_section_nav(base_path, ""));
 
-        Ok(self.templates.render("base", &base_ctx)?)
+        self.render_base(base_ctx)
     }
 
     /// Generate a taxonomy term page HTML.
@@ -195,6 +636,7 @@ impl HtmlGenerator {
         term: &str,
         items_html: &str,
         pagination_html: Option<&str>,
+        feed_url: Option<&str>,
     ) -> Result<String> {
         let mut ctx = TemplateContext::new()
             .with_var("taxonomy_name", taxonomy_name)
@@ -212,7 +654,7 @@ impl HtmlGenerator {
         let base_path = self.config.base_path();
 
         // Wrap in base template
-        let base_ctx = TemplateContext::new()
+        let mut base_ctx = TemplateContext::new()
             .with_var("lang", &self.config.site.default_language)
             .with_var("title", &title)
             .with_var("base_path", base_path)
@@ -239,19 +681,141 @@ impl HtmlGenerator {
             .with_var("nav_about_url", format!("{base_path}/about"))
             .with_var("section_nav", self.generate_section_nav(base_path, ""));
 
-        Ok(self.templates.render("base", &base_ctx)?)
+        // Advertise the term feed for auto-discovery
+        if let Some(feed_url) = feed_url {
+            base_ctx.insert(
+                "feed_links",
+                feed_link_html(&format!("{} | {}", title, self.config.site.title), feed_url),
+            );
+        }
+
+        self.render_base(base_ctx)
+    }
+
+    /// Render the base template, adding the main feed discovery link.
+    fn render_base(&self, mut ctx: TemplateContext) -> Result<String> {
+        if self.config.rss.enabled {
+            let main_feed = feed_link_html(
+                &self.config.site.title,
+                &format!("{}/rss.xml", self.config.base_path()),
+            );
+            let links = match ctx.get("feed_links") {
+                Some(extra) => format!("{main_feed}\n{extra}"),
+                None => main_feed,
+            };
+            ctx.insert("feed_links", links);
+        }
+
+        let webmentions = &self.config.webmentions;
+        let endpoints = [
+            ("webmention", &webmentions.endpoint),
+            ("pingback", &webmentions.pingback),
+        ]
+        .into_iter()
+        .filter_map(|(rel, href)| {
+            href.as_ref()
+                .map(|href| format!(r#"<link rel="{rel}" href="{}">"#, escape_html(href)))
+        })
+        .collect::<Vec<_>>();
+        if !endpoints.is_empty() {
+            ctx.insert("webmention_links", endpoints.join("\n    "));
+        }
+
+        if let Some(links) = identity::head_links(&self.config.identity) {
+            ctx.insert("identity_links", links);
+        }
+
+        if let Some(lang) = ctx.get("lang").map(str::to_string) {
+            self.add_search_index(&mut ctx, &lang);
+        }
+
+        Ok(self.templates.render("base", &ctx)?)
     }
 
     /// Build template context for page content.
     fn build_page_context(&self, page: &Page) -> Result<TemplateContext> {
         let mut ctx = TemplateContext::new()
+            .with_var("url", &page.url)
             .with_var("title", &page.title)
-            .with_var("content", &page.content);
+            .with_var("content", &page.content)
+            .with_var("tags", page.tags.clone())
+            .with_var("categories", page.categories.clone());
+        if let Some(minutes) = page.reading_time {
+            ctx.insert("reading_time", minutes);
+        }
+        if let Some(words) = page.word_count {
+            ctx.insert("word_count", words);
+        }
 
         // Add date if present
         if let Some(date) = page.date {
-            ctx.insert("date_iso", date.format("%Y-%m-%d").to_string());
-            ctx.insert("date_formatted", date.format("%B %d, %Y").to_string());
+            let local = date.with_timezone(&self.config.timezone());
+            ctx.insert("date_iso", local.format("%Y-%m-%d").to_string());
+            ctx.insert("date_formatted", self.config.format_date(date, &page.lang));
+        }
+
+        if let Some(event) = &page.event {
+            ctx.insert("event_start_iso", event.start.to_rfc3339());
+            if let Some(when) = events::event_when(&self.config, page) {
+                ctx.insert("event_when", when);
+            }
+            if let Some(location) = &event.location {
+                ctx.insert("event_location", location);
+            }
+        }
+
+        if let Some(breadcrumbs) = self.page_breadcrumbs(page) {
+            ctx.insert("breadcrumbs_html", breadcrumbs);
+        }
+
+        if let Some(toc) = toc_html(&TocEntry::nest(
+            &page.toc,
+            self.config.toc.min_depth,
+            self.config.toc.max_depth,
+        )) {
+            ctx.insert("toc", toc);
+        }
+
+        if let Some(backlinks) = self.generate_backlinks(&page.url) {
+            ctx.insert("backlinks_html", backlinks);
+        }
+
+        if self.config.build.print_button {
+            ctx.insert("print_button", PRINT_BUTTON);
+        }
+
+        if let Some(mentions) = self
+            .webmentions
+            .as_ref()
+            .and_then(|w| w.html(&format!("{}{}", self.config.base_path(), page.url)))
+        {
+            ctx.insert("webmentions", mentions);
+        }
+
+        let (prev, next) = self.neighbor_links(&page.url);
+        if let Some(prev) = prev {
+            ctx.insert("prev_post", prev);
+        }
+        if let Some(next) = next {
+            ctx.insert("next_post", next);
+        }
+        if let Some(neighbors) = self.neighbors.get(&page.url) {
+            let base_path = self.config.base_path();
+            for (rel, url) in [("prev", &neighbors.prev), ("next", &neighbors.next)] {
+                if let Some(url) = url {
+                    let title = self.titles.get(url).unwrap_or(url);
+                    ctx.insert(format!("{rel}_url"), format!("{base_path}{url}"));
+                    ctx.insert(format!("{rel}_title"), title);
+                }
+            }
+        }
+
+        if let Some(series) = self.series_html(page) {
+            ctx.insert("series_html", series);
+        }
+
+        if let Some(gallery) = short_gallery_html(page) {
+            ctx.insert("gallery", gallery);
         }
 
         // Add author info for short templates
@@ -277,11 +841,15 @@ impl HtmlGenerator {
                 .tags
                 .iter()
                 .map(|tag| {
-                    format!(
-                        r#"<a href="{base_path}{lang_prefix}/tags/{}" rel="tag">{}</a>"#,
-                        slug_from_str(tag),
-                        tag
-                    )
+                    if self.thin_tags.contains(tag) {
+                        format!("<span>{}</span>", escape_html(tag))
+                    } else {
+                        format!(
+                            r#"<a href="{base_path}{lang_prefix}/tags/{}" rel="tag">{}</a>"#,
+                            slug_from_str(tag),
+                            escape_html(tag)
+                        )
+                    }
                 })
                 .collect::<Vec<_>>()
                 .join(" ");
@@ -312,6 +880,7 @@ impl HtmlGenerator {
         };
 
         let mut ctx = TemplateContext::new()
+            .with_var("url", &page.url)
             .with_var("lang", &page.lang)
             .with_var("title", &page.title)
             .with_var("base_path", base_path)
@@ -340,8 +909,8 @@ impl HtmlGenerator {
                 self.generate_section_nav(base_path, &lang_prefix),
             );
 
-        // Add description if present
-        if let Some(desc) = &page.description {
+        // Add description, falling back to the page summary
+        if let Some(desc) = page.description.as_ref().or(page.summary.as_ref()) {
             ctx.insert("description", desc);
         } else if let Some(site_desc) = self.config.description_for_language(&page.lang) {
             ctx.insert("description", site_desc);
@@ -357,23 +926,60 @@ impl HtmlGenerator {
             let css_links = page
                 .custom_css
                 .iter()
-                .map(|href| format!(r#"<link rel="stylesheet" href="{href}">"#))
+                .map(|href| format!(r#"<link rel="stylesheet" href="{}">"#, escape_html(href)))
                 .collect::<Vec<_>>()
                 .join("\n");
             ctx.insert("custom_css", css_links);
         }
 
+        if !page.extra_meta.is_empty() {
+            ctx.insert("extra_meta", extra_meta_tags(&page.extra_meta));
+        }
+
+        if math::has_math(inner_html)
+            && let Some(math) = math::head_html(&self.config)
+        {
+            ctx.insert("math_assets", math);
+        }
+
+        if mermaid::has_diagrams(inner_html) {
+            ctx.insert("mermaid_assets", mermaid::head_html());
+        }
+
+        let lang_prefix = if page.is_default_lang {
+            String::new()
+        } else {
+            format!("/{}", page.lang)
+        };
+        if let Some(scripts) = structured_data::head_scripts(&self.config, page, |section| {
+            self.section_display_title(&lang_prefix, section)
+        }) {
+            ctx.insert("structured_data", scripts);
+        }
+
         // Add custom JS
         if !page.custom_js.is_empty() {
             let js_scripts = page
                 .custom_js
                 .iter()
-                .map(|src| format!(r#"<script src="{src}"></script>"#))
+                .map(|src| format!(r#"<script src="{}"></script>"#, escape_html(src)))
                 .collect::<Vec<_>>()
                 .join("\n");
             ctx.insert("custom_js", js_scripts);
         }
 
+        // Mark drafts in preview builds so they can't be indexed or mistaken
+        // for published content
+        if page.draft && self.config.build.drafts {
+            ctx.insert(
+                "robots_meta",
+                r#"<meta name="robots" content="noindex, nofollow">"#,
+            );
+            ctx.insert("draft_banner", DRAFT_BANNER);
+        } else if let Some(meta) = self.section_robots_meta(&page.url) {
+            ctx.insert("robots_meta", meta);
+        }
+
         // Generate language switcher HTML
         let lang_switcher = self.generate_lang_switcher(&page.lang, &page.canonical_id);
         if !lang_switcher.is_empty() {
@@ -386,10 +992,9 @@ impl HtmlGenerator {
                 .iter()
                 .map(|(lang, url)| {
                     format!(
-                        r#"<link rel="alternate" hreflang="{}" href="{}{}" />"#,
-                        lang,
-                        self.config.base_url(),
-                        url
+                        r#"<link rel="alternate" hreflang="{}" href="{}" />"#,
+                        escape_html(lang),
+                        escape_html(&format!("{}{url}", self.config.base_url()))
                     )
                 })
                 .collect::<Vec<_>>()
@@ -430,7 +1035,8 @@ impl HtmlGenerator {
 
             let selected_class = if *lang == current_lang { " active" } else { "" };
             options.push(format!(
-                r#"<a href="{url}" class="lang-option{selected_class}">{name}</a>"#,
+                r#"<a href="{url}" class="lang-option{selected_class}">{}</a>"#,
+                escape_html(name)
             ));
         }
 
@@ -480,17 +1086,26 @@ impl HtmlGenerator {
         let base_path = self.config.base_path();
 
         let mut items: Vec<_> = tags.iter().collect();
-        items.sort_by(|a, b| b.1.len().cmp(&a.1.len())); // Sort by count descending
+        // Sort by count descending, then by name so the order is stable
+        items.sort_by_key(|(tag, pages)| (std::cmp::Reverse(pages.len()), *tag));
 
         let items_html: String = items
             .iter()
             .map(|(tag, pages)| {
-                format!(
-                    r#"<a href="{base_path}{lang_prefix}/tags/{}" class="tag-item"><span class="tag-name">{}</span><span class="tag-count">{}</span></a>"#,
-                    slug_from_str(tag),
-                    tag,
-                    pages.len()
-                )
+                if self.thin_tags.contains(*tag) {
+                    format!(
+                        r#"<span class="tag-item"><span class="tag-name">{}</span><span class="tag-count">{}</span></span>"#,
+                        escape_html(tag),
+                        pages.len()
+                    )
+                } else {
+                    format!(
+                        r#"<a href="{base_path}{lang_prefix}/tags/{}" class="tag-item"><span class="tag-name">{}</span><span class="tag-count">{}</span></a>"#,
+                        slug_from_str(tag),
+                        escape_html(tag),
+                        pages.len()
+                    )
+                }
             })
             .collect::<Vec<_>>()
             .join("\n");
@@ -532,7 +1147,7 @@ impl HtmlGenerator {
             base_ctx.insert("lang_switcher", lang_switcher);
         }
 
-        Ok(self.templates.render("base", &base_ctx)?)
+        self.render_base(base_ctx)
     }
 
     /// Generate a categories index page listing all categories with their counts.
@@ -557,12 +1172,20 @@ impl HtmlGenerator {
         let items_html: String = items
             .iter()
             .map(|(category, pages)| {
-                format!(
-                    r#"<li><a href="{base_path}{lang_prefix}/categories/{}">{}</a> <span class="count">({})</span></li>"#,
-                    slug_from_str(category),
-                    category,
-                    pages.len()
-                )
+                if self.thin_categories.contains(*category) {
+                    format!(
+                        r#"<li>{} <span class="count">({})</span></li>"#,
+                        escape_html(category),
+                        pages.len()
+                    )
+                } else {
+                    format!(
+                        r#"<li><a href="{base_path}{lang_prefix}/categories/{}">{}</a> <span class="count">({})</span></li>"#,
+                        slug_from_str(category),
+                        escape_html(category),
+                        pages.len()
+                    )
+                }
             })
             .collect::<Vec<_>>()
             .join("\n");
@@ -604,7 +1227,7 @@ impl HtmlGenerator {
             base_ctx.insert("lang_switcher", lang_switcher);
         }
 
-        Ok(self.templates.render("base", &base_ctx)?)
+        self.render_base(base_ctx)
     }
 
     /// Generate an archives page listing all posts grouped by year.
@@ -628,7 +1251,7 @@ impl HtmlGenerator {
 
         // Sort pages within each year by date (newest first)
         for pages in by_year.values_mut() {
-            pages.sort_by(|a, b| b.date.cmp(&a.date));
+            pages.sort_by_key(|p| std::cmp::Reverse(p.date));
         }
 
         // Generate HTML (years in descending order)
@@ -655,7 +1278,11 @@ impl HtmlGenerator {
                         };
                         format!(
                             r#"<li><span class="archive-date">{}</span><span class="archive-badge {}">{}</span><a href="{}">{}</a></li>"#,
-                            date_str, badge_class, badge_label, p.url, p.title
+                            date_str,
+                            badge_class,
+                            badge_label,
+                            p.url,
+                            escape_html(&p.title)
                         )
                     })
                     .collect::<Vec<_>>()
@@ -706,52 +1333,33 @@ impl HtmlGenerator {
             base_ctx.insert("lang_switcher", lang_switcher);
         }
 
-        Ok(self.templates.render("base", &base_ctx)?)
+        self.render_base(base_ctx)
     }
 
-    /// Generate a section index page (e.g., /posts/).
-    pub fn generate_section_page(
+    /// Generate the events page (`/events/` or `/{lang}/events/`) from the
+    /// rendered event list, advertising the calendar at `calendar_url`.
+    pub fn generate_events_page(
         &self,
-        section: &str,
-        description: Option<&str>,
         items_html: &str,
-        pagination_html: Option<&str>,
+        calendar_url: &str,
         lang: &str,
     ) -> Result<String> {
-        let is_default_lang = lang == self.config.site.default_language;
-        let lang_prefix = if is_default_lang {
+        let lang_prefix = if lang == self.config.site.default_language {
             String::new()
         } else {
             format!("/{lang}")
         };
 
-        // Convert section name to title case
-        let title = section
-            .chars()
-            .next()
-            .map(|c| c.to_uppercase().collect::<String>() + &section[1..])
-            .unwrap_or_else(|| section.to_string());
-
-        let mut ctx = TemplateContext::new()
-            .with_var("title", &title)
+        let ctx = TemplateContext::new()
+            .with_var("title", "Events")
             .with_var("items", items_html);
+        let inner_html = self.templates.render("events", &ctx)?;
 
-        if let Some(desc) = description {
-            ctx.insert("description", desc);
-        }
-
-        if let Some(pagination) = pagination_html {
-            ctx.insert("pagination", pagination);
-        }
-
-        let inner_html = self.templates.render("section", &ctx)?;
-
-        // Get the base path for subdirectory deployments
         let base_path = self.config.base_path();
 
         let mut base_ctx = TemplateContext::new()
             .with_var("lang", lang)
-            .with_var("title", &title)
+            .with_var("title", "Events")
             .with_var("base_path", base_path)
             .with_var(
                 "site_title_suffix",
@@ -759,7 +1367,7 @@ impl HtmlGenerator {
             )
             .with_var(
                 "canonical_url",
-                format!("{}{}/{}", self.config.base_url(), lang_prefix, section),
+                format!("{}{}/events", self.config.base_url(), lang_prefix),
             )
             .with_var("content", &inner_html)
             .with_var("site_title", self.config.title_for_language(lang))
@@ -775,15 +1383,190 @@ impl HtmlGenerator {
             .with_var(
                 "section_nav",
                 self.generate_section_nav(base_path, &lang_prefix),
+            )
+            .with_var(
+                "feed_links",
+                format!(
+                    r#"<link rel="alternate" type="text/calendar" title="{}" href="{}">"#,
+                    escape_html(&format!(
+                        "Events | {}",
+                        self.config.title_for_language(lang)
+                    )),
+                    escape_html(calendar_url)
+                ),
             );
 
-        // Generate language switcher
-        let lang_switcher = self.generate_lang_switcher(lang, section);
+        let lang_switcher = self.generate_lang_switcher(lang, "events");
         if !lang_switcher.is_empty() {
             base_ctx.insert("lang_switcher", lang_switcher);
         }
 
-        Ok(self.templates.render("base", &base_ctx)?)
+        self.render_base(base_ctx)
+    }
+
+    /// Generate the changelog page from rendered changelog entries.
+    pub fn generate_changelog_page(&self, items_html: &str) -> Result<String> {
+        let ctx = TemplateContext::new().with_var("items", items_html);
+        let inner_html = self.templates.render("changelog", &ctx)?;
+
+        let base_path = self.config.base_path();
+        let lang = &self.config.site.default_language;
+
+        let base_ctx = TemplateContext::new()
+            .with_var("lang", lang)
+            .with_var("title", "Changelog")
+            .with_var("base_path", base_path)
+            .with_var(
+                "site_title_suffix",
+                format!(" | {}", self.config.title_for_language(lang)),
+            )
+            .with_var(
+                "canonical_url",
+                format!("{}/changelog", self.config.base_url()),
+            )
+            .with_var("content", &inner_html)
+            .with_var("site_title", self.config.title_for_language(lang))
+            .with_var("year", Utc::now().year().to_string())
+            // Navigation URLs
+            .with_var("nav_home_url", format!("{base_path}/"))
+            .with_var("nav_archives_url", format!("{base_path}/archives"))
+            .with_var("nav_tags_url", format!("{base_path}/tags"))
+            .with_var("nav_about_url", format!("{base_path}/about"))
+            .with_var("section_nav", self.generate_section_nav(base_path, ""));
+
+        self.render_base(base_ctx)
+    }
+
+    /// Generate the blogroll page from its rendered list of sites.
+    pub fn generate_blogroll_page(&self, title: &str, items_html: &str) -> Result<String> {
+        let ctx = TemplateContext::new()
+            .with_var("title", title)
+            .with_var("items", items_html);
+        let inner_html = self.templates.render("blogroll", &ctx)?;
+
+        let base_path = self.config.base_path();
+        let lang = &self.config.site.default_language;
+
+        let base_ctx = TemplateContext::new()
+            .with_var("lang", lang)
+            .with_var("title", title)
+            .with_var("base_path", base_path)
+            .with_var(
+                "site_title_suffix",
+                format!(" | {}", self.config.title_for_language(lang)),
+            )
+            .with_var(
+                "canonical_url",
+                format!("{}/blogroll", self.config.base_url()),
+            )
+            .with_var("content", &inner_html)
+            .with_var("site_title", self.config.title_for_language(lang))
+            .with_var("year", Utc::now().year().to_string())
+            // Navigation URLs
+            .with_var("nav_home_url", format!("{base_path}/"))
+            .with_var("nav_archives_url", format!("{base_path}/archives"))
+            .with_var("nav_tags_url", format!("{base_path}/tags"))
+            .with_var("nav_about_url", format!("{base_path}/about"))
+            .with_var("section_nav", self.generate_section_nav(base_path, ""));
+
+        self.render_base(base_ctx)
+    }
+
+    /// Generate a section index page (e.g., /posts/ or /docs/guides/).
+    ///
+    /// `section` is the section path without the language prefix. The
+    /// section's `_index` file, if any, sets its title, a description when
+    /// `description` is `None`, and the template instead of `section`.
+    pub fn generate_section_page(
+        &self,
+        section: &str,
+        description: Option<&str>,
+        items_html: &str,
+        subsections_html: Option<&str>,
+        pagination_html: Option<&str>,
+        lang: &str,
+    ) -> Result<String> {
+        let is_default_lang = lang == self.config.site.default_language;
+        let lang_prefix = if is_default_lang {
+            String::new()
+        } else {
+            format!("/{lang}")
+        };
+
+        // Get the base path for subdirectory deployments
+        let base_path = self.config.base_path();
+
+        let meta = self.section_meta.get(&format!("{lang_prefix}/{section}"));
+        let title = self.section_display_title(&lang_prefix, section);
+        let description = description.or_else(|| meta.and_then(|meta| meta.description.as_deref()));
+
+        let mut ctx = TemplateContext::new()
+            .with_var("title", &title)
+            .with_var("items", items_html)
+            .with_var(
+                "breadcrumbs",
+                breadcrumbs_html(&format!("{base_path}{lang_prefix}"), section, None, |s| {
+                    self.section_display_title(&lang_prefix, s)
+                }),
+            );
+
+        if let Some(desc) = description {
+            ctx.insert("description", desc);
+        }
+
+        if let Some(subsections) = subsections_html {
+            ctx.insert("subsections", subsections);
+        }
+
+        if let Some(pagination) = pagination_html {
+            ctx.insert("pagination", pagination);
+        }
+
+        let template = meta
+            .and_then(|meta| meta.template.as_deref())
+            .unwrap_or("section");
+        let inner_html = self.templates.render(template, &ctx)?;
+        let inner_html = self.wrap_docs(&format!("{lang_prefix}/{section}"), inner_html)?;
+
+        let mut base_ctx = TemplateContext::new()
+            .with_var("lang", lang)
+            .with_var("title", &title)
+            .with_var("base_path", base_path)
+            .with_var(
+                "site_title_suffix",
+                format!(" | {}", self.config.title_for_language(lang)),
+            )
+            .with_var(
+                "canonical_url",
+                format!("{}{}/{}", self.config.base_url(), lang_prefix, section),
+            )
+            .with_var("content", &inner_html)
+            .with_var("site_title", self.config.title_for_language(lang))
+            .with_var("year", Utc::now().year().to_string())
+            // Navigation URLs
+            .with_var("nav_home_url", format!("{base_path}{lang_prefix}/"))
+            .with_var(
+                "nav_archives_url",
+                format!("{base_path}{lang_prefix}/archives"),
+            )
+            .with_var("nav_tags_url", format!("{base_path}{lang_prefix}/tags"))
+            .with_var("nav_about_url", format!("{base_path}{lang_prefix}/about"))
+            .with_var(
+                "section_nav",
+                self.generate_section_nav(base_path, &lang_prefix),
+            );
+
+        if let Some(meta) = self.section_robots_meta(section) {
+            base_ctx.insert("robots_meta", meta);
+        }
+
+        // Generate language switcher
+        let lang_switcher = self.generate_lang_switcher(lang, section);
+        if !lang_switcher.is_empty() {
+            base_ctx.insert("lang_switcher", lang_switcher);
+        }
+
+        self.render_base(base_ctx)
     }
 
     /// Generate a shorts section index page (uses shorts-specific template).
@@ -793,6 +1576,7 @@ impl HtmlGenerator {
         description: Option<&str>,
         items_html: &str,
         pagination_html: Option<&str>,
+        archive_html: Option<&str>,
         lang: &str,
     ) -> Result<String> {
         let is_default_lang = lang == self.config.site.default_language;
@@ -821,6 +1605,10 @@ impl HtmlGenerator {
             ctx.insert("pagination", pagination);
         }
 
+        if let Some(archive) = archive_html {
+            ctx.insert("archive", archive);
+        }
+
         // Use shorts template
         let inner_html = self.templates.render("shorts", &ctx)?;
 
@@ -861,12 +1649,12 @@ impl HtmlGenerator {
             base_ctx.insert("lang_switcher", lang_switcher);
         }
 
-        Ok(self.templates.render("base", &base_ctx)?)
+        self.render_base(base_ctx)
     }
 }
 
 /// Generate a URL-safe slug from a string.
-fn slug_from_str(s: &str) -> String {
+pub(crate) fn slug_from_str(s: &str) -> String {
     s.to_lowercase()
         .chars()
         .map(|c| if c.is_alphanumeric() { c } else { '-' })
@@ -893,19 +1681,29 @@ pub fn list_item_html(page: &Page) -> String {
     let description_html = page
         .description
         .as_ref()
+        .or(page.summary.as_ref())
         .filter(|d| !d.is_empty())
-        .map(|d| format!(r#"<p class="post-description">{d}</p>"#))
+        .map(|d| format!(r#"<p class="post-description">{}</p>"#, escape_html(d)))
         .unwrap_or_default();
 
+    let class = if page.pinned {
+        "post-item pinned"
+    } else {
+        "post-item"
+    };
+
     format!(
-        r#"<li class="post-item">
+        r#"<li class="{class}">
     <div class="post-item-header">
         <a href="{}" class="post-title">{}</a>
         {}
     </div>
     {}
 </li>"#,
-        page.url, page.title, date_html, description_html
+        page.url,
+        escape_html(&page.title),
+        date_html,
+        description_html
     )
 }
 
@@ -924,6 +1722,7 @@ pub fn short_item_html(page: &Page, _author: &str) -> String {
 
     // Use actual content for shorts display
     let content_html = &page.content;
+    let gallery_html = short_gallery_html(page).unwrap_or_default();
 
     format!(
         r#"<div class="short-item">
@@ -931,10 +1730,68 @@ pub fn short_item_html(page: &Page, _author: &str) -> String {
     <div class="short-content">
         {content_html}
     </div>
+    {gallery_html}
 </div>"#
     )
 }
 
+/// Generate the nested table of contents of `entries` (see
+/// [`TocEntry::nest`]).
+pub fn toc_html(entries: &[TocEntry]) -> Option<String> {
+    if entries.is_empty() {
+        return None;
+    }
+    Some(format!(
+        r#"<nav class="toc" aria-label="Table of contents">{}</nav>"#,
+        toc_list_html(entries)
+    ))
+}
+
+fn toc_list_html(entries: &[TocEntry]) -> String {
+    let items: String = entries
+        .iter()
+        .map(|entry| {
+            let children = if entry.children.is_empty() {
+                String::new()
+            } else {
+                toc_list_html(&entry.children)
+            };
+            format!(
+                r##"<li><a href="#{}">{}</a>{children}</li>"##,
+                escape_html(&entry.id),
+                escape_html(&entry.text)
+            )
+        })
+        .collect();
+    format!("<ul>{items}</ul>")
+}
+
+/// Generate the gallery of a short's attached images, linking each
+/// thumbnail to the full image.
+pub fn short_gallery_html(page: &Page) -> Option<String> {
+    if page.images.is_empty() {
+        return None;
+    }
+
+    let items: String = page
+        .images
+        .iter()
+        .map(|image| {
+            format!(
+                r#"<a href="{}"><img src="{}" alt="{}" loading="lazy"></a>"#,
+                escape_html(&image.src),
+                escape_html(image.thumbnail.as_deref().unwrap_or(&image.src)),
+                escape_html(image.alt.as_deref().unwrap_or_default())
+            )
+        })
+        .collect();
+
+    Some(format!(
+        r#"<div class="short-gallery" data-count="{}">{items}</div>"#,
+        page.images.len()
+    ))
+}
+
 /// Generate HTML for shorts with date separators.
 pub fn shorts_with_separators_html(pages: &[&Page], author: &str) -> String {
     let mut result = String::new();
@@ -993,6 +1850,118 @@ pub fn pagination_html(current: usize, total: usize, base_url: &str) -> Option<S
     ))
 }
 
+/// URL of a time window page; the newest window lives at `base_url`.
+fn window_url(windows: &[TimeWindow<'_>], index: usize, base_url: &str) -> String {
+    if index == 0 {
+        base_url.to_string()
+    } else {
+        format!("{base_url}/archive/{}", windows[index].key)
+    }
+}
+
+/// Generate newer/older links between time window pages.
+pub fn window_pagination_html(
+    windows: &[TimeWindow<'_>],
+    current: usize,
+    base_url: &str,
+) -> Option<String> {
+    if windows.len() <= 1 {
+        return None;
+    }
+
+    let mut parts = Vec::new();
+    if current > 0 {
+        parts.push(format!(
+            r#"<a href="{}" rel="prev">← {}</a>"#,
+            window_url(windows, current - 1, base_url),
+            windows[current - 1].label
+        ));
+    }
+    parts.push(format!(
+        r#"<span class="current">{}</span>"#,
+        windows[current].label
+    ));
+    if current + 1 < windows.len() {
+        parts.push(format!(
+            r#"<a href="{}" rel="next">{} →</a>"#,
+            window_url(windows, current + 1, base_url),
+            windows[current + 1].label
+        ));
+    }
+
+    Some(format!(
+        r#"<nav class="pagination">{}</nav>"#,
+        parts.join(" ")
+    ))
+}
+
+/// Generate the archive picker listing every time window with its item
+/// count.
+pub fn archive_picker_html(
+    windows: &[TimeWindow<'_>],
+    current: usize,
+    base_url: &str,
+) -> Option<String> {
+    if windows.len() <= 1 {
+        return None;
+    }
+
+    let items: String = windows
+        .iter()
+        .enumerate()
+        .map(|(i, window)| {
+            let current = if i == current {
+                r#" aria-current="page""#
+            } else {
+                ""
+            };
+            format!(
+                r#"<li><a href="{}"{current}>{}</a> <span class="archive-count">{}</span></li>"#,
+                window_url(windows, i, base_url),
+                window.label,
+                window.pages.len()
+            )
+        })
+        .collect();
+
+    Some(format!(
+        r#"<nav class="archive-picker" aria-label="Archive"><details><summary>{}</summary><ul>{items}</ul></details></nav>"#,
+        windows[current].label
+    ))
+}
+
+/// Build an RSS auto-discovery `<link>` tag.
+fn feed_link_html(title: &str, href: &str) -> String {
+    format!(
+        r#"<link rel="alternate" type="application/rss+xml" title="{}" href="{}">"#,
+        escape_html(title),
+        escape_html(href)
+    )
+}
+
+/// Build `<meta>` tags from a page's `extra_meta` frontmatter.
+///
+/// Open Graph style keys (`og:`, `article:`, ...) use `property`, everything
+/// else (`fediverse:creator`, verification tokens) uses `name`.
+fn extra_meta_tags(meta: &std::collections::BTreeMap<String, String>) -> String {
+    const PROPERTY_PREFIXES: &[&str] = &["og:", "article:", "book:", "profile:", "fb:"];
+    meta.iter()
+        .map(|(key, content)| {
+            let attr = if PROPERTY_PREFIXES.iter().any(|p| key.starts_with(p)) {
+                "property"
+            } else {
+                "name"
+            };
+            format!(
+                r#"<meta {attr}="{}" content="{}">"#,
+                escape_html(key),
+                escape_html(content)
+            )
+        })
+        .collect::<Vec<_>>()
+        .join("\n    ")
+}
+
 #[cfg(test)]
 mod tests {
     use std::collections::HashMap;
@@ -1001,6 +1970,7 @@ mod tests {
 
     fn test_config() -> Config {
         Config {
+            theme: None,
             site: typstify_core::config::SiteConfig {
                 title: "Test Site".to_string(),
                 host: "https://example.com".to_string(),
@@ -1008,6 +1978,7 @@ mod tests {
                 default_language: "en".to_string(),
                 description: Some("A test site".to_string()),
                 author: Some("Test Author".to_string()),
+                timezone: None,
             },
             languages: HashMap::new(),
             build: typstify_core::config::BuildConfig::default(),
@@ -1015,6 +1986,21 @@ mod tests {
             rss: typstify_core::config::RssConfig::default(),
             robots: typstify_core::config::RobotsConfig::default(),
             taxonomies: typstify_core::config::TaxonomyConfig::default(),
+            dates: typstify_core::config::DatesConfig::default(),
+            css: typstify_core::config::CssConfig::default(),
+            images: typstify_core::config::ImagesConfig::default(),
+            markdown: typstify_core::config::MarkdownConfig::default(),
+            typography: typstify_core::config::TypographyConfig::default(),
+            sections: std::collections::HashMap::new(),
+            check: typstify_core::config::CheckConfig::default(),
+            webmentions: typstify_core::config::WebmentionsConfig::default(),
+            identity: typstify_core::config::IdentityConfig::default(),
+            content: typstify_core::config::ContentConfig::default(),
+            ping: typstify_core::config::PingConfig::default(),
+            toc: typstify_core::config::TocConfig::default(),
+            math: typstify_core::config::MathConfig::default(),
+            mermaid: typstify_core::config::MermaidConfig::default(),
+            fmt: typstify_core::config::FmtConfig::default(),
         }
     }
 
@@ -1025,6 +2011,7 @@ mod tests {
             description: Some("A test page".to_string()),
             date: None,
             updated: None,
+            expiry_date: None,
             draft: false,
             lang: "en".to_string(),
             is_default_lang: true,
@@ -1041,10 +2028,325 @@ mod tests {
             aliases: vec![],
             template: None,
             weight: 0,
+            pinned: false,
+            series: None,
+            content_id: None,
+            extra_meta: std::collections::BTreeMap::new(),
+            structured_data: None,
+            images: vec![],
+            event: None,
             source_path: Some(PathBuf::from("test-page.md")),
         }
     }
 
+    #[test]
+    fn test_generate_page_structured_data() {
+        let generator = HtmlGenerator::new(test_config());
+        let mut page = test_page();
+
+        let html = generator.generate_page(&page, &[]).unwrap();
+        assert!(html.contains(r#"<script type="application/ld+json">{"@context":"https://schema.org","@type":"WebSite""#));
+        assert!(html.contains(r#""@type":"BreadcrumbList""#));
+        assert!(!html.contains("BlogPosting"));
+
+        page.structured_data = Some(false);
+        let html = generator.generate_page(&page, &[]).unwrap();
+        assert!(!html.contains("application/ld+json"));
+    }
+
+    #[test]
+    fn test_generate_draft_page() {
+        let mut config = test_config();
+        let mut page = test_page();
+        page.draft = true;
+
+        // Drafts excluded: no marker (the page would not be built anyway)
+        let html = HtmlGenerator::new(config.clone())
+            .generate_page(&page, &[])
+            .unwrap();
+        assert!(!html.contains("noindex"));
+        assert!(!html.contains("draft-banner"));
+
+        config.build.drafts = true;
+        let html = HtmlGenerator::new(config.clone())
+            .generate_page(&page, &[])
+            .unwrap();
+        assert!(html.contains(r#"<meta name="robots" content="noindex, nofollow">"#));
+        assert!(html.contains("DRAFT — not published"));
+
+        // Published pages in a draft build stay indexable
+        page.draft = false;
+        let html = HtmlGenerator::new(config)
+            .generate_page(&page, &[])
+            .unwrap();
+        assert!(!html.contains("noindex"));
+    }
+
+    #[test]
+    fn test_localized_date() {
+        let mut config = test_config();
+        config.languages.insert(
+            "zh".to_string(),
+            typstify_core::config::LanguageConfig {
+                date_format: Some("%Y年%-m月%-d日".to_string()),
+                ..Default::default()
+            },
+        );
+        let generator = HtmlGenerator::new(config);
+        let mut page = test_page();
+        page.date = Some(
+            chrono::DateTime::parse_from_rfc3339("2026-01-14T00:00:00Z")
+                .unwrap()
+                .with_timezone(&Utc),
+        );
+
+        let html = generator.generate_page(&page, &[]).unwrap();
+        assert!(html.contains("January 14, 2026"));
+
+        page.lang = "zh".to_string();
+        page.is_default_lang = false;
+        let html = generator.generate_page(&page, &[]).unwrap();
+        assert!(html.contains("2026年1月14日"));
+    }
+
+    #[test]
+    fn test_generate_page_backlinks() {
+        let config = test_config();
+        let target = test_page();
+        let mut source = test_page();
+        source.url = "/source".to_string();
+        source.title = "Source Page".to_string();
+        source.content = r#"<p>See <a href="/test-page">the test page</a>.</p>"#.to_string();
+
+        let mut content = crate::collector::SiteContent::default();
+        content.pages.insert(target.url.clone(), target.clone());
+        content.pages.insert(source.url.clone(), source.clone());
+        let graph = LinkGraph::build(&config, &content, |_| true);
+        let generator = HtmlGenerator::new(config).with_link_graph(graph);
+
+        let html = generator.generate_page(&target, &[]).unwrap();
+        assert!(html.contains("<h2>Linked from</h2>"));
+        assert!(html.contains(r#"<li><a href="/source">Source Page</a></li>"#));
+
+        let html = generator.generate_page(&source, &[]).unwrap();
+        assert!(!html.contains("Linked from"));
+    }
+
+    #[test]
+    fn test_generate_page_print_button() {
+        let page = test_page();
+        let html = HtmlGenerator::new(test_config())
+            .generate_page(&page, &[])
+            .unwrap();
+        assert!(html.contains(r#"href="/assets/print.css" media="print""#));
+        assert!(!html.contains("print-button"));
+
+        let mut config = test_config();
+        config.build.print_button = true;
+        let html = HtmlGenerator::new(config)
+            .generate_page(&page, &[])
+            .unwrap();
+        assert!(html.contains(PRINT_BUTTON));
+    }
+
+    #[test]
+    fn test_generate_page_math_assets() {
+        let mut config = test_config();
+        config.math.enabled = true;
+        let generator = HtmlGenerator::new(config);
+
+        let mut page = test_page();
+        assert!(
+            !generator
+                .generate_page(&page, &[])
+                .unwrap()
+                .contains("katex")
+        );
+
+        page.content = r#"<p><span class="math inline">\(x^2\)</span></p>"#.to_string();
+        let html = generator.generate_page(&page, &[]).unwrap();
+        assert!(html.contains("katex.min.css"));
+        assert!(html.contains("contrib/auto-render.min.js"));
+    }
+
+    #[test]
+    fn test_generate_page_mermaid_assets() {
+        let generator = HtmlGenerator::new(test_config());
+        let mut page = test_page();
+        assert!(
+            !generator
+                .generate_page(&page, &[])
+                .unwrap()
+                .contains("mermaid.esm")
+        );
+
+        page.content = "<pre class=\"mermaid\">graph LR</pre>".to_string();
+        let html = generator.generate_page(&page, &[]).unwrap();
+        assert!(html.contains("mermaid.esm.min.mjs"));
+    }
+
+    #[test]
+    fn test_generate_page_breadcrumbs() {
+        let mut config = test_config();
+        config.site.base_path = "/blog".to_string();
+        let generator = HtmlGenerator::new(config);
+        let mut page = test_page();
+        page.url = "/docs/guides/install".to_string();
+        page.title = "Install".to_string();
+
+        let html = generator.generate_page(&page, &[]).unwrap();
+        assert!(html.contains(
+            r#"<nav class="breadcrumbs" aria-label="Breadcrumb"><ol><li><a href="/blog/">Home</a></li><li><a href="/blog/docs">Docs</a></li><li><a href="/blog/docs/guides">Guides</a></li><li aria-current="page">Install</li></ol></nav>"#
+        ));
+
+        page.url = "/".to_string();
+        let ctx = generator.build_page_context(&page).unwrap();
+        assert!(ctx.get("breadcrumbs_html").is_none());
+    }
+
+    #[test]
+    fn test_generate_post_neighbors() {
+        let mut page = test_page();
+        page.date = Some(Utc::now());
+        let neighbors = HashMap::from([(
+            page.url.clone(),
+            Neighbors {
+                prev: Some("/older".to_string()),
+                next: None,
+            },
+        )]);
+        let titles = HashMap::from([("/older".to_string(), "Older Post".to_string())]);
+        let generator = HtmlGenerator::new(test_config()).with_neighbors(neighbors, titles);
+
+        let html = generator.generate_page(&page, &[]).unwrap();
+        assert!(html.contains(
+            r#"<nav class="post-nav" aria-label="Posts"><a class="post-nav-prev" href="/older" rel="prev"><span>← Previous</span>Older Post</a></nav>"#
+        ));
+
+        let ctx = generator.build_page_context(&page).unwrap();
+        assert_eq!(ctx.get("prev_url"), Some("/older"));
+        assert_eq!(ctx.get("prev_title"), Some("Older Post"));
+        assert_eq!(ctx.get("next_url"), None);
+    }
+
+    #[test]
+    fn test_search_index_hints() {
+        let mut config = test_config();
+        config.site.base_path = "/blog".to_string();
+        config.search.preload = SearchPreload::Prefetch;
+        let page = test_page();
+
+        let html = HtmlGenerator::new(config.clone())
+            .generate_page(&page, &[])
+            .unwrap();
+        assert!(html.contains(
+            r#"<link rel="prefetch" href="/blog/search-index.json" as="fetch" crossorigin data-search-index>"#
+        ));
+
+        let inline = HashMap::from([("en".to_string(), r#"{"t":"</script>"}"#.to_string())]);
+        let html = HtmlGenerator::new(config)
+            .with_inline_search(inline)
+            .generate_page(&page, &[])
+            .unwrap();
+        assert!(html.contains(
+            r#"<script type="application/json" id="search-index-data">{"t":"<\/script>"}</script>"#
+        ));
+        assert!(!html.contains("data-search-index"));
+    }
+
+    #[test]
+    fn test_chunked_search_config() {
+        let mut config = test_config();
+        config.site.base_path = "/blog".to_string();
+        let page = test_page();
+        let html = HtmlGenerator::new(config.clone())
+            .generate_page(&page, &[])
+            .unwrap();
+        assert!(!html.contains("search-engine-config"));
+
+        let html = HtmlGenerator::new(config)
+            .with_chunked_search(HashSet::from(["en".to_string()]))
+            .generate_page(&page, &[])
+            .unwrap();
+        assert!(html.contains(
+            r#"<script type="application/json" id="search-engine-config">{"index":"/blog/search","runtime":"/blog/pkg/typstify_search_wasm.js"}</script>"#
+        ));
+    }
+
+    #[test]
+    fn test_search_misses_config() {
+        let mut config = test_config();
+        let page = test_page();
+        let html = HtmlGenerator::new(config.clone())
+            .generate_page(&page, &[])
+            .unwrap();
+        assert!(!html.contains("search-misses-config"));
+
+        config.search.miss_endpoint = Some("https://stats.example.com/miss".to_string());
+        let html = HtmlGenerator::new(config)
+            .generate_page(&page, &[])
+            .unwrap();
+        assert!(html.contains(
+            r#"<script type="application/json" id="search-misses-config">{"endpoint":"https://stats.example.com/miss","local":false}</script>"#
+        ));
+    }
+
+    #[test]
+    fn test_extra_meta() {
+        let mut page = test_page();
+        page.extra_meta.insert(
+            "fediverse:creator".to_string(),
+            "@alice@example.social".to_string(),
+        );
+        page.extra_meta.insert(
+            "og:audio".to_string(),
+            "https://example.com/a.mp3".to_string(),
+        );
+        page.extra_meta
+            .insert("x-note".to_string(), r#""quoted" <b>"#.to_string());
+
+        let html = HtmlGenerator::new(test_config())
+            .generate_page(&page, &[])
+            .unwrap();
+
+        assert!(
+            html.contains(r#"<meta name="fediverse:creator" content="@alice@example.social">"#)
+        );
+        assert!(html.contains(r#"<meta property="og:audio" content="https://example.com/a.mp3">"#));
+        assert!(html.contains(r#"<meta name="x-note" content="&quot;quoted&quot; &lt;b&gt;">"#));
+    }
+
+    #[test]
+    fn test_page_text_escaped() {
+        let mut page = test_page();
+        page.title = "Tips & <Tricks>".to_string();
+        page.description = Some(r#"Say "hi""#.to_string());
+        page.content = "<p>Body &amp; more</p>".to_string();
+
+        let html = HtmlGenerator::new(test_config())
+            .generate_page(&page, &[])
+            .unwrap();
+
+        assert!(html.contains("<title>Tips &amp; &lt;Tricks&gt; | Test Site</title>"));
+        assert!(html.contains(r#"<meta name="description" content="Say &quot;hi&quot;">"#));
+        assert!(html.contains("<p>Body &amp; more</p>"));
+    }
+
+    #[test]
+    fn test_head_links_escaped() {
+        let mut config = test_config();
+        config.site.title = "Tom & Jerry".to_string();
+        config.rss.enabled = true;
+        let page = test_page();
+
+        let html = HtmlGenerator::new(config)
+            .generate_page(&page, &[("en", "/test?a=1&b=2")])
+            .unwrap();
+
+        assert!(html.contains(r#"title="Tom &amp; Jerry" href="/rss.xml""#));
+        assert!(html.contains(r#"hreflang="en" href="https://example.com/test?a=1&amp;b=2""#));
+    }
+
     #[test]
     fn test_generate_page() {
         let generator = HtmlGenerator::new(test_config());
@@ -1090,6 +2392,20 @@ mod tests {
         assert!(html.contains("/test-page"));
     }
 
+    #[test]
+    fn test_toc_html() {
+        assert_eq!(toc_html(&[]), None);
+
+        let toc = [TocEntry {
+            children: vec![TocEntry::new(2, "A & B", "a-b")],
+            ..TocEntry::new(1, "Intro", "intro")
+        }];
+        assert_eq!(
+            toc_html(&toc).unwrap(),
+            r##"<nav class="toc" aria-label="Table of contents"><ul><li><a href="#intro">Intro</a><ul><li><a href="#a-b">A &amp; B</a></li></ul></li></ul></nav>"##
+        );
+    }
+
     #[test]
     fn test_pagination_html() {
         // Single page - no pagination
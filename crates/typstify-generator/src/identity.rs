@@ -0,0 +1,165 @@
+//! Author identity for fediverse verification.
+//!
+//! Renders `rel="me"` links for the profiles in `[identity]` and a static
+//! `/.well-known/webfinger` document pointing at the author's fediverse
+//! account, so `@anything@example.com` lookups resolve to it.
+
+use serde_json::json;
+use typstify_core::{Config, config::IdentityConfig, escape_html};
+
+/// Path of the webfinger document, relative to the output directory.
+pub const WEBFINGER_FILE: &str = ".well-known/webfinger";
+
+/// A fediverse account (`@user@instance`).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FediverseAccount {
+    /// User name.
+    pub user: String,
+
+    /// Instance host name.
+    pub host: String,
+}
+
+impl FediverseAccount {
+    /// Parse `@user@instance` (the leading `@` and an `acct:` prefix are
+    /// optional).
+    #[must_use]
+    pub fn parse(account: &str) -> Option<Self> {
+        let account = account.trim();
+        let account = account.strip_prefix("acct:").unwrap_or(account);
+        let (user, host) = account.trim_start_matches('@').split_once('@')?;
+        if user.is_empty() || host.is_empty() || host.contains(['/', '@']) {
+            return None;
+        }
+        Some(Self {
+            user: user.to_string(),
+            host: host.to_string(),
+        })
+    }
+
+    /// Public profile page (Mastodon style).
+    #[must_use]
+    pub fn profile_url(&self) -> String {
+        format!("https://{}/@{}", self.host, self.user)
+    }
+
+    /// ActivityPub actor URL (Mastodon style).
+    #[must_use]
+    pub fn actor_url(&self) -> String {
+        format!("https://{}/users/{}", self.host, self.user)
+    }
+
+    /// The webfinger JRD document for this account.
+    #[must_use]
+    pub fn webfinger_json(&self) -> String {
+        let profile = self.profile_url();
+        let actor = self.actor_url();
+        let document = json!({
+            "subject": format!("acct:{}@{}", self.user, self.host),
+            "aliases": [profile, actor],
+            "links": [
+                {
+                    "rel": "http://webfinger.net/rel/profile-page",
+                    "type": "text/html",
+                    "href": profile,
+                },
+                {
+                    "rel": "self",
+                    "type": "application/activity+json",
+                    "href": actor,
+                },
+            ],
+        });
+        serde_json::to_string_pretty(&document).unwrap_or_default()
+    }
+}
+
+/// `<link rel="me">` and `fediverse:creator` tags for the page head.
+#[must_use]
+pub fn head_links(identity: &IdentityConfig) -> Option<String> {
+    let account = identity
+        .fediverse
+        .as_deref()
+        .and_then(FediverseAccount::parse);
+
+    let mut profiles: Vec<String> = identity.me.clone();
+    if let Some(ref account) = account {
+        let profile = account.profile_url();
+        if !profiles.contains(&profile) {
+            profiles.push(profile);
+        }
+    }
+
+    let mut tags: Vec<String> = profiles
+        .iter()
+        .map(|href| format!(r#"<link rel="me" href="{}">"#, escape_html(href)))
+        .collect();
+    if let Some(account) = account {
+        tags.push(format!(
+            r#"<meta name="fediverse:creator" content="@{}@{}">"#,
+            account.user, account.host
+        ));
+    }
+
+    (!tags.is_empty()).then(|| tags.join("\n    "))
+}
+
+/// The webfinger document for the configured account, if any.
+#[must_use]
+pub fn webfinger(config: &Config) -> Option<String> {
+    config
+        .identity
+        .fediverse
+        .as_deref()
+        .and_then(FediverseAccount::parse)
+        .map(|account| account.webfinger_json())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_account() {
+        let account = FediverseAccount::parse("@alice@mastodon.social").unwrap();
+        assert_eq!(account.user, "alice");
+        assert_eq!(account.host, "mastodon.social");
+        assert_eq!(
+            FediverseAccount::parse("acct:alice@mastodon.social"),
+            Some(account)
+        );
+        assert_eq!(FediverseAccount::parse("alice"), None);
+        assert_eq!(FediverseAccount::parse("@alice@"), None);
+    }
+
+    #[test]
+    fn test_webfinger_json() {
+        let json = FediverseAccount::parse("@alice@mastodon.social")
+            .unwrap()
+            .webfinger_json();
+        let value: serde_json::Value = serde_json::from_str(&json).unwrap();
+
+        assert_eq!(value["subject"], "acct:alice@mastodon.social");
+        assert_eq!(value["links"][1]["rel"], "self");
+        assert_eq!(
+            value["links"][1]["href"],
+            "https://mastodon.social/users/alice"
+        );
+    }
+
+    #[test]
+    fn test_head_links() {
+        let identity = IdentityConfig {
+            me: vec!["https://github.com/alice".to_string()],
+            fediverse: Some("@alice@mastodon.social".to_string()),
+        };
+
+        assert_eq!(
+            head_links(&identity).unwrap(),
+            "<link rel=\"me\" href=\"https://github.com/alice\">\n    \
+             <link rel=\"me\" href=\"https://mastodon.social/@alice\">\n    \
+             <meta name=\"fediverse:creator\" content=\"@alice@mastodon.social\">"
+        );
+        assert_eq!(head_links(&IdentityConfig::default()), None);
+    }
+}
@@ -0,0 +1,410 @@
+//! Responsive image generation.
+//!
+//! Resizes local raster images to the configured breakpoints, encodes them
+//! in additional formats (WebP, and AVIF with the `avif` feature) and wraps
+//! the `<img>` tags in `<picture>` elements with `srcset`/`sizes`.
+
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
+
+use image::{DynamicImage, ImageFormat, ImageReader, imageops::FilterType};
+use thiserror::Error;
+use tracing::warn;
+use typstify_core::{config::ImagesConfig, escape_html};
+
+use crate::audit::scan_html;
+
+/// Image processing errors.
+#[derive(Debug, Error)]
+pub enum ImageError {
+    /// IO error.
+    #[error("IO error: {0}")]
+    Io(#[from] std::io::Error),
+
+    /// Decoding or encoding failed.
+    #[error("image error: {0}")]
+    Image(#[from] image::ImageError),
+
+    /// The file is not a supported raster image.
+    #[error("unsupported image: {0}")]
+    Unsupported(PathBuf),
+}
+
+/// Result type for image operations.
+pub type Result<T> = std::result::Result<T, ImageError>;
+
+/// A set of variants in one format.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct VariantSet {
+    /// MIME type of the variants.
+    pub mime: &'static str,
+
+    /// `(file name, width)` pairs, narrowest first.
+    pub files: Vec<(String, u32)>,
+}
+
+/// Generated variants of one image.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ImageVariants {
+    /// Original width in pixels.
+    pub width: u32,
+
+    /// Original height in pixels.
+    pub height: u32,
+
+    /// Variants in the original format (used for the fallback `<img>`).
+    pub fallback: VariantSet,
+
+    /// Variants in the additional formats, preferred first.
+    pub sources: Vec<VariantSet>,
+}
+
+/// Edge length in pixels of gallery thumbnails.
+pub const THUMBNAIL_SIZE: u32 = 320;
+
+/// Generates resized image variants.
+#[derive(Debug, Clone)]
+pub struct ImagePipeline {
+    breakpoints: Vec<u32>,
+    formats: Vec<ImageFormat>,
+    sizes: String,
+}
+
+impl ImagePipeline {
+    /// Create a pipeline from the `[images]` configuration.
+    ///
+    /// Unknown formats are skipped with a warning.
+    #[must_use]
+    pub fn new(config: &ImagesConfig) -> Self {
+        let mut breakpoints = config.breakpoints.clone();
+        breakpoints.sort_unstable();
+        breakpoints.dedup();
+
+        let formats = config
+            .formats
+            .iter()
+            .filter_map(|name| {
+                let format = match name.as_str() {
+                    "webp" => Some(ImageFormat::WebP),
+                    "avif" => Some(ImageFormat::Avif),
+                    _ => None,
+                };
+                if format.is_none() {
+                    warn!(format = %name, "unknown responsive image format");
+                }
+                format
+            })
+            .collect();
+
+        Self {
+            breakpoints,
+            formats,
+            sizes: config.sizes.clone(),
+        }
+    }
+
+    /// Whether the file at `path` can be processed (PNG, JPEG or WebP).
+    #[must_use]
+    pub fn supports(path: &Path) -> bool {
+        source_format(path).is_some()
+    }
+
+    /// Generate variants of `source` into `dest_dir`, named
+    /// `{stem}-{width}.{ext}`.
+    pub fn process(&self, source: &Path, dest_dir: &Path) -> Result<ImageVariants> {
+        let format =
+            source_format(source).ok_or_else(|| ImageError::Unsupported(source.to_path_buf()))?;
+        let image = ImageReader::open(source)?.with_guessed_format()?.decode()?;
+        let (width, height) = (image.width(), image.height());
+        let stem = source
+            .file_stem()
+            .unwrap_or_default()
+            .to_string_lossy()
+            .into_owned();
+
+        let mut widths: Vec<u32> = self
+            .breakpoints
+            .iter()
+            .copied()
+            .filter(|w| *w > 0 && *w < width)
+            .collect();
+        widths.push(width);
+
+        fs::create_dir_all(dest_dir)?;
+        let resized: Vec<(u32, DynamicImage)> = widths
+            .iter()
+            .map(|&w| {
+                let img = if w == width {
+                    image.clone()
+                } else {
+                    let h = (u64::from(height) * u64::from(w) / u64::from(width)).max(1) as u32;
+                    image.resize_exact(w, h, FilterType::CatmullRom)
+                };
+                (w, img)
+            })
+            .collect();
+
+        let encode = |format: ImageFormat| -> Result<VariantSet> {
+            let ext = format.extensions_str()[0];
+            let mut files = Vec::new();
+            for (w, img) in &resized {
+                let name = format!("{stem}-{w}.{ext}");
+                let img = if format == ImageFormat::Jpeg {
+                    DynamicImage::ImageRgb8(img.to_rgb8())
+                } else {
+                    img.clone()
+                };
+                img.save_with_format(dest_dir.join(&name), format)?;
+                files.push((name, *w));
+            }
+            Ok(VariantSet {
+                mime: format.to_mime_type(),
+                files,
+            })
+        };
+
+        let fallback = encode(format)?;
+        let mut sources = Vec::new();
+        for &extra in self.formats.iter().filter(|f| **f != format) {
+            match encode(extra) {
+                Ok(set) => sources.push(set),
+                Err(e) => {
+                    warn!(
+                        image = %source.display(),
+                        format = ?extra,
+                        error = %e,
+                        "failed to encode image variant"
+                    );
+                }
+            }
+        }
+
+        Ok(ImageVariants {
+            width,
+            height,
+            fallback,
+            sources,
+        })
+    }
+
+    /// Generate a square thumbnail of `source` into `dest_dir`, cropped to
+    /// fill `size` pixels and named `{stem}-thumb.{ext}`. Returns the file
+    /// name.
+    pub fn thumbnail(source: &Path, dest_dir: &Path, size: u32) -> Result<String> {
+        let format =
+            source_format(source).ok_or_else(|| ImageError::Unsupported(source.to_path_buf()))?;
+        let image = ImageReader::open(source)?.with_guessed_format()?.decode()?;
+        let stem = source.file_stem().unwrap_or_default().to_string_lossy();
+        let name = format!("{stem}-thumb.{}", format.extensions_str()[0]);
+
+        let thumb = image.resize_to_fill(size, size, FilterType::CatmullRom);
+        let thumb = if format == ImageFormat::Jpeg {
+            DynamicImage::ImageRgb8(thumb.to_rgb8())
+        } else {
+            thumb
+        };
+        fs::create_dir_all(dest_dir)?;
+        thumb.save_with_format(dest_dir.join(&name), format)?;
+        Ok(name)
+    }
+
+    /// Wrap an `<img>` tag in a `<picture>` element offering the variants.
+    ///
+    /// `url_dir` is the URL prefix (ending in `/`, or empty) of the variant
+    /// files, i.e. the directory part of the original `src`.
+    #[must_use]
+    pub fn picture_html(&self, img_tag: &str, url_dir: &str, variants: &ImageVariants) -> String {
+        let srcset = |set: &VariantSet| {
+            set.files
+                .iter()
+                .map(|(name, w)| format!("{url_dir}{name} {w}w"))
+                .collect::<Vec<_>>()
+                .join(", ")
+        };
+        let sizes = escape_html(&self.sizes);
+
+        let mut html = String::from("<picture>");
+        for set in &variants.sources {
+            html.push_str(&format!(
+                r#"<source type="{}" srcset="{}" sizes="{sizes}">"#,
+                set.mime,
+                srcset(set)
+            ));
+        }
+
+        let has_attr = |name: &str| {
+            scan_html(img_tag)
+                .first()
+                .is_some_and(|token| token.attr(name).is_some())
+        };
+        let mut attrs = format!(
+            r#" srcset="{}" sizes="{sizes}""#,
+            srcset(&variants.fallback)
+        );
+        if !has_attr("width") && !has_attr("height") {
+            attrs.push_str(&format!(
+                r#" width="{}" height="{}""#,
+                variants.width, variants.height
+            ));
+        }
+        html.push_str(&img_tag.replacen("<img", &format!("<img{attrs}"), 1));
+        html.push_str("</picture>");
+        html
+    }
+}
+
+/// Rewrite `<img>` tags outside existing `<picture>` elements.
+///
+/// `rewrite` receives each tag and its `src`, and returns the replacement
+/// HTML or `None` to keep the tag.
+pub fn rewrite_img_tags(
+    html: &str,
+    mut rewrite: impl FnMut(&str, &str) -> Option<String>,
+) -> String {
+    let mut out = String::with_capacity(html.len());
+    let mut rest = html;
+    let mut picture_depth = 0usize;
+
+    while let Some(start) = rest.find('<') {
+        out.push_str(&rest[..start]);
+        let tail = &rest[start..];
+        let Some(end) = tail.find('>') else {
+            break;
+        };
+        let tag = &tail[..=end];
+        let lower = tag.to_ascii_lowercase();
+
+        if lower.starts_with("<picture") {
+            picture_depth += 1;
+        } else if lower.starts_with("</picture") {
+            picture_depth = picture_depth.saturating_sub(1);
+        } else if picture_depth == 0
+            && (lower.starts_with("<img ") || lower.starts_with("<img\t"))
+            && let Some(src) = scan_html(tag).first().and_then(|t| t.attr("src"))
+            && let Some(replacement) = rewrite(tag, src)
+        {
+            out.push_str(&replacement);
+            rest = &tail[end + 1..];
+            continue;
+        }
+
+        out.push_str(tag);
+        rest = &tail[end + 1..];
+    }
+
+    out.push_str(rest);
+    out
+}
+
+/// Raster format of a processable image, by file extension.
+fn source_format(path: &Path) -> Option<ImageFormat> {
+    let ext = path.extension()?.to_string_lossy().to_ascii_lowercase();
+    match ext.as_str() {
+        "png" => Some(ImageFormat::Png),
+        "jpg" | "jpeg" => Some(ImageFormat::Jpeg),
+        "webp" => Some(ImageFormat::WebP),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use image::RgbImage;
+    use tempfile::TempDir;
+
+    use super::*;
+
+    fn pipeline() -> ImagePipeline {
+        ImagePipeline::new(&ImagesConfig {
+            responsive: true,
+            breakpoints: vec![200, 50, 100],
+            formats: vec!["webp".to_string(), "bogus".to_string()],
+            sizes: "100vw".to_string(),
+        })
+    }
+
+    #[test]
+    fn test_thumbnail() {
+        let dir = TempDir::new().unwrap();
+        let source = dir.path().join("photo.jpg");
+        RgbImage::new(120, 60).save(&source).unwrap();
+
+        let out = dir.path().join("out");
+        let name = ImagePipeline::thumbnail(&source, &out, 40).unwrap();
+
+        assert_eq!(name, "photo-thumb.jpg");
+        let thumb = image::open(out.join(&name)).unwrap();
+        assert_eq!((thumb.width(), thumb.height()), (40, 40));
+    }
+
+    #[test]
+    fn test_process_image() {
+        let dir = TempDir::new().unwrap();
+        let source = dir.path().join("photo.png");
+        RgbImage::new(120, 60).save(&source).unwrap();
+
+        let out = dir.path().join("out");
+        let variants = pipeline().process(&source, &out).unwrap();
+
+        assert_eq!((variants.width, variants.height), (120, 60));
+        assert_eq!(variants.fallback.mime, "image/png");
+        assert_eq!(
+            variants.fallback.files,
+            vec![
+                ("photo-50.png".to_string(), 50),
+                ("photo-100.png".to_string(), 100),
+                ("photo-120.png".to_string(), 120),
+            ]
+        );
+        assert_eq!(variants.sources.len(), 1);
+        assert_eq!(variants.sources[0].mime, "image/webp");
+
+        let small = ImageReader::open(out.join("photo-50.webp"))
+            .unwrap()
+            .into_dimensions()
+            .unwrap();
+        assert_eq!(small, (50, 25));
+    }
+
+    #[test]
+    fn test_picture_html() {
+        let variants = ImageVariants {
+            width: 120,
+            height: 60,
+            fallback: VariantSet {
+                mime: "image/jpeg",
+                files: vec![("a-50.jpg".to_string(), 50), ("a-120.jpg".to_string(), 120)],
+            },
+            sources: vec![VariantSet {
+                mime: "image/webp",
+                files: vec![("a-50.webp".to_string(), 50)],
+            }],
+        };
+
+        let html =
+            pipeline().picture_html(r#"<img src="/img/a.jpg" alt="A" />"#, "/img/", &variants);
+        assert_eq!(
+            html,
+            r#"<picture><source type="image/webp" srcset="/img/a-50.webp 50w" sizes="100vw"><img srcset="/img/a-50.jpg 50w, /img/a-120.jpg 120w" sizes="100vw" width="120" height="60" src="/img/a.jpg" alt="A" /></picture>"#
+        );
+    }
+
+    #[test]
+    fn test_rewrite_img_tags() {
+        let html =
+            r#"<p><img src="a.png" /></p><picture><img src="b.png"></picture><img src="c.gif">"#;
+        let mut seen = Vec::new();
+        let out = rewrite_img_tags(html, |tag, src| {
+            seen.push(src.to_string());
+            (src == "a.png").then(|| format!("<x>{tag}</x>"))
+        });
+
+        assert_eq!(seen, vec!["a.png", "c.gif"]);
+        assert_eq!(
+            out,
+            r#"<p><x><img src="a.png" /></x></p><picture><img src="b.png"></picture><img src="c.gif">"#
+        );
+    }
+}
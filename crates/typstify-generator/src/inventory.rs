@@ -0,0 +1,132 @@
+//! Content inventory.
+//!
+//! One entry per page with the metadata listings are built from, newest
+//! first. `typstify list` prints it, as a table or (`--json`) as the JSON
+//! that client-side components such as `typstify_ui::ArchiveTimeline` load.
+
+use std::cmp::Reverse;
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use typstify_core::Page;
+
+use crate::collector::SiteContent;
+
+/// A page in the inventory.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct InventoryEntry {
+    /// Page URL.
+    pub url: String,
+
+    /// Page title.
+    pub title: String,
+
+    /// Publication date.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub date: Option<DateTime<Utc>>,
+
+    /// Language code.
+    pub lang: String,
+
+    /// Top-level section, if the page is in one.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub section: Option<String>,
+
+    /// Template the page is rendered with, if not the default.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub template: Option<String>,
+
+    /// Whether the page is a draft.
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub draft: bool,
+
+    /// Tags.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub tags: Vec<String>,
+}
+
+impl From<&Page> for InventoryEntry {
+    fn from(page: &Page) -> Self {
+        Self {
+            url: page.url.clone(),
+            title: page.title.clone(),
+            date: page.date,
+            lang: page.lang.clone(),
+            section: page
+                .canonical_id
+                .split_once('/')
+                .map(|(section, _)| section.to_string()),
+            template: page.template.clone(),
+            draft: page.draft,
+            tags: page.tags.clone(),
+        }
+    }
+}
+
+/// The pages of `content`, newest first; undated pages follow by URL.
+#[must_use]
+pub fn inventory(content: &SiteContent) -> Vec<InventoryEntry> {
+    let mut entries: Vec<InventoryEntry> =
+        content.pages.values().map(InventoryEntry::from).collect();
+    entries.sort_by(|a, b| (Reverse(a.date), &a.url).cmp(&(Reverse(b.date), &b.url)));
+    entries
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::Path;
+
+    use chrono::TimeZone;
+    use typstify_core::{
+        content::{ContentPath, ParsedContent},
+        frontmatter::Frontmatter,
+    };
+
+    use super::*;
+
+    fn page(path: &str, title: &str, day: Option<u32>) -> Page {
+        Page::from_parsed(
+            ParsedContent {
+                frontmatter: Frontmatter {
+                    title: title.to_string(),
+                    date: day.map(|day| Utc.with_ymd_and_hms(2026, 1, day, 0, 0, 0).unwrap()),
+                    tags: vec!["rust".to_string()],
+                    ..Frontmatter::default()
+                },
+                html: String::new(),
+                raw: String::new(),
+                toc: vec![],
+                dependencies: vec![],
+            },
+            &ContentPath::from_path(Path::new(path), "en").unwrap(),
+        )
+    }
+
+    #[test]
+    fn test_inventory() {
+        let mut content = SiteContent::default();
+        for page in [
+            page("about.md", "About", None),
+            page("posts/old.md", "Old", Some(1)),
+            page("posts/new.md", "New", Some(2)),
+        ] {
+            content.pages.insert(page.url.clone(), page);
+        }
+
+        let entries = inventory(&content);
+        let urls: Vec<&str> = entries.iter().map(|e| e.url.as_str()).collect();
+        assert_eq!(urls, vec!["/posts/new", "/posts/old", "/about"]);
+        assert_eq!(entries[0].section.as_deref(), Some("posts"));
+        assert_eq!(entries[2].section, None);
+
+        let json = serde_json::to_string(&entries[0]).unwrap();
+        assert_eq!(
+            json,
+            r#"{"url":"/posts/new","title":"New","date":"2026-01-02T00:00:00Z","lang":"en","section":"posts","tags":["rust"]}"#
+        );
+        assert_eq!(
+            serde_json::from_str::<InventoryEntry>(&json).unwrap(),
+            entries[0]
+        );
+    }
+}
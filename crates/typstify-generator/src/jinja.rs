@@ -0,0 +1,348 @@
+//! Jinja templates (`minijinja` feature).
+//!
+//! A `*.jinja` file in the site's or the theme's templates directory is a
+//! full [Jinja](https://docs.rs/minijinja) template with loops, conditionals,
+//! `{% extends %}` and `{% include %}`, and replaces the simple template of
+//! the same name: `templates/post.jinja` renders posts in place of
+//! `post.html`. Besides the variables of the simple template, it sees the
+//! whole site:
+//!
+//! - `config`: the site configuration
+//! - `pages`: every page, newest first
+//! - `sections`: section name → its pages, newest first
+//! - `taxonomies`: `tags` and `categories`, term → its pages, newest first
+//! - `page`: the page being rendered, in page templates
+//!
+//! Values are HTML-escaped unless marked `| safe`, as in simple templates.
+
+use std::{
+    cmp::Reverse,
+    collections::{BTreeMap, HashMap},
+    io,
+};
+
+use minijinja::{AutoEscape, Environment, Error, ErrorKind, Value, escape_formatter, value::Serde};
+use typstify_core::{Config, Page, escape_html};
+
+use crate::{
+    collector::SiteContent,
+    manifest::content_hash,
+    template::{Result, TemplateContext, TemplateError},
+};
+
+/// File extension of Jinja templates.
+pub const JINJA_EXTENSION: &str = "jinja";
+
+/// Jinja templates and the site data they are rendered with.
+#[derive(Debug, Clone)]
+pub struct JinjaTemplates {
+    /// Template environment, with templates by file name.
+    env: Environment<'static>,
+
+    /// File name -> source, for the digest.
+    sources: BTreeMap<String, String>,
+
+    /// Site-wide variables (`config`, `pages`, `sections`, `taxonomies`).
+    site: BTreeMap<&'static str, Value>,
+
+    /// Page URL -> page, for `page`.
+    pages: HashMap<String, Value>,
+
+    /// Content hash of the site data.
+    site_digest: String,
+}
+
+impl Default for JinjaTemplates {
+    fn default() -> Self {
+        let mut env = Environment::new();
+        env.set_auto_escape_callback(|_| AutoEscape::Html);
+        // Escape strings like simple templates do (minijinja also escapes `/`)
+        env.set_formatter(|out, state, value| match value.as_str() {
+            Some(s) if !value.is_safe() && matches!(state.auto_escape(), AutoEscape::Html) => out
+                .write_str(&escape_html(s))
+                .map_err(|e| Error::new(ErrorKind::WriteFailure, e.to_string())),
+            _ => escape_formatter(out, state, value),
+        });
+        Self {
+            env,
+            sources: BTreeMap::new(),
+            site: BTreeMap::new(),
+            pages: HashMap::new(),
+            site_digest: String::new(),
+        }
+    }
+}
+
+impl JinjaTemplates {
+    /// Add the template in the file `file_name` (`post.jinja`), replacing one
+    /// of the same name.
+    pub fn add(&mut self, file_name: &str, source: String) -> io::Result<()> {
+        self.env
+            .add_template_owned(file_name.to_string(), source.clone())
+            .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e.to_string()))?;
+        self.sources.insert(file_name.to_string(), source);
+        Ok(())
+    }
+
+    /// Whether no templates were added.
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.sources.is_empty()
+    }
+
+    /// Whether there is a template for the template name `name` (`post`).
+    #[must_use]
+    pub fn contains(&self, name: &str) -> bool {
+        self.sources.contains_key(&file_name(name))
+    }
+
+    /// Make the pages, sections and taxonomies of `content` and `config`
+    /// available to templates. Skipped when there are no templates.
+    pub fn set_site(&mut self, config: &Config, content: &SiteContent) {
+        if self.is_empty() {
+            return;
+        }
+
+        let mut pages: Vec<&Page> = content.pages.values().collect();
+        pages.sort_by(|a, b| (Reverse(a.date), &a.url).cmp(&(Reverse(b.date), &b.url)));
+        self.pages = pages
+            .iter()
+            .map(|page| (page.url.clone(), Value::from(Serde(page))))
+            .collect();
+
+        let list = |urls: &[String]| {
+            let mut listed: Vec<&Page> = urls
+                .iter()
+                .filter_map(|url| content.pages.get(url))
+                .collect();
+            listed.sort_by(|a, b| (Reverse(a.date), &a.url).cmp(&(Reverse(b.date), &b.url)));
+            Value::from(
+                listed
+                    .iter()
+                    .map(|page| self.pages[&page.url].clone())
+                    .collect::<Vec<_>>(),
+            )
+        };
+        let terms = |index: &HashMap<String, Vec<String>>| {
+            Value::from(
+                index
+                    .iter()
+                    .map(|(term, urls)| (term.clone(), list(urls)))
+                    .collect::<BTreeMap<_, _>>(),
+            )
+        };
+
+        let sections = Value::from(
+            content
+                .sections
+                .iter()
+                .map(|(name, urls)| (name.clone(), list(urls)))
+                .collect::<BTreeMap<_, _>>(),
+        );
+        let taxonomies = Value::from(BTreeMap::from([
+            ("tags", terms(&content.taxonomies.tags)),
+            ("categories", terms(&content.taxonomies.categories)),
+        ]));
+        let all = Value::from(
+            pages
+                .iter()
+                .map(|page| self.pages[&page.url].clone())
+                .collect::<Vec<_>>(),
+        );
+
+        self.site_digest = content_hash(
+            serde_json::to_string(&(config, &pages, &content.sections))
+                .unwrap_or_default()
+                .as_bytes(),
+        );
+        self.site = BTreeMap::from([
+            ("config", Value::from(Serde(config))),
+            ("pages", all),
+            ("sections", sections),
+            ("taxonomies", taxonomies),
+        ]);
+    }
+
+    /// Content hash of the templates and the site data, to detect changes
+    /// between builds.
+    #[must_use]
+    pub fn digest(&self) -> String {
+        let mut bytes = Vec::new();
+        for (name, source) in &self.sources {
+            bytes.extend(name.as_bytes());
+            bytes.push(0);
+            bytes.extend(source.as_bytes());
+            bytes.push(0);
+        }
+        bytes.extend(self.site_digest.as_bytes());
+        content_hash(&bytes)
+    }
+
+    /// Render the template for the template name `name` with the variables
+    /// of `context` and the site data.
+    pub fn render(&self, name: &str, context: &TemplateContext) -> Result<String> {
+        let file_name = file_name(name);
+        let template = self
+            .env
+            .get_template(&file_name)
+            .map_err(|_| TemplateError::NotFound(name.to_string()))?;
+
+        let mut variables: BTreeMap<&str, Value> = self
+            .site
+            .iter()
+            .map(|(name, value)| (*name, value.clone()))
+            .collect();
+        for (key, value) in context.iter() {
+            variables.insert(key, Value::from(Serde(value)));
+        }
+        if let Some(page) = context.get("url").and_then(|url| self.pages.get(url)) {
+            variables.insert("page", page.clone());
+        }
+
+        template
+            .render(Value::from(variables))
+            .map_err(|e| TemplateError::Render(e.to_string()))
+    }
+}
+
+/// File name of the Jinja template for the template name `name`.
+fn file_name(name: &str) -> String {
+    format!("{name}.{JINJA_EXTENSION}")
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::Path;
+
+    use chrono::{TimeZone, Utc};
+    use typstify_core::{
+        content::{ContentPath, ParsedContent},
+        frontmatter::Frontmatter,
+    };
+
+    use super::*;
+
+    fn config() -> Config {
+        toml::from_str("[site]\ntitle = \"Test <Site>\"\nhost = \"https://example.com\"\n").unwrap()
+    }
+
+    fn page(path: &str, title: &str, day: u32, tags: &[&str]) -> Page {
+        let path = ContentPath::from_path(Path::new(path), "en").unwrap();
+        Page::from_parsed(
+            ParsedContent {
+                frontmatter: Frontmatter {
+                    title: title.to_string(),
+                    date: Some(Utc.with_ymd_and_hms(2026, 1, day, 0, 0, 0).unwrap()),
+                    tags: tags.iter().map(ToString::to_string).collect(),
+                    ..Frontmatter::default()
+                },
+                html: format!("<p>{title}</p>"),
+                raw: title.to_string(),
+                toc: vec![],
+                dependencies: vec![],
+            },
+            &path,
+        )
+    }
+
+    fn content() -> SiteContent {
+        let mut content = SiteContent::default();
+        for page in [
+            page("posts/first.md", "First", 1, &["rust"]),
+            page("posts/second.md", "Second & more", 2, &["rust", "typst"]),
+        ] {
+            content
+                .sections
+                .entry("posts".to_string())
+                .or_default()
+                .push(page.url.clone());
+            for tag in &page.tags {
+                content
+                    .taxonomies
+                    .tags
+                    .entry(tag.clone())
+                    .or_default()
+                    .push(page.url.clone());
+            }
+            content.pages.insert(page.url.clone(), page);
+        }
+        content
+    }
+
+    #[test]
+    fn test_render_site_data() {
+        let mut templates = JinjaTemplates::default();
+        templates
+            .add(
+                "post.jinja",
+                "{{ config.site.title }}|{% for p in sections.posts %}{{ p.title }};{% endfor %}|\
+                 {{ taxonomies.tags.rust | length }}|{{ page.title }}|{{ content | safe }}|\
+                 {% if featured %}{{ related | map(attribute=\"title\") | join(\",\") }}{% endif %}"
+                    .to_string(),
+            )
+            .unwrap();
+        assert!(templates.contains("post"));
+        assert!(!templates.contains("page"));
+
+        let content = content();
+        templates.set_site(&config(), &content);
+        let context = TemplateContext::new()
+            .with_var("url", "/posts/first")
+            .with_var("content", "<p>First</p>")
+            .with_var("featured", true)
+            .with_var("related", vec![&content.pages["/posts/second"]]);
+
+        assert_eq!(
+            templates.render("post", &context).unwrap(),
+            "Test &lt;Site&gt;|Second &amp; more;First;|2|First|<p>First</p>|Second &amp; more"
+        );
+        assert!(matches!(
+            templates.render("page", &context),
+            Err(TemplateError::NotFound(_))
+        ));
+    }
+
+    #[test]
+    fn test_extends_and_errors() {
+        let mut templates = JinjaTemplates::default();
+        templates
+            .add(
+                "base.jinja",
+                "<main>{% block body %}{% endblock %}</main>".to_string(),
+            )
+            .unwrap();
+        templates
+            .add(
+                "list.jinja",
+                "{% extends \"base.jinja\" %}{% block body %}{% for p in pages %}{% if p.tags %}<a href=\"{{ p.url }}\">{{ p.title }}</a>{% endif %}{% endfor %}{% endblock %}"
+                    .to_string(),
+            )
+            .unwrap();
+        let content = content();
+        templates.set_site(&config(), &content);
+
+        assert_eq!(
+            templates.render("list", &TemplateContext::new()).unwrap(),
+            r#"<main><a href="/posts/second">Second &amp; more</a><a href="/posts/first">First</a></main>"#
+        );
+
+        let before = templates.digest();
+        let mut changed = content;
+        changed.pages.get_mut("/posts/first").unwrap().title = "Renamed".to_string();
+        templates.set_site(&config(), &changed);
+        assert_ne!(templates.digest(), before);
+
+        assert!(
+            templates
+                .add("broken.jinja", "{% if %}".to_string())
+                .is_err()
+        );
+        templates
+            .add("missing.jinja", "{{ nothing.here }}".to_string())
+            .unwrap();
+        assert!(matches!(
+            templates.render("missing", &TemplateContext::new()),
+            Err(TemplateError::Render(_))
+        ));
+    }
+}
@@ -4,30 +4,91 @@
 //!
 //! # Modules
 //!
+//! - [`audit`] - Static checks over rendered HTML
+//! - [`blogroll`] - Blogroll page and OPML export from `data/blogroll.toml`
+//! - [`cache`] - Parsed and rendered pages kept between builds
 //! - [`template`] - HTML template system with variable interpolation
 //! - [`html`] - HTML generation from parsed content
+//! - [`identity`] - Author `rel="me"` links and webfinger document
+//! - [`changelog`] - Content changelog page from git history
+//! - [`events`] - Upcoming-events page and iCalendar feed
 //! - [`collector`] - Content collection and organization
+//! - [`diff`] - Pages that differ between two builds
+//! - [`data`] - Build-time JSON data from the `data` shortcode
+//! - `jinja` - Jinja templates with site data (`minijinja` feature)
+//! - [`images`] - Responsive image variants and `<picture>` markup
+//! - [`inventory`] - Page inventory printed by `typstify list`
+//! - [`gallery`] - Photo galleries from the `gallery` shortcode
+//! - [`links`] - Internal link graph and orphan detection
+//! - [`manifest`] - Build manifest with per-file content hashes
+//! - [`math`] - KaTeX assets for math, from a CDN or self-hosted
+//! - [`mermaid`] - Mermaid diagrams, drawn in the browser or pre-rendered
+//! - [`ping`] - WebSub and sitemap pings after a deploy
+//! - [`remote`] - Download and cache remote images
 //! - [`rss`] - RSS feed generation
+//! - [`sections`] - Nested content section hierarchy
+//! - [`shortcodes`] - Site-defined shortcodes from `templates/shortcodes`
 //! - [`sitemap`] - XML sitemap generation
+//! - [`sources`] - Remote content sources fetched into the content tree
+//! - [`structured_data`] - JSON-LD `BlogPosting`, `BreadcrumbList` and `WebSite` data
+//! - [`urlmap`] - Source path to URL map for proxies and migrations
+//! - [`themes`] - Installable themes with templates and static assets
+//! - [`webmentions`] - Received webmentions rendered under posts
 //! - [`assets`] - Static asset processing with optional fingerprinting
 //! - [`build`] - Build orchestration
 
 pub mod assets;
+pub mod audit;
+pub mod blogroll;
 pub mod build;
+pub mod cache;
+pub mod changelog;
 pub mod collector;
+pub mod data;
+pub mod diff;
+pub mod events;
+pub mod gallery;
 pub mod html;
+pub mod identity;
+pub mod images;
+pub mod inventory;
+#[cfg(feature = "minijinja")]
+pub mod jinja;
+pub mod links;
+pub mod manifest;
+pub mod math;
+pub mod mermaid;
+pub mod ping;
+pub mod remote;
 pub mod robots;
 pub mod rss;
+pub mod sections;
+pub mod shortcodes;
 pub mod sitemap;
+pub mod sources;
 pub mod static_assets;
+pub mod structured_data;
 pub mod template;
+pub mod themes;
+pub mod urlmap;
+pub mod webmentions;
 
 pub use assets::{AssetManifest, AssetProcessor};
-pub use build::{BuildStats, Builder};
-pub use collector::{ContentCollector, SiteContent, TaxonomyIndex};
+pub use build::{BuildStats, BuildWarning, Builder, PageTiming, SEARCH_KEY_ENV, WarningKind};
+pub use cache::BuildCache;
+pub use collector::{ChangeSet, ContentCollector, SiteContent, TaxonomyIndex};
 pub use html::HtmlGenerator;
+pub use links::LinkGraph;
+pub use manifest::BuildManifest;
 pub use robots::RobotsGenerator;
 pub use rss::RssGenerator;
+pub use shortcodes::ShortcodeTemplates;
 pub use sitemap::SitemapGenerator;
 pub use static_assets::generate_static_assets;
-pub use template::{Template, TemplateContext, TemplateRegistry};
+pub use template::{
+    EscapeMode, LintKind, Template, TemplateContext, TemplateIssue, TemplateRegistry,
+    TemplateValue, TemplateVariables,
+};
+pub use themes::Theme;
+pub use urlmap::UrlMap;
+pub use webmentions::Webmentions;
@@ -0,0 +1,428 @@
+//! Internal link graph between content pages.
+//!
+//! Extracts `<a href>` links from rendered page content, resolves them to
+//! page URLs, and reports orphan and most-linked pages.
+
+use std::collections::{BTreeMap, BTreeSet};
+
+use serde::Serialize;
+use typstify_core::{Config, Page};
+
+use crate::{
+    audit::{HtmlToken, scan_html},
+    collector::SiteContent,
+};
+
+/// File name of the serialized link graph in the output directory.
+pub const LINK_GRAPH_FILE: &str = "link-graph.json";
+
+/// Internal links between pages, keyed by page URL.
+#[derive(Debug, Clone, Default)]
+pub struct LinkGraph {
+    /// Page URL -> title.
+    nodes: BTreeMap<String, String>,
+
+    /// Page URL -> URLs of pages it links to.
+    links: BTreeMap<String, BTreeSet<String>>,
+}
+
+/// Graph node for JSON output.
+#[derive(Debug, Serialize)]
+struct JsonNode<'a> {
+    id: &'a str,
+    title: &'a str,
+    inbound: usize,
+    outbound: usize,
+}
+
+/// Graph edge for JSON output.
+#[derive(Debug, Serialize)]
+struct JsonEdge<'a> {
+    source: &'a str,
+    target: &'a str,
+}
+
+/// JSON representation (`nodes` + `edges`, as used by most graph viewers).
+#[derive(Debug, Serialize)]
+struct JsonGraph<'a> {
+    nodes: Vec<JsonNode<'a>>,
+    edges: Vec<JsonEdge<'a>>,
+}
+
+impl LinkGraph {
+    /// Build the link graph from the collected pages for which `include`
+    /// returns true.
+    ///
+    /// Only links that resolve to an included page are kept; self-links are
+    /// ignored.
+    #[must_use]
+    pub fn build(config: &Config, content: &SiteContent, include: impl Fn(&Page) -> bool) -> Self {
+        let mut graph = Self::default();
+        let included = |url: &str| content.pages.get(url).is_some_and(&include);
+
+        for (url, page) in content.pages.iter().filter(|(_, page)| include(page)) {
+            graph.nodes.insert(url.clone(), page.title.clone());
+
+            let targets: BTreeSet<String> = scan_html(&page.content)
+                .iter()
+                .filter_map(|token| match token {
+                    HtmlToken::Start { name, .. } if name == "a" => token.attr("href"),
+                    _ => None,
+                })
+                .filter_map(|href| resolve_link(config, url, href))
+                .filter(|target| target != url && included(target))
+                .collect();
+
+            graph.links.insert(url.clone(), targets);
+        }
+
+        graph
+    }
+
+    /// Number of pages in the graph.
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.nodes.len()
+    }
+
+    /// Whether the graph has no pages.
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.nodes.is_empty()
+    }
+
+    /// Pages linked from `url`.
+    pub fn outbound(&self, url: &str) -> impl Iterator<Item = &str> {
+        self.links
+            .get(url)
+            .into_iter()
+            .flatten()
+            .map(String::as_str)
+    }
+
+    /// Title of a page in the graph.
+    #[must_use]
+    pub fn title(&self, url: &str) -> Option<&str> {
+        self.nodes.get(url).map(String::as_str)
+    }
+
+    /// Pages linking to `url`.
+    pub fn inbound<'a>(&'a self, url: &'a str) -> impl Iterator<Item = &'a str> {
+        self.links
+            .iter()
+            .filter(move |(_, targets)| targets.contains(url))
+            .map(|(source, _)| source.as_str())
+    }
+
+    /// Inbound link count for every page (including zero counts).
+    #[must_use]
+    pub fn inbound_counts(&self) -> BTreeMap<&str, usize> {
+        let mut counts: BTreeMap<&str, usize> =
+            self.nodes.keys().map(|url| (url.as_str(), 0)).collect();
+        for target in self.links.values().flatten() {
+            *counts.entry(target.as_str()).or_default() += 1;
+        }
+        counts
+    }
+
+    /// Pages with the most inbound links, most-linked first.
+    #[must_use]
+    pub fn most_linked(&self, limit: usize) -> Vec<(&str, usize)> {
+        let mut counts: Vec<_> = self
+            .inbound_counts()
+            .into_iter()
+            .filter(|(_, count)| *count > 0)
+            .collect();
+        counts.sort_by(|a, b| b.1.cmp(&a.1).then(a.0.cmp(b.0)));
+        counts.truncate(limit);
+        counts
+    }
+
+    /// Pages that nothing links to and that no index lists.
+    ///
+    /// Pages nested under a section (listed on its index page), language home
+    /// pages and the `about` page (linked from the default navigation) are
+    /// always reachable.
+    #[must_use]
+    pub fn orphans(&self, config: &Config) -> Vec<&str> {
+        self.inbound_counts()
+            .into_iter()
+            .filter(|(url, count)| *count == 0 && !is_listed(config, url))
+            .map(|(url, _)| url)
+            .collect()
+    }
+
+    /// Serialize as `{ "nodes": [...], "edges": [...] }`.
+    pub fn to_json(&self) -> serde_json::Result<String> {
+        let inbound = self.inbound_counts();
+        let nodes = self
+            .nodes
+            .iter()
+            .map(|(url, title)| JsonNode {
+                id: url,
+                title,
+                inbound: inbound.get(url.as_str()).copied().unwrap_or(0),
+                outbound: self.links.get(url).map_or(0, BTreeSet::len),
+            })
+            .collect();
+        let edges = self
+            .links
+            .iter()
+            .flat_map(|(source, targets)| {
+                targets
+                    .iter()
+                    .map(move |target| JsonEdge { source, target })
+            })
+            .collect();
+
+        serde_json::to_string_pretty(&JsonGraph { nodes, edges })
+    }
+}
+
+/// Whether a page is reachable without an inbound content link.
+fn is_listed(config: &Config, url: &str) -> bool {
+    let mut segments: Vec<&str> = url.split('/').filter(|s| !s.is_empty()).collect();
+    if let Some(first) = segments.first()
+        && *first != config.site.default_language
+        && config.languages.contains_key(*first)
+    {
+        segments.remove(0);
+    }
+
+    matches!(segments.as_slice(), [] | ["about"] | [_, _, ..])
+}
+
+/// Resolve an `href` found on page `from` to a page URL.
+///
+/// Returns `None` for external links, fragments and non-HTTP schemes.
+/// Relative links resolve against the page's parent path; a trailing
+/// `/`, `index.html` or source extension (`.md`, `.typ`) is dropped.
+#[must_use]
+pub fn resolve_link(config: &Config, from: &str, href: &str) -> Option<String> {
+    // Absolute URLs on this site are already relative to the base path
+    let base_url = config.base_url();
+    let (href, has_base_path) = match href.strip_prefix(base_url.as_str()) {
+        Some(rest) if rest.is_empty() || rest.starts_with(['/', '#', '?']) => (rest, false),
+        _ => (href, true),
+    };
+
+    if (href.is_empty() && has_base_path)
+        || href.starts_with('#')
+        || href.starts_with("//")
+        || href.contains("://")
+        || href.starts_with("mailto:")
+        || href.starts_with("tel:")
+        || href.starts_with("javascript:")
+    {
+        return None;
+    }
+
+    let path = href.split(['#', '?']).next().unwrap_or("");
+    let path = if path.is_empty() && !has_base_path {
+        "/"
+    } else {
+        path
+    };
+    if path.is_empty() {
+        return None;
+    }
+
+    let joined = if path.starts_with('/') {
+        let base_path = config.base_path();
+        let relative = if has_base_path && !base_path.is_empty() {
+            path.strip_prefix(base_path)
+                .filter(|rest| rest.is_empty() || rest.starts_with('/'))
+                .unwrap_or(path)
+        } else {
+            path
+        };
+        relative.to_string()
+    } else {
+        let parent = from
+            .trim_matches('/')
+            .rsplit_once('/')
+            .map_or("", |(p, _)| p);
+        format!("{parent}/{path}")
+    };
+
+    // Normalize `.` and `..` segments
+    let mut segments: Vec<&str> = Vec::new();
+    for segment in joined.split('/') {
+        match segment {
+            "" | "." => {}
+            ".." => {
+                segments.pop();
+            }
+            _ => segments.push(segment),
+        }
+    }
+    if segments.last() == Some(&"index.html") {
+        segments.pop();
+    }
+
+    let mut url = format!("/{}", segments.join("/"));
+    for ext in [".html", ".md", ".typ"] {
+        if let Some(stripped) = url.strip_suffix(ext) {
+            url = stripped.to_string();
+            break;
+        }
+    }
+    Some(url)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use typstify_core::Page;
+
+    use super::*;
+
+    fn test_config() -> Config {
+        Config {
+            theme: None,
+            site: typstify_core::config::SiteConfig {
+                title: "Test Site".to_string(),
+                host: "https://example.com".to_string(),
+                base_path: "/blog".to_string(),
+                default_language: "en".to_string(),
+                description: None,
+                author: None,
+                timezone: None,
+            },
+            languages: HashMap::new(),
+            build: typstify_core::config::BuildConfig::default(),
+            search: typstify_core::config::SearchConfig::default(),
+            rss: typstify_core::config::RssConfig::default(),
+            robots: typstify_core::config::RobotsConfig::default(),
+            taxonomies: typstify_core::config::TaxonomyConfig::default(),
+            dates: typstify_core::config::DatesConfig::default(),
+            css: typstify_core::config::CssConfig::default(),
+            images: typstify_core::config::ImagesConfig::default(),
+            markdown: typstify_core::config::MarkdownConfig::default(),
+            typography: typstify_core::config::TypographyConfig::default(),
+            sections: std::collections::HashMap::new(),
+            check: typstify_core::config::CheckConfig::default(),
+            webmentions: typstify_core::config::WebmentionsConfig::default(),
+            identity: typstify_core::config::IdentityConfig::default(),
+            content: typstify_core::config::ContentConfig::default(),
+            ping: typstify_core::config::PingConfig::default(),
+            toc: typstify_core::config::TocConfig::default(),
+            math: typstify_core::config::MathConfig::default(),
+            mermaid: typstify_core::config::MermaidConfig::default(),
+            fmt: typstify_core::config::FmtConfig::default(),
+        }
+    }
+
+    fn test_page(url: &str, content: &str) -> Page {
+        Page {
+            url: url.to_string(),
+            title: url.to_string(),
+            description: None,
+            date: None,
+            updated: None,
+            expiry_date: None,
+            draft: false,
+            lang: "en".to_string(),
+            is_default_lang: true,
+            canonical_id: url.trim_start_matches('/').to_string(),
+            tags: vec![],
+            categories: vec![],
+            content: content.to_string(),
+            summary: None,
+            reading_time: None,
+            word_count: None,
+            toc: vec![],
+            custom_js: vec![],
+            custom_css: vec![],
+            aliases: vec![],
+            template: None,
+            weight: 0,
+            pinned: false,
+            series: None,
+            content_id: None,
+            extra_meta: std::collections::BTreeMap::new(),
+            structured_data: None,
+            images: vec![],
+            event: None,
+            source_path: None,
+        }
+    }
+
+    #[test]
+    fn test_resolve_link() {
+        let config = test_config();
+        let from = "/posts/hello";
+
+        assert_eq!(
+            resolve_link(&config, from, "/blog/posts/other/").as_deref(),
+            Some("/posts/other")
+        );
+        assert_eq!(
+            resolve_link(&config, from, "https://example.com/blog/about#team").as_deref(),
+            Some("/about")
+        );
+        assert_eq!(
+            resolve_link(&config, from, "./other.md").as_deref(),
+            Some("/posts/other")
+        );
+        assert_eq!(
+            resolve_link(&config, from, "../docs/index.html").as_deref(),
+            Some("/docs")
+        );
+        assert_eq!(
+            resolve_link(&config, from, "/blog-archive").as_deref(),
+            Some("/blog-archive")
+        );
+        assert_eq!(
+            resolve_link(&config, from, "https://example.com/blog").as_deref(),
+            Some("/")
+        );
+        assert_eq!(
+            resolve_link(&config, from, "https://example.com/blogroll"),
+            None
+        );
+        assert_eq!(resolve_link(&config, from, "#section"), None);
+        assert_eq!(resolve_link(&config, from, "https://rust-lang.org"), None);
+        assert_eq!(resolve_link(&config, from, "mailto:me@example.com"), None);
+    }
+
+    #[test]
+    fn test_link_graph() {
+        let config = test_config();
+        let mut content = SiteContent::default();
+        for page in [
+            test_page("/", r#"<a href="/blog/projects">Projects</a>"#),
+            test_page(
+                "/projects",
+                r#"<a href="/blog/posts/a">A</a> <a href="posts/b">B</a> <a href="/blog/missing">x</a>"#,
+            ),
+            test_page(
+                "/posts/a",
+                r#"<a href="/blog/posts/b">B</a> <a href="/blog/posts/a">self</a>"#,
+            ),
+            test_page("/posts/b", ""),
+            test_page("/lonely", ""),
+        ] {
+            content.pages.insert(page.url.clone(), page);
+        }
+
+        let graph = LinkGraph::build(&config, &content, |_| true);
+
+        assert_eq!(graph.len(), 5);
+        assert_eq!(
+            graph.outbound("/projects").collect::<Vec<_>>(),
+            vec!["/posts/a", "/posts/b"]
+        );
+        assert_eq!(
+            graph.inbound("/posts/b").collect::<Vec<_>>(),
+            vec!["/posts/a", "/projects"]
+        );
+        assert_eq!(graph.most_linked(1), vec![("/posts/b", 2)]);
+        assert_eq!(graph.orphans(&config), vec!["/lonely"]);
+
+        let json: serde_json::Value = serde_json::from_str(&graph.to_json().unwrap()).unwrap();
+        assert_eq!(json["nodes"].as_array().unwrap().len(), 5);
+        assert_eq!(json["edges"].as_array().unwrap().len(), 4);
+    }
+}
@@ -0,0 +1,247 @@
+//! Build manifest for change detection.
+//!
+//! Records a content hash for every source file so later builds can tell
+//! which files were added, changed, or removed since the previous build, and
+//! the URLs each stable content ID has been published at so renamed pages
+//! keep redirecting from their old URLs.
+
+use std::{collections::BTreeMap, fs, path::Path};
+
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+
+/// Current manifest format version.
+pub const MANIFEST_VERSION: u32 = 1;
+
+/// Manifest file name, written into `build.cache_dir`.
+pub const MANIFEST_FILE: &str = "build-manifest.json";
+
+/// Manifest errors.
+#[derive(Debug, Error)]
+pub enum ManifestError {
+    /// IO error.
+    #[error("IO error: {0}")]
+    Io(#[from] std::io::Error),
+
+    /// Serialization error.
+    #[error("manifest serialization error: {0}")]
+    Serialization(#[from] serde_json::Error),
+}
+
+/// Result type for manifest operations.
+pub type Result<T> = std::result::Result<T, ManifestError>;
+
+/// A single source file entry in the manifest.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ManifestEntry {
+    /// Content hash of the source file.
+    pub hash: String,
+
+    /// URL of the page produced from this file.
+    pub url: String,
+}
+
+/// Per-file content hashes from a build.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BuildManifest {
+    /// Manifest format version.
+    pub version: u32,
+
+    /// Source path (relative to the content directory) -> entry.
+    pub files: BTreeMap<String, ManifestEntry>,
+
+    /// Content ID key (`{lang}:{content_id}`) -> URLs it has been published
+    /// at, current first.
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub content_ids: BTreeMap<String, Vec<String>>,
+}
+
+impl Default for BuildManifest {
+    fn default() -> Self {
+        Self {
+            version: MANIFEST_VERSION,
+            files: BTreeMap::new(),
+            content_ids: BTreeMap::new(),
+        }
+    }
+}
+
+impl BuildManifest {
+    /// Create a new empty manifest.
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Record a source file.
+    pub fn insert(
+        &mut self,
+        path: impl Into<String>,
+        hash: impl Into<String>,
+        url: impl Into<String>,
+    ) {
+        self.files.insert(
+            path.into(),
+            ManifestEntry {
+                hash: hash.into(),
+                url: url.into(),
+            },
+        );
+    }
+
+    /// Get the entry for a source file.
+    #[must_use]
+    pub fn get(&self, path: &str) -> Option<&ManifestEntry> {
+        self.files.get(path)
+    }
+
+    /// Record that the content with `key` is now published at `url`.
+    ///
+    /// Returns the URLs it was published at in `previous` builds, which
+    /// should redirect to `url`.
+    pub fn track_content_id(&mut self, key: &str, url: &str, previous: &Self) -> Vec<String> {
+        let old: Vec<String> = previous
+            .content_ids
+            .get(key)
+            .into_iter()
+            .flatten()
+            .filter(|u| *u != url)
+            .cloned()
+            .collect();
+
+        let mut urls = vec![url.to_string()];
+        urls.extend(old.iter().cloned());
+        self.content_ids.insert(key.to_string(), urls);
+        old
+    }
+
+    /// Number of files in the manifest.
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.files.len()
+    }
+
+    /// Whether the manifest is empty.
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.files.is_empty()
+    }
+
+    /// Serialize manifest to JSON.
+    pub fn to_json(&self) -> Result<String> {
+        Ok(serde_json::to_string_pretty(self)?)
+    }
+
+    /// Deserialize manifest from JSON.
+    pub fn from_json(json: &str) -> Result<Self> {
+        Ok(serde_json::from_str(json)?)
+    }
+
+    /// Load a manifest from disk.
+    ///
+    /// Returns an empty manifest if the file is missing, unreadable, or was
+    /// written by a different manifest version, so callers fall back to a
+    /// full rebuild.
+    #[must_use]
+    pub fn load(path: &Path) -> Self {
+        fs::read_to_string(path)
+            .ok()
+            .and_then(|json| Self::from_json(&json).ok())
+            .filter(|m| m.version == MANIFEST_VERSION)
+            .unwrap_or_default()
+    }
+
+    /// Write the manifest to disk.
+    pub fn write_to(&self, path: &Path) -> Result<()> {
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        fs::write(path, self.to_json()?)?;
+        Ok(())
+    }
+}
+
+/// Compute a content hash (64-bit FNV-1a, hex encoded).
+#[must_use]
+pub fn content_hash(bytes: &[u8]) -> String {
+    let mut hash: u64 = 0xcbf29ce484222325;
+    for byte in bytes {
+        hash ^= u64::from(*byte);
+        hash = hash.wrapping_mul(0x100000001b3);
+    }
+    format!("{hash:016x}")
+}
+
+#[cfg(test)]
+mod tests {
+    use tempfile::TempDir;
+
+    use super::*;
+
+    #[test]
+    fn test_content_hash() {
+        assert_eq!(content_hash(b"hello"), content_hash(b"hello"));
+        assert_ne!(content_hash(b"hello"), content_hash(b"hello!"));
+        assert_eq!(content_hash(b"").len(), 16);
+    }
+
+    #[test]
+    fn test_manifest_roundtrip() {
+        let mut manifest = BuildManifest::new();
+        manifest.insert("posts/hello.md", "abc", "/posts/hello");
+
+        let json = manifest.to_json().unwrap();
+        let loaded = BuildManifest::from_json(&json).unwrap();
+
+        assert_eq!(loaded, manifest);
+        assert_eq!(loaded.get("posts/hello.md").unwrap().url, "/posts/hello");
+    }
+
+    #[test]
+    fn test_track_content_id() {
+        let mut first = BuildManifest::new();
+        assert!(
+            first
+                .track_content_id("en:abc", "/a", &BuildManifest::new())
+                .is_empty()
+        );
+
+        let mut second = BuildManifest::new();
+        assert_eq!(second.track_content_id("en:abc", "/b", &first), vec!["/a"]);
+
+        // Earlier URLs are remembered across renames
+        let mut third = BuildManifest::new();
+        assert_eq!(
+            third.track_content_id("en:abc", "/c", &second),
+            vec!["/b", "/a"]
+        );
+        assert_eq!(third.content_ids["en:abc"], vec!["/c", "/b", "/a"]);
+
+        // Renaming back drops the URL from the redirects
+        let mut fourth = BuildManifest::new();
+        assert_eq!(
+            fourth.track_content_id("en:abc", "/a", &third),
+            vec!["/c", "/b"]
+        );
+    }
+
+    #[test]
+    fn test_manifest_load_missing_or_stale() {
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join(MANIFEST_FILE);
+
+        assert!(BuildManifest::load(&path).is_empty());
+
+        fs::write(
+            &path,
+            r#"{"version": 0, "files": {"a.md": {"hash": "1", "url": "/a"}}}"#,
+        )
+        .unwrap();
+        assert!(BuildManifest::load(&path).is_empty());
+
+        let mut manifest = BuildManifest::new();
+        manifest.insert("a.md", "1", "/a");
+        manifest.write_to(&path).unwrap();
+        assert_eq!(BuildManifest::load(&path).len(), 1);
+    }
+}
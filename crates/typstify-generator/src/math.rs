@@ -0,0 +1,177 @@
+//! KaTeX assets for math rendering.
+//!
+//! With `[math] enabled`, pages with math (Markdown `$...$` and `$$...$$`)
+//! load KaTeX, which renders the `\(...\)` and `\[...\]` math of `.math`
+//! elements in the browser. By default KaTeX
+//! comes from a CDN; with `katex_dir` set to a local KaTeX distribution its
+//! stylesheet, scripts and fonts are published under `assets/katex/` and
+//! referenced from there (below the site's base path), so math renders
+//! offline and where the CDN is blocked.
+
+use std::{fs, path::Path};
+
+use thiserror::Error;
+use typstify_core::{Config, escape_html};
+
+/// KaTeX version loaded from the CDN.
+pub const KATEX_VERSION: &str = "0.16.22";
+
+/// Directory (relative to the output directory) of self-hosted KaTeX.
+pub const KATEX_ASSETS_DIR: &str = "assets/katex";
+
+/// Files of a KaTeX distribution that pages load; the fonts the stylesheet
+/// references are in `fonts/`.
+const KATEX_FILES: &[&str] = &[
+    "katex.min.css",
+    "katex.min.js",
+    "contrib/auto-render.min.js",
+];
+
+/// Math asset errors.
+#[derive(Debug, Error)]
+pub enum MathError {
+    /// IO error.
+    #[error("IO error: {0}")]
+    Io(#[from] std::io::Error),
+
+    /// `katex_dir` is not a KaTeX distribution.
+    #[error("{0} is not a KaTeX distribution (no {1})")]
+    NotKatex(String, &'static str),
+}
+
+/// Result type for math asset operations.
+pub type Result<T> = std::result::Result<T, MathError>;
+
+/// Publish the KaTeX distribution `katex_dir` in `output_dir`, returning
+/// the number of files copied.
+pub fn copy_katex(katex_dir: &Path, output_dir: &Path) -> Result<usize> {
+    let target = output_dir.join(KATEX_ASSETS_DIR);
+    let mut copied = 0;
+
+    for file in KATEX_FILES {
+        let source = katex_dir.join(file);
+        if !source.is_file() {
+            return Err(MathError::NotKatex(katex_dir.display().to_string(), file));
+        }
+        let dest = target.join(file);
+        if let Some(parent) = dest.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        fs::copy(&source, &dest)?;
+        copied += 1;
+    }
+
+    let fonts = katex_dir.join("fonts");
+    if fonts.is_dir() {
+        fs::create_dir_all(target.join("fonts"))?;
+        for entry in fs::read_dir(&fonts)? {
+            let entry = entry?;
+            if entry.file_type()?.is_file() {
+                fs::copy(entry.path(), target.join("fonts").join(entry.file_name()))?;
+                copied += 1;
+            }
+        }
+    }
+
+    Ok(copied)
+}
+
+/// Whether rendered page HTML contains math for KaTeX.
+#[must_use]
+pub fn has_math(html: &str) -> bool {
+    html.contains("<span class=\"math inline\">") || html.contains("<span class=\"math display\">")
+}
+
+/// Base URL KaTeX is loaded from: the self-hosted copy with `katex_dir`,
+/// the CDN otherwise.
+#[must_use]
+pub fn katex_base_url(config: &Config) -> String {
+    if config.math.katex_dir.is_some() {
+        format!("{}/{KATEX_ASSETS_DIR}", config.base_path())
+    } else {
+        format!("https://cdn.jsdelivr.net/npm/katex@{KATEX_VERSION}/dist")
+    }
+}
+
+/// `<head>` tags loading KaTeX, or `None` unless math is enabled.
+#[must_use]
+pub fn head_html(config: &Config) -> Option<String> {
+    if !config.math.enabled {
+        return None;
+    }
+    let base = escape_html(&katex_base_url(config));
+    Some(format!(
+        r#"<link rel="stylesheet" href="{base}/katex.min.css">
+    <script defer src="{base}/katex.min.js"></script>
+    <script defer src="{base}/contrib/auto-render.min.js"></script>"#
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use tempfile::TempDir;
+
+    use super::*;
+
+    fn config(katex_dir: Option<&str>) -> Config {
+        let mut config: Config = toml::from_str(
+            r#"
+[site]
+title = "Test"
+host = "https://example.com"
+base_path = "/blog"
+
+[math]
+enabled = true
+"#,
+        )
+        .unwrap();
+        config.math.katex_dir = katex_dir.map(ToString::to_string);
+        config
+    }
+
+    #[test]
+    fn test_head_html() {
+        let cdn = head_html(&config(None)).unwrap();
+        assert!(cdn.contains(&format!(
+            r#"href="https://cdn.jsdelivr.net/npm/katex@{KATEX_VERSION}/dist/katex.min.css""#
+        )));
+
+        let local = head_html(&config(Some("katex"))).unwrap();
+        assert!(local.contains(r#"href="/blog/assets/katex/katex.min.css""#));
+        assert!(local.contains(r#"src="/blog/assets/katex/contrib/auto-render.min.js""#));
+
+        let mut disabled = config(None);
+        disabled.math.enabled = false;
+        assert_eq!(head_html(&disabled), None);
+    }
+
+    #[test]
+    fn test_has_math() {
+        assert!(has_math(r#"<p><span class="math inline">\(x\)</span></p>"#));
+        assert!(has_math(r#"<span class="math display">\[x\]</span>"#));
+        assert!(!has_math("<p>$5 or $10</p>"));
+    }
+
+    #[test]
+    fn test_copy_katex() {
+        let katex = TempDir::new().unwrap();
+        let output = TempDir::new().unwrap();
+        assert!(matches!(
+            copy_katex(katex.path(), output.path()),
+            Err(MathError::NotKatex(_, "katex.min.css"))
+        ));
+
+        fs::create_dir_all(katex.path().join("contrib")).unwrap();
+        fs::create_dir_all(katex.path().join("fonts")).unwrap();
+        for file in KATEX_FILES {
+            fs::write(katex.path().join(file), "x").unwrap();
+        }
+        fs::write(katex.path().join("fonts/KaTeX_Main-Regular.woff2"), "font").unwrap();
+
+        assert_eq!(copy_katex(katex.path(), output.path()).unwrap(), 4);
+        let target = output.path().join(KATEX_ASSETS_DIR);
+        assert!(target.join("contrib/auto-render.min.js").is_file());
+        assert!(target.join("fonts/KaTeX_Main-Regular.woff2").is_file());
+    }
+}
@@ -0,0 +1,184 @@
+//! Mermaid diagrams.
+//!
+//! The parsers render ```` ```mermaid ```` fences as `<pre class="mermaid">`.
+//! Pages with such diagrams load the Mermaid script, which draws them in the
+//! browser. With `[mermaid] prerender`, the Mermaid CLI (`mmdc`) renders each
+//! diagram to an inline SVG at build time instead, so pages need no script;
+//! the SVGs are kept in the build cache directory, keyed by their source.
+
+use std::{fs, path::PathBuf, process::Command};
+
+use thiserror::Error;
+use typstify_core::unescape_html;
+
+use crate::manifest::content_hash;
+
+/// Major version of Mermaid loaded from the CDN.
+pub const MERMAID_VERSION: &str = "11";
+
+/// Subdirectory of the cache directory holding pre-rendered diagrams.
+pub const MERMAID_DIR: &str = "mermaid";
+
+/// Opening tag of a diagram.
+const OPEN: &str = "<pre class=\"mermaid\">";
+
+/// Closing tag of a diagram.
+const CLOSE: &str = "</pre>";
+
+/// Mermaid errors.
+#[derive(Debug, Error)]
+pub enum MermaidError {
+    /// IO error.
+    #[error("IO error: {0}")]
+    Io(#[from] std::io::Error),
+
+    /// The Mermaid CLI could not be run.
+    #[error("failed to run {0}: {1}")]
+    Command(String, std::io::Error),
+
+    /// The Mermaid CLI rejected a diagram.
+    #[error("failed to render diagram: {0}")]
+    Render(String),
+}
+
+/// Result type for Mermaid operations.
+pub type Result<T> = std::result::Result<T, MermaidError>;
+
+/// Whether rendered page HTML contains diagrams for the Mermaid script.
+#[must_use]
+pub fn has_diagrams(html: &str) -> bool {
+    html.contains(OPEN)
+}
+
+/// Script loading Mermaid, which renders the page's diagrams in the site's
+/// light or dark theme.
+#[must_use]
+pub fn head_html() -> String {
+    format!(
+        r#"<script type="module">
+      import mermaid from 'https://cdn.jsdelivr.net/npm/mermaid@{MERMAID_VERSION}/dist/mermaid.esm.min.mjs';
+      const theme = document.documentElement.dataset.theme
+        || (matchMedia('(prefers-color-scheme: dark)').matches ? 'dark' : 'light');
+      mermaid.initialize({{ startOnLoad: true, theme: theme === 'dark' ? 'dark' : 'default' }});
+    </script>"#
+    )
+}
+
+/// Renders diagrams to SVG with the Mermaid CLI.
+#[derive(Debug, Clone)]
+pub struct Prerenderer {
+    command: String,
+    cache_dir: PathBuf,
+}
+
+impl Prerenderer {
+    /// Create a prerenderer running `command`, caching SVGs in `cache_dir`.
+    #[must_use]
+    pub fn new(command: impl Into<String>, cache_dir: impl Into<PathBuf>) -> Self {
+        Self {
+            command: command.into(),
+            cache_dir: cache_dir.into(),
+        }
+    }
+
+    /// Replace the diagrams of `html` with their SVGs.
+    pub fn prerender(&self, html: &str) -> Result<String> {
+        let mut output = String::with_capacity(html.len());
+        let mut rest = html;
+        while let Some(start) = rest.find(OPEN) {
+            let Some(len) = rest[start + OPEN.len()..].find(CLOSE) else {
+                break;
+            };
+            output.push_str(&rest[..start]);
+            let source = unescape_html(&rest[start + OPEN.len()..start + OPEN.len() + len]);
+            output.push_str("<div class=\"mermaid-diagram\">");
+            output.push_str(self.render(&source)?.trim());
+            output.push_str("</div>");
+            rest = &rest[start + OPEN.len() + len + CLOSE.len()..];
+        }
+        output.push_str(rest);
+        Ok(output)
+    }
+
+    /// SVG of the diagram `source`, from the cache or the Mermaid CLI.
+    pub fn render(&self, source: &str) -> Result<String> {
+        let hash = content_hash(source.as_bytes());
+        let svg_path = self.cache_dir.join(format!("{hash}.svg"));
+        if let Ok(svg) = fs::read_to_string(&svg_path) {
+            return Ok(svg);
+        }
+
+        fs::create_dir_all(&self.cache_dir)?;
+        let input = self.cache_dir.join(format!("{hash}.mmd"));
+        fs::write(&input, source)?;
+        let output = Command::new(&self.command)
+            .arg("--input")
+            .arg(&input)
+            .arg("--output")
+            .arg(&svg_path)
+            // Unique ids, so that several diagrams on a page don't clash
+            .args(["--svgId", &format!("mermaid-{hash}")])
+            .args(["--backgroundColor", "transparent", "--quiet"])
+            .output();
+        let _ = fs::remove_file(&input);
+        let output = output.map_err(|e| MermaidError::Command(self.command.clone(), e))?;
+        if !output.status.success() {
+            let _ = fs::remove_file(&svg_path);
+            return Err(MermaidError::Render(
+                String::from_utf8_lossy(&output.stderr).trim().to_string(),
+            ));
+        }
+        Ok(fs::read_to_string(&svg_path)?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use tempfile::TempDir;
+
+    use super::*;
+
+    #[test]
+    fn test_has_diagrams() {
+        assert!(has_diagrams("<pre class=\"mermaid\">graph LR</pre>"));
+        assert!(!has_diagrams("<pre><code>mermaid</code></pre>"));
+        assert!(head_html().contains(&format!("mermaid@{MERMAID_VERSION}/")));
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_prerender() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let dir = TempDir::new().unwrap();
+        // Stand-in for mmdc: an SVG with the diagram source as its title
+        let command = dir.path().join("mmdc");
+        fs::write(
+            &command,
+            "#!/bin/sh\nprintf '<svg><title>%s</title></svg>\\n' \"$(cat \"$2\")\" > \"$4\"\n",
+        )
+        .unwrap();
+        fs::set_permissions(&command, fs::Permissions::from_mode(0o755)).unwrap();
+
+        let cache = dir.path().join("cache");
+        let prerenderer = Prerenderer::new(command.display().to_string(), &cache);
+        let html = prerenderer
+            .prerender("<p>A</p>\n<pre class=\"mermaid\">A --&gt; B</pre>\n<p>B</p>")
+            .unwrap();
+        assert_eq!(
+            html,
+            "<p>A</p>\n<div class=\"mermaid-diagram\"><svg><title>A --> B</title></svg></div>\n<p>B</p>"
+        );
+
+        // Cached: rendered again without the CLI
+        fs::remove_file(&command).unwrap();
+        assert_eq!(
+            prerenderer.render("A --> B").unwrap(),
+            "<svg><title>A --> B</title></svg>\n"
+        );
+        assert!(matches!(
+            prerenderer.render("graph TD"),
+            Err(MermaidError::Command(..))
+        ));
+    }
+}
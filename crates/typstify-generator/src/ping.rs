@@ -0,0 +1,280 @@
+//! Update notifications after a deploy.
+//!
+//! Tells WebSub hubs (`ping.websub`) that the published feeds changed, so
+//! subscribed feed readers fetch them at once, and calls sitemap ping URLs
+//! (`ping.sitemap`) with the published sitemap. The feeds advertise the hubs
+//! with `<atom:link rel="hub">`, so readers know where to subscribe.
+
+use std::{path::Path, time::Duration};
+
+use thiserror::Error;
+use tracing::debug;
+use typstify_core::Config;
+
+/// Request timeout.
+const TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Ping errors.
+#[derive(Debug, Error)]
+pub enum PingError {
+    /// The endpoint could not be reached or rejected the ping.
+    #[error("ping to {url} failed: {message}")]
+    Request {
+        /// Endpoint URL.
+        url: String,
+        /// Error message.
+        message: String,
+    },
+}
+
+/// Result type for ping operations.
+pub type Result<T> = std::result::Result<T, PingError>;
+
+/// A notification to send.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Ping {
+    /// WebSub publish request telling `hub` that the feed at `topic` changed.
+    WebSub {
+        /// Hub URL.
+        hub: String,
+        /// Feed URL.
+        topic: String,
+    },
+
+    /// Sitemap ping, with the sitemap URL filled in.
+    Sitemap {
+        /// Ping URL.
+        url: String,
+    },
+}
+
+impl Ping {
+    /// URL the ping is sent to.
+    #[must_use]
+    pub fn endpoint(&self) -> &str {
+        match self {
+            Self::WebSub { hub, .. } => hub,
+            Self::Sitemap { url } => url,
+        }
+    }
+}
+
+/// The pings to send for the site built into `output_dir`: one per hub for
+/// the main feed and each language feed found there, and one per sitemap
+/// ping URL when `sitemap.xml` was built.
+#[must_use]
+pub fn pings(config: &Config, output_dir: &Path) -> Vec<Ping> {
+    let base_url = config.base_url();
+    let mut feeds = Vec::new();
+    if output_dir.join("rss.xml").is_file() {
+        feeds.push(format!("{base_url}/rss.xml"));
+    }
+    for lang in config.all_languages() {
+        if output_dir.join(lang).join("rss.xml").is_file() {
+            feeds.push(format!("{base_url}/{lang}/rss.xml"));
+        }
+    }
+
+    let mut pings: Vec<Ping> = config
+        .ping
+        .websub
+        .iter()
+        .flat_map(|hub| {
+            feeds.iter().map(|topic| Ping::WebSub {
+                hub: hub.clone(),
+                topic: topic.clone(),
+            })
+        })
+        .collect();
+
+    if output_dir.join("sitemap.xml").is_file() {
+        let sitemap = url_encode(&format!("{base_url}/sitemap.xml"));
+        pings.extend(config.ping.sitemap.iter().map(|url| Ping::Sitemap {
+            url: url.replace("{sitemap}", &sitemap),
+        }));
+    }
+    pings
+}
+
+/// Sends pings over HTTP.
+#[derive(Debug)]
+pub struct Pinger {
+    /// HTTP client.
+    agent: ureq::Agent,
+}
+
+impl Default for Pinger {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Pinger {
+    /// Create a pinger.
+    #[must_use]
+    pub fn new() -> Self {
+        let agent = ureq::Agent::config_builder()
+            .timeout_global(Some(TIMEOUT))
+            .build()
+            .into();
+        Self { agent }
+    }
+
+    /// Send `ping`, failing unless the endpoint answers with a success status.
+    pub fn send(&self, ping: &Ping) -> Result<()> {
+        let response = match ping {
+            Ping::WebSub { hub, topic } => self
+                .agent
+                .post(hub)
+                .send_form([("hub.mode", "publish"), ("hub.url", topic.as_str())]),
+            Ping::Sitemap { url } => self.agent.get(url).call(),
+        };
+        response.map_err(|e| PingError::Request {
+            url: ping.endpoint().to_string(),
+            message: e.to_string(),
+        })?;
+        debug!(url = ping.endpoint(), "sent ping");
+        Ok(())
+    }
+}
+
+/// Percent-encode `s` for use as a query parameter value.
+fn url_encode(s: &str) -> String {
+    s.bytes()
+        .map(|b| match b {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'.' | b'_' | b'~' => {
+                char::from(b).to_string()
+            }
+            _ => format!("%{b:02X}"),
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        fs,
+        io::{Read, Write},
+        net::TcpListener,
+        thread,
+    };
+
+    use tempfile::TempDir;
+
+    use super::*;
+
+    fn config() -> Config {
+        toml::from_str(
+            r#"
+[site]
+title = "Test"
+host = "https://example.com"
+base_path = "/blog"
+
+[languages.zh]
+name = "中文"
+
+[ping]
+websub = ["https://hub.example.com/"]
+sitemap = ["https://search.example.com/ping?sitemap={sitemap}"]
+"#,
+        )
+        .unwrap()
+    }
+
+    /// Answer a single request with `status`, returning the URL and a
+    /// receiver for the request.
+    fn serve_once(status: &'static str) -> (String, std::sync::mpsc::Receiver<String>) {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let url = format!("http://{}/", listener.local_addr().unwrap());
+        let (sender, receiver) = std::sync::mpsc::channel();
+        thread::spawn(move || {
+            let (mut stream, _) = listener.accept().unwrap();
+            // Read the headers and a `Content-Length` body
+            let mut request = Vec::new();
+            let mut buf = [0; 1024];
+            while !is_complete(&request) {
+                let n = stream.read(&mut buf).unwrap();
+                if n == 0 {
+                    break;
+                }
+                request.extend(&buf[..n]);
+            }
+            sender
+                .send(String::from_utf8_lossy(&request).into_owned())
+                .unwrap();
+            let response =
+                format!("HTTP/1.1 {status}\r\nContent-Length: 0\r\nConnection: close\r\n\r\n");
+            stream.write_all(response.as_bytes()).unwrap();
+        });
+        (url, receiver)
+    }
+
+    /// Whether `request` holds the headers and the whole body.
+    fn is_complete(request: &[u8]) -> bool {
+        let text = String::from_utf8_lossy(request);
+        let Some((headers, body)) = text.split_once("\r\n\r\n") else {
+            return false;
+        };
+        let length = headers
+            .lines()
+            .find_map(|line| {
+                let (name, value) = line.split_once(':')?;
+                name.eq_ignore_ascii_case("content-length")
+                    .then(|| value.trim().parse::<usize>().ok())?
+            })
+            .unwrap_or(0);
+        body.len() >= length
+    }
+
+    #[test]
+    fn test_pings() {
+        let dir = TempDir::new().unwrap();
+        assert!(pings(&config(), dir.path()).is_empty());
+
+        fs::write(dir.path().join("rss.xml"), "").unwrap();
+        fs::create_dir_all(dir.path().join("zh")).unwrap();
+        fs::write(dir.path().join("zh/rss.xml"), "").unwrap();
+        fs::write(dir.path().join("sitemap.xml"), "").unwrap();
+
+        assert_eq!(
+            pings(&config(), dir.path()),
+            vec![
+                Ping::WebSub {
+                    hub: "https://hub.example.com/".to_string(),
+                    topic: "https://example.com/blog/rss.xml".to_string(),
+                },
+                Ping::WebSub {
+                    hub: "https://hub.example.com/".to_string(),
+                    topic: "https://example.com/blog/zh/rss.xml".to_string(),
+                },
+                Ping::Sitemap {
+                    url: "https://search.example.com/ping?sitemap=https%3A%2F%2Fexample.com%2Fblog%2Fsitemap.xml"
+                        .to_string(),
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_send() {
+        let pinger = Pinger::new();
+
+        let (hub, request) = serve_once("204 No Content");
+        pinger
+            .send(&Ping::WebSub {
+                hub,
+                topic: "https://example.com/rss.xml".to_string(),
+            })
+            .unwrap();
+        let request = request.recv().unwrap();
+        assert!(request.starts_with("POST / "));
+        assert!(request.ends_with("hub.mode=publish&hub.url=https%3A%2F%2Fexample.com%2Frss.xml"));
+
+        let (url, _) = serve_once("404 Not Found");
+        assert!(matches!(
+            pinger.send(&Ping::Sitemap { url }),
+            Err(PingError::Request { .. })
+        ));
+    }
+}
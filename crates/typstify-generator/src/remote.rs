@@ -0,0 +1,248 @@
+//! Localization of remote images.
+//!
+//! Downloads images referenced by absolute `http(s)` URLs in page content,
+//! stores them in a cache directory (`static/remote/` by default) and rewrites
+//! the references to point at the local copies. Cached files are reused
+//! between builds, so each image is only downloaded once.
+
+use std::{collections::HashMap, fs, path::PathBuf, time::Duration};
+
+use thiserror::Error;
+use tracing::{debug, warn};
+
+use crate::audit::{HtmlToken, scan_html};
+
+/// Directory (relative to the static directory and the output) that holds
+/// localized images.
+pub const REMOTE_DIR: &str = "remote";
+
+/// Download timeout per image.
+const TIMEOUT: Duration = Duration::from_secs(30);
+
+/// Largest image that will be downloaded.
+const MAX_SIZE: u64 = 20 * 1024 * 1024;
+
+/// Remote image errors.
+#[derive(Debug, Error)]
+pub enum RemoteError {
+    /// IO error.
+    #[error("IO error: {0}")]
+    Io(#[from] std::io::Error),
+}
+
+/// Result type for remote image operations.
+pub type Result<T> = std::result::Result<T, RemoteError>;
+
+/// Downloads and caches remote images.
+#[derive(Debug)]
+pub struct RemoteImages {
+    /// Cache directory for downloaded files.
+    cache_dir: PathBuf,
+
+    /// Cached file name by URL hash.
+    cached: HashMap<String, String>,
+
+    /// HTTP client.
+    agent: ureq::Agent,
+
+    /// URL and error of each image that failed since the last
+    /// [`take_failed`](Self::take_failed).
+    failed: Vec<(String, String)>,
+}
+
+impl RemoteImages {
+    /// Create a localizer caching downloads in `cache_dir`.
+    pub fn new(cache_dir: impl Into<PathBuf>) -> Result<Self> {
+        let cache_dir = cache_dir.into();
+
+        let mut cached = HashMap::new();
+        if cache_dir.is_dir() {
+            for entry in fs::read_dir(&cache_dir)? {
+                let name = entry?.file_name().to_string_lossy().into_owned();
+                if let Some((stem, _)) = name.split_once('.') {
+                    cached.insert(stem.to_string(), name);
+                }
+            }
+        }
+
+        let agent = ureq::Agent::config_builder()
+            .timeout_global(Some(TIMEOUT))
+            .build()
+            .into();
+
+        Ok(Self {
+            cache_dir,
+            cached,
+            agent,
+            failed: Vec::new(),
+        })
+    }
+
+    /// URL and error of each image that could not be localized since the
+    /// last call.
+    pub fn take_failed(&mut self) -> Vec<(String, String)> {
+        std::mem::take(&mut self.failed)
+    }
+
+    /// Rewrite remote `<img src>` URLs in `html` to `{url_prefix}/{file}`,
+    /// downloading images that aren't cached yet.
+    ///
+    /// Images that fail to download keep their original URL.
+    pub fn localize(&mut self, html: &str, url_prefix: &str) -> Result<String> {
+        let sources: Vec<String> = scan_html(html)
+            .iter()
+            .filter(|token| matches!(token, HtmlToken::Start { name, .. } if name == "img"))
+            .filter_map(|token| token.attr("src"))
+            .filter(|src| src.starts_with("https://") || src.starts_with("http://"))
+            .map(str::to_string)
+            .collect();
+
+        let mut html = html.to_string();
+        for src in sources {
+            let Some(file) = self.fetch(&src.replace("&amp;", "&"))? else {
+                continue;
+            };
+            html = html.replace(
+                &format!(r#"src="{src}""#),
+                &format!(r#"src="{url_prefix}/{file}""#),
+            );
+        }
+        Ok(html)
+    }
+
+    /// Cached file name for `url`, downloading it if needed.
+    fn fetch(&mut self, url: &str) -> Result<Option<String>> {
+        let key = url_hash(url);
+        if let Some(file) = self.cached.get(&key) {
+            return Ok(Some(file.clone()));
+        }
+
+        let (bytes, content_type) = match self.download(url) {
+            Ok(result) => result,
+            Err(e) => {
+                warn!(url, error = %e, "failed to download remote image");
+                self.failed
+                    .push((url.to_string(), format!("download failed: {e}")));
+                return Ok(None);
+            }
+        };
+
+        let Some(ext) = image_extension(url, content_type.as_deref()) else {
+            warn!(url, content_type, "remote URL is not an image");
+            self.failed
+                .push((url.to_string(), "not an image".to_string()));
+            return Ok(None);
+        };
+
+        let file = format!("{key}.{ext}");
+        fs::create_dir_all(&self.cache_dir)?;
+        fs::write(self.cache_dir.join(&file), bytes)?;
+        debug!(url, file, "downloaded remote image");
+
+        self.cached.insert(key, file.clone());
+        Ok(Some(file))
+    }
+
+    /// Download `url`, returning the body and content type.
+    fn download(&self, url: &str) -> std::result::Result<(Vec<u8>, Option<String>), ureq::Error> {
+        let mut response = self.agent.get(url).call()?;
+        let content_type = response
+            .headers()
+            .get("content-type")
+            .and_then(|v| v.to_str().ok())
+            .map(str::to_string);
+        let bytes = response
+            .body_mut()
+            .with_config()
+            .limit(MAX_SIZE)
+            .read_to_vec()?;
+        Ok((bytes, content_type))
+    }
+}
+
+/// Stable cache key for a URL (FNV-1a, 16 hex characters).
+pub(crate) fn url_hash(url: &str) -> String {
+    let mut hash: u64 = 0xcbf29ce484222325;
+    for byte in url.bytes() {
+        hash ^= u64::from(byte);
+        hash = hash.wrapping_mul(0x100000001b3);
+    }
+    format!("{hash:016x}")
+}
+
+/// File extension for a downloaded image, from the URL or content type.
+fn image_extension(url: &str, content_type: Option<&str>) -> Option<&'static str> {
+    let path = url.split(['?', '#']).next().unwrap_or(url);
+    let from_url = path
+        .rsplit_once('/')
+        .and_then(|(_, name)| name.rsplit_once('.'))
+        .map(|(_, ext)| ext.to_ascii_lowercase());
+    if let Some(ext) = from_url.as_deref() {
+        let ext = match ext {
+            "png" => Some("png"),
+            "jpg" | "jpeg" => Some("jpg"),
+            "gif" => Some("gif"),
+            "webp" => Some("webp"),
+            "avif" => Some("avif"),
+            "svg" => Some("svg"),
+            _ => None,
+        };
+        if ext.is_some() {
+            return ext;
+        }
+    }
+
+    let mime = content_type?.split(';').next()?.trim();
+    match mime {
+        "image/png" => Some("png"),
+        "image/jpeg" => Some("jpg"),
+        "image/gif" => Some("gif"),
+        "image/webp" => Some("webp"),
+        "image/avif" => Some("avif"),
+        "image/svg+xml" => Some("svg"),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use tempfile::TempDir;
+
+    use super::*;
+
+    #[test]
+    fn test_image_extension() {
+        assert_eq!(
+            image_extension("https://x.com/a/photo.JPEG?w=100", None),
+            Some("jpg")
+        );
+        assert_eq!(
+            image_extension("https://x.com/avatar?id=1", Some("image/png; q=1")),
+            Some("png")
+        );
+        assert_eq!(
+            image_extension("https://x.com/page", Some("text/html")),
+            None
+        );
+    }
+
+    #[test]
+    fn test_localize_from_cache() {
+        let cache = TempDir::new().unwrap();
+        let url = "https://images.example.com/cat.png?size=1&fmt=2";
+        let file = format!("{}.png", url_hash(url));
+        fs::write(cache.path().join(&file), b"png").unwrap();
+
+        let mut remote = RemoteImages::new(cache.path()).unwrap();
+        let html = r#"<p><img src="https://images.example.com/cat.png?size=1&amp;fmt=2" alt="cat" /> <img src="/local.png" /></p>"#;
+        let localized = remote.localize(html, "/blog/remote").unwrap();
+
+        assert_eq!(
+            localized,
+            format!(
+                r#"<p><img src="/blog/remote/{file}" alt="cat" /> <img src="/local.png" /></p>"#
+            )
+        );
+        assert!(remote.take_failed().is_empty());
+    }
+}
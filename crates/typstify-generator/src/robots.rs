@@ -6,7 +6,7 @@ use std::{fs::File, io::Write, path::Path};
 
 use thiserror::Error;
 use tracing::info;
-use typstify_core::Config;
+use typstify_core::{Config, config::SectionRobots};
 
 /// Robots generation errors.
 #[derive(Debug, Error)]
@@ -33,30 +33,256 @@ impl RobotsGenerator {
     }
 
     /// Generate robots.txt.
-    pub fn generate(&self, output_dir: &Path) -> Result<()> {
+    ///
+    /// `sitemaps` are paths relative to the site root (e.g. `sitemap.xml`,
+    /// `zh/sitemap.xml`); each gets its own `Sitemap:` line.
+    pub fn generate(&self, output_dir: &Path, sitemaps: &[String]) -> Result<()> {
         if !self.config.robots.enabled {
             return Ok(());
         }
 
-        info!("generating robots.txt");
+        info!(sitemaps = sitemaps.len(), "generating robots.txt");
 
         let path = output_dir.join("robots.txt");
         let mut file = File::create(path)?;
+        file.write_all(self.render(sitemaps).as_bytes())?;
 
-        writeln!(file, "User-agent: *")?;
+        Ok(())
+    }
+
+    /// Render robots.txt content.
+    #[must_use]
+    pub fn render(&self, sitemaps: &[String]) -> String {
+        let mut out = String::from("User-agent: *\n");
 
         for path in &self.config.robots.disallow {
-            writeln!(file, "Disallow: {path}")?;
+            out.push_str(&format!("Disallow: {path}\n"));
+        }
+
+        for path in self.disallowed_sections() {
+            out.push_str(&format!("Disallow: {path}\n"));
         }
 
         for path in &self.config.robots.allow {
-            writeln!(file, "Allow: {path}")?;
+            out.push_str(&format!("Allow: {path}\n"));
         }
 
-        // Add sitemap reference if configured (defaulting to sitemap.xml in root)
-        let sitemap_url = format!("{}/sitemap.xml", self.config.base_url());
-        writeln!(file, "Sitemap: {sitemap_url}")?;
+        // Default to sitemap.xml in root when no sitemaps were reported
+        if sitemaps.is_empty() {
+            out.push_str(&format!(
+                "Sitemap: {}\n",
+                self.config.url_for("sitemap.xml")
+            ));
+        }
+        for sitemap in sitemaps {
+            out.push_str(&format!("Sitemap: {}\n", self.config.url_for(sitemap)));
+        }
 
-        Ok(())
+        out
+    }
+
+    /// Paths of the sections configured with `robots = "disallow"`, in every
+    /// language, sorted.
+    fn disallowed_sections(&self) -> Vec<String> {
+        let base_path = self.config.base_path();
+        let default_lang = &self.config.site.default_language;
+        let mut paths: Vec<String> = self
+            .config
+            .sections
+            .iter()
+            .filter(|(_, section)| section.robots == SectionRobots::Disallow)
+            .flat_map(|(section, _)| {
+                let section = section.trim_matches('/');
+                self.config.all_languages().into_iter().map(move |lang| {
+                    if lang == default_lang {
+                        format!("{base_path}/{section}/")
+                    } else {
+                        format!("{base_path}/{lang}/{section}/")
+                    }
+                })
+            })
+            .collect();
+        paths.sort();
+        paths
+    }
+
+    /// Validate allow/disallow patterns against the site's generated URLs.
+    ///
+    /// Returns a warning for every pattern that is malformed or matches none
+    /// of `urls` (page URLs without the base path).
+    #[must_use]
+    pub fn validate(&self, urls: &[&str]) -> Vec<String> {
+        let base_path = self.config.base_path();
+        let paths: Vec<String> = urls.iter().map(|u| format!("{base_path}{u}")).collect();
+        let mut warnings = Vec::new();
+
+        let rules = self
+            .config
+            .robots
+            .disallow
+            .iter()
+            .map(|p| ("disallow", p))
+            .chain(self.config.robots.allow.iter().map(|p| ("allow", p)));
+
+        for (kind, pattern) in rules {
+            if pattern.is_empty() {
+                continue;
+            }
+            if !pattern.starts_with('/') && !pattern.starts_with('*') {
+                warnings.push(format!(
+                    "robots.{kind} pattern '{pattern}' should start with '/' or '*'"
+                ));
+                continue;
+            }
+            if !paths.iter().any(|path| pattern_matches(pattern, path)) {
+                warnings.push(format!(
+                    "robots.{kind} pattern '{pattern}' does not match any generated URL"
+                ));
+            }
+        }
+
+        warnings
+    }
+}
+
+/// Check whether a robots.txt path pattern matches a URL path.
+///
+/// Supports `*` wildcards and a trailing `$` end anchor; otherwise the
+/// pattern is a prefix match, as crawlers interpret it.
+#[must_use]
+pub fn pattern_matches(pattern: &str, path: &str) -> bool {
+    let (pattern, anchored) = match pattern.strip_suffix('$') {
+        Some(p) => (p, true),
+        None => (pattern, false),
+    };
+
+    let parts: Vec<&str> = pattern.split('*').collect();
+    let mut rest = path;
+
+    for (i, part) in parts.iter().enumerate() {
+        if i == 0 {
+            match rest.strip_prefix(part) {
+                Some(r) => rest = r,
+                None => return false,
+            }
+        } else if i == parts.len() - 1 && anchored {
+            return rest.ends_with(part);
+        } else {
+            match rest.find(part) {
+                Some(pos) => rest = &rest[pos + part.len()..],
+                None => return false,
+            }
+        }
+    }
+
+    !anchored || rest.is_empty()
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use super::*;
+
+    fn test_config() -> Config {
+        Config {
+            theme: None,
+            site: typstify_core::config::SiteConfig {
+                title: "Test Site".to_string(),
+                host: "https://example.com".to_string(),
+                base_path: String::new(),
+                default_language: "en".to_string(),
+                description: None,
+                author: None,
+                timezone: None,
+            },
+            languages: HashMap::new(),
+            build: typstify_core::config::BuildConfig::default(),
+            search: typstify_core::config::SearchConfig::default(),
+            rss: typstify_core::config::RssConfig::default(),
+            robots: typstify_core::config::RobotsConfig {
+                enabled: true,
+                disallow: vec!["/drafts/".to_string(), "/*.pdf$".to_string()],
+                allow: vec!["/blog".to_string()],
+            },
+            taxonomies: typstify_core::config::TaxonomyConfig::default(),
+            dates: typstify_core::config::DatesConfig::default(),
+            css: typstify_core::config::CssConfig::default(),
+            images: typstify_core::config::ImagesConfig::default(),
+            markdown: typstify_core::config::MarkdownConfig::default(),
+            typography: typstify_core::config::TypographyConfig::default(),
+            sections: std::collections::HashMap::new(),
+            check: typstify_core::config::CheckConfig::default(),
+            webmentions: typstify_core::config::WebmentionsConfig::default(),
+            identity: typstify_core::config::IdentityConfig::default(),
+            content: typstify_core::config::ContentConfig::default(),
+            ping: typstify_core::config::PingConfig::default(),
+            toc: typstify_core::config::TocConfig::default(),
+            math: typstify_core::config::MathConfig::default(),
+            mermaid: typstify_core::config::MermaidConfig::default(),
+            fmt: typstify_core::config::FmtConfig::default(),
+        }
+    }
+
+    #[test]
+    fn test_render_sitemaps() {
+        let generator = RobotsGenerator::new(test_config());
+        let robots = generator.render(&["sitemap.xml".to_string(), "zh/sitemap.xml".to_string()]);
+
+        assert!(robots.starts_with("User-agent: *\n"));
+        assert!(robots.contains("Disallow: /drafts/\n"));
+        assert!(robots.contains("Allow: /blog\n"));
+        assert!(robots.contains("Sitemap: https://example.com/sitemap.xml\n"));
+        assert!(robots.contains("Sitemap: https://example.com/zh/sitemap.xml\n"));
+    }
+
+    #[test]
+    fn test_render_disallowed_sections() {
+        let mut config = test_config();
+        config.site.base_path = "/blog".to_string();
+        config
+            .languages
+            .insert("zh".to_string(), Default::default());
+        config.sections.insert(
+            "internal".to_string(),
+            typstify_core::config::SectionConfig {
+                robots: SectionRobots::Disallow,
+                ..Default::default()
+            },
+        );
+
+        let robots = RobotsGenerator::new(config).render(&[]);
+        assert!(robots.contains("Disallow: /blog/internal/\nDisallow: /blog/zh/internal/\n"));
+    }
+
+    #[test]
+    fn test_render_default_sitemap() {
+        let generator = RobotsGenerator::new(test_config());
+        let robots = generator.render(&[]);
+        assert!(robots.contains("Sitemap: https://example.com/sitemap.xml"));
+    }
+
+    #[test]
+    fn test_pattern_matches() {
+        assert!(pattern_matches("/drafts/", "/drafts/post"));
+        assert!(!pattern_matches("/drafts/", "/posts/drafts/"));
+        assert!(pattern_matches("/*.pdf$", "/files/doc.pdf"));
+        assert!(!pattern_matches("/*.pdf$", "/files/doc.pdf.html"));
+        assert!(pattern_matches("/*/private", "/docs/private/a"));
+        assert!(pattern_matches("/about$", "/about"));
+        assert!(!pattern_matches("/about$", "/about/team"));
+    }
+
+    #[test]
+    fn test_validate_patterns() {
+        let mut config = test_config();
+        config.robots.allow.push("blog".to_string());
+        let generator = RobotsGenerator::new(config);
+
+        let warnings = generator.validate(&["/blog/hello", "/drafts/wip"]);
+
+        assert_eq!(warnings.len(), 2);
+        assert!(warnings[0].contains("'/*.pdf$' does not match"));
+        assert!(warnings[1].contains("'blog' should start with"));
     }
 }
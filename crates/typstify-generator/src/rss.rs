@@ -5,7 +5,10 @@
 use std::io::Write;
 
 use chrono::Utc;
-use rss::{ChannelBuilder, GuidBuilder, Item, ItemBuilder};
+use rss::{
+    ChannelBuilder, GuidBuilder, Item, ItemBuilder,
+    extension::atom::{AtomExtension, Link},
+};
 use thiserror::Error;
 use tracing::debug;
 use typstify_core::{Config, Page};
@@ -62,6 +65,7 @@ impl RssGenerator {
             )
             .language(Some(self.config.site.default_language.clone()))
             .last_build_date(Some(Utc::now().to_rfc2822()))
+            .atom_ext(self.websub_links(format!("{}/rss.xml", self.config.base_url())))
             .items(items)
             .build();
 
@@ -103,6 +107,67 @@ impl RssGenerator {
             .description(description)
             .language(Some(lang.to_string()))
             .last_build_date(Some(Utc::now().to_rfc2822()))
+            .atom_ext(self.websub_links(format!("{}/{lang}/rss.xml", self.config.base_url())))
+            .items(items)
+            .build();
+
+        Ok(channel.to_string())
+    }
+
+    /// `<atom:link>`s to the feed at `feed_url` itself and to the configured
+    /// WebSub hubs, if there are any.
+    fn websub_links(&self, feed_url: String) -> Option<AtomExtension> {
+        let hubs = &self.config.ping.websub;
+        if hubs.is_empty() {
+            return None;
+        }
+
+        let mut links = vec![Link {
+            href: feed_url,
+            rel: "self".to_string(),
+            mime_type: Some("application/rss+xml".to_string()),
+            ..Link::default()
+        }];
+        links.extend(hubs.iter().map(|hub| Link {
+            href: hub.clone(),
+            rel: "hub".to_string(),
+            ..Link::default()
+        }));
+        Some(AtomExtension { links })
+    }
+
+    /// Generate RSS feed for a single taxonomy term.
+    ///
+    /// `term_url` is the term page URL relative to the site root
+    /// (e.g., `/tags/rust`).
+    pub fn generate_for_term(
+        &self,
+        pages: &[&Page],
+        taxonomy_name: &str,
+        term: &str,
+        term_url: &str,
+    ) -> Result<String> {
+        let limit = self.config.rss.limit;
+        let pages: Vec<_> = pages.iter().take(limit).collect();
+
+        debug!(
+            count = pages.len(),
+            limit, term, "generating taxonomy term RSS feed"
+        );
+
+        let items: Vec<Item> = pages
+            .iter()
+            .filter_map(|page| self.page_to_item(page))
+            .collect();
+
+        let title = format!("{} | {taxonomy_name}: {term}", self.config.site.title);
+
+        let channel = ChannelBuilder::default()
+            .title(&title)
+            .link(format!("{}{term_url}", self.config.base_url()))
+            .description(&title)
+            .language(Some(self.config.site.default_language.clone()))
+            .last_build_date(Some(Utc::now().to_rfc2822()))
             .items(items)
             .build();
 
@@ -172,6 +237,7 @@ mod tests {
 
     fn test_config() -> Config {
         Config {
+            theme: None,
             site: typstify_core::config::SiteConfig {
                 title: "Test Blog".to_string(),
                 host: "https://example.com".to_string(),
@@ -179,6 +245,7 @@ mod tests {
                 default_language: "en".to_string(),
                 description: Some("A test blog".to_string()),
                 author: Some("Test Author".to_string()),
+                timezone: None,
             },
             languages: HashMap::new(),
             build: typstify_core::config::BuildConfig::default(),
@@ -186,9 +253,25 @@ mod tests {
             rss: typstify_core::config::RssConfig {
                 enabled: true,
                 limit: 20,
+                exclude_pinned: false,
             },
             robots: typstify_core::config::RobotsConfig::default(),
             taxonomies: typstify_core::config::TaxonomyConfig::default(),
+            dates: typstify_core::config::DatesConfig::default(),
+            css: typstify_core::config::CssConfig::default(),
+            images: typstify_core::config::ImagesConfig::default(),
+            markdown: typstify_core::config::MarkdownConfig::default(),
+            typography: typstify_core::config::TypographyConfig::default(),
+            sections: std::collections::HashMap::new(),
+            check: typstify_core::config::CheckConfig::default(),
+            webmentions: typstify_core::config::WebmentionsConfig::default(),
+            identity: typstify_core::config::IdentityConfig::default(),
+            content: typstify_core::config::ContentConfig::default(),
+            ping: typstify_core::config::PingConfig::default(),
+            toc: typstify_core::config::TocConfig::default(),
+            math: typstify_core::config::MathConfig::default(),
+            mermaid: typstify_core::config::MermaidConfig::default(),
+            fmt: typstify_core::config::FmtConfig::default(),
         }
     }
 
@@ -199,6 +282,7 @@ mod tests {
             description: Some(format!("Description for {}", title)),
             date,
             updated: None,
+            expiry_date: None,
             draft: false,
             lang: "en".to_string(),
             is_default_lang: true,
@@ -215,6 +299,13 @@ mod tests {
             aliases: vec![],
             template: None,
             weight: 0,
+            pinned: false,
+            series: None,
+            content_id: None,
+            extra_meta: std::collections::BTreeMap::new(),
+            structured_data: None,
+            images: vec![],
+            event: None,
             source_path: Some(PathBuf::from("test.md")),
         }
     }
@@ -235,6 +326,41 @@ mod tests {
         assert!(xml.contains("<category>rust</category>"));
     }
 
+    #[test]
+    fn test_websub_hub_links() {
+        let page = test_page("First Post", Some(Utc::now()));
+        let xml = RssGenerator::new(test_config()).generate(&[&page]).unwrap();
+        assert!(!xml.contains("atom:link"));
+
+        let mut config = test_config();
+        config.ping.websub = vec!["https://hub.example.com/".to_string()];
+        let generator = RssGenerator::new(config);
+
+        let xml = generator.generate(&[&page]).unwrap();
+        assert!(xml.contains(r#"xmlns:atom="http://www.w3.org/2005/Atom""#));
+        assert!(xml.contains(
+            r#"<atom:link href="https://example.com/rss.xml" rel="self" type="application/rss+xml"/>"#
+        ));
+        assert!(xml.contains(r#"<atom:link href="https://hub.example.com/" rel="hub"/>"#));
+
+        let xml = generator.generate_for_lang(&[&page], "zh").unwrap();
+        assert!(xml.contains(r#"href="https://example.com/zh/rss.xml" rel="self""#));
+    }
+
+    #[test]
+    fn test_generate_for_term() {
+        let generator = RssGenerator::new(test_config());
+        let page = test_page("Tagged", Some(Utc::now()));
+
+        let xml = generator
+            .generate_for_term(&[&page], "Tags", "rust", "/tags/rust")
+            .unwrap();
+
+        assert!(xml.contains("<title>Test Blog | Tags: rust</title>"));
+        assert!(xml.contains("<link>https://example.com/tags/rust</link>"));
+        assert!(xml.contains("<title>Tagged</title>"));
+    }
+
     #[test]
     fn test_rss_limit() {
         let mut config = test_config();
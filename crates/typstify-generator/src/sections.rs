@@ -0,0 +1,630 @@
+//! Content section hierarchy.
+//!
+//! Pages are grouped by their parent URL path: `/docs/guides/install` belongs
+//! to the `docs/guides` section, which is a subsection of `docs`. Top-level
+//! pages such as `/about` belong to no section. An `_index` file in a
+//! section directory (`content/docs/guides/_index.md`) sets the section's
+//! title, description, weight and template; see [`SectionMeta`].
+//!
+//! Each language's hierarchy is also written as [`SITE_STRUCTURE_FILE`] for
+//! client-side components such as `typstify_ui::Breadcrumbs`.
+
+use std::collections::{BTreeMap, HashMap};
+
+use serde::Serialize;
+use typstify_core::{content::Page, escape_html};
+
+/// File name of the site structure JSON, written next to each language's
+/// search index (`/sections.json`, `/zh/sections.json`).
+pub const SITE_STRUCTURE_FILE: &str = "sections.json";
+
+/// A content section.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Section {
+    /// URLs of the pages directly in this section.
+    pub pages: Vec<String>,
+
+    /// Paths of the direct subsections, by weight, then path.
+    pub subsections: Vec<String>,
+
+    /// Metadata from the section's `_index` file.
+    pub meta: Option<SectionMeta>,
+}
+
+/// Section metadata from the frontmatter of an `_index` file.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct SectionMeta {
+    /// Language of the `_index` file.
+    pub lang: String,
+
+    /// Display title, replacing the one derived from the section path.
+    pub title: Option<String>,
+
+    /// Description shown on the section index page.
+    pub description: Option<String>,
+
+    /// Sort weight among sibling sections (lower first).
+    pub weight: i32,
+
+    /// Template for the section index page instead of `section`.
+    pub template: Option<String>,
+}
+
+impl SectionMeta {
+    /// Metadata from an `_index` file parsed as a page.
+    #[must_use]
+    pub fn from_page(page: &Page) -> Self {
+        Self {
+            lang: page.lang.clone(),
+            title: Some(page.title.clone()).filter(|title| !title.is_empty()),
+            description: page.description.clone(),
+            weight: page.weight,
+            template: page.template.clone(),
+        }
+    }
+}
+
+/// Previous and next page URLs of a page within its section.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Neighbors {
+    /// The page before (lower weight or older).
+    pub prev: Option<String>,
+
+    /// The page after (higher weight or newer).
+    pub next: Option<String>,
+}
+
+/// Sections of one language, keyed by path without the language prefix
+/// (e.g. "docs/guides").
+#[derive(Debug, Clone, Default)]
+pub struct SectionTree {
+    sections: BTreeMap<String, Section>,
+}
+
+impl SectionTree {
+    /// Build the tree from pages whose URLs start with `lang_prefix`
+    /// (e.g. "/zh", or "" for the default language).
+    pub fn build<'a>(pages: impl IntoIterator<Item = &'a Page>, lang_prefix: &str) -> Self {
+        let mut tree = Self::default();
+
+        for page in pages {
+            let Some(path) = page.url.strip_prefix(lang_prefix) else {
+                continue;
+            };
+            let Some((parent, _)) = path.trim_matches('/').rsplit_once('/') else {
+                continue;
+            };
+
+            tree.insert(parent).pages.push(page.url.clone());
+        }
+
+        tree.sort_subsections();
+        tree
+    }
+
+    /// Attach the `_index` metadata in `meta`, keyed by section URL, to the
+    /// sections whose URLs start with `lang_prefix`.
+    ///
+    /// Sections with an `_index` file but no pages are added, so they get
+    /// an index page too.
+    #[must_use]
+    pub fn with_meta<'a>(
+        mut self,
+        meta: impl IntoIterator<Item = (&'a String, &'a SectionMeta)>,
+        lang_prefix: &str,
+    ) -> Self {
+        for (url, meta) in meta {
+            let Some(path) = url.strip_prefix(lang_prefix) else {
+                continue;
+            };
+            let path = path.trim_matches('/');
+            if path.is_empty() {
+                continue;
+            }
+            self.insert(path).meta = Some(meta.clone());
+        }
+
+        self.sort_subsections();
+        self
+    }
+
+    /// Get or add the section at `path`, registering every ancestor and
+    /// linking it to its child.
+    fn insert(&mut self, path: &str) -> &mut Section {
+        let mut child = path;
+        while let Some((ancestor, _)) = child.rsplit_once('/') {
+            let subsections = &mut self
+                .sections
+                .entry(ancestor.to_string())
+                .or_default()
+                .subsections;
+            if !subsections.iter().any(|s| s == child) {
+                subsections.push(child.to_string());
+            }
+            child = ancestor;
+        }
+        self.sections.entry(path.to_string()).or_default()
+    }
+
+    /// Order the subsections of every section by weight, then path.
+    fn sort_subsections(&mut self) {
+        let weights: HashMap<String, i32> = self
+            .sections
+            .iter()
+            .filter_map(|(path, section)| Some((path.clone(), section.meta.as_ref()?.weight)))
+            .collect();
+        for section in self.sections.values_mut() {
+            section.subsections.sort_by(|a, b| {
+                let weight = |path: &String| weights.get(path).copied().unwrap_or_default();
+                weight(a).cmp(&weight(b)).then_with(|| a.cmp(b))
+            });
+        }
+    }
+
+    /// Get a section by path.
+    #[must_use]
+    pub fn get(&self, path: &str) -> Option<&Section> {
+        self.sections.get(path)
+    }
+
+    /// Display title of the section at `path`: the title of its `_index`
+    /// file, or [`section_title`].
+    #[must_use]
+    pub fn title(&self, path: &str) -> String {
+        self.get(path)
+            .and_then(|section| section.meta.as_ref()?.title.clone())
+            .unwrap_or_else(|| section_title(path))
+    }
+
+    /// Iterate over all sections, parents before children.
+    pub fn iter(&self) -> impl Iterator<Item = (&str, &Section)> {
+        self.sections
+            .iter()
+            .map(|(path, section)| (path.as_str(), section))
+    }
+
+    /// Previous/next pages of every page in the tree.
+    ///
+    /// Pages are ordered within their own section by weight, then date
+    /// (oldest first), then title.
+    #[must_use]
+    pub fn neighbors(&self, pages: &HashMap<String, Page>) -> HashMap<String, Neighbors> {
+        let mut neighbors = HashMap::new();
+
+        for section in self.sections.values() {
+            let mut ordered: Vec<&Page> = section
+                .pages
+                .iter()
+                .filter_map(|url| pages.get(url))
+                .collect();
+            ordered.sort_by(|a, b| {
+                a.weight
+                    .cmp(&b.weight)
+                    .then_with(|| a.date.cmp(&b.date))
+                    .then_with(|| a.title.cmp(&b.title))
+            });
+
+            for (i, page) in ordered.iter().enumerate() {
+                let prev = i.checked_sub(1).map(|j| ordered[j].url.clone());
+                let next = ordered.get(i + 1).map(|p| p.url.clone());
+                neighbors.insert(page.url.clone(), Neighbors { prev, next });
+            }
+        }
+        neighbors
+    }
+
+    /// Whether there are no sections.
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.sections.is_empty()
+    }
+}
+
+/// Sections and page titles of one language, as [`SITE_STRUCTURE_FILE`].
+///
+/// URLs include the base path and language prefix, as browsers see them.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+pub struct SiteStructure {
+    /// URL of the home page (e.g. "/blog/zh/").
+    pub home: String,
+
+    /// Section path (e.g. "docs/guides") -> section.
+    pub sections: BTreeMap<String, SectionEntry>,
+
+    /// Page URL -> page title.
+    pub pages: BTreeMap<String, String>,
+}
+
+/// A section in the [`SiteStructure`].
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct SectionEntry {
+    /// Display title.
+    pub title: String,
+
+    /// Section URL.
+    pub url: String,
+}
+
+impl SiteStructure {
+    /// Structure of `tree`, with the titles of its pages from `pages`.
+    ///
+    /// `base_path` prefixes every URL; `lang_prefix` is the language part of
+    /// the section URLs (e.g. "/zh").
+    #[must_use]
+    pub fn build(
+        tree: &SectionTree,
+        pages: &HashMap<String, Page>,
+        base_path: &str,
+        lang_prefix: &str,
+    ) -> Self {
+        let sections = tree
+            .iter()
+            .map(|(path, _)| {
+                let entry = SectionEntry {
+                    title: tree.title(path),
+                    url: format!("{base_path}{lang_prefix}/{path}"),
+                };
+                (path.to_string(), entry)
+            })
+            .collect();
+        let pages = tree
+            .iter()
+            .flat_map(|(_, section)| &section.pages)
+            .filter_map(|url| pages.get(url))
+            .map(|page| (format!("{base_path}{}", page.url), page.title.clone()))
+            .collect();
+        Self {
+            home: format!("{base_path}{lang_prefix}/"),
+            sections,
+            pages,
+        }
+    }
+}
+
+/// Display title for a section path: its last segment, capitalized, with
+/// hyphens as spaces.
+#[must_use]
+pub fn section_title(path: &str) -> String {
+    let name = path.rsplit('/').next().unwrap_or(path).replace('-', " ");
+    let mut chars = name.chars();
+    match chars.next() {
+        Some(first) => first.to_uppercase().collect::<String>() + chars.as_str(),
+        None => name,
+    }
+}
+
+/// Breadcrumb trail from the home page to the section at `path`.
+///
+/// `prefix` is the base path plus language prefix (e.g. "/blog/zh"). With
+/// `current`, the section is linked and `current` is the last crumb;
+/// otherwise the section itself is the current crumb. `title` gives the
+/// display title of a section path, such as [`section_title`].
+#[must_use]
+pub fn breadcrumbs_html(
+    prefix: &str,
+    path: &str,
+    current: Option<&str>,
+    title: impl Fn(&str) -> String,
+) -> String {
+    let mut crumbs = vec![format!(r#"<li><a href="{prefix}/">Home</a></li>"#)];
+
+    let segments: Vec<&str> = path.split('/').filter(|s| !s.is_empty()).collect();
+    for i in 0..segments.len() {
+        let section = segments[..=i].join("/");
+        let title = escape_html(&title(&section));
+        if i + 1 == segments.len() && current.is_none() {
+            crumbs.push(format!(r#"<li aria-current="page">{title}</li>"#));
+        } else {
+            crumbs.push(format!(
+                r#"<li><a href="{prefix}/{section}">{title}</a></li>"#
+            ));
+        }
+    }
+    if let Some(current) = current {
+        crumbs.push(format!(
+            r#"<li aria-current="page">{}</li>"#,
+            escape_html(current)
+        ));
+    }
+
+    format!(
+        "<nav class=\"breadcrumbs\" aria-label=\"Breadcrumb\"><ol>{}</ol></nav>",
+        crumbs.concat()
+    )
+}
+
+/// Links to the subsections of `section` in `tree`, or `None` if it has
+/// none.
+#[must_use]
+pub fn subsections_html(prefix: &str, tree: &SectionTree, section: &Section) -> Option<String> {
+    if section.subsections.is_empty() {
+        return None;
+    }
+
+    let items: String = section
+        .subsections
+        .iter()
+        .map(|path| {
+            format!(
+                r#"<li><a href="{prefix}/{path}">{}</a></li>"#,
+                escape_html(&tree.title(path))
+            )
+        })
+        .collect();
+    Some(format!(
+        "<nav class=\"subsections\" aria-label=\"Subsections\"><ul>{items}</ul></nav>"
+    ))
+}
+
+/// Sidebar tree of the section at `root` and all its subsections, or `None`
+/// if there is no such section.
+///
+/// `pages` maps URLs to pages, which are listed by weight, then title. Links
+/// are prefixed with `base_path`; `lang_prefix` is the language part of the
+/// section URLs (e.g. "/zh").
+#[must_use]
+pub fn sidebar_html(
+    tree: &SectionTree,
+    root: &str,
+    base_path: &str,
+    lang_prefix: &str,
+    pages: &HashMap<String, Page>,
+) -> Option<String> {
+    fn list(
+        tree: &SectionTree,
+        section: &Section,
+        base_path: &str,
+        lang_prefix: &str,
+        pages: &HashMap<String, Page>,
+    ) -> String {
+        let mut section_pages: Vec<&Page> = section
+            .pages
+            .iter()
+            .filter_map(|url| pages.get(url))
+            .collect();
+        section_pages.sort_by(|a, b| a.weight.cmp(&b.weight).then_with(|| a.title.cmp(&b.title)));
+
+        let mut items: Vec<String> = section_pages
+            .iter()
+            .map(|page| {
+                format!(
+                    r#"<li><a href="{base_path}{}">{}</a></li>"#,
+                    page.url,
+                    escape_html(&page.title)
+                )
+            })
+            .collect();
+        for path in &section.subsections {
+            let Some(child) = tree.get(path) else {
+                continue;
+            };
+            items.push(format!(
+                r#"<li class="sidebar-section"><a href="{base_path}{lang_prefix}/{path}">{}</a>{}</li>"#,
+                escape_html(&tree.title(path)),
+                list(tree, child, base_path, lang_prefix, pages)
+            ));
+        }
+        format!("<ul>{}</ul>", items.concat())
+    }
+
+    let section = tree.get(root)?;
+    Some(format!(
+        r#"<nav class="docs-nav"><a class="sidebar-title" href="{base_path}{lang_prefix}/{root}">{}</a>{}</nav>"#,
+        escape_html(&tree.title(root)),
+        list(tree, section, base_path, lang_prefix, pages)
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::TimeZone;
+
+    use super::*;
+
+    fn page(url: &str) -> Page {
+        Page {
+            url: url.to_string(),
+            title: url.to_string(),
+            description: None,
+            date: None,
+            updated: None,
+            expiry_date: None,
+            draft: false,
+            lang: "en".to_string(),
+            is_default_lang: true,
+            canonical_id: String::new(),
+            tags: vec![],
+            categories: vec![],
+            content: String::new(),
+            summary: None,
+            reading_time: None,
+            word_count: None,
+            toc: vec![],
+            custom_js: vec![],
+            custom_css: vec![],
+            aliases: vec![],
+            template: None,
+            weight: 0,
+            pinned: false,
+            series: None,
+            content_id: None,
+            extra_meta: std::collections::BTreeMap::new(),
+            structured_data: None,
+            images: vec![],
+            event: None,
+            source_path: None,
+        }
+    }
+
+    #[test]
+    fn test_build_tree() {
+        let pages = [
+            page("/about"),
+            page("/docs/intro"),
+            page("/docs/guides/install"),
+            page("/docs/guides/advanced/tuning"),
+            page("/docs/api/core"),
+        ];
+        let tree = SectionTree::build(&pages, "");
+
+        assert!(tree.get("about").is_none());
+        let docs = tree.get("docs").unwrap();
+        assert_eq!(docs.pages, vec!["/docs/intro"]);
+        assert_eq!(docs.subsections, vec!["docs/api", "docs/guides"]);
+        assert_eq!(
+            tree.get("docs/guides").unwrap().subsections,
+            vec!["docs/guides/advanced"]
+        );
+        assert_eq!(
+            tree.iter().map(|(path, _)| path).collect::<Vec<_>>(),
+            vec!["docs", "docs/api", "docs/guides", "docs/guides/advanced"]
+        );
+    }
+
+    #[test]
+    fn test_build_tree_with_language_prefix() {
+        let pages = [page("/zh/posts/hello"), page("/posts/hello")];
+        let tree = SectionTree::build(&pages, "/zh");
+        assert_eq!(tree.get("posts").unwrap().pages, vec!["/zh/posts/hello"]);
+    }
+
+    #[test]
+    fn test_tree_with_meta() {
+        let pages = [
+            page("/docs/intro"),
+            page("/docs/guides/install"),
+            page("/docs/api/core"),
+        ];
+        let meta: HashMap<String, SectionMeta> = [
+            (
+                "/docs/guides",
+                SectionMeta {
+                    title: Some("User Guide".to_string()),
+                    weight: -1,
+                    ..SectionMeta::default()
+                },
+            ),
+            ("/docs/reference/advanced", SectionMeta::default()),
+            ("/zh/docs", SectionMeta::default()),
+        ]
+        .into_iter()
+        .map(|(url, meta)| (url.to_string(), meta))
+        .collect();
+        let tree = SectionTree::build(&pages, "")
+            .with_meta(meta.iter().filter(|(url, _)| !url.starts_with("/zh/")), "");
+
+        assert_eq!(
+            tree.get("docs").unwrap().subsections,
+            vec!["docs/guides", "docs/api", "docs/reference"]
+        );
+        assert_eq!(
+            tree.get("docs/reference").unwrap().subsections,
+            vec!["docs/reference/advanced"]
+        );
+        assert!(tree.get("zh/docs").is_none());
+        assert_eq!(tree.title("docs/guides"), "User Guide");
+        assert_eq!(tree.title("docs/api"), "Api");
+        assert_eq!(
+            subsections_html("", &tree, tree.get("docs").unwrap()).unwrap(),
+            r#"<nav class="subsections" aria-label="Subsections"><ul><li><a href="/docs/guides">User Guide</a></li><li><a href="/docs/api">Api</a></li><li><a href="/docs/reference">Reference</a></li></ul></nav>"#
+        );
+    }
+
+    #[test]
+    fn test_neighbors() {
+        let dated = |url: &str, day: u32| {
+            let mut p = page(url);
+            p.date = chrono::Utc.with_ymd_and_hms(2024, 1, day, 0, 0, 0).single();
+            p
+        };
+        let mut pinned = dated("/posts/pinned", 1);
+        pinned.weight = -1;
+
+        let pages: HashMap<String, Page> = [
+            dated("/posts/new", 3),
+            dated("/posts/old", 1),
+            dated("/posts/mid", 2),
+            pinned,
+            page("/about"),
+        ]
+        .into_iter()
+        .map(|p| (p.url.clone(), p))
+        .collect();
+        let neighbors = SectionTree::build(pages.values(), "").neighbors(&pages);
+
+        assert_eq!(
+            neighbors["/posts/mid"],
+            Neighbors {
+                prev: Some("/posts/old".to_string()),
+                next: Some("/posts/new".to_string()),
+            }
+        );
+        assert_eq!(neighbors["/posts/pinned"].prev, None);
+        assert_eq!(neighbors["/posts/new"].next, None);
+        assert!(!neighbors.contains_key("/about"));
+    }
+
+    #[test]
+    fn test_sidebar() {
+        let mut intro = page("/docs/intro");
+        intro.title = "Intro".to_string();
+        intro.weight = 2;
+        let mut start = page("/docs/start");
+        start.title = "Start".to_string();
+        start.weight = 1;
+        let mut install = page("/docs/guides/install");
+        install.title = "Install".to_string();
+
+        let pages: HashMap<String, Page> = [intro, start, install]
+            .into_iter()
+            .map(|p| (p.url.clone(), p))
+            .collect();
+        let tree = SectionTree::build(pages.values(), "");
+
+        assert_eq!(
+            sidebar_html(&tree, "docs", "/site", "", &pages).unwrap(),
+            r#"<nav class="docs-nav"><a class="sidebar-title" href="/site/docs">Docs</a><ul><li><a href="/site/docs/start">Start</a></li><li><a href="/site/docs/intro">Intro</a></li><li class="sidebar-section"><a href="/site/docs/guides">Guides</a><ul><li><a href="/site/docs/guides/install">Install</a></li></ul></li></ul></nav>"#
+        );
+        assert!(sidebar_html(&tree, "posts", "", "", &pages).is_none());
+    }
+
+    #[test]
+    fn test_site_structure() {
+        let pages: HashMap<String, Page> = ["/zh/docs/intro", "/zh/docs/guides/install", "/about"]
+            .into_iter()
+            .map(|url| (url.to_string(), page(url)))
+            .collect();
+        let tree = SectionTree::build(pages.values(), "/zh");
+        let structure = SiteStructure::build(&tree, &pages, "/blog", "/zh");
+
+        assert_eq!(structure.home, "/blog/zh/");
+        assert_eq!(
+            structure.sections["docs/guides"],
+            SectionEntry {
+                title: "Guides".to_string(),
+                url: "/blog/zh/docs/guides".to_string(),
+            }
+        );
+        assert_eq!(
+            structure.pages.keys().collect::<Vec<_>>(),
+            vec!["/blog/zh/docs/guides/install", "/blog/zh/docs/intro"]
+        );
+        assert_eq!(
+            serde_json::to_value(&structure).unwrap()["pages"]["/blog/zh/docs/intro"],
+            "/zh/docs/intro"
+        );
+    }
+
+    #[test]
+    fn test_breadcrumbs() {
+        assert_eq!(
+            breadcrumbs_html("/blog", "docs/getting-started", None, section_title),
+            r#"<nav class="breadcrumbs" aria-label="Breadcrumb"><ol><li><a href="/blog/">Home</a></li><li><a href="/blog/docs">Docs</a></li><li aria-current="page">Getting started</li></ol></nav>"#
+        );
+        assert!(
+            breadcrumbs_html("", "docs", Some("Intro"), section_title).ends_with(
+                r#"<li><a href="/docs">Docs</a></li><li aria-current="page">Intro</li></ol></nav>"#
+            )
+        );
+    }
+}
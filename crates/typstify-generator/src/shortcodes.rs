@@ -0,0 +1,132 @@
+//! Site-defined shortcodes.
+//!
+//! Every `*.html` file in a `shortcodes/` directory of the site's or the
+//! theme's templates defines the shortcode named after it:
+//! `templates/shortcodes/youtube.html` renders `{{< youtube dQw4w9WgXcQ >}}`.
+//! Named arguments are template variables by name and positional ones by
+//! index (`{{ 0 }}`); values are HTML-escaped unless marked `| safe`. Site
+//! shortcodes replace the theme's, and both replace built-in shortcodes of
+//! the same name.
+
+use std::path::Path;
+
+use typstify_parser::{Shortcode, ShortcodeRenderer};
+
+use crate::template::{TemplateContext, TemplateRegistry};
+
+/// Directory (relative to a templates directory) holding shortcode
+/// templates.
+pub const SHORTCODES_DIR: &str = "shortcodes";
+
+/// Shortcodes defined by templates.
+#[derive(Debug, Clone, Default)]
+pub struct ShortcodeTemplates {
+    /// Templates by shortcode name.
+    templates: TemplateRegistry,
+
+    /// Number of templates loaded.
+    count: usize,
+}
+
+impl ShortcodeTemplates {
+    /// Load the shortcode templates of each templates directory in turn,
+    /// later ones replacing earlier ones. Missing directories are skipped.
+    pub fn load<P: AsRef<Path>>(templates_dirs: &[P]) -> std::io::Result<Self> {
+        let mut shortcodes = Self::default();
+        for dir in templates_dirs {
+            shortcodes.count += shortcodes
+                .templates
+                .load_dir(&dir.as_ref().join(SHORTCODES_DIR))?;
+        }
+        Ok(shortcodes)
+    }
+
+    /// Whether no shortcodes are defined.
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.count == 0
+    }
+
+    /// Content hash of the templates, to detect changes between builds.
+    #[must_use]
+    pub fn digest(&self) -> String {
+        self.templates.digest()
+    }
+}
+
+impl ShortcodeRenderer for ShortcodeTemplates {
+    fn render(&self, shortcode: &Shortcode) -> Option<Result<String, String>> {
+        if !self.templates.contains(&shortcode.name) {
+            return None;
+        }
+
+        let mut context = TemplateContext::new();
+        for (index, value) in shortcode.positional.iter().enumerate() {
+            context.insert(index.to_string(), value.as_str());
+        }
+        for (name, value) in &shortcode.named {
+            context.insert(name.as_str(), value.as_str());
+        }
+        Some(
+            self.templates
+                .render(&shortcode.name, &context)
+                .map_err(|e| format!("shortcode `{}`: {e}", shortcode.name)),
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+
+    use tempfile::TempDir;
+
+    use super::*;
+
+    #[test]
+    fn test_load_and_render() {
+        let theme = TempDir::new().unwrap();
+        let site = TempDir::new().unwrap();
+        for dir in [theme.path(), site.path()] {
+            fs::create_dir_all(dir.join(SHORTCODES_DIR)).unwrap();
+        }
+        fs::write(
+            theme.path().join("shortcodes/callout.html"),
+            r#"<aside class="theme">{{ 0 }}</aside>"#,
+        )
+        .unwrap();
+        fs::write(
+            theme.path().join("shortcodes/youtube.html"),
+            r#"<iframe src="https://www.youtube-nocookie.com/embed/{{ 0 }}" title="{{ title? }}"></iframe>"#,
+        )
+        .unwrap();
+        fs::write(
+            site.path().join("shortcodes/callout.html"),
+            r#"<aside class="callout {{ kind }}">{{ 0 }}</aside>"#,
+        )
+        .unwrap();
+
+        let shortcodes = ShortcodeTemplates::load(&[theme.path(), site.path()]).unwrap();
+        assert!(!shortcodes.is_empty());
+
+        let render = |inner: &str| shortcodes.render(&Shortcode::parse(inner).unwrap());
+        assert_eq!(
+            render(r#"callout "Mind the <gap>" kind=warning"#),
+            Some(Ok(
+                r#"<aside class="callout warning">Mind the &lt;gap&gt;</aside>"#.to_string()
+            ))
+        );
+        assert_eq!(
+            render("youtube abc123"),
+            Some(Ok(
+                r#"<iframe src="https://www.youtube-nocookie.com/embed/abc123" title=""></iframe>"#
+                    .to_string()
+            ))
+        );
+        assert!(matches!(render("callout text"), Some(Err(_))));
+        assert_eq!(render("video a.mp4"), None);
+
+        let none = ShortcodeTemplates::load(&[theme.path().join("missing")]).unwrap();
+        assert!(none.is_empty());
+    }
+}
@@ -7,7 +7,7 @@ use std::io::Write;
 use chrono::{DateTime, Utc};
 use thiserror::Error;
 use tracing::debug;
-use typstify_core::{Config, Page};
+use typstify_core::{Config, Page, escape_xml};
 
 /// Sitemap generation errors.
 #[derive(Debug, Error)]
@@ -24,6 +24,19 @@ pub enum SitemapError {
 /// Result type for sitemap operations.
 pub type Result<T> = std::result::Result<T, SitemapError>;
 
+/// Maximum number of URLs per sitemap file allowed by the sitemap protocol.
+pub const MAX_URLS_PER_SITEMAP: usize = 50_000;
+
+/// A generated sitemap file.
+#[derive(Debug, Clone)]
+pub struct SitemapFile {
+    /// Path relative to the output root (e.g. `sitemap.xml`, `zh/sitemap.xml`).
+    pub path: String,
+
+    /// XML content.
+    pub xml: String,
+}
+
 /// Change frequency for sitemap entries.
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum ChangeFreq {
@@ -83,13 +96,75 @@ pub struct AlternateLink {
 #[derive(Debug)]
 pub struct SitemapGenerator {
     config: Config,
+    max_urls: usize,
 }
 
 impl SitemapGenerator {
     /// Create a new sitemap generator.
     #[must_use]
     pub fn new(config: Config) -> Self {
-        Self { config }
+        Self {
+            config,
+            max_urls: MAX_URLS_PER_SITEMAP,
+        }
+    }
+
+    /// Set the maximum number of URLs per sitemap file before splitting.
+    #[must_use]
+    pub fn with_max_urls(mut self, max_urls: usize) -> Self {
+        self.max_urls = max_urls.max(1);
+        self
+    }
+
+    /// Generate all sitemap files for the site.
+    ///
+    /// Produces `sitemap.xml` for every page, split into `sitemap-N.xml`
+    /// files referenced from a `sitemap.xml` index when the page count
+    /// exceeds the per-file limit. Multi-language sites additionally get
+    /// `{lang}/sitemap.xml` per language.
+    pub fn generate_files(&self, pages: &[&Page]) -> Result<Vec<SitemapFile>> {
+        let mut files = self.generate_split(pages, "")?;
+
+        let all_languages = self.config.all_languages();
+        if all_languages.len() > 1 {
+            for lang in all_languages {
+                let lang_pages: Vec<_> = pages.iter().filter(|p| p.lang == lang).copied().collect();
+                if lang_pages.is_empty() {
+                    continue;
+                }
+                files.extend(self.generate_split(&lang_pages, &format!("{lang}/"))?);
+            }
+        }
+
+        Ok(files)
+    }
+
+    /// Generate `{dir}sitemap.xml`, splitting it into an index plus
+    /// numbered files when needed.
+    fn generate_split(&self, pages: &[&Page], dir: &str) -> Result<Vec<SitemapFile>> {
+        if pages.len() <= self.max_urls {
+            return Ok(vec![SitemapFile {
+                path: format!("{dir}sitemap.xml"),
+                xml: self.generate(pages)?,
+            }]);
+        }
+
+        let mut files = Vec::new();
+        for (i, chunk) in pages.chunks(self.max_urls).enumerate() {
+            files.push(SitemapFile {
+                path: format!("{dir}sitemap-{}.xml", i + 1),
+                xml: self.generate(chunk)?,
+            });
+        }
+
+        let parts: Vec<&str> = files.iter().map(|f| f.path.as_str()).collect();
+        let index = SitemapFile {
+            path: format!("{dir}sitemap.xml"),
+            xml: self.generate_index(&parts),
+        };
+        files.insert(0, index);
+
+        Ok(files)
     }
 
     /// Generate sitemap XML from pages.
@@ -198,7 +273,7 @@ impl SitemapGenerator {
         for alt in &url.alternates {
             xml.push_str(&format!(
                 r#"    <xhtml:link rel="alternate" hreflang="{}" href="{}" />"#,
-                alt.hreflang,
+                escape_xml(&alt.hreflang),
                 escape_xml(&alt.href)
             ));
             xml.push('\n');
@@ -227,9 +302,8 @@ impl SitemapGenerator {
         for sitemap in sitemaps {
             xml.push_str("  <sitemap>\n");
             xml.push_str(&format!(
-                "    <loc>{}/{}</loc>\n",
-                self.config.base_url(),
-                sitemap
+                "    <loc>{}</loc>\n",
+                escape_xml(&format!("{}/{sitemap}", self.config.base_url()))
             ));
             xml.push_str(&format!("    <lastmod>{now}</lastmod>\n"));
             xml.push_str("  </sitemap>\n");
@@ -240,15 +314,6 @@ impl SitemapGenerator {
     }
 }
 
-/// Escape special XML characters.
-fn escape_xml(s: &str) -> String {
-    s.replace('&', "&amp;")
-        .replace('<', "&lt;")
-        .replace('>', "&gt;")
-        .replace('"', "&quot;")
-        .replace('\'', "&apos;")
-}
-
 /// Generate XSLT stylesheet for sitemap rendering in browsers.
 ///
 /// This creates a modern, clean stylesheet with light/dark mode support
@@ -565,6 +630,7 @@ mod tests {
 
     fn test_config() -> Config {
         Config {
+            theme: None,
             site: typstify_core::config::SiteConfig {
                 title: "Test Site".to_string(),
                 host: "https://example.com".to_string(),
@@ -572,6 +638,7 @@ mod tests {
                 default_language: "en".to_string(),
                 description: None,
                 author: None,
+                timezone: None,
             },
             languages: HashMap::new(),
             build: typstify_core::config::BuildConfig::default(),
@@ -579,6 +646,21 @@ mod tests {
             rss: typstify_core::config::RssConfig::default(),
             robots: typstify_core::config::RobotsConfig::default(),
             taxonomies: typstify_core::config::TaxonomyConfig::default(),
+            dates: typstify_core::config::DatesConfig::default(),
+            css: typstify_core::config::CssConfig::default(),
+            images: typstify_core::config::ImagesConfig::default(),
+            markdown: typstify_core::config::MarkdownConfig::default(),
+            typography: typstify_core::config::TypographyConfig::default(),
+            sections: std::collections::HashMap::new(),
+            check: typstify_core::config::CheckConfig::default(),
+            webmentions: typstify_core::config::WebmentionsConfig::default(),
+            identity: typstify_core::config::IdentityConfig::default(),
+            content: typstify_core::config::ContentConfig::default(),
+            ping: typstify_core::config::PingConfig::default(),
+            toc: typstify_core::config::TocConfig::default(),
+            math: typstify_core::config::MathConfig::default(),
+            mermaid: typstify_core::config::MermaidConfig::default(),
+            fmt: typstify_core::config::FmtConfig::default(),
         }
     }
 
@@ -589,6 +671,7 @@ mod tests {
             description: None,
             date,
             updated: None,
+            expiry_date: None,
             draft: false,
             lang: "en".to_string(),
             is_default_lang: true,
@@ -605,6 +688,13 @@ mod tests {
             aliases: vec![],
             template: None,
             weight: 0,
+            pinned: false,
+            series: None,
+            content_id: None,
+            extra_meta: std::collections::BTreeMap::new(),
+            structured_data: None,
+            images: vec![],
+            event: None,
             source_path: Some(PathBuf::from("test.md")),
         }
     }
@@ -657,6 +747,29 @@ mod tests {
         assert!(xml.contains("sitemap-pages.xml"));
     }
 
+    #[test]
+    fn test_generate_files_split() {
+        let generator = SitemapGenerator::new(test_config()).with_max_urls(2);
+        let owned: Vec<Page> = (0..5).map(|i| test_page(&format!("p{i}"), None)).collect();
+        let pages: Vec<&Page> = owned.iter().collect();
+
+        let files = generator.generate_files(&pages).unwrap();
+        let paths: Vec<&str> = files.iter().map(|f| f.path.as_str()).collect();
+
+        assert_eq!(
+            paths,
+            vec![
+                "sitemap.xml",
+                "sitemap-1.xml",
+                "sitemap-2.xml",
+                "sitemap-3.xml"
+            ]
+        );
+        assert!(files[0].xml.contains("<sitemapindex"));
+        assert!(files[0].xml.contains("https://example.com/sitemap-3.xml"));
+        assert!(files[3].xml.contains("/p4</loc>"));
+    }
+
     #[test]
     fn test_multilang_sitemap() {
         let mut config = test_config();
@@ -664,16 +777,14 @@ mod tests {
             "en".to_string(),
             LanguageConfig {
                 name: Some("English".to_string()),
-                title: None,
-                description: None,
+                ..Default::default()
             },
         );
         config.languages.insert(
             "zh".to_string(),
             LanguageConfig {
                 name: Some("中文".to_string()),
-                title: None,
-                description: None,
+                ..Default::default()
             },
         );
         let generator = SitemapGenerator::new(config);
@@ -686,5 +797,13 @@ mod tests {
         assert!(xml.contains("xmlns:xhtml"));
         assert!(xml.contains(r#"hreflang="en""#));
         assert!(xml.contains(r#"hreflang="zh""#));
+
+        let mut zh_page = test_page("zh/about", None);
+        zh_page.lang = "zh".to_string();
+        let files = generator.generate_files(&[&page, &zh_page]).unwrap();
+        let paths: Vec<&str> = files.iter().map(|f| f.path.as_str()).collect();
+        assert!(paths.contains(&"sitemap.xml"));
+        assert!(paths.contains(&"en/sitemap.xml"));
+        assert!(paths.contains(&"zh/sitemap.xml"));
     }
 }
@@ -0,0 +1,444 @@
+//! Remote content sources.
+//!
+//! Fetches the `[content.remote]` sources (a git repository at a ref, or an
+//! HTTPS tarball) into the build cache and copies them into the content tree
+//! before collection, so documentation maintained in another repository is
+//! published like local content. When a fetch fails the previously cached
+//! copy is used.
+
+use std::{
+    fs,
+    path::{Component, Path, PathBuf},
+    process::Command,
+    time::Duration,
+};
+
+use thiserror::Error;
+use tracing::{debug, warn};
+use typstify_core::config::RemoteContentConfig;
+
+use crate::remote::url_hash;
+
+/// Directory (relative to the build cache) holding fetched sources.
+pub const SOURCES_DIR: &str = "remote";
+
+/// Marker written to every target directory, so it can be replaced on the
+/// next build without touching hand-written content.
+pub const MARKER_FILE: &str = ".typstify-remote";
+
+/// Download timeout for tarballs.
+const TIMEOUT: Duration = Duration::from_secs(120);
+
+/// Largest tarball that will be downloaded.
+const MAX_ARCHIVE_SIZE: u64 = 200 * 1024 * 1024;
+
+/// Remote content errors.
+#[derive(Debug, Error)]
+pub enum SourceError {
+    /// IO error.
+    #[error("IO error: {0}")]
+    Io(#[from] std::io::Error),
+
+    /// `git` failed.
+    #[error("git failed: {0}")]
+    Git(String),
+
+    /// The tarball could not be downloaded.
+    #[error("failed to download {url}: {message}")]
+    Download {
+        /// Tarball URL.
+        url: String,
+        /// Error message.
+        message: String,
+    },
+
+    /// The tarball could not be extracted.
+    #[error("failed to extract archive: {0}")]
+    Archive(String),
+
+    /// `path` does not exist in the source.
+    #[error("{0} not found in remote source")]
+    MissingPath(String),
+
+    /// `target` or `path` is absolute or leaves its directory.
+    #[error("{0} must be a relative path without `..`")]
+    InvalidPath(String),
+
+    /// The target directory holds content that did not come from a remote
+    /// source.
+    #[error("{} already exists and is not remote content", .0.display())]
+    TargetExists(PathBuf),
+}
+
+/// Result type for remote content operations.
+pub type Result<T> = std::result::Result<T, SourceError>;
+
+/// Fetch `source` into `cache_dir` and copy it into `content_dir`. Returns
+/// the number of files copied.
+pub fn sync(source: &RemoteContentConfig, cache_dir: &Path, content_dir: &Path) -> Result<usize> {
+    let target = relative_path(&source.target)?;
+    let root = fetch(source, cache_dir)?;
+    install(&root, &content_dir.join(target), &source.url)
+}
+
+/// Fetch `source` into the cache, falling back to the cached copy when the
+/// fetch fails. Returns the directory to publish (`path` inside the source).
+pub fn fetch(source: &RemoteContentConfig, cache_dir: &Path) -> Result<PathBuf> {
+    let key = url_hash(&format!(
+        "{}#{}",
+        source.url,
+        source.git_ref.as_deref().unwrap_or_default()
+    ));
+    let dir = cache_dir.join(SOURCES_DIR).join(key);
+
+    let fetched = if source.is_archive() {
+        fetch_archive(&source.url, &dir)
+    } else {
+        fetch_git(&source.url, source.git_ref.as_deref(), &dir)
+    };
+    match fetched {
+        Ok(()) => debug!(url = %source.url, dir = %dir.display(), "fetched remote content"),
+        Err(e) if dir.is_dir() => {
+            warn!(url = %source.url, error = %e, "failed to fetch remote content, using cached copy");
+        }
+        Err(e) => return Err(e),
+    }
+
+    let mut root = if source.is_archive() {
+        archive_root(&dir)?
+    } else {
+        dir
+    };
+    if let Some(path) = source.path.as_deref().filter(|p| !p.is_empty()) {
+        root = root.join(relative_path(path)?);
+        if !root.is_dir() {
+            return Err(SourceError::MissingPath(path.to_string()));
+        }
+    }
+    Ok(root)
+}
+
+/// Replace `target` with the files under `root`, skipping `.git`. Returns
+/// the number of files copied.
+///
+/// A non-empty target without the [`MARKER_FILE`] is left alone and
+/// reported as an error.
+pub fn install(root: &Path, target: &Path, url: &str) -> Result<usize> {
+    if target.exists() {
+        let is_remote = target.join(MARKER_FILE).is_file();
+        let is_empty = fs::read_dir(target)?.next().is_none();
+        if !is_remote && !is_empty {
+            return Err(SourceError::TargetExists(target.to_path_buf()));
+        }
+        fs::remove_dir_all(target)?;
+    }
+
+    fs::create_dir_all(target)?;
+    let count = copy_tree(root, target)?;
+    fs::write(target.join(MARKER_FILE), format!("{url}\n"))?;
+    Ok(count)
+}
+
+/// Check out `git_ref` (or the default branch) of `url` in `dir` with a
+/// shallow fetch, reusing the repository from earlier builds.
+fn fetch_git(url: &str, git_ref: Option<&str>, dir: &Path) -> Result<()> {
+    fs::create_dir_all(dir)?;
+    if !dir.join(".git").exists() {
+        git(dir, &["init", "--quiet"])?;
+    }
+    git(
+        dir,
+        &[
+            "fetch",
+            "--quiet",
+            "--depth",
+            "1",
+            "--",
+            url,
+            git_ref.unwrap_or("HEAD"),
+        ],
+    )?;
+    git(
+        dir,
+        &["checkout", "--quiet", "--force", "--detach", "FETCH_HEAD"],
+    )?;
+    git(dir, &["clean", "--quiet", "-ffdx"])
+}
+
+/// `value` as a path if it is relative and stays inside its directory, so
+/// configuration cannot make a source read or replace files elsewhere.
+fn relative_path(value: &str) -> Result<&Path> {
+    let path = Path::new(value);
+    if path
+        .components()
+        .all(|c| matches!(c, Component::Normal(_) | Component::CurDir))
+    {
+        Ok(path)
+    } else {
+        Err(SourceError::InvalidPath(value.to_string()))
+    }
+}
+
+/// Run `git` in `dir`.
+fn git(dir: &Path, args: &[&str]) -> Result<()> {
+    let output = Command::new("git").arg("-C").arg(dir).args(args).output()?;
+    if !output.status.success() {
+        return Err(SourceError::Git(
+            String::from_utf8_lossy(&output.stderr).trim().to_string(),
+        ));
+    }
+    Ok(())
+}
+
+/// Download the tarball at `url` and extract it into `dir`.
+fn fetch_archive(url: &str, dir: &Path) -> Result<()> {
+    let download_error = |e: ureq::Error| SourceError::Download {
+        url: url.to_string(),
+        message: e.to_string(),
+    };
+    let agent: ureq::Agent = ureq::Agent::config_builder()
+        .timeout_global(Some(TIMEOUT))
+        .build()
+        .into();
+    let bytes = agent
+        .get(url)
+        .call()
+        .map_err(download_error)?
+        .body_mut()
+        .with_config()
+        .limit(MAX_ARCHIVE_SIZE)
+        .read_to_vec()
+        .map_err(download_error)?;
+
+    let archive = dir.with_extension("tar.gz");
+    if let Some(parent) = archive.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    fs::write(&archive, bytes)?;
+    let result = extract_archive(&archive, dir);
+    let _ = fs::remove_file(&archive);
+    result
+}
+
+/// Extract a `.tar.gz` archive into `dir`, replacing its contents. The
+/// existing contents are kept if extraction fails.
+fn extract_archive(archive: &Path, dir: &Path) -> Result<()> {
+    let staging = dir.with_extension("partial");
+    if staging.exists() {
+        fs::remove_dir_all(&staging)?;
+    }
+    fs::create_dir_all(&staging)?;
+
+    let output = Command::new("tar")
+        .arg("-xzf")
+        .arg(archive)
+        .arg("-C")
+        .arg(&staging)
+        .output()?;
+    if !output.status.success() {
+        let _ = fs::remove_dir_all(&staging);
+        return Err(SourceError::Archive(
+            String::from_utf8_lossy(&output.stderr).trim().to_string(),
+        ));
+    }
+
+    if dir.exists() {
+        fs::remove_dir_all(dir)?;
+    }
+    fs::rename(&staging, dir)?;
+    Ok(())
+}
+
+/// Root of an extracted archive: its only top-level directory when there is
+/// one (as in GitHub tarballs), otherwise the extraction directory.
+fn archive_root(dir: &Path) -> Result<PathBuf> {
+    let entries: Vec<PathBuf> = fs::read_dir(dir)?
+        .map(|entry| entry.map(|e| e.path()))
+        .collect::<std::io::Result<_>>()?;
+    match entries.as_slice() {
+        [only] if only.is_dir() => Ok(only.clone()),
+        _ => Ok(dir.to_path_buf()),
+    }
+}
+
+/// Copy the files under `source` into `dest`, skipping `.git`.
+fn copy_tree(source: &Path, dest: &Path) -> Result<usize> {
+    let mut count = 0;
+    for entry in fs::read_dir(source)? {
+        let entry = entry?;
+        let name = entry.file_name();
+        if name == ".git" || name == MARKER_FILE {
+            continue;
+        }
+        let path = entry.path();
+        let file_type = entry.file_type()?;
+        if file_type.is_dir() {
+            fs::create_dir_all(dest.join(&name))?;
+            count += copy_tree(&path, &dest.join(&name))?;
+        } else if file_type.is_file() {
+            fs::copy(&path, dest.join(&name))?;
+            count += 1;
+        }
+    }
+    Ok(count)
+}
+
+#[cfg(test)]
+mod tests {
+    use tempfile::TempDir;
+
+    use super::*;
+
+    fn source(url: &str, path: Option<&str>) -> RemoteContentConfig {
+        RemoteContentConfig {
+            url: url.to_string(),
+            git_ref: None,
+            path: path.map(str::to_string),
+            target: "docs".to_string(),
+        }
+    }
+
+    #[test]
+    fn test_install_replaces_remote_content() {
+        let dir = TempDir::new().unwrap();
+        let root = dir.path().join("source");
+        fs::create_dir_all(root.join("guides/.git")).unwrap();
+        fs::write(root.join("intro.md"), "# Intro").unwrap();
+        fs::write(root.join("guides/setup.md"), "# Setup").unwrap();
+        fs::write(root.join("guides/.git/HEAD"), "ref").unwrap();
+
+        let target = dir.path().join("content/docs");
+        fs::create_dir_all(&target).unwrap();
+        fs::write(target.join(MARKER_FILE), "old\n").unwrap();
+        fs::write(target.join("stale.md"), "# Stale").unwrap();
+
+        assert_eq!(
+            install(&root, &target, "https://example.com/x.git").unwrap(),
+            2
+        );
+        assert!(target.join("guides/setup.md").is_file());
+        assert!(!target.join("guides/.git").exists());
+        assert!(!target.join("stale.md").exists());
+        assert_eq!(
+            fs::read_to_string(target.join(MARKER_FILE)).unwrap(),
+            "https://example.com/x.git\n"
+        );
+    }
+
+    #[test]
+    fn test_install_keeps_local_content() {
+        let dir = TempDir::new().unwrap();
+        let target = dir.path().join("docs");
+        fs::create_dir_all(&target).unwrap();
+        fs::write(target.join("mine.md"), "# Mine").unwrap();
+
+        assert!(matches!(
+            install(dir.path(), &target, "https://example.com/x.git"),
+            Err(SourceError::TargetExists(_))
+        ));
+        assert!(target.join("mine.md").is_file());
+    }
+
+    #[test]
+    fn test_fetch_git() {
+        let dir = TempDir::new().unwrap();
+        let repo = dir.path().join("repo");
+        fs::create_dir_all(repo.join("docs")).unwrap();
+        fs::write(repo.join("docs/index.md"), "# Docs").unwrap();
+        fs::write(repo.join("README.md"), "readme").unwrap();
+        for args in [
+            &["init", "--quiet"][..],
+            &["add", "."],
+            &[
+                "-c",
+                "user.name=Test",
+                "-c",
+                "user.email=test@example.com",
+                "commit",
+                "--quiet",
+                "-m",
+                "Add docs",
+            ],
+        ] {
+            git(&repo, args).unwrap();
+        }
+
+        let cache = dir.path().join("cache");
+        let source = source(&repo.to_string_lossy(), Some("docs"));
+        let root = fetch(&source, &cache).unwrap();
+        assert!(root.join("index.md").is_file());
+
+        // A failed fetch falls back to the cached checkout
+        fs::remove_dir_all(&repo).unwrap();
+        assert_eq!(fetch(&source, &cache).unwrap(), root);
+
+        let missing = RemoteContentConfig {
+            path: Some("nope".to_string()),
+            ..source
+        };
+        assert!(matches!(
+            fetch(&missing, &cache),
+            Err(SourceError::MissingPath(_))
+        ));
+
+        let escaping = RemoteContentConfig {
+            path: Some("../..".to_string()),
+            ..missing
+        };
+        assert!(matches!(
+            fetch(&escaping, &cache),
+            Err(SourceError::InvalidPath(_))
+        ));
+    }
+
+    #[test]
+    fn test_sync_rejects_escaping_target() {
+        let dir = TempDir::new().unwrap();
+        for target in ["../outside", "/tmp/outside", "docs/../../outside"] {
+            let source = RemoteContentConfig {
+                target: target.to_string(),
+                ..source("https://example.com/x.git", None)
+            };
+            assert!(matches!(
+                sync(
+                    &source,
+                    &dir.path().join("cache"),
+                    &dir.path().join("content")
+                ),
+                Err(SourceError::InvalidPath(_))
+            ));
+        }
+        assert_eq!(
+            relative_path("guides/./v1").unwrap(),
+            Path::new("guides/./v1")
+        );
+    }
+
+    #[test]
+    fn test_extract_archive() {
+        let dir = TempDir::new().unwrap();
+        let packed = dir.path().join("project-1.0");
+        fs::create_dir_all(packed.join("docs")).unwrap();
+        fs::write(packed.join("docs/index.md"), "# Docs").unwrap();
+        let archive = dir.path().join("project.tar.gz");
+        let status = Command::new("tar")
+            .arg("-czf")
+            .arg(&archive)
+            .arg("-C")
+            .arg(dir.path())
+            .arg("project-1.0")
+            .status()
+            .unwrap();
+        assert!(status.success());
+
+        let out = dir.path().join("out");
+        extract_archive(&archive, &out).unwrap();
+        assert_eq!(archive_root(&out).unwrap(), out.join("project-1.0"));
+        assert!(out.join("project-1.0/docs/index.md").is_file());
+
+        // A broken archive keeps the previous extraction
+        fs::write(&archive, "not a tarball").unwrap();
+        assert!(extract_archive(&archive, &out).is_err());
+        assert!(out.join("project-1.0/docs/index.md").is_file());
+    }
+}
@@ -21,13 +21,21 @@ pub type Result<T> = std::result::Result<T, StaticAssetError>;
 /// Generate static CSS and JS files in the output directory.
 ///
 /// These files are referenced by the HTML templates and cached by browsers.
-pub fn generate_static_assets(output_dir: &Path) -> Result<()> {
+/// `syntax_css` styles highlighted code (see
+/// `typstify_parser::SyntaxHighlighter::css`); `assets/syntax.css` is empty
+/// without it.
+pub fn generate_static_assets(output_dir: &Path, syntax_css: Option<&str>) -> Result<()> {
     // Create assets directory
     let assets_dir = output_dir.join("assets");
     fs::create_dir_all(&assets_dir)?;
 
     // Write CSS file
     fs::write(assets_dir.join("style.css"), DEFAULT_CSS)?;
+    fs::write(
+        assets_dir.join("syntax.css"),
+        syntax_css.unwrap_or_default(),
+    )?;
+    fs::write(assets_dir.join("print.css"), PRINT_CSS)?;
 
     // Write JS file
     fs::write(assets_dir.join("main.js"), DEFAULT_JS)?;
@@ -507,6 +515,33 @@ pre code {
     line-height: 1.6;
 }
 
+/* Code block lines (line numbers, highlighted lines) */
+pre .line {
+    display: inline-block;
+    min-width: 100%;
+}
+
+pre .line.highlighted {
+    background-color: rgba(127, 127, 127, 0.18);
+    box-shadow: inset 3px 0 var(--color-primary);
+}
+
+pre.line-numbers code {
+    counter-reset: code-line;
+}
+
+pre.line-numbers .line::before {
+    counter-increment: code-line;
+    content: counter(code-line);
+    display: inline-block;
+    width: 2.5em;
+    margin-right: 1em;
+    text-align: right;
+    color: var(--color-text-secondary);
+    opacity: 0.6;
+    user-select: none;
+}
+
 /* Article */
 article.post header,
 article.page h1 {
@@ -542,6 +577,46 @@ article .content blockquote {
     font-style: italic;
 }
 
+/* Mermaid diagrams */
+article .content pre.mermaid,
+article .content .mermaid-diagram {
+    margin: 1.5rem 0;
+    background: none;
+    border: none;
+    text-align: center;
+}
+
+article .content .mermaid-diagram svg {
+    max-width: 100%;
+    height: auto;
+}
+
+/* Admonitions */
+.admonition {
+    --admonition-color: #3B82F6;
+    margin: 1.5rem 0;
+    padding: 0.75rem 1rem;
+    border-left: 4px solid var(--admonition-color);
+    border-radius: 0.375rem;
+    background-color: color-mix(in srgb, var(--admonition-color) 8%, transparent);
+}
+
+.admonition > :last-child {
+    margin-bottom: 0;
+}
+
+.admonition-title {
+    margin: 0 0 0.5rem;
+    font-weight: 600;
+    color: var(--admonition-color);
+}
+
+.admonition-tip { --admonition-color: #10B981; }
+.admonition-important { --admonition-color: #8B5CF6; }
+.admonition-warning { --admonition-color: #F59E0B; }
+.admonition-caution,
+.admonition-danger { --admonition-color: #EF4444; }
+
 article .content ul,
 article .content ol {
     margin: 1rem 0 1.5rem 1.5rem;
@@ -551,6 +626,374 @@ article .content li {
     margin-bottom: 0.5rem;
 }
 
+/* Table of contents */
+.toc {
+    margin: 0 0 2rem;
+    padding: 1rem 1.25rem;
+    border-left: 3px solid var(--color-border);
+    font-size: 0.9rem;
+}
+
+.toc ul {
+    margin: 0;
+    padding-left: 1rem;
+    list-style: none;
+}
+
+.toc > ul {
+    padding-left: 0;
+}
+
+.toc li {
+    margin: 0.25rem 0;
+}
+
+.toc a {
+    color: var(--color-text-secondary);
+}
+
+/* Print button */
+.print-button {
+    display: inline-flex;
+    align-items: center;
+    gap: 0.375rem;
+    margin-top: 0.75rem;
+    padding: 0.25rem 0.75rem;
+    border: 1px solid var(--color-border);
+    border-radius: 0.375rem;
+    background: none;
+    color: var(--color-text-secondary);
+    font: inherit;
+    font-size: 0.875rem;
+    cursor: pointer;
+}
+
+.print-button:hover {
+    color: var(--color-text);
+}
+
+/* Backlinks */
+.backlinks {
+    margin-top: 3rem;
+    padding-top: 1.5rem;
+    border-top: 1px solid var(--color-border);
+}
+
+.backlinks h2 {
+    margin: 0 0 0.75rem;
+    font-size: 1rem;
+    color: var(--color-text-secondary);
+}
+
+.backlinks ul {
+    margin: 0;
+    padding-left: 1.25rem;
+}
+
+/* Previous/next post navigation */
+.post-nav {
+    display: grid;
+    grid-template-columns: 1fr 1fr;
+    gap: 1rem;
+    margin-top: 3rem;
+}
+
+.post-nav:empty {
+    display: none;
+}
+
+.post-nav a {
+    display: flex;
+    flex-direction: column;
+    gap: 0.25rem;
+    padding: 0.75rem 1rem;
+    border: 1px solid var(--color-border);
+    border-radius: 0.5rem;
+    color: var(--color-text);
+}
+
+.post-nav a:hover {
+    border-color: var(--color-primary);
+}
+
+.post-nav span {
+    font-size: 0.8125rem;
+    color: var(--color-text-secondary);
+}
+
+.post-nav-next {
+    grid-column: 2;
+    text-align: right;
+}
+
+/* Webmentions */
+.webmentions {
+    margin-top: 3rem;
+    padding-top: 1.5rem;
+    border-top: 1px solid var(--color-border);
+}
+
+.webmentions h2 {
+    font-size: 1.125rem;
+    margin: 0 0 0.75rem;
+}
+
+.webmention-counts {
+    margin: 0 0 0.5rem;
+    font-size: 0.875rem;
+    color: var(--color-text-secondary);
+}
+
+.webmention-faces {
+    display: flex;
+    flex-wrap: wrap;
+    gap: 0.25rem;
+    margin: 0 0 1rem;
+    padding: 0;
+    list-style: none;
+}
+
+.webmentions img {
+    width: 2rem;
+    height: 2rem;
+    border-radius: 50%;
+    object-fit: cover;
+    vertical-align: middle;
+}
+
+.webmention-replies {
+    margin: 0;
+    padding: 0;
+    list-style: none;
+}
+
+.webmention-reply {
+    padding: 0.75rem 0;
+    border-bottom: 1px solid var(--color-border);
+}
+
+.webmention-reply .webmention-author span {
+    margin-left: 0.5rem;
+    font-weight: 600;
+}
+
+.webmention-source {
+    margin-left: 0.5rem;
+    font-size: 0.8125rem;
+    color: var(--color-text-secondary);
+}
+
+.webmention-reply p {
+    margin: 0.5rem 0 0;
+}
+
+/* Video and audio shortcodes */
+.media {
+    margin: 1.5rem 0;
+}
+
+.media video,
+.media audio {
+    display: block;
+    width: 100%;
+}
+
+.media video {
+    height: auto;
+    border-radius: 0.5rem;
+    background: #000;
+}
+
+.media figcaption {
+    margin-top: 0.5rem;
+    font-size: 0.875rem;
+    text-align: center;
+    color: var(--color-text-secondary);
+}
+
+/* Breadcrumbs and subsections */
+.breadcrumbs ol {
+    display: flex;
+    flex-wrap: wrap;
+    gap: 0.25rem;
+    margin: 0 0 1rem;
+    padding: 0;
+    font-size: 0.875rem;
+    list-style: none;
+    color: var(--color-text-secondary);
+}
+
+.breadcrumbs li + li::before {
+    content: "›";
+    margin-right: 0.25rem;
+}
+
+.subsections ul {
+    display: flex;
+    flex-wrap: wrap;
+    gap: 0.5rem;
+    margin: 0 0 1.5rem;
+    padding: 0;
+    list-style: none;
+}
+
+.subsections a {
+    display: inline-block;
+    padding: 0.25rem 0.75rem;
+    border: 1px solid var(--color-border);
+    border-radius: 9999px;
+    font-size: 0.875rem;
+}
+
+/* Docs layout */
+.docs-layout {
+    display: grid;
+    grid-template-columns: 12rem minmax(0, 1fr);
+    gap: 2rem;
+}
+
+.docs-sidebar {
+    position: sticky;
+    top: 5rem;
+    align-self: start;
+    max-height: calc(100vh - 6rem);
+    overflow-y: auto;
+    font-size: 0.875rem;
+}
+
+.docs-nav ul {
+    margin: 0.5rem 0 0;
+    padding: 0;
+    list-style: none;
+}
+
+.docs-nav ul ul {
+    margin: 0.25rem 0 0.5rem;
+    padding-left: 0.75rem;
+    border-left: 1px solid var(--color-border);
+}
+
+.docs-nav li {
+    margin: 0.25rem 0;
+}
+
+.docs-nav a {
+    color: var(--color-text-secondary);
+}
+
+.docs-nav .sidebar-title,
+.docs-nav .sidebar-section > a {
+    font-weight: 600;
+    color: var(--color-text);
+}
+
+.docs-nav a[aria-current="page"] {
+    color: var(--color-primary);
+    font-weight: 600;
+}
+
+/* Hang the sidebar into the left margin on wide screens */
+@media (min-width: 1200px) {
+    .docs-layout { margin-left: -14rem; }
+}
+
+@media (max-width: 900px) {
+    .docs-layout { grid-template-columns: minmax(0, 1fr); }
+    .docs-sidebar {
+        position: static;
+        max-height: none;
+        padding-bottom: 1rem;
+        border-bottom: 1px solid var(--color-border);
+    }
+}
+
+/* Tables */
+.table-wrapper {
+    margin: 1.5rem 0;
+    overflow-x: auto;
+}
+
+.table-wrapper table {
+    width: 100%;
+    border-collapse: collapse;
+    font-size: 0.9375rem;
+}
+
+.table-wrapper caption {
+    caption-side: bottom;
+    padding-top: 0.5rem;
+    font-size: 0.875rem;
+    color: var(--color-text-secondary);
+}
+
+.table-wrapper th,
+.table-wrapper td {
+    padding: 0.5rem 0.75rem;
+    text-align: left;
+    border-bottom: 1px solid var(--color-border);
+}
+
+.table-wrapper th {
+    background: var(--color-bg-secondary);
+}
+
+/* Footnotes */
+.footnote-ref a {
+    text-decoration: none;
+}
+
+.footnote-ref a::before {
+    content: "[";
+}
+
+.footnote-ref a::after {
+    content: "]";
+}
+
+.footnotes {
+    margin-top: 3rem;
+    font-size: 0.875rem;
+    color: var(--color-text-secondary);
+}
+
+.footnotes li p {
+    margin: 0.25rem 0;
+}
+
+.footnote-backref {
+    text-decoration: none;
+}
+
+.footnote-popover {
+    position: absolute;
+    z-index: 100;
+    max-width: min(24rem, calc(100vw - 1rem));
+    padding: 0.5rem 0.75rem;
+    font-size: 0.875rem;
+    background: var(--color-bg);
+    border: 1px solid var(--color-border);
+    border-radius: 0.5rem;
+    box-shadow: 0 4px 12px rgba(0, 0, 0, 0.15);
+}
+
+.footnote-popover p {
+    margin: 0;
+}
+
+/* Draft banner (preview builds only) */
+.draft-banner {
+    margin: 1rem 0;
+    padding: 0.5rem 1rem;
+    font-size: 0.875rem;
+    font-weight: 600;
+    text-align: center;
+    letter-spacing: 0.05em;
+    color: #92400e;
+    background-color: #fef3c7;
+    border: 1px solid #f59e0b;
+    border-radius: 0.5rem;
+}
+
 /* Tags */
 .tags {
     display: flex;
@@ -622,6 +1065,10 @@ article .content li {
     line-height: 1.5;
 }
 
+.post-item.pinned .post-title::before {
+    content: "📌 ";
+}
+
 /* Shorts - Minimalist Layout */
 .shorts-section {
     margin: 2rem 0;
@@ -646,32 +1093,83 @@ article .content li {
     border-bottom: 1px solid var(--color-border);
 }
 
-.short-item:last-child {
-    border-bottom: none;
+.short-item:last-child {
+    border-bottom: none;
+}
+
+.short-date {
+    display: block;
+    font-size: 0.875rem;
+    color: var(--color-text-muted);
+    margin-bottom: 0.25rem;
+}
+
+.short-content {
+    font-size: 1rem;
+    color: var(--color-text-secondary);
+    line-height: 1.7;
+    white-space: pre-wrap;
+    word-wrap: break-word;
+}
+
+.short-content a {
+    color: var(--color-primary);
+    text-decoration: none;
+}
+
+.short-content a:hover {
+    text-decoration: underline;
+}
+
+.short-gallery {
+    display: grid;
+    grid-template-columns: repeat(auto-fill, minmax(8rem, 1fr));
+    gap: 0.375rem;
+    margin-top: 0.75rem;
+}
+
+.short-gallery[data-count="1"] {
+    grid-template-columns: minmax(0, 20rem);
+}
+
+.short-gallery img {
+    display: block;
+    width: 100%;
+    aspect-ratio: 1;
+    object-fit: cover;
+    border-radius: 0.375rem;
+}
+
+.archive-picker {
+    margin-bottom: 1rem;
+    font-size: 0.875rem;
+}
+
+.archive-picker summary {
+    cursor: pointer;
+    color: var(--color-text-secondary);
 }
 
-.short-date {
-    display: block;
-    font-size: 0.875rem;
-    color: var(--color-text-muted);
-    margin-bottom: 0.25rem;
+.archive-picker ul {
+    list-style: none;
+    padding: 0.5rem 0 0;
+    margin: 0;
+    columns: 10rem;
 }
 
-.short-content {
-    font-size: 1rem;
+.archive-picker a {
     color: var(--color-text-secondary);
-    line-height: 1.7;
-    white-space: pre-wrap;
-    word-wrap: break-word;
+    text-decoration: none;
 }
 
-.short-content a {
+.archive-picker a[aria-current] {
     color: var(--color-primary);
-    text-decoration: none;
+    font-weight: 600;
 }
 
-.short-content a:hover {
-    text-decoration: underline;
+.archive-count {
+    color: var(--color-text-muted);
+    font-variant-numeric: tabular-nums;
 }
 
 /* Pagination */
@@ -847,6 +1345,198 @@ article .content li {
     color: var(--color-primary);
 }
 
+/* Events */
+.event-when,
+.event-location,
+.events-empty {
+    color: var(--color-text-muted);
+}
+
+.events-subscribe {
+    font-size: 0.875rem;
+}
+
+.event-list {
+    list-style: none;
+    padding: 0;
+    margin-bottom: 2rem;
+}
+
+.event-item {
+    padding: 0.5rem 0;
+}
+
+.event-item time {
+    display: inline-block;
+    min-width: 12rem;
+    font-size: 0.8125rem;
+    color: var(--color-text-muted);
+    font-variant-numeric: tabular-nums;
+}
+
+.event-item .event-location::before {
+    content: "· ";
+}
+
+.event-list-past a {
+    color: var(--color-text-muted);
+}
+
+/* GitHub repository cards */
+.github-card {
+    margin: 1.5rem 0;
+    padding: 1rem 1.25rem;
+    border: 1px solid var(--color-border);
+    border-radius: 0.5rem;
+}
+
+.github-card-name {
+    font-weight: 600;
+}
+
+.github-card-description {
+    margin: 0.5rem 0;
+    color: var(--color-text-muted);
+}
+
+.github-card-description:empty,
+.github-card-language:empty {
+    display: none;
+}
+
+.github-card-meta {
+    display: flex;
+    gap: 1rem;
+    margin: 0;
+    font-size: 0.875rem;
+    color: var(--color-text-muted);
+}
+
+/* Galleries */
+.gallery {
+    margin: 1.5rem 0;
+}
+
+.gallery-grid {
+    display: grid;
+    grid-template-columns: repeat(auto-fill, minmax(9rem, 1fr));
+    gap: 0.5rem;
+}
+
+.gallery-grid img {
+    display: block;
+    width: 100%;
+    height: auto;
+    aspect-ratio: 1;
+    object-fit: cover;
+    border-radius: 0.375rem;
+}
+
+.gallery figcaption,
+.lightbox figcaption {
+    margin-top: 0.5rem;
+    font-size: 0.875rem;
+    color: var(--color-text-muted);
+    text-align: center;
+}
+
+.lightbox {
+    max-width: 100vw;
+    max-height: 100vh;
+    padding: 0;
+    border: none;
+    background: transparent;
+}
+
+.lightbox::backdrop {
+    background: rgba(0, 0, 0, 0.85);
+}
+
+.lightbox figure {
+    margin: 0;
+}
+
+.lightbox img {
+    display: block;
+    max-width: 92vw;
+    max-height: 85vh;
+    margin: 0 auto;
+}
+
+.lightbox figcaption {
+    color: #e5e5e5;
+}
+
+.lightbox button {
+    position: fixed;
+    padding: 0.25rem 0.75rem;
+    border: none;
+    background: none;
+    color: #fff;
+    font-size: 2.5rem;
+    line-height: 1;
+    cursor: pointer;
+}
+
+.lightbox-prev {
+    top: 50%;
+    left: 0.5rem;
+}
+
+.lightbox-next {
+    top: 50%;
+    right: 0.5rem;
+}
+
+.lightbox-close {
+    top: 0.5rem;
+    right: 0.5rem;
+}
+
+/* Changelog */
+.changelog h1 {
+    margin-bottom: 2rem;
+}
+
+.changelog-contributors,
+.changelog-month {
+    margin-bottom: 2rem;
+}
+
+.changelog h2 {
+    font-size: 1.25rem;
+    margin-bottom: 0.75rem;
+    color: var(--color-primary);
+}
+
+.changelog ul {
+    list-style: none;
+    padding: 0;
+}
+
+.changelog-contributors ul {
+    display: flex;
+    flex-wrap: wrap;
+    gap: 0.5rem 1rem;
+}
+
+.changelog-month li {
+    padding: 0.5rem 0;
+}
+
+.changelog-date,
+.changelog-count,
+.changelog-author {
+    font-size: 0.8125rem;
+    color: var(--color-text-muted);
+    font-variant-numeric: tabular-nums;
+}
+
+.changelog-pages::before {
+    content: "· ";
+    color: var(--color-text-muted);
+}
+
 /* Section List */
 .section-list h1 {
     margin-bottom: 0.5rem;
@@ -880,6 +1570,87 @@ article .content li {
 }
 "#;
 
+/// Print styles, linked with `media="print"`.
+///
+/// Leaves out the site header and footer, navigation and interactive
+/// controls, prints black on white, and shows the link notes `main.js` adds
+/// before printing.
+pub const PRINT_CSS: &str = r#"/* Print styles */
+@page {
+    margin: 2cm;
+}
+
+body {
+    background: #fff;
+    color: #000;
+    font-size: 11pt;
+    line-height: 1.5;
+}
+
+body > header,
+body > footer,
+.nav-actions,
+.toc,
+.post-nav,
+.pagination,
+.backlinks,
+.docs-sidebar,
+.draft-banner,
+.print-button,
+.footnote-backref,
+.footnote-popover,
+.lightbox {
+    display: none !important;
+}
+
+main .container,
+.docs-layout {
+    display: block;
+    max-width: none;
+    padding: 0;
+}
+
+a {
+    color: #000;
+    text-decoration: underline;
+}
+
+h1, h2, h3, h4 {
+    break-after: avoid;
+}
+
+pre, blockquote, figure, table, img {
+    break-inside: avoid;
+}
+
+pre {
+    white-space: pre-wrap;
+    border: 1px solid #ccc;
+}
+
+img {
+    max-width: 100% !important;
+}
+
+/* Link notes added by main.js */
+.print-link-ref {
+    font-size: 0.75em;
+}
+
+.print-links {
+    display: block !important;
+    margin-top: 2rem;
+    padding-top: 1rem;
+    border-top: 1px solid #ccc;
+    font-size: 9pt;
+    word-break: break-all;
+}
+
+.print-links h2 {
+    font-size: 11pt;
+}
+"#;
+
 /// Default JavaScript for theme toggle, search, and language switcher.
 /// Extracted from inline scripts for better caching.
 pub const DEFAULT_JS: &str = r#"// Prevent duplicate initialization
@@ -925,6 +1696,191 @@ window.addEventListener('beforeunload', () => cleanupController.abort());
     }, { signal });
 })();
 
+// Print: link URLs as numbered notes, light theme
+(function() {
+    let added = [];
+    let theme = null;
+
+    window.addEventListener('beforeprint', () => {
+        const html = document.documentElement;
+        theme = html.getAttribute('data-theme');
+        html.setAttribute('data-theme', 'light');
+
+        document.querySelectorAll('article .content').forEach(content => {
+            const urls = [];
+            content.querySelectorAll('a[href]').forEach(a => {
+                if (!/^https?:/.test(a.href) || a.closest('.footnote-ref') ||
+                    a.textContent.trim() === a.href) return;
+                let n = urls.indexOf(a.href) + 1;
+                if (!n) n = urls.push(a.href);
+                const ref = document.createElement('sup');
+                ref.className = 'print-link-ref';
+                ref.textContent = `[${n}]`;
+                a.after(ref);
+                added.push(ref);
+            });
+            if (!urls.length) return;
+
+            const notes = document.createElement('section');
+            notes.className = 'print-links';
+            notes.hidden = true;
+            notes.innerHTML = '<h2>Links</h2><ol></ol>';
+            const list = notes.querySelector('ol');
+            urls.forEach(url => {
+                const item = document.createElement('li');
+                item.textContent = url;
+                list.appendChild(item);
+            });
+            content.appendChild(notes);
+            added.push(notes);
+        });
+    }, { signal });
+
+    window.addEventListener('afterprint', () => {
+        added.forEach(el => el.remove());
+        added = [];
+        if (theme) document.documentElement.setAttribute('data-theme', theme);
+        else document.documentElement.removeAttribute('data-theme');
+    }, { signal });
+
+    document.querySelectorAll('.print-button').forEach(button => {
+        button.addEventListener('click', () => window.print(), { signal });
+    });
+})();
+
+// Math: KaTeX renders \(...\) and \[...\] in .math elements
+(function() {
+    if (typeof renderMathInElement !== 'function') return;
+    document.querySelectorAll('.math').forEach(el => renderMathInElement(el, {
+        delimiters: [
+            { left: '\\[', right: '\\]', display: true },
+            { left: '\\(', right: '\\)', display: false },
+        ],
+        throwOnError: false,
+    }));
+})();
+
+// Gallery lightbox
+(function() {
+    const items = Array.from(document.querySelectorAll('.gallery-item'));
+    if (!items.length) return;
+
+    const dialog = document.createElement('dialog');
+    dialog.className = 'lightbox';
+    dialog.innerHTML = '<figure><img alt=""><figcaption></figcaption></figure>' +
+        '<button type="button" class="lightbox-prev" aria-label="Previous">&#8249;</button>' +
+        '<button type="button" class="lightbox-next" aria-label="Next">&#8250;</button>' +
+        '<button type="button" class="lightbox-close" aria-label="Close">&times;</button>';
+    document.body.appendChild(dialog);
+    signal.addEventListener('abort', () => dialog.remove());
+
+    const img = dialog.querySelector('img');
+    const caption = dialog.querySelector('figcaption');
+    let group = [];
+    let index = 0;
+
+    function show(i) {
+        index = (i + group.length) % group.length;
+        const item = group[index];
+        img.src = item.href;
+        img.alt = item.querySelector('img')?.alt || '';
+        caption.textContent = item.dataset.caption || '';
+        caption.hidden = !item.dataset.caption;
+    }
+
+    items.forEach(item => {
+        item.addEventListener('click', (e) => {
+            e.preventDefault();
+            group = Array.from(item.closest('.gallery-grid').querySelectorAll('.gallery-item'));
+            show(group.indexOf(item));
+            dialog.showModal();
+        }, { signal });
+    });
+
+    dialog.querySelector('.lightbox-prev').addEventListener('click', () => show(index - 1), { signal });
+    dialog.querySelector('.lightbox-next').addEventListener('click', () => show(index + 1), { signal });
+    dialog.querySelector('.lightbox-close').addEventListener('click', () => dialog.close(), { signal });
+    dialog.addEventListener('click', (e) => {
+        if (e.target === dialog) dialog.close();
+    }, { signal });
+    dialog.addEventListener('keydown', (e) => {
+        if (e.key === 'ArrowLeft') show(index - 1);
+        if (e.key === 'ArrowRight') show(index + 1);
+    }, { signal });
+})();
+
+// Footnote popovers
+(function() {
+    const refs = document.querySelectorAll('.footnote-ref a');
+    if (!refs.length) return;
+
+    const popover = document.createElement('div');
+    popover.className = 'footnote-popover';
+    popover.setAttribute('role', 'tooltip');
+    popover.hidden = true;
+    document.body.appendChild(popover);
+
+    let current = null;
+    let hideTimer = null;
+
+    signal.addEventListener('abort', () => {
+        clearTimeout(hideTimer);
+        popover.remove();
+    });
+
+    function show(ref) {
+        const note = document.getElementById(decodeURIComponent(ref.hash.slice(1)));
+        if (!note) return;
+        clearTimeout(hideTimer);
+
+        const content = note.cloneNode(true);
+        content.querySelectorAll('.footnote-backref').forEach(a => a.remove());
+        popover.innerHTML = content.innerHTML;
+        popover.hidden = false;
+
+        const rect = ref.getBoundingClientRect();
+        const maxLeft = document.documentElement.clientWidth - popover.offsetWidth - 8;
+        const left = Math.max(8, Math.min(rect.left + rect.width / 2 - popover.offsetWidth / 2, maxLeft));
+        popover.style.left = `${left + window.scrollX}px`;
+        popover.style.top = `${rect.bottom + window.scrollY + 6}px`;
+        current = ref;
+    }
+
+    function hide() {
+        popover.hidden = true;
+        current = null;
+    }
+
+    function scheduleHide() {
+        clearTimeout(hideTimer);
+        hideTimer = setTimeout(hide, 200);
+    }
+
+    refs.forEach(ref => {
+        ref.addEventListener('mouseenter', () => show(ref), { signal });
+        ref.addEventListener('mouseleave', scheduleHide, { signal });
+        ref.addEventListener('focus', () => show(ref), { signal });
+        ref.addEventListener('blur', scheduleHide, { signal });
+        // On touch devices a tap toggles the popover instead of jumping
+        ref.addEventListener('click', (e) => {
+            if (!window.matchMedia('(hover: none)').matches) return;
+            e.preventDefault();
+            if (current === ref) hide(); else show(ref);
+        }, { signal });
+    });
+
+    popover.addEventListener('mouseenter', () => clearTimeout(hideTimer), { signal });
+    popover.addEventListener('mouseleave', scheduleHide, { signal });
+
+    document.addEventListener('keydown', (e) => {
+        if (e.key === 'Escape') hide();
+    }, { signal });
+
+    document.addEventListener('click', (e) => {
+        if (current && !popover.contains(e.target) && !current.contains(e.target)) hide();
+    }, { signal });
+})();
+
 // Search functionality
 (function() {
     const wrapper = document.getElementById('searchWrapper');
@@ -933,12 +1889,38 @@ window.addEventListener('beforeunload', () => cleanupController.abort());
     const results = document.getElementById('searchResults');
     if (!wrapper || !btn || !input || !results) return;
 
+    // Search schema the script understands (typstify_search::SCHEMA_VERSION)
+    const SCHEMA_VERSION = 2;
+
     let searchIndex = null;
+    let searchEngine = null;
+    let outdated = false;
     let isLoading = false;
     let debounceTimer = null;
+    let missTimer = null;
+
+    // Where to report queries with no results
+    const missesEl = document.getElementById('search-misses-config');
+    const misses = missesEl ? JSON.parse(missesEl.textContent) : null;
+
+    // Large sites: a chunked index searched by the WASM runtime
+    const engineEl = document.getElementById('search-engine-config');
+    const engineConfig = engineEl ? JSON.parse(engineEl.textContent) : null;
+
+    // Preview deployments: a `#search-key=...` link unlocks the encrypted
+    // index of drafts and protected pages for the session
+    const keyMatch = window.location.hash.match(/^#search-key=(.+)$/);
+    if (keyMatch) {
+        sessionStorage.setItem('typstify-search-key', decodeURIComponent(keyMatch[1]));
+        history.replaceState(null, '', window.location.pathname + window.location.search);
+    }
+    const searchKey = sessionStorage.getItem('typstify-search-key');
 
     // Clear debounce on cleanup
-    signal.addEventListener('abort', () => clearTimeout(debounceTimer));
+    signal.addEventListener('abort', () => {
+        clearTimeout(debounceTimer);
+        clearTimeout(missTimer);
+    });
 
     btn.addEventListener('click', (e) => {
         e.stopPropagation();
@@ -983,16 +1965,36 @@ window.addEventListener('beforeunload', () => cleanupController.abort());
     }, { signal });
 
     async function loadSearchIndex() {
-        if (searchIndex || isLoading) return;
+        if (searchIndex || searchEngine || outdated || isLoading) return;
         isLoading = true;
         try {
+            // Reuse the preloaded URL so the hint's response is used
+            const hint = document.querySelector('link[data-search-index]');
             const pathParts = window.location.pathname.split('/').filter(Boolean);
             const langPrefix = pathParts.length > 0 && pathParts[0].length === 2 ? pathParts[0] : '';
-            const indexPath = langPrefix ? `/${langPrefix}/search-index.json` : '/search-index.json';
-            
+            const indexPath = hint ? hint.href : (langPrefix ? `/${langPrefix}/search-index.json` : '/search-index.json');
+
+            if (searchKey && await loadPreviewIndex(indexPath.replace(/search-index\.json$/, 'search-index.preview.json'))) {
+                isLoading = false;
+                return;
+            }
+
+            if (engineConfig && await loadSearchEngine(engineConfig)) {
+                isLoading = false;
+                return;
+            }
+
+            // Small sites embed the index in the page
+            const inline = document.getElementById('search-index-data');
+            if (inline) {
+                useIndex(JSON.parse(inline.textContent));
+                isLoading = false;
+                return;
+            }
+
             const response = await fetch(indexPath, { signal });
             if (response.ok) {
-                searchIndex = await response.json();
+                useIndex(await response.json());
             }
         } catch (err) {
             if (err.name !== 'AbortError') {
@@ -1002,13 +2004,70 @@ window.addEventListener('beforeunload', () => cleanupController.abort());
         isLoading = false;
     }
 
+    // Open the chunked index with the search runtime, falling back to the
+    // JSON index when the runtime isn't deployed
+    async function loadSearchEngine(config) {
+        try {
+            const runtime = await import(config.runtime);
+            await runtime.default();
+            searchEngine = await runtime.SearchEngine.load(new URL(config.index, window.location.href).href);
+            return true;
+        } catch (err) {
+            console.log('Search runtime not available');
+            return false;
+        }
+    }
+
+    // Decrypt the preview index (typstify_search::EncryptedIndex), falling
+    // back to the public index with a wrong key or no preview index
+    async function loadPreviewIndex(path) {
+        try {
+            const response = await fetch(path, { signal });
+            if (!response.ok) return false;
+            const envelope = await response.json();
+            const bytes = (b64) => Uint8Array.from(atob(b64), c => c.charCodeAt(0));
+            const material = await crypto.subtle.importKey(
+                'raw', new TextEncoder().encode(searchKey), 'PBKDF2', false, ['deriveKey']);
+            const key = await crypto.subtle.deriveKey(
+                { name: 'PBKDF2', hash: 'SHA-256', salt: bytes(envelope.salt), iterations: envelope.iterations },
+                material, { name: 'AES-GCM', length: 256 }, false, ['decrypt']);
+            const json = await crypto.subtle.decrypt(
+                { name: 'AES-GCM', iv: bytes(envelope.nonce) }, key, bytes(envelope.data));
+            useIndex(JSON.parse(new TextDecoder().decode(json)));
+            return true;
+        } catch (err) {
+            if (err.name === 'AbortError') throw err;
+            console.log('Preview search index not available');
+            return false;
+        }
+    }
+
+    // An index from another build (a cached page or index) can't be searched
+    function useIndex(index) {
+        if ((index.schema_version || 0) === SCHEMA_VERSION) {
+            searchIndex = index;
+        } else {
+            outdated = true;
+        }
+    }
+
     function performSearch(query) {
+        if (outdated) {
+            results.innerHTML = '<div class="search-no-results">Search was updated. <a href="">Reload the page</a> to search.</div>';
+            results.classList.add('show');
+            return;
+        }
+        if (searchEngine) {
+            searchChunked(query);
+            return;
+        }
         if (!searchIndex || !searchIndex.documents) {
             results.innerHTML = '<div class="search-no-results">Search is loading...</div>';
             results.classList.add('show');
             return;
         }
 
+        clearTimeout(missTimer);
         const q = query.toLowerCase();
         const matches = searchIndex.documents.filter(doc => {
             const title = doc.title.toLowerCase();
@@ -1019,9 +2078,29 @@ window.addEventListener('beforeunload', () => cleanupController.abort());
             if (terms.some(t => t.includes(q) || q.includes(t))) return true;
             return false;
         }).slice(0, 10);
+        showResults(q, matches);
+    }
+
+    // The runtime cancels a search when the next one starts
+    async function searchChunked(query) {
+        clearTimeout(missTimer);
+        try {
+            const found = await searchEngine.search(query, 10);
+            showResults(query.toLowerCase(), found.results);
+        } catch (err) {
+            if (err.name !== 'AbortError') {
+                console.log('Search failed', err);
+            }
+        }
+    }
 
+    function showResults(q, matches) {
         if (matches.length === 0) {
             results.innerHTML = '<div class="search-no-results">No results found</div>';
+            // Wait until typing settles so prefixes aren't reported
+            if (misses && q.length >= 2) {
+                missTimer = setTimeout(() => reportMiss(q), 1500);
+            }
         } else {
             results.innerHTML = matches.map(doc => 
                 `<a href="${doc.url}" class="search-result-item">
@@ -1033,6 +2112,35 @@ window.addEventListener('beforeunload', () => cleanupController.abort());
         results.classList.add('show');
     }
     
+    function reportMiss(query) {
+        if (misses.local) {
+            try {
+                const key = 'typstify:search-misses';
+                const stored = JSON.parse(localStorage.getItem(key) || '{}');
+                const entry = stored[query] || { count: 0 };
+                entry.count += 1;
+                entry.last = new Date().toISOString();
+                entry.page = window.location.pathname;
+                stored[query] = entry;
+                localStorage.setItem(key, JSON.stringify(stored));
+            } catch (err) {
+                // Storage may be full or disabled
+            }
+        }
+        if (misses.endpoint) {
+            const body = JSON.stringify({
+                query,
+                lang: document.documentElement.lang,
+                page: window.location.pathname
+            });
+            const blob = new Blob([body], { type: 'application/json' });
+            if (!navigator.sendBeacon || !navigator.sendBeacon(misses.endpoint, blob)) {
+                fetch(misses.endpoint, { method: 'POST', body, keepalive: true,
+                    headers: { 'Content-Type': 'application/json' } }).catch(() => {});
+            }
+        }
+    }
+
     function escapeHtml(text) {
         const div = document.createElement('div');
         div.textContent = text;
@@ -1052,7 +2160,7 @@ mod tests {
         let temp_dir = TempDir::new().unwrap();
         let output_dir = temp_dir.path();
 
-        generate_static_assets(output_dir).unwrap();
+        generate_static_assets(output_dir, Some(".hl-code {}")).unwrap();
 
         // Check CSS file exists
         let css_path = output_dir.join("assets/style.css");
@@ -1061,11 +2169,22 @@ mod tests {
         assert!(css_content.contains(":root"));
         assert!(css_content.contains("--color-primary"));
 
+        let syntax_css = std::fs::read_to_string(output_dir.join("assets/syntax.css")).unwrap();
+        assert_eq!(syntax_css, ".hl-code {}");
+        let print_css = std::fs::read_to_string(output_dir.join("assets/print.css")).unwrap();
+        assert!(print_css.contains(".print-links"));
+
         // Check JS file exists
         let js_path = output_dir.join("assets/main.js");
         assert!(js_path.exists());
         let js_content = std::fs::read_to_string(&js_path).unwrap();
         assert!(js_content.contains("theme-toggle"));
         assert!(js_content.contains("searchIndex"));
+        assert!(js_content.contains("footnote-popover"));
+        assert!(js_content.contains("beforeprint"));
+        assert!(js_content.contains(&format!(
+            "const SCHEMA_VERSION = {};",
+            typstify_search::SCHEMA_VERSION
+        )));
     }
 }
@@ -0,0 +1,288 @@
+//! JSON-LD structured data for page heads.
+//!
+//! Every content page describes the site as a `WebSite` and its place in the
+//! section hierarchy as a `BreadcrumbList`; dated pages (posts) are also a
+//! `BlogPosting`. Search engines use these for rich results. A page leaves
+//! them out with `structured_data: false` in its frontmatter.
+
+use serde_json::{Value, json};
+use typstify_core::{Config, Page};
+
+/// The site as a schema.org `WebSite`, in the language `lang`.
+#[must_use]
+pub fn website(config: &Config, lang: &str) -> Value {
+    let mut website = json!({
+        "@context": "https://schema.org",
+        "@type": "WebSite",
+        "name": config.title_for_language(lang),
+        "url": format!("{}{}/", config.base_url(), lang_prefix(config, lang)),
+        "inLanguage": lang,
+    });
+    if let Some(description) = config.description_for_language(lang) {
+        website["description"] = json!(description);
+    }
+    website
+}
+
+/// `page` as a schema.org `BlogPosting`, or `None` unless it is a dated,
+/// non-event page.
+#[must_use]
+pub fn blog_posting(config: &Config, page: &Page) -> Option<Value> {
+    let date = page.date.filter(|_| page.event.is_none())?;
+    let url = format!("{}{}", config.base_url(), page.url);
+
+    let mut posting = json!({
+        "@context": "https://schema.org",
+        "@type": "BlogPosting",
+        "headline": page.title,
+        "url": url,
+        "mainEntityOfPage": { "@type": "WebPage", "@id": url },
+        "datePublished": date.to_rfc3339(),
+        "dateModified": page.updated.unwrap_or(date).to_rfc3339(),
+        "inLanguage": page.lang,
+    });
+    if let Some(description) = page.description.as_ref().or(page.summary.as_ref()) {
+        posting["description"] = json!(description);
+    }
+    if let Some(author) = &config.site.author {
+        let mut person = json!({ "@type": "Person", "name": author });
+        if !config.identity.me.is_empty() {
+            person["sameAs"] = json!(config.identity.me);
+        }
+        posting["author"] = person;
+    }
+    if !page.tags.is_empty() {
+        posting["keywords"] = json!(page.tags);
+    }
+    if let Some(category) = page.categories.first() {
+        posting["articleSection"] = json!(category);
+    }
+    if let Some(words) = page.word_count {
+        posting["wordCount"] = json!(words);
+    }
+    Some(posting)
+}
+
+/// The trail from the home page through the sections containing `page` to
+/// the page itself, as a schema.org `BreadcrumbList`.
+///
+/// `title` gives the display title of a section path, such as
+/// [`section_title`](crate::sections::section_title).
+#[must_use]
+pub fn breadcrumb_list(config: &Config, page: &Page, title: impl Fn(&str) -> String) -> Value {
+    let prefix = lang_prefix(config, &page.lang);
+    let home = format!("{}{prefix}", config.base_url());
+    let path = page
+        .url
+        .strip_prefix(&prefix)
+        .unwrap_or(&page.url)
+        .trim_matches('/');
+
+    let mut items = vec![(String::from("Home"), format!("{home}/"))];
+    let segments: Vec<&str> = path.split('/').filter(|s| !s.is_empty()).collect();
+    if let Some((_, sections)) = segments.split_last() {
+        for i in 0..sections.len() {
+            let section = sections[..=i].join("/");
+            items.push((title(&section), format!("{home}/{section}")));
+        }
+        items.push((page.title.clone(), format!("{home}/{path}")));
+    }
+
+    let elements: Vec<Value> = items
+        .into_iter()
+        .enumerate()
+        .map(|(i, (name, item))| {
+            json!({
+                "@type": "ListItem",
+                "position": i + 1,
+                "name": name,
+                "item": item,
+            })
+        })
+        .collect();
+    json!({
+        "@context": "https://schema.org",
+        "@type": "BreadcrumbList",
+        "itemListElement": elements,
+    })
+}
+
+/// `<script type="application/ld+json">` tags for the head of `page`, or
+/// `None` when its frontmatter turns structured data off.
+///
+/// `title` gives the display title of a section path.
+#[must_use]
+pub fn head_scripts(
+    config: &Config,
+    page: &Page,
+    title: impl Fn(&str) -> String,
+) -> Option<String> {
+    if page.structured_data == Some(false) {
+        return None;
+    }
+
+    let mut data = vec![website(config, &page.lang)];
+    data.extend(blog_posting(config, page));
+    data.push(breadcrumb_list(config, page, title));
+    Some(
+        data.iter()
+            .map(|value| {
+                format!(
+                    r#"<script type="application/ld+json">{}</script>"#,
+                    script_json(value)
+                )
+            })
+            .collect::<Vec<_>>()
+            .join("\n    "),
+    )
+}
+
+/// `value` as JSON that can't end its `<script>` element early.
+fn script_json(value: &Value) -> String {
+    value.to_string().replace("</", r"<\/")
+}
+
+/// URL prefix of the language `lang` (empty for the default language).
+fn lang_prefix(config: &Config, lang: &str) -> String {
+    if lang == config.site.default_language {
+        String::new()
+    } else {
+        format!("/{lang}")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::Path;
+
+    use chrono::{TimeZone, Utc};
+    use typstify_core::{
+        content::{ContentPath, ParsedContent},
+        frontmatter::Frontmatter,
+    };
+
+    use super::*;
+    use crate::sections::section_title;
+
+    fn config() -> Config {
+        toml::from_str(
+            r#"
+[site]
+title = "Test"
+host = "https://example.com"
+base_path = "/blog"
+author = "Jane"
+description = "Notes"
+
+[languages.zh]
+name = "中文"
+title = "测试"
+
+[identity]
+me = ["https://github.com/jane"]
+"#,
+        )
+        .unwrap()
+    }
+
+    fn page(path: &str, frontmatter: Frontmatter) -> Page {
+        Page::from_parsed(
+            ParsedContent {
+                frontmatter,
+                html: "<p>Hello</p>".to_string(),
+                raw: "Hello".to_string(),
+                toc: vec![],
+                dependencies: vec![],
+            },
+            &ContentPath::from_path(Path::new(path), "en").unwrap(),
+        )
+    }
+
+    fn post() -> Page {
+        page(
+            "posts/rust/hello.md",
+            Frontmatter {
+                title: "Hello </script>".to_string(),
+                date: Some(Utc.with_ymd_and_hms(2026, 1, 2, 0, 0, 0).unwrap()),
+                tags: vec!["rust".to_string()],
+                ..Frontmatter::default()
+            },
+        )
+    }
+
+    #[test]
+    fn test_blog_posting() {
+        let posting = blog_posting(&config(), &post()).unwrap();
+
+        assert_eq!(posting["@type"], "BlogPosting");
+        assert_eq!(posting["url"], "https://example.com/blog/posts/rust/hello");
+        assert_eq!(posting["datePublished"], "2026-01-02T00:00:00+00:00");
+        assert_eq!(posting["dateModified"], posting["datePublished"]);
+        assert_eq!(posting["author"]["name"], "Jane");
+        assert_eq!(posting["author"]["sameAs"][0], "https://github.com/jane");
+        assert_eq!(posting["keywords"][0], "rust");
+
+        let undated = page("about.md", Frontmatter::default());
+        assert!(blog_posting(&config(), &undated).is_none());
+    }
+
+    #[test]
+    fn test_breadcrumb_list() {
+        let list = breadcrumb_list(&config(), &post(), section_title);
+        let items: Vec<(&str, &str)> = list["itemListElement"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|item| {
+                (
+                    item["name"].as_str().unwrap(),
+                    item["item"].as_str().unwrap(),
+                )
+            })
+            .collect();
+        assert_eq!(
+            items,
+            vec![
+                ("Home", "https://example.com/blog/"),
+                ("Posts", "https://example.com/blog/posts"),
+                ("Rust", "https://example.com/blog/posts/rust"),
+                (
+                    "Hello </script>",
+                    "https://example.com/blog/posts/rust/hello"
+                ),
+            ]
+        );
+        assert_eq!(list["itemListElement"][3]["position"], 4);
+
+        let translated = page("about.zh.md", Frontmatter::default());
+        let list = breadcrumb_list(&config(), &translated, section_title);
+        assert_eq!(
+            list["itemListElement"][0]["item"],
+            "https://example.com/blog/zh/"
+        );
+        assert_eq!(
+            list["itemListElement"][1]["item"],
+            "https://example.com/blog/zh/about"
+        );
+        assert_eq!(website(&config(), "zh")["name"], "测试");
+    }
+
+    #[test]
+    fn test_head_scripts() {
+        let scripts = head_scripts(&config(), &post(), section_title).unwrap();
+        assert_eq!(
+            scripts
+                .matches("<script type=\"application/ld+json\">")
+                .count(),
+            3
+        );
+        assert!(scripts.contains(r#""@type":"WebSite""#));
+        assert!(scripts.contains(r#""description":"Notes""#));
+        assert!(scripts.contains(r"Hello <\/script>"));
+        assert!(!scripts.contains("Hello </script>"));
+
+        let mut off = post();
+        off.structured_data = Some(false);
+        assert!(head_scripts(&config(), &off, section_title).is_none());
+    }
+}
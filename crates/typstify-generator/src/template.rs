@@ -2,10 +2,32 @@
 //!
 //! Provides a lightweight template system using string interpolation rather than
 //! heavy template engines like Tera or Handlebars.
+//!
+//! Interpolated values are HTML-escaped unless marked `{{ name | safe }}`, so
+//! a title containing `<` or `&` can't break the markup. Variables holding
+//! generated HTML (content, navigation, item lists) are marked `safe` in the
+//! built-in templates.
+//!
+//! Besides strings, variables hold booleans, numbers, lists and maps (see
+//! [`TemplateValue`]); `{{ page.title }}` and `{{ pages.0.url }}` reach into
+//! maps and lists.
+//!
+//! With the `minijinja` feature, `*.jinja` templates can be used as well (see
+//! [`crate::jinja`]).
 
-use std::collections::HashMap;
+use std::{
+    collections::{BTreeMap, HashMap},
+    fmt, fs,
+    path::Path,
+};
 
+use serde::Serialize;
 use thiserror::Error;
+use typstify_core::{Page, escape_html};
+
+#[cfg(feature = "minijinja")]
+use crate::jinja::{JINJA_EXTENSION, JinjaTemplates};
+use crate::manifest::content_hash;
 
 /// Template rendering errors.
 #[derive(Debug, Error)]
@@ -21,15 +43,199 @@ pub enum TemplateError {
     /// Invalid template syntax.
     #[error("invalid template syntax: {0}")]
     InvalidSyntax(String),
+
+    /// A Jinja template failed to render.
+    #[error("template rendering failed: {0}")]
+    Render(String),
+
+    /// A list or map where text was expected.
+    #[error("variable `{0}` is a list or map and can't be interpolated")]
+    NotScalar(String),
 }
 
 /// Result type for template operations.
 pub type Result<T> = std::result::Result<T, TemplateError>;
 
+/// A template variable value.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(untagged)]
+pub enum TemplateValue {
+    /// Text.
+    String(String),
+
+    /// Boolean, interpolated as `true` or `false`.
+    Bool(bool),
+
+    /// Integer.
+    Int(i64),
+
+    /// Floating-point number.
+    Float(f64),
+
+    /// List of values, reached by index (`{{ items.0 }}`).
+    List(Vec<TemplateValue>),
+
+    /// Values by name, reached with `.` (`{{ page.title }}`).
+    Map(BTreeMap<String, TemplateValue>),
+}
+
+impl TemplateValue {
+    /// The text of a string value.
+    #[must_use]
+    pub fn as_str(&self) -> Option<&str> {
+        match self {
+            Self::String(s) => Some(s),
+            _ => None,
+        }
+    }
+
+    /// Whether the value counts as true in a condition: non-empty strings,
+    /// lists and maps, non-zero numbers and `true`.
+    #[must_use]
+    pub fn is_truthy(&self) -> bool {
+        match self {
+            Self::String(s) => !s.is_empty(),
+            Self::Bool(b) => *b,
+            Self::Int(n) => *n != 0,
+            Self::Float(n) => *n != 0.0,
+            Self::List(items) => !items.is_empty(),
+            Self::Map(members) => !members.is_empty(),
+        }
+    }
+
+    /// The member at the `.`-separated `path` (map keys and list indexes).
+    #[must_use]
+    pub fn lookup(&self, path: &str) -> Option<&Self> {
+        path.split('.').try_fold(self, |value, key| match value {
+            Self::Map(members) => members.get(key),
+            Self::List(items) => items.get(key.parse::<usize>().ok()?),
+            _ => None,
+        })
+    }
+}
+
+impl fmt::Display for TemplateValue {
+    /// Scalars as text; lists and maps as JSON.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::String(s) => f.write_str(s),
+            Self::Bool(b) => write!(f, "{b}"),
+            Self::Int(n) => write!(f, "{n}"),
+            Self::Float(n) => write!(f, "{n}"),
+            Self::List(_) | Self::Map(_) => {
+                f.write_str(&serde_json::to_string(self).map_err(|_| fmt::Error)?)
+            }
+        }
+    }
+}
+
+impl From<String> for TemplateValue {
+    fn from(value: String) -> Self {
+        Self::String(value)
+    }
+}
+
+impl From<&str> for TemplateValue {
+    fn from(value: &str) -> Self {
+        Self::String(value.to_string())
+    }
+}
+
+impl From<&String> for TemplateValue {
+    fn from(value: &String) -> Self {
+        Self::String(value.clone())
+    }
+}
+
+impl From<bool> for TemplateValue {
+    fn from(value: bool) -> Self {
+        Self::Bool(value)
+    }
+}
+
+impl From<i64> for TemplateValue {
+    fn from(value: i64) -> Self {
+        Self::Int(value)
+    }
+}
+
+impl From<u32> for TemplateValue {
+    fn from(value: u32) -> Self {
+        Self::Int(i64::from(value))
+    }
+}
+
+impl From<usize> for TemplateValue {
+    fn from(value: usize) -> Self {
+        Self::Int(i64::try_from(value).unwrap_or(i64::MAX))
+    }
+}
+
+impl From<f64> for TemplateValue {
+    fn from(value: f64) -> Self {
+        Self::Float(value)
+    }
+}
+
+impl<T: Into<TemplateValue>> From<Vec<T>> for TemplateValue {
+    fn from(values: Vec<T>) -> Self {
+        Self::List(values.into_iter().map(Into::into).collect())
+    }
+}
+
+impl<T: Into<TemplateValue>> From<BTreeMap<String, T>> for TemplateValue {
+    fn from(members: BTreeMap<String, T>) -> Self {
+        Self::Map(
+            members
+                .into_iter()
+                .map(|(key, value)| (key, value.into()))
+                .collect(),
+        )
+    }
+}
+
+impl From<&Page> for TemplateValue {
+    /// A page summary for listings: `url`, `title`, `lang`, `draft`,
+    /// `pinned`, `tags`, `categories` and, when set, `description`,
+    /// `summary`, `date` and `updated` (RFC 3339), `reading_time` and
+    /// `word_count`.
+    fn from(page: &Page) -> Self {
+        let mut members: BTreeMap<String, Self> = BTreeMap::from([
+            ("url".to_string(), page.url.as_str().into()),
+            ("title".to_string(), page.title.as_str().into()),
+            ("lang".to_string(), page.lang.as_str().into()),
+            ("draft".to_string(), page.draft.into()),
+            ("pinned".to_string(), page.pinned.into()),
+            ("tags".to_string(), page.tags.clone().into()),
+            ("categories".to_string(), page.categories.clone().into()),
+        ]);
+        let optional = [
+            ("description", page.description.clone().map(Self::from)),
+            ("summary", page.summary.clone().map(Self::from)),
+            ("date", page.date.map(|d| d.to_rfc3339().into())),
+            ("updated", page.updated.map(|d| d.to_rfc3339().into())),
+            ("reading_time", page.reading_time.map(Self::from)),
+            ("word_count", page.word_count.map(Self::from)),
+        ];
+        for (key, value) in optional {
+            if let Some(value) = value {
+                members.insert(key.to_string(), value);
+            }
+        }
+        Self::Map(members)
+    }
+}
+
+impl From<&&Page> for TemplateValue {
+    fn from(page: &&Page) -> Self {
+        Self::from(*page)
+    }
+}
+
 /// Template context with variables for interpolation.
 #[derive(Debug, Clone, Default)]
 pub struct TemplateContext {
-    variables: HashMap<String, String>,
+    variables: HashMap<String, TemplateValue>,
 }
 
 impl TemplateContext {
@@ -40,20 +246,32 @@ impl TemplateContext {
     }
 
     /// Insert a variable into the context.
-    pub fn insert(&mut self, key: impl Into<String>, value: impl Into<String>) {
+    pub fn insert(&mut self, key: impl Into<String>, value: impl Into<TemplateValue>) {
         self.variables.insert(key.into(), value.into());
     }
 
     /// Create context with initial variables.
-    pub fn with_var(mut self, key: impl Into<String>, value: impl Into<String>) -> Self {
+    #[must_use]
+    pub fn with_var(mut self, key: impl Into<String>, value: impl Into<TemplateValue>) -> Self {
         self.insert(key, value);
         self
     }
 
-    /// Get a variable value.
+    /// Get the text of a string variable.
     #[must_use]
     pub fn get(&self, key: &str) -> Option<&str> {
-        self.variables.get(key).map(String::as_str)
+        self.variables.get(key).and_then(TemplateValue::as_str)
+    }
+
+    /// Get the value at `path`: a variable by name, or a member of one
+    /// (`page.title`, `pages.0.url`).
+    #[must_use]
+    pub fn value(&self, path: &str) -> Option<&TemplateValue> {
+        if let Some(value) = self.variables.get(path) {
+            return Some(value);
+        }
+        let (name, rest) = path.split_once('.')?;
+        self.variables.get(name)?.lookup(rest)
     }
 
     /// Check if a variable exists.
@@ -61,15 +279,37 @@ impl TemplateContext {
     pub fn contains(&self, key: &str) -> bool {
         self.variables.contains_key(key)
     }
+
+    /// Iterate over the variables, in no particular order.
+    pub fn iter(&self) -> impl Iterator<Item = (&str, &TemplateValue)> {
+        self.variables
+            .iter()
+            .map(|(key, value)| (key.as_str(), value))
+    }
+}
+
+/// How interpolated values are escaped.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum EscapeMode {
+    /// HTML-escape values not marked `| safe`.
+    #[default]
+    Html,
+
+    /// Insert every value as-is (for non-HTML output or templates whose
+    /// values are escaped by the caller).
+    None,
 }
 
 /// A simple template that supports variable interpolation.
 ///
-/// Variables are specified as `{{ variable_name }}` in the template string.
+/// Variables are specified as `{{ variable_name }}` in the template string,
+/// `{{ variable_name? }}` when optional, with an optional `| safe` filter to
+/// skip escaping (`{{ content | safe }}`).
 #[derive(Debug, Clone)]
 pub struct Template {
     name: String,
     content: String,
+    escape: EscapeMode,
 }
 
 impl Template {
@@ -79,9 +319,17 @@ impl Template {
         Self {
             name: name.into(),
             content: content.into(),
+            escape: EscapeMode::default(),
         }
     }
 
+    /// Set how interpolated values are escaped.
+    #[must_use]
+    pub fn with_escape(mut self, escape: EscapeMode) -> Self {
+        self.escape = escape;
+        self
+    }
+
     /// Get the template name.
     #[must_use]
     pub fn name(&self) -> &str {
@@ -102,7 +350,20 @@ impl Template {
                 .ok_or_else(|| TemplateError::InvalidSyntax("unclosed {{ delimiter".to_string()))?;
             let end = start + end + 2;
 
-            let var_name = result[start + 2..end - 2].trim();
+            let mut parts = result[start + 2..end - 2].split('|').map(str::trim);
+            let var_name = parts.next().unwrap_or_default();
+            let mut safe = self.escape == EscapeMode::None;
+            for filter in parts {
+                match filter {
+                    "safe" => safe = true,
+                    _ => {
+                        return Err(TemplateError::InvalidSyntax(format!(
+                            "unknown filter `{filter}` in template {}",
+                            self.name
+                        )));
+                    }
+                }
+            }
 
             // Check for optional variable syntax: {{ variable? }}
             let (var_name, optional) = if let Some(stripped) = var_name.strip_suffix('?') {
@@ -111,8 +372,12 @@ impl Template {
                 (var_name, false)
             };
 
-            let value = match context.get(var_name) {
-                Some(v) => v.to_string(),
+            let value = match context.value(var_name) {
+                Some(TemplateValue::List(_) | TemplateValue::Map(_)) => {
+                    return Err(TemplateError::NotScalar(var_name.to_string()));
+                }
+                Some(v) if safe => v.to_string(),
+                Some(v) => escape_html(&v.to_string()),
                 None if optional => String::new(),
                 None => return Err(TemplateError::MissingVariable(var_name.to_string())),
             };
@@ -123,12 +388,264 @@ impl Template {
 
         Ok(result)
     }
+
+    /// Check the template for unclosed `{{`, unknown filters and, given the
+    /// `variables` it is rendered with, unknown and unused variables.
+    #[must_use]
+    pub fn lint(&self, variables: Option<&TemplateVariables>) -> Vec<TemplateIssue> {
+        let source = &self.content;
+        let mut issues = Vec::new();
+        let mut used = Vec::new();
+        let issue = |kind, offset, message| TemplateIssue {
+            kind,
+            offset,
+            line: source[..offset].matches('\n').count() + 1,
+            message,
+        };
+
+        let mut pos = 0;
+        while let Some(start) = source[pos..].find("{{") {
+            let start = pos + start;
+            let Some(end) = source[start..].find("}}") else {
+                issues.push(issue(
+                    LintKind::Unclosed,
+                    start,
+                    "`{{` is never closed".to_string(),
+                ));
+                break;
+            };
+            let end = start + end + 2;
+            pos = end;
+
+            let mut parts = source[start + 2..end - 2].split('|').map(str::trim);
+            let expression = parts.next().unwrap_or_default();
+            for filter in parts.filter(|filter| *filter != "safe") {
+                issues.push(issue(
+                    LintKind::UnknownFilter,
+                    start,
+                    format!("unknown filter `{filter}`"),
+                ));
+            }
+
+            let name = expression.strip_suffix('?').unwrap_or(expression);
+            let root = name.split('.').next().unwrap_or(name);
+            used.push(root);
+            if let Some(variables) = variables
+                && !variables.provided.contains(&root)
+            {
+                issues.push(issue(
+                    LintKind::UnknownVariable,
+                    start,
+                    format!("unknown variable `{name}` for the `{}` template", self.name),
+                ));
+            }
+        }
+
+        if let Some(variables) = variables {
+            for name in variables.expected {
+                if !used.contains(name) {
+                    issues.push(issue(
+                        LintKind::UnusedVariable,
+                        0,
+                        format!("`{name}` is never used, so it is missing from the output"),
+                    ));
+                }
+            }
+        }
+        issues
+    }
+}
+
+/// Category of a [`TemplateIssue`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LintKind {
+    /// `{{` without a matching `}}`; the template can't be rendered.
+    Unclosed,
+    /// Filter other than `safe`; the template can't be rendered.
+    UnknownFilter,
+    /// Variable the template is never rendered with.
+    UnknownVariable,
+    /// Variable holding the main output that the template leaves out.
+    UnusedVariable,
+}
+
+impl LintKind {
+    /// Short identifier used in reports.
+    #[must_use]
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Unclosed => "unclosed",
+            Self::UnknownFilter => "unknown-filter",
+            Self::UnknownVariable => "unknown-variable",
+            Self::UnusedVariable => "unused-variable",
+        }
+    }
+
+    /// Whether the issue makes rendering fail.
+    #[must_use]
+    pub fn is_error(&self) -> bool {
+        matches!(self, Self::Unclosed | Self::UnknownFilter)
+    }
+}
+
+/// A problem found by [`Template::lint`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TemplateIssue {
+    /// Issue category.
+    pub kind: LintKind,
+
+    /// Byte offset in the template source.
+    pub offset: usize,
+
+    /// 1-based line number in the template source.
+    pub line: usize,
+
+    /// Human-readable description.
+    pub message: String,
+}
+
+impl fmt::Display for TemplateIssue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "line {} (offset {}): [{}] {}",
+            self.line,
+            self.offset,
+            self.kind.as_str(),
+            self.message
+        )
+    }
+}
+
+/// The variables a template is rendered with.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TemplateVariables {
+    /// Every variable the template may receive; most are only set when
+    /// they apply, so templates mark them optional.
+    pub provided: &'static [&'static str],
+
+    /// Variables holding the main output, which the template should use.
+    pub expected: &'static [&'static str],
+}
+
+impl TemplateVariables {
+    /// The documented variables of the built-in template `name`, or `None`
+    /// for other templates (custom page layouts, `data` shortcode
+    /// templates), whose variables depend on their use.
+    #[must_use]
+    pub fn for_template(name: &str) -> Option<Self> {
+        let (provided, expected): (&'static [&'static str], &'static [&'static str]) = match name {
+            "base" => (BASE_VARIABLES, &["content"]),
+            "page" | "post" | "event" | "short" => (PAGE_VARIABLES, &["content"]),
+            "docs" => (&["sidebar", "content"], &["sidebar", "content"]),
+            "redirect" => (&["redirect_url", "lang"], &["redirect_url"]),
+            "list" => (&["title", "items", "pagination"], &["items"]),
+            "taxonomy" => (
+                &["taxonomy_name", "term", "items", "pagination"],
+                &["items"],
+            ),
+            "tags_index" | "categories_index" | "archives" | "changelog" => {
+                (&["items"], &["items"])
+            }
+            "events" | "blogroll" => (&["title", "items"], &["items"]),
+            "section" => (
+                &[
+                    "title",
+                    "items",
+                    "breadcrumbs",
+                    "description",
+                    "subsections",
+                    "pagination",
+                ],
+                &["items"],
+            ),
+            "shorts" => (
+                &["title", "items", "description", "pagination", "archive"],
+                &["items"],
+            ),
+            _ => return None,
+        };
+        Some(Self { provided, expected })
+    }
 }
 
+/// Variables of the `base` template.
+const BASE_VARIABLES: &[&str] = &[
+    "url",
+    "lang",
+    "title",
+    "description",
+    "author",
+    "base_path",
+    "site_title",
+    "site_title_suffix",
+    "canonical_url",
+    "content",
+    "year",
+    "nav_home_url",
+    "nav_archives_url",
+    "nav_tags_url",
+    "nav_about_url",
+    "section_nav",
+    "lang_switcher",
+    "hreflang",
+    "feed_links",
+    "webmention_links",
+    "identity_links",
+    "robots_meta",
+    "extra_meta",
+    "structured_data",
+    "math_assets",
+    "mermaid_assets",
+    "custom_css",
+    "custom_js",
+    "draft_banner",
+    "search_hint",
+    "search_data",
+    "search_misses",
+    "search_engine",
+];
+
+/// Variables of page templates (`page`, `post`, `event`, `short`).
+const PAGE_VARIABLES: &[&str] = &[
+    "url",
+    "title",
+    "content",
+    "tags",
+    "tags_html",
+    "categories",
+    "reading_time",
+    "word_count",
+    "date_iso",
+    "date_formatted",
+    "event_start_iso",
+    "event_when",
+    "event_location",
+    "author",
+    "author_initials",
+    "gallery",
+    "toc",
+    "print_button",
+    "backlinks_html",
+    "webmentions",
+    "prev_post",
+    "next_post",
+    "prev_url",
+    "prev_title",
+    "next_url",
+    "next_title",
+    "series_html",
+    "breadcrumbs_html",
+];
+
 /// Registry of templates.
 #[derive(Debug, Clone, Default)]
 pub struct TemplateRegistry {
     templates: HashMap<String, Template>,
+
+    /// Jinja templates, which take precedence over simple ones.
+    #[cfg(feature = "minijinja")]
+    jinja: JinjaTemplates,
 }
 
 impl TemplateRegistry {
@@ -155,8 +672,14 @@ impl TemplateRegistry {
             DEFAULT_CATEGORIES_INDEX_TEMPLATE,
         ));
         self.register(Template::new("archives", DEFAULT_ARCHIVES_TEMPLATE));
+        self.register(Template::new("changelog", DEFAULT_CHANGELOG_TEMPLATE));
+        self.register(Template::new("blogroll", DEFAULT_BLOGROLL_TEMPLATE));
+        self.register(Template::new("event", DEFAULT_EVENT_TEMPLATE));
+        self.register(Template::new("events", DEFAULT_EVENTS_TEMPLATE));
         self.register(Template::new("section", DEFAULT_SECTION_TEMPLATE));
         self.register(Template::new("shorts", DEFAULT_SHORTS_SECTION_TEMPLATE));
+        self.register(Template::new("docs", DEFAULT_DOCS_TEMPLATE));
+        self.register(Template::new("github_card", DEFAULT_GITHUB_CARD_TEMPLATE));
     }
 
     /// Register a template.
@@ -164,14 +687,89 @@ impl TemplateRegistry {
         self.templates.insert(template.name.clone(), template);
     }
 
+    /// Register every `*.html` file in `dir` as a template named after the
+    /// file (`post.html` → `post`), replacing templates of the same name,
+    /// and with the `minijinja` feature every `*.jinja` file as a Jinja
+    /// template. Returns the number of templates loaded; a missing `dir`
+    /// loads none.
+    pub fn load_dir(&mut self, dir: &Path) -> std::io::Result<usize> {
+        if !dir.is_dir() {
+            return Ok(0);
+        }
+
+        let mut files: Vec<_> = fs::read_dir(dir)?
+            .filter_map(|entry| entry.ok().map(|e| e.path()))
+            .filter(|path| path.is_file() && path.extension().is_some_and(is_template_extension))
+            .collect();
+        files.sort();
+
+        for path in &files {
+            let Some(name) = path.file_stem().and_then(|stem| stem.to_str()) else {
+                continue;
+            };
+            let source = fs::read_to_string(path)?;
+            #[cfg(feature = "minijinja")]
+            if path.extension().is_some_and(|ext| ext == JINJA_EXTENSION) {
+                self.jinja
+                    .add(&format!("{name}.{JINJA_EXTENSION}"), source)
+                    .map_err(|e| {
+                        std::io::Error::new(e.kind(), format!("{}: {e}", path.display()))
+                    })?;
+                continue;
+            }
+            self.register(Template::new(name, source));
+        }
+        Ok(files.len())
+    }
+
+    /// Make the site's pages, sections, taxonomies and configuration
+    /// available to Jinja templates.
+    #[cfg(feature = "minijinja")]
+    pub fn set_site(&mut self, config: &typstify_core::Config, content: &crate::SiteContent) {
+        self.jinja.set_site(config, content);
+    }
+
+    /// Content hash of all templates, to detect template changes between
+    /// builds.
+    #[must_use]
+    pub fn digest(&self) -> String {
+        let mut names: Vec<&String> = self.templates.keys().collect();
+        names.sort();
+
+        let mut bytes = Vec::new();
+        for name in names {
+            let template = &self.templates[name];
+            bytes.extend(format!("{name}\0{:?}\0", template.escape).as_bytes());
+            bytes.extend(template.content.as_bytes());
+            bytes.push(0);
+        }
+        #[cfg(feature = "minijinja")]
+        bytes.extend(self.jinja.digest().as_bytes());
+        content_hash(&bytes)
+    }
+
     /// Get a template by name.
     #[must_use]
     pub fn get(&self, name: &str) -> Option<&Template> {
         self.templates.get(name)
     }
 
+    /// Whether there is a template named `name`.
+    #[must_use]
+    pub fn contains(&self, name: &str) -> bool {
+        #[cfg(feature = "minijinja")]
+        if self.jinja.contains(name) {
+            return true;
+        }
+        self.templates.contains_key(name)
+    }
+
     /// Render a named template with the given context.
     pub fn render(&self, name: &str, context: &TemplateContext) -> Result<String> {
+        #[cfg(feature = "minijinja")]
+        if self.jinja.contains(name) {
+            return self.jinja.render(name, context);
+        }
         let template = self
             .get(name)
             .ok_or_else(|| TemplateError::NotFound(name.to_string()))?;
@@ -179,6 +777,15 @@ impl TemplateRegistry {
     }
 }
 
+/// Whether files with the extension `ext` are loaded as templates.
+fn is_template_extension(ext: &std::ffi::OsStr) -> bool {
+    #[cfg(feature = "minijinja")]
+    if ext == JINJA_EXTENSION {
+        return true;
+    }
+    ext == "html"
+}
+
 /// Default base HTML template.
 /// Uses external CSS and JS files for better caching and smaller HTML files.
 pub const DEFAULT_BASE_TEMPLATE: &str = r##"<!DOCTYPE html>
@@ -189,13 +796,24 @@ pub const DEFAULT_BASE_TEMPLATE: &str = r##"<!DOCTYPE html>
     <title>{{ title }}{{ site_title_suffix? }}</title>
     <meta name="description" content="{{ description? }}">
     <meta name="author" content="{{ author? }}">
+    {{ robots_meta? | safe }}
+    {{ extra_meta? | safe }}
+    {{ structured_data? | safe }}
     <link rel="canonical" href="{{ canonical_url }}">
-    {{ hreflang? }}
+    {{ hreflang? | safe }}
+    {{ feed_links? | safe }}
+    {{ webmention_links? | safe }}
+    {{ identity_links? | safe }}
+    {{ search_hint? | safe }}
     <link rel="preconnect" href="https://fonts.googleapis.com">
     <link rel="preconnect" href="https://fonts.gstatic.com" crossorigin>
     <link href="https://fonts.googleapis.com/css2?family=Inter:wght@300;400;500;600;700&display=swap" rel="stylesheet">
     <link rel="stylesheet" href="{{ base_path }}/assets/style.css">
-    {{ custom_css? }}
+    <link rel="stylesheet" href="{{ base_path }}/assets/syntax.css">
+    <link rel="stylesheet" href="{{ base_path }}/assets/print.css" media="print">
+    {{ math_assets? | safe }}
+    {{ mermaid_assets? | safe }}
+    {{ custom_css? | safe }}
     <script>
         // Inline critical JS to prevent FOUC (Flash of Unstyled Content)
         (function() {
@@ -211,7 +829,7 @@ pub const DEFAULT_BASE_TEMPLATE: &str = r##"<!DOCTYPE html>
             <nav>
                 <a href="{{ nav_home_url }}" class="site-title">{{ site_title }}</a>
                 <div class="nav-links">
-                    {{ section_nav? }}
+                    {{ section_nav? | safe }}
                     <a href="{{ nav_archives_url }}">Archives</a>
                     <a href="{{ nav_tags_url }}">Tags</a>
                     <a href="{{ nav_about_url }}">About</a>
@@ -225,7 +843,7 @@ pub const DEFAULT_BASE_TEMPLATE: &str = r##"<!DOCTYPE html>
                             </button>
                             <div class="search-results" id="searchResults"></div>
                         </div>
-                        {{ lang_switcher? }}
+                        {{ lang_switcher? | safe }}
                         <button class="theme-toggle" aria-label="Toggle theme" type="button">
                             <svg class="icon-sun" xmlns="http://www.w3.org/2000/svg" fill="none" viewBox="0 0 24 24" stroke="currentColor" stroke-width="2">
                                 <path stroke-linecap="round" stroke-linejoin="round" d="M12 3v1m0 16v1m9-9h-1M4 12H3m15.364 6.364l-.707-.707M6.343 6.343l-.707-.707m12.728 0l-.707.707M6.343 17.657l-.707.707M16 12a4 4 0 11-8 0 4 4 0 018 0z" />
@@ -241,7 +859,8 @@ pub const DEFAULT_BASE_TEMPLATE: &str = r##"<!DOCTYPE html>
     </header>
     <main>
         <div class="container">
-            {{ content }}
+            {{ draft_banner? | safe }}
+            {{ content | safe }}
         </div>
     </main>
     <footer>
@@ -249,52 +868,87 @@ pub const DEFAULT_BASE_TEMPLATE: &str = r##"<!DOCTYPE html>
             <p>&copy; {{ year }} {{ site_title }}. Built with <a href="https://github.com/longcipher/typstify">Typstify</a>.</p>
         </div>
     </footer>
+    {{ search_data? | safe }}
+    {{ search_misses? | safe }}
+    {{ search_engine? | safe }}
     <script src="{{ base_path }}/assets/main.js" defer></script>
-    {{ custom_js? }}
+    {{ custom_js? | safe }}
 </body>
 </html>"##;
 
 /// Default page template (for standalone pages).
 pub const DEFAULT_PAGE_TEMPLATE: &str = r#"<article class="page">
+    {{ breadcrumbs_html? | safe }}
     <h1>{{ title }}</h1>
+    {{ print_button? | safe }}
+    {{ toc? | safe }}
     <div class="content">
-        {{ content }}
+        {{ content | safe }}
     </div>
+    {{ backlinks_html? | safe }}
 </article>"#;
 
 /// Default post template (for blog posts with metadata).
 pub const DEFAULT_POST_TEMPLATE: &str = r#"<article class="post">
     <header>
+        {{ breadcrumbs_html? | safe }}
         <h1>{{ title }}</h1>
         <time datetime="{{ date_iso }}">{{ date_formatted }}</time>
-        {{ tags_html? }}
+        {{ tags_html? | safe }}
+        {{ print_button? | safe }}
     </header>
+    {{ series_html? | safe }}
+    {{ toc? | safe }}
     <div class="content">
-        {{ content }}
+        {{ content | safe }}
     </div>
+    {{ backlinks_html? | safe }}
+    {{ webmentions? | safe }}
+    <nav class="post-nav" aria-label="Posts">{{ prev_post? | safe }}{{ next_post? | safe }}</nav>
 </article>"#;
 
+/// Default event template (pages with a `start` date).
+pub const DEFAULT_EVENT_TEMPLATE: &str = r#"<article class="event">
+    <header>
+        <h1>{{ title }}</h1>
+        <p class="event-when"><time datetime="{{ event_start_iso }}">{{ event_when }}</time></p>
+        <p class="event-location">{{ event_location? }}</p>
+        {{ tags_html? | safe }}
+    </header>
+    <div class="content">
+        {{ content | safe }}
+    </div>
+    {{ backlinks_html? | safe }}
+    {{ webmentions? | safe }}
+</article>"#;
+
+/// Default events list template (upcoming and past events).
+pub const DEFAULT_EVENTS_TEMPLATE: &str = r#"<section class="events">
+    <h1>{{ title }}</h1>
+    {{ items | safe }}
+</section>"#;
+
 /// Default list template (for index pages).
 pub const DEFAULT_LIST_TEMPLATE: &str = r#"<section class="post-list">
     <h1>{{ title }}</h1>
     <ul>
-        {{ items }}
+        {{ items | safe }}
     </ul>
-    <div class="pagination">{{ pagination? }}</div>
+    <div class="pagination">{{ pagination? | safe }}</div>
 </section>"#;
 
 /// Default taxonomy term template (for tag/category pages).
 pub const DEFAULT_TAXONOMY_TEMPLATE: &str = r#"<section class="taxonomy post-list">
     <h1>{{ taxonomy_name }}: <span>{{ term }}</span></h1>
     <ul>
-        {{ items }}
+        {{ items | safe }}
     </ul>
-    <div class="pagination">{{ pagination? }}</div>
+    <div class="pagination">{{ pagination? | safe }}</div>
 </section>"#;
 
 /// Default redirect template for URL aliases.
 pub const DEFAULT_REDIRECT_TEMPLATE: &str = r#"<!DOCTYPE html>
-<html>
+<html lang="{{ lang }}">
 <head>
     <meta charset="UTF-8">
     <meta http-equiv="refresh" content="0; url={{ redirect_url }}">
@@ -310,7 +964,7 @@ pub const DEFAULT_REDIRECT_TEMPLATE: &str = r#"<!DOCTYPE html>
 pub const DEFAULT_TAGS_INDEX_TEMPLATE: &str = r#"<section class="taxonomy-index">
     <h1>Tags</h1>
     <div class="tags-cloud">
-        {{ items }}
+        {{ items | safe }}
     </div>
 </section>"#;
 
@@ -318,44 +972,82 @@ pub const DEFAULT_TAGS_INDEX_TEMPLATE: &str = r#"<section class="taxonomy-index"
 pub const DEFAULT_CATEGORIES_INDEX_TEMPLATE: &str = r#"<section class="taxonomy-index">
     <h1>Categories</h1>
     <ul class="categories-list">
-        {{ items }}
+        {{ items | safe }}
     </ul>
 </section>"#;
 
 /// Default archives template (lists all posts grouped by year).
 pub const DEFAULT_ARCHIVES_TEMPLATE: &str = r#"<section class="archives">
     <h1>Archives</h1>
-    {{ items }}
+    {{ items | safe }}
+</section>"#;
+
+/// Default changelog template (content commits grouped by month).
+pub const DEFAULT_CHANGELOG_TEMPLATE: &str = r#"<section class="changelog">
+    <h1>Changelog</h1>
+    {{ items | safe }}
+</section>"#;
+
+/// Default blogroll template (listed sites grouped by category).
+pub const DEFAULT_BLOGROLL_TEMPLATE: &str = r#"<section class="blogroll">
+    <h1>{{ title }}</h1>
+    {{ items | safe }}
 </section>"#;
 
 /// Default section template (lists all posts in a section).
 pub const DEFAULT_SECTION_TEMPLATE: &str = r#"<section class="section-list post-list">
+    {{ breadcrumbs? | safe }}
     <h1>{{ title }}</h1>
     <p class="section-description">{{ description? }}</p>
+    {{ subsections? | safe }}
     <ul>
-        {{ items }}
+        {{ items | safe }}
     </ul>
-    <div class="pagination">{{ pagination? }}</div>
+    <div class="pagination">{{ pagination? | safe }}</div>
 </section>"#;
 
 /// Default short template (minimalist layout).
 pub const DEFAULT_SHORT_TEMPLATE: &str = r#"<div class="short-item">
     <time class="short-date" datetime="{{ date_iso }}">{{ date_formatted }}</time>
     <div class="short-content">
-        {{ content }}
+        {{ content | safe }}
     </div>
+    {{ gallery? | safe }}
 </div>"#;
 
 /// Default shorts section template (minimalist layout).
 pub const DEFAULT_SHORTS_SECTION_TEMPLATE: &str = r#"<section class="shorts-section">
     <h1>{{ title }}</h1>
     <p class="section-description">{{ description? }}</p>
+    {{ archive? | safe }}
     <div class="short-list">
-        {{ items }}
+        {{ items | safe }}
     </div>
-    <div class="pagination">{{ pagination? }}</div>
+    <div class="pagination">{{ pagination? | safe }}</div>
 </section>"#;
 
+/// Default docs layout, wrapping page or section content with a sidebar.
+pub const DEFAULT_DOCS_TEMPLATE: &str = r#"<div class="docs-layout">
+    <aside class="docs-sidebar" aria-label="Documentation">
+        {{ sidebar | safe }}
+    </aside>
+    <div class="docs-content">
+        {{ content | safe }}
+    </div>
+</div>"#;
+
+/// Default GitHub repository card template (`github` shortcode), rendered with
+/// the fields of the GitHub API's repository response.
+pub const DEFAULT_GITHUB_CARD_TEMPLATE: &str = r#"<div class="github-card">
+    <a class="github-card-name" href="{{ html_url }}">{{ full_name }}</a>
+    <p class="github-card-description">{{ description? }}</p>
+    <p class="github-card-meta">
+        <span class="github-card-language">{{ language? }}</span>
+        <span class="github-card-stars" title="Stars">★ {{ stargazers_count }}</span>
+        <span class="github-card-forks" title="Forks">⑂ {{ forks_count }}</span>
+    </p>
+</div>"#;
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -407,6 +1099,135 @@ mod tests {
         assert!(matches!(result, Err(TemplateError::MissingVariable(_))));
     }
 
+    #[test]
+    fn test_template_escaping() {
+        let template = Template::new(
+            "test",
+            r#"<h1 title="{{ title }}">{{ title }}</h1>{{ body | safe }}{{ note? | safe }}"#,
+        );
+        let ctx = TemplateContext::new()
+            .with_var("title", r#"Tom & "Jerry" <script>"#)
+            .with_var("body", "<p>Hi</p>");
+
+        assert_eq!(
+            template.render(&ctx).unwrap(),
+            r#"<h1 title="Tom &amp; &quot;Jerry&quot; &lt;script&gt;">Tom &amp; &quot;Jerry&quot; &lt;script&gt;</h1><p>Hi</p>"#
+        );
+
+        let raw = template.clone().with_escape(EscapeMode::None);
+        assert!(
+            raw.render(&ctx)
+                .unwrap()
+                .starts_with(r#"<h1 title="Tom & "Jerry" <script>">"#)
+        );
+
+        let unknown = Template::new("test", "{{ title | upper }}");
+        assert!(matches!(
+            unknown.render(&ctx),
+            Err(TemplateError::InvalidSyntax(_))
+        ));
+    }
+
+    #[test]
+    fn test_typed_values() {
+        let path =
+            typstify_core::ContentPath::from_path(Path::new("posts/hello.md"), "en").unwrap();
+        let page = Page::from_parsed(
+            typstify_core::content::ParsedContent {
+                frontmatter: typstify_core::frontmatter::Frontmatter {
+                    title: "Hello <World>".to_string(),
+                    tags: vec!["rust".to_string()],
+                    ..Default::default()
+                },
+                html: String::new(),
+                raw: "word ".repeat(600),
+                toc: vec![],
+                dependencies: vec![],
+            },
+            &path,
+        );
+        let ctx = TemplateContext::new()
+            .with_var("count", 2usize)
+            .with_var("ratio", 0.5)
+            .with_var("featured", true)
+            .with_var("pages", vec![&page])
+            .with_var("missing.dot", "flat");
+
+        let template = Template::new(
+            "test",
+            "{{ count }} {{ ratio }} {{ featured }} {{ pages.0.title }} {{ pages.0.tags.0 }} \
+             {{ pages.0.reading_time }} {{ pages.1.title? }}{{ missing.dot }}",
+        );
+        assert_eq!(
+            template.render(&ctx).unwrap(),
+            "2 0.5 true Hello &lt;World&gt; rust 3 flat"
+        );
+        assert!(matches!(
+            Template::new("test", "{{ pages }}").render(&ctx),
+            Err(TemplateError::NotScalar(_))
+        ));
+
+        assert_eq!(ctx.get("count"), None);
+        assert_eq!(ctx.value("count"), Some(&TemplateValue::Int(2)));
+        assert!(ctx.value("pages").is_some_and(TemplateValue::is_truthy));
+        assert!(!TemplateValue::from(Vec::<String>::new()).is_truthy());
+        assert_eq!(
+            TemplateValue::from(vec!["a", "b"]).to_string(),
+            r#"["a","b"]"#
+        );
+    }
+
+    #[test]
+    fn test_lint_builtin_templates() {
+        let registry = TemplateRegistry::new();
+        let mut names: Vec<&String> = registry.templates.keys().collect();
+        names.sort();
+        for name in names {
+            let variables = TemplateVariables::for_template(name);
+            assert!(
+                variables.is_some() || name == "github_card",
+                "{name} is undocumented"
+            );
+            assert_eq!(
+                registry.templates[name].lint(variables.as_ref()),
+                vec![],
+                "{name}"
+            );
+        }
+    }
+
+    #[test]
+    fn test_lint() {
+        let template = Template::new(
+            "post",
+            "<h1>{{ title }}</h1>\n{{ subtitle? }} {{ tags.0 | upper }}\n<p>{{ date_iso",
+        );
+        let issues = template.lint(TemplateVariables::for_template("post").as_ref());
+        let kinds: Vec<_> = issues
+            .iter()
+            .map(|issue| (issue.kind, issue.line))
+            .collect();
+        assert_eq!(
+            kinds,
+            vec![
+                (LintKind::UnknownVariable, 2),
+                (LintKind::UnknownFilter, 2),
+                (LintKind::Unclosed, 3),
+                (LintKind::UnusedVariable, 1),
+            ]
+        );
+        assert_eq!(issues[0].offset, 21);
+        assert_eq!(
+            issues[0].to_string(),
+            "line 2 (offset 21): [unknown-variable] unknown variable `subtitle` for the `post` template"
+        );
+        assert!(issues[2].kind.is_error());
+
+        // Without documented variables only the syntax is checked
+        let custom = Template::new("landing", "{{ anything }}");
+        assert!(custom.lint(None).is_empty());
+    }
+
     #[test]
     fn test_template_registry() {
         let registry = TemplateRegistry::new();
@@ -418,6 +1239,43 @@ mod tests {
         assert!(registry.get("nonexistent").is_none());
     }
 
+    #[test]
+    fn test_load_dir() {
+        let dir = tempfile::TempDir::new().unwrap();
+        fs::write(
+            dir.path().join("post.html"),
+            "<main>{{ content | safe }}</main>",
+        )
+        .unwrap();
+        fs::write(dir.path().join("recipe.html"), "<div>{{ title }}</div>").unwrap();
+        fs::write(dir.path().join("notes.txt"), "ignored").unwrap();
+
+        let mut registry = TemplateRegistry::new();
+        assert_eq!(registry.load_dir(dir.path()).unwrap(), 2);
+        assert_eq!(registry.load_dir(&dir.path().join("missing")).unwrap(), 0);
+
+        let ctx = TemplateContext::new()
+            .with_var("content", "<p>Hi</p>")
+            .with_var("title", "Soup");
+        assert_eq!(
+            registry.render("post", &ctx).unwrap(),
+            "<main><p>Hi</p></main>"
+        );
+        assert_eq!(registry.render("recipe", &ctx).unwrap(), "<div>Soup</div>");
+        assert!(registry.get("notes").is_none());
+        assert!(registry.get("base").is_some());
+    }
+
+    #[test]
+    fn test_digest() {
+        let mut registry = TemplateRegistry::new();
+        let digest = registry.digest();
+        assert_eq!(TemplateRegistry::new().digest(), digest);
+
+        registry.register(Template::new("post", "<main>{{ content | safe }}</main>"));
+        assert_ne!(registry.digest(), digest);
+    }
+
     #[test]
     fn test_render_base_template() {
         let registry = TemplateRegistry::new();
@@ -0,0 +1,365 @@
+//! Themes: reusable templates and static assets.
+//!
+//! A theme is a directory in `themes/` holding a `theme.toml`, HTML templates
+//! in `templates/` and static assets in `static/`:
+//!
+//! ```text
+//! themes/paper/
+//! ├── theme.toml
+//! ├── templates/post.html
+//! └── static/assets/style.css
+//! ```
+//!
+//! The `theme` setting selects one. Templates resolve site → theme →
+//! built-in (a site's `templates/post.html` wins over the theme's), and the
+//! theme's assets are published before the site's `static/`, so a site can
+//! override any file of its theme.
+
+use std::{
+    fs,
+    path::{Path, PathBuf},
+    process::Command,
+};
+
+use serde::Deserialize;
+use thiserror::Error;
+
+/// Directory (relative to the site root) holding installed themes.
+pub const THEMES_DIR: &str = "themes";
+
+/// Metadata file every theme has at its root.
+pub const THEME_FILE: &str = "theme.toml";
+
+/// Theme errors.
+#[derive(Debug, Error)]
+pub enum ThemeError {
+    /// IO error.
+    #[error("IO error: {0}")]
+    Io(#[from] std::io::Error),
+
+    /// No theme directory with that name.
+    #[error("theme `{name}` not found in {}", .dir.display())]
+    NotFound {
+        /// Theme name.
+        name: String,
+        /// Themes directory searched.
+        dir: PathBuf,
+    },
+
+    /// Missing or invalid `theme.toml`.
+    #[error("invalid {}: {message}", .path.display())]
+    Metadata {
+        /// Path of the metadata file.
+        path: PathBuf,
+        /// Error message.
+        message: String,
+    },
+
+    /// `git clone` failed.
+    #[error("git clone failed: {0}")]
+    Git(String),
+
+    /// The install directory already exists.
+    #[error("{} already exists", .0.display())]
+    Exists(PathBuf),
+
+    /// No theme name could be derived from the URL.
+    #[error("cannot derive a theme name from {0}")]
+    InvalidUrl(String),
+
+    /// The theme name is not a plain directory name.
+    #[error("invalid theme name: {0:?}")]
+    InvalidName(String),
+}
+
+/// Result type for theme operations.
+pub type Result<T> = std::result::Result<T, ThemeError>;
+
+/// Contents of `theme.toml`.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Deserialize)]
+pub struct ThemeMeta {
+    /// Display name (defaults to the directory name).
+    #[serde(default)]
+    pub name: Option<String>,
+
+    /// Short description.
+    #[serde(default)]
+    pub description: Option<String>,
+
+    /// Theme author.
+    #[serde(default)]
+    pub author: Option<String>,
+
+    /// Theme version.
+    #[serde(default)]
+    pub version: Option<String>,
+
+    /// License identifier.
+    #[serde(default)]
+    pub license: Option<String>,
+
+    /// Homepage or repository URL.
+    #[serde(default)]
+    pub homepage: Option<String>,
+}
+
+/// An installed theme.
+#[derive(Debug, Clone)]
+pub struct Theme {
+    /// Theme directory.
+    root: PathBuf,
+
+    /// Parsed `theme.toml`.
+    meta: ThemeMeta,
+}
+
+impl Theme {
+    /// Load the theme at `root`, which must contain a `theme.toml`.
+    pub fn load(root: impl Into<PathBuf>) -> Result<Self> {
+        let root = root.into();
+        let path = root.join(THEME_FILE);
+        let metadata_error = |message: String| ThemeError::Metadata {
+            path: path.clone(),
+            message,
+        };
+
+        let text = fs::read_to_string(&path).map_err(|e| metadata_error(e.to_string()))?;
+        let meta = toml::from_str(&text).map_err(|e| metadata_error(e.message().to_string()))?;
+        Ok(Self { root, meta })
+    }
+
+    /// Load the theme called `name` from `themes_dir`.
+    pub fn find(themes_dir: &Path, name: &str) -> Result<Self> {
+        let root = themes_dir.join(name);
+        if !root.is_dir() {
+            return Err(ThemeError::NotFound {
+                name: name.to_string(),
+                dir: themes_dir.to_path_buf(),
+            });
+        }
+        Self::load(root)
+    }
+
+    /// Display name: `name` from `theme.toml`, or the directory name.
+    #[must_use]
+    pub fn name(&self) -> &str {
+        self.meta.name.as_deref().unwrap_or_else(|| {
+            self.root
+                .file_name()
+                .and_then(|name| name.to_str())
+                .unwrap_or_default()
+        })
+    }
+
+    /// Parsed `theme.toml`.
+    #[must_use]
+    pub fn meta(&self) -> &ThemeMeta {
+        &self.meta
+    }
+
+    /// Theme directory.
+    #[must_use]
+    pub fn root(&self) -> &Path {
+        &self.root
+    }
+
+    /// Directory of the theme's templates.
+    #[must_use]
+    pub fn templates_dir(&self) -> PathBuf {
+        self.root.join("templates")
+    }
+
+    /// Directory of the theme's static assets.
+    #[must_use]
+    pub fn static_dir(&self) -> PathBuf {
+        self.root.join("static")
+    }
+}
+
+/// Themes installed in `themes_dir`, by directory name. Directories without a
+/// valid `theme.toml` are skipped; a missing `themes_dir` has none.
+pub fn list(themes_dir: &Path) -> Result<Vec<Theme>> {
+    if !themes_dir.is_dir() {
+        return Ok(Vec::new());
+    }
+
+    let mut dirs: Vec<PathBuf> = fs::read_dir(themes_dir)?
+        .filter_map(|entry| entry.ok().map(|e| e.path()))
+        .filter(|path| path.is_dir())
+        .collect();
+    dirs.sort();
+    Ok(dirs
+        .into_iter()
+        .filter_map(|dir| Theme::load(dir).ok())
+        .collect())
+}
+
+/// Clone the theme repository at `url` into `themes_dir`, under `name` or
+/// the repository name. The clone is removed again if it is not a theme.
+pub fn install(url: &str, themes_dir: &Path, name: Option<&str>) -> Result<Theme> {
+    let name = match name {
+        Some(name) if is_valid_name(name) => name.to_string(),
+        Some(name) => return Err(ThemeError::InvalidName(name.to_string())),
+        None => name_from_url(url).ok_or_else(|| ThemeError::InvalidUrl(url.to_string()))?,
+    };
+    let target = themes_dir.join(&name);
+    if target.exists() {
+        return Err(ThemeError::Exists(target));
+    }
+
+    fs::create_dir_all(themes_dir)?;
+    let output = Command::new("git")
+        .args(["clone", "--quiet", "--depth", "1", "--", url])
+        .arg(&target)
+        .output()?;
+    if !output.status.success() {
+        return Err(ThemeError::Git(
+            String::from_utf8_lossy(&output.stderr).trim().to_string(),
+        ));
+    }
+
+    Theme::load(&target).inspect_err(|_| {
+        let _ = fs::remove_dir_all(&target);
+    })
+}
+
+/// Theme name for a repository URL: its last path segment without `.git`
+/// or a `typstify-theme-` prefix.
+#[must_use]
+pub fn name_from_url(url: &str) -> Option<String> {
+    let segment = url
+        .trim_end_matches('/')
+        .rsplit(['/', ':'])
+        .next()?
+        .trim_end_matches(".git");
+    let name = segment.strip_prefix("typstify-theme-").unwrap_or(segment);
+    is_valid_name(name).then(|| name.to_string())
+}
+
+/// Whether `name` can be used as a theme directory name.
+fn is_valid_name(name: &str) -> bool {
+    !name.is_empty() && !name.starts_with('.') && !name.contains(['/', '\\'])
+}
+
+#[cfg(test)]
+mod tests {
+    use tempfile::TempDir;
+
+    use super::*;
+
+    fn write_theme(dir: &Path, meta: &str) {
+        fs::create_dir_all(dir.join("templates")).unwrap();
+        fs::write(dir.join(THEME_FILE), meta).unwrap();
+    }
+
+    #[test]
+    fn test_find_and_list() {
+        let dir = TempDir::new().unwrap();
+        write_theme(
+            &dir.path().join("paper"),
+            "name = \"Paper\"\ndescription = \"A quiet theme\"\nversion = \"1.0.0\"\n",
+        );
+        write_theme(&dir.path().join("plain"), "");
+        fs::create_dir_all(dir.path().join("not-a-theme")).unwrap();
+
+        let paper = Theme::find(dir.path(), "paper").unwrap();
+        assert_eq!(paper.name(), "Paper");
+        assert_eq!(paper.meta().version.as_deref(), Some("1.0.0"));
+        assert_eq!(paper.templates_dir(), dir.path().join("paper/templates"));
+
+        let names: Vec<_> = list(dir.path())
+            .unwrap()
+            .iter()
+            .map(|theme| theme.name().to_string())
+            .collect();
+        assert_eq!(names, vec!["Paper", "plain"]);
+
+        assert!(matches!(
+            Theme::find(dir.path(), "missing"),
+            Err(ThemeError::NotFound { .. })
+        ));
+        assert!(matches!(
+            Theme::find(dir.path(), "not-a-theme"),
+            Err(ThemeError::Metadata { .. })
+        ));
+    }
+
+    #[test]
+    fn test_install() {
+        let dir = TempDir::new().unwrap();
+        let repo = dir.path().join("typstify-theme-paper");
+        write_theme(&repo, "name = \"Paper\"\n");
+        fs::write(repo.join("templates/post.html"), "{{ content | safe }}").unwrap();
+        let git = |args: &[&str]| {
+            let status = Command::new("git")
+                .arg("-C")
+                .arg(&repo)
+                .args(args)
+                .output()
+                .unwrap()
+                .status;
+            assert!(status.success());
+        };
+        git(&["init", "--quiet"]);
+        git(&["add", "."]);
+        git(&[
+            "-c",
+            "user.name=Test",
+            "-c",
+            "user.email=test@example.com",
+            "commit",
+            "--quiet",
+            "-m",
+            "Add theme",
+        ]);
+
+        let themes = dir.path().join("site/themes");
+        let url = repo.to_string_lossy();
+        let theme = install(&url, &themes, None).unwrap();
+        assert_eq!(theme.root(), themes.join("paper"));
+        assert!(theme.templates_dir().join("post.html").is_file());
+
+        assert!(matches!(
+            install(&url, &themes, None),
+            Err(ThemeError::Exists(_))
+        ));
+        assert!(matches!(
+            install(
+                &dir.path().join("nope").to_string_lossy(),
+                &themes,
+                Some("nope")
+            ),
+            Err(ThemeError::Git(_))
+        ));
+        assert!(matches!(
+            install(&url, &themes, Some("../paper")),
+            Err(ThemeError::InvalidName(_))
+        ));
+
+        // A URL is never parsed as a git option
+        let marker = dir.path().join("injected");
+        let injection = format!("--upload-pack=touch {}", marker.display());
+        assert!(matches!(
+            install(&injection, &themes, Some("evil")),
+            Err(ThemeError::Git(_))
+        ));
+        assert!(!marker.exists());
+    }
+
+    #[test]
+    fn test_name_from_url() {
+        assert_eq!(
+            name_from_url("https://github.com/someone/typstify-theme-paper.git").as_deref(),
+            Some("paper")
+        );
+        assert_eq!(
+            name_from_url("git@github.com:someone/minimal").as_deref(),
+            Some("minimal")
+        );
+        assert_eq!(
+            name_from_url("https://example.com/themes/dusk/").as_deref(),
+            Some("dusk")
+        );
+        assert_eq!(name_from_url("https://example.com/.git"), None);
+    }
+}
@@ -0,0 +1,170 @@
+//! URL map of content sources.
+//!
+//! Maps each content file (relative to the content directory) to the URL its
+//! page is published at, the aliases redirecting to it and the URLs of its
+//! translations. Written as `urlmap.json` with `[build] url_map`, it is the
+//! input for generating reverse proxy rules, link checker configuration and
+//! migration scripts. URLs include the site's base path.
+
+use std::collections::BTreeMap;
+
+use serde::{Deserialize, Serialize};
+use typstify_core::{Config, Page};
+
+use crate::collector::SiteContent;
+
+/// File name of the URL map in the output directory.
+pub const URL_MAP_FILE: &str = "urlmap.json";
+
+/// Where a content file is published.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct UrlMapEntry {
+    /// Page URL.
+    pub url: String,
+
+    /// Language code.
+    pub lang: String,
+
+    /// URLs redirecting to the page.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub aliases: Vec<String>,
+
+    /// Language code -> URL of the page's translations.
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub translations: BTreeMap<String, String>,
+}
+
+/// Content file path -> where it is published.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct UrlMap {
+    /// Source path (relative to the content directory) -> entry.
+    pub pages: BTreeMap<String, UrlMapEntry>,
+}
+
+impl UrlMap {
+    /// Map the sources of the pages of `content` that `include` accepts.
+    ///
+    /// Translations are limited to included pages as well.
+    #[must_use]
+    pub fn build(config: &Config, content: &SiteContent, include: impl Fn(&Page) -> bool) -> Self {
+        let base_path = config.base_path();
+        let url = |path: &str| format!("{base_path}{path}");
+
+        let mut pages = BTreeMap::new();
+        for page in content.pages.values().filter(|p| include(p)) {
+            let Some(source) = &page.source_path else {
+                continue;
+            };
+            let translations = content
+                .translations
+                .get(&page.canonical_id)
+                .into_iter()
+                .flatten()
+                .filter_map(|slug| content.pages.get(slug))
+                .filter(|other| other.lang != page.lang && include(other))
+                .map(|other| (other.lang.clone(), url(&other.url)))
+                .collect();
+            pages.insert(
+                source.to_string_lossy().replace('\\', "/"),
+                UrlMapEntry {
+                    url: url(&page.url),
+                    lang: page.lang.clone(),
+                    aliases: page.aliases.iter().map(|alias| url(alias)).collect(),
+                    translations,
+                },
+            );
+        }
+        Self { pages }
+    }
+
+    /// Serialize the map to pretty-printed JSON.
+    pub fn to_json(&self) -> serde_json::Result<String> {
+        serde_json::to_string_pretty(self)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::PathBuf;
+
+    use super::*;
+
+    fn page(url: &str, lang: &str, source: &str) -> Page {
+        Page {
+            url: url.to_string(),
+            title: url.to_string(),
+            description: None,
+            date: None,
+            updated: None,
+            expiry_date: None,
+            draft: false,
+            lang: lang.to_string(),
+            is_default_lang: lang == "en",
+            canonical_id: "posts/hello".to_string(),
+            tags: vec![],
+            categories: vec![],
+            content: String::new(),
+            summary: None,
+            reading_time: None,
+            word_count: None,
+            toc: vec![],
+            custom_js: vec![],
+            custom_css: vec![],
+            aliases: vec![],
+            template: None,
+            weight: 0,
+            pinned: false,
+            series: None,
+            content_id: None,
+            extra_meta: BTreeMap::new(),
+            structured_data: None,
+            images: vec![],
+            event: None,
+            source_path: Some(PathBuf::from(source)),
+        }
+    }
+
+    #[test]
+    fn test_build_url_map() {
+        let config: Config = toml::from_str(
+            "[site]\ntitle = \"Test\"\nhost = \"https://example.com\"\nbase_path = \"/blog\"\n",
+        )
+        .unwrap();
+        let mut content = SiteContent::default();
+        let mut en = page("/posts/hello", "en", "posts/hello.md");
+        en.aliases.push("/old/hello".to_string());
+        let zh = page("/zh/posts/hello", "zh", "posts/hello.zh.md");
+        let mut draft = page("/de/posts/hello", "de", "posts/hello.de.md");
+        draft.draft = true;
+        for page in [en, zh, draft] {
+            content
+                .translations
+                .entry(page.canonical_id.clone())
+                .or_default()
+                .push(page.url.clone());
+            content.pages.insert(page.url.clone(), page);
+        }
+
+        let map = UrlMap::build(&config, &content, |page| !page.draft);
+        assert_eq!(
+            map.pages.keys().collect::<Vec<_>>(),
+            ["posts/hello.md", "posts/hello.zh.md"]
+        );
+        assert_eq!(
+            map.pages["posts/hello.md"],
+            UrlMapEntry {
+                url: "/blog/posts/hello".to_string(),
+                lang: "en".to_string(),
+                aliases: vec!["/blog/old/hello".to_string()],
+                translations: BTreeMap::from([(
+                    "zh".to_string(),
+                    "/blog/zh/posts/hello".to_string()
+                )]),
+            }
+        );
+
+        let json = map.to_json().unwrap();
+        assert!(json.contains("\"posts/hello.zh.md\": {"));
+        assert!(!json.contains("\"aliases\": []"));
+    }
+}
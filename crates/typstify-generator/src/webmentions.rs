@@ -0,0 +1,376 @@
+//! Received webmentions rendered under posts.
+//!
+//! Reads a webmention.io export (the JF2 feed from `/api/mentions.jf2`, or a
+//! plain array of its entries) and renders likes, reposts and replies for
+//! each target page. Mentions are matched by URL path, so `http`/`https` and
+//! trailing-slash differences don't matter.
+
+use std::{collections::HashMap, fs, path::Path};
+
+use serde::Deserialize;
+use thiserror::Error;
+use typstify_core::escape_html;
+
+/// Webmention errors.
+#[derive(Debug, Error)]
+pub enum WebmentionError {
+    /// IO error.
+    #[error("IO error: {0}")]
+    Io(#[from] std::io::Error),
+
+    /// The export is not valid JF2 JSON.
+    #[error("invalid webmention export: {0}")]
+    Json(#[from] serde_json::Error),
+}
+
+/// Result type for webmention operations.
+pub type Result<T> = std::result::Result<T, WebmentionError>;
+
+/// Author of a webmention.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Deserialize)]
+pub struct MentionAuthor {
+    /// Display name.
+    #[serde(default)]
+    pub name: String,
+
+    /// Profile URL.
+    #[serde(default)]
+    pub url: Option<String>,
+
+    /// Avatar URL.
+    #[serde(default)]
+    pub photo: Option<String>,
+}
+
+/// Content of a mention; only the plain text is rendered.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Deserialize)]
+pub struct MentionContent {
+    /// Plain text content.
+    #[serde(default)]
+    pub text: Option<String>,
+}
+
+/// A single received webmention.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+pub struct Webmention {
+    /// URL of the page that was mentioned.
+    #[serde(rename = "wm-target")]
+    pub target: String,
+
+    /// Mention type: `in-reply-to`, `like-of`, `repost-of`, `bookmark-of`
+    /// or `mention-of`.
+    #[serde(rename = "wm-property", default)]
+    pub property: String,
+
+    /// URL of the mentioning post.
+    #[serde(default)]
+    pub url: Option<String>,
+
+    /// Publication date (RFC 3339).
+    #[serde(default)]
+    pub published: Option<String>,
+
+    /// Whether the mention is private and must not be published.
+    #[serde(rename = "wm-private", default)]
+    pub private: bool,
+
+    /// Author of the mentioning post.
+    #[serde(default)]
+    pub author: MentionAuthor,
+
+    /// Content of the mentioning post.
+    #[serde(default)]
+    pub content: Option<MentionContent>,
+}
+
+impl Webmention {
+    /// Whether this is a like or repost (shown as an avatar) rather than a
+    /// reply or mention (shown with its text).
+    #[must_use]
+    pub fn is_reaction(&self) -> bool {
+        matches!(
+            self.property.as_str(),
+            "like-of" | "repost-of" | "bookmark-of"
+        )
+    }
+}
+
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum Export {
+    Feed { children: Vec<Webmention> },
+    Entries(Vec<Webmention>),
+}
+
+/// Received webmentions grouped by target path.
+#[derive(Debug, Clone, Default)]
+pub struct Webmentions {
+    by_target: HashMap<String, Vec<Webmention>>,
+}
+
+impl Webmentions {
+    /// Load a webmention.io export file.
+    pub fn load(path: &Path) -> Result<Self> {
+        Self::from_json(&fs::read_to_string(path)?)
+    }
+
+    /// Parse a webmention.io export. Private mentions are dropped.
+    pub fn from_json(json: &str) -> Result<Self> {
+        let entries = match serde_json::from_str(json)? {
+            Export::Feed { children } => children,
+            Export::Entries(entries) => entries,
+        };
+
+        let mut by_target: HashMap<String, Vec<Webmention>> = HashMap::new();
+        for mention in entries.into_iter().filter(|m| !m.private) {
+            by_target
+                .entry(target_path(&mention.target))
+                .or_default()
+                .push(mention);
+        }
+        for mentions in by_target.values_mut() {
+            mentions.sort_by(|a, b| a.published.cmp(&b.published));
+        }
+
+        Ok(Self { by_target })
+    }
+
+    /// Mentions of the page at `url` (a path or absolute URL), oldest first.
+    #[must_use]
+    pub fn for_url(&self, url: &str) -> &[Webmention] {
+        self.by_target
+            .get(&target_path(url))
+            .map_or(&[], Vec::as_slice)
+    }
+
+    /// Render the mentions of the page at `url`, or `None` if it has none.
+    #[must_use]
+    pub fn html(&self, url: &str) -> Option<String> {
+        let mentions = self.for_url(url);
+        if mentions.is_empty() {
+            return None;
+        }
+
+        let (reactions, replies): (Vec<&Webmention>, Vec<&Webmention>) =
+            mentions.iter().partition(|m| m.is_reaction());
+
+        let mut html = String::from(
+            r#"<section class="webmentions" aria-labelledby="webmentions-title"><h2 id="webmentions-title">Webmentions</h2>"#,
+        );
+
+        if !reactions.is_empty() {
+            let counts = [
+                ("like-of", "like"),
+                ("repost-of", "repost"),
+                ("bookmark-of", "bookmark"),
+            ]
+            .iter()
+            .filter_map(|(property, label)| {
+                let n = reactions.iter().filter(|m| m.property == *property).count();
+                (n > 0).then(|| format!("{n} {label}{}", if n == 1 { "" } else { "s" }))
+            })
+            .collect::<Vec<_>>()
+            .join(" · ");
+            html.push_str(&format!(
+                r#"<p class="webmention-counts">{counts}</p><ul class="webmention-faces">"#
+            ));
+            for mention in reactions {
+                html.push_str(&format!("<li>{}</li>", author_html(&mention.author, false)));
+            }
+            html.push_str("</ul>");
+        }
+
+        if !replies.is_empty() {
+            html.push_str(r#"<ol class="webmention-replies">"#);
+            for mention in replies {
+                html.push_str(r#"<li class="webmention-reply">"#);
+                html.push_str(&author_html(&mention.author, true));
+                if let Some(published) = &mention.published {
+                    let date = published.get(..10).unwrap_or(published);
+                    let time = format!(
+                        r#"<time datetime="{}">{}</time>"#,
+                        escape_html(published),
+                        escape_html(date)
+                    );
+                    match mention.url.as_deref().and_then(http_url) {
+                        Some(url) => html.push_str(&format!(
+                            r#" <a class="webmention-source" href="{}" rel="nofollow ugc">{time}</a>"#,
+                            escape_html(url)
+                        )),
+                        None => html.push_str(&format!(" {time}")),
+                    }
+                }
+                if let Some(text) = mention.content.as_ref().and_then(|c| c.text.as_deref()) {
+                    html.push_str(&format!("<p>{}</p>", escape_html(text.trim())));
+                }
+                html.push_str("</li>");
+            }
+            html.push_str("</ol>");
+        }
+
+        html.push_str("</section>");
+        Some(html)
+    }
+}
+
+/// Avatar (and optionally name) of a mention author, linked to their
+/// profile.
+fn author_html(author: &MentionAuthor, with_name: bool) -> String {
+    let name = if author.name.is_empty() {
+        "Anonymous"
+    } else {
+        &author.name
+    };
+    let photo = author
+        .photo
+        .as_deref()
+        .and_then(http_url)
+        .map_or(String::new(), |src| {
+            format!(
+                r#"<img src="{}" alt="{}" width="32" height="32" loading="lazy">"#,
+                escape_html(src),
+                escape_html(if with_name { "" } else { name })
+            )
+        });
+    let label = if with_name || photo.is_empty() {
+        format!("<span>{}</span>", escape_html(name))
+    } else {
+        String::new()
+    };
+
+    match author.url.as_deref().and_then(http_url) {
+        Some(url) => format!(
+            r#"<a class="webmention-author" href="{}" title="{}" rel="nofollow ugc">{photo}{label}</a>"#,
+            escape_html(url),
+            escape_html(name)
+        ),
+        None => format!(r#"<span class="webmention-author">{photo}{label}</span>"#),
+    }
+}
+
+/// `url` if it is an absolute `http` or `https` URL. Mentions come from
+/// third parties, so other schemes (such as `javascript:`) are never linked.
+fn http_url(url: &str) -> Option<&str> {
+    let url = url.trim();
+    let (scheme, rest) = url.split_once("://")?;
+    let is_http = scheme.eq_ignore_ascii_case("http") || scheme.eq_ignore_ascii_case("https");
+    (is_http && !rest.is_empty() && !url.chars().any(char::is_control)).then_some(url)
+}
+
+/// Normalize a URL or path to the path used for matching.
+fn target_path(url: &str) -> String {
+    let path = url
+        .split_once("://")
+        .map_or(url, |(_, rest)| rest.find('/').map_or("/", |i| &rest[i..]));
+    let path = path.split(['?', '#']).next().unwrap_or_default();
+    let path = path.strip_suffix("index.html").unwrap_or(path);
+    let path = path.trim_end_matches('/');
+    if path.is_empty() {
+        "/".to_string()
+    } else {
+        path.to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EXPORT: &str = r#"{
+        "type": "feed",
+        "name": "Webmentions",
+        "children": [
+            {
+                "type": "entry",
+                "author": {"name": "Bob", "url": "https://bob.example", "photo": "https://bob.example/me.jpg"},
+                "url": "https://bob.example/notes/1",
+                "published": "2024-03-02T10:00:00+00:00",
+                "wm-target": "https://example.com/posts/hello/",
+                "wm-property": "in-reply-to",
+                "content": {"text": "Great <post>!", "html": "<p>Great post!</p>"}
+            },
+            {
+                "author": {"name": "Carol", "photo": "https://carol.example/a.png"},
+                "wm-target": "http://example.com/posts/hello",
+                "wm-property": "like-of"
+            },
+            {
+                "author": {"name": "Eve"},
+                "wm-target": "https://example.com/posts/hello/",
+                "wm-property": "in-reply-to",
+                "wm-private": true
+            }
+        ]
+    }"#;
+
+    #[test]
+    fn test_group_by_target() {
+        let mentions = Webmentions::from_json(EXPORT).unwrap();
+
+        assert_eq!(mentions.for_url("/posts/hello").len(), 2);
+        assert_eq!(
+            mentions
+                .for_url("https://example.com/posts/hello/index.html")
+                .len(),
+            2
+        );
+        assert!(mentions.for_url("/posts/other").is_empty());
+    }
+
+    #[test]
+    fn test_html() {
+        let mentions = Webmentions::from_json(EXPORT).unwrap();
+        let html = mentions.html("/posts/hello").unwrap();
+
+        assert!(html.contains(r#"<p class="webmention-counts">1 like</p>"#));
+        assert!(html.contains(r#"<img src="https://carol.example/a.png" alt="Carol""#));
+        assert!(html.contains(
+            r#"<a class="webmention-source" href="https://bob.example/notes/1" rel="nofollow ugc"><time datetime="2024-03-02T10:00:00+00:00">2024-03-02</time></a>"#
+        ));
+        assert!(html.contains("<p>Great &lt;post&gt;!</p>"));
+        assert!(!html.contains("Eve"));
+        assert!(mentions.html("/posts/other").is_none());
+    }
+
+    #[test]
+    fn test_html_drops_unsafe_urls() {
+        let mentions = Webmentions::from_json(
+            r#"[{
+                "author": {"name": "Mallory", "url": "javascript:alert(1)", "photo": "data:image/svg+xml,<svg onload=alert(1)>"},
+                "url": " JavaScript:alert(1)",
+                "published": "2024-03-02T10:00:00+00:00",
+                "wm-target": "https://example.com/posts/hello/",
+                "wm-property": "in-reply-to"
+            }]"#,
+        )
+        .unwrap();
+        let html = mentions.html("/posts/hello").unwrap();
+
+        assert!(!html.to_lowercase().contains("javascript:"));
+        assert!(!html.contains("<img"));
+        assert!(html.contains(r#"<span class="webmention-author"><span>Mallory</span></span>"#));
+        assert!(html.contains(r#" <time datetime="2024-03-02T10:00:00+00:00">"#));
+    }
+
+    #[test]
+    fn test_http_url() {
+        assert_eq!(http_url("https://bob.example"), Some("https://bob.example"));
+        assert_eq!(
+            http_url("HTTP://bob.example/a"),
+            Some("HTTP://bob.example/a")
+        );
+        assert_eq!(http_url("javascript:alert(1)"), None);
+        assert_eq!(http_url("java\tscript://x"), None);
+        assert_eq!(http_url("//bob.example"), None);
+        assert_eq!(http_url("https://"), None);
+    }
+
+    #[test]
+    fn test_plain_array() {
+        let mentions = Webmentions::from_json(
+            r#"[{"wm-target": "https://example.com/", "wm-property": "mention-of"}]"#,
+        )
+        .unwrap();
+        assert_eq!(mentions.for_url("/").len(), 1);
+    }
+}
@@ -0,0 +1,119 @@
+//! Admonitions (callouts) in Markdown.
+//!
+//! Two syntaxes render into the same `<aside class="admonition
+//! admonition-KIND">` block, with a `<p class="admonition-title">`:
+//!
+//! ```markdown
+//! > [!WARNING]
+//! > GitHub-style alert: note, tip, important, warning or caution.
+//!
+//! :::tip Custom title
+//! Fenced container of any kind, with an optional title.
+//! :::
+//! ```
+
+use pulldown_cmark::BlockQuoteKind;
+use typstify_core::escape_html;
+
+/// Opening HTML of an admonition of `kind` (a lowercase word), titled
+/// `title` or else after its kind.
+pub fn open_html(kind: &str, title: Option<&str>) -> String {
+    let title = title.map_or_else(|| default_title(kind), escape_html);
+    format!(
+        "<aside class=\"admonition admonition-{kind}\" role=\"note\">\n<p class=\"admonition-title\">{title}</p>\n"
+    )
+}
+
+/// Closing HTML of an admonition.
+pub const CLOSE_HTML: &str = "</aside>\n";
+
+/// Kind of a GitHub-style alert.
+pub fn alert_kind(kind: BlockQuoteKind) -> &'static str {
+    match kind {
+        BlockQuoteKind::Note => "note",
+        BlockQuoteKind::Tip => "tip",
+        BlockQuoteKind::Important => "important",
+        BlockQuoteKind::Warning => "warning",
+        BlockQuoteKind::Caution => "caution",
+    }
+}
+
+/// `kind` capitalized (`tip` → `Tip`).
+fn default_title(kind: &str) -> String {
+    let mut chars = kind.chars();
+    chars
+        .next()
+        .map(|first| first.to_uppercase().chain(chars).collect())
+        .unwrap_or_default()
+}
+
+/// Replace `:::kind [title]` … `:::` containers outside code blocks with
+/// admonition HTML around their (still Markdown) content.
+///
+/// Containers nest, and are closed at the end of the content if left open.
+/// The HTML is separated from the content by blank lines so that it is
+/// parsed as Markdown, which moves later lines down by two per container.
+pub fn expand_containers(content: &str) -> String {
+    if !content.contains(":::") {
+        return content.to_string();
+    }
+
+    let mut output = String::with_capacity(content.len());
+    let mut fence: Option<&str> = None;
+    let mut open = 0usize;
+
+    for line in content.split_inclusive('\n') {
+        let trimmed = line.trim_start();
+        if let Some(marker) = fence {
+            if trimmed.starts_with(marker) {
+                fence = None;
+            }
+        } else if let Some(marker) = ["```", "~~~"].into_iter().find(|m| trimmed.starts_with(m)) {
+            fence = Some(marker);
+        } else if let Some(rest) = trimmed.strip_prefix(":::") {
+            let rest = rest.trim_start_matches(':').trim();
+            let indent = &line[..line.len() - trimmed.len()];
+            if rest.is_empty() && open > 0 {
+                open -= 1;
+                output.push('\n');
+                output.push_str(indent);
+                output.push_str(CLOSE_HTML);
+                continue;
+            }
+            let (kind, title) = rest
+                .split_once(char::is_whitespace)
+                .map_or((rest, None), |(kind, title)| (kind, Some(title.trim())));
+            if !kind.is_empty() && kind.chars().all(|c| c.is_ascii_alphanumeric() || c == '-') {
+                open += 1;
+                output.push_str(indent);
+                output.push_str(&open_html(&kind.to_ascii_lowercase(), title));
+                output.push('\n');
+                continue;
+            }
+        }
+        output.push_str(line);
+    }
+
+    for _ in 0..open {
+        output.push('\n');
+        output.push_str(CLOSE_HTML);
+    }
+    output
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_expand_containers() {
+        let expanded = expand_containers(
+            ":::tip\nUse **this**.\n:::\n\n```\n:::note\n```\n\n::: Warning Mind <the> gap\ntext\n",
+        );
+        assert_eq!(
+            expanded,
+            "<aside class=\"admonition admonition-tip\" role=\"note\">\n<p class=\"admonition-title\">Tip</p>\n\nUse **this**.\n\n</aside>\n\n```\n:::note\n```\n\n<aside class=\"admonition admonition-warning\" role=\"note\">\n<p class=\"admonition-title\">Mind &lt;the&gt; gap</p>\n\ntext\n\n</aside>\n"
+        );
+        assert_eq!(expand_containers("a ::: b"), "a ::: b");
+    }
+}
@@ -0,0 +1,165 @@
+//! Source diagnostics with file locations and snippets.
+//!
+//! Renders parser errors in a compiler-style format:
+//!
+//! ```text
+//! error: unclosed delimiter
+//!  --> posts/hello.typ:3:10
+//!   |
+//! 3 | #let x = (1, 2
+//!   |          ^
+//!   = hint: ...
+//! ```
+
+use std::{fmt, ops::Range, path::PathBuf};
+
+/// A diagnostic pointing at a location in a source file.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Diagnostic {
+    /// Source file path.
+    pub path: PathBuf,
+
+    /// 1-based line number.
+    pub line: usize,
+
+    /// 1-based column number (in characters).
+    pub column: usize,
+
+    /// Error message.
+    pub message: String,
+
+    /// Additional hints.
+    pub hints: Vec<String>,
+
+    /// The source line the diagnostic points at.
+    pub source_line: String,
+
+    /// Number of characters to underline (at least 1).
+    pub width: usize,
+}
+
+impl Diagnostic {
+    /// Create a diagnostic for a byte range within `source`.
+    #[must_use]
+    pub fn new(
+        path: impl Into<PathBuf>,
+        source: &str,
+        range: Range<usize>,
+        message: impl Into<String>,
+    ) -> Self {
+        let start = floor_char_boundary(source, range.start.min(source.len()));
+        let end = floor_char_boundary(source, range.end.clamp(start, source.len()));
+
+        let line_start = source[..start].rfind('\n').map_or(0, |i| i + 1);
+        let line_end = source[start..]
+            .find('\n')
+            .map_or(source.len(), |i| start + i);
+        let source_line = source[line_start..line_end].trim_end_matches('\r');
+
+        let line = source[..start].matches('\n').count() + 1;
+        let column = source[line_start..start].chars().count() + 1;
+        let width = source[start..end.min(line_end)].chars().count().max(1);
+
+        Self {
+            path: path.into(),
+            line,
+            column,
+            message: message.into(),
+            hints: Vec::new(),
+            source_line: source_line.to_string(),
+            width,
+        }
+    }
+
+    /// Attach hints to the diagnostic.
+    #[must_use]
+    pub fn with_hints(mut self, hints: impl IntoIterator<Item = impl Into<String>>) -> Self {
+        self.hints.extend(hints.into_iter().map(Into::into));
+        self
+    }
+}
+
+impl fmt::Display for Diagnostic {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let gutter = self.line.to_string().len();
+        let pad = " ".repeat(gutter);
+
+        writeln!(f, "error: {}", self.message)?;
+        writeln!(
+            f,
+            "{pad}--> {}:{}:{}",
+            self.path.display(),
+            self.line,
+            self.column
+        )?;
+        writeln!(f, "{pad} |")?;
+        writeln!(f, "{} | {}", self.line, self.source_line)?;
+        write!(
+            f,
+            "{pad} | {}{}",
+            " ".repeat(self.column - 1),
+            "^".repeat(self.width)
+        )?;
+        for hint in &self.hints {
+            write!(f, "\n{pad} = hint: {hint}")?;
+        }
+        Ok(())
+    }
+}
+
+/// Render a list of diagnostics separated by blank lines.
+#[must_use]
+pub fn render_all(diagnostics: &[Diagnostic]) -> String {
+    diagnostics
+        .iter()
+        .map(ToString::to_string)
+        .collect::<Vec<_>>()
+        .join("\n\n")
+}
+
+/// Largest char boundary not greater than `index`.
+fn floor_char_boundary(s: &str, mut index: usize) -> usize {
+    while index > 0 && !s.is_char_boundary(index) {
+        index -= 1;
+    }
+    index
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_location() {
+        let source = "line one\nlet x = (1, 2\nend";
+        let offset = source.find('(').unwrap();
+        let diag = Diagnostic::new("a.typ", source, offset..offset + 1, "unclosed delimiter");
+
+        assert_eq!(diag.line, 2);
+        assert_eq!(diag.column, 9);
+        assert_eq!(diag.source_line, "let x = (1, 2");
+    }
+
+    #[test]
+    fn test_render() {
+        let source = "#let x = (1, 2";
+        let diag = Diagnostic::new("a.typ", source, 9..10, "unclosed delimiter")
+            .with_hints(["add a closing parenthesis"]);
+
+        let rendered = diag.to_string();
+        assert_eq!(
+            rendered,
+            "error: unclosed delimiter\n --> a.typ:1:10\n  |\n1 | #let x = (1, 2\n  |          ^\n  = hint: add a closing parenthesis"
+        );
+    }
+
+    #[test]
+    fn test_multibyte_columns() {
+        let source = "标题 = (";
+        let offset = source.find('(').unwrap();
+        let diag = Diagnostic::new("a.typ", source, offset..source.len() + 5, "oops");
+
+        assert_eq!(diag.column, 6);
+        assert_eq!(diag.width, 1);
+    }
+}
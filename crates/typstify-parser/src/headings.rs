@@ -0,0 +1,196 @@
+//! Heading structure lint for Markdown content.
+//!
+//! Reports skipped heading levels (`##` followed by `####`), more than one
+//! top-level heading, and headings nested deeper than a configured maximum.
+//! [`fix`] rewrites ATX (`#`) headings to resolve those issues; setext
+//! (underlined) headings are reported but left alone.
+
+use pulldown_cmark::{Event, Options, Parser, Tag};
+use typstify_core::frontmatter::split_frontmatter;
+
+/// A heading structure problem.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct HeadingIssue {
+    /// 1-based line number of the heading.
+    pub line: usize,
+
+    /// Description of the problem.
+    pub message: String,
+}
+
+/// A heading found in the source.
+#[derive(Debug, Clone, Copy)]
+struct Heading {
+    /// Byte offset of the heading line in the whole file.
+    start: usize,
+    /// 1-based line number.
+    line: usize,
+    level: u8,
+    /// Written with `#` markers, so the level can be rewritten.
+    atx: bool,
+}
+
+/// Find heading structure issues. `max_depth` limits the deepest allowed
+/// level (e.g. 4 for `####`).
+#[must_use]
+pub fn lint(content: &str, max_depth: Option<u8>) -> Vec<HeadingIssue> {
+    let headings = headings(content);
+    let mut issues = Vec::new();
+    let mut first_h1: Option<usize> = None;
+
+    for (i, heading) in headings.iter().enumerate() {
+        if heading.level == 1 {
+            match first_h1 {
+                Some(line) => issues.push(HeadingIssue {
+                    line: heading.line,
+                    message: format!("multiple h1 headings (first on line {line})"),
+                }),
+                None => first_h1 = Some(heading.line),
+            }
+        }
+
+        if let Some(prev) = i.checked_sub(1).map(|p| headings[p].level)
+            && heading.level > prev + 1
+        {
+            issues.push(HeadingIssue {
+                line: heading.line,
+                message: format!("heading level jumps from h{prev} to h{}", heading.level),
+            });
+        }
+
+        if let Some(max) = max_depth
+            && heading.level > max
+        {
+            issues.push(HeadingIssue {
+                line: heading.line,
+                message: format!(
+                    "h{} is deeper than the maximum heading depth (h{max})",
+                    heading.level
+                ),
+            });
+        }
+    }
+
+    issues
+}
+
+/// Rewrite ATX headings so there is at most one h1, no skipped levels and
+/// nothing deeper than `max_depth`. Returns `None` when nothing changes.
+///
+/// Headings after a second h1 are demoted one level so that section keeps
+/// its shape; skipped levels are then closed up and deep headings promoted.
+#[must_use]
+pub fn fix(content: &str, max_depth: Option<u8>) -> Option<String> {
+    let headings = headings(content);
+    let mut levels: Vec<u8> = headings.iter().map(|h| h.level).collect();
+
+    // Demote everything from the second h1 on
+    if let Some(second) = levels
+        .iter()
+        .enumerate()
+        .filter(|(_, l)| **l == 1)
+        .nth(1)
+        .map(|(i, _)| i)
+    {
+        for level in &mut levels[second..] {
+            *level = (*level + 1).min(6);
+        }
+    }
+    // Close skipped levels
+    for i in 1..levels.len() {
+        levels[i] = levels[i].min(levels[i - 1] + 1);
+    }
+    if let Some(max) = max_depth {
+        for level in &mut levels {
+            *level = (*level).min(max.max(1));
+        }
+    }
+
+    let mut out = String::with_capacity(content.len());
+    let mut copied = 0;
+    for (heading, &level) in headings.iter().zip(&levels) {
+        if level == heading.level || !heading.atx {
+            continue;
+        }
+        let line = &content[heading.start..];
+        let indent = line.len() - line.trim_start_matches(' ').len();
+        let marker_end = heading.start + indent + usize::from(heading.level);
+        out.push_str(&content[copied..heading.start + indent]);
+        out.push_str(&"#".repeat(usize::from(level)));
+        copied = marker_end;
+    }
+
+    if copied == 0 {
+        return None;
+    }
+    out.push_str(&content[copied..]);
+    Some(out)
+}
+
+/// Headings of the Markdown body, skipping frontmatter and code blocks.
+fn headings(content: &str) -> Vec<Heading> {
+    let body = split_frontmatter(content).map_or(content, |(_, _, body)| body);
+    let body_offset = content.len() - body.len();
+    let options = Options::ENABLE_TABLES
+        | Options::ENABLE_FOOTNOTES
+        | Options::ENABLE_HEADING_ATTRIBUTES
+        | Options::ENABLE_DEFINITION_LIST;
+
+    Parser::new_ext(body, options)
+        .into_offset_iter()
+        .filter_map(|(event, range)| {
+            let Event::Start(Tag::Heading { level, .. }) = event else {
+                return None;
+            };
+            let start = body_offset + range.start;
+            let line_start = content[..start].rfind('\n').map_or(0, |i| i + 1);
+            Some(Heading {
+                start: line_start,
+                line: content[..start].matches('\n').count() + 1,
+                level: level as u8,
+                atx: content[start..].trim_start_matches(' ').starts_with('#'),
+            })
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const CONTENT: &str = "---\ntitle: \"H\"\n---\n\n# One\n\n## Two\n\n#### Four\n\n```\n# not a heading\n```\n\n# Again\n\n### Three\n\nSetext\n======\n";
+
+    #[test]
+    fn test_lint() {
+        let issues = lint(CONTENT, Some(3));
+        let found: Vec<_> = issues
+            .iter()
+            .map(|i| (i.line, i.message.as_str()))
+            .collect();
+
+        assert_eq!(
+            found,
+            vec![
+                (9, "heading level jumps from h2 to h4"),
+                (9, "h4 is deeper than the maximum heading depth (h3)"),
+                (15, "multiple h1 headings (first on line 5)"),
+                (17, "heading level jumps from h1 to h3"),
+                (19, "multiple h1 headings (first on line 5)"),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_fix() {
+        let fixed = fix(CONTENT, Some(3)).unwrap();
+
+        assert!(fixed.contains("\n## Two\n\n### Four\n"));
+        assert!(fixed.contains("\n# not a heading\n"));
+        assert!(fixed.contains("\n## Again\n\n### Three\n"));
+        // Setext headings are reported but not rewritten
+        assert!(fixed.contains("\nSetext\n======\n"));
+        assert_eq!(lint(&fixed, Some(3)).len(), 1);
+
+        assert_eq!(fix("# Fine\n\n## Good\n", None), None);
+    }
+}
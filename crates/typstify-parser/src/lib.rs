@@ -2,16 +2,32 @@
 //!
 //! Content parsers for Markdown and Typst formats.
 
+pub mod admonition;
+pub mod diagnostic;
+pub mod headings;
 pub mod markdown;
+pub mod mermaid;
+pub mod prose;
+pub mod shortcode;
+pub mod spelling;
 pub mod syntax;
+pub mod typography;
 pub mod typst_parser;
+pub mod typst_world;
 
 use std::path::Path;
 
-pub use markdown::MarkdownParser;
-pub use syntax::SyntaxHighlighter;
+pub use diagnostic::Diagnostic;
+pub use markdown::{MarkdownParser, MarkdownWarning};
+pub use shortcode::{Shortcode, ShortcodeRenderer};
+pub use spelling::SpellChecker;
+pub use syntax::{
+    AUTO_THEME, CodeInfo, DEFAULT_DARK_THEME, DEFAULT_LIGHT_THEME, SyntaxHighlighter,
+};
 use thiserror::Error;
+pub use typography::SmartPunctuation;
 pub use typst_parser::TypstParser;
+pub use typst_world::TypstWorld;
 use typstify_core::content::{ContentType, ParsedContent};
 
 /// Parser errors.
@@ -85,6 +101,53 @@ impl ParserRegistry {
         }
     }
 
+    /// Set the highlighter for Markdown code blocks.
+    #[must_use]
+    pub fn with_highlighter(self, highlighter: SyntaxHighlighter) -> Self {
+        Self {
+            markdown: self.markdown.with_highlighter(highlighter),
+            typst: self.typst,
+        }
+    }
+
+    /// Set the Markdown autolink and mention settings.
+    #[must_use]
+    pub fn with_markdown_config(self, config: typstify_core::config::MarkdownConfig) -> Self {
+        Self {
+            markdown: self.markdown.with_config(config),
+            typst: self.typst,
+        }
+    }
+
+    /// Set the timezone used for frontmatter dates without an offset.
+    #[must_use]
+    pub fn with_timezone(self, timezone: typstify_core::config::Tz) -> Self {
+        Self {
+            markdown: self.markdown.with_timezone(timezone),
+            typst: self.typst.with_timezone(timezone),
+        }
+    }
+
+    /// Set the renderer for shortcodes that are not built in, for Markdown
+    /// and Typst content.
+    #[must_use]
+    pub fn with_shortcodes(self, shortcodes: std::sync::Arc<dyn ShortcodeRenderer>) -> Self {
+        Self {
+            markdown: self.markdown.with_shortcodes(shortcodes.clone()),
+            typst: self.typst.with_shortcodes(shortcodes),
+        }
+    }
+
+    /// Set the site root that `include` shortcode paths are resolved
+    /// against, for Markdown and Typst content.
+    #[must_use]
+    pub fn with_include_root(self, root: &Path) -> Self {
+        Self {
+            markdown: self.markdown.with_include_root(root),
+            typst: self.typst.with_include_root(root),
+        }
+    }
+
     /// Parse content, auto-detecting the parser from file extension.
     pub fn parse(&self, content: &str, path: &Path) -> Result<ParsedContent> {
         let ext = path
@@ -99,6 +162,30 @@ impl ParserRegistry {
         }
     }
 
+    /// Parse content like [`Self::parse`], applying `typography` to Markdown.
+    ///
+    /// Typst handles quotes and dashes itself, so Typst files are parsed
+    /// unchanged.
+    pub fn parse_with_typography(
+        &self,
+        content: &str,
+        path: &Path,
+        typography: &typstify_core::config::TypographyConfig,
+    ) -> Result<ParsedContent> {
+        if let Some(ext) = path.extension().and_then(|e| e.to_str())
+            && matches!(
+                ContentType::from_extension(ext),
+                Some(ContentType::Markdown)
+            )
+        {
+            let (parsed, _) = self
+                .markdown
+                .parse_with_typography(content, path, typography)?;
+            return Ok(parsed);
+        }
+        self.parse(content, path)
+    }
+
     /// Get the markdown parser.
     pub fn markdown(&self) -> &MarkdownParser {
         &self.markdown
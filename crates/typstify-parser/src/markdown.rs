@@ -1,15 +1,30 @@
 //! Markdown parser using pulldown-cmark.
 
-use std::path::Path;
+use std::{
+    collections::HashMap,
+    fmt,
+    path::{Path, PathBuf},
+    sync::Arc,
+};
 
-use pulldown_cmark::{CodeBlockKind, Event, Options, Parser, Tag, TagEnd};
+use pulldown_cmark::{
+    Alignment, BrokenLink, CodeBlockKind, Event, LinkType, Options, Parser, Tag, TagEnd,
+    TextMergeWithOffset,
+};
 use thiserror::Error;
 use typstify_core::{
-    content::{ParsedContent, TocEntry},
-    frontmatter::parse_frontmatter,
+    config::{MarkdownConfig, TypographyConfig, Tz},
+    content::{HeadingAnchors, ParsedContent, TocEntry},
+    escape_html,
+    frontmatter::parse_frontmatter_with_timezone,
 };
 
-use crate::syntax::SyntaxHighlighter;
+use crate::{
+    admonition, mermaid,
+    shortcode::{self, ShortcodeRenderer},
+    syntax::SyntaxHighlighter,
+    typography::SmartPunctuation,
+};
 
 /// Markdown parsing errors.
 #[derive(Debug, Error)]
@@ -22,11 +37,32 @@ pub enum MarkdownError {
 /// Result type for markdown operations.
 pub type Result<T> = std::result::Result<T, MarkdownError>;
 
+/// A non-fatal content-quality issue found while rendering Markdown.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MarkdownWarning {
+    /// 1-based line number in the source file.
+    pub line: usize,
+
+    /// Human-readable description.
+    pub message: String,
+}
+
+impl fmt::Display for MarkdownWarning {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "line {}: {}", self.line, self.message)
+    }
+}
+
 /// Markdown parser with syntax highlighting support.
 #[derive(Debug)]
 pub struct MarkdownParser {
     highlighter: SyntaxHighlighter,
     options: Options,
+    timezone: Tz,
+    typography: TypographyConfig,
+    config: MarkdownConfig,
+    shortcodes: Option<Arc<dyn ShortcodeRenderer>>,
+    include_root: PathBuf,
 }
 
 impl Default for MarkdownParser {
@@ -44,10 +80,18 @@ impl MarkdownParser {
         options.insert(Options::ENABLE_STRIKETHROUGH);
         options.insert(Options::ENABLE_TASKLISTS);
         options.insert(Options::ENABLE_HEADING_ATTRIBUTES);
+        options.insert(Options::ENABLE_DEFINITION_LIST);
+        options.insert(Options::ENABLE_GFM);
+        options.insert(Options::ENABLE_MATH);
 
         Self {
             highlighter: SyntaxHighlighter::default(),
             options,
+            timezone: Tz::UTC,
+            typography: TypographyConfig::default(),
+            config: MarkdownConfig::default(),
+            shortcodes: None,
+            include_root: PathBuf::from("."),
         }
     }
 
@@ -58,56 +102,228 @@ impl MarkdownParser {
         parser
     }
 
+    /// Set the highlighter for code blocks.
+    #[must_use]
+    pub fn with_highlighter(mut self, highlighter: SyntaxHighlighter) -> Self {
+        self.highlighter = highlighter;
+        self
+    }
+
+    /// Set the timezone used for frontmatter dates without an offset.
+    #[must_use]
+    pub fn with_timezone(mut self, timezone: Tz) -> Self {
+        self.timezone = timezone;
+        self
+    }
+
+    /// Set the smart typography settings.
+    #[must_use]
+    pub fn with_typography(mut self, typography: TypographyConfig) -> Self {
+        self.typography = typography;
+        self
+    }
+
+    /// Set the autolink and mention settings.
+    #[must_use]
+    pub fn with_config(mut self, config: MarkdownConfig) -> Self {
+        self.config = config;
+        self
+    }
+
+    /// Set the renderer for shortcodes that are not built in.
+    #[must_use]
+    pub fn with_shortcodes(mut self, shortcodes: Arc<dyn ShortcodeRenderer>) -> Self {
+        self.shortcodes = Some(shortcodes);
+        self
+    }
+
+    /// Set the site root that `include` shortcode paths are resolved
+    /// against (the working directory by default).
+    #[must_use]
+    pub fn with_include_root(mut self, root: impl Into<PathBuf>) -> Self {
+        self.include_root = root.into();
+        self
+    }
+
     /// Parse markdown content with frontmatter.
     pub fn parse(&self, content: &str, path: &Path) -> Result<ParsedContent> {
+        self.parse_with_warnings(content, path)
+            .map(|(parsed, _)| parsed)
+    }
+
+    /// Parse markdown content with frontmatter, also returning non-fatal
+    /// warnings (bare URLs, undefined reference links, images without alt
+    /// text) with line numbers relative to the whole file.
+    pub fn parse_with_warnings(
+        &self,
+        content: &str,
+        path: &Path,
+    ) -> Result<(ParsedContent, Vec<MarkdownWarning>)> {
+        self.parse_with_typography(content, path, &self.typography)
+    }
+
+    /// Like [`Self::parse_with_warnings`], but with `typography` replacing
+    /// the parser's own settings (e.g. for a page in another language).
+    pub fn parse_with_typography(
+        &self,
+        content: &str,
+        path: &Path,
+        typography: &TypographyConfig,
+    ) -> Result<(ParsedContent, Vec<MarkdownWarning>)> {
         // Split frontmatter from body
-        let (frontmatter, body) = parse_frontmatter(content, path)?;
+        let (frontmatter, body) = parse_frontmatter_with_timezone(content, path, self.timezone)?;
+
+        // The body is a suffix of the content; count the lines before it
+        let body_offset = content.len().saturating_sub(body.len());
+        let first_line = content[..body_offset].matches('\n').count() + 1;
 
         // Parse the markdown body
-        let (html, toc) = self.render_markdown(&body);
+        let mut warnings = Vec::new();
+        let (html, toc) = self.render_markdown(&body, first_line, typography, &mut warnings);
 
-        Ok(ParsedContent {
-            frontmatter,
-            html,
-            raw: body,
-            toc,
-        })
+        Ok((
+            ParsedContent {
+                frontmatter,
+                html,
+                raw: body,
+                toc,
+                dependencies: Vec::new(),
+            },
+            warnings,
+        ))
     }
 
     /// Parse markdown without frontmatter (body only).
     pub fn parse_body(&self, body: &str) -> (String, Vec<TocEntry>) {
-        self.render_markdown(body)
+        self.render_markdown(body, 1, &self.typography, &mut Vec::new())
     }
 
     /// Render markdown to HTML with TOC extraction.
-    fn render_markdown(&self, content: &str) -> (String, Vec<TocEntry>) {
-        let parser = Parser::new_ext(content, self.options);
+    ///
+    /// `first_line` is the line number of the first body line in the
+    /// source file, used to locate warnings.
+    fn render_markdown(
+        &self,
+        content: &str,
+        first_line: usize,
+        typography: &TypographyConfig,
+        warnings: &mut Vec<MarkdownWarning>,
+    ) -> (String, Vec<TocEntry>) {
+        let highlight = |code: &str, lang: Option<&str>| self.highlighter.highlight(code, lang);
+        let (expanded, shortcode_warnings) = shortcode::expand_with(
+            content,
+            &highlight,
+            self.shortcodes.as_deref(),
+            &self.include_root,
+        );
+        warnings.extend(shortcode_warnings.into_iter().map(|w| MarkdownWarning {
+            line: first_line + w.line,
+            message: w.message,
+        }));
+        let (content, abbreviations) = extract_abbreviations(&expanded);
+        let content = admonition::expand_containers(&content);
+        let content = content.as_str();
+
+        let line_at = |offset: usize| first_line + content[..offset].matches('\n').count();
+
+        let mut broken_links = Vec::new();
+        let callback = |link: BrokenLink<'_>| {
+            if matches!(link.link_type, LinkType::Reference | LinkType::Collapsed) {
+                broken_links.push((link.span.start, link.reference.to_string()));
+            }
+            None
+        };
+        let parser = TextMergeWithOffset::new(
+            Parser::new_with_broken_link_callback(content, self.options, Some(callback))
+                .into_offset_iter(),
+        );
+
         let mut toc = Vec::new();
         let mut html = String::new();
         let mut current_heading: Option<(u8, String)> = None;
         let mut code_block_lang: Option<String> = None;
         let mut code_block_content = String::new();
+        let mut image_alt: Option<String> = None;
+        let mut link_depth = 0usize;
+        let mut footnotes = Footnotes::default();
+        let mut smart = SmartPunctuation::new(typography);
+        let mut table: Option<TableState> = None;
+        let mut previous_table: Option<usize> = None;
+        let mut caption: Option<(usize, String)> = None;
+        let mut heading_id: Option<String> = None;
+        let mut heading_at = 0usize;
+        let mut anchors = HeadingAnchors::default();
+        let mut link_starts: Vec<usize> = Vec::new();
+        let mut image_start: Option<usize> = None;
+        let mut attributes_at: Option<usize> = None;
+
+        for (event, range) in parser {
+            // A `Table: ...` paragraph right after a table becomes its caption
+            let caption_at = previous_table.take().filter(|_| {
+                matches!(event, Event::Start(Tag::Paragraph))
+                    && content[range.clone()].starts_with("Table:")
+            });
+
+            // `{#id .class key=value}` right after a link or image
+            let mut event = event;
+            if let Some(at) = attributes_at.take()
+                && let Event::Text(ref text) = event
+                && let Some((attributes, len)) = Attributes::parse(text)
+            {
+                let rest = text[len..].to_string();
+                html.insert_str(at, &attributes.to_html());
+                event = Event::Text(rest.into());
+            }
+
+            if let Event::Start(tag) = &event
+                && is_block(tag)
+            {
+                smart.reset();
+            }
 
-        for event in parser {
             match event {
                 // Handle heading start
-                Event::Start(Tag::Heading { level, id, .. }) => {
+                Event::Start(Tag::Heading {
+                    level,
+                    id,
+                    classes,
+                    attrs,
+                }) => {
                     let lvl = level as u8;
                     current_heading = Some((lvl, String::new()));
-                    let id_attr = id.map(|i| format!(" id=\"{i}\"")).unwrap_or_default();
-                    html.push_str(&format!("<h{lvl}{id_attr}>"));
+                    heading_id = id.as_ref().map(|i| i.to_string());
+                    if let Some(id) = &heading_id {
+                        anchors.reserve(id);
+                    }
+                    heading_at = html.len();
+                    let attributes = Attributes {
+                        id: heading_id.clone(),
+                        classes: classes.iter().map(|c| c.to_string()).collect(),
+                        pairs: attrs
+                            .iter()
+                            .map(|(k, v)| (k.to_string(), v.as_ref().map(|v| v.to_string())))
+                            .collect(),
+                    };
+                    html.push_str(&format!("<h{lvl}{}>", attributes.to_html()));
                 }
 
                 // Handle heading end
                 Event::End(TagEnd::Heading(level)) => {
                     let lvl = level as u8;
                     if let Some((_, ref text)) = current_heading {
-                        let id = slugify(text);
-                        toc.push(TocEntry {
-                            level: lvl,
-                            text: text.clone(),
-                            id: id.clone(),
-                        });
+                        let id = match heading_id.take() {
+                            Some(id) => id,
+                            None => {
+                                // Anchor the heading after its text is known
+                                let id = anchors.unique(&slugify(text));
+                                html.insert_str(
+                                    heading_at + "<hN".len(),
+                                    &format!(" id=\"{}\"", escape_html(&id)),
+                                );
+                                id
+                            }
+                        };
+                        toc.push(TocEntry::new(lvl, text.clone(), id));
                     }
                     html.push_str(&format!("</h{lvl}>"));
                     current_heading = None;
@@ -127,10 +343,14 @@ impl MarkdownParser {
 
                 // Handle code block end
                 Event::End(TagEnd::CodeBlock) => {
-                    let highlighted = self
-                        .highlighter
-                        .highlight(&code_block_content, code_block_lang.as_deref());
-                    html.push_str(&highlighted);
+                    if mermaid::is_diagram(code_block_lang.as_deref()) {
+                        html.push_str(&mermaid::diagram_html(&code_block_content));
+                    } else {
+                        let highlighted = self
+                            .highlighter
+                            .highlight(&code_block_content, code_block_lang.as_deref());
+                        html.push_str(&highlighted);
+                    }
                     code_block_lang = None;
                     code_block_content.clear();
                 }
@@ -142,39 +362,190 @@ impl MarkdownParser {
                     code_block_content.push_str(&text);
                 }
 
+                // Collect image alt text as plain text, dropping nested markup
+                Event::Text(text) | Event::Code(text) if image_alt.is_some() => {
+                    if let Some(ref mut alt) = image_alt {
+                        alt.push_str(&text);
+                    }
+                }
+                Event::Start(_) | Event::End(_)
+                    if image_alt.is_some() && !matches!(event, Event::End(TagEnd::Image)) => {}
+
                 // Handle regular text
                 Event::Text(text) => {
-                    if let Some((_, ref mut heading_text)) = current_heading {
-                        heading_text.push_str(&text);
+                    if link_depth == 0
+                        && !self.config.autolink
+                        && let Some(url) = find_bare_url(&text)
+                    {
+                        warnings.push(MarkdownWarning {
+                            line: line_at(range.start),
+                            message: format!("bare URL '{url}'; use <{url}> or [text]({url})"),
+                        });
+                    }
+
+                    let segments = if link_depth == 0 {
+                        autolink_segments(
+                            &text,
+                            self.config.autolink,
+                            self.config.mention_url.is_some(),
+                        )
+                    } else {
+                        vec![Segment::Text(&text)]
+                    };
+                    for segment in segments {
+                        let (label, link) = match segment {
+                            Segment::Text(plain) => (smart.apply(plain), None),
+                            Segment::Url(url) => {
+                                smart.skip(url);
+                                let url = escape_html(url);
+                                (url.clone(), Some(format!("<a href=\"{url}\">{url}</a>")))
+                            }
+                            Segment::Mention(user) => {
+                                smart.skip(user);
+                                let base = self.config.mention_url.as_deref().unwrap_or_default();
+                                let href = format!("{}/{user}", base.trim_end_matches('/'));
+                                let link = format!(
+                                    "<a href=\"{}\" class=\"mention\">@{user}</a>",
+                                    escape_html(&href)
+                                );
+                                (format!("@{user}"), Some(link))
+                            }
+                        };
+                        if let Some((_, ref mut heading_text)) = current_heading {
+                            heading_text.push_str(&label);
+                        }
+                        match link {
+                            Some(link) => html.push_str(&link),
+                            None => html.push_str(&abbreviate(&label, &abbreviations)),
+                        }
                     }
-                    html.push_str(&html_escape(&text));
                 }
 
                 // Handle code (inline)
                 Event::Code(code) => {
+                    smart.skip(&code);
                     if let Some((_, ref mut heading_text)) = current_heading {
                         heading_text.push_str(&code);
                     }
-                    html.push_str(&format!("<code>{}</code>", html_escape(&code)));
+                    html.push_str(&format!("<code>{}</code>", escape_html(&code)));
                 }
 
                 // Handle soft breaks
                 Event::SoftBreak => {
+                    smart.skip("\n");
                     html.push('\n');
                 }
 
                 // Handle hard breaks
                 Event::HardBreak => {
+                    smart.skip("\n");
                     html.push_str("<br />\n");
                 }
 
+                // Handle image start; alt text arrives as nested text events
+                Event::Start(tag @ Tag::Image { .. }) => {
+                    image_start = Some(html.len() + "<img".len());
+                    html.push_str(&tag_to_html_start(&tag));
+                    image_alt = Some(String::new());
+                }
+
+                // Handle image end
+                Event::End(TagEnd::Image) => {
+                    let alt = image_alt.take().unwrap_or_default();
+                    if alt.trim().is_empty() {
+                        warnings.push(MarkdownWarning {
+                            line: line_at(range.start),
+                            message: "image has no alt text".to_string(),
+                        });
+                    }
+                    html.push_str(&format!(" alt=\"{}\"", escape_html(&alt)));
+                    html.push_str(&tag_to_html_end(&TagEnd::Image));
+                    attributes_at = image_start.take();
+                }
+
+                // Render footnote definitions into their own buffer; they are
+                // emitted together at the end of the document
+                Event::Start(Tag::FootnoteDefinition(name)) => {
+                    footnotes.open = Some((name.to_string(), std::mem::take(&mut html)));
+                }
+                Event::End(TagEnd::FootnoteDefinition) => {
+                    if let Some((name, outer)) = footnotes.open.take() {
+                        let body = std::mem::replace(&mut html, outer);
+                        footnotes.definitions.push((name, body));
+                    }
+                }
+
+                // Handle table start; cells are aligned from the delimiter row
+                Event::Start(Tag::Table(alignments)) => {
+                    html.push_str("<div class=\"table-wrapper\">\n<table>");
+                    table = Some(TableState {
+                        alignments,
+                        caption_at: html.len(),
+                        in_head: false,
+                        cell: 0,
+                    });
+                }
+
+                // Handle table end
+                Event::End(TagEnd::Table) => {
+                    html.push_str("</tbody>\n</table>\n</div>\n");
+                    previous_table = table.take().map(|t| t.caption_at);
+                }
+
+                // Handle table head and rows
+                Event::Start(Tag::TableHead) | Event::Start(Tag::TableRow) => {
+                    let head = matches!(event, Event::Start(Tag::TableHead));
+                    if let Some(ref mut t) = table {
+                        t.in_head = head;
+                        t.cell = 0;
+                    }
+                    html.push_str(if head { "<thead><tr>" } else { "<tr>" });
+                }
+                Event::End(TagEnd::TableHead) => {
+                    if let Some(ref mut t) = table {
+                        t.in_head = false;
+                    }
+                    html.push_str("</tr></thead>\n<tbody>\n");
+                }
+
+                // Handle table cells
+                Event::Start(Tag::TableCell) => {
+                    if let Some(ref mut t) = table {
+                        html.push_str(&t.open_cell());
+                    }
+                }
+                Event::End(TagEnd::TableCell) => {
+                    let head = table.as_ref().is_some_and(|t| t.in_head);
+                    html.push_str(if head { "</th>" } else { "</td>" });
+                }
+
+                // Handle table caption
+                Event::Start(Tag::Paragraph) if caption_at.is_some() => {
+                    caption = caption_at.map(|at| (at, std::mem::take(&mut html)));
+                }
+                Event::End(TagEnd::Paragraph) if caption.is_some() => {
+                    if let Some((at, outer)) = caption.take() {
+                        let text = std::mem::replace(&mut html, outer);
+                        let text = text.strip_prefix("Table:").unwrap_or(&text).trim();
+                        html.insert_str(at, &format!("\n<caption>{text}</caption>"));
+                    }
+                }
+
                 // Handle other start tags
                 Event::Start(tag) => {
+                    if matches!(tag, Tag::Link { .. }) {
+                        link_depth += 1;
+                        link_starts.push(html.len() + "<a".len());
+                    }
                     html.push_str(&tag_to_html_start(&tag));
                 }
 
                 // Handle other end tags
                 Event::End(tag) => {
+                    if matches!(tag, TagEnd::Link) {
+                        link_depth = link_depth.saturating_sub(1);
+                        attributes_at = link_starts.pop();
+                    }
                     html.push_str(&tag_to_html_end(&tag));
                 }
 
@@ -185,9 +556,7 @@ impl MarkdownParser {
 
                 // Handle footnote references
                 Event::FootnoteReference(name) => {
-                    html.push_str(&format!(
-                        "<sup class=\"footnote-ref\"><a href=\"#fn-{name}\">[{name}]</a></sup>"
-                    ));
+                    html.push_str(&footnotes.reference(&name));
                 }
 
                 // Handle rules
@@ -205,20 +574,208 @@ impl MarkdownParser {
                     html.push_str(checkbox);
                 }
 
+                // Math is left to KaTeX, which renders `.math` elements in
+                // the browser
                 Event::InlineMath(math) => {
-                    html.push_str(&format!("<span class=\"math inline\">\\({math}\\)</span>"));
+                    html.push_str(&format!(
+                        "<span class=\"math inline\">\\({}\\)</span>",
+                        escape_html(&math)
+                    ));
                 }
 
                 Event::DisplayMath(math) => {
-                    html.push_str(&format!("<div class=\"math display\">\\[{math}\\]</div>"));
+                    html.push_str(&format!(
+                        "<span class=\"math display\">\\[{}\\]</span>",
+                        escape_html(&math)
+                    ));
                 }
             }
         }
 
+        html.push_str(&footnotes.finish());
+
+        for (offset, reference) in broken_links {
+            warnings.push(MarkdownWarning {
+                line: line_at(offset),
+                message: format!("reference link '[{reference}]' has no definition"),
+            });
+        }
+        warnings.sort_by_key(|w| w.line);
+
         (html, toc)
     }
 }
 
+/// Remove `*[ABBR]: Expansion` definitions outside code blocks, returning
+/// the remaining content and the abbreviations (longest first).
+///
+/// Definition lines are blanked rather than removed so line numbers of the
+/// surrounding content are preserved.
+fn extract_abbreviations(content: &str) -> (String, Vec<(String, String)>) {
+    let mut output = String::with_capacity(content.len());
+    let mut abbreviations: Vec<(String, String)> = Vec::new();
+    let mut fence: Option<&str> = None;
+
+    for line in content.split_inclusive('\n') {
+        let trimmed = line.trim_start();
+        if let Some(marker) = fence {
+            if trimmed.starts_with(marker) {
+                fence = None;
+            }
+        } else if let Some(marker) = ["```", "~~~"].into_iter().find(|m| trimmed.starts_with(m)) {
+            fence = Some(marker);
+        } else if let Some(rest) = line.strip_prefix("*[")
+            && let Some((abbr, expansion)) = rest.split_once("]:")
+            && !abbr.trim().is_empty()
+        {
+            let abbr = abbr.trim().to_string();
+            abbreviations.retain(|(a, _)| *a != abbr);
+            abbreviations.push((abbr, expansion.trim().to_string()));
+            if line.ends_with('\n') {
+                output.push('\n');
+            }
+            continue;
+        }
+        output.push_str(line);
+    }
+
+    abbreviations.sort_by_key(|(abbr, _)| std::cmp::Reverse(abbr.len()));
+    (output, abbreviations)
+}
+
+/// Escape a text run, wrapping whole-word abbreviations in `<abbr>`.
+fn abbreviate(text: &str, abbreviations: &[(String, String)]) -> String {
+    if abbreviations.is_empty() {
+        return escape_html(text);
+    }
+
+    let is_word = |c: char| c.is_alphanumeric() || c == '_';
+    let mut html = String::with_capacity(text.len());
+    let mut plain_start = 0;
+    let mut i = 0;
+
+    while i < text.len() {
+        let at_boundary = !text[..i].chars().next_back().is_some_and(is_word);
+        let found = at_boundary
+            .then(|| {
+                abbreviations.iter().find(|(abbr, _)| {
+                    text[i..].starts_with(abbr.as_str())
+                        && !text[i + abbr.len()..].chars().next().is_some_and(is_word)
+                })
+            })
+            .flatten();
+
+        if let Some((abbr, expansion)) = found {
+            html.push_str(&escape_html(&text[plain_start..i]));
+            html.push_str(&format!(
+                "<abbr title=\"{}\">{}</abbr>",
+                escape_html(expansion),
+                escape_html(abbr)
+            ));
+            i += abbr.len();
+            plain_start = i;
+        } else {
+            i += text[i..].chars().next().map_or(1, char::len_utf8);
+        }
+    }
+
+    html.push_str(&escape_html(&text[plain_start..]));
+    html
+}
+
+/// A piece of a text run split for autolinking.
+#[derive(Debug, PartialEq, Eq)]
+enum Segment<'a> {
+    /// Plain text.
+    Text(&'a str),
+
+    /// A bare URL.
+    Url(&'a str),
+
+    /// An `@user` mention (without the `@`).
+    Mention(&'a str),
+}
+
+/// Split a text run into plain text, bare URLs and `@user` mentions.
+fn autolink_segments(text: &str, urls: bool, mentions: bool) -> Vec<Segment<'_>> {
+    if !urls && !mentions {
+        return vec![Segment::Text(text)];
+    }
+
+    let is_word = |c: char| c.is_alphanumeric() || c == '_';
+    let mut segments = Vec::new();
+    let mut plain_start = 0;
+    let mut i = 0;
+
+    while i < text.len() {
+        let rest = &text[i..];
+        let prev = text[..i].chars().next_back();
+
+        let found = if urls
+            && !prev.is_some_and(is_word)
+            && (rest.starts_with("https://") || rest.starts_with("http://"))
+            && let Some(url) = find_bare_url(rest)
+            && !url.ends_with("//")
+        {
+            Some((Segment::Url(url), url.len()))
+        } else if mentions
+            && rest.starts_with('@')
+            && !prev.is_some_and(|c| is_word(c) || matches!(c, '@' | '.' | '/'))
+        {
+            let len = rest[1..]
+                .find(|c: char| !(c.is_ascii_alphanumeric() || c == '-' || c == '_'))
+                .unwrap_or(rest.len() - 1);
+            let user = rest[1..1 + len].trim_end_matches('-');
+            (!user.is_empty() && !user.starts_with('-'))
+                .then(|| (Segment::Mention(user), user.len() + 1))
+        } else {
+            None
+        };
+
+        match found {
+            Some((segment, len)) => {
+                if plain_start < i {
+                    segments.push(Segment::Text(&text[plain_start..i]));
+                }
+                segments.push(segment);
+                i += len;
+                plain_start = i;
+            }
+            None => i += rest.chars().next().map_or(1, char::len_utf8),
+        }
+    }
+
+    if plain_start < text.len() {
+        segments.push(Segment::Text(&text[plain_start..]));
+    }
+    segments
+}
+
+/// Find the first bare `http(s)://` URL in a text run.
+fn find_bare_url(text: &str) -> Option<&str> {
+    let start = text.find("https://").or_else(|| text.find("http://"))?;
+    let url = &text[start..];
+    let end = url
+        .find(|c: char| c.is_whitespace() || matches!(c, ')' | '>' | '"'))
+        .unwrap_or(url.len());
+    Some(url[..end].trim_end_matches(['.', ',', ';', ':']))
+}
+
+/// Whether a tag starts a block, after which quotes open again.
+fn is_block(tag: &Tag) -> bool {
+    matches!(
+        tag,
+        Tag::Paragraph
+            | Tag::Heading { .. }
+            | Tag::BlockQuote(_)
+            | Tag::Item
+            | Tag::FootnoteDefinition(_)
+            | Tag::TableCell
+            | Tag::DefinitionListTitle
+            | Tag::DefinitionListDefinition
+    )
+}
+
 /// Convert a pulldown-cmark tag to HTML opening tag.
 fn tag_to_html_start(tag: &Tag) -> String {
     match tag {
@@ -230,21 +787,15 @@ fn tag_to_html_start(tag: &Tag) -> String {
                 .unwrap_or_default();
             format!("<h{}{id_attr}>", *level as u8)
         }
-        Tag::BlockQuote(_) => "<blockquote>".to_string(),
+        Tag::BlockQuote(Some(kind)) => admonition::open_html(admonition::alert_kind(*kind), None),
+        Tag::BlockQuote(None) => "<blockquote>".to_string(),
         Tag::CodeBlock(_) => String::new(), // Handled separately
         Tag::List(Some(start)) => format!("<ol start=\"{start}\">"),
         Tag::List(None) => "<ul>".to_string(),
         Tag::Item => "<li>".to_string(),
-        Tag::FootnoteDefinition(name) => {
-            format!("<div class=\"footnote\" id=\"fn-{name}\">")
-        }
-        Tag::Table(alignments) => {
-            let _ = alignments; // Alignments handled per cell
-            "<table>".to_string()
-        }
-        Tag::TableHead => "<thead><tr>".to_string(),
+        Tag::FootnoteDefinition(_) => String::new(), // Handled separately
+        Tag::Table(_) | Tag::TableHead | Tag::TableCell => String::new(), // Handled separately
         Tag::TableRow => "<tr>".to_string(),
-        Tag::TableCell => "<td>".to_string(),
         Tag::Emphasis => "<em>".to_string(),
         Tag::Strong => "<strong>".to_string(),
         Tag::Strikethrough => "<del>".to_string(),
@@ -254,9 +805,9 @@ fn tag_to_html_start(tag: &Tag) -> String {
             let title_attr = if title.is_empty() {
                 String::new()
             } else {
-                format!(" title=\"{}\"", html_escape(title))
+                format!(" title=\"{}\"", escape_html(title))
             };
-            format!("<a href=\"{}\"{}> ", html_escape(dest_url), title_attr)
+            format!("<a href=\"{}\"{}> ", escape_html(dest_url), title_attr)
         }
         Tag::Image {
             dest_url, title, ..
@@ -264,12 +815,12 @@ fn tag_to_html_start(tag: &Tag) -> String {
             let title_attr = if title.is_empty() {
                 String::new()
             } else {
-                format!(" title=\"{}\"", html_escape(title))
+                format!(" title=\"{}\"", escape_html(title))
             };
             // Add loading="lazy" and decoding="async" for performance
             format!(
                 "<img src=\"{}\" loading=\"lazy\" decoding=\"async\"{}",
-                html_escape(dest_url),
+                escape_html(dest_url),
                 title_attr
             )
         }
@@ -288,7 +839,8 @@ fn tag_to_html_end(tag: &TagEnd) -> String {
     match tag {
         TagEnd::Paragraph => "</p>\n".to_string(),
         TagEnd::Heading(level) => format!("</h{}>\n", *level as u8),
-        TagEnd::BlockQuote(_) => "</blockquote>\n".to_string(),
+        TagEnd::BlockQuote(Some(_)) => admonition::CLOSE_HTML.to_string(),
+        TagEnd::BlockQuote(None) => "</blockquote>\n".to_string(),
         TagEnd::CodeBlock => String::new(), // Handled separately
         TagEnd::List(ordered) => {
             if *ordered {
@@ -298,11 +850,9 @@ fn tag_to_html_end(tag: &TagEnd) -> String {
             }
         }
         TagEnd::Item => "</li>\n".to_string(),
-        TagEnd::FootnoteDefinition => "</div>\n".to_string(),
-        TagEnd::Table => "</table>\n".to_string(),
-        TagEnd::TableHead => "</tr></thead>\n".to_string(),
+        TagEnd::FootnoteDefinition => String::new(), // Handled separately
+        TagEnd::Table | TagEnd::TableHead | TagEnd::TableCell => String::new(), /* Handled separately */
         TagEnd::TableRow => "</tr>\n".to_string(),
-        TagEnd::TableCell => "</td>".to_string(),
         TagEnd::Emphasis => "</em>".to_string(),
         TagEnd::Strong => "</strong>".to_string(),
         TagEnd::Strikethrough => "</del>".to_string(),
@@ -318,12 +868,207 @@ fn tag_to_html_end(tag: &TagEnd) -> String {
     }
 }
 
-/// Escape HTML special characters.
-fn html_escape(s: &str) -> String {
-    s.replace('&', "&amp;")
-        .replace('<', "&lt;")
-        .replace('>', "&gt;")
-        .replace('"', "&quot;")
+/// Pandoc-style `{#id .class key=value}` attributes.
+#[derive(Debug, Default, PartialEq, Eq)]
+struct Attributes {
+    id: Option<String>,
+    classes: Vec<String>,
+    pairs: Vec<(String, Option<String>)>,
+}
+
+impl Attributes {
+    /// Parse an attribute block at the start of `text`, returning it and its
+    /// length. Anything that isn't a well-formed block yields `None`.
+    fn parse(text: &str) -> Option<(Self, usize)> {
+        let body = text.strip_prefix('{')?;
+        let end = body.find('}')?;
+        let mut rest = body[..end].trim_start();
+        let mut attributes = Self::default();
+        let is_name = |s: &str| {
+            !s.is_empty()
+                && s.chars()
+                    .all(|c| c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | ':'))
+        };
+
+        while !rest.is_empty() {
+            let token_end = rest.find(char::is_whitespace).unwrap_or(rest.len());
+            if let Some(id) = rest.strip_prefix('#') {
+                let id = &id[..token_end - 1];
+                if !is_name(id) {
+                    return None;
+                }
+                attributes.id = Some(id.to_string());
+                rest = &rest[token_end..];
+            } else if let Some(class) = rest.strip_prefix('.') {
+                let class = &class[..token_end - 1];
+                if !is_name(class) {
+                    return None;
+                }
+                attributes.classes.push(class.to_string());
+                rest = &rest[token_end..];
+            } else {
+                let (key, value) = rest.split_once('=')?;
+                if !is_name(key) {
+                    return None;
+                }
+                let (value, remaining) = match value.strip_prefix('"') {
+                    Some(quoted) => {
+                        let close = quoted.find('"')?;
+                        (&quoted[..close], &quoted[close + 1..])
+                    }
+                    None => {
+                        let value_end = value.find(char::is_whitespace).unwrap_or(value.len());
+                        value.split_at(value_end)
+                    }
+                };
+                attributes
+                    .pairs
+                    .push((key.to_string(), Some(value.to_string())));
+                rest = remaining;
+            }
+            rest = rest.trim_start();
+        }
+
+        (attributes != Self::default()).then_some((attributes, end + 2))
+    }
+
+    /// Attributes as HTML, each preceded by a space.
+    fn to_html(&self) -> String {
+        let mut html = String::new();
+        if let Some(ref id) = self.id {
+            html.push_str(&format!(" id=\"{}\"", escape_html(id)));
+        }
+        if !self.classes.is_empty() {
+            html.push_str(&format!(
+                " class=\"{}\"",
+                escape_html(&self.classes.join(" "))
+            ));
+        }
+        for (key, value) in &self.pairs {
+            match value {
+                Some(value) => html.push_str(&format!(" {key}=\"{}\"", escape_html(value))),
+                None => html.push_str(&format!(" {key}")),
+            }
+        }
+        html
+    }
+}
+
+/// State of the table being rendered.
+#[derive(Debug)]
+struct TableState {
+    /// Column alignments from the delimiter row.
+    alignments: Vec<Alignment>,
+
+    /// Offset just after `<table>`, where a caption is inserted.
+    caption_at: usize,
+
+    /// Whether the current row is the header row.
+    in_head: bool,
+
+    /// Index of the next cell in the row.
+    cell: usize,
+}
+
+impl TableState {
+    /// Opening tag for the next cell in the row.
+    fn open_cell(&mut self) -> String {
+        let align = match self.alignments.get(self.cell) {
+            Some(Alignment::Left) => " style=\"text-align: left\"",
+            Some(Alignment::Center) => " style=\"text-align: center\"",
+            Some(Alignment::Right) => " style=\"text-align: right\"",
+            Some(Alignment::None) | None => "",
+        };
+        self.cell += 1;
+        let tag = if self.in_head { "th" } else { "td" };
+        format!("<{tag}{align}>")
+    }
+}
+
+/// Footnote state collected while rendering.
+///
+/// Footnotes are numbered in order of first reference. Every reference gets
+/// an id so the definition can link back to it.
+#[derive(Debug, Default)]
+struct Footnotes {
+    /// Number by footnote name.
+    numbers: HashMap<String, usize>,
+
+    /// References seen so far by footnote name.
+    refs: HashMap<String, usize>,
+
+    /// Rendered definitions in source order.
+    definitions: Vec<(String, String)>,
+
+    /// The definition being rendered and the HTML it interrupted.
+    open: Option<(String, String)>,
+}
+
+impl Footnotes {
+    /// Markup for a reference to `name`.
+    fn reference(&mut self, name: &str) -> String {
+        let next = self.numbers.len() + 1;
+        let number = *self.numbers.entry(name.to_string()).or_insert(next);
+        let count = self.refs.entry(name.to_string()).or_default();
+        *count += 1;
+
+        let id = footnote_id(name);
+        format!(
+            "<sup class=\"footnote-ref\" id=\"{}\"><a href=\"#fn-{id}\" role=\"doc-noteref\">{number}</a></sup>",
+            ref_id(&id, *count)
+        )
+    }
+
+    /// The footnotes section, or an empty string when there are none.
+    fn finish(mut self) -> String {
+        if self.definitions.is_empty() {
+            return String::new();
+        }
+
+        for (name, _) in &self.definitions {
+            let next = self.numbers.len() + 1;
+            self.numbers.entry(name.clone()).or_insert(next);
+        }
+        let mut definitions = std::mem::take(&mut self.definitions);
+        definitions.sort_by_key(|(name, _)| self.numbers[name]);
+
+        let mut html =
+            String::from("<section class=\"footnotes\" role=\"doc-endnotes\">\n<hr />\n<ol>\n");
+        for (name, body) in definitions {
+            let id = footnote_id(&name);
+            let backrefs: String = (1..=self.refs.get(&name).copied().unwrap_or(0))
+                .map(|n| {
+                    format!(
+                        " <a href=\"#{}\" class=\"footnote-backref\" role=\"doc-backlink\" aria-label=\"Back to reference {n}\">↩</a>",
+                        ref_id(&id, n)
+                    )
+                })
+                .collect();
+
+            let body = body.trim_end();
+            let body = match body.strip_suffix("</p>") {
+                Some(inner) => format!("{inner}{backrefs}</p>"),
+                None => format!("{body}{backrefs}"),
+            };
+            html.push_str(&format!("<li id=\"fn-{id}\">{body}</li>\n"));
+        }
+        html.push_str("</ol>\n</section>\n");
+        html
+    }
+}
+
+/// Id fragment for a footnote name.
+fn footnote_id(name: &str) -> String {
+    escape_html(&name.replace(char::is_whitespace, "-"))
+}
+
+/// Id of the `n`th reference to a footnote.
+fn ref_id(id: &str, n: usize) -> String {
+    if n == 1 {
+        format!("fnref-{id}")
+    } else {
+        format!("fnref-{id}-{n}")
+    }
 }
 
 /// Convert text to a URL-safe slug.
@@ -385,6 +1130,17 @@ fn main() {
         assert!(html.contains("main"));
     }
 
+    #[test]
+    fn test_mermaid_diagram() {
+        let parser = MarkdownParser::new();
+        let (html, _) = parser.parse_body("```mermaid\ngraph LR\n  A --> B\n```\n");
+
+        assert_eq!(
+            html,
+            "<pre class=\"mermaid\">graph LR\n  A --&gt; B</pre>\n"
+        );
+    }
+
     #[test]
     fn test_toc_extraction() {
         let parser = MarkdownParser::new();
@@ -401,6 +1157,20 @@ fn main() {
         assert_eq!(toc[2].level, 3);
     }
 
+    #[test]
+    fn test_heading_anchors() {
+        let parser = MarkdownParser::new();
+        let (html, toc) = parser.parse_body("## Usage {#setup-1}\n\n## Setup\n\n## Setup\n");
+
+        assert!(html.contains(r#"<h2 id="setup">Setup</h2>"#));
+        assert!(html.contains(r#"<h2 id="setup-2">Setup</h2>"#));
+        assert!(html.contains(r#"<h2 id="setup-1">Usage</h2>"#));
+        assert_eq!(
+            toc.iter().map(|e| e.id.as_str()).collect::<Vec<_>>(),
+            vec!["setup-1", "setup", "setup-2"]
+        );
+    }
+
     #[test]
     fn test_slugify() {
         assert_eq!(slugify("Hello World"), "hello-world");
@@ -421,9 +1191,27 @@ fn main() {
         assert!(html.contains("<table>"));
         assert!(html.contains("<thead>"));
         assert!(html.contains("<tr>"));
+        assert!(html.contains("<th>Header 1</th>"));
         assert!(html.contains("<td>"));
     }
 
+    #[test]
+    fn test_table_alignment_and_caption() {
+        let parser = MarkdownParser::new();
+        let (html, _) = parser.parse_body(
+            "| Left | Center | Right |\n|:-----|:------:|------:|\n| a | b | c |\n\nTable: Sample *data*\n\nAfter.\n",
+        );
+
+        assert!(html.starts_with(
+            "<div class=\"table-wrapper\">\n<table>\n<caption>Sample <em>data</em></caption><thead>"
+        ));
+        assert!(html.contains(r#"<th style="text-align: center">Center</th>"#));
+        assert!(html.contains(r#"<td style="text-align: right">c</td>"#));
+        assert!(html.contains("</tbody>\n</table>\n</div>"));
+        assert!(html.contains("<p>After.</p>"));
+        assert!(!html.contains("Table:"));
+    }
+
     #[test]
     fn test_task_list() {
         let parser = MarkdownParser::new();
@@ -436,6 +1224,219 @@ fn main() {
         assert!(html.contains("checked"));
     }
 
+    #[test]
+    fn test_footnotes() {
+        let parser = MarkdownParser::new();
+        let (html, _) = parser.parse_body(
+            "Second[^b] then first[^a] and again[^b].\n\n[^a]: Note A.\n[^b]: Note B.\n",
+        );
+
+        assert!(html.contains(
+            r##"<sup class="footnote-ref" id="fnref-b"><a href="#fn-b" role="doc-noteref">1</a></sup>"##
+        ));
+        assert!(html.contains(r##"id="fnref-b-2"><a href="#fn-b" role="doc-noteref">1</a>"##));
+        assert!(html.contains(r##"id="fnref-a"><a href="#fn-a" role="doc-noteref">2</a>"##));
+
+        // Definitions are collected at the end, in reference order
+        let section = &html[html.find("<section class=\"footnotes\"").unwrap()..];
+        assert!(section.find(r#"id="fn-b""#).unwrap() < section.find(r#"id="fn-a""#).unwrap());
+        assert!(section.contains(
+            r##"Note B. <a href="#fnref-b" class="footnote-backref" role="doc-backlink" aria-label="Back to reference 1">↩</a> <a href="#fnref-b-2""##
+        ));
+        assert!(!html.contains("class=\"footnote\""));
+    }
+
+    #[test]
+    fn test_definition_list() {
+        let parser = MarkdownParser::new();
+        let (html, _) = parser.parse_body("Term\n: The definition.\n");
+
+        assert!(html.contains("<dl>"));
+        assert!(html.contains("<dt>Term</dt>"));
+        assert!(html.contains("<dd>The definition.</dd>"));
+    }
+
+    #[test]
+    fn test_abbreviations() {
+        let parser = MarkdownParser::new();
+        let (html, _) = parser.parse_body(
+            "HTML and HTML5 in XHTML, not `HTML`.\n\n*[HTML]: HyperText Markup Language\n*[HTML5]: HTML \"5\"\n",
+        );
+
+        assert!(html.contains(
+            r#"<abbr title="HyperText Markup Language">HTML</abbr> and <abbr title="HTML &quot;5&quot;">HTML5</abbr> in XHTML"#
+        ));
+        assert!(html.contains("<code>HTML</code>"));
+        assert!(!html.contains("*["));
+    }
+
+    #[test]
+    fn test_typography() {
+        let parser = MarkdownParser::new().with_typography(TypographyConfig {
+            smart_quotes: true,
+            dashes: true,
+            ..Default::default()
+        });
+        let (html, toc) =
+            parser.parse_body("# \"Quoted\" title\n\n\"*Hello*\" -- it's `\"code\"`...\n");
+
+        assert_eq!(toc[0].text, "\u{201C}Quoted\u{201D} title");
+        assert!(html.contains(
+            "<p>\u{201C}<em>Hello</em>\u{201D} \u{2013} it\u{2019}s <code>&quot;code&quot;</code>...</p>"
+        ));
+
+        let (plain, _) = MarkdownParser::new().parse_body("\"a\" -- b");
+        assert!(plain.contains("&quot;a&quot; -- b"));
+    }
+
+    #[test]
+    fn test_autolink_segments() {
+        assert_eq!(
+            autolink_segments("See https://x.com/a, thanks @alice-b!", true, true),
+            vec![
+                Segment::Text("See "),
+                Segment::Url("https://x.com/a"),
+                Segment::Text(", thanks "),
+                Segment::Mention("alice-b"),
+                Segment::Text("!"),
+            ]
+        );
+        assert_eq!(
+            autolink_segments("mail me@x.com or @ alone", false, true),
+            vec![Segment::Text("mail me@x.com or @ alone")]
+        );
+    }
+
+    #[test]
+    fn test_autolinks_and_mentions() {
+        let parser = MarkdownParser::new().with_config(MarkdownConfig {
+            autolink: true,
+            mention_url: Some("https://github.com/".to_string()),
+        });
+        let mut warnings = Vec::new();
+        let (html, _) = parser.render_markdown(
+            "Fixed by @octocat, see https://example.com/pr?id=1&x=2.\n\n[@linked](https://a.b) `@code`\n",
+            1,
+            &TypographyConfig::default(),
+            &mut warnings,
+        );
+
+        assert!(html.contains(
+            r#"Fixed by <a href="https://github.com/octocat" class="mention">@octocat</a>, see <a href="https://example.com/pr?id=1&amp;x=2">https://example.com/pr?id=1&amp;x=2</a>."#
+        ));
+        assert!(html.contains("@linked</a>"));
+        assert!(!html.contains("github.com/linked"));
+        assert!(html.contains("<code>@code</code>"));
+        assert!(warnings.is_empty());
+    }
+
+    #[test]
+    fn test_admonitions() {
+        let parser = MarkdownParser::new();
+        let (html, _) = parser.parse_body(
+            "> [!WARNING]\n> Back up *first*.\n\n> Plain quote\n\n:::tip Faster builds\n- Use `--no-cache` rarely\n:::\n",
+        );
+
+        assert!(html.contains(
+            "<aside class=\"admonition admonition-warning\" role=\"note\">\n<p class=\"admonition-title\">Warning</p>\n<p>Back up <em>first</em>.</p>\n</aside>"
+        ));
+        assert!(html.contains("<blockquote><p>Plain quote</p>"));
+        assert!(html.contains(
+            "<aside class=\"admonition admonition-tip\" role=\"note\">\n<p class=\"admonition-title\">Faster builds</p>"
+        ));
+        assert!(html.contains("<li>Use <code>--no-cache</code> rarely</li>"));
+        assert!(html.trim_end().ends_with("</aside>"));
+    }
+
+    #[test]
+    fn test_math() {
+        let parser = MarkdownParser::new();
+        let (html, _) = parser
+            .parse_body("Inline $a < b$ costs $5 or $10.\n\n$$\n\\sum_i x_i\n$$\n\n`$not math$`\n");
+
+        assert!(html.contains(r#"<span class="math inline">\(a &lt; b\)</span> costs $5 or $10."#));
+        assert!(html.contains("<p><span class=\"math display\">\\[\n\\sum_i x_i\n\\]</span></p>"));
+        assert!(html.contains("<code>$not math$</code>"));
+    }
+
+    #[test]
+    fn test_attributes() {
+        let parser = MarkdownParser::new();
+        let (html, toc) = parser.parse_body(
+            "# Setup {#install .wide data-x=1}\n\n![Logo](logo.png){.logo width=120 title=\"Our logo\"} and [Get it](/dl){#cta .btn .primary} now {not attrs}\n",
+        );
+
+        assert!(html.contains(r#"<h1 id="install" class="wide" data-x="1">Setup</h1>"#));
+        assert_eq!(toc[0].id, "install");
+        assert!(html.contains(r#"<img class="logo" width="120" title="Our logo" src="logo.png""#));
+        assert!(html.contains(r#"<a id="cta" class="btn primary" href="/dl">"#));
+        assert!(html.contains("</a> now {not attrs}"));
+        assert!(!html.contains("{.logo"));
+    }
+
+    #[test]
+    fn test_image_alt() {
+        let parser = MarkdownParser::new();
+        let (html, _) = parser.parse_body("![A *cat*](cat.png)");
+
+        assert!(
+            html.contains(r#"<img src="cat.png" loading="lazy" decoding="async" alt="A cat" />"#)
+        );
+    }
+
+    #[test]
+    fn test_shortcodes() {
+        let parser = MarkdownParser::new();
+        let content = "---\ntitle: \"S\"\n---\n\n{{< video clip.mp4 >}}\n\n{{< missing >}}\n";
+
+        let (parsed, warnings) = parser
+            .parse_with_warnings(content, Path::new("s.md"))
+            .unwrap();
+
+        assert!(parsed.html.contains(
+            r#"<figure class="media video"><video controls preload="metadata" playsinline><source src="clip.mp4" type="video/mp4">"#
+        ));
+        assert_eq!(warnings.len(), 1);
+        assert_eq!(warnings[0].line, 7);
+        assert_eq!(warnings[0].message, "unknown shortcode `missing`");
+    }
+
+    #[test]
+    fn test_include_shortcode() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::create_dir(dir.path().join("src")).unwrap();
+        let file = dir.path().join("src/lib.rs");
+        std::fs::write(&file, "mod a;\n\npub fn answer() -> u32 {\n    42\n}\n").unwrap();
+        let parser = MarkdownParser::new().with_include_root(dir.path());
+        let content = "---\ntitle: \"I\"\n---\n\n{{< include file=\"src/lib.rs\" lines=\"3-5\" >}}\n\n{{< missing >}}\n";
+
+        let (parsed, warnings) = parser
+            .parse_with_warnings(content, Path::new("i.md"))
+            .unwrap();
+
+        assert!(parsed.html.contains("<pre"));
+        assert!(parsed.html.contains("answer"));
+        assert!(!parsed.html.contains("mod a"));
+        assert_eq!(warnings.len(), 1);
+        assert_eq!(warnings[0].line, 7);
+    }
+
+    #[test]
+    fn test_parse_warnings() {
+        let parser = MarkdownParser::new();
+        let content = "---\ntitle: \"W\"\n---\n\nSee https://example.com.\n\n![](a.png)\n\n[ok](https://ok.example) and [missing][nope]\n\n`https://code.example`";
+
+        let (_, warnings) = parser
+            .parse_with_warnings(content, Path::new("w.md"))
+            .unwrap();
+        let lines: Vec<_> = warnings.iter().map(|w| w.line).collect();
+
+        assert_eq!(lines, vec![5, 7, 9]);
+        assert!(warnings[0].message.contains("'https://example.com'"));
+        assert!(warnings[1].message.contains("alt text"));
+        assert!(warnings[2].message.contains("'[nope]'"));
+    }
+
     #[test]
     fn test_no_frontmatter() {
         let parser = MarkdownParser::new();
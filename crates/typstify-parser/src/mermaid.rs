@@ -0,0 +1,62 @@
+//! Mermaid diagrams in code fences.
+//!
+//! A ```` ```mermaid ```` fence, in Markdown or Typst, is not highlighted
+//! but rendered as `<pre class="mermaid">` holding the diagram source, which
+//! the Mermaid script turns into an SVG in the browser (or the generator at
+//! build time).
+
+use typstify_core::{escape_html, unescape_html};
+
+/// Code fence language of Mermaid diagrams.
+pub const LANG: &str = "mermaid";
+
+/// Whether the code fence info string `info` is a Mermaid diagram.
+pub fn is_diagram(info: Option<&str>) -> bool {
+    info.and_then(|info| info.split_whitespace().next()) == Some(LANG)
+}
+
+/// HTML of the diagram with the source `source`.
+pub fn diagram_html(source: &str) -> String {
+    format!(
+        "<pre class=\"mermaid\">{}</pre>\n",
+        escape_html(source.trim_end())
+    )
+}
+
+/// Replace the `<pre><code data-lang="mermaid">` blocks of Typst's HTML
+/// export with diagrams.
+pub fn expand_typst_diagrams(html: &str) -> String {
+    const OPEN: &str = "<pre><code data-lang=\"mermaid\">";
+    const CLOSE: &str = "</code></pre>";
+
+    let mut output = String::with_capacity(html.len());
+    let mut rest = html;
+    while let Some(start) = rest.find(OPEN) {
+        let Some(len) = rest[start + OPEN.len()..].find(CLOSE) else {
+            break;
+        };
+        output.push_str(&rest[..start]);
+        let code = &rest[start + OPEN.len()..start + OPEN.len() + len];
+        output.push_str(diagram_html(&unescape_html(&code.replace("<br>", "\n"))).trim_end());
+        rest = &rest[start + OPEN.len() + len + CLOSE.len()..];
+    }
+    output.push_str(rest);
+    output
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_expand_typst_diagrams() {
+        let html = "<p>Flow:</p>\n<pre><code data-lang=\"mermaid\">graph TD<br>  A --> B &amp; \"C\"</code></pre>\n<pre><code data-lang=\"rust\">fn a() {}</code></pre>";
+        assert_eq!(
+            expand_typst_diagrams(html),
+            "<p>Flow:</p>\n<pre class=\"mermaid\">graph TD\n  A --&gt; B &amp; &quot;C&quot;</pre>\n<pre><code data-lang=\"rust\">fn a() {}</code></pre>"
+        );
+        assert!(is_diagram(Some("mermaid")));
+        assert!(!is_diagram(Some("rust")));
+        assert!(!is_diagram(None));
+    }
+}
@@ -0,0 +1,104 @@
+//! Plain-text extraction of Markdown prose.
+//!
+//! Used by content checks (spelling, external prose linters) that should see
+//! only what readers read. Frontmatter, code blocks, inline code, raw HTML
+//! and shortcodes are dropped, but line numbers are kept: line `n` of the
+//! extraction holds the prose of line `n` of the source file.
+
+use pulldown_cmark::{Event, Options, Parser, Tag, TagEnd, TextMergeWithOffset};
+use typstify_core::frontmatter::split_frontmatter;
+
+/// Extract the prose of a Markdown file (with optional frontmatter).
+#[must_use]
+pub fn plain_text(content: &str) -> String {
+    let body = split_frontmatter(content).map_or(content, |(_, _, body)| body);
+    let body_offset = content.len() - body.len();
+    let mut lines = vec![String::new(); content.lines().count().max(1)];
+    let mut code_depth = 0usize;
+
+    let parser = TextMergeWithOffset::new(Parser::new_ext(body, options()).into_offset_iter());
+    for (event, range) in parser {
+        let line = content[..body_offset + range.start].matches('\n').count();
+        match event {
+            Event::Start(Tag::CodeBlock(_) | Tag::HtmlBlock | Tag::MetadataBlock(_)) => {
+                code_depth += 1;
+            }
+            Event::End(TagEnd::CodeBlock | TagEnd::HtmlBlock | TagEnd::MetadataBlock(_)) => {
+                code_depth = code_depth.saturating_sub(1);
+            }
+            Event::Text(text) if code_depth == 0 => {
+                let text = strip_shortcodes(&text);
+                for (offset, chunk) in text.split('\n').enumerate() {
+                    if let Some(target) = lines.get_mut(line + offset) {
+                        target.push_str(chunk);
+                    }
+                }
+            }
+            // Keep the words around dropped inline content apart
+            Event::Code(_) | Event::InlineHtml(_) => {
+                if let Some(target) = lines.get_mut(line) {
+                    target.push(' ');
+                }
+            }
+            _ => {}
+        }
+    }
+
+    let mut text = lines
+        .iter()
+        .map(|line| line.trim_end())
+        .collect::<Vec<_>>()
+        .join("\n");
+    text.push('\n');
+    text
+}
+
+/// Markdown extensions recognised by the content parser.
+fn options() -> Options {
+    Options::ENABLE_TABLES
+        | Options::ENABLE_FOOTNOTES
+        | Options::ENABLE_STRIKETHROUGH
+        | Options::ENABLE_TASKLISTS
+        | Options::ENABLE_HEADING_ATTRIBUTES
+        | Options::ENABLE_DEFINITION_LIST
+}
+
+/// Remove `{{< ... >}}` shortcode invocations from text.
+fn strip_shortcodes(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    let mut rest = text;
+    while let Some(start) = rest.find("{{<") {
+        out.push_str(&rest[..start]);
+        match rest[start..].find(">}}") {
+            Some(end) => rest = &rest[start + end + 3..],
+            None => {
+                rest = "";
+                break;
+            }
+        }
+    }
+    out.push_str(rest);
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_plain_text_keeps_lines() {
+        let content = "---\ntitle: \"Skipped\"\n---\n\n# Heading\n\nSome **bold** and `code` text\nover two lines.\n\n```rust\nlet x = 1;\n```\n\n{{< video src=\"a.mp4\" >}}\nEnd <span>inline</span>.\n";
+
+        let text = plain_text(content);
+        let lines: Vec<&str> = text.lines().collect();
+
+        assert_eq!(lines.len(), content.lines().count());
+        assert_eq!(lines[4], "Heading");
+        assert_eq!(lines[6], "Some bold and   text");
+        assert_eq!(lines[7], "over two lines.");
+        assert_eq!(lines[10], "");
+        assert_eq!(lines[14], "End  inline .");
+        assert!(!text.contains("Skipped"));
+        assert!(!text.contains("video"));
+    }
+}
@@ -0,0 +1,839 @@
+//! Shortcode expansion.
+//!
+//! Shortcodes are `{{< name arg key="value" >}}` invocations in content
+//! that expand into HTML snippets before the content is rendered. Arguments
+//! may be positional or named (`key=value`, quoted when they contain spaces).
+//! Write `{{</* name */>}}` to show an invocation literally. Invocations
+//! inside fenced code blocks and inline code are left untouched.
+//!
+//! Built-in shortcodes:
+//!
+//! - `video` — `<video>` with `src`, `poster`, `caption`, `track`
+//!   (WebVTT captions), `srclang`, `preload`, `autoplay`, `loop`, `muted`
+//! - `audio` — `<audio>` with `src`, `caption`, `preload`, `autoplay`, `loop`
+//! - `gallery` — photo grid of the images in `dir` (relative to the page),
+//!   with an optional `caption` and `exif` (captions from the images' EXIF
+//!   descriptions). The grid and thumbnails are filled in by the build.
+//! - `include` — highlighted slice of a source file: `file` (relative to the
+//!   site root, which it may not leave), `lines` (`10-30`, `10-`, `-30` or
+//!   `12`) and `lang` (defaults to the file extension). Common indentation
+//!   is removed.
+//! - `data` — JSON fetched from `url` at build time and rendered through the
+//!   site template named by `template`. The build fills in the output.
+//! - `github` — card for the GitHub repository `repo` (`owner/name`) with its
+//!   description, stars, forks and language, rendered through the
+//!   `github_card` template from the API at build time like `data`.
+//!
+//! Other shortcodes are rendered by a [`ShortcodeRenderer`], such as the
+//! site's `templates/shortcodes/*.html`, which also takes precedence over
+//! the built-ins of the same name.
+
+use std::{
+    collections::BTreeMap,
+    fmt,
+    path::{Path, PathBuf},
+};
+
+use typstify_core::escape_html;
+
+const OPEN: &str = "{{<";
+const CLOSE: &str = ">}}";
+const ESCAPED_OPEN: &str = "{{</*";
+const ESCAPED_CLOSE: &str = "*/>}}";
+
+/// A parsed shortcode invocation.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Shortcode {
+    /// Shortcode name.
+    pub name: String,
+
+    /// Positional arguments in order.
+    pub positional: Vec<String>,
+
+    /// Named arguments.
+    pub named: BTreeMap<String, String>,
+}
+
+impl Shortcode {
+    /// Parse the inside of `{{< ... >}}`.
+    #[must_use]
+    pub fn parse(inner: &str) -> Option<Self> {
+        let mut tokens = tokenize(inner)?.into_iter();
+        let (name, None) = tokens.next()? else {
+            return None;
+        };
+
+        let mut shortcode = Self {
+            name,
+            ..Default::default()
+        };
+        for (key, value) in tokens {
+            match value {
+                Some(value) => {
+                    shortcode.named.insert(key, value);
+                }
+                None => shortcode.positional.push(key),
+            }
+        }
+        Some(shortcode)
+    }
+
+    /// Named argument, or the positional argument at `index` as a fallback.
+    #[must_use]
+    pub fn arg(&self, name: &str, index: usize) -> Option<&str> {
+        self.named
+            .get(name)
+            .or_else(|| self.positional.get(index))
+            .map(String::as_str)
+    }
+
+    /// Boolean flag, given either as `name=true` or as a bare positional.
+    #[must_use]
+    pub fn flag(&self, name: &str) -> bool {
+        match self.named.get(name) {
+            Some(value) => value == "true",
+            None => self.positional.iter().any(|p| p == name),
+        }
+    }
+}
+
+/// A shortcode that could not be expanded.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ShortcodeWarning {
+    /// 0-based line offset of the invocation within the expanded source.
+    pub line: usize,
+
+    /// Human-readable description.
+    pub message: String,
+}
+
+/// Renders code as HTML for the `include` shortcode, given the code and its
+/// language.
+pub type Highlight<'a> = &'a dyn Fn(&str, Option<&str>) -> String;
+
+/// Renders shortcodes that are not built in.
+pub trait ShortcodeRenderer: fmt::Debug + Send + Sync {
+    /// Render `shortcode` to HTML, or `None` if it is not one of this
+    /// renderer's. Errors are reported as warnings.
+    fn render(&self, shortcode: &Shortcode) -> Option<Result<String, String>>;
+}
+
+/// Expand all shortcodes in `source`, rendering included code as a plain
+/// `<pre><code>` block and resolving `include` paths against the working
+/// directory.
+///
+/// Unknown or malformed shortcodes are left in place and reported as
+/// warnings. Expansions never contain newlines, so line numbers of the
+/// surrounding content are preserved.
+#[must_use]
+pub fn expand(source: &str) -> (String, Vec<ShortcodeWarning>) {
+    expand_with(source, &plain_code, None, Path::new("."))
+}
+
+/// Expand all shortcodes in `source` like [`expand`], rendering included
+/// code with `highlight` and shortcodes that are not built in with `custom`.
+/// `include` paths are resolved against the site root `root`.
+#[must_use]
+pub fn expand_with(
+    source: &str,
+    highlight: Highlight<'_>,
+    custom: Option<&dyn ShortcodeRenderer>,
+    root: &Path,
+) -> (String, Vec<ShortcodeWarning>) {
+    expand_each(source, |shortcode| {
+        render(shortcode, highlight, custom, root)
+    })
+}
+
+/// Expand all shortcodes in `source`, outside code, with the output of
+/// `render`. Failed invocations are left in place and reported as warnings.
+pub(crate) fn expand_each(
+    source: &str,
+    mut render: impl FnMut(&Shortcode) -> Result<String, String>,
+) -> (String, Vec<ShortcodeWarning>) {
+    let mut output = String::with_capacity(source.len());
+    let mut warnings = Vec::new();
+    let mut fence: Option<&str> = None;
+
+    for (line_no, line) in source.split_inclusive('\n').enumerate() {
+        let trimmed = line.trim_start();
+        if let Some(marker) = fence {
+            if trimmed.starts_with(marker) {
+                fence = None;
+            }
+            output.push_str(line);
+            continue;
+        }
+        if let Some(marker) = ["```", "~~~"].into_iter().find(|m| trimmed.starts_with(m)) {
+            fence = Some(marker);
+            output.push_str(line);
+            continue;
+        }
+
+        expand_line(line, line_no, &mut render, &mut output, &mut warnings);
+    }
+
+    (output, warnings)
+}
+
+/// Expand shortcodes on a single line outside code blocks.
+fn expand_line(
+    line: &str,
+    line_no: usize,
+    render: &mut impl FnMut(&Shortcode) -> Result<String, String>,
+    output: &mut String,
+    warnings: &mut Vec<ShortcodeWarning>,
+) {
+    let mut rest = line;
+    let mut in_code = false;
+
+    while let Some(start) = rest.find(OPEN) {
+        let before = &rest[..start];
+        in_code ^= before.matches('`').count() % 2 == 1;
+        output.push_str(before);
+        let candidate = &rest[start..];
+
+        if in_code {
+            output.push_str(OPEN);
+            rest = &candidate[OPEN.len()..];
+            continue;
+        }
+
+        // Escaped invocation: emit it literally without the comment markers
+        if let Some(escaped) = candidate.strip_prefix(ESCAPED_OPEN)
+            && let Some(end) = escaped.find(ESCAPED_CLOSE)
+        {
+            output.push_str(OPEN);
+            output.push_str(&escaped[..end]);
+            output.push_str(CLOSE);
+            rest = &escaped[end + ESCAPED_CLOSE.len()..];
+            continue;
+        }
+
+        let Some(end) = candidate.find(CLOSE) else {
+            warnings.push(ShortcodeWarning {
+                line: line_no,
+                message: "unclosed shortcode".to_string(),
+            });
+            output.push_str(candidate);
+            return;
+        };
+
+        let invocation = &candidate[..end + CLOSE.len()];
+        let inner = &candidate[OPEN.len()..end];
+        match Shortcode::parse(inner)
+            .ok_or_else(|| "malformed shortcode".to_string())
+            .and_then(|sc| render(&sc))
+        {
+            Ok(html) => output.push_str(&html),
+            Err(message) => {
+                warnings.push(ShortcodeWarning {
+                    line: line_no,
+                    message,
+                });
+                output.push_str(invocation);
+            }
+        }
+        rest = &candidate[invocation.len()..];
+    }
+
+    output.push_str(rest);
+}
+
+/// Render a shortcode with `custom`, or as a built-in, resolving `include`
+/// paths against the site root `root`.
+pub(crate) fn render(
+    shortcode: &Shortcode,
+    highlight: Highlight<'_>,
+    custom: Option<&dyn ShortcodeRenderer>,
+    root: &Path,
+) -> Result<String, String> {
+    if let Some(result) = custom.and_then(|custom| custom.render(shortcode)) {
+        return result.map(|html| single_line(&html));
+    }
+
+    match shortcode.name.as_str() {
+        "include" => render_include(shortcode, highlight, root),
+        "data" => render_data(shortcode),
+        "github" => render_github(shortcode),
+        "video" => render_media(shortcode, "video"),
+        "audio" => render_media(shortcode, "audio"),
+        "gallery" => render_gallery(shortcode),
+        name => Err(format!("unknown shortcode `{name}`")),
+    }
+}
+
+/// Render a `<video>` or `<audio>` element wrapped in a `<figure>`.
+fn render_media(shortcode: &Shortcode, tag: &str) -> Result<String, String> {
+    let src = shortcode
+        .arg("src", 0)
+        .ok_or_else(|| format!("`{tag}` shortcode requires `src`"))?;
+
+    let preload = shortcode
+        .named
+        .get("preload")
+        .map_or("metadata", String::as_str);
+    if !matches!(preload, "none" | "metadata" | "auto") {
+        return Err(format!(
+            "`{tag}` preload must be `none`, `metadata` or `auto`, got `{preload}`"
+        ));
+    }
+
+    let mut attrs = format!(r#" controls preload="{preload}""#);
+    if tag == "video" {
+        if let Some(poster) = shortcode.named.get("poster") {
+            attrs.push_str(&format!(r#" poster="{}""#, escape_html(poster)));
+        }
+        attrs.push_str(" playsinline");
+    }
+    for flag in ["autoplay", "loop", "muted"] {
+        if shortcode.flag(flag) {
+            attrs.push(' ');
+            attrs.push_str(flag);
+        }
+    }
+
+    let mut html = format!(r#"<figure class="media {tag}"><{tag}{attrs}>"#);
+    html.push_str(&format!(r#"<source src="{}""#, escape_html(src)));
+    if let Some(mime) = media_type(src, tag) {
+        html.push_str(&format!(r#" type="{mime}""#));
+    }
+    html.push('>');
+    if let Some(track) = shortcode.named.get("track") {
+        let srclang = shortcode.named.get("srclang").map_or("en", String::as_str);
+        html.push_str(&format!(
+            r#"<track kind="captions" src="{}" srclang="{}" default>"#,
+            escape_html(track),
+            escape_html(srclang)
+        ));
+    }
+    html.push_str(&format!(
+        r#"<a href="{}">Download {tag}</a></{tag}>"#,
+        escape_html(src)
+    ));
+    if let Some(caption) = shortcode.named.get("caption") {
+        html.push_str(&format!(
+            "<figcaption>{}</figcaption>",
+            escape_html(caption)
+        ));
+    }
+    html.push_str("</figure>");
+
+    Ok(html)
+}
+
+/// Render the placeholder `<figure>` of a gallery; the build adds the
+/// images of its directory.
+fn render_gallery(shortcode: &Shortcode) -> Result<String, String> {
+    let dir = shortcode
+        .arg("dir", 0)
+        .ok_or_else(|| "`gallery` shortcode requires `dir`".to_string())?;
+
+    let mut html = format!(
+        r#"<figure class="gallery" data-gallery="{}""#,
+        escape_html(dir)
+    );
+    if shortcode.flag("exif") {
+        html.push_str(" data-exif");
+    }
+    html.push('>');
+    if let Some(caption) = shortcode.named.get("caption") {
+        html.push_str(&format!(
+            "<figcaption>{}</figcaption>",
+            escape_html(caption)
+        ));
+    }
+    html.push_str("</figure>");
+
+    Ok(html)
+}
+
+/// Render the placeholder of a `data` shortcode; the build replaces it with
+/// the rendered template.
+fn render_data(shortcode: &Shortcode) -> Result<String, String> {
+    let url = shortcode
+        .arg("url", 0)
+        .ok_or_else(|| "`data` shortcode requires `url`".to_string())?;
+    if !url.starts_with("https://") && !url.starts_with("http://") {
+        return Err(format!("`data` url must be http(s): `{url}`"));
+    }
+    let template = shortcode
+        .named
+        .get("template")
+        .ok_or_else(|| "`data` shortcode requires `template`".to_string())?;
+
+    Ok(format!(
+        r#"<div class="data" data-url="{}" data-template="{}"></div>"#,
+        escape_html(url),
+        escape_html(template)
+    ))
+}
+
+/// Render the placeholder of a `github` shortcode: a `data` placeholder for
+/// the repository's API URL and the `github_card` template.
+fn render_github(shortcode: &Shortcode) -> Result<String, String> {
+    let repo = shortcode
+        .arg("repo", 0)
+        .ok_or_else(|| "`github` shortcode requires `repo`".to_string())?;
+    let valid = |part: &str| {
+        !part.is_empty()
+            && part
+                .chars()
+                .all(|c| c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | '.'))
+    };
+    match repo.split_once('/') {
+        Some((owner, name)) if valid(owner) && valid(name) => Ok(format!(
+            r#"<div class="data" data-url="https://api.github.com/repos/{owner}/{name}" data-template="github_card"></div>"#
+        )),
+        _ => Err(format!("`github` repo must be `owner/name`: `{repo}`")),
+    }
+}
+
+/// Files of the `include` shortcodes in `source`, outside code, as written.
+#[must_use]
+pub fn includes(source: &str) -> Vec<String> {
+    let mut files = Vec::new();
+    expand_each(source, |shortcode| {
+        if shortcode.name == "include"
+            && let Some(file) = shortcode.arg("file", 0)
+        {
+            files.push(file.to_string());
+        }
+        Ok(String::new())
+    });
+    files
+}
+
+/// Resolve the `include` path `file` against `root`.
+///
+/// Content may come from remote sources, so absolute paths and paths that
+/// leave `root` (through `..` or symlinks) are rejected.
+pub fn resolve_include(root: &Path, file: &str) -> Result<PathBuf, String> {
+    if Path::new(file).is_absolute() {
+        return Err(format!("cannot include `{file}`: path must be relative"));
+    }
+    let root = root
+        .canonicalize()
+        .map_err(|e| format!("cannot include `{file}`: {e}"))?;
+    let path = root
+        .join(file)
+        .canonicalize()
+        .map_err(|e| format!("cannot include `{file}`: {e}"))?;
+    if !path.starts_with(&root) {
+        return Err(format!(
+            "cannot include `{file}`: path is outside the site root"
+        ));
+    }
+    Ok(path)
+}
+
+/// Render a slice of a file under `root` as a highlighted code block, on
+/// one line.
+fn render_include(
+    shortcode: &Shortcode,
+    highlight: Highlight<'_>,
+    root: &Path,
+) -> Result<String, String> {
+    let file = shortcode
+        .arg("file", 0)
+        .ok_or_else(|| "`include` shortcode requires `file`".to_string())?;
+    let source = std::fs::read_to_string(resolve_include(root, file)?)
+        .map_err(|e| format!("cannot include `{file}`: {e}"))?;
+
+    let lines: Vec<&str> = source.lines().collect();
+    let (start, end) = match shortcode.named.get("lines") {
+        Some(range) => line_range(range, lines.len())
+            .ok_or_else(|| format!("invalid `lines` range `{range}` for `{file}`"))?,
+        None => (1, lines.len()),
+    };
+    let code = dedent(&lines[start.saturating_sub(1)..end.min(lines.len())]);
+
+    let lang = shortcode.named.get("lang").map(String::as_str).or_else(|| {
+        std::path::Path::new(file)
+            .extension()
+            .and_then(|ext| ext.to_str())
+    });
+
+    Ok(highlight(&code, lang).replace('\n', "&#10;"))
+}
+
+/// Parse a 1-based inclusive line range (`10-30`, `10-`, `-30` or `12`)
+/// within a file of `len` lines; the end is clamped to the file.
+fn line_range(range: &str, len: usize) -> Option<(usize, usize)> {
+    let (start, end) = match range.split_once('-') {
+        Some((start, end)) => (start.trim(), end.trim()),
+        None => (range.trim(), range.trim()),
+    };
+    let start = if start.is_empty() {
+        1
+    } else {
+        start.parse().ok()?
+    };
+    let end = if end.is_empty() {
+        len
+    } else {
+        end.parse::<usize>().ok()?.min(len)
+    };
+    (start >= 1 && start <= end).then_some((start, end))
+}
+
+/// Join lines, removing the indentation they have in common.
+fn dedent(lines: &[&str]) -> String {
+    let indent = lines
+        .iter()
+        .filter(|line| !line.trim().is_empty())
+        .map(|line| line.len() - line.trim_start().len())
+        .min()
+        .unwrap_or(0);
+    let mut code = lines
+        .iter()
+        .map(|line| line.get(indent..).unwrap_or_default())
+        .collect::<Vec<_>>()
+        .join("\n");
+    code.push('\n');
+    code
+}
+
+/// Code as an unhighlighted `<pre><code>` block.
+pub(crate) fn plain_code(code: &str, lang: Option<&str>) -> String {
+    let class = lang.map_or(String::new(), |lang| {
+        format!(r#" class="language-{}""#, escape_html(lang))
+    });
+    format!("<pre><code{class}>{}</code></pre>", escape_html(code))
+}
+
+/// Join the lines of `html` with spaces, dropping indentation, so it can
+/// replace an invocation without moving the lines after it.
+fn single_line(html: &str) -> String {
+    html.lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty())
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// MIME type for a media file based on its extension.
+fn media_type(src: &str, tag: &str) -> Option<&'static str> {
+    let path = src.split(['?', '#']).next().unwrap_or(src);
+    let ext = path.rsplit_once('.')?.1.to_ascii_lowercase();
+    let mime = match (tag, ext.as_str()) {
+        ("video", "mp4" | "m4v") => "video/mp4",
+        ("video", "webm") => "video/webm",
+        ("video", "ogv" | "ogg") => "video/ogg",
+        ("video", "mov") => "video/quicktime",
+        ("audio", "mp3") => "audio/mpeg",
+        ("audio", "m4a" | "aac") => "audio/mp4",
+        ("audio", "ogg" | "oga" | "opus") => "audio/ogg",
+        ("audio", "wav") => "audio/wav",
+        ("audio", "flac") => "audio/flac",
+        ("audio", "webm") => "audio/webm",
+        _ => return None,
+    };
+    Some(mime)
+}
+
+/// Split shortcode arguments into `(key, value)` pairs; positional
+/// arguments have no value.
+fn tokenize(input: &str) -> Option<Vec<(String, Option<String>)>> {
+    let mut tokens = Vec::new();
+    let mut chars = input.trim().chars().peekable();
+
+    while chars.peek().is_some() {
+        while chars.next_if(|c| c.is_whitespace()).is_some() {}
+        if chars.peek().is_none() {
+            break;
+        }
+
+        let mut key = String::new();
+        let mut value = None;
+        if chars.peek() == Some(&'"') {
+            key = read_quoted(&mut chars)?;
+        } else {
+            while let Some(c) = chars.next_if(|c| !c.is_whitespace() && *c != '=') {
+                key.push(c);
+            }
+            if chars.next_if_eq(&'=').is_some() {
+                value = Some(if chars.peek() == Some(&'"') {
+                    read_quoted(&mut chars)?
+                } else {
+                    let mut value = String::new();
+                    while let Some(c) = chars.next_if(|c| !c.is_whitespace()) {
+                        value.push(c);
+                    }
+                    value
+                });
+            }
+        }
+        if key.is_empty() {
+            return None;
+        }
+        tokens.push((key, value));
+    }
+
+    Some(tokens)
+}
+
+/// Read a double-quoted string, supporting `\"` and `\\` escapes.
+fn read_quoted(chars: &mut std::iter::Peekable<std::str::Chars<'_>>) -> Option<String> {
+    chars.next();
+    let mut value = String::new();
+    loop {
+        match chars.next()? {
+            '"' => return Some(value),
+            '\\' => value.push(chars.next()?),
+            c => value.push(c),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_shortcode() {
+        let sc = Shortcode::parse(r#" video clip.mp4 caption="A \"short\" clip" muted "#).unwrap();
+        assert_eq!(sc.name, "video");
+        assert_eq!(sc.positional, vec!["clip.mp4", "muted"]);
+        assert_eq!(sc.arg("src", 0), Some("clip.mp4"));
+        assert_eq!(sc.named["caption"], r#"A "short" clip"#);
+        assert!(sc.flag("muted"));
+        assert!(!sc.flag("loop"));
+
+        assert!(Shortcode::parse("").is_none());
+        assert!(Shortcode::parse(r#"video src="unterminated"#).is_none());
+    }
+
+    #[test]
+    fn test_expand_video() {
+        let (html, warnings) = expand(
+            r#"Intro {{< video src="clip.webm" poster="poster.jpg" caption="Demo" track="subs.vtt" preload="none" >}}"#,
+        );
+        assert!(warnings.is_empty());
+        assert!(html.starts_with(r#"Intro <figure class="media video"><video controls preload="none" poster="poster.jpg" playsinline>"#));
+        assert!(html.contains(r#"<source src="clip.webm" type="video/webm">"#));
+        assert!(html.contains(r#"<track kind="captions" src="subs.vtt" srclang="en" default>"#));
+        assert!(html.ends_with("<figcaption>Demo</figcaption></figure>"));
+    }
+
+    #[test]
+    fn test_expand_audio() {
+        let (html, warnings) = expand("{{< audio episode.mp3 loop >}}\n");
+        assert!(warnings.is_empty());
+        assert_eq!(
+            html,
+            "<figure class=\"media audio\"><audio controls preload=\"metadata\" loop><source src=\"episode.mp3\" type=\"audio/mpeg\"><a href=\"episode.mp3\">Download audio</a></audio></figure>\n"
+        );
+    }
+
+    #[test]
+    fn test_expand_gallery() {
+        let (html, warnings) =
+            expand(r#"{{< gallery "photos/trip" exif caption="Alps & lakes" >}}"#);
+        assert!(warnings.is_empty());
+        assert_eq!(
+            html,
+            r#"<figure class="gallery" data-gallery="photos/trip" data-exif><figcaption>Alps &amp; lakes</figcaption></figure>"#
+        );
+
+        let (_, warnings) = expand("{{< gallery >}}");
+        assert_eq!(warnings[0].message, "`gallery` shortcode requires `dir`");
+    }
+
+    #[test]
+    fn test_expand_data() {
+        let (html, warnings) = expand(
+            r#"{{< data url="https://api.github.com/repos/a/b?x=1&y=2" template="stars" >}}"#,
+        );
+        assert!(warnings.is_empty());
+        assert_eq!(
+            html,
+            r#"<div class="data" data-url="https://api.github.com/repos/a/b?x=1&amp;y=2" data-template="stars"></div>"#
+        );
+
+        let (_, warnings) = expand(r#"{{< data url="file:///etc/passwd" template="t" >}}"#);
+        assert_eq!(
+            warnings[0].message,
+            "`data` url must be http(s): `file:///etc/passwd`"
+        );
+        let (_, warnings) = expand(r#"{{< data "https://example.com/a.json" >}}"#);
+        assert_eq!(warnings[0].message, "`data` shortcode requires `template`");
+    }
+
+    #[test]
+    fn test_expand_github() {
+        let (html, warnings) = expand(r#"{{< github "longcipher/typstify" >}}"#);
+        assert!(warnings.is_empty());
+        assert_eq!(
+            html,
+            r#"<div class="data" data-url="https://api.github.com/repos/longcipher/typstify" data-template="github_card"></div>"#
+        );
+
+        let (_, warnings) = expand(r#"{{< github repo="typstify" >}}"#);
+        assert_eq!(
+            warnings[0].message,
+            "`github` repo must be `owner/name`: `typstify`"
+        );
+        let (_, warnings) = expand(r#"{{< github repo="a/b?x=1" >}}"#);
+        assert_eq!(warnings.len(), 1);
+        let (_, warnings) = expand("{{< github >}}");
+        assert_eq!(warnings[0].message, "`github` shortcode requires `repo`");
+    }
+
+    #[test]
+    fn test_expand_include() {
+        let dir = tempfile::tempdir().unwrap();
+        let file = dir.path().join("main.rs");
+        std::fs::write(
+            &file,
+            "use std::io;\n\nfn main() {\n    let x = 1 < 2;\n\n    println!(\"{x}\");\n}\n",
+        )
+        .unwrap();
+
+        let include = |inner: &str, highlight: Highlight<'_>| {
+            render_include(&Shortcode::parse(inner).unwrap(), highlight, dir.path())
+        };
+
+        assert_eq!(
+            include(r#"include file="main.rs" lines="4-6""#, &plain_code).unwrap(),
+            r#"<pre><code class="language-rs">let x = 1 &lt; 2;&#10;&#10;println!(&quot;{x}&quot;);&#10;</code></pre>"#
+        );
+
+        let highlight =
+            |code: &str, lang: Option<&str>| format!("[{}|{code}]", lang.unwrap_or_default());
+        assert_eq!(
+            include(r#"include "main.rs" lines="-1" lang="rust""#, &highlight).unwrap(),
+            "[rust|use std::io;&#10;]"
+        );
+    }
+
+    #[test]
+    fn test_include_warnings() {
+        let (_, warnings) = expand(r#"{{< include file="does/not/exist.rs" >}}"#);
+        assert!(
+            warnings[0]
+                .message
+                .starts_with("cannot include `does/not/exist.rs`")
+        );
+
+        let (_, warnings) = expand(r#"{{< include file="Cargo.toml" lines="9-3" >}}"#);
+        assert_eq!(
+            warnings[0].message,
+            "invalid `lines` range `9-3` for `Cargo.toml`"
+        );
+    }
+
+    #[test]
+    fn test_include_outside_root() {
+        let site = tempfile::tempdir().unwrap();
+        let root = site.path().join("site");
+        std::fs::create_dir(&root).unwrap();
+        std::fs::write(site.path().join("secret.txt"), "secret").unwrap();
+        let secret = site.path().join("secret.txt").canonicalize().unwrap();
+
+        assert_eq!(
+            resolve_include(&root, "../secret.txt").unwrap_err(),
+            "cannot include `../secret.txt`: path is outside the site root"
+        );
+        assert_eq!(
+            resolve_include(&root, &secret.display().to_string()).unwrap_err(),
+            format!(
+                "cannot include `{}`: path must be relative",
+                secret.display()
+            )
+        );
+
+        let (html, warnings) = expand(r#"{{< include file="/etc/passwd" >}}"#);
+        assert_eq!(html, r#"{{< include file="/etc/passwd" >}}"#);
+        assert_eq!(
+            warnings[0].message,
+            "cannot include `/etc/passwd`: path must be relative"
+        );
+    }
+
+    #[test]
+    fn test_includes() {
+        assert_eq!(
+            includes(
+                "{{< include file=\"a.rs\" >}}\n```\n{{< include b.rs >}}\n```\n{{< include \"c.rs\" lines=\"1\" >}}"
+            ),
+            vec!["a.rs", "c.rs"]
+        );
+    }
+
+    #[test]
+    fn test_line_range() {
+        assert_eq!(line_range("10-30", 100), Some((10, 30)));
+        assert_eq!(line_range("10-", 20), Some((10, 20)));
+        assert_eq!(line_range("-5", 20), Some((1, 5)));
+        assert_eq!(line_range("12", 20), Some((12, 12)));
+        assert_eq!(line_range("15-40", 20), Some((15, 20)));
+        assert_eq!(line_range("0-3", 20), None);
+        assert_eq!(line_range("30-40", 20), None);
+        assert_eq!(line_range("a-b", 20), None);
+    }
+
+    #[test]
+    fn test_expand_skips_code_and_escapes() {
+        let source =
+            "```\n{{< video a.mp4 >}}\n```\n`{{< video a.mp4 >}}` and {{</* video a.mp4 */>}}\n";
+        let (html, warnings) = expand(source);
+        assert!(warnings.is_empty());
+        assert_eq!(
+            html,
+            "```\n{{< video a.mp4 >}}\n```\n`{{< video a.mp4 >}}` and {{< video a.mp4 >}}\n"
+        );
+    }
+
+    /// Renders `youtube` and `video` shortcodes from fixed templates.
+    #[derive(Debug)]
+    struct Templates;
+
+    impl ShortcodeRenderer for Templates {
+        fn render(&self, shortcode: &Shortcode) -> Option<Result<String, String>> {
+            match shortcode.name.as_str() {
+                "youtube" => Some(
+                    shortcode
+                        .arg("id", 0)
+                        .map(|id| format!("<div class=\"embed\">\n    <iframe src=\"{id}\"></iframe>\n</div>\n"))
+                        .ok_or_else(|| "missing id".to_string()),
+                ),
+                "video" => Some(Ok("<p>custom</p>".to_string())),
+                _ => None,
+            }
+        }
+    }
+
+    #[test]
+    fn test_expand_custom() {
+        let (html, warnings) = expand_with(
+            "{{< youtube abc >}}\n{{< video a.mp4 >}} {{< audio a.mp3 >}}\n{{< youtube >}}\n",
+            &plain_code,
+            Some(&Templates),
+            Path::new("."),
+        );
+        assert_eq!(
+            html.lines().next(),
+            Some(r#"<div class="embed"> <iframe src="abc"></iframe> </div>"#)
+        );
+        assert!(html.contains("<p>custom</p> <figure class=\"media audio\">"));
+        assert!(html.ends_with("{{< youtube >}}\n"));
+        assert_eq!(warnings.len(), 1);
+        assert_eq!(warnings[0].line, 2);
+        assert_eq!(warnings[0].message, "missing id");
+    }
+
+    #[test]
+    fn test_expand_warnings() {
+        let (html, warnings) =
+            expand("one\n{{< nope >}} {{< video preload=eager a.mp4 >}}\n{{< video");
+        assert_eq!(
+            html,
+            "one\n{{< nope >}} {{< video preload=eager a.mp4 >}}\n{{< video"
+        );
+        let lines: Vec<_> = warnings.iter().map(|w| w.line).collect();
+        assert_eq!(lines, vec![1, 1, 2]);
+        assert_eq!(warnings[0].message, "unknown shortcode `nope`");
+        assert_eq!(warnings[2].message, "unclosed shortcode");
+    }
+}
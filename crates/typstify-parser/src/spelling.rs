@@ -0,0 +1,222 @@
+//! Spell checking for Markdown content.
+//!
+//! Words are checked against a bundled English word list plus any
+//! project-specific words. Only [prose](crate::prose) is checked; URLs,
+//! paths, acronyms (`HTML`) and mixed-case identifiers (`GitHub`,
+//! `camelCase`) are skipped too.
+
+use std::{collections::HashSet, sync::LazyLock};
+
+use crate::prose::plain_text;
+
+/// Bundled English word list, one lowercase word per line.
+const BUNDLED: &str = include_str!("../dictionary/en.txt");
+
+static DICTIONARY: LazyLock<HashSet<&'static str>> = LazyLock::new(|| {
+    BUNDLED
+        .lines()
+        .filter(|line| !line.is_empty() && !line.starts_with('#'))
+        .collect()
+});
+
+/// Suffixes stripped to find a dictionary base word, with the ending that
+/// replaces them (`tries` → `try`, `making` → `make`).
+const SUFFIXES: &[(&str, &str)] = &[
+    ("'s", ""),
+    ("s", ""),
+    ("es", ""),
+    ("ies", "y"),
+    ("ed", ""),
+    ("ed", "e"),
+    ("ied", "y"),
+    ("ing", ""),
+    ("ing", "e"),
+    ("ly", ""),
+    ("ily", "y"),
+    ("er", ""),
+    ("er", "e"),
+    ("ers", ""),
+    ("est", ""),
+];
+
+/// A word not found in the dictionary.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Misspelling {
+    /// The word as written.
+    pub word: String,
+
+    /// 1-based line number in the source file.
+    pub line: usize,
+}
+
+/// Checks prose against the bundled dictionary and a project word list.
+#[derive(Debug, Clone, Default)]
+pub struct SpellChecker {
+    words: HashSet<String>,
+}
+
+impl SpellChecker {
+    /// Create a checker using only the bundled dictionary.
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Accept additional words (case-insensitive).
+    #[must_use]
+    pub fn with_words<I, S>(mut self, words: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: AsRef<str>,
+    {
+        self.words
+            .extend(words.into_iter().map(|w| normalize(w.as_ref())));
+        self
+    }
+
+    /// Accept the words of a word list file: one word per line, blank lines
+    /// and `#` comments ignored.
+    #[must_use]
+    pub fn with_word_list(self, list: &str) -> Self {
+        self.with_words(
+            list.lines()
+                .map(str::trim)
+                .filter(|line| !line.is_empty() && !line.starts_with('#')),
+        )
+    }
+
+    /// Whether a word is spelled correctly.
+    #[must_use]
+    pub fn is_known(&self, word: &str) -> bool {
+        let word = normalize(word);
+        if self.lookup(&word) {
+            return true;
+        }
+
+        SUFFIXES.iter().any(|(suffix, ending)| {
+            word.strip_suffix(suffix).is_some_and(|stem| {
+                let base = format!("{stem}{ending}");
+                self.lookup(&base)
+                    // Doubled final consonant: `running` → `run`
+                    || (ending.is_empty() && undouble(stem).is_some_and(|s| self.lookup(s)))
+            })
+        })
+    }
+
+    /// Check a Markdown file (with optional frontmatter), returning unknown
+    /// words with their line numbers.
+    #[must_use]
+    pub fn check_markdown(&self, content: &str) -> Vec<Misspelling> {
+        let mut misspellings = Vec::new();
+
+        for (index, line) in plain_text(content).lines().enumerate() {
+            for token in line.split_whitespace() {
+                if is_reference(token) {
+                    continue;
+                }
+                for word in token.split(|c: char| !(c.is_alphanumeric() || is_apostrophe(c))) {
+                    let word = word.trim_matches(is_apostrophe);
+                    if should_check(word) && !self.is_known(word) {
+                        misspellings.push(Misspelling {
+                            word: word.to_string(),
+                            line: index + 1,
+                        });
+                    }
+                }
+            }
+        }
+
+        misspellings
+    }
+
+    fn lookup(&self, word: &str) -> bool {
+        DICTIONARY.contains(word) || self.words.contains(word)
+    }
+}
+
+fn is_apostrophe(c: char) -> bool {
+    matches!(c, '\'' | '\u{2019}')
+}
+
+fn normalize(word: &str) -> String {
+    word.replace('\u{2019}', "'").to_lowercase()
+}
+
+/// `runn` → `run` when the last two letters are the same consonant.
+fn undouble(stem: &str) -> Option<&str> {
+    let mut chars = stem.chars().rev();
+    let (last, prev) = (chars.next()?, chars.next()?);
+    (last == prev && !"aeiou".contains(last)).then(|| &stem[..stem.len() - last.len_utf8()])
+}
+
+/// URLs, email addresses, paths and file names are not prose.
+fn is_reference(token: &str) -> bool {
+    let token = token.trim_matches(|c: char| !c.is_alphanumeric());
+    token.contains("://")
+        || token.contains('@')
+        || token.contains('/')
+        || token.contains('\\')
+        || token.contains('_')
+        || token.starts_with("www.")
+        || token
+            .split_once('.')
+            .is_some_and(|(a, b)| !a.is_empty() && b.starts_with(|c: char| c.is_alphanumeric()))
+}
+
+/// Whether a word is checked at all: ASCII letters only, no acronyms or
+/// mixed-case identifiers.
+fn should_check(word: &str) -> bool {
+    let letters = word.chars().filter(|c| !is_apostrophe(*c));
+    if word.chars().count() < 2 || !letters.clone().all(|c| c.is_ascii_alphabetic()) {
+        return false;
+    }
+    // Acronyms and identifiers like `GitHub` or `camelCase`
+    !letters.skip(1).any(|c| c.is_ascii_uppercase())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn words(misspellings: &[Misspelling]) -> Vec<(&str, usize)> {
+        misspellings
+            .iter()
+            .map(|m| (m.word.as_str(), m.line))
+            .collect()
+    }
+
+    #[test]
+    fn test_known_words() {
+        let checker = SpellChecker::new();
+        for word in [
+            "the",
+            "Because",
+            "running",
+            "tries",
+            "making",
+            "don't",
+            "it\u{2019}s",
+        ] {
+            assert!(checker.is_known(word), "{word}");
+        }
+        assert!(!checker.is_known("teh"));
+        assert!(!checker.is_known("recieve"));
+    }
+
+    #[test]
+    fn test_check_markdown() {
+        let content = "---\ntitle: \"Nto checked\"\n---\n\n# Helo world\n\nThe `inlinee` code, https://exmaple.com and config_fiel.toml.\n\n```rust\nlet wrod = 1;\n```\n\nA secnod paragraph with HTML and GitHub.\n{{< video src=\"clpi.mp4\" >}}\n";
+
+        let misspellings = SpellChecker::new().check_markdown(content);
+
+        assert_eq!(words(&misspellings), vec![("Helo", 5), ("secnod", 13)]);
+    }
+
+    #[test]
+    fn test_word_list() {
+        let checker = SpellChecker::new().with_word_list("# project words\nTypstify\n\nhelo\n");
+        let misspellings = checker.check_markdown("Typstify says helo.\n");
+
+        assert!(misspellings.is_empty(), "{misspellings:?}");
+    }
+}
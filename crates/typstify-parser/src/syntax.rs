@@ -1,7 +1,54 @@
 //! Syntax highlighting for code blocks.
+//!
+//! By default code is highlighted with CSS classes (`hl-keyword`,
+//! `hl-string`, ...) rather than inline colors, and [`SyntaxHighlighter::css`]
+//! styles them with a light and a dark theme, scoped like the site theme:
+//! `data-theme` on the root element when set, `prefers-color-scheme`
+//! otherwise. Code blocks then switch with the rest of the page. A fixed
+//! theme ([`SyntaxHighlighter::new`]) highlights with inline colors instead.
+//!
+//! Code block info strings take options after the language ([`CodeInfo`]):
+//! ```` ```rust {1,3-5 linenos} ```` highlights lines 1 and 3 to 5 and
+//! numbers the lines. Each line is then wrapped in a `<span class="line">`
+//! (`line highlighted` for highlighted lines), and the `<pre>` of numbered
+//! blocks has the `line-numbers` class.
+//!
+//! Besides syntect's bundled syntaxes and themes, `.sublime-syntax` and
+//! `.tmTheme` files in `build.syntaxes_dir` ([`SyntaxHighlighter::load_dir`])
+//! are loaded, for languages syntect doesn't know; themes are named after
+//! their file.
 
-use syntect::{highlighting::ThemeSet, html::highlighted_html_for_string, parsing::SyntaxSet};
+use std::{fs, path::Path};
+
+use syntect::{
+    easy::HighlightLines,
+    highlighting::{Color, Theme, ThemeSet},
+    html::{
+        ClassStyle, ClassedHTMLGenerator, IncludeBackground, css_for_theme_with_class_style,
+        styled_line_to_highlighted_html,
+    },
+    parsing::{SyntaxDefinition, SyntaxReference, SyntaxSet},
+    util::LinesWithEndings,
+};
 use thiserror::Error;
+use typstify_core::{config::BuildConfig, escape_html};
+
+/// Prefix of the highlighting classes; the `<pre>` of a code block is
+/// `hl-code`.
+pub const CLASS_PREFIX: &str = "hl-";
+
+/// Default theme in light mode.
+pub const DEFAULT_LIGHT_THEME: &str = "InspiredGitHub";
+
+/// Default theme in dark mode.
+pub const DEFAULT_DARK_THEME: &str = "base16-ocean.dark";
+
+/// `build.syntax_theme` value for the default light and dark themes.
+pub const AUTO_THEME: &str = "auto";
+
+const CLASS_STYLE: ClassStyle = ClassStyle::SpacedPrefixed {
+    prefix: CLASS_PREFIX,
+};
 
 /// Syntax highlighting errors.
 #[derive(Debug, Error)]
@@ -9,6 +56,10 @@ pub enum SyntaxError {
     /// Failed to highlight code.
     #[error("syntax highlighting failed: {0}")]
     Highlight(String),
+
+    /// Failed to load a syntax definition or theme.
+    #[error("failed to load {0}: {1}")]
+    Load(String, String),
 }
 
 /// Syntax highlighter using syntect.
@@ -17,78 +68,397 @@ pub struct SyntaxHighlighter {
     syntax_set: SyntaxSet,
     theme_set: ThemeSet,
     default_theme: String,
+    /// Theme in dark mode; highlighting uses classes when set.
+    dark_theme: Option<String>,
+    /// Whether code blocks are numbered unless they opt out.
+    line_numbers: bool,
 }
 
 impl Default for SyntaxHighlighter {
     fn default() -> Self {
-        Self::new("base16-ocean.dark")
+        Self::adaptive(DEFAULT_LIGHT_THEME, DEFAULT_DARK_THEME)
     }
 }
 
 impl SyntaxHighlighter {
-    /// Create a new syntax highlighter with the specified theme.
+    /// Create a new syntax highlighter with the specified theme, fixed
+    /// whatever the site theme.
     pub fn new(theme: &str) -> Self {
         Self {
             syntax_set: SyntaxSet::load_defaults_newlines(),
             theme_set: ThemeSet::load_defaults(),
             default_theme: theme.to_string(),
+            dark_theme: None,
+            line_numbers: false,
+        }
+    }
+
+    /// Create the highlighter configured by `build.syntaxes_dir`,
+    /// `build.syntax_theme`, `build.syntax_theme_dark` and
+    /// `build.line_numbers`. Unknown themes are ignored, and so is the
+    /// syntaxes directory if it fails to load.
+    pub fn from_config(build: &BuildConfig) -> Self {
+        Self::try_from_config(build).unwrap_or_else(|_| Self::default().configured(build))
+    }
+
+    /// Like [`Self::from_config`], but fails if the syntaxes directory
+    /// fails to load.
+    pub fn try_from_config(build: &BuildConfig) -> Result<Self, SyntaxError> {
+        let mut highlighter = Self::default();
+        let dir = Path::new(&build.syntaxes_dir);
+        if dir.is_dir() {
+            highlighter.load_dir(dir)?;
+        }
+        Ok(highlighter.configured(build))
+    }
+
+    /// The highlighter with the themes and line numbers of `build`.
+    fn configured(mut self, build: &BuildConfig) -> Self {
+        let theme = build.syntax_theme.as_str();
+        let dark = build
+            .syntax_theme_dark
+            .as_deref()
+            .filter(|dark| self.has_theme(dark));
+        match (theme, dark) {
+            (AUTO_THEME, None) => {}
+            (AUTO_THEME, Some(dark)) => self.set_themes(DEFAULT_LIGHT_THEME, dark),
+            (light, Some(dark)) => self.set_themes(light, dark),
+            (theme, None) => self.set_theme(theme),
+        }
+        self.line_numbers = build.line_numbers;
+        self
+    }
+
+    /// Load the `.sublime-syntax` and `.tmTheme` files in `dir` (not its
+    /// subdirectories), returning how many were loaded. Themes are named
+    /// after their file (`dir/nord.tmTheme` is `nord`). Nothing is loaded
+    /// if any of the files fails to.
+    pub fn load_dir(&mut self, dir: &Path) -> Result<usize, SyntaxError> {
+        let load_error = |path: &Path, e: &dyn std::fmt::Display| {
+            SyntaxError::Load(path.display().to_string(), e.to_string())
+        };
+        let mut paths: Vec<_> = fs::read_dir(dir)
+            .map_err(|e| load_error(dir, &e))?
+            .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+            .filter(|path| path.is_file())
+            .collect();
+        paths.sort();
+
+        let mut syntaxes = Vec::new();
+        let mut themes = Vec::new();
+        for path in paths {
+            let Some(stem) = path.file_stem().and_then(|s| s.to_str()) else {
+                continue;
+            };
+            match path.extension().and_then(|e| e.to_str()) {
+                Some("sublime-syntax") => {
+                    let source = fs::read_to_string(&path).map_err(|e| load_error(&path, &e))?;
+                    let syntax = SyntaxDefinition::load_from_str(&source, true, Some(stem))
+                        .map_err(|e| load_error(&path, &e))?;
+                    syntaxes.push(syntax);
+                }
+                Some("tmTheme") => {
+                    let theme = ThemeSet::get_theme(&path).map_err(|e| load_error(&path, &e))?;
+                    themes.push((stem.to_string(), theme));
+                }
+                _ => {}
+            }
+        }
+
+        let loaded = syntaxes.len() + themes.len();
+        if !syntaxes.is_empty() {
+            let mut builder = std::mem::take(&mut self.syntax_set).into_builder();
+            syntaxes.into_iter().for_each(|syntax| builder.add(syntax));
+            self.syntax_set = builder.build();
+        }
+        self.theme_set.themes.extend(themes);
+        Ok(loaded)
+    }
+
+    /// Create a syntax highlighter following the site theme, with `light`
+    /// and `dark` themes styled by [`Self::css`].
+    pub fn adaptive(light: &str, dark: &str) -> Self {
+        Self {
+            dark_theme: Some(dark.to_string()),
+            ..Self::new(light)
         }
     }
 
+    /// Whether code is highlighted with classes following the site theme.
+    pub fn is_adaptive(&self) -> bool {
+        self.dark_theme.is_some()
+    }
+
+    /// Number code blocks unless they opt out with `nolinenos`.
+    pub fn set_line_numbers(&mut self, line_numbers: bool) {
+        self.line_numbers = line_numbers;
+    }
+
     /// Get available theme names.
     pub fn available_themes(&self) -> Vec<&str> {
         self.theme_set.themes.keys().map(|s| s.as_str()).collect()
     }
 
-    /// Highlight code with the given language.
+    /// Whether there is a theme named `name`.
+    pub fn has_theme(&self, name: &str) -> bool {
+        self.theme_set.themes.contains_key(name)
+    }
+
+    /// Highlight code with the code block info string `info`: a language
+    /// and [`CodeInfo`] options.
     ///
-    /// If the language is not recognized, returns the code wrapped in a `<pre><code>` block.
-    pub fn highlight(&self, code: &str, lang: Option<&str>) -> String {
-        let syntax = lang
+    /// If the language is not recognized, returns the code wrapped in a
+    /// `<pre><code>` block.
+    pub fn highlight(&self, code: &str, info: Option<&str>) -> String {
+        let info = CodeInfo::parse(info.unwrap_or_default());
+        let syntax = info
+            .lang
             .and_then(|l| self.syntax_set.find_syntax_by_token(l))
             .or_else(|| self.syntax_set.find_syntax_by_extension("txt"));
 
-        let theme = self
-            .theme_set
-            .themes
-            .get(&self.default_theme)
-            .or_else(|| self.theme_set.themes.values().next());
-
-        match (syntax, theme) {
-            (Some(syntax), Some(theme)) => {
-                match highlighted_html_for_string(code, &self.syntax_set, syntax, theme) {
-                    Ok(html) => html,
-                    Err(_) => self.fallback_highlight(code, lang),
-                }
+        let adaptive = self.is_adaptive();
+        let theme = self.theme(&self.default_theme);
+        let background = theme
+            .and_then(|theme| theme.settings.background)
+            .unwrap_or(Color::WHITE);
+        let highlighted = syntax.and_then(|syntax| match theme {
+            _ if adaptive => self.classed_html(code, syntax).ok(),
+            Some(theme) => self.styled_html(code, syntax, theme, background).ok(),
+            None => None,
+        });
+
+        let mut classes = Vec::new();
+        let mut style = String::new();
+        if highlighted.is_some() {
+            if adaptive {
+                classes.push(format!("{CLASS_PREFIX}code"));
+            } else {
+                style = format!(
+                    " style=\"background-color:#{:02x}{:02x}{:02x};\"",
+                    background.r, background.g, background.b
+                );
             }
-            _ => self.fallback_highlight(code, lang),
         }
-    }
+        let html = highlighted.unwrap_or_else(|| escape_html(code));
 
-    /// Fallback highlighting when syntect fails.
-    fn fallback_highlight(&self, code: &str, lang: Option<&str>) -> String {
-        let escaped = html_escape(code);
-        let lang_class = lang
-            .map(|l| format!(" class=\"language-{l}\""))
+        let line_numbers = info.line_numbers.unwrap_or(self.line_numbers);
+        if line_numbers {
+            classes.push("line-numbers".to_string());
+        }
+        let class = if classes.is_empty() {
+            String::new()
+        } else {
+            format!(" class=\"{}\"", classes.join(" "))
+        };
+
+        let html = if line_numbers || !info.highlight.is_empty() {
+            wrap_lines(&html, |line| info.is_highlighted(line))
+        } else {
+            html
+        };
+        let lang_class = info
+            .lang
+            .map(|l| format!(" class=\"language-{}\"", escape_html(l)))
             .unwrap_or_default();
-        format!("<pre><code{lang_class}>{escaped}</code></pre>")
+        format!("<pre{class}{style}><code{lang_class}>{html}</code></pre>")
     }
 
-    /// Set the default theme.
+    /// Code highlighted with classes.
+    fn classed_html(&self, code: &str, syntax: &SyntaxReference) -> Result<String, SyntaxError> {
+        let mut generator =
+            ClassedHTMLGenerator::new_with_class_style(syntax, &self.syntax_set, CLASS_STYLE);
+        for line in LinesWithEndings::from(code) {
+            generator
+                .parse_html_for_line_which_includes_newline(line)
+                .map_err(|e| SyntaxError::Highlight(e.to_string()))?;
+        }
+        Ok(generator.finalize())
+    }
+
+    /// Code highlighted with the inline colors of `theme`.
+    fn styled_html(
+        &self,
+        code: &str,
+        syntax: &SyntaxReference,
+        theme: &Theme,
+        background: Color,
+    ) -> Result<String, SyntaxError> {
+        let mut highlighter = HighlightLines::new(syntax, theme);
+        let mut html = String::with_capacity(code.len() * 4);
+        for line in LinesWithEndings::from(code) {
+            let regions = highlighter
+                .highlight_line(line, &self.syntax_set)
+                .map_err(|e| SyntaxError::Highlight(e.to_string()))?;
+            html.push_str(
+                &styled_line_to_highlighted_html(
+                    &regions,
+                    IncludeBackground::IfDifferent(background),
+                )
+                .map_err(|e| SyntaxError::Highlight(e.to_string()))?,
+            );
+        }
+        Ok(html)
+    }
+
+    /// Stylesheet for class-highlighted code: the light theme when the root
+    /// element has `data-theme="light"`, the dark theme for
+    /// `data-theme="dark"`, and without `data-theme` the one matching
+    /// `prefers-color-scheme`. `None` for a fixed theme, whose colors are
+    /// inline.
+    pub fn css(&self) -> Option<String> {
+        let light = self.theme(&self.default_theme)?;
+        let dark = self.theme(self.dark_theme.as_deref()?)?;
+
+        Some(format!(
+            "{}\n{}\n@media (prefers-color-scheme: light) {{\n{}}}\n\n@media (prefers-color-scheme: dark) {{\n{}}}\n",
+            scoped_css(light, "[data-theme=\"light\"]")?,
+            scoped_css(dark, "[data-theme=\"dark\"]")?,
+            scoped_css(light, ":root:not([data-theme=\"dark\"])")?,
+            scoped_css(dark, ":root:not([data-theme=\"light\"])")?,
+        ))
+    }
+
+    /// Theme named `name`, or any theme if there is none.
+    fn theme(&self, name: &str) -> Option<&Theme> {
+        self.theme_set
+            .themes
+            .get(name)
+            .or_else(|| self.theme_set.themes.values().next())
+    }
+
+    /// Set a fixed theme, highlighting with inline colors.
     pub fn set_theme(&mut self, theme: &str) {
         if self.theme_set.themes.contains_key(theme) {
             self.default_theme = theme.to_string();
+            self.dark_theme = None;
+        }
+    }
+
+    /// Set the light and dark themes, highlighting with classes.
+    pub fn set_themes(&mut self, light: &str, dark: &str) {
+        if self.theme_set.themes.contains_key(light) && self.theme_set.themes.contains_key(dark) {
+            self.default_theme = light.to_string();
+            self.dark_theme = Some(dark.to_string());
         }
     }
 }
 
-/// Escape HTML special characters.
-fn html_escape(s: &str) -> String {
-    s.replace('&', "&amp;")
-        .replace('<', "&lt;")
-        .replace('>', "&gt;")
-        .replace('"', "&quot;")
-        .replace('\'', "&#x27;")
+/// Options of a code block info string, after the language:
+/// ```` ```rust {1,3-5 linenos} ````.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct CodeInfo<'a> {
+    /// Language.
+    pub lang: Option<&'a str>,
+
+    /// Highlighted lines, as inclusive 1-based ranges.
+    pub highlight: Vec<(usize, usize)>,
+
+    /// `linenos` or `nolinenos`, overriding `build.line_numbers`.
+    pub line_numbers: Option<bool>,
+}
+
+impl<'a> CodeInfo<'a> {
+    /// Parse an info string. Unknown options are ignored.
+    pub fn parse(info: &'a str) -> Self {
+        let (lang, options) = match info.split_once('{') {
+            Some((lang, options)) => (lang, options.trim_end().trim_end_matches('}')),
+            None => (info, ""),
+        };
+        let mut parsed = Self {
+            lang: lang.split_whitespace().next(),
+            ..Self::default()
+        };
+
+        for option in options.split([',', ' ']).filter(|o| !o.is_empty()) {
+            match option {
+                "linenos" => parsed.line_numbers = Some(true),
+                "nolinenos" => parsed.line_numbers = Some(false),
+                range => {
+                    let (start, end) = range.split_once('-').unwrap_or((range, range));
+                    if let (Ok(start), Ok(end)) = (start.parse(), end.parse()) {
+                        parsed.highlight.push((start, end));
+                    }
+                }
+            }
+        }
+        parsed
+    }
+
+    /// Whether line `line` (1-based) is highlighted.
+    pub fn is_highlighted(&self, line: usize) -> bool {
+        self.highlight
+            .iter()
+            .any(|(start, end)| (*start..=*end).contains(&line))
+    }
+}
+
+/// Wrap each line of highlighted `html` in a `<span class="line">`, closing
+/// the spans open at the end of a line and reopening them on the next.
+fn wrap_lines(html: &str, highlighted: impl Fn(usize) -> bool) -> String {
+    let line_start = |line: usize| {
+        if highlighted(line) {
+            "<span class=\"line highlighted\">"
+        } else {
+            "<span class=\"line\">"
+        }
+    };
+
+    let mut output = String::with_capacity(html.len() * 2);
+    let mut open: Vec<&str> = Vec::new();
+    let mut line = 1;
+    output.push_str(line_start(line));
+
+    let mut rest = html;
+    while let Some(i) = rest.find(['<', '\n']) {
+        output.push_str(&rest[..i]);
+        rest = &rest[i..];
+        if let Some(after) = rest.strip_prefix('\n') {
+            output.push_str(&"</span>".repeat(open.len() + 1));
+            output.push('\n');
+            // Spans closed after the last line are closed already
+            if after.trim_start_matches("</span>").is_empty() {
+                return output;
+            }
+            rest = after;
+            line += 1;
+            output.push_str(line_start(line));
+            open.iter().for_each(|tag| output.push_str(tag));
+        } else {
+            let end = rest.find('>').map_or(rest.len(), |end| end + 1);
+            let tag = &rest[..end];
+            if tag.starts_with("</") {
+                open.pop();
+            } else {
+                open.push(tag);
+            }
+            output.push_str(tag);
+            rest = &rest[end..];
+        }
+    }
+    output.push_str(rest);
+    output.push_str("</span>");
+    output
+}
+
+/// The class stylesheet of `theme` with every selector under `scope`.
+fn scoped_css(theme: &Theme, scope: &str) -> Option<String> {
+    let css = css_for_theme_with_class_style(theme, CLASS_STYLE).ok()?;
+    let mut scoped = String::with_capacity(css.len() * 2);
+    for line in css.lines() {
+        match line.strip_suffix(" {") {
+            Some(selectors) if !line.starts_with(' ') => {
+                let selectors: Vec<String> = selectors
+                    .split(", ")
+                    .map(|selector| format!("{scope} {selector}"))
+                    .collect();
+                scoped.push_str(&selectors.join(", "));
+                scoped.push_str(" {");
+            }
+            _ => scoped.push_str(line),
+        }
+        scoped.push('\n');
+    }
+    Some(scoped)
 }
 
 #[cfg(test)]
@@ -126,8 +496,155 @@ mod tests {
 
     #[test]
     fn test_html_escape() {
-        assert_eq!(html_escape("<script>"), "&lt;script&gt;");
-        assert_eq!(html_escape("a & b"), "a &amp; b");
+        assert_eq!(escape_html("<script>"), "&lt;script&gt;");
+        assert_eq!(escape_html("a & b"), "a &amp; b");
+    }
+
+    #[test]
+    fn test_adaptive_highlight() {
+        let highlighter = SyntaxHighlighter::default();
+        assert!(highlighter.is_adaptive());
+        let html = highlighter.highlight("let x = \"<a>\";", Some("rust"));
+
+        assert!(html.starts_with("<pre class=\"hl-code\"><code class=\"language-rust\">"));
+        assert!(html.contains("class=\"hl-"));
+        assert!(html.contains("&lt;a&gt;"));
+        assert!(!html.contains("style="));
+
+        let css = highlighter.css().unwrap();
+        assert!(css.contains("[data-theme=\"light\"] .hl-code {"));
+        assert!(css.contains("[data-theme=\"dark\"] .hl-code {"));
+        assert!(css.contains("@media (prefers-color-scheme: dark) {"));
+        assert!(css.contains(":root:not([data-theme=\"light\"]) .hl-comment"));
+        // Every rule is scoped
+        for line in css.lines().filter(|line| line.ends_with(" {")) {
+            for selector in line.trim_end_matches(" {").split(", ") {
+                assert!(
+                    selector.starts_with('[')
+                        || selector.starts_with(":root")
+                        || selector.starts_with('@'),
+                    "unscoped selector {selector}"
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_fixed_theme() {
+        let mut highlighter = SyntaxHighlighter::default();
+        highlighter.set_theme("base16-ocean.dark");
+        assert!(!highlighter.is_adaptive());
+        assert!(highlighter.css().is_none());
+
+        let html = highlighter.highlight("fn main() {}", Some("rust"));
+        assert!(html.contains("<pre style=\"background-color:"));
+    }
+
+    #[test]
+    fn test_code_info() {
+        let info = CodeInfo::parse("rust {1,3-5 linenos}");
+        assert_eq!(info.lang, Some("rust"));
+        assert_eq!(info.highlight, vec![(1, 1), (3, 5)]);
+        assert_eq!(info.line_numbers, Some(true));
+        assert!(info.is_highlighted(4));
+        assert!(!info.is_highlighted(2));
+
+        assert_eq!(CodeInfo::parse("{nolinenos}").lang, None);
+        assert_eq!(CodeInfo::parse("{nolinenos}").line_numbers, Some(false));
+        assert_eq!(CodeInfo::parse("python").highlight, vec![]);
+    }
+
+    #[test]
+    fn test_line_numbers_and_highlight() {
+        let highlighter = SyntaxHighlighter::default();
+        let html = highlighter.highlight("/* a\nb */\nlet x = 1;\n", Some("rust {2}"));
+
+        assert!(html.starts_with(
+            "<pre class=\"hl-code\"><code class=\"language-rust\"><span class=\"line\">"
+        ));
+        assert_eq!(html.matches("<span class=\"line").count(), 3);
+        assert_eq!(
+            html.matches("<span").count(),
+            html.matches("</span>").count()
+        );
+        // The comment continues on the second line, which is highlighted
+        assert!(html.contains("<span class=\"line highlighted\"><span class=\"hl-source hl-rust\"><span class=\"hl-comment"));
+
+        let html = highlighter.highlight("a\nb", Some("text {linenos}"));
+        assert_eq!(
+            html,
+            "<pre class=\"hl-code line-numbers\"><code class=\"language-text\"><span class=\"line\"><span class=\"hl-text hl-plain\">a</span></span>\n<span class=\"line\"><span class=\"hl-text hl-plain\">b</span></span></code></pre>"
+        );
+
+        let mut build = BuildConfig {
+            syntax_theme: "base16-ocean.dark".to_string(),
+            line_numbers: true,
+            ..BuildConfig::default()
+        };
+        let highlighter = SyntaxHighlighter::from_config(&build);
+        assert!(!highlighter.is_adaptive());
+        let html = highlighter.highlight("fn main() {}", Some("rust {nolinenos}"));
+        assert!(html.starts_with(
+            "<pre style=\"background-color:#2b303b;\"><code class=\"language-rust\"><span"
+        ));
+        assert!(!html.contains("class=\"line"));
+        assert!(
+            highlighter
+                .highlight("x", None)
+                .starts_with("<pre class=\"line-numbers\"")
+        );
+
+        build.syntax_theme_dark = Some("Solarized (dark)".to_string());
+        assert!(SyntaxHighlighter::from_config(&build).is_adaptive());
+    }
+
+    #[test]
+    fn test_load_dir() {
+        let dir = tempfile::TempDir::new().unwrap();
+        fs::write(
+            dir.path().join("move.sublime-syntax"),
+            "%YAML 1.2\n---\nname: Move\nfile_extensions: [move]\nscope: source.move\ncontexts:\n  main:\n    - match: '\\b(module|fun)\\b'\n      scope: keyword.control.move\n",
+        )
+        .unwrap();
+        fs::write(
+            dir.path().join("mono.tmTheme"),
+            r#"<?xml version="1.0" encoding="UTF-8"?>
+<plist version="1.0"><dict>
+<key>name</key><string>Mono</string>
+<key>settings</key><array><dict><key>settings</key><dict>
+<key>background</key><string>#101010</string>
+<key>foreground</key><string>#EEEEEE</string>
+</dict></dict></array>
+</dict></plist>"#,
+        )
+        .unwrap();
+        fs::write(dir.path().join("README.md"), "Syntaxes").unwrap();
+
+        let build = BuildConfig {
+            syntaxes_dir: dir.path().display().to_string(),
+            syntax_theme: "mono".to_string(),
+            ..BuildConfig::default()
+        };
+        let highlighter = SyntaxHighlighter::try_from_config(&build).unwrap();
+        assert!(highlighter.has_theme("mono"));
+        let html = highlighter.highlight("module m { fun f() {} }", Some("move"));
+        assert!(html.starts_with("<pre style=\"background-color:#101010;\">"));
+        assert!(html.contains("module"));
+        assert!(
+            highlighter
+                .syntax_set
+                .find_syntax_by_token("move")
+                .is_some()
+        );
+
+        fs::write(dir.path().join("broken.sublime-syntax"), "name: [").unwrap();
+        let mut highlighter = SyntaxHighlighter::default();
+        assert!(matches!(
+            highlighter.load_dir(dir.path()),
+            Err(SyntaxError::Load(path, _)) if path.ends_with("broken.sublime-syntax")
+        ));
+        assert!(!highlighter.has_theme("mono"));
+        assert!(!SyntaxHighlighter::from_config(&build).has_theme("mono"));
     }
 
     #[test]
@@ -0,0 +1,168 @@
+//! Smart typography for rendered text.
+//!
+//! Converts straight quotes, `--`/`---` and `...` into their typographic
+//! forms according to a [`TypographyConfig`]. Quote direction depends on the
+//! preceding character, which is tracked across text runs so quotes around
+//! inline markup (`"*emphasis*"`) are paired correctly.
+
+use typstify_core::config::TypographyConfig;
+
+/// Applies smart typography to consecutive text runs of a document.
+#[derive(Debug)]
+pub struct SmartPunctuation<'a> {
+    config: &'a TypographyConfig,
+    prev: Option<char>,
+}
+
+impl<'a> SmartPunctuation<'a> {
+    /// Create a converter for the given settings.
+    #[must_use]
+    pub fn new(config: &'a TypographyConfig) -> Self {
+        Self { config, prev: None }
+    }
+
+    /// Whether any conversion is enabled.
+    #[must_use]
+    pub fn enabled(&self) -> bool {
+        self.config.smart_quotes || self.config.dashes || self.config.ellipsis
+    }
+
+    /// Start a new block; the next quote opens.
+    pub fn reset(&mut self) {
+        self.prev = None;
+    }
+
+    /// Record text that was emitted without conversion (e.g. inline code).
+    pub fn skip(&mut self, text: &str) {
+        if let Some(c) = text.chars().next_back() {
+            self.prev = Some(c);
+        }
+    }
+
+    /// Convert a run of plain text.
+    pub fn apply(&mut self, text: &str) -> String {
+        if !self.enabled() {
+            self.skip(text);
+            return text.to_string();
+        }
+
+        let [open_double, close_double, open_single, close_single] = self.config.quotes.marks();
+        let mut out = String::with_capacity(text.len());
+        let mut chars = text.char_indices().peekable();
+
+        while let Some((i, c)) = chars.next() {
+            let rest = &text[i..];
+            let replacement = if self.config.dashes && rest.starts_with("---") {
+                chars.nth(1);
+                "\u{2014}"
+            } else if self.config.dashes && rest.starts_with("--") {
+                chars.next();
+                "\u{2013}"
+            } else if self.config.ellipsis && rest.starts_with("...") {
+                chars.nth(1);
+                "\u{2026}"
+            } else if self.config.smart_quotes && c == '"' {
+                if self.opens() {
+                    open_double
+                } else {
+                    close_double
+                }
+            } else if self.config.smart_quotes && c == '\'' {
+                let next = chars.peek().map(|(_, n)| *n);
+                if self.prev.is_some_and(char::is_alphanumeric)
+                    && next.is_some_and(char::is_alphanumeric)
+                {
+                    // Apostrophe inside a word
+                    "\u{2019}"
+                } else if self.opens() {
+                    open_single
+                } else {
+                    close_single
+                }
+            } else {
+                out.push(c);
+                self.prev = Some(c);
+                continue;
+            };
+
+            out.push_str(replacement);
+            self.prev = replacement.chars().next_back();
+        }
+
+        out
+    }
+
+    /// Whether a quote at the current position opens.
+    fn opens(&self) -> bool {
+        let marks = self.config.quotes.marks();
+        match self.prev {
+            None => true,
+            Some(c) => {
+                c.is_whitespace()
+                    || matches!(c, '(' | '[' | '{' | '\u{2013}' | '\u{2014}')
+                    || [marks[0], marks[2]].iter().any(|m| m.ends_with(c))
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use typstify_core::config::QuoteStyle;
+
+    use super::*;
+
+    fn all(quotes: QuoteStyle) -> TypographyConfig {
+        TypographyConfig {
+            smart_quotes: true,
+            dashes: true,
+            ellipsis: true,
+            quotes,
+        }
+    }
+
+    #[test]
+    fn test_english() {
+        let config = all(QuoteStyle::English);
+        let mut smart = SmartPunctuation::new(&config);
+        assert_eq!(
+            smart.apply(r#"He said "don't" -- 'twice'... --- done"#),
+            "He said \u{201C}don\u{2019}t\u{201D} \u{2013} \u{2018}twice\u{2019}\u{2026} \u{2014} done"
+        );
+    }
+
+    #[test]
+    fn test_quotes_across_runs() {
+        let config = all(QuoteStyle::German);
+        let mut smart = SmartPunctuation::new(&config);
+        let open = smart.apply("Er sagte \"");
+        let inner = smart.apply("hallo");
+        let close = smart.apply("\".");
+        assert_eq!(
+            format!("{open}{inner}{close}"),
+            "Er sagte \u{201E}hallo\u{201C}."
+        );
+    }
+
+    #[test]
+    fn test_french_and_japanese() {
+        let config = all(QuoteStyle::French);
+        assert_eq!(
+            SmartPunctuation::new(&config).apply(r#""Bonjour""#),
+            "\u{AB}\u{202F}Bonjour\u{202F}\u{BB}"
+        );
+
+        let config = all(QuoteStyle::Japanese);
+        assert_eq!(
+            SmartPunctuation::new(&config).apply(r#""こんにちは""#),
+            "\u{300C}こんにちは\u{300D}"
+        );
+    }
+
+    #[test]
+    fn test_disabled() {
+        let config = TypographyConfig::default();
+        let text = r#""a" -- b..."#;
+        assert_eq!(SmartPunctuation::new(&config).apply(text), text);
+    }
+}
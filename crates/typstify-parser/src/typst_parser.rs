@@ -1,17 +1,40 @@
 //! Typst parser for converting Typst documents to HTML.
 //!
-//! This module provides Typst document parsing with frontmatter extraction
-//! and TOC generation. The actual Typst compilation requires setting up
-//! a proper TypstWorld which is deferred to the generator phase.
+//! Documents are compiled with the Typst compiler in a [`TypstWorld`] and
+//! exported through Typst's HTML export. Headings get `id` anchors and make
+//! up the table of contents.
+//!
+//! Shortcodes are expanded too: each invocation is compiled as an empty
+//! placeholder `<span>` that is replaced with the shortcode's HTML after
+//! export. Invocations that fail are left as text.
 
-use std::path::Path;
+use std::{
+    path::{Path, PathBuf},
+    sync::Arc,
+};
 
 use thiserror::Error;
+use typst::{diag::Warned, syntax::Source};
+use typst_html::HtmlDocument;
 use typstify_core::{
-    content::{ParsedContent, TocEntry},
-    frontmatter::parse_typst_frontmatter,
+    config::Tz,
+    content::{HeadingAnchors, ParsedContent, TocEntry},
+    escape_html,
+    frontmatter::parse_typst_frontmatter_with_timezone,
+    unescape_html,
+};
+
+use crate::{
+    diagnostic::{Diagnostic, render_all},
+    mermaid,
+    shortcode::{self, ShortcodeRenderer},
+    typst_world::TypstWorld,
 };
 
+/// Attribute of the `<span>` compiled in place of a shortcode, holding its
+/// index. A `<span>` stays inline, within the paragraph around it.
+const PLACEHOLDER: &str = "data-typstify-shortcode";
+
 /// Typst parsing errors.
 #[derive(Debug, Error)]
 pub enum TypstError {
@@ -19,29 +42,39 @@ pub enum TypstError {
     #[error("frontmatter error: {0}")]
     Frontmatter(#[from] typstify_core::error::CoreError),
 
-    /// Typst compilation error.
-    #[error("typst compilation failed: {0}")]
-    Compilation(String),
+    /// Typst syntax errors with source locations.
+    #[error("{}", render_all(.0))]
+    Syntax(Vec<Diagnostic>),
+
+    /// Typst compilation errors with source locations.
+    #[error("{}", render_all(.0))]
+    Compilation(Vec<Diagnostic>),
 
-    /// SVG rendering error.
-    #[error("SVG rendering failed: {0}")]
-    Render(String),
+    /// HTML export errors with source locations.
+    #[error("{}", render_all(.0))]
+    Export(Vec<Diagnostic>),
 }
 
 /// Result type for Typst operations.
 pub type Result<T> = std::result::Result<T, TypstError>;
 
-/// Typst parser that extracts frontmatter and prepares content for compilation.
+/// Typst parser that extracts frontmatter and compiles documents to HTML.
 ///
-/// Note: Full Typst compilation requires a proper World implementation with
-/// file system access and font loading. This parser focuses on:
-/// - Extracting frontmatter from Typst comment syntax
-/// - Extracting TOC from heading patterns
-/// - Preparing content for later compilation
+/// Imports and files are read relative to the document's directory; package
+/// imports are not available.
 #[derive(Debug)]
 pub struct TypstParser {
     /// Whether to extract TOC from headings.
     extract_toc: bool,
+
+    /// Timezone for frontmatter dates without an offset.
+    timezone: Tz,
+
+    /// Renderer for shortcodes that are not built in.
+    shortcodes: Option<Arc<dyn ShortcodeRenderer>>,
+
+    /// Site root that `include` shortcode paths are resolved against.
+    include_root: PathBuf,
 }
 
 impl Default for TypstParser {
@@ -53,84 +86,234 @@ impl Default for TypstParser {
 impl TypstParser {
     /// Create a new Typst parser.
     pub fn new() -> Self {
-        Self { extract_toc: true }
+        Self {
+            extract_toc: true,
+            timezone: Tz::UTC,
+            shortcodes: None,
+            include_root: PathBuf::from("."),
+        }
+    }
+
+    /// Set the timezone used for frontmatter dates without an offset.
+    #[must_use]
+    pub fn with_timezone(mut self, timezone: Tz) -> Self {
+        self.timezone = timezone;
+        self
+    }
+
+    /// Set the renderer for shortcodes that are not built in.
+    #[must_use]
+    pub fn with_shortcodes(mut self, shortcodes: Arc<dyn ShortcodeRenderer>) -> Self {
+        self.shortcodes = Some(shortcodes);
+        self
+    }
+
+    /// Set the site root that `include` shortcode paths are resolved
+    /// against (the working directory by default).
+    #[must_use]
+    pub fn with_include_root(mut self, root: impl Into<PathBuf>) -> Self {
+        self.include_root = root.into();
+        self
     }
 
     /// Parse a Typst document with frontmatter.
     ///
-    /// This extracts frontmatter and TOC but does not perform full compilation.
-    /// The HTML field will contain the raw Typst source wrapped in a code block
-    /// for preview, or can be compiled later with a proper World implementation.
+    /// The whole file is compiled (the frontmatter is made of comments), so
+    /// diagnostics point at lines in the file on disk.
     pub fn parse(&self, content: &str, path: &Path) -> Result<ParsedContent> {
         // Parse frontmatter from Typst comments
-        let (frontmatter, body) = parse_typst_frontmatter(content, path)?;
+        let (frontmatter, body) =
+            parse_typst_frontmatter_with_timezone(content, path, self.timezone)?;
 
-        // Extract TOC from source
-        let toc = if self.extract_toc {
-            self.extract_toc_from_source(&body)
-        } else {
-            Vec::new()
-        };
+        // Replace shortcodes with placeholders; lines are kept, so
+        // diagnostics still point at the file on disk
+        let (content, shortcodes) = self.extract_shortcodes(content);
 
-        // For now, wrap the Typst source in a placeholder
-        // Full compilation will be done in the generator with proper World setup
-        let html = format!(
-            "<div class=\"typst-source\" data-path=\"{}\">\n<pre><code class=\"language-typst\">{}</code></pre>\n</div>",
-            path.display(),
-            html_escape(&body)
-        );
+        // Reject documents with syntax errors, pointing at the offending source
+        let diagnostics = syntax_diagnostics(&content, path);
+        if !diagnostics.is_empty() {
+            return Err(TypstError::Syntax(diagnostics));
+        }
+
+        let world = TypstWorld::new(path, content);
+        let Warned { output, .. } = typst::compile::<HtmlDocument>(&world);
+        let document =
+            output.map_err(|errors| TypstError::Compilation(world.diagnostics(&errors)))?;
+        let html = typst_html::html(&document)
+            .map_err(|errors| TypstError::Export(world.diagnostics(&errors)))?;
+
+        let (html, toc) = anchor_headings(body_html(&html));
+        let html = mermaid::expand_typst_diagrams(&insert_shortcodes(html, &shortcodes));
 
         Ok(ParsedContent {
             frontmatter,
             html,
             raw: body,
-            toc,
+            toc: if self.extract_toc { toc } else { Vec::new() },
+            dependencies: world.dependencies(),
         })
     }
+}
 
-    /// Extract TOC entries from Typst source (simple heuristic).
-    fn extract_toc_from_source(&self, content: &str) -> Vec<TocEntry> {
-        let mut toc = Vec::new();
-
-        for line in content.lines() {
-            let trimmed = line.trim();
-
-            // Match Typst headings: = Title, == Subtitle, etc.
-            if let Some(heading) = parse_typst_heading(trimmed) {
-                toc.push(heading);
-            }
+impl TypstParser {
+    /// Replace the shortcodes in `content` with placeholder elements,
+    /// returning the new source and the HTML of each placeholder.
+    fn extract_shortcodes(&self, content: &str) -> (String, Vec<String>) {
+        if !content.contains("{{<") {
+            return (content.to_string(), Vec::new());
         }
 
-        toc
+        let mut rendered = Vec::new();
+        let (source, _) = shortcode::expand_each(content, |sc| {
+            let html = shortcode::render(
+                sc,
+                &shortcode::plain_code,
+                self.shortcodes.as_deref(),
+                &self.include_root,
+            )?;
+            rendered.push(html);
+            Ok(format!(
+                r#"#html.elem("span", attrs: ("{PLACEHOLDER}": "{}"));"#,
+                rendered.len() - 1
+            ))
+        });
+        (source, rendered)
     }
 }
 
-/// Parse a Typst heading line into a TocEntry.
-fn parse_typst_heading(line: &str) -> Option<TocEntry> {
-    if !line.starts_with('=') {
-        return None;
+/// Replace the shortcode placeholders in exported `html` with their HTML,
+/// unwrapping placeholders that make up a whole paragraph.
+fn insert_shortcodes(html: String, shortcodes: &[String]) -> String {
+    let mut html = html;
+    for (index, shortcode) in shortcodes.iter().enumerate() {
+        let element = format!(r#"<span {PLACEHOLDER}="{index}"></span>"#);
+        let paragraph = format!("<p>{element}</p>");
+        html = if html.contains(&paragraph) {
+            html.replacen(&paragraph, shortcode, 1)
+        } else {
+            html.replacen(&element, shortcode, 1)
+        };
     }
+    html
+}
 
-    // Count the number of = at the start
-    let level = line.chars().take_while(|c| *c == '=').count();
-    if level == 0 || level > 6 {
-        return None;
+/// Collect Typst syntax errors as located diagnostics.
+///
+/// The whole file is parsed (frontmatter comments included) so reported
+/// line numbers match the file on disk.
+pub fn syntax_diagnostics(content: &str, path: &Path) -> Vec<Diagnostic> {
+    let source = Source::detached(content);
+    source
+        .root()
+        .errors()
+        .into_iter()
+        .map(|error| {
+            let range = source.range(error.span).unwrap_or(0..0);
+            Diagnostic::new(path, content, range, error.message.as_str())
+                .with_hints(error.hints.iter().map(|h| h.as_str()))
+        })
+        .collect()
+}
+
+/// The contents of `<body>` in an exported HTML document.
+fn body_html(document: &str) -> &str {
+    let Some(start) = document.find("<body>") else {
+        return document;
+    };
+    let body = &document[start + "<body>".len()..];
+    body.rfind("</body>")
+        .map_or(body, |end| &body[..end])
+        .trim()
+}
+
+/// Give headings without an `id` a unique one derived from their text and
+/// collect them as TOC entries.
+///
+/// Typst exports `=` headings as `<h2>` (the page title is the `<h1>`), so
+/// TOC levels are one less than the tag's.
+fn anchor_headings(html: &str) -> (String, Vec<TocEntry>) {
+    let mut output = String::with_capacity(html.len());
+    let mut toc = Vec::new();
+    let mut anchors = HeadingAnchors::default();
+    let mut rest = html;
+
+    while let Some(start) = rest.find("<h") {
+        let (before, tag) = rest.split_at(start);
+        output.push_str(before);
+
+        let Some((level, attrs, inner, after)) = split_heading(tag) else {
+            output.push_str("<h");
+            rest = &tag[2..];
+            continue;
+        };
+
+        let text = unescape_html(&strip_tags(inner))
+            .split_whitespace()
+            .collect::<Vec<_>>()
+            .join(" ");
+        let id = if let Some(id) = attr_value(attrs, "id") {
+            anchors.reserve(id);
+            output.push_str(&format!("<h{level}{attrs}>"));
+            id.to_string()
+        } else {
+            let id = anchors.unique(&slugify(&text));
+            output.push_str(&format!(r#"<h{level}{attrs} id="{}">"#, escape_html(&id)));
+            id
+        };
+        output.push_str(inner);
+        output.push_str(&format!("</h{level}>"));
+
+        toc.push(TocEntry::new(level - 1, text, id));
+        rest = after;
     }
+    output.push_str(rest);
+
+    (output, toc)
+}
 
-    // Extract the heading text
-    let text = line[level..].trim().to_string();
-    if text.is_empty() {
+/// Split `<hN attrs>inner</hN>rest` (N = 2–6) into its parts.
+fn split_heading(tag: &str) -> Option<(u8, &str, &str, &str)> {
+    let level = tag
+        .as_bytes()
+        .get(2)
+        .filter(|b| (b'2'..=b'6').contains(*b))?
+        - b'0';
+    let open_end = tag.find('>')?;
+    let attrs = &tag[3..open_end];
+    if !(attrs.is_empty() || attrs.starts_with(' ')) {
         return None;
     }
+    let close = format!("</h{level}>");
+    let content = &tag[open_end + 1..];
+    let inner_end = content.find(&close)?;
+    Some((
+        level,
+        attrs,
+        &content[..inner_end],
+        &content[inner_end + close.len()..],
+    ))
+}
 
-    // Generate a slug from the text
-    let id = slugify(&text);
+/// Value of a double-quoted attribute in an attribute list.
+fn attr_value<'a>(attrs: &'a str, name: &str) -> Option<&'a str> {
+    let start = attrs.find(&format!(" {name}=\""))? + name.len() + 3;
+    let len = attrs[start..].find('"')?;
+    Some(&attrs[start..start + len])
+}
 
-    Some(TocEntry {
-        level: level as u8,
-        text,
-        id,
-    })
+/// Remove tags, keeping text.
+fn strip_tags(html: &str) -> String {
+    let mut text = String::with_capacity(html.len());
+    let mut in_tag = false;
+    for c in html.chars() {
+        match c {
+            '<' => in_tag = true,
+            '>' => in_tag = false,
+            _ if !in_tag => text.push(c),
+            _ => {}
+        }
+    }
+    text
 }
 
 /// Convert text to a URL-safe slug.
@@ -154,30 +337,25 @@ fn slugify(text: &str) -> String {
         .join("-")
 }
 
-/// Escape HTML special characters.
-fn html_escape(s: &str) -> String {
-    s.replace('&', "&amp;")
-        .replace('<', "&lt;")
-        .replace('>', "&gt;")
-        .replace('"', "&quot;")
-}
-
 #[cfg(test)]
 mod tests {
     use super::*;
 
     #[test]
-    fn test_parse_typst_heading() {
-        let h1 = parse_typst_heading("= Introduction").unwrap();
-        assert_eq!(h1.level, 1);
-        assert_eq!(h1.text, "Introduction");
-
-        let h2 = parse_typst_heading("== Sub Section").unwrap();
-        assert_eq!(h2.level, 2);
-        assert_eq!(h2.text, "Sub Section");
+    fn test_anchor_headings() {
+        let (html, toc) = anchor_headings(
+            r#"<h2>Main &amp; Title</h2><p>x</p><h3 id="custom">Sub <em>Section</em></h3><hr><h7>x</h7>"#,
+        );
 
-        assert!(parse_typst_heading("Not a heading").is_none());
-        assert!(parse_typst_heading("=").is_none()); // Empty heading
+        assert_eq!(
+            html,
+            r#"<h2 id="main-title">Main &amp; Title</h2><p>x</p><h3 id="custom">Sub <em>Section</em></h3><hr><h7>x</h7>"#
+        );
+        assert_eq!(toc.len(), 2);
+        assert_eq!(toc[0].level, 1);
+        assert_eq!(toc[0].text, "Main & Title");
+        assert_eq!(toc[1].id, "custom");
+        assert_eq!(toc[1].text, "Sub Section");
     }
 
     #[test]
@@ -192,15 +370,31 @@ mod tests {
         let content = r#"= Main Title
 == Section One
 === Subsection
+```
+= Not a heading
+```
 == Section Two"#;
 
-        let toc = parser.extract_toc_from_source(content);
+        let toc = parser.parse(content, Path::new("toc.typ")).unwrap().toc;
 
         assert_eq!(toc.len(), 4);
         assert_eq!(toc[0].level, 1);
         assert_eq!(toc[0].text, "Main Title");
         assert_eq!(toc[1].level, 2);
         assert_eq!(toc[2].level, 3);
+        assert_eq!(toc[3].id, "section-two");
+    }
+
+    #[test]
+    fn test_unique_heading_anchors() {
+        let (html, toc) =
+            anchor_headings(r#"<h2>Notes</h2><p>a</p><h3 id="notes-1">Own</h3><h2>Notes</h2>"#);
+
+        assert_eq!(
+            html,
+            r#"<h2 id="notes">Notes</h2><p>a</p><h3 id="notes-1">Own</h3><h2 id="notes-2">Notes</h2>"#
+        );
+        assert_eq!(toc[1], TocEntry::new(2, "Own", "notes-1"));
     }
 
     #[test]
@@ -217,12 +411,132 @@ This is a test document."#;
 
         assert_eq!(result.frontmatter.title, "Test Document");
         assert!(!result.toc.is_empty());
-        assert!(result.html.contains("typst-source"));
+        assert!(
+            result
+                .html
+                .contains(r#"<h2 id="hello-typst">Hello Typst</h2>"#)
+        );
+        assert!(result.html.contains("<p>This is a test document.</p>"));
+        assert!(!result.html.contains("<body>"));
+    }
+
+    #[test]
+    fn test_parse_show_rules_and_functions() {
+        let parser = TypstParser::new();
+        let content = r#"// typstify:frontmatter
+// title: "Scripting"
+
+#let greet(name) = [Hello, #name!]
+#show "Typst": strong
+
+#greet("World") Typst is compiled for real: #(1 + 2)."#;
+
+        let html = parser.parse(content, Path::new("script.typ")).unwrap().html;
+
+        assert!(html.contains("Hello, World!"));
+        assert!(html.contains("<strong>Typst</strong>"));
+        assert!(html.contains("for real: 3."));
+    }
+
+    #[test]
+    fn test_parse_math() {
+        let parser = TypstParser::new();
+        let content =
+            "// typstify:frontmatter\n// title: \"Math\"\n\nInline $x^2$ here.\n\n$ sum_i i $\n";
+
+        let html = parser.parse(content, Path::new("math.typ")).unwrap().html;
+
+        assert!(html.starts_with(
+            r#"<p>Inline <span style="display: inline-block"><svg class="typst-frame""#
+        ));
+        assert_eq!(html.matches("<svg").count(), 2);
+    }
+
+    #[test]
+    fn test_parse_import() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(dir.path().join("lib.typ"), "#let answer = 42").unwrap();
+        let path = dir.path().join("post.typ");
+        let content = "// typstify:frontmatter\n// title: \"Import\"\n\n#import \"lib.typ\": answer\nThe answer is #answer.\n";
+
+        let parsed = TypstParser::new().parse(content, &path).unwrap();
+        assert!(parsed.html.contains("The answer is 42."));
+        assert_eq!(parsed.dependencies, vec![dir.path().join("lib.typ")]);
+
+        let content =
+            "// typstify:frontmatter\n// title: \"Escape\"\n\n#import \"../secret.typ\": x\n";
+        assert!(TypstParser::new().parse(content, &path).is_err());
+    }
+
+    #[test]
+    fn test_parse_shortcodes() {
+        #[derive(Debug)]
+        struct Callout;
+
+        impl ShortcodeRenderer for Callout {
+            fn render(
+                &self,
+                shortcode: &shortcode::Shortcode,
+            ) -> Option<std::result::Result<String, String>> {
+                (shortcode.name == "callout").then(|| {
+                    Ok(format!(
+                        r#"<aside class="callout">{}</aside>"#,
+                        shortcode.arg("text", 0).unwrap_or_default()
+                    ))
+                })
+            }
+        }
+
+        let parser = TypstParser::new().with_shortcodes(Arc::new(Callout));
+        let content = "// typstify:frontmatter\n// title: \"Shortcodes\"\n\n= Intro\n\n{{< callout Hi >}}\n\nSee {{< video clip.mp4 >}} and {{< nope >}}.\n\n```\n{{< callout raw >}}\n```\n";
+
+        let html = parser
+            .parse(content, Path::new("shortcodes.typ"))
+            .unwrap()
+            .html;
+        assert!(html.contains(r#"<aside class="callout">Hi</aside>"#));
+        assert!(!html.contains(r#"<p><aside"#));
+        assert!(html.contains(r#"<figure class="media video">"#));
+        assert!(html.contains("</figure> and {{&lt; nope >}}.</p>"));
+        assert!(html.contains("callout raw"));
+        assert!(!html.contains(PLACEHOLDER));
+    }
+
+    #[test]
+    fn test_parse_compile_error() {
+        let parser = TypstParser::new();
+        let content =
+            "// typstify:frontmatter\n// title: \"Broken\"\n\nText\n\n#undefined-function()\n";
+
+        let err = parser.parse(content, Path::new("broken.typ")).unwrap_err();
+        let TypstError::Compilation(diagnostics) = &err else {
+            panic!("expected compilation error, got {err:?}");
+        };
+
+        assert_eq!(diagnostics[0].line, 6);
+        assert!(diagnostics[0].message.contains("unknown variable"));
+        assert!(err.to_string().contains("--> broken.typ:6:"));
+    }
+
+    #[test]
+    fn test_parse_syntax_error() {
+        let parser = TypstParser::new();
+        let content =
+            "// typstify:frontmatter\n// title: \"Broken\"\n\n= Hello\n\n#let x = (1, 2\n";
+
+        let err = parser.parse(content, Path::new("broken.typ")).unwrap_err();
+        let TypstError::Syntax(diagnostics) = &err else {
+            panic!("expected syntax error, got {err:?}");
+        };
+
+        assert_eq!(diagnostics[0].line, 6);
+        assert!(err.to_string().contains("--> broken.typ:6:"));
+        assert!(err.to_string().contains("#let x = (1, 2"));
     }
 
     #[test]
     fn test_html_escape() {
-        assert_eq!(html_escape("<script>"), "&lt;script&gt;");
-        assert_eq!(html_escape("a & b"), "a &amp; b");
+        assert_eq!(escape_html("<script>"), "&lt;script&gt;");
+        assert_eq!(escape_html("a & b"), "a &amp; b");
     }
 }
@@ -0,0 +1,211 @@
+//! Compilation environment for Typst documents.
+//!
+//! [`TypstWorld`] gives the Typst compiler access to a single document, the
+//! files next to it (imports, images, data files) and the fonts bundled with
+//! `typst-assets`. Package imports (`@preview/...`) are not resolved, so
+//! builds never touch the network.
+
+use std::{
+    collections::BTreeSet,
+    fs,
+    path::{Path, PathBuf},
+    sync::{LazyLock, Mutex},
+};
+
+use chrono::{Datelike, Duration, Utc};
+use typst::{
+    Feature, Library, LibraryExt, World,
+    diag::{FileError, FileResult, PackageError, SourceDiagnostic},
+    foundations::{
+        Bytes, Datetime, Element, NativeElement, Recipe, Selector, Style, Transformation, Value,
+    },
+    layout::BoxElem,
+    math::EquationElem,
+    syntax::{FileId, Source, Span, VirtualPath},
+    text::{Font, FontBook},
+    utils::LazyHash,
+};
+use typst_html::FrameElem;
+
+use crate::diagnostic::Diagnostic;
+
+/// Standard library with HTML export enabled, shared by all documents.
+///
+/// HTML export has no native math output yet, so equations are laid out
+/// like in PDF export and embedded as SVG, the equivalent of
+///
+/// ```typst
+/// #show math.equation: html.frame
+/// #show math.equation.where(block: false): box
+/// ```
+static LIBRARY: LazyLock<LazyHash<Library>> = LazyLock::new(|| {
+    let mut library = Library::builder()
+        .with_features([Feature::Html].into_iter().collect())
+        .build();
+
+    let inline = EquationElem::ELEM
+        .field_id("block")
+        .map(|block| [(block, Value::Bool(false))].into_iter().collect());
+    let show = |selector, func: Element| {
+        Style::from(Recipe::new(
+            Some(selector),
+            Transformation::Func(func.into()),
+            Span::detached(),
+        ))
+    };
+    library.styles.push(show(
+        Selector::Elem(EquationElem::ELEM, None),
+        FrameElem::ELEM,
+    ));
+    library.styles.push(show(
+        Selector::Elem(EquationElem::ELEM, inline),
+        BoxElem::ELEM,
+    ));
+
+    LazyHash::new(library)
+});
+
+/// Bundled fonts and their metadata, loaded once.
+static FONTS: LazyLock<(LazyHash<FontBook>, Vec<Font>)> = LazyLock::new(|| {
+    let fonts: Vec<Font> = typst_assets::fonts()
+        .flat_map(|data| Font::iter(Bytes::new(data)))
+        .collect();
+    (LazyHash::new(FontBook::from_fonts(&fonts)), fonts)
+});
+
+/// A Typst document and the directory it may read files from.
+pub struct TypstWorld {
+    /// Directory imports and file reads are resolved against.
+    root: PathBuf,
+
+    /// Path of the document, for diagnostics.
+    path: PathBuf,
+
+    /// The document itself.
+    main: Source,
+
+    /// Paths of the other files the compiler asked for.
+    loaded: Mutex<BTreeSet<PathBuf>>,
+}
+
+impl TypstWorld {
+    /// Create a world for the document at `path` with the given source.
+    ///
+    /// Files are read relative to the document's directory and may not
+    /// escape it.
+    pub fn new(path: &Path, text: impl Into<String>) -> Self {
+        let root = path.parent().map(Path::to_path_buf).unwrap_or_default();
+        let name = path
+            .file_name()
+            .map_or_else(|| "main.typ".into(), PathBuf::from);
+        let id = FileId::new(None, VirtualPath::new(name));
+
+        Self {
+            root,
+            path: path.to_path_buf(),
+            main: Source::new(id, text.into()),
+            loaded: Mutex::default(),
+        }
+    }
+
+    /// Paths of the files besides the document that compilation read or
+    /// tried to read (imports, images, data files), in order.
+    pub fn dependencies(&self) -> Vec<PathBuf> {
+        self.loaded
+            .lock()
+            .map(|loaded| loaded.iter().cloned().collect())
+            .unwrap_or_default()
+    }
+
+    /// Convert compiler diagnostics to located [`Diagnostic`]s.
+    ///
+    /// Diagnostics without a location point at the start of the document.
+    pub fn diagnostics(&self, errors: &[SourceDiagnostic]) -> Vec<Diagnostic> {
+        errors
+            .iter()
+            .map(|error| {
+                let source = error
+                    .span
+                    .id()
+                    .and_then(|id| self.source(id).ok())
+                    .unwrap_or_else(|| self.main.clone());
+                let path = if source.id() == self.main.id() {
+                    self.path.clone()
+                } else {
+                    source
+                        .id()
+                        .vpath()
+                        .resolve(&self.root)
+                        .unwrap_or_else(|| self.path.clone())
+                };
+                let range = source.range(error.span).unwrap_or(0..0);
+                Diagnostic::new(path, source.text(), range, error.message.as_str())
+                    .with_hints(error.hints.iter().map(|h| h.as_str()))
+            })
+            .collect()
+    }
+
+    /// Resolve a file id to a path inside the root, recording it as a
+    /// dependency.
+    fn resolve(&self, id: FileId) -> FileResult<PathBuf> {
+        if let Some(spec) = id.package() {
+            return Err(FileError::Package(PackageError::NotFound(spec.clone())));
+        }
+        let path = id
+            .vpath()
+            .resolve(&self.root)
+            .ok_or(FileError::AccessDenied)?;
+        if let Ok(mut loaded) = self.loaded.lock() {
+            loaded.insert(path.clone());
+        }
+        Ok(path)
+    }
+}
+
+impl World for TypstWorld {
+    fn library(&self) -> &LazyHash<Library> {
+        &LIBRARY
+    }
+
+    fn book(&self) -> &LazyHash<FontBook> {
+        &FONTS.0
+    }
+
+    fn main(&self) -> FileId {
+        self.main.id()
+    }
+
+    fn source(&self, id: FileId) -> FileResult<Source> {
+        if id == self.main.id() {
+            return Ok(self.main.clone());
+        }
+        let path = self.resolve(id)?;
+        let text = fs::read_to_string(&path).map_err(|e| FileError::from_io(e, &path))?;
+        Ok(Source::new(id, text))
+    }
+
+    fn file(&self, id: FileId) -> FileResult<Bytes> {
+        if id == self.main.id() {
+            return Ok(Bytes::from_string(self.main.text().to_string()));
+        }
+        let path = self.resolve(id)?;
+        if path.is_dir() {
+            return Err(FileError::IsDirectory);
+        }
+        let data = fs::read(&path).map_err(|e| FileError::from_io(e, &path))?;
+        Ok(Bytes::new(data))
+    }
+
+    fn font(&self, index: usize) -> Option<Font> {
+        FONTS.1.get(index).cloned()
+    }
+
+    fn today(&self, offset: Option<i64>) -> Option<Datetime> {
+        let now = Utc::now() + Duration::hours(offset.unwrap_or(0));
+        Datetime::from_ymd(
+            now.year(),
+            now.month().try_into().ok()?,
+            now.day().try_into().ok()?,
+        )
+    }
+}
@@ -0,0 +1,132 @@
+//! Stopword removal and stemming of query terms.
+//!
+//! Mirrors `typstify_search::Analyzer`: indexes record the analyzer their
+//! terms went through, and queries go through the same one, so "building"
+//! finds pages indexed under "build".
+
+use serde::{Deserialize, Serialize};
+use tantivy::tokenizer::{
+    Language, LowerCaser, SimpleTokenizer, Stemmer as StemmerFilter, StopWordFilter, TextAnalyzer,
+};
+
+/// File in a Tantivy index directory recording its [`Analyzer`].
+pub const ANALYZER_FILE: &str = "analyzer.json";
+
+/// Algorithm reducing words to their stems.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Stemmer {
+    /// Porter stemmer for English.
+    English,
+}
+
+impl Stemmer {
+    /// Stem of `term`.
+    pub fn stem(self, term: &str) -> String {
+        rust_stemmers::Stemmer::create(self.algorithm())
+            .stem(term)
+            .into_owned()
+    }
+
+    fn algorithm(self) -> rust_stemmers::Algorithm {
+        match self {
+            Self::English => rust_stemmers::Algorithm::English,
+        }
+    }
+
+    fn language(self) -> Language {
+        match self {
+            Self::English => Language::English,
+        }
+    }
+}
+
+/// Stopword removal and stemming an index was built with.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Analyzer {
+    /// Stemmer, if terms are stemmed.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub stemmer: Option<Stemmer>,
+
+    /// Lowercase words left out, sorted.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub stopwords: Vec<String>,
+}
+
+impl Analyzer {
+    /// Parse an analyzer from JSON.
+    pub fn from_json(json: &[u8]) -> Result<Self, String> {
+        serde_json::from_slice(json).map_err(|e| e.to_string())
+    }
+
+    /// Whether terms pass through unchanged.
+    pub fn is_identity(&self) -> bool {
+        self.stemmer.is_none() && self.stopwords.is_empty()
+    }
+
+    /// Whether the lowercase `term` is a stopword.
+    pub fn is_stopword(&self, term: &str) -> bool {
+        self.stopwords
+            .binary_search_by(|word| word.as_str().cmp(term))
+            .is_ok()
+    }
+
+    /// `term` as it was indexed, or `None` for a stopword.
+    pub fn analyze_term(&self, term: &str) -> Option<String> {
+        if self.is_stopword(term) {
+            return None;
+        }
+        Some(match self.stemmer {
+            Some(stemmer) => stemmer.stem(term),
+            None => term.to_string(),
+        })
+    }
+
+    /// `terms` as they were indexed, without stopwords.
+    pub fn analyze(&self, terms: Vec<String>) -> Vec<String> {
+        if self.is_identity() {
+            return terms;
+        }
+        terms
+            .iter()
+            .filter_map(|term| self.analyze_term(term))
+            .collect()
+    }
+
+    /// Tantivy tokenizer applying the analyzer after splitting text into
+    /// lowercased words.
+    pub fn text_analyzer(&self) -> TextAnalyzer {
+        let mut builder = TextAnalyzer::builder(SimpleTokenizer::default())
+            .filter(LowerCaser)
+            .dynamic();
+        if !self.stopwords.is_empty() {
+            builder = builder.filter_dynamic(StopWordFilter::remove(self.stopwords.clone()));
+        }
+        if let Some(stemmer) = self.stemmer {
+            builder = builder.filter_dynamic(StemmerFilter::new(stemmer.language()));
+        }
+        builder.build()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use super::*;
+
+    #[test]
+    fn test_matches_indexer() {
+        let stopwords = HashMap::from([("en".to_string(), vec!["and".to_string()])]);
+        let native = typstify_search::Analyzer::for_language("en", true, &stopwords);
+        let analyzer = Analyzer::from_json(&serde_json::to_vec(&native).unwrap()).unwrap();
+        assert_eq!(analyzer.stemmer, Some(Stemmer::English));
+
+        let terms: Vec<String> = ["building", "and", "running", "generously", "ponies"]
+            .map(String::from)
+            .to_vec();
+        assert_eq!(analyzer.analyze(terms.clone()), native.analyze(terms));
+        assert_eq!(analyzer.analyze_term("building").as_deref(), Some("build"));
+        assert!(Analyzer::from_json(b"{}").unwrap().is_identity());
+    }
+}
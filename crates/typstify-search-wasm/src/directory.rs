@@ -2,12 +2,15 @@
 //!
 //! Implements a virtual directory that fetches chunks on-demand from an HTTP server.
 
-use std::{collections::HashMap as StdHashMap, sync::Arc};
+use std::{collections::HashMap as StdHashMap, ops::Range, sync::Arc};
 
 use gloo_net::http::Request;
 use scc::HashMap;
 use serde::{Deserialize, Serialize};
 use wasm_bindgen::prelude::*;
+use web_sys::AbortSignal;
+
+use crate::SchemaMismatch;
 
 /// Manifest describing the chunked index structure.
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -15,6 +18,11 @@ pub struct IndexManifest {
     /// Manifest format version.
     pub version: u32,
 
+    /// Search schema version; 0 for manifests written before it was
+    /// recorded.
+    #[serde(default)]
+    pub schema_version: u32,
+
     /// Chunk size used for splitting.
     pub chunk_size: usize,
 
@@ -44,6 +52,10 @@ pub enum DirectoryError {
     Parse(String),
     /// Chunk not found.
     NotFound(String),
+    /// Index built with another search schema.
+    Outdated(SchemaMismatch),
+    /// Abandoned for a newer search.
+    Cancelled,
 }
 
 impl std::fmt::Display for DirectoryError {
@@ -52,13 +64,25 @@ impl std::fmt::Display for DirectoryError {
             DirectoryError::Network(e) => write!(f, "Network error: {e}"),
             DirectoryError::Parse(e) => write!(f, "Parse error: {e}"),
             DirectoryError::NotFound(e) => write!(f, "Not found: {e}"),
+            DirectoryError::Outdated(e) => write!(f, "Outdated index: {e}"),
+            DirectoryError::Cancelled => write!(f, "Cancelled by a newer search"),
         }
     }
 }
 
 impl From<DirectoryError> for JsValue {
     fn from(err: DirectoryError) -> Self {
-        JsValue::from_str(&err.to_string())
+        match err {
+            DirectoryError::Outdated(mismatch) => mismatch.into(),
+            // Named like the error of an aborted `fetch`, for callers that
+            // already ignore those
+            DirectoryError::Cancelled => {
+                let error = js_sys::Error::new(&err.to_string());
+                error.set_name("AbortError");
+                error.into()
+            }
+            err => JsValue::from_str(&err.to_string()),
+        }
     }
 }
 
@@ -101,6 +125,7 @@ impl HttpDirectory {
 
         let manifest: IndexManifest = serde_json::from_str(&manifest_text)
             .map_err(|e| DirectoryError::Parse(e.to_string()))?;
+        SchemaMismatch::check(manifest.schema_version).map_err(DirectoryError::Outdated)?;
 
         Ok(Self {
             base_url: base_url.trim_end_matches('/').to_string(),
@@ -193,8 +218,92 @@ impl HttpDirectory {
         Ok(full_data[data_start..data_end].to_vec())
     }
 
+    /// Size of a file in the index, if it is in the manifest.
+    pub fn file_size(&self, filename: &str) -> Option<usize> {
+        self.manifest.files.get(filename).map(|file| file.size)
+    }
+
+    /// Names of the chunks holding `range` of a file that are not cached
+    /// yet.
+    pub fn missing_chunks(&self, filename: &str, range: Range<usize>) -> Vec<String> {
+        self.chunks_for_range(filename, range)
+            .into_iter()
+            .filter(|chunk| !self.chunk_cache.contains_sync(chunk.as_str()))
+            .collect()
+    }
+
+    /// A byte range of a file, if all of its chunks are cached.
+    ///
+    /// Doesn't touch the network, for callers that can't wait: fetch the
+    /// [`missing_chunks`](Self::missing_chunks) with
+    /// [`fetch_chunk`](Self::fetch_chunk) when it returns `None`.
+    pub fn cached_range(&self, filename: &str, range: Range<usize>) -> Option<Vec<u8>> {
+        if range.is_empty() {
+            return Some(Vec::new());
+        }
+
+        let chunk_size = self.manifest.chunk_size;
+        let first = range.start / chunk_size;
+        let mut data = Vec::with_capacity(range.len());
+        for (i, chunk) in self
+            .chunks_for_range(filename, range.clone())
+            .iter()
+            .enumerate()
+        {
+            let chunk_start = (first + i) * chunk_size;
+            let from = range.start.saturating_sub(chunk_start);
+            let to = (range.end - chunk_start).min(chunk_size);
+            self.chunk_cache.read_sync(chunk.as_str(), |_, bytes| {
+                data.extend_from_slice(&bytes[from.min(bytes.len())..to.min(bytes.len())]);
+            })?;
+        }
+        (data.len() == range.len()).then_some(data)
+    }
+
+    /// Add a chunk to the cache, such as one fetched some other way.
+    pub fn insert_chunk(&self, chunk_name: &str, data: Vec<u8>) {
+        let _ = self.chunk_cache.insert_sync(chunk_name.to_string(), data);
+    }
+
+    /// Fetch a chunk into the cache, unless it is cached already. Aborting
+    /// `signal` aborts the request.
+    pub async fn fetch_chunk(
+        &self,
+        chunk_name: &str,
+        signal: Option<&AbortSignal>,
+    ) -> Result<(), DirectoryError> {
+        self.load_chunk_with(chunk_name, signal).await.map(|_| ())
+    }
+
+    /// Names of the chunks holding `range` of a file.
+    fn chunks_for_range(&self, filename: &str, range: Range<usize>) -> Vec<String> {
+        let Some(file) = self.manifest.files.get(filename) else {
+            return Vec::new();
+        };
+        if range.is_empty() {
+            return Vec::new();
+        }
+        let chunk_size = self.manifest.chunk_size;
+        file.chunks
+            .iter()
+            .skip(range.start / chunk_size)
+            .take((range.end - 1) / chunk_size - range.start / chunk_size + 1)
+            .cloned()
+            .collect()
+    }
+
     /// Load a single chunk, using cache if available.
     async fn load_chunk(&self, chunk_name: &str) -> Result<Vec<u8>, DirectoryError> {
+        self.load_chunk_with(chunk_name, None).await
+    }
+
+    /// Load a single chunk, using cache if available, with a request
+    /// aborted along with `signal`.
+    async fn load_chunk_with(
+        &self,
+        chunk_name: &str,
+        signal: Option<&AbortSignal>,
+    ) -> Result<Vec<u8>, DirectoryError> {
         // Check cache first
         if let Some(entry) = self.chunk_cache.get_async(chunk_name).await {
             return Ok(entry.get().clone());
@@ -204,6 +313,7 @@ impl HttpDirectory {
         let chunk_url = format!("{}/{}", self.base_url, chunk_name);
 
         let response = Request::get(&chunk_url)
+            .abort_signal(signal)
             .send()
             .await
             .map_err(|e| DirectoryError::Network(e.to_string()))?;
@@ -267,6 +377,7 @@ mod tests {
 
         IndexManifest {
             version: 1,
+            schema_version: crate::SCHEMA_VERSION,
             chunk_size: 512,
             total_size: 1000,
             files,
@@ -289,6 +400,7 @@ mod tests {
 
         let manifest: IndexManifest = serde_json::from_str(json).unwrap();
         assert_eq!(manifest.version, 1);
+        assert_eq!(manifest.schema_version, 0);
         assert_eq!(manifest.chunk_size, 65536);
         assert!(manifest.files.contains_key("data.bin"));
     }
@@ -303,6 +415,42 @@ mod tests {
         assert!(dir.list_files().contains(&"test.bin"));
     }
 
+    #[test]
+    fn test_cached_range() {
+        let dir =
+            HttpDirectory::with_manifest("https://example.com/search", create_test_manifest());
+        assert_eq!(dir.file_size("test.bin"), Some(1000));
+        assert_eq!(
+            dir.missing_chunks("test.bin", 500..600),
+            vec!["chunk_0000.bin", "chunk_0001.bin"]
+        );
+        assert_eq!(
+            dir.missing_chunks("test.bin", 0..10),
+            vec!["chunk_0000.bin"]
+        );
+        assert_eq!(dir.cached_range("test.bin", 500..600), None);
+
+        let bytes: Vec<u8> = (0..1000).map(|i| (i % 251) as u8).collect();
+        dir.insert_chunk("chunk_0000.bin", bytes[..512].to_vec());
+        assert_eq!(
+            dir.cached_range("test.bin", 10..20),
+            Some(bytes[10..20].to_vec())
+        );
+        assert_eq!(
+            dir.missing_chunks("test.bin", 500..600),
+            vec!["chunk_0001.bin"]
+        );
+
+        dir.insert_chunk("chunk_0001.bin", bytes[512..].to_vec());
+        assert_eq!(
+            dir.cached_range("test.bin", 500..600),
+            Some(bytes[500..600].to_vec())
+        );
+        assert_eq!(dir.cached_range("test.bin", 0..1000), Some(bytes));
+        assert_eq!(dir.cached_range("test.bin", 5..5), Some(Vec::new()));
+        assert!(dir.missing_chunks("test.bin", 0..1000).is_empty());
+    }
+
     #[test]
     fn test_directory_error_display() {
         let err = DirectoryError::Network("connection refused".to_string());
@@ -310,5 +458,8 @@ mod tests {
 
         let err = DirectoryError::NotFound("file.bin".to_string());
         assert!(err.to_string().contains("Not found"));
+
+        let err = DirectoryError::Outdated(SchemaMismatch { found: 0 });
+        assert!(err.to_string().contains("Outdated index"));
     }
 }
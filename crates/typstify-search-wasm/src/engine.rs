@@ -0,0 +1,630 @@
+//! Chunked Tantivy search for large sites.
+//!
+//! Runs Tantivy queries in the browser against an index split into chunks
+//! by `typstify_search::IndexChunker`, for sites whose simple JSON index is
+//! too large to load at once. Chunks are fetched through [`HttpDirectory`]
+//! the first time a query reads them and stay cached. Tantivy reads
+//! synchronously, so a read of a chunk that isn't cached yet fails and
+//! records the chunk; the chunk is then fetched and the read retried.
+//!
+//! A search started while another is waiting for chunks cancels it: the
+//! older search aborts its fetches and fails with an `AbortError`, so
+//! results of a stale query never replace newer ones as the user types.
+
+use std::{
+    cell::{Cell, RefCell},
+    collections::BTreeSet,
+    fmt, io,
+    ops::Range,
+    path::{Path, PathBuf},
+    sync::{Arc, Mutex},
+};
+
+use tantivy::{
+    HasLen, Index, IndexReader, ReloadPolicy, TantivyDocument, Term,
+    collector::TopDocs,
+    directory::{
+        Directory, DirectoryLock, FileHandle, Lock, OwnedBytes, WatchCallback, WatchHandle,
+        WritePtr,
+        error::{DeleteError, LockError, OpenReadError, OpenWriteError},
+    },
+    query::{BooleanQuery, Occur, Query, QueryParser, TermQuery},
+    schema::{Field, IndexRecordOption, Value},
+};
+use wasm_bindgen::prelude::*;
+use web_sys::{AbortController, AbortSignal};
+
+use crate::{
+    analyzer::{ANALYZER_FILE, Analyzer},
+    directory::{DirectoryError, HttpDirectory},
+    query::{SearchResult, SearchResults},
+};
+
+/// Title matches count this much more than body matches.
+const TITLE_BOOST: f32 = 2.0;
+
+/// A read-only Tantivy directory over the cached chunks of an
+/// [`HttpDirectory`], recording the chunks reads were missing.
+#[derive(Clone)]
+pub struct ChunkedDirectory {
+    /// Chunk source and cache.
+    http: HttpDirectory,
+
+    /// Chunks reads needed that aren't cached yet.
+    missing: Arc<Mutex<BTreeSet<String>>>,
+}
+
+impl fmt::Debug for ChunkedDirectory {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ChunkedDirectory")
+            .field("files", &self.http.list_files())
+            .field("cached_chunks", &self.http.cached_chunk_count())
+            .finish()
+    }
+}
+
+impl ChunkedDirectory {
+    /// Create a directory over the chunks of `http`.
+    pub fn new(http: HttpDirectory) -> Self {
+        Self {
+            http,
+            missing: Arc::new(Mutex::new(BTreeSet::new())),
+        }
+    }
+
+    /// Chunks reads needed since the last call that aren't cached yet.
+    pub fn take_missing(&self) -> Vec<String> {
+        let mut missing = self.missing.lock().unwrap_or_else(|e| e.into_inner());
+        std::mem::take(&mut *missing).into_iter().collect()
+    }
+
+    /// Run `operation`, fetching the chunks it reads and retrying it until
+    /// it no longer fails for lack of a chunk.
+    ///
+    /// Fails with [`DirectoryError::Cancelled`] once `cancelled` returns
+    /// true; fetches are made with `signal`, so aborting it along with
+    /// cancelling stops them at once.
+    pub async fn with_chunks<T>(
+        &self,
+        mut operation: impl FnMut() -> tantivy::Result<T>,
+        cancelled: impl Fn() -> bool,
+        signal: Option<&AbortSignal>,
+    ) -> Result<T, DirectoryError> {
+        loop {
+            if cancelled() {
+                return Err(DirectoryError::Cancelled);
+            }
+            match operation() {
+                Ok(value) => return Ok(value),
+                Err(e) => {
+                    let missing = self.take_missing();
+                    if missing.is_empty() {
+                        return Err(DirectoryError::Parse(e.to_string()));
+                    }
+                    for chunk in &missing {
+                        self.http.fetch_chunk(chunk, signal).await.map_err(|e| {
+                            if cancelled() {
+                                DirectoryError::Cancelled
+                            } else {
+                                e
+                            }
+                        })?;
+                    }
+                }
+            }
+        }
+    }
+
+    /// Bytes of `range` of the file `name`, or an error recording the
+    /// chunks left to fetch.
+    fn read(&self, name: &str, range: Range<usize>) -> io::Result<Vec<u8>> {
+        if let Some(data) = self.http.cached_range(name, range.clone()) {
+            return Ok(data);
+        }
+        let missing = self.http.missing_chunks(name, range);
+        self.missing
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .extend(missing);
+        Err(io::Error::new(
+            io::ErrorKind::WouldBlock,
+            format!("chunks of {name} are not loaded yet"),
+        ))
+    }
+
+    /// Size of the file at `path`, if the index has it.
+    fn file(&self, path: &Path) -> Result<(String, usize), OpenReadError> {
+        let name = path.to_string_lossy().into_owned();
+        match self.http.file_size(&name) {
+            Some(size) => Ok((name, size)),
+            None => Err(OpenReadError::FileDoesNotExist(path.to_path_buf())),
+        }
+    }
+}
+
+/// Error for writes, which the directory doesn't support.
+fn read_only() -> io::Error {
+    io::Error::new(
+        io::ErrorKind::PermissionDenied,
+        "the search index is read-only",
+    )
+}
+
+impl Directory for ChunkedDirectory {
+    fn get_file_handle(&self, path: &Path) -> Result<Arc<dyn FileHandle>, OpenReadError> {
+        let (name, size) = self.file(path)?;
+        Ok(Arc::new(ChunkedFile {
+            directory: self.clone(),
+            name,
+            size,
+        }))
+    }
+
+    fn delete(&self, path: &Path) -> Result<(), DeleteError> {
+        Err(DeleteError::IoError {
+            io_error: Arc::new(read_only()),
+            filepath: path.to_path_buf(),
+        })
+    }
+
+    fn exists(&self, path: &Path) -> Result<bool, OpenReadError> {
+        Ok(self.file(path).is_ok())
+    }
+
+    fn open_write(&self, path: &Path) -> Result<WritePtr, OpenWriteError> {
+        Err(OpenWriteError::wrap_io_error(
+            read_only(),
+            PathBuf::from(path),
+        ))
+    }
+
+    fn atomic_read(&self, path: &Path) -> Result<Vec<u8>, OpenReadError> {
+        let (name, size) = self.file(path)?;
+        self.read(&name, 0..size)
+            .map_err(|e| OpenReadError::wrap_io_error(e, path.to_path_buf()))
+    }
+
+    fn atomic_write(&self, _path: &Path, _data: &[u8]) -> io::Result<()> {
+        Err(read_only())
+    }
+
+    fn sync_directory(&self) -> io::Result<()> {
+        Ok(())
+    }
+
+    fn acquire_lock(&self, _lock: &Lock) -> Result<DirectoryLock, LockError> {
+        // Nothing writes the index, so readers need no lock
+        Ok(DirectoryLock::from(Box::new(())))
+    }
+
+    fn watch(&self, _watch_callback: WatchCallback) -> tantivy::Result<WatchHandle> {
+        Ok(WatchHandle::empty())
+    }
+}
+
+/// A file of a [`ChunkedDirectory`].
+#[derive(Debug)]
+struct ChunkedFile {
+    /// Directory holding the file.
+    directory: ChunkedDirectory,
+
+    /// File name.
+    name: String,
+
+    /// File size.
+    size: usize,
+}
+
+impl HasLen for ChunkedFile {
+    fn len(&self) -> usize {
+        self.size
+    }
+}
+
+impl FileHandle for ChunkedFile {
+    fn read_bytes(&self, range: Range<usize>) -> io::Result<OwnedBytes> {
+        self.directory.read(&self.name, range).map(OwnedBytes::new)
+    }
+}
+
+/// Fields of the search schema the engine uses.
+#[derive(Debug, Clone, Copy)]
+struct Fields {
+    title: Field,
+    body: Field,
+    url: Field,
+    lang: Field,
+    tags: Field,
+}
+
+impl Fields {
+    /// Look the fields up in the schema of `index`.
+    fn of(index: &Index) -> tantivy::Result<Self> {
+        let schema = index.schema();
+        Ok(Self {
+            title: schema.get_field("title")?,
+            body: schema.get_field("body")?,
+            url: schema.get_field("url")?,
+            lang: schema.get_field("lang")?,
+            tags: schema.get_field("tags")?,
+        })
+    }
+}
+
+/// Tantivy search over a chunked index.
+#[wasm_bindgen]
+pub struct SearchEngine {
+    directory: ChunkedDirectory,
+    index: Index,
+    reader: IndexReader,
+    fields: Fields,
+
+    /// Number of the latest search; older ones are cancelled.
+    latest: Cell<u64>,
+
+    /// Aborts the chunk fetches of the latest search.
+    controller: RefCell<Option<AbortController>>,
+
+    /// Milliseconds a search waits for a newer one before it starts.
+    debounce_ms: Cell<u32>,
+}
+
+#[wasm_bindgen]
+impl SearchEngine {
+    /// Open the chunked index at `base_url` (the directory holding
+    /// `manifest.json`). Only the chunks needed to open it are fetched.
+    #[wasm_bindgen(js_name = load)]
+    pub async fn load(base_url: &str) -> Result<SearchEngine, JsValue> {
+        let directory = ChunkedDirectory::new(HttpDirectory::new(base_url).await?);
+        Ok(Self::open(directory).await?)
+    }
+
+    /// Search the index, fetching the chunks the query reads. With `lang`,
+    /// only pages in that language are returned.
+    ///
+    /// Cancels the search before it: its promise rejects with an
+    /// `AbortError` and its chunk fetches are aborted.
+    pub async fn search(
+        &self,
+        query: String,
+        limit: Option<usize>,
+        lang: Option<String>,
+    ) -> Result<JsValue, JsValue> {
+        let generation = self.latest.get() + 1;
+        self.latest.set(generation);
+        if let Some(previous) = self.controller.take() {
+            previous.abort();
+        }
+        let controller = AbortController::new().ok();
+        let signal = controller.as_ref().map(AbortController::signal);
+        self.controller.replace(controller);
+        let cancelled = || self.latest.get() != generation;
+
+        let debounce_ms = self.debounce_ms.get();
+        if debounce_ms > 0 {
+            sleep(debounce_ms).await;
+        }
+
+        #[cfg(target_arch = "wasm32")]
+        let start = js_sys::Date::now();
+
+        let limit = limit.unwrap_or(10);
+        let mut results = self
+            .directory
+            .with_chunks(
+                || self.run(&query, limit, lang.as_deref()),
+                cancelled,
+                signal.as_ref(),
+            )
+            .await?;
+
+        #[cfg(target_arch = "wasm32")]
+        {
+            results.duration_ms = (js_sys::Date::now() - start) as u32;
+        }
+        #[cfg(not(target_arch = "wasm32"))]
+        {
+            results.duration_ms = 0;
+        }
+        results.to_js()
+    }
+
+    /// Make each search wait `ms` milliseconds before it starts, so that
+    /// only the last of quickly typed queries runs (0, the default, starts
+    /// at once).
+    #[wasm_bindgen(js_name = setDebounce)]
+    pub fn set_debounce(&self, ms: u32) {
+        self.debounce_ms.set(ms);
+    }
+
+    /// Get the number of indexed documents.
+    #[wasm_bindgen(js_name = documentCount)]
+    pub fn document_count(&self) -> u64 {
+        self.reader.searcher().num_docs()
+    }
+
+    /// Get the number of chunks fetched so far.
+    #[wasm_bindgen(js_name = cachedChunkCount)]
+    pub fn cached_chunk_count(&self) -> usize {
+        self.directory.http.cached_chunk_count()
+    }
+}
+
+impl SearchEngine {
+    /// Open the index in `directory`, fetching the chunks that takes.
+    pub async fn open(directory: ChunkedDirectory) -> Result<Self, DirectoryError> {
+        let index = directory
+            .with_chunks(|| Index::open(directory.clone()), || false, None)
+            .await?;
+        let analyzer = directory
+            .with_chunks(|| read_analyzer(&directory), || false, None)
+            .await?;
+        register_tokenizers(&index, &analyzer);
+        let fields = Fields::of(&index).map_err(|e| DirectoryError::Parse(e.to_string()))?;
+        let reader = directory
+            .with_chunks(
+                || {
+                    index
+                        .reader_builder()
+                        .reload_policy(ReloadPolicy::Manual)
+                        .try_into()
+                },
+                || false,
+                None,
+            )
+            .await?;
+        Ok(Self::new(directory, index, reader, fields))
+    }
+
+    /// An engine searching `index`.
+    fn new(directory: ChunkedDirectory, index: Index, reader: IndexReader, fields: Fields) -> Self {
+        Self {
+            directory,
+            index,
+            reader,
+            fields,
+            latest: Cell::new(0),
+            controller: RefCell::new(None),
+            debounce_ms: Cell::new(0),
+        }
+    }
+
+    /// Run `query` against the chunks cached so far.
+    fn run(&self, query: &str, limit: usize, lang: Option<&str>) -> tantivy::Result<SearchResults> {
+        if query.trim().is_empty() {
+            return Ok(SearchResults::empty(query));
+        }
+
+        let mut parser = QueryParser::for_index(
+            &self.index,
+            vec![self.fields.title, self.fields.body, self.fields.tags],
+        );
+        parser.set_field_boost(self.fields.title, TITLE_BOOST);
+        // Search what was typed, even if it isn't valid query syntax
+        let (parsed, _) = parser.parse_query_lenient(query);
+        let parsed: Box<dyn Query> = match lang {
+            Some(lang) => Box::new(BooleanQuery::new(vec![
+                (Occur::Must, parsed),
+                (
+                    Occur::Must,
+                    Box::new(TermQuery::new(
+                        Term::from_field_text(self.fields.lang, lang),
+                        IndexRecordOption::Basic,
+                    )),
+                ),
+            ])),
+            None => parsed,
+        };
+
+        let searcher = self.reader.searcher();
+        let top = searcher.search(&parsed, &TopDocs::with_limit(limit))?;
+        let mut results = Vec::with_capacity(top.len());
+        for (score, address) in top {
+            let doc: TantivyDocument = searcher.doc(address)?;
+            let text = |field| {
+                doc.get_first(field)
+                    .and_then(|value| value.as_str())
+                    .unwrap_or_default()
+                    .to_string()
+            };
+            results.push(SearchResult {
+                url: text(self.fields.url),
+                title: text(self.fields.title),
+                description: None,
+                score,
+                snippet: None,
+            });
+        }
+
+        Ok(SearchResults {
+            query: query.to_string(),
+            total: results.len(),
+            results,
+            duration_ms: 0,
+        })
+    }
+}
+
+/// Wait `ms` milliseconds (not at all outside a browser window).
+async fn sleep(ms: u32) {
+    let Some(window) = web_sys::window() else {
+        return;
+    };
+    let promise = js_sys::Promise::new(&mut |resolve, _| {
+        let _ = window.set_timeout_with_callback_and_timeout_and_arguments_0(
+            &resolve,
+            i32::try_from(ms).unwrap_or(i32::MAX),
+        );
+    });
+    let _ = wasm_bindgen_futures::JsFuture::from(promise).await;
+}
+
+/// The analyzer recorded in the index in `directory`; indexes recording
+/// none were built without one.
+fn read_analyzer(directory: &ChunkedDirectory) -> tantivy::Result<Analyzer> {
+    match directory.atomic_read(Path::new(ANALYZER_FILE)) {
+        Ok(json) => Analyzer::from_json(&json).map_err(tantivy::TantivyError::InvalidArgument),
+        Err(OpenReadError::FileDoesNotExist(_)) => Ok(Analyzer::default()),
+        Err(e) => Err(e.into()),
+    }
+}
+
+/// Register the tokenizers the index was built with, applying `analyzer`
+/// (`typstify_search::register_tokenizers`).
+fn register_tokenizers(index: &Index, analyzer: &Analyzer) {
+    index
+        .tokenizers()
+        .register("default", analyzer.text_analyzer());
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{fs, future::Future, path::Path};
+
+    use typstify_core::{
+        Page,
+        content::{ContentPath, ParsedContent},
+        frontmatter::Frontmatter,
+    };
+    use typstify_search::{ChunkerConfig, IndexChunker, IndexerConfig, SearchIndexer};
+
+    use super::*;
+
+    fn page(path: &str, title: &str, body: &str) -> Page {
+        Page::from_parsed(
+            ParsedContent {
+                frontmatter: Frontmatter {
+                    title: title.to_string(),
+                    ..Frontmatter::default()
+                },
+                html: format!("<p>{body}</p>"),
+                raw: body.to_string(),
+                toc: vec![],
+                dependencies: vec![],
+            },
+            &ContentPath::from_path(Path::new(path), "en").unwrap(),
+        )
+    }
+
+    /// Run `operation` like [`ChunkedDirectory::with_chunks`], reading
+    /// missing chunks from `chunks_dir` instead of the network.
+    fn with_chunks<T>(
+        directory: &ChunkedDirectory,
+        chunks_dir: &Path,
+        fetched: &mut usize,
+        mut operation: impl FnMut() -> tantivy::Result<T>,
+    ) -> T {
+        loop {
+            match operation() {
+                Ok(value) => return value,
+                Err(e) => {
+                    let missing = directory.take_missing();
+                    assert!(!missing.is_empty(), "{e}");
+                    for chunk in missing {
+                        let data = fs::read(chunks_dir.join(&chunk)).unwrap();
+                        directory.http.insert_chunk(&chunk, data);
+                        *fetched += 1;
+                    }
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_search_chunked_index() {
+        let temp = tempfile::TempDir::new().unwrap();
+        let index_dir = temp.path().join("index");
+        let chunks_dir = temp.path().join("chunks");
+
+        let pages = [
+            page("posts/rust.md", "Learning Rust", "Ownership and borrowing"),
+            page("posts/go.md", "Learning Go", "Goroutines and channels"),
+            page("posts/rust.zh.md", "学习 Rust", "所有权 rust"),
+        ];
+        let indexer = SearchIndexer::new(&index_dir, IndexerConfig::default()).unwrap();
+        indexer
+            .index_pages(&pages.iter().collect::<Vec<_>>())
+            .unwrap();
+        drop(indexer);
+        let manifest = IndexChunker::new(ChunkerConfig {
+            chunk_size: 1024,
+            ..ChunkerConfig::default()
+        })
+        .chunk_directory(&index_dir, &chunks_dir)
+        .unwrap();
+        let total_chunks: usize = manifest.files.values().map(|f| f.chunks.len()).sum();
+        let manifest = serde_json::from_str(&manifest.to_json().unwrap()).unwrap();
+
+        let directory = ChunkedDirectory::new(HttpDirectory::with_manifest(
+            "https://example.com/search",
+            manifest,
+        ));
+        let mut fetched = 0;
+        let index = with_chunks(&directory, &chunks_dir, &mut fetched, || {
+            Index::open(directory.clone())
+        });
+        let analyzer = with_chunks(&directory, &chunks_dir, &mut fetched, || {
+            read_analyzer(&directory)
+        });
+        assert_eq!(analyzer.stemmer, Some(crate::Stemmer::English));
+        register_tokenizers(&index, &analyzer);
+        let reader = with_chunks(&directory, &chunks_dir, &mut fetched, || {
+            index
+                .reader_builder()
+                .reload_policy(ReloadPolicy::Manual)
+                .try_into()
+        });
+        let fields = Fields::of(&index).unwrap();
+        let engine = SearchEngine::new(directory.clone(), index, reader, fields);
+        assert_eq!(engine.document_count(), 3);
+        // Chunks are fetched as they are read, not all up front
+        assert!(fetched < total_chunks, "{fetched} of {total_chunks}");
+
+        let mut search = |query: &str, lang: Option<&str>| {
+            let results = with_chunks(&directory, &chunks_dir, &mut fetched, || {
+                engine.run(query, 10, lang)
+            });
+            results
+                .results
+                .into_iter()
+                .map(|result| result.url)
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(search("borrowing", None), vec!["/posts/rust"]);
+        // Queries are stemmed like the index
+        assert_eq!(search("borrowed", None), vec!["/posts/rust"]);
+        assert_eq!(search("learning", Some("en")).len(), 2);
+        assert_eq!(search("rust", Some("zh")), vec!["/zh/posts/rust"]);
+        // Invalid query syntax is searched leniently
+        assert_eq!(search("goroutines AND (", None), vec!["/posts/go"]);
+        assert!(search("  ", None).is_empty());
+        assert_eq!(engine.cached_chunk_count(), fetched);
+    }
+
+    #[test]
+    fn test_cancelled_search() {
+        let directory = ChunkedDirectory::new(HttpDirectory::with_manifest(
+            "https://example.com/search",
+            serde_json::from_str(r#"{"version":1,"chunk_size":1024,"total_size":0,"files":{}}"#)
+                .unwrap(),
+        ));
+        let mut ran = false;
+        let search = directory.with_chunks(
+            || {
+                ran = true;
+                Ok(())
+            },
+            || true,
+            None,
+        );
+
+        // Cancelled before it does any work, so it finishes on the first poll
+        let mut context = std::task::Context::from_waker(std::task::Waker::noop());
+        let poll = std::pin::pin!(search).poll(&mut context);
+        assert!(matches!(
+            poll,
+            std::task::Poll::Ready(Err(DirectoryError::Cancelled))
+        ));
+        assert!(!ran);
+    }
+}
@@ -0,0 +1,267 @@
+//! Tag and section filters and their suggestions.
+//!
+//! A query word `tag:rust` or `section:posts` (`tag:"web dev"` for names with
+//! spaces) keeps only the documents with that tag or in that section. While
+//! a word is typed, [`suggest`] offers the index's tags and sections it
+//! starts, before any full-text search runs.
+
+use serde::{Deserialize, Serialize};
+
+/// What a [`Facet`] filters by.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum FacetKind {
+    /// A tag.
+    Tag,
+
+    /// A top-level section.
+    Section,
+}
+
+impl FacetKind {
+    /// Query word prefix of filters of this kind.
+    pub fn prefix(self) -> &'static str {
+        match self {
+            Self::Tag => "tag:",
+            Self::Section => "section:",
+        }
+    }
+}
+
+/// A tag or section name with the number of documents it has.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Facet {
+    /// Tag or section.
+    pub kind: FacetKind,
+
+    /// Tag or section name.
+    pub name: String,
+
+    /// Number of documents.
+    pub count: usize,
+}
+
+/// A filter from a query word.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Filter {
+    /// Tag or section.
+    pub kind: FacetKind,
+
+    /// Tag or section name.
+    pub name: String,
+}
+
+impl Filter {
+    /// Whether a document with `tags` in `section` passes the filter.
+    /// Names compare case-insensitively.
+    pub fn matches(&self, tags: &[String], section: Option<&str>) -> bool {
+        match self.kind {
+            FacetKind::Tag => tags.iter().any(|tag| tag.eq_ignore_ascii_case(&self.name)),
+            FacetKind::Section => section.is_some_and(|s| s.eq_ignore_ascii_case(&self.name)),
+        }
+    }
+
+    /// The filter as a query word.
+    pub fn to_query_word(&self) -> String {
+        if self.name.contains(char::is_whitespace) {
+            format!("{}\"{}\"", self.kind.prefix(), self.name)
+        } else {
+            format!("{}{}", self.kind.prefix(), self.name)
+        }
+    }
+}
+
+/// A facet offered for the word being typed.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Suggestion {
+    /// Tag or section.
+    pub kind: FacetKind,
+
+    /// Tag or section name.
+    pub name: String,
+
+    /// Number of documents.
+    pub count: usize,
+
+    /// The query with the typed word replaced by the filter.
+    pub query: String,
+}
+
+/// Split the filters off `query`, returning the rest of the query and the
+/// filters.
+pub fn split_filters(query: &str) -> (String, Vec<Filter>) {
+    let mut rest = Vec::new();
+    let mut filters = Vec::new();
+    for word in words(query) {
+        match parse_filter(word) {
+            Some(filter) => filters.push(filter),
+            None => rest.push(word),
+        }
+    }
+    (rest.join(" "), filters)
+}
+
+/// Up to `limit` of `facets` (most documents first) whose names start with
+/// the last word of `query`, skipping those already filtered by.
+///
+/// The word matches names case-insensitively; `tag:` or `section:` in front
+/// of it only suggests that kind. Nothing is suggested while no word is
+/// being typed.
+pub fn suggest(facets: &[Facet], query: &str, limit: usize) -> Vec<Suggestion> {
+    if query.ends_with(char::is_whitespace) {
+        return Vec::new();
+    }
+    let words = words(query);
+    let Some((typed, before)) = words.split_last() else {
+        return Vec::new();
+    };
+
+    let (kind, prefix) = [FacetKind::Tag, FacetKind::Section]
+        .into_iter()
+        .find_map(|kind| Some((Some(kind), typed.strip_prefix(kind.prefix())?)))
+        .unwrap_or((None, typed));
+    let prefix = prefix.trim_start_matches('"').to_lowercase();
+    if prefix.is_empty() && kind.is_none() {
+        return Vec::new();
+    }
+    let used: Vec<Filter> = before.iter().filter_map(|w| parse_filter(w)).collect();
+
+    facets
+        .iter()
+        .filter(|facet| kind.is_none_or(|kind| facet.kind == kind))
+        .filter(|facet| facet.name.to_lowercase().starts_with(&prefix))
+        .filter(|facet| {
+            !used
+                .iter()
+                .any(|f| f.kind == facet.kind && f.name.eq_ignore_ascii_case(&facet.name))
+        })
+        .take(limit)
+        .map(|facet| {
+            let filter = Filter {
+                kind: facet.kind,
+                name: facet.name.clone(),
+            };
+            let mut query: Vec<String> = before.iter().map(ToString::to_string).collect();
+            query.push(filter.to_query_word());
+            Suggestion {
+                kind: facet.kind,
+                name: facet.name.clone(),
+                count: facet.count,
+                query: query.join(" ") + " ",
+            }
+        })
+        .collect()
+}
+
+/// The filter written as `word`, if it is one.
+fn parse_filter(word: &str) -> Option<Filter> {
+    [FacetKind::Tag, FacetKind::Section]
+        .into_iter()
+        .find_map(|kind| {
+            let name = word.strip_prefix(kind.prefix())?.trim_matches('"');
+            (!name.is_empty()).then(|| Filter {
+                kind,
+                name: name.to_string(),
+            })
+        })
+}
+
+/// Whitespace-separated words of `query`, keeping a quoted filter name
+/// (`tag:"web dev"`) in one word.
+fn words(query: &str) -> Vec<&str> {
+    let mut words = Vec::new();
+    let mut start = None;
+    let mut quoted = false;
+    for (i, c) in query.char_indices() {
+        if c == '"' {
+            quoted = !quoted;
+        }
+        if c.is_whitespace() && !quoted {
+            if let Some(start) = start.take() {
+                words.push(&query[start..i]);
+            }
+        } else {
+            start.get_or_insert(i);
+        }
+    }
+    if let Some(start) = start {
+        words.push(&query[start..]);
+    }
+    words
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn facets() -> Vec<Facet> {
+        [
+            (FacetKind::Tag, "rust", 3),
+            (FacetKind::Section, "posts", 2),
+            (FacetKind::Tag, "web dev", 2),
+            (FacetKind::Section, "recipes", 1),
+        ]
+        .into_iter()
+        .map(|(kind, name, count)| Facet {
+            kind,
+            name: name.to_string(),
+            count,
+        })
+        .collect()
+    }
+
+    fn names(suggestions: &[Suggestion]) -> Vec<&str> {
+        suggestions.iter().map(|s| s.name.as_str()).collect()
+    }
+
+    #[test]
+    fn test_split_filters() {
+        let (rest, filters) = split_filters("async tag:Rust  section:posts tag:\"web dev\" io");
+        assert_eq!(rest, "async io");
+        assert_eq!(
+            filters,
+            vec![
+                Filter {
+                    kind: FacetKind::Tag,
+                    name: "Rust".to_string()
+                },
+                Filter {
+                    kind: FacetKind::Section,
+                    name: "posts".to_string()
+                },
+                Filter {
+                    kind: FacetKind::Tag,
+                    name: "web dev".to_string()
+                },
+            ]
+        );
+        assert!(filters[0].matches(&["rust".to_string()], None));
+        assert!(filters[1].matches(&[], Some("posts")));
+        assert!(!filters[1].matches(&["posts".to_string()], None));
+        assert_eq!(filters[2].to_query_word(), "tag:\"web dev\"");
+
+        // A bare prefix isn't a filter
+        assert_eq!(split_filters("tag: rust").0, "tag: rust");
+    }
+
+    #[test]
+    fn test_suggest() {
+        let facets = facets();
+        assert_eq!(names(&suggest(&facets, "r", 10)), vec!["rust", "recipes"]);
+        assert_eq!(names(&suggest(&facets, "R", 1)), vec!["rust"]);
+        assert_eq!(
+            names(&suggest(&facets, "section:", 10)),
+            vec!["posts", "recipes"]
+        );
+        assert_eq!(names(&suggest(&facets, "tag:W", 10)), vec!["web dev"]);
+
+        let suggestions = suggest(&facets, "async w", 10);
+        assert_eq!(suggestions[0].query, "async tag:\"web dev\" ");
+        assert_eq!(suggestions[0].count, 2);
+
+        // Nothing typed, or already filtered by
+        assert!(suggest(&facets, "", 10).is_empty());
+        assert!(suggest(&facets, "rust ", 10).is_empty());
+        assert_eq!(names(&suggest(&facets, "tag:rust r", 10)), vec!["recipes"]);
+    }
+}
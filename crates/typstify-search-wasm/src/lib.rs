@@ -5,8 +5,16 @@
 //! # Features
 //!
 //! - **SimpleSearchEngine**: Lightweight JSON-based search for small sites (<500KB)
-//! - **SearchEngine**: Full chunked index support for larger sites (coming soon)
+//! - **SearchEngine**: Tantivy search over a chunked index for larger sites,
+//!   fetching chunks as queries need them
 //! - **Chunk caching**: Efficient network usage with `scc::HashMap`
+//! - **Highlighting**: `highlight(query, text)` marks matches the way the
+//!   index tokenizes text, CJK bigrams included
+//! - **Stemming and stopwords**: queries go through the analyzer the index
+//!   was built with, so "building" finds "build"
+//! - **Filter suggestions**: `SimpleSearchEngine.suggest(query)` offers the
+//!   index's tags and sections as `tag:`/`section:` filters while a word is
+//!   typed, without searching
 //!
 //! # Example (JavaScript)
 //!
@@ -20,16 +28,88 @@
 //! const results = engine.search('rust programming', 10);
 //! console.log(results);
 //! ```
+//!
+//! A `SearchEngine` search is asynchronous, and a newer search cancels it:
+//!
+//! ```javascript
+//! import { SearchEngine } from 'typstify-search-wasm';
+//!
+//! const engine = await SearchEngine.load('/search');
+//! engine.setDebounce(150);
+//! input.addEventListener('input', async () => {
+//!     try {
+//!         render(await engine.search(input.value, 10, 'en'));
+//!     } catch (err) {
+//!         if (err.name !== 'AbortError') throw err;
+//!     }
+//! });
+//! ```
+//!
+//! An index built with another search schema than the runtime's (a stale
+//! cached index, or a cached page with a new index) fails to load with an
+//! error named `SearchIndexOutdated`; reloading the page fetches matching
+//! ones.
 
+pub mod analyzer;
 pub mod directory;
+pub mod engine;
+pub mod facet;
 pub mod query;
 pub mod simple;
+pub mod tokenizer;
 
+pub use analyzer::{Analyzer, Stemmer};
 pub use directory::{DirectoryError, FileManifest, HttpDirectory, IndexManifest};
-pub use query::{SearchQuery, SearchResult, SearchResults};
+pub use engine::{ChunkedDirectory, SearchEngine};
+pub use facet::{Facet, FacetKind, Filter, Suggestion};
+pub use query::{SearchQuery, SearchResult, SearchResults, highlight};
 pub use simple::{SimpleDocument, SimpleSearchEngine, SimpleSearchIndex};
 use wasm_bindgen::prelude::*;
 
+/// Search schema version the runtime understands. Must match
+/// `typstify_search::SCHEMA_VERSION`, which the tests check; the runtime
+/// cannot depend on the indexer crate.
+pub const SCHEMA_VERSION: u32 = 2;
+
+/// Name of the JavaScript error for indexes of another schema version.
+pub const OUTDATED_INDEX_ERROR: &str = "SearchIndexOutdated";
+
+/// An index built with another search schema than the runtime's.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SchemaMismatch {
+    /// Schema version of the index (0 if it records none).
+    pub found: u32,
+}
+
+impl SchemaMismatch {
+    /// Fail unless `found` is the runtime's [`SCHEMA_VERSION`].
+    pub fn check(found: u32) -> Result<(), Self> {
+        if found == SCHEMA_VERSION {
+            Ok(())
+        } else {
+            Err(Self { found })
+        }
+    }
+}
+
+impl std::fmt::Display for SchemaMismatch {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "search index schema version {} does not match the search runtime (version {SCHEMA_VERSION}); reload the page",
+            self.found
+        )
+    }
+}
+
+impl From<SchemaMismatch> for JsValue {
+    fn from(err: SchemaMismatch) -> Self {
+        let error = js_sys::Error::new(&err.to_string());
+        error.set_name(OUTDATED_INDEX_ERROR);
+        error.into()
+    }
+}
+
 /// Initialize the WASM module.
 ///
 /// Sets up panic hook for better error messages in the console.
@@ -44,6 +124,12 @@ pub fn get_version() -> String {
     env!("CARGO_PKG_VERSION").to_string()
 }
 
+/// Get the search schema version the runtime understands.
+#[wasm_bindgen(js_name = schemaVersion)]
+pub fn schema_version() -> u32 {
+    SCHEMA_VERSION
+}
+
 /// Check if the library is ready.
 #[wasm_bindgen(js_name = isReady)]
 pub fn is_ready() -> bool {
@@ -65,4 +151,17 @@ mod tests {
     fn test_is_ready() {
         assert!(is_ready());
     }
+
+    #[test]
+    fn test_schema_version_matches_indexer() {
+        assert_eq!(SCHEMA_VERSION, typstify_search::SCHEMA_VERSION);
+    }
+
+    #[test]
+    fn test_schema_mismatch() {
+        assert_eq!(SchemaMismatch::check(SCHEMA_VERSION), Ok(()));
+        let err = SchemaMismatch::check(0).unwrap_err();
+        assert_eq!(err.found, 0);
+        assert!(err.to_string().contains("reload the page"));
+    }
 }
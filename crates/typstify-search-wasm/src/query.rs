@@ -2,9 +2,16 @@
 //!
 //! Provides query parsing and search functionality for the WASM runtime.
 
+use std::collections::HashSet;
+
 use serde::{Deserialize, Serialize};
 use wasm_bindgen::prelude::*;
 
+use crate::{
+    facet::{Filter, split_filters},
+    tokenizer::{is_cjk_char, normalize_term, tokenize_text},
+};
+
 /// A search query with parsed terms.
 #[derive(Debug, Clone)]
 pub struct SearchQuery {
@@ -14,6 +21,9 @@ pub struct SearchQuery {
     /// Parsed and normalized terms.
     pub terms: Vec<String>,
 
+    /// Tag and section filters (`tag:rust`).
+    pub filters: Vec<Filter>,
+
     /// Maximum number of results.
     pub limit: usize,
 }
@@ -21,18 +31,25 @@ pub struct SearchQuery {
 impl SearchQuery {
     /// Parse a query string.
     pub fn parse(query: &str, limit: usize) -> Self {
-        let terms = tokenize_query(query);
+        let (text, filters) = split_filters(query);
+        let terms = tokenize_query(&text);
 
         Self {
             raw: query.to_string(),
             terms,
+            filters,
             limit,
         }
     }
 
-    /// Check if the query is empty.
+    /// Check if the query has neither terms nor filters.
     pub fn is_empty(&self) -> bool {
-        self.terms.is_empty()
+        self.terms.is_empty() && self.filters.is_empty()
+    }
+
+    /// Whether a document with `tags` in `section` passes all filters.
+    pub fn passes_filters(&self, tags: &[String], section: Option<&str>) -> bool {
+        self.filters.iter().all(|f| f.matches(tags, section))
     }
 }
 
@@ -90,46 +107,117 @@ impl SearchResults {
     }
 }
 
-/// Tokenize a query string into normalized terms.
+/// Tokenize a query string into normalized terms, as indexed text is.
 fn tokenize_query(query: &str) -> Vec<String> {
-    query
-        .split(|c: char| !c.is_alphanumeric())
-        .filter(|s| s.len() >= 2) // Skip single characters
-        .map(|s| s.to_lowercase())
-        .collect()
+    tokenize_text(query)
 }
 
-/// Score a document against a query.
+/// HTML of `text` with the matches of `query` in `<mark>` elements.
 ///
-/// Returns a relevance score based on term frequency and position.
-pub fn score_document(query_terms: &[String], title: &str, body_terms: &[String]) -> f32 {
-    let title_lower = title.to_lowercase();
-    let title_terms: Vec<String> = tokenize_query(&title_lower);
-
-    let mut score = 0.0f32;
-
-    for query_term in query_terms {
-        // Title matches are worth more
-        for title_term in &title_terms {
-            if title_term.contains(query_term) {
-                score += 10.0;
-            }
-            if title_term == query_term {
-                score += 5.0; // Exact match bonus
+/// Words match the query's words as the index tokenizes them. CJK text
+/// matches by bigram, or character by character for a single-character
+/// query. The rest of the text is HTML-escaped.
+#[wasm_bindgen]
+pub fn highlight(query: &str, text: &str) -> String {
+    let terms: HashSet<String> = tokenize_query(query).into_iter().collect();
+    if terms.is_empty() {
+        return escape_html(text);
+    }
+
+    // Bytes of `text` inside a match
+    let mut marked = vec![false; text.len()];
+
+    let mut word_start = None;
+    for (i, c) in text.char_indices().chain([(text.len(), ' ')]) {
+        if c.is_alphanumeric() {
+            word_start.get_or_insert(i);
+        } else if let Some(start) = word_start.take() {
+            let word = &text[start..i];
+            if word.len() >= 2 && terms.contains(&normalize_term(word)) {
+                marked[start..i].fill(true);
             }
         }
+    }
 
-        // Body matches
-        for body_term in body_terms {
-            if body_term == query_term {
-                score += 1.0;
-            } else if body_term.contains(query_term) {
-                score += 0.5;
+    let query_cjk: Vec<char> = query.chars().filter(|c| is_cjk_char(*c)).collect();
+    let chars: Vec<(usize, char)> = text.char_indices().collect();
+    if let [single] = query_cjk[..] {
+        for &(i, c) in &chars {
+            if c == single {
+                marked[i..i + c.len_utf8()].fill(true);
             }
         }
+    } else if !query_cjk.is_empty() {
+        for pair in chars.windows(2) {
+            let [(start, a), (end, b)] = [pair[0], pair[1]];
+            if is_cjk_char(a) && is_cjk_char(b) && terms.contains(&format!("{a}{b}")) {
+                marked[start..end + b.len_utf8()].fill(true);
+            }
+        }
+    }
+
+    let mut html = String::with_capacity(text.len());
+    let mut open = false;
+    for (i, c) in text.char_indices() {
+        if marked[i] != open {
+            html.push_str(if open { "</mark>" } else { "<mark>" });
+            open = marked[i];
+        }
+        push_escaped(&mut html, c);
+    }
+    if open {
+        html.push_str("</mark>");
+    }
+    html
+}
+
+/// `text` with HTML special characters escaped, like
+/// `typstify_core::escape_html`, which the tests check; the runtime does not
+/// depend on `typstify-core`.
+fn escape_html(text: &str) -> String {
+    let mut html = String::with_capacity(text.len());
+    for c in text.chars() {
+        push_escaped(&mut html, c);
     }
+    html
+}
 
-    score
+/// Push `c` to `html`, escaped if it is an HTML special character.
+fn push_escaped(html: &mut String, c: char) {
+    match c {
+        '&' => html.push_str("&amp;"),
+        '<' => html.push_str("&lt;"),
+        '>' => html.push_str("&gt;"),
+        '"' => html.push_str("&quot;"),
+        '\'' => html.push_str("&#39;"),
+        c => html.push(c),
+    }
+}
+
+/// BM25 term frequency saturation.
+const BM25_K1: f32 = 1.2;
+
+/// BM25 document length normalization.
+const BM25_B: f32 = 0.75;
+
+/// BM25 score of a term occurring `weight` times in a document of `length`
+/// terms, given the `average_length` and that `matching` of `documents`
+/// documents contain the term.
+///
+/// Occurrences are weighted by field as the index records them (title >
+/// tags > body), so this matches `typstify_search`'s ranking.
+pub fn bm25(
+    weight: u32,
+    length: u32,
+    average_length: f32,
+    matching: usize,
+    documents: usize,
+) -> f32 {
+    let matching = matching as f32;
+    let idf = (1.0 + (documents as f32 - matching + 0.5) / (matching + 0.5)).ln();
+    let tf = weight as f32;
+    let norm = 1.0 - BM25_B + BM25_B * length as f32 / average_length.max(1.0);
+    idf * tf * (BM25_K1 + 1.0) / (tf + BM25_K1 * norm)
 }
 
 /// Generate a highlighted snippet for a result.
@@ -211,15 +299,37 @@ mod tests {
     }
 
     #[test]
-    fn test_score_document() {
-        let query_terms = vec!["rust".to_string()];
-        let body_terms = vec!["rust".to_string(), "programming".to_string()];
+    fn test_bm25() {
+        // More occurrences score higher, with diminishing returns
+        let once = bm25(1, 10, 10.0, 1, 10);
+        let thrice = bm25(3, 10, 10.0, 1, 10);
+        assert!(thrice > once);
+        assert!(thrice < 3.0 * once);
+
+        // Rarer terms and shorter documents score higher
+        assert!(bm25(1, 10, 10.0, 5, 10) < once);
+        assert!(bm25(1, 5, 10.0, 1, 10) > once);
+    }
 
-        // Title match should score higher
-        let score_with_title = score_document(&query_terms, "Learning Rust", &body_terms);
-        let score_without_title = score_document(&query_terms, "Programming Guide", &body_terms);
+    #[test]
+    fn test_bm25_matches_indexer() {
+        for (weight, length, average_length, matching, documents) in [
+            (1, 10, 10.0, 1, 10),
+            (3, 10, 10.0, 1, 10),
+            (5, 200, 35.5, 7, 40),
+            (1, 0, 0.0, 1, 1),
+        ] {
+            assert_eq!(
+                bm25(weight, length, average_length, matching, documents),
+                typstify_search::simple::bm25(weight, length, average_length, matching, documents)
+            );
+        }
+    }
 
-        assert!(score_with_title > score_without_title);
+    #[test]
+    fn test_escape_matches_core() {
+        let text = r#"<a href="x">Tom & Jerry's</a> 中文"#;
+        assert_eq!(escape_html(text), typstify_core::escape_html(text));
     }
 
     #[test]
@@ -232,6 +342,34 @@ mod tests {
         assert!(snippet.unwrap().to_lowercase().contains("rust"));
     }
 
+    #[test]
+    fn test_highlight() {
+        assert_eq!(
+            highlight("rust safety", "Rust: memory <safety> without rusty GC"),
+            "<mark>Rust</mark>: memory &lt;<mark>safety</mark>&gt; without rusty GC"
+        );
+        // Single letters are not indexed, so they don't match
+        assert_eq!(highlight("a", "a cat"), "a cat");
+        assert_eq!(highlight("", "<b>"), "&lt;b&gt;");
+    }
+
+    #[test]
+    fn test_highlight_cjk() {
+        assert_eq!(
+            highlight("搜索", "全文搜索引擎，搜一搜"),
+            "全文<mark>搜索</mark>引擎，搜一搜"
+        );
+        assert_eq!(
+            highlight("搜", "全文搜索引擎，搜一搜"),
+            "全文<mark>搜</mark>索引擎，<mark>搜</mark>一<mark>搜</mark>"
+        );
+        // Adjacent bigrams merge into one match
+        assert_eq!(
+            highlight("搜索引擎", "用搜索引擎"),
+            "用<mark>搜索引擎</mark>"
+        );
+    }
+
     #[test]
     fn test_search_results_empty() {
         let results = SearchResults::empty("test");
@@ -9,7 +9,12 @@ use gloo_net::http::Request;
 use serde::{Deserialize, Serialize};
 use wasm_bindgen::prelude::*;
 
-use crate::query::{SearchQuery, SearchResult, SearchResults, generate_snippet, score_document};
+use crate::{
+    SCHEMA_VERSION, SchemaMismatch,
+    analyzer::Analyzer,
+    facet::{Facet, Suggestion, suggest},
+    query::{SearchQuery, SearchResult, SearchResults, bm25, generate_snippet},
+};
 
 /// A simple search index document.
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -32,12 +37,40 @@ pub struct SimpleDocument {
     #[serde(default, skip_serializing_if = "Vec::is_empty")]
     pub tags: Vec<String>,
 
+    /// Top-level section, if any.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub section: Option<String>,
+
     /// Publication date as ISO string.
     #[serde(skip_serializing_if = "Option::is_none")]
     pub date: Option<String>,
 
-    /// Pre-tokenized terms from title and body.
+    /// Pre-tokenized terms from title, tags and body, sorted.
     pub terms: Vec<String>,
+
+    /// Weighted frequency of each of `terms`; empty for indexes written
+    /// before it was recorded, where every term counts once.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub weights: Vec<u32>,
+}
+
+impl SimpleDocument {
+    /// Weighted frequency of `term` in the document.
+    pub fn weight(&self, term: &str) -> u32 {
+        match self.terms.binary_search_by(|t| t.as_str().cmp(term)) {
+            Ok(i) => self.weights.get(i).copied().unwrap_or(1),
+            Err(_) => 0,
+        }
+    }
+
+    /// Weighted number of terms in the document.
+    pub fn length(&self) -> u32 {
+        if self.weights.is_empty() {
+            self.terms.len() as u32
+        } else {
+            self.weights.iter().sum()
+        }
+    }
 }
 
 /// A simple JSON-based search index.
@@ -46,11 +79,25 @@ pub struct SimpleSearchIndex {
     /// Index format version.
     pub version: u32,
 
+    /// Search schema version; 0 for indexes written before it was recorded.
+    #[serde(default)]
+    pub schema_version: u32,
+
     /// All indexed documents.
     pub documents: Vec<SimpleDocument>,
 
     /// Inverted index: term -> document indices.
     pub index: HashMap<String, Vec<usize>>,
+
+    /// Tags and sections of the documents, most documents first; empty for
+    /// indexes written before they were recorded.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub facets: Vec<Facet>,
+
+    /// Stopword removal and stemming the terms went through, applied to
+    /// query terms too.
+    #[serde(default, skip_serializing_if = "Analyzer::is_identity")]
+    pub analyzer: Analyzer,
 }
 
 impl SimpleSearchIndex {
@@ -58,8 +105,11 @@ impl SimpleSearchIndex {
     pub fn empty() -> Self {
         Self {
             version: 1,
+            schema_version: SCHEMA_VERSION,
             documents: Vec::new(),
             index: HashMap::new(),
+            facets: Vec::new(),
+            analyzer: Analyzer::default(),
         }
     }
 
@@ -68,32 +118,64 @@ impl SimpleSearchIndex {
         serde_json::from_str(json).map_err(|e| e.to_string())
     }
 
-    /// Search the index.
+    /// Fail unless the index was built with the runtime's schema.
+    pub fn check_schema(&self) -> Result<(), SchemaMismatch> {
+        SchemaMismatch::check(self.schema_version)
+    }
+
+    /// Search the index for documents containing any query term, ranked by
+    /// the sum of their terms' BM25 scores.
     pub fn search(&self, query: &SearchQuery) -> SearchResults {
         #[cfg(target_arch = "wasm32")]
         let start = js_sys::Date::now();
 
-        if query.is_empty() {
+        let mut terms = self.analyzer.analyze(query.terms.clone());
+        if terms.is_empty() && query.filters.is_empty() {
             return SearchResults::empty(&query.raw);
         }
 
-        // Find documents containing any query term
+        // Find documents containing any query term, or every document
+        // when the query only filters
         let mut doc_scores: HashMap<usize, f32> = HashMap::new();
+        let passes = |doc: &SimpleDocument| query.passes_filters(&doc.tags, doc.section.as_deref());
 
-        for term in &query.terms {
+        if terms.is_empty() {
+            for (doc_idx, doc) in self.documents.iter().enumerate() {
+                if passes(doc) {
+                    doc_scores.insert(doc_idx, 0.0);
+                }
+            }
+        }
+        let total_length: u32 = self.documents.iter().map(SimpleDocument::length).sum();
+        let average_length = total_length as f32 / self.documents.len().max(1) as f32;
+        terms.sort_unstable();
+        terms.dedup();
+        for term in &terms {
             if let Some(postings) = self.index.get(term) {
                 for &doc_idx in postings {
                     let doc = &self.documents[doc_idx];
-                    let score = score_document(&query.terms, &doc.title, &doc.terms);
-                    let entry = doc_scores.entry(doc_idx).or_insert(0.0);
-                    *entry = entry.max(score);
+                    if !passes(doc) {
+                        continue;
+                    }
+                    let score = bm25(
+                        doc.weight(term),
+                        doc.length(),
+                        average_length,
+                        postings.len(),
+                        self.documents.len(),
+                    );
+                    *doc_scores.entry(doc_idx).or_insert(0.0) += score;
                 }
             }
         }
 
-        // Sort by score
+        // Sort by score, then index order
         let mut scored: Vec<_> = doc_scores.into_iter().collect();
-        scored.sort_by(|a, b| b.1.partial_cmp(&a.1).unwrap_or(std::cmp::Ordering::Equal));
+        scored.sort_by(|a, b| {
+            b.1.partial_cmp(&a.1)
+                .unwrap_or(std::cmp::Ordering::Equal)
+                .then(a.0.cmp(&b.0))
+        });
 
         // Take top results
         let results: Vec<SearchResult> = scored
@@ -129,6 +211,12 @@ impl SimpleSearchIndex {
         }
     }
 
+    /// Up to `limit` tags and sections to filter by for the word being
+    /// typed at the end of `query`.
+    pub fn suggest(&self, query: &str, limit: usize) -> Vec<Suggestion> {
+        suggest(&self.facets, query, limit)
+    }
+
     /// Get document count.
     pub fn document_count(&self) -> usize {
         self.documents.len()
@@ -170,6 +258,7 @@ impl SimpleSearchEngine {
 
         let index = SimpleSearchIndex::from_json(&json)
             .map_err(|e| JsValue::from_str(&format!("Failed to parse index: {e}")))?;
+        index.check_schema()?;
 
         Ok(Self { index })
     }
@@ -179,6 +268,7 @@ impl SimpleSearchEngine {
     pub fn from_json(json: &str) -> Result<SimpleSearchEngine, JsValue> {
         let index = SimpleSearchIndex::from_json(json)
             .map_err(|e| JsValue::from_str(&format!("Failed to parse index: {e}")))?;
+        index.check_schema()?;
 
         Ok(Self { index })
     }
@@ -191,6 +281,14 @@ impl SimpleSearchEngine {
         results.to_js()
     }
 
+    /// Tags and sections to filter by for the word being typed, each with
+    /// its document count and the query to search with it. Needs no search,
+    /// so it can show while the query is still typed.
+    pub fn suggest(&self, query: &str, limit: Option<usize>) -> Result<JsValue, JsValue> {
+        let suggestions = self.index.suggest(query, limit.unwrap_or(5));
+        serde_wasm_bindgen::to_value(&suggestions).map_err(|e| JsValue::from_str(&e.to_string()))
+    }
+
     /// Get the number of indexed documents.
     #[wasm_bindgen(js_name = documentCount)]
     pub fn document_count(&self) -> usize {
@@ -207,6 +305,7 @@ impl SimpleSearchEngine {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::facet::FacetKind;
 
     fn create_test_index() -> SimpleSearchIndex {
         let documents = vec![
@@ -216,12 +315,14 @@ mod tests {
                 description: Some("A guide to Rust programming".to_string()),
                 lang: Some("en".to_string()),
                 tags: vec!["rust".to_string()],
+                section: Some("posts".to_string()),
                 date: None,
                 terms: vec![
                     "learning".to_string(),
-                    "rust".to_string(),
                     "programming".to_string(),
+                    "rust".to_string(),
                 ],
+                weights: vec![3, 1, 5],
             },
             SimpleDocument {
                 url: "/go".to_string(),
@@ -229,12 +330,14 @@ mod tests {
                 description: Some("A guide to Go programming".to_string()),
                 lang: Some("en".to_string()),
                 tags: vec!["go".to_string()],
+                section: Some("notes".to_string()),
                 date: None,
                 terms: vec![
-                    "learning".to_string(),
                     "go".to_string(),
+                    "learning".to_string(),
                     "programming".to_string(),
                 ],
+                weights: vec![5, 3, 3],
             },
         ];
 
@@ -246,8 +349,11 @@ mod tests {
 
         SimpleSearchIndex {
             version: 1,
+            schema_version: SCHEMA_VERSION,
             documents,
             index,
+            facets: vec![],
+            analyzer: Analyzer::default(),
         }
     }
 
@@ -268,6 +374,13 @@ mod tests {
         let results = index.search(&query);
 
         assert_eq!(results.total, 2);
+        // More weighted occurrences rank first
+        assert_eq!(results.results[0].url, "/go");
+        assert!(results.results[0].score > results.results[1].score);
+
+        // Documents with more of the terms rank first
+        let results = index.search(&SearchQuery::parse("rust programming", 10));
+        assert_eq!(results.results[0].url, "/rust");
     }
 
     #[test]
@@ -288,6 +401,142 @@ mod tests {
         assert_eq!(results.total, 0);
     }
 
+    #[test]
+    fn test_simple_search_filters() {
+        let index = create_test_index();
+        let urls = |query: &str| {
+            index
+                .search(&SearchQuery::parse(query, 10))
+                .results
+                .into_iter()
+                .map(|r| r.url)
+                .collect::<Vec<_>>()
+        };
+
+        assert_eq!(urls("programming tag:go"), vec!["/go"]);
+        assert_eq!(urls("section:posts"), vec!["/rust"]);
+        assert_eq!(urls("tag:Rust section:posts"), vec!["/rust"]);
+        assert!(urls("rust section:notes").is_empty());
+    }
+
+    /// A page at `path` with `title`, `body` and `tags`.
+    fn page(path: &str, title: &str, body: &str, tags: &[&str]) -> typstify_core::Page {
+        use std::path::Path;
+
+        use typstify_core::content::{ContentPath, ParsedContent};
+
+        typstify_core::Page::from_parsed(
+            ParsedContent {
+                frontmatter: typstify_core::frontmatter::Frontmatter {
+                    title: title.to_string(),
+                    tags: tags.iter().map(ToString::to_string).collect(),
+                    ..Default::default()
+                },
+                html: format!("<p>{body}</p>"),
+                raw: body.to_string(),
+                toc: vec![],
+                dependencies: vec![],
+            },
+            &ContentPath::from_path(Path::new(path), "en").unwrap(),
+        )
+    }
+
+    /// `pages` indexed by `typstify_search`, as the runtime loads them.
+    fn built_index(pages: &[typstify_core::Page]) -> SimpleSearchIndex {
+        let json =
+            typstify_search::SimpleSearchIndex::from_pages(&pages.iter().collect::<Vec<_>>())
+                .to_json()
+                .unwrap();
+        SimpleSearchIndex::from_json(&json).unwrap()
+    }
+
+    #[test]
+    fn test_ranking_matches_indexer() {
+        let pages = [
+            page("a.md", "Notes", "Typst once, among words", &[]),
+            page("b.md", "Typst", "Other words", &[]),
+            page("c.md", "Notes", "Other words", &["typst"]),
+            page("d.md", "Unrelated", "Nothing", &[]),
+        ];
+        let native =
+            typstify_search::SimpleSearchIndex::from_pages(&pages.iter().collect::<Vec<_>>());
+        let expected: Vec<(String, f32)> = native
+            .search_scored("typst", typstify_search::MatchMode::Exact)
+            .into_iter()
+            .map(|(doc, score)| (doc.url.clone(), score))
+            .collect();
+
+        let results = built_index(&pages).search(&SearchQuery::parse("typst", 10));
+        let ranked: Vec<(String, f32)> = results
+            .results
+            .into_iter()
+            .map(|r| (r.url, r.score))
+            .collect();
+        assert_eq!(ranked, expected);
+        assert_eq!(ranked[0].0, "/b");
+    }
+
+    #[test]
+    fn test_analyzed_query_matches_indexer() {
+        let stopwords =
+            std::collections::HashMap::from([("en".to_string(), vec!["the".to_string()])]);
+        let pages = [
+            page("build.md", "Build", "How the site builds", &[]),
+            page("deploy.md", "Deploying", "The server", &[]),
+        ];
+        let native = typstify_search::SimpleSearchIndex::from_pages_with(
+            &pages.iter().collect::<Vec<_>>(),
+            typstify_search::Analyzer::for_language("en", true, &stopwords),
+        );
+        let index = SimpleSearchIndex::from_json(&native.to_json().unwrap()).unwrap();
+        assert_eq!(index.analyzer.stemmer, Some(crate::Stemmer::English));
+
+        let urls = |query: &str| {
+            index
+                .search(&SearchQuery::parse(query, 10))
+                .results
+                .into_iter()
+                .map(|r| r.url)
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(urls("building"), vec!["/build"]);
+        assert_eq!(urls("deploys"), vec!["/deploy"]);
+        // Only stopwords: nothing to search for
+        assert!(urls("the").is_empty());
+        assert_eq!(
+            urls("the building"),
+            native
+                .search_with("the building", typstify_search::MatchMode::Exact)
+                .iter()
+                .map(|doc| doc.url.clone())
+                .collect::<Vec<_>>()
+        );
+    }
+
+    #[test]
+    fn test_suggest_from_built_index() {
+        let index = built_index(&[
+            page("posts/a.md", "A", "Text", &["rust", "rustdoc"]),
+            page("posts/b.md", "B", "Text", &["rust"]),
+            page("recipes/c.md", "C", "Text", &[]),
+        ]);
+
+        let suggestions = index.suggest("ru", 5);
+        assert_eq!(
+            suggestions
+                .iter()
+                .map(|s| (s.kind, s.name.as_str(), s.count, s.query.as_str()))
+                .collect::<Vec<_>>(),
+            vec![
+                (FacetKind::Tag, "rust", 2, "tag:rust "),
+                (FacetKind::Tag, "rustdoc", 1, "tag:rustdoc "),
+            ]
+        );
+        assert_eq!(index.suggest("section:", 5).len(), 2);
+        let results = index.search(&SearchQuery::parse("text section:recipes", 10));
+        assert_eq!(results.results[0].url, "/recipes/c");
+    }
+
     #[test]
     fn test_index_from_json() {
         let json = r#"{
@@ -303,6 +552,9 @@ mod tests {
         let index = SimpleSearchIndex::from_json(json).unwrap();
         assert_eq!(index.documents.len(), 1);
         assert_eq!(index.index.len(), 1);
+        // Written before schema versions were recorded
+        assert_eq!(index.check_schema(), Err(SchemaMismatch { found: 0 }));
+        assert!(create_test_index().check_schema().is_ok());
     }
 
     #[test]
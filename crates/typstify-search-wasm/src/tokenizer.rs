@@ -0,0 +1,83 @@
+//! Text tokenization.
+//!
+//! Mirrors the tokenizer `typstify-search` builds the simple index with, so
+//! queries and highlighting find the terms that were indexed: lowercased
+//! words of at least two bytes, plus CJK characters, their bigrams and short
+//! CJK runs.
+
+/// Tokenize text into normalized terms, as the indexer does.
+pub fn tokenize_text(text: &str) -> Vec<String> {
+    let mut terms = Vec::new();
+
+    // Words (English and other space-separated languages)
+    for word in text.split(|c: char| !c.is_alphanumeric()) {
+        if word.len() >= 2 {
+            terms.push(normalize_term(word));
+        }
+    }
+
+    // CJK characters, bigrams and the whole CJK text when short
+    let cjk_text: String = text.chars().filter(|c| is_cjk_char(*c)).collect();
+    if !cjk_text.is_empty() {
+        for c in cjk_text.chars() {
+            terms.push(c.to_string());
+        }
+
+        let chars: Vec<char> = cjk_text.chars().collect();
+        for pair in chars.windows(2) {
+            terms.push(format!("{}{}", pair[0], pair[1]));
+        }
+
+        if cjk_text.len() <= 20 && chars.len() >= 2 {
+            terms.push(cjk_text.to_lowercase());
+        }
+    }
+
+    terms
+}
+
+/// Check if a character is a CJK (Chinese, Japanese, Korean) character.
+pub fn is_cjk_char(c: char) -> bool {
+    matches!(c,
+        '\u{4E00}'..='\u{9FFF}' |      // CJK Unified Ideographs
+        '\u{3400}'..='\u{4DBF}' |      // CJK Unified Ideographs Extension A
+        '\u{20000}'..='\u{2A6DF}' |    // CJK Unified Ideographs Extension B
+        '\u{2A700}'..='\u{2B73F}' |    // CJK Unified Ideographs Extension C
+        '\u{2B740}'..='\u{2B81F}' |    // CJK Unified Ideographs Extension D
+        '\u{2B820}'..='\u{2CEAF}' |    // CJK Unified Ideographs Extension E
+        '\u{2CEB0}'..='\u{2EBEF}' |    // CJK Unified Ideographs Extension F
+        '\u{30000}'..='\u{3134F}' |    // CJK Unified Ideographs Extension G
+        '\u{F900}'..='\u{FAFF}' |      // CJK Compatibility Ideographs
+        '\u{2F800}'..='\u{2FA1F}' |    // CJK Compatibility Ideographs Supplement
+        '\u{3040}'..='\u{309F}' |      // Hiragana
+        '\u{30A0}'..='\u{30FF}' |      // Katakana
+        '\u{AC00}'..='\u{D7AF}'        // Korean Hangul Syllables
+    )
+}
+
+/// Normalize a term (lowercase, trim).
+pub fn normalize_term(term: &str) -> String {
+    term.to_lowercase().trim().to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_matches_indexer() {
+        for text in [
+            "Hello World! This is a test.",
+            "你好世界",
+            "Rust 编程语言 and ひらがな, 한국어 text",
+            "a b 中",
+            "",
+        ] {
+            assert_eq!(
+                tokenize_text(text),
+                typstify_search::simple::tokenize_text(text),
+                "{text}"
+            );
+        }
+    }
+}
@@ -0,0 +1,246 @@
+//! Language-aware term analysis.
+//!
+//! After text is split into terms, an [`Analyzer`] drops the stopwords of
+//! the page language and reduces English words to their Porter stems, so
+//! that "building" and "builds" both find "build". Indexes record the
+//! analyzer they were built with, and the browser runtime
+//! (`typstify-search-wasm`) analyzes queries with it, so query terms are
+//! always those that were indexed.
+
+use std::{collections::HashMap, fs, path::Path};
+
+use serde::{Deserialize, Serialize};
+use tantivy::tokenizer::{
+    Language, LowerCaser, SimpleTokenizer, Stemmer as StemmerFilter, StopWordFilter, TextAnalyzer,
+};
+
+use crate::SearchError;
+
+/// File in a Tantivy index directory recording its [`Analyzer`].
+pub const ANALYZER_FILE: &str = "analyzer.json";
+
+/// Algorithm reducing words to their stems.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Stemmer {
+    /// Porter stemmer for English.
+    English,
+}
+
+impl Stemmer {
+    /// Stemmer of the language `lang` (`en`, `en-US`), if it has one.
+    pub fn for_language(lang: &str) -> Option<Self> {
+        match primary_subtag(lang).to_ascii_lowercase().as_str() {
+            "en" => Some(Self::English),
+            _ => None,
+        }
+    }
+
+    /// Stem of `term`.
+    pub fn stem(self, term: &str) -> String {
+        rust_stemmers::Stemmer::create(self.algorithm())
+            .stem(term)
+            .into_owned()
+    }
+
+    fn algorithm(self) -> rust_stemmers::Algorithm {
+        match self {
+            Self::English => rust_stemmers::Algorithm::English,
+        }
+    }
+
+    fn language(self) -> Language {
+        match self {
+            Self::English => Language::English,
+        }
+    }
+}
+
+/// Stopword removal and stemming applied to terms after tokenizing.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Analyzer {
+    /// Stemmer, if terms are stemmed.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub stemmer: Option<Stemmer>,
+
+    /// Lowercase words left out, sorted.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub stopwords: Vec<String>,
+}
+
+impl Analyzer {
+    /// Analyzer for pages in the language `lang`: stemming when `stemming`
+    /// is set and the language has a stemmer, and the stopwords listed for
+    /// `lang` (or its primary subtag) in `stopwords`.
+    pub fn for_language(
+        lang: &str,
+        stemming: bool,
+        stopwords: &HashMap<String, Vec<String>>,
+    ) -> Self {
+        let mut words: Vec<String> = stopwords
+            .get(lang)
+            .or_else(|| stopwords.get(primary_subtag(lang)))
+            .into_iter()
+            .flatten()
+            .map(|word| word.trim().to_lowercase())
+            .filter(|word| !word.is_empty())
+            .collect();
+        words.sort_unstable();
+        words.dedup();
+
+        Self {
+            stemmer: if stemming {
+                Stemmer::for_language(lang)
+            } else {
+                None
+            },
+            stopwords: words,
+        }
+    }
+
+    /// Whether terms pass through unchanged.
+    pub fn is_identity(&self) -> bool {
+        self.stemmer.is_none() && self.stopwords.is_empty()
+    }
+
+    /// Whether the lowercase `term` is a stopword.
+    pub fn is_stopword(&self, term: &str) -> bool {
+        self.stopwords
+            .binary_search_by(|word| word.as_str().cmp(term))
+            .is_ok()
+    }
+
+    /// `term` as it is indexed, or `None` for a stopword.
+    pub fn analyze_term(&self, term: &str) -> Option<String> {
+        if self.is_stopword(term) {
+            return None;
+        }
+        Some(match self.stemmer {
+            Some(stemmer) => stemmer.stem(term),
+            None => term.to_string(),
+        })
+    }
+
+    /// `terms` as they are indexed, without stopwords.
+    pub fn analyze(&self, terms: Vec<String>) -> Vec<String> {
+        if self.is_identity() {
+            return terms;
+        }
+        terms
+            .iter()
+            .filter_map(|term| self.analyze_term(term))
+            .collect()
+    }
+
+    /// Tantivy tokenizer applying the analyzer after splitting text into
+    /// lowercased words.
+    pub fn text_analyzer(&self) -> TextAnalyzer {
+        let mut builder = TextAnalyzer::builder(SimpleTokenizer::default())
+            .filter(LowerCaser)
+            .dynamic();
+        if !self.stopwords.is_empty() {
+            builder = builder.filter_dynamic(StopWordFilter::remove(self.stopwords.clone()));
+        }
+        if let Some(stemmer) = self.stemmer {
+            builder = builder.filter_dynamic(StemmerFilter::new(stemmer.language()));
+        }
+        builder.build()
+    }
+
+    /// Read the analyzer recorded in the index directory `dir`; indexes
+    /// recording none were built without one.
+    pub fn read_from(dir: &Path) -> Result<Self, SearchError> {
+        match fs::read_to_string(dir.join(ANALYZER_FILE)) {
+            Ok(json) => {
+                serde_json::from_str(&json).map_err(|e| SearchError::Serialization(e.to_string()))
+            }
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(Self::default()),
+            Err(e) => Err(SearchError::Io(e.to_string())),
+        }
+    }
+
+    /// Record the analyzer in the index directory `dir`.
+    pub fn write_to(&self, dir: &Path) -> Result<(), SearchError> {
+        let json =
+            serde_json::to_string(self).map_err(|e| SearchError::Serialization(e.to_string()))?;
+        fs::write(dir.join(ANALYZER_FILE), json).map_err(|e| SearchError::Io(e.to_string()))
+    }
+}
+
+/// Primary language subtag of `lang` (`en` for `en-US`).
+fn primary_subtag(lang: &str) -> &str {
+    lang.split(['-', '_']).next().unwrap_or(lang)
+}
+
+#[cfg(test)]
+mod tests {
+    use tantivy::tokenizer::TokenStream;
+
+    use super::*;
+
+    fn stopwords() -> HashMap<String, Vec<String>> {
+        HashMap::from([
+            ("en".to_string(), vec!["The".to_string(), "and".to_string()]),
+            ("de".to_string(), vec!["und".to_string()]),
+        ])
+    }
+
+    fn tantivy_terms(analyzer: &Analyzer, text: &str) -> Vec<String> {
+        let mut tokenizer = analyzer.text_analyzer();
+        let mut stream = tokenizer.token_stream(text);
+        let mut terms = Vec::new();
+        while stream.advance() {
+            terms.push(stream.token().text.clone());
+        }
+        terms
+    }
+
+    #[test]
+    fn test_analyze() {
+        let english = Analyzer::for_language("en-US", true, &stopwords());
+        assert_eq!(english.stemmer, Some(Stemmer::English));
+        assert_eq!(english.stopwords, vec!["and", "the"]);
+        assert_eq!(
+            english.analyze(
+                ["the", "building", "and", "builds", "build"]
+                    .map(String::from)
+                    .to_vec()
+            ),
+            vec!["build", "build", "build"]
+        );
+
+        // Only English is stemmed
+        let german = Analyzer::for_language("de", true, &stopwords());
+        assert_eq!(german.stemmer, None);
+        assert_eq!(
+            german.analyze(vec!["häuser".to_string(), "und".to_string()]),
+            vec!["häuser"]
+        );
+
+        let plain = Analyzer::for_language("en", false, &HashMap::new());
+        assert!(plain.is_identity());
+        assert_eq!(plain, Analyzer::default());
+    }
+
+    #[test]
+    fn test_text_analyzer_matches_analyze() {
+        let analyzer = Analyzer::for_language("en", true, &stopwords());
+        assert_eq!(
+            tantivy_terms(&analyzer, "The Building and running"),
+            analyzer.analyze(vec!["building".to_string(), "running".to_string(),])
+        );
+    }
+
+    #[test]
+    fn test_read_and_write() {
+        let temp = tempfile::TempDir::new().unwrap();
+        assert_eq!(
+            Analyzer::read_from(temp.path()).unwrap(),
+            Analyzer::default()
+        );
+
+        let analyzer = Analyzer::for_language("en", true, &stopwords());
+        analyzer.write_to(temp.path()).unwrap();
+        assert_eq!(Analyzer::read_from(temp.path()).unwrap(), analyzer);
+    }
+}
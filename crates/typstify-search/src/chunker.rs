@@ -8,17 +8,29 @@ use std::{collections::HashMap, fs, path::Path};
 use serde::{Deserialize, Serialize};
 use tracing::{debug, info};
 
-use crate::SearchError;
+use crate::{SearchError, schema::SCHEMA_VERSION};
 
 /// Default chunk size (64KB).
 pub const DEFAULT_CHUNK_SIZE: usize = 64 * 1024;
 
+/// Directory of a chunked index in the site output, next to
+/// `search-index.json`.
+pub const CHUNKED_INDEX_DIR: &str = "search";
+
+/// Manifest file of a chunked index, read first by the browser runtime.
+pub const INDEX_MANIFEST_FILE: &str = "manifest.json";
+
 /// Manifest describing the chunked index structure.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct IndexManifest {
     /// Manifest format version.
     pub version: u32,
 
+    /// Search schema version of the chunked index ([`SCHEMA_VERSION`]); 0
+    /// for manifests written before it was recorded.
+    #[serde(default)]
+    pub schema_version: u32,
+
     /// Chunk size used for splitting.
     pub chunk_size: usize,
 
@@ -34,6 +46,7 @@ impl IndexManifest {
     pub fn new(chunk_size: usize) -> Self {
         Self {
             version: 1,
+            schema_version: SCHEMA_VERSION,
             chunk_size,
             total_size: 0,
             files: HashMap::new(),
@@ -324,6 +337,7 @@ mod tests {
         let parsed = IndexManifest::from_json(&json).unwrap();
 
         assert_eq!(parsed.version, manifest.version);
+        assert_eq!(parsed.schema_version, SCHEMA_VERSION);
         assert_eq!(parsed.chunk_size, manifest.chunk_size);
         assert_eq!(parsed.total_size, manifest.total_size);
         assert_eq!(parsed.files.len(), 1);
@@ -0,0 +1,155 @@
+//! Encrypted search indexes for preview deployments.
+//!
+//! Drafts and pages of protected sections are left out of the public search
+//! index. A preview deployment can still search them through a second index,
+//! published encrypted with AES-256-GCM under a key derived from a
+//! passphrase with PBKDF2-HMAC-SHA256. The envelope holds everything the
+//! browser needs to decrypt it with the Web Crypto API.
+
+use std::num::NonZeroU32;
+
+use base64::{Engine, engine::general_purpose::STANDARD};
+use ring::{
+    aead::{AES_256_GCM, Aad, LessSafeKey, NONCE_LEN, Nonce, UnboundKey},
+    pbkdf2,
+    rand::{SecureRandom, SystemRandom},
+};
+use serde::{Deserialize, Serialize};
+
+use crate::{Result, SearchError};
+
+/// File name of the encrypted index, next to `search-index.json`.
+pub const ENCRYPTED_INDEX_FILE: &str = "search-index.preview.json";
+
+/// Envelope format version.
+pub const ENCRYPTED_INDEX_VERSION: u32 = 1;
+
+/// PBKDF2 iterations for new envelopes.
+pub const PBKDF2_ITERATIONS: u32 = 600_000;
+
+/// Salt length in bytes.
+const SALT_LEN: usize = 16;
+
+/// An encrypted search index.
+///
+/// Binary fields are base64; `data` is the ciphertext followed by the GCM
+/// tag, as Web Crypto's `decrypt` expects.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct EncryptedIndex {
+    /// Envelope format version.
+    pub version: u32,
+
+    /// PBKDF2-HMAC-SHA256 iterations.
+    pub iterations: u32,
+
+    /// PBKDF2 salt.
+    pub salt: String,
+
+    /// AES-GCM nonce.
+    pub nonce: String,
+
+    /// Encrypted index JSON.
+    pub data: String,
+}
+
+impl EncryptedIndex {
+    /// Encrypt the index JSON `json` with `passphrase`.
+    pub fn seal(json: &str, passphrase: &str) -> Result<Self> {
+        let rng = SystemRandom::new();
+        let mut salt = [0u8; SALT_LEN];
+        let mut nonce = [0u8; NONCE_LEN];
+        rng.fill(&mut salt)
+            .and_then(|()| rng.fill(&mut nonce))
+            .map_err(|_| SearchError::Encryption("no secure random numbers".to_string()))?;
+
+        let key = derive_key(passphrase, &salt, PBKDF2_ITERATIONS)?;
+        let mut data = json.as_bytes().to_vec();
+        key.seal_in_place_append_tag(Nonce::assume_unique_for_key(nonce), Aad::empty(), &mut data)
+            .map_err(|_| SearchError::Encryption("encryption failed".to_string()))?;
+
+        Ok(Self {
+            version: ENCRYPTED_INDEX_VERSION,
+            iterations: PBKDF2_ITERATIONS,
+            salt: STANDARD.encode(salt),
+            nonce: STANDARD.encode(nonce),
+            data: STANDARD.encode(data),
+        })
+    }
+
+    /// Decrypt the index JSON with `passphrase`.
+    pub fn open(&self, passphrase: &str) -> Result<String> {
+        if self.version != ENCRYPTED_INDEX_VERSION {
+            return Err(SearchError::Encryption(format!(
+                "unsupported envelope version {}",
+                self.version
+            )));
+        }
+        let decode = |field: &str| {
+            STANDARD
+                .decode(field)
+                .map_err(|e| SearchError::Encryption(e.to_string()))
+        };
+        let salt = decode(&self.salt)?;
+        let nonce = Nonce::try_assume_unique_for_key(&decode(&self.nonce)?)
+            .map_err(|_| SearchError::Encryption("invalid nonce".to_string()))?;
+        let mut data = decode(&self.data)?;
+
+        let key = derive_key(passphrase, &salt, self.iterations)?;
+        let json = key
+            .open_in_place(nonce, Aad::empty(), &mut data)
+            .map_err(|_| SearchError::Encryption("wrong key or corrupted index".to_string()))?;
+        String::from_utf8(json.to_vec()).map_err(|e| SearchError::Encryption(e.to_string()))
+    }
+
+    /// Serialize the envelope to JSON.
+    pub fn to_json(&self) -> Result<String> {
+        serde_json::to_string(self).map_err(|e| SearchError::Serialization(e.to_string()))
+    }
+
+    /// Deserialize an envelope from JSON.
+    pub fn from_json(json: &str) -> Result<Self> {
+        serde_json::from_str(json).map_err(|e| SearchError::Serialization(e.to_string()))
+    }
+}
+
+/// AES-256 key derived from `passphrase`.
+fn derive_key(passphrase: &str, salt: &[u8], iterations: u32) -> Result<LessSafeKey> {
+    let iterations = NonZeroU32::new(iterations)
+        .ok_or_else(|| SearchError::Encryption("zero PBKDF2 iterations".to_string()))?;
+    let mut key = [0u8; 32];
+    pbkdf2::derive(
+        pbkdf2::PBKDF2_HMAC_SHA256,
+        iterations,
+        salt,
+        passphrase.as_bytes(),
+        &mut key,
+    );
+    UnboundKey::new(&AES_256_GCM, &key)
+        .map(LessSafeKey::new)
+        .map_err(|_| SearchError::Encryption("invalid key".to_string()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_seal_and_open() {
+        let json = r#"{"documents":[{"url":"/drafts/wip","title":"WIP"}]}"#;
+        let sealed = EncryptedIndex::seal(json, "hunter2").unwrap();
+        assert_eq!(sealed.iterations, PBKDF2_ITERATIONS);
+        assert!(!sealed.data.contains("WIP"));
+
+        let envelope = EncryptedIndex::from_json(&sealed.to_json().unwrap()).unwrap();
+        assert_eq!(envelope.open("hunter2").unwrap(), json);
+        assert!(matches!(
+            envelope.open("wrong"),
+            Err(SearchError::Encryption(_))
+        ));
+
+        // Fresh salt and nonce every time
+        let again = EncryptedIndex::seal(json, "hunter2").unwrap();
+        assert_ne!(again.salt, sealed.salt);
+        assert_ne!(again.data, sealed.data);
+    }
+}
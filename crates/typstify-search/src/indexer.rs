@@ -2,17 +2,21 @@
 //!
 //! Provides functionality to index pages and build optimized search indexes.
 
-use std::path::Path;
+use std::{collections::HashMap, fs, path::Path};
 
 use tantivy::{
     DateTime as TantivyDateTime, Index, IndexWriter, TantivyDocument, directory::MmapDirectory,
 };
 use tracing::{debug, info};
-use typstify_core::Page;
+use typstify_core::{Page, unescape_html};
 
 use crate::{
     SearchError,
-    schema::{SearchFields, create_search_schema, register_tokenizers},
+    analyzer::Analyzer,
+    schema::{
+        SCHEMA_VERSION, SCHEMA_VERSION_FILE, SearchFields, create_search_schema,
+        register_tokenizers,
+    },
 };
 
 /// Configuration for the search indexer.
@@ -24,6 +28,10 @@ pub struct IndexerConfig {
 
     /// Default language for pages without explicit language.
     pub default_lang: String,
+
+    /// Stopword removal and stemming of the indexed text, recorded in the
+    /// index for the browser runtime. Default: English stemming.
+    pub analyzer: Analyzer,
 }
 
 impl Default for IndexerConfig {
@@ -31,6 +39,7 @@ impl Default for IndexerConfig {
         Self {
             memory_budget: 50_000_000, // 50MB
             default_lang: "en".to_string(),
+            analyzer: Analyzer::for_language("en", true, &HashMap::new()),
         }
     }
 }
@@ -54,28 +63,49 @@ pub struct SearchIndexer {
     config: IndexerConfig,
     index: Index,
     fields: SearchFields,
+    rebuilt: bool,
 }
 
 impl SearchIndexer {
     /// Create a new indexer with index stored at the given path.
     ///
-    /// Creates the directory if it doesn't exist.
+    /// Creates the directory if it doesn't exist. An existing typstify index
+    /// (one with a [`SCHEMA_VERSION_FILE`]) built with another
+    /// [`SCHEMA_VERSION`] or analyzer is deleted and rebuilt from scratch.
+    /// A directory without a readable version file is never deleted.
     pub fn new(index_path: &Path, config: IndexerConfig) -> Result<Self, SearchError> {
+        let rebuilt = index_path.join("meta.json").exists()
+            && stored_schema_version(index_path).is_some_and(|version| {
+                version.trim() != SCHEMA_VERSION.to_string()
+                    || Analyzer::read_from(index_path).ok().as_ref() != Some(&config.analyzer)
+            });
+        if rebuilt {
+            info!(path = ?index_path, "Search index schema changed, rebuilding");
+            fs::remove_dir_all(index_path).map_err(|e| SearchError::Io(e.to_string()))?;
+        }
+
         // Create directory if needed
-        std::fs::create_dir_all(index_path).map_err(|e| SearchError::Io(e.to_string()))?;
+        fs::create_dir_all(index_path).map_err(|e| SearchError::Io(e.to_string()))?;
 
         let (schema, fields) = create_search_schema();
         let directory =
             MmapDirectory::open(index_path).map_err(|e| SearchError::Index(e.to_string()))?;
         let index = Index::open_or_create(directory, schema)
             .map_err(|e| SearchError::Index(e.to_string()))?;
+        fs::write(
+            index_path.join(SCHEMA_VERSION_FILE),
+            SCHEMA_VERSION.to_string(),
+        )
+        .map_err(|e| SearchError::Io(e.to_string()))?;
+        config.analyzer.write_to(index_path)?;
 
-        register_tokenizers(&index);
+        register_tokenizers(&index, &config.analyzer);
 
         Ok(Self {
             config,
             index,
             fields,
+            rebuilt,
         })
     }
 
@@ -84,15 +114,21 @@ impl SearchIndexer {
         let (schema, fields) = create_search_schema();
         let index = Index::create_in_ram(schema);
 
-        register_tokenizers(&index);
+        register_tokenizers(&index, &config.analyzer);
 
         Ok(Self {
             config,
             index,
             fields,
+            rebuilt: false,
         })
     }
 
+    /// Whether an outdated on-disk index was deleted when opening it.
+    pub fn rebuilt(&self) -> bool {
+        self.rebuilt
+    }
+
     /// Index a collection of pages.
     ///
     /// Returns the number of documents indexed.
@@ -205,6 +241,12 @@ impl SearchIndexer {
     }
 }
 
+/// Contents of the schema version file of the index at `index_path`, if it
+/// can be read.
+fn stored_schema_version(index_path: &Path) -> Option<String> {
+    fs::read_to_string(index_path.join(SCHEMA_VERSION_FILE)).ok()
+}
+
 /// Strip HTML tags from content to get plain text.
 ///
 /// This is a simple implementation that handles common cases.
@@ -252,14 +294,8 @@ fn strip_html_tags(html: &str) -> String {
         i += 1;
     }
 
-    // Decode common HTML entities
-    result = result
-        .replace("&nbsp;", " ")
-        .replace("&amp;", "&")
-        .replace("&lt;", "<")
-        .replace("&gt;", ">")
-        .replace("&quot;", "\"")
-        .replace("&#39;", "'");
+    // Decode HTML entities
+    result = unescape_html(&result);
 
     // Collapse multiple whitespace
     let mut collapsed = String::with_capacity(result.len());
@@ -292,6 +328,7 @@ mod tests {
             description: None,
             date: Some(Utc::now()),
             updated: None,
+            expiry_date: None,
             draft: false,
             lang: "en".to_string(),
             is_default_lang: true,
@@ -309,6 +346,13 @@ mod tests {
             custom_css: vec![],
             template: None,
             weight: 0,
+            pinned: false,
+            series: None,
+            content_id: None,
+            extra_meta: std::collections::BTreeMap::new(),
+            structured_data: None,
+            images: vec![],
+            event: None,
         }
     }
 
@@ -363,6 +407,63 @@ mod tests {
         assert_eq!(stats.document_count, 3);
     }
 
+    #[test]
+    fn test_rebuild_outdated_index() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let path = dir.path().join("index");
+        let page = create_test_page("/test", "Test Page", "<p>Test content</p>");
+
+        let indexer = SearchIndexer::new(&path, IndexerConfig::default()).unwrap();
+        assert!(!indexer.rebuilt());
+        indexer.index_pages(&[&page]).unwrap();
+        drop(indexer);
+
+        // Same schema: the index is kept
+        let indexer = SearchIndexer::new(&path, IndexerConfig::default()).unwrap();
+        assert!(!indexer.rebuilt());
+        assert_eq!(indexer.stats().unwrap().document_count, 1);
+        drop(indexer);
+
+        // Built before the schema changed: the index is started over
+        fs::write(path.join(SCHEMA_VERSION_FILE), "0").unwrap();
+        let indexer = SearchIndexer::new(&path, IndexerConfig::default()).unwrap();
+        assert!(indexer.rebuilt());
+        assert_eq!(indexer.stats().unwrap().document_count, 0);
+        assert_eq!(
+            stored_schema_version(&path),
+            Some(SCHEMA_VERSION.to_string())
+        );
+        indexer.index_pages(&[&page]).unwrap();
+        drop(indexer);
+
+        // Built with another analyzer: the index is started over
+        let config = IndexerConfig {
+            analyzer: Analyzer::default(),
+            ..IndexerConfig::default()
+        };
+        let indexer = SearchIndexer::new(&path, config.clone()).unwrap();
+        assert!(indexer.rebuilt());
+        assert_eq!(Analyzer::read_from(&path).unwrap(), config.analyzer);
+    }
+
+    #[test]
+    fn test_keep_index_without_version_file() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let path = dir.path().join("index");
+        let page = create_test_page("/test", "Test Page", "<p>Test content</p>");
+
+        let indexer = SearchIndexer::new(&path, IndexerConfig::default()).unwrap();
+        indexer.index_pages(&[&page]).unwrap();
+        drop(indexer);
+
+        // A missing version file doesn't prove the index is outdated
+        fs::remove_file(path.join(SCHEMA_VERSION_FILE)).unwrap();
+        let indexer = SearchIndexer::new(&path, IndexerConfig::default()).unwrap();
+        assert!(!indexer.rebuilt());
+        assert_eq!(indexer.stats().unwrap().document_count, 1);
+        assert!(path.join(SCHEMA_VERSION_FILE).is_file());
+    }
+
     #[test]
     fn test_indexer_config_default() {
         let config = IndexerConfig::default();
@@ -7,6 +7,10 @@
 //! - **Tantivy-based indexing**: Full-text search with language-aware tokenization
 //! - **Index chunking**: Split large indexes for efficient browser loading
 //! - **Simple index**: Lightweight JSON-based alternative for small sites
+//! - **Analyzers**: Per-language stopwords and English stemming, recorded
+//!   in the index so the browser analyzes queries the same way
+//! - **Encrypted indexes**: Searchable previews of drafts and protected
+//!   sections, decrypted in the browser with a key
 //!
 //! # Example
 //!
@@ -25,15 +29,27 @@
 //! // simple_index.write_to_file(Path::new("search.json"))?;
 //! ```
 
+pub mod analyzer;
 pub mod chunker;
+pub mod encrypted;
 pub mod indexer;
 pub mod schema;
 pub mod simple;
 
-pub use chunker::{ChunkerConfig, FileManifest, IndexChunker, IndexManifest};
+pub use analyzer::{ANALYZER_FILE, Analyzer, Stemmer};
+pub use chunker::{
+    CHUNKED_INDEX_DIR, ChunkerConfig, FileManifest, INDEX_MANIFEST_FILE, IndexChunker,
+    IndexManifest,
+};
+pub use encrypted::{ENCRYPTED_INDEX_FILE, EncryptedIndex};
 pub use indexer::{IndexStats, IndexerConfig, SearchIndexer};
-pub use schema::{SearchFields, create_search_schema, register_tokenizers};
-pub use simple::{MAX_SIMPLE_INDEX_SIZE, SimpleDocument, SimpleSearchIndex};
+pub use schema::{
+    SCHEMA_VERSION, SCHEMA_VERSION_FILE, SearchFields, create_search_schema, register_tokenizers,
+};
+pub use simple::{
+    Facet, FacetKind, MAX_SIMPLE_INDEX_SIZE, MIN_FUZZY_TERM_CHARS, MatchMode, SimpleDocument,
+    SimpleSearchIndex, TAG_WEIGHT, TITLE_WEIGHT,
+};
 use thiserror::Error;
 
 /// Search-related errors.
@@ -54,6 +70,10 @@ pub enum SearchError {
     /// Query error.
     #[error("Query error: {0}")]
     Query(String),
+
+    /// Encryption error.
+    #[error("Encryption error: {0}")]
+    Encryption(String),
 }
 
 /// Result type for search operations.
@@ -8,9 +8,18 @@ use tantivy::{
         DateOptions, FAST, Field, STORED, STRING, Schema, SchemaBuilder, TextFieldIndexing,
         TextOptions,
     },
-    tokenizer::{LowerCaser, SimpleTokenizer, TextAnalyzer},
 };
 
+use crate::analyzer::Analyzer;
+
+/// Version of the search schema: the fields documents carry and how they
+/// are tokenized. Bump it whenever either changes, so that indexes built
+/// with an older schema are rebuilt on disk and detected in the browser.
+pub const SCHEMA_VERSION: u32 = 2;
+
+/// File in a Tantivy index directory recording its [`SCHEMA_VERSION`].
+pub const SCHEMA_VERSION_FILE: &str = "schema_version";
+
 /// Search schema field references.
 #[derive(Debug, Clone)]
 pub struct SearchFields {
@@ -92,13 +101,12 @@ pub fn create_search_schema() -> (Schema, SearchFields) {
 
 /// Register custom tokenizers for the search index.
 ///
-/// Sets up the default tokenizer with lowercase normalization.
-pub fn register_tokenizers(index: &Index) {
-    let tokenizer = TextAnalyzer::builder(SimpleTokenizer::default())
-        .filter(LowerCaser)
-        .build();
-
-    index.tokenizers().register("default", tokenizer);
+/// Sets up the default tokenizer with lowercase normalization, followed by
+/// the stopword removal and stemming of `analyzer`.
+pub fn register_tokenizers(index: &Index, analyzer: &Analyzer) {
+    index
+        .tokenizers()
+        .register("default", analyzer.text_analyzer());
 }
 
 #[cfg(test)]
@@ -148,7 +156,7 @@ mod tests {
         let (schema, _) = create_search_schema();
         let index = Index::create_in_ram(schema);
 
-        register_tokenizers(&index);
+        register_tokenizers(&index, &Analyzer::default());
 
         // Verify the tokenizer is registered
         let tokenizer = index.tokenizers().get("default");
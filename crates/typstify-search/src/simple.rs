@@ -2,18 +2,71 @@
 //!
 //! Provides a lightweight alternative to Tantivy for sites with fewer pages.
 //! The entire index is loaded into memory in the browser.
+//!
+//! Results are ranked with BM25 over term frequencies weighted by field: a
+//! title occurrence counts [`TITLE_WEIGHT`] times, a tag [`TAG_WEIGHT`]
+//! times and a body occurrence once.
+//!
+//! Terms pass through the index's [`Analyzer`] when indexed, and the
+//! analyzer is stored with the index so that queries are analyzed alike.
 
-use std::{collections::HashMap, fs, path::Path};
+use std::{
+    collections::{BTreeMap, HashMap},
+    fs,
+    path::Path,
+};
 
 use serde::{Deserialize, Serialize};
 use tracing::info;
 use typstify_core::Page;
 
-use crate::SearchError;
+use crate::{SearchError, analyzer::Analyzer, schema::SCHEMA_VERSION};
 
 /// Maximum recommended size for simple index (500KB).
 pub const MAX_SIMPLE_INDEX_SIZE: usize = 500 * 1024;
 
+/// How loosely query terms may match indexed terms.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum MatchMode {
+    /// Only identical terms match.
+    Exact,
+
+    /// Indexed terms starting with the query term match too: "progra"
+    /// finds "programming".
+    #[default]
+    Prefix,
+
+    /// Indexed terms one edit (insertion, deletion or substitution) away
+    /// from a query term of at least [`MIN_FUZZY_TERM_CHARS`] characters
+    /// match too: "progrqmming" finds "programming".
+    Fuzzy,
+}
+
+/// Shortest query term matched fuzzily; shorter ones are one edit away
+/// from too many terms.
+pub const MIN_FUZZY_TERM_CHARS: usize = 4;
+
+/// Weight of a term occurrence in the title.
+pub const TITLE_WEIGHT: u32 = 3;
+
+/// Weight of a tag.
+pub const TAG_WEIGHT: u32 = 2;
+
+/// BM25 term frequency saturation.
+const BM25_K1: f32 = 1.2;
+
+/// BM25 document length normalization.
+const BM25_B: f32 = 0.75;
+
+/// How a query term matched a document, best first. Results are ranked by
+/// match before score.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+enum TermMatch {
+    Exact,
+    Prefix,
+    Fuzzy,
+}
+
 /// A simple search index document.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct SimpleDocument {
@@ -35,12 +88,68 @@ pub struct SimpleDocument {
     #[serde(default, skip_serializing_if = "Vec::is_empty")]
     pub tags: Vec<String>,
 
+    /// Top-level section (`posts` for `posts/hello.md`), if any.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub section: Option<String>,
+
     /// Publication date as ISO string.
     #[serde(skip_serializing_if = "Option::is_none")]
     pub date: Option<String>,
 
-    /// Pre-tokenized terms from title and body.
+    /// Pre-tokenized terms from title, tags and body, sorted.
     pub terms: Vec<String>,
+
+    /// Weighted frequency of each of `terms` (see [`TITLE_WEIGHT`]); empty
+    /// for indexes written before it was recorded, where every term counts
+    /// once.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub weights: Vec<u32>,
+}
+
+impl SimpleDocument {
+    /// Weighted frequency of `term` in the document.
+    #[must_use]
+    pub fn weight(&self, term: &str) -> u32 {
+        match self.terms.binary_search_by(|t| t.as_str().cmp(term)) {
+            Ok(i) => self.weights.get(i).copied().unwrap_or(1),
+            Err(_) => 0,
+        }
+    }
+
+    /// Weighted number of terms in the document.
+    #[must_use]
+    pub fn length(&self) -> u32 {
+        if self.weights.is_empty() {
+            self.terms.len() as u32
+        } else {
+            self.weights.iter().sum()
+        }
+    }
+}
+
+/// What a [`Facet`] filters by.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum FacetKind {
+    /// A tag.
+    Tag,
+
+    /// A top-level section.
+    Section,
+}
+
+/// A tag or section name with the number of documents it has, offered as
+/// a search filter suggestion.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Facet {
+    /// Tag or section.
+    pub kind: FacetKind,
+
+    /// Tag or section name.
+    pub name: String,
+
+    /// Number of documents.
+    pub count: usize,
 }
 
 /// A simple JSON-based search index.
@@ -49,11 +158,24 @@ pub struct SimpleSearchIndex {
     /// Index format version.
     pub version: u32,
 
+    /// Search schema version ([`SCHEMA_VERSION`]); 0 for indexes written
+    /// before it was recorded.
+    #[serde(default)]
+    pub schema_version: u32,
+
     /// All indexed documents.
     pub documents: Vec<SimpleDocument>,
 
     /// Inverted index: term -> document indices.
     pub index: HashMap<String, Vec<usize>>,
+
+    /// Tags and sections of the documents, most documents first.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub facets: Vec<Facet>,
+
+    /// Stopword removal and stemming of indexed and query terms.
+    #[serde(default, skip_serializing_if = "Analyzer::is_identity")]
+    pub analyzer: Analyzer,
 }
 
 impl SimpleSearchIndex {
@@ -61,14 +183,31 @@ impl SimpleSearchIndex {
     pub fn new() -> Self {
         Self {
             version: 1,
+            schema_version: SCHEMA_VERSION,
             documents: Vec::new(),
             index: HashMap::new(),
+            facets: Vec::new(),
+            analyzer: Analyzer::default(),
         }
     }
 
-    /// Build an index from a collection of pages.
+    /// Analyze indexed and query terms with `analyzer`.
+    #[must_use]
+    pub fn with_analyzer(mut self, analyzer: Analyzer) -> Self {
+        self.analyzer = analyzer;
+        self
+    }
+
+    /// Build an index from a collection of pages, without stopwords or
+    /// stemming.
     pub fn from_pages(pages: &[&Page]) -> Self {
-        let mut index = Self::new();
+        Self::from_pages_with(pages, Analyzer::default())
+    }
+
+    /// Build an index from a collection of pages, analyzing their terms
+    /// with `analyzer`.
+    pub fn from_pages_with(pages: &[&Page], analyzer: Analyzer) -> Self {
+        let mut index = Self::new().with_analyzer(analyzer);
 
         for page in pages {
             index.add_page(page);
@@ -80,7 +219,10 @@ impl SimpleSearchIndex {
 
     /// Add a page to the index.
     pub fn add_page(&mut self, page: &Page) {
-        let terms = tokenize_content(&page.title, &page.content, &page.tags);
+        let (terms, weights) =
+            weighted_terms(&page.title, &page.content, &page.tags, &self.analyzer)
+                .into_iter()
+                .unzip();
 
         let doc = SimpleDocument {
             url: page.url.clone(),
@@ -88,8 +230,13 @@ impl SimpleSearchIndex {
             description: page.description.clone().or(page.summary.clone()),
             lang: Some(page.lang.clone()),
             tags: page.tags.clone(),
+            section: page
+                .canonical_id
+                .split_once('/')
+                .map(|(section, _)| section.to_string()),
             date: page.date.map(|d| d.to_rfc3339()),
             terms,
+            weights,
         };
 
         self.documents.push(doc);
@@ -111,6 +258,8 @@ impl SimpleSearchIndex {
             postings.dedup();
         }
 
+        self.build_facets();
+
         info!(
             documents = self.documents.len(),
             terms = self.index.len(),
@@ -118,43 +267,154 @@ impl SimpleSearchIndex {
         );
     }
 
-    /// Search the index for matching documents.
+    /// Count the documents of each tag and section.
+    fn build_facets(&mut self) {
+        let mut counts: HashMap<(FacetKind, &str), usize> = HashMap::new();
+        for doc in &self.documents {
+            let mut tags: Vec<&str> = doc.tags.iter().map(String::as_str).collect();
+            tags.sort_unstable();
+            tags.dedup();
+            for tag in tags {
+                *counts.entry((FacetKind::Tag, tag)).or_default() += 1;
+            }
+            if let Some(section) = &doc.section {
+                *counts.entry((FacetKind::Section, section)).or_default() += 1;
+            }
+        }
+
+        let mut facets: Vec<Facet> = counts
+            .into_iter()
+            .map(|((kind, name), count)| Facet {
+                kind,
+                name: name.to_string(),
+                count,
+            })
+            .collect();
+        facets.sort_by(|a, b| (b.count, a.kind, &a.name).cmp(&(a.count, b.kind, &b.name)));
+        self.facets = facets;
+    }
+
+    /// Search the index for matching documents, with prefix matching.
     ///
-    /// Returns documents matching all query terms (AND search).
+    /// Returns documents matching all query terms (AND search), most
+    /// relevant first.
     pub fn search(&self, query: &str) -> Vec<&SimpleDocument> {
-        let query_terms = tokenize_query(query);
+        self.search_with(query, MatchMode::default())
+    }
+
+    /// Search the index for documents matching all query terms under
+    /// `mode`, most relevant first.
+    pub fn search_with(&self, query: &str, mode: MatchMode) -> Vec<&SimpleDocument> {
+        self.search_scored(query, mode)
+            .into_iter()
+            .map(|(doc, _)| doc)
+            .collect()
+    }
+
+    /// Search the index for documents matching all query terms under
+    /// `mode`, with their BM25 scores, highest first.
+    ///
+    /// Documents matching every term exactly come first, then those that
+    /// need a prefix match, then fuzzy matches; each by score, ties keeping
+    /// index order.
+    pub fn search_scored(&self, query: &str, mode: MatchMode) -> Vec<(&SimpleDocument, f32)> {
+        let query_terms = self.analyzer.analyze(tokenize_query(query));
 
         if query_terms.is_empty() {
             return Vec::new();
         }
 
-        // Find documents containing all query terms
-        let mut result_indices: Option<Vec<usize>> = None;
+        let average_length = self.average_length();
+        let mut results: Option<HashMap<usize, (TermMatch, f32)>> = None;
 
         for term in &query_terms {
-            if let Some(postings) = self.index.get(term) {
-                match &mut result_indices {
-                    None => {
-                        result_indices = Some(postings.clone());
-                    }
-                    Some(indices) => {
-                        // Intersect with existing results
-                        indices.retain(|idx| postings.contains(idx));
-                    }
-                }
-            } else {
+            let matches = self.term_scores(term, mode, average_length);
+            let merged: HashMap<usize, (TermMatch, f32)> = match results {
+                None => matches,
+                // Intersect with existing results; a document matches as
+                // well as its weakest term
+                Some(previous) => previous
+                    .into_iter()
+                    .filter_map(|(idx, (kind, score))| {
+                        let (term_kind, term_score) = matches.get(&idx)?;
+                        Some((idx, (kind.max(*term_kind), score + term_score)))
+                    })
+                    .collect(),
+            };
+            if merged.is_empty() {
                 // Term not found, no results
                 return Vec::new();
             }
+            results = Some(merged);
         }
 
-        result_indices
-            .unwrap_or_default()
-            .iter()
-            .filter_map(|&idx| self.documents.get(idx))
+        let mut ranked: Vec<(usize, (TermMatch, f32))> =
+            results.unwrap_or_default().into_iter().collect();
+        ranked.sort_by(|(a, (a_kind, a_score)), (b, (b_kind, b_score))| {
+            a_kind
+                .cmp(b_kind)
+                .then(b_score.total_cmp(a_score))
+                .then(a.cmp(b))
+        });
+        ranked
+            .into_iter()
+            .filter_map(|(idx, (_, score))| Some((self.documents.get(idx)?, score)))
             .collect()
     }
 
+    /// Match and score of the documents containing a term matching the
+    /// query term `term` under `mode`, by their best matching term: the best
+    /// match, then the highest score.
+    fn term_scores(
+        &self,
+        term: &str,
+        mode: MatchMode,
+        average_length: f32,
+    ) -> HashMap<usize, (TermMatch, f32)> {
+        let fuzzy = mode == MatchMode::Fuzzy && term.chars().count() >= MIN_FUZZY_TERM_CHARS;
+        let mut scores: HashMap<usize, (TermMatch, f32)> = HashMap::new();
+        let mut add = |indexed: &str, postings: &[usize], kind: TermMatch| {
+            for &idx in postings {
+                let doc = &self.documents[idx];
+                let score = bm25(
+                    doc.weight(indexed),
+                    doc.length(),
+                    average_length,
+                    postings.len(),
+                    self.documents.len(),
+                );
+                let best = scores.entry(idx).or_insert((kind, score));
+                if kind < best.0 || (kind == best.0 && score > best.1) {
+                    *best = (kind, score);
+                }
+            }
+        };
+
+        if let Some(postings) = self.index.get(term) {
+            add(term, postings, TermMatch::Exact);
+        }
+        if mode == MatchMode::Exact {
+            return scores;
+        }
+        for (indexed, postings) in &self.index {
+            if indexed == term {
+                continue;
+            }
+            if indexed.starts_with(term) {
+                add(indexed, postings, TermMatch::Prefix);
+            } else if fuzzy && within_one_edit(term, indexed) {
+                add(indexed, postings, TermMatch::Fuzzy);
+            }
+        }
+        scores
+    }
+
+    /// Average weighted document length.
+    fn average_length(&self) -> f32 {
+        let total: u32 = self.documents.iter().map(SimpleDocument::length).sum();
+        total as f32 / self.documents.len().max(1) as f32
+    }
+
     /// Serialize the index to JSON.
     pub fn to_json(&self) -> Result<String, SearchError> {
         serde_json::to_string(self).map_err(|e| SearchError::Serialization(e.to_string()))
@@ -197,6 +457,7 @@ impl SimpleSearchIndex {
                     + d.title.len()
                     + d.description.as_ref().map(|s| s.len()).unwrap_or(0)
                     + d.terms.iter().map(|t| t.len() + 3).sum::<usize>()
+                    + d.weights.len() * 2
                     + 100 // JSON overhead
             })
             .sum()
@@ -214,31 +475,81 @@ impl Default for SimpleSearchIndex {
     }
 }
 
-/// Tokenize content for indexing.
+/// BM25 score of a term occurring `weight` times in a document of `length`
+/// terms, given the `average_length` and that `matching` of `documents`
+/// documents contain the term.
 ///
-/// Extracts terms from title, body content, and tags.
-fn tokenize_content(title: &str, content: &str, tags: &[String]) -> Vec<String> {
-    let mut terms = Vec::new();
+/// The browser runtime (`typstify-search-wasm`) scores with the same
+/// formula.
+#[must_use]
+pub fn bm25(
+    weight: u32,
+    length: u32,
+    average_length: f32,
+    matching: usize,
+    documents: usize,
+) -> f32 {
+    let matching = matching as f32;
+    let idf = (1.0 + (documents as f32 - matching + 0.5) / (matching + 0.5)).ln();
+    let tf = weight as f32;
+    let norm = 1.0 - BM25_B + BM25_B * length as f32 / average_length.max(1.0);
+    idf * tf * (BM25_K1 + 1.0) / (tf + BM25_K1 * norm)
+}
 
-    // Tokenize title (higher weight, keep as-is)
-    for term in tokenize_text(title) {
-        terms.push(term);
+/// Whether `a` becomes `b` with at most one inserted, deleted or replaced
+/// character.
+fn within_one_edit(a: &str, b: &str) -> bool {
+    let a: Vec<char> = a.chars().collect();
+    let b: Vec<char> = b.chars().collect();
+    let (shorter, longer) = if a.len() <= b.len() {
+        (&a, &b)
+    } else {
+        (&b, &a)
+    };
+    if longer.len() - shorter.len() > 1 {
+        return false;
     }
 
-    // Tokenize body content
-    let body_text = strip_html(content);
-    for term in tokenize_text(&body_text) {
-        terms.push(term);
+    let prefix = shorter
+        .iter()
+        .zip(longer.iter())
+        .take_while(|(x, y)| x == y)
+        .count();
+    if shorter.len() == longer.len() {
+        // Replace one character
+        shorter[prefix.min(shorter.len())..]
+            .iter()
+            .skip(1)
+            .eq(longer[prefix.min(longer.len())..].iter().skip(1))
+    } else {
+        // Insert one character into the shorter term
+        shorter[prefix..].iter().eq(longer[prefix + 1..].iter())
     }
+}
 
-    // Add tags
+/// Tokenize content for indexing.
+///
+/// Extracts terms from title, body content, and tags as `analyzer` indexes
+/// them, with the number of times each occurs weighted by field.
+fn weighted_terms(
+    title: &str,
+    content: &str,
+    tags: &[String],
+    analyzer: &Analyzer,
+) -> BTreeMap<String, u32> {
+    let mut terms: BTreeMap<String, u32> = BTreeMap::new();
+
+    for term in analyzer.analyze(tokenize_text(title)) {
+        *terms.entry(term).or_default() += TITLE_WEIGHT;
+    }
     for tag in tags {
-        terms.push(normalize_term(tag));
+        if let Some(term) = analyzer.analyze_term(&normalize_term(tag)) {
+            *terms.entry(term).or_default() += TAG_WEIGHT;
+        }
+    }
+    for term in analyzer.analyze(tokenize_text(&strip_html(content))) {
+        *terms.entry(term).or_default() += 1;
     }
-
-    // Deduplicate
-    terms.sort();
-    terms.dedup();
 
     terms
 }
@@ -250,7 +561,10 @@ fn tokenize_query(query: &str) -> Vec<String> {
 
 /// Tokenize text into normalized terms.
 /// Supports both space-separated languages (English) and CJK languages (Chinese, Japanese, Korean).
-fn tokenize_text(text: &str) -> Vec<String> {
+///
+/// The browser runtime (`typstify-search-wasm`) tokenizes queries the same
+/// way.
+pub fn tokenize_text(text: &str) -> Vec<String> {
     let mut terms = Vec::new();
 
     // First, extract word-based terms (for English and other space-separated languages)
@@ -341,6 +655,7 @@ mod tests {
             description: Some(format!("Description of {}", title)),
             date: Some(Utc::now()),
             updated: None,
+            expiry_date: None,
             draft: false,
             lang: "en".to_string(),
             is_default_lang: true,
@@ -358,6 +673,13 @@ mod tests {
             custom_css: vec![],
             template: None,
             weight: 0,
+            pinned: false,
+            series: None,
+            content_id: None,
+            extra_meta: std::collections::BTreeMap::new(),
+            structured_data: None,
+            images: vec![],
+            event: None,
         }
     }
 
@@ -479,6 +801,11 @@ mod tests {
 
         assert_eq!(parsed.documents.len(), 1);
         assert_eq!(parsed.documents[0].url, "/test");
+        assert_eq!(parsed.schema_version, SCHEMA_VERSION);
+
+        let legacy =
+            SimpleSearchIndex::from_json(r#"{"version":1,"documents":[],"index":{}}"#).unwrap();
+        assert_eq!(legacy.schema_version, 0);
     }
 
     #[test]
@@ -504,6 +831,210 @@ mod tests {
         assert_eq!(results[0].url, "/post1");
     }
 
+    #[test]
+    fn test_prefix_and_fuzzy_search() {
+        let page1 = create_test_page("/programs", "Programs", "<p>Example programs.</p>", vec![]);
+        let page2 = create_test_page(
+            "/programming",
+            "Programming",
+            "<p>Programming languages.</p>",
+            vec![],
+        );
+        let page3 = create_test_page("/progress", "Progress", "<p>Status.</p>", vec![]);
+        let index = SimpleSearchIndex::from_pages(&[&page1, &page2, &page3]);
+        let urls = |results: Vec<&SimpleDocument>| {
+            results
+                .into_iter()
+                .map(|doc| doc.url.clone())
+                .collect::<Vec<_>>()
+        };
+
+        assert!(index.search_with("progra", MatchMode::Exact).is_empty());
+        assert_eq!(
+            urls(index.search("progra")),
+            vec!["/programs", "/programming"]
+        );
+        assert_eq!(urls(index.search("programs")), vec!["/programs"]);
+        assert_eq!(
+            urls(index.search("program")),
+            vec!["/programs", "/programming"]
+        );
+
+        assert!(index.search("progrqmming").is_empty());
+        assert_eq!(
+            urls(index.search_with("progrqmming", MatchMode::Fuzzy)),
+            vec!["/programming"]
+        );
+        // Exact > prefix > fuzzy, whatever the index order
+        let typo = create_test_page("/typo", "Progrem", "<p>Tag notes.</p>", vec![]);
+        let exact = create_test_page("/exact", "Program", "<p>Notes.</p>", vec![]);
+        let index = SimpleSearchIndex::from_pages(&[&typo, &page1, &exact]);
+        assert_eq!(
+            urls(index.search_with("program", MatchMode::Fuzzy)),
+            vec!["/exact", "/programs", "/typo"]
+        );
+        // Short terms aren't matched fuzzily
+        assert_eq!(
+            urls(index.search_with("tags", MatchMode::Fuzzy)),
+            vec!["/typo"]
+        );
+        assert!(index.search_with("tap", MatchMode::Fuzzy).is_empty());
+    }
+
+    #[test]
+    fn test_exact_match_outranks_rare_prefix_match() {
+        // "deploy" is in every document, so its exact matches score low;
+        // "deployment" is rare and heavily weighted in the title
+        let mut pages: Vec<Page> = (0..8)
+            .map(|i| {
+                create_test_page(
+                    &format!("/common/{i}"),
+                    "Notes",
+                    "<p>How we deploy things, with many other words around it.</p>",
+                    vec![],
+                )
+            })
+            .collect();
+        pages.push(create_test_page(
+            "/rare",
+            "Deployment",
+            "<p>Deployment deployment.</p>",
+            vec!["deployment".to_string()],
+        ));
+        let index = SimpleSearchIndex::from_pages(&pages.iter().collect::<Vec<_>>());
+
+        let scored = index.search_scored("deploy", MatchMode::Prefix);
+        assert_eq!(scored.len(), 9);
+        assert_eq!(scored.last().unwrap().0.url, "/rare");
+        // Raw scores would rank it first
+        assert!(scored.last().unwrap().1 > scored[0].1);
+    }
+
+    #[test]
+    fn test_bm25_ranking() {
+        let body = create_test_page(
+            "/body",
+            "Notes",
+            "<p>Mentions typst once among other words.</p>",
+            vec![],
+        );
+        let tagged = create_test_page(
+            "/tagged",
+            "Notes",
+            "<p>Other words.</p>",
+            vec!["typst".to_string()],
+        );
+        let title = create_test_page("/title", "Typst", "<p>Other words.</p>", vec![]);
+        let repeated = create_test_page(
+            "/repeated",
+            "Notes",
+            "<p>Typst typst typst typst.</p>",
+            vec![],
+        );
+        let unrelated = create_test_page("/unrelated", "Other", "<p>Nothing.</p>", vec![]);
+        let index = SimpleSearchIndex::from_pages(&[&body, &tagged, &title, &repeated, &unrelated]);
+
+        let doc = &index.documents[2];
+        assert_eq!(doc.weight("typst"), TITLE_WEIGHT);
+        assert_eq!(doc.weight("missing"), 0);
+        assert_eq!(index.documents[3].weight("typst"), 4);
+
+        let scored = index.search_scored("typst", MatchMode::Exact);
+        let urls: Vec<&str> = scored.iter().map(|(doc, _)| doc.url.as_str()).collect();
+        assert_eq!(urls, vec!["/repeated", "/title", "/tagged", "/body"]);
+        assert!(scored.windows(2).all(|w| w[0].1 > w[1].1));
+        assert!(scored.iter().all(|(_, score)| *score > 0.0));
+
+        // Indexes without weights count every term once
+        let mut legacy = index.clone();
+        for doc in &mut legacy.documents {
+            doc.weights.clear();
+        }
+        assert_eq!(legacy.documents[3].weight("typst"), 1);
+        assert_eq!(legacy.search("typst").len(), 4);
+    }
+
+    #[test]
+    fn test_analyzed_search() {
+        let stopwords = HashMap::from([("en".to_string(), vec!["the".to_string()])]);
+        let analyzer = Analyzer::for_language("en", true, &stopwords);
+        let build = create_test_page(
+            "/build",
+            "Build the site",
+            "<p>How it builds.</p>",
+            vec!["testing".to_string()],
+        );
+        let deploy = create_test_page("/deploy", "Deploy", "<p>The server.</p>", vec![]);
+        let index = SimpleSearchIndex::from_pages_with(&[&build, &deploy], analyzer.clone());
+
+        assert_eq!(index.documents[0].weight("build"), TITLE_WEIGHT + 1);
+        assert_eq!(index.documents[0].weight("test"), TAG_WEIGHT);
+        assert!(!index.index.contains_key("the"));
+        assert_eq!(index.search_with("building", MatchMode::Exact).len(), 1);
+        assert_eq!(index.search("tests")[0].url, "/build");
+        // Stopwords in a query are ignored
+        assert_eq!(index.search("the deploy")[0].url, "/deploy");
+        assert!(index.search("the").is_empty());
+
+        let restored = SimpleSearchIndex::from_json(&index.to_json().unwrap()).unwrap();
+        assert_eq!(restored.analyzer, analyzer);
+        assert!(
+            !SimpleSearchIndex::new()
+                .to_json()
+                .unwrap()
+                .contains("analyzer")
+        );
+    }
+
+    #[test]
+    fn test_within_one_edit() {
+        assert!(within_one_edit("rust", "rust"));
+        assert!(within_one_edit("rust", "rest"));
+        assert!(within_one_edit("rust", "rusty"));
+        assert!(within_one_edit("rusty", "rust"));
+        assert!(within_one_edit("rust", "ust"));
+        assert!(within_one_edit("搜索", "搜素"));
+        assert!(!within_one_edit("rust", "rsut"));
+        assert!(!within_one_edit("rust", "rustacean"));
+        assert!(!within_one_edit("rust", "bust!"));
+    }
+
+    #[test]
+    fn test_facets() {
+        let page1 = create_test_page(
+            "/posts/rust",
+            "Rust",
+            "<p>Rust.</p>",
+            vec!["rust".to_string(), "lang".to_string()],
+        );
+        let page2 = create_test_page("/posts/go", "Go", "<p>Go.</p>", vec!["lang".to_string()]);
+        let page3 = create_test_page("/about", "About", "<p>Me.</p>", vec![]);
+        let index = SimpleSearchIndex::from_pages(&[&page1, &page2, &page3]);
+
+        assert_eq!(index.documents[0].section.as_deref(), Some("posts"));
+        assert_eq!(index.documents[2].section, None);
+        let facets: Vec<(FacetKind, &str, usize)> = index
+            .facets
+            .iter()
+            .map(|f| (f.kind, f.name.as_str(), f.count))
+            .collect();
+        assert_eq!(
+            facets,
+            vec![
+                (FacetKind::Tag, "lang", 2),
+                (FacetKind::Section, "posts", 2),
+                (FacetKind::Tag, "rust", 1),
+            ]
+        );
+
+        let json = index.to_json().unwrap();
+        assert!(json.contains(r#"{"kind":"section","name":"posts","count":2}"#));
+        assert_eq!(
+            SimpleSearchIndex::from_json(&json).unwrap().facets,
+            index.facets
+        );
+    }
+
     #[test]
     fn test_estimated_size() {
         let page = create_test_page(
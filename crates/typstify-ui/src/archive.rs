@@ -0,0 +1,280 @@
+//! Archive timeline of dated pages.
+//!
+//! Groups the content inventory that `typstify list --json` prints by year
+//! and month, newest first, with each year collapsible.
+
+use leptos::prelude::*;
+use serde::{Deserialize, Serialize};
+
+/// English month names, for month headings.
+const MONTH_NAMES: [&str; 12] = [
+    "January",
+    "February",
+    "March",
+    "April",
+    "May",
+    "June",
+    "July",
+    "August",
+    "September",
+    "October",
+    "November",
+    "December",
+];
+
+/// A page of the content inventory (`typstify list --json`).
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, Hash)]
+pub struct InventoryEntry {
+    /// Page URL.
+    pub url: String,
+
+    /// Page title.
+    pub title: String,
+
+    /// Publication date (RFC 3339).
+    #[serde(default)]
+    pub date: Option<String>,
+
+    /// Language code.
+    #[serde(default)]
+    pub lang: String,
+
+    /// Top-level section.
+    #[serde(default)]
+    pub section: Option<String>,
+
+    /// Template, if not the default.
+    #[serde(default)]
+    pub template: Option<String>,
+
+    /// Whether the page is a draft.
+    #[serde(default)]
+    pub draft: bool,
+
+    /// Tags.
+    #[serde(default)]
+    pub tags: Vec<String>,
+}
+
+impl InventoryEntry {
+    /// Parse the inventory JSON.
+    pub fn list_from_json(json: &str) -> Result<Vec<Self>, serde_json::Error> {
+        serde_json::from_str(json)
+    }
+
+    /// Year and month (1-12) of the publication date.
+    fn year_month(&self) -> Option<(i32, u32)> {
+        let date = self.date.as_deref()?;
+        let year = date.get(0..4)?.parse().ok()?;
+        let month = date
+            .get(5..7)?
+            .parse()
+            .ok()
+            .filter(|m| (1..=12).contains(m))?;
+        Some((year, month))
+    }
+}
+
+/// The pages of one year in the timeline.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct ArchiveYear {
+    /// Year.
+    pub year: i32,
+
+    /// Months with pages, newest first.
+    pub months: Vec<ArchiveMonth>,
+}
+
+impl ArchiveYear {
+    /// Number of pages in the year.
+    #[must_use]
+    pub fn count(&self) -> usize {
+        self.months.iter().map(|month| month.entries.len()).sum()
+    }
+}
+
+/// The pages of one month in the timeline.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct ArchiveMonth {
+    /// Month (1-12).
+    pub month: u32,
+
+    /// Pages, newest first.
+    pub entries: Vec<InventoryEntry>,
+}
+
+impl ArchiveMonth {
+    /// English name of the month.
+    #[must_use]
+    pub fn name(&self) -> &'static str {
+        MONTH_NAMES[(self.month as usize).saturating_sub(1) % 12]
+    }
+}
+
+/// Group the dated pages of `entries` by year and month, newest first.
+/// Undated pages are left out.
+#[must_use]
+pub fn timeline(entries: &[InventoryEntry]) -> Vec<ArchiveYear> {
+    let mut dated: Vec<(i32, u32, &InventoryEntry)> = entries
+        .iter()
+        .filter_map(|entry| {
+            let (year, month) = entry.year_month()?;
+            Some((year, month, entry))
+        })
+        .collect();
+    // RFC 3339 dates in UTC sort as strings
+    dated.sort_by(|a, b| (&b.2.date, &a.2.url).cmp(&(&a.2.date, &b.2.url)));
+
+    let mut years: Vec<ArchiveYear> = Vec::new();
+    for (year, month, entry) in dated {
+        if years.last().is_none_or(|last| last.year != year) {
+            years.push(ArchiveYear {
+                year,
+                months: Vec::new(),
+            });
+        }
+        let months = &mut years.last_mut().expect("year was pushed").months;
+        if months.last().is_none_or(|last| last.month != month) {
+            months.push(ArchiveMonth {
+                month,
+                entries: Vec::new(),
+            });
+        }
+        months
+            .last_mut()
+            .expect("month was pushed")
+            .entries
+            .push(entry.clone());
+    }
+    years
+}
+
+/// Archive timeline component.
+///
+/// Lists the dated pages of the inventory by year and month. Years are
+/// `<details>` elements; the `expanded` most recent ones (1 unless given)
+/// start open.
+#[component]
+pub fn ArchiveTimeline(
+    /// Pages of the inventory (`typstify list --json`).
+    entries: Signal<Vec<InventoryEntry>>,
+    /// Number of most recent years shown expanded.
+    #[prop(optional)]
+    expanded: Option<usize>,
+) -> impl IntoView {
+    let expanded = expanded.unwrap_or(1);
+
+    view! {
+      <div class="typstify-archive">
+        <For
+          each=move || entries.with(|entries| timeline(entries).into_iter().enumerate())
+          key=|(i, year)| (*i, year.clone())
+          children=move |(i, year)| {
+            let count = year.count();
+            view! {
+              <details class="typstify-archive-year" open=i < expanded>
+                <summary>
+                  <span class="typstify-archive-year-label">{year.year}</span>
+                  <span class="typstify-archive-count">{count}</span>
+                </summary>
+                {year
+                  .months
+                  .into_iter()
+                  .map(|month| {
+                    let name = month.name();
+                    view! {
+                      <section class="typstify-archive-month">
+                        <h3>{name}</h3>
+                        <ul>
+                          {month
+                            .entries
+                            .into_iter()
+                            .map(|entry| {
+                              let date = entry
+                                .date
+                                .as_deref()
+                                .and_then(|d| d.get(..10))
+                                .unwrap_or_default()
+                                .to_string();
+                              view! {
+                                <li class="typstify-archive-entry">
+                                  <time datetime=date.clone()>{date.get(5..).unwrap_or_default().to_string()}</time>
+                                  <a href=entry.url.clone()>{entry.title.clone()}</a>
+                                </li>
+                              }
+                            })
+                            .collect_view()}
+                        </ul>
+                      </section>
+                    }
+                  })
+                  .collect_view()}
+              </details>
+            }
+          }
+        />
+      </div>
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(url: &str, date: Option<&str>) -> InventoryEntry {
+        InventoryEntry {
+            url: url.to_string(),
+            title: url.to_string(),
+            date: date.map(ToString::to_string),
+            lang: "en".to_string(),
+            section: None,
+            template: None,
+            draft: false,
+            tags: vec![],
+        }
+    }
+
+    #[test]
+    fn test_timeline() {
+        let entries = [
+            entry("/old", Some("2025-12-31T00:00:00Z")),
+            entry("/about", None),
+            entry("/march", Some("2026-03-02T00:00:00Z")),
+            entry("/january", Some("2026-01-05T00:00:00Z")),
+            entry("/march-later", Some("2026-03-20T00:00:00Z")),
+        ];
+        let years = timeline(&entries);
+
+        assert_eq!(
+            years
+                .iter()
+                .map(|y| (y.year, y.count()))
+                .collect::<Vec<_>>(),
+            vec![(2026, 3), (2025, 1)]
+        );
+        let months = &years[0].months;
+        assert_eq!(months[0].name(), "March");
+        assert_eq!(
+            months[0]
+                .entries
+                .iter()
+                .map(|e| e.url.as_str())
+                .collect::<Vec<_>>(),
+            vec!["/march-later", "/march"]
+        );
+        assert_eq!(months[1].month, 1);
+        assert_eq!(years[1].months[0].name(), "December");
+    }
+
+    #[test]
+    fn test_list_from_json() {
+        let entries = InventoryEntry::list_from_json(
+            r#"[{"url":"/posts/a","title":"A","date":"2026-01-02T00:00:00Z","lang":"en","section":"posts","tags":["rust"]},{"url":"/about","title":"About","lang":"en"}]"#,
+        )
+        .unwrap();
+        assert_eq!(entries[0].section.as_deref(), Some("posts"));
+        assert_eq!(entries[0].year_month(), Some((2026, 1)));
+        assert_eq!(entries[1].year_month(), None);
+        assert!(!entries[1].draft);
+    }
+}
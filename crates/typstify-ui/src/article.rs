@@ -4,6 +4,8 @@
 
 use leptos::prelude::*;
 
+use crate::hydrate::hydrate;
+
 /// Article component properties.
 #[derive(Clone, Debug, Default, PartialEq)]
 pub struct ArticleData {
@@ -22,12 +24,24 @@ pub struct ArticleData {
 
 /// Article component for rendering HTML content.
 ///
-/// Renders pre-rendered HTML content with optional custom CSS and JS.
+/// Renders pre-rendered HTML content with optional custom CSS and JS. Once
+/// the content is mounted, and again whenever it changes, code blocks get
+/// copy buttons, headings anchor links, `<img data-src>` images lazy
+/// loading and footnote references popovers (see [`crate::hydrate`]).
 #[component]
 pub fn Article(
     /// The article data to render.
     data: Signal<ArticleData>,
 ) -> impl IntoView {
+    let content_ref = NodeRef::<leptos::html::Div>::new();
+
+    Effect::new(move |previous: Option<Option<crate::hydrate::Hydration>>| {
+        // Detach from the replaced content first
+        drop(previous);
+        data.with(|data| data.content.len());
+        content_ref.get().map(|content| hydrate(&content))
+    });
+
     view! {
       <article class="typstify-article">
         // Custom CSS links
@@ -45,7 +59,11 @@ pub fn Article(
         </header>
 
         // Article content (rendered HTML)
-        <div class="typstify-article-content" inner_html=move || data.get().content.clone()></div>
+        <div
+          node_ref=content_ref
+          class="typstify-article-content"
+          inner_html=move || data.get().content.clone()
+        ></div>
 
         // Custom JS scripts (deferred)
         <For
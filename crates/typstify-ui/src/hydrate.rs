@@ -0,0 +1,475 @@
+//! Behavior for article HTML injected by [`Article`](crate::Article).
+//!
+//! Rendered content arrives as an HTML string, so its interactive parts are
+//! attached once it is in the page, matching the static theme's script:
+//!
+//! - copy buttons on code blocks
+//! - anchor links on headings, with the parser's slug as id where missing
+//! - lazy images: an `<img data-src>` loads when scrolled near
+//! - footnote popovers: hovering, focusing or tapping a footnote reference
+//!   shows the note
+
+use std::{cell::RefCell, rc::Rc};
+
+use wasm_bindgen::{JsCast, prelude::*};
+use web_sys::{
+    AbortController, AddEventListenerOptions, Document, Element, Event, HtmlElement,
+    IntersectionObserver, IntersectionObserverEntry, IntersectionObserverInit, KeyboardEvent,
+    Window,
+};
+
+/// How long a footnote popover stays after the pointer leaves, in ms.
+const POPOVER_HIDE_DELAY_MS: i32 = 200;
+
+/// How long a copy button says "Copied", in ms.
+const COPIED_LABEL_MS: i32 = 2000;
+
+/// How far outside the viewport lazy images start loading.
+const LAZY_ROOT_MARGIN: &str = "200px";
+
+/// Listeners and observers attached to article content; dropping it
+/// detaches them.
+#[derive(Default)]
+pub struct Hydration {
+    /// Aborts the event listeners.
+    controller: Option<AbortController>,
+
+    /// Lazy image observer.
+    observer: Option<IntersectionObserver>,
+
+    /// Footnote popover element.
+    popover: Option<Element>,
+
+    /// Pending popover hide timer.
+    hide_timer: Rc<RefCell<Option<i32>>>,
+
+    /// Event listener callbacks, kept alive while attached.
+    listeners: Vec<Closure<dyn FnMut(Event)>>,
+
+    /// Lazy image observer callback.
+    observer_callback: Option<Closure<dyn FnMut(js_sys::Array)>>,
+
+    /// Popover hide callback, for timers.
+    hide_callback: Option<Closure<dyn FnMut()>>,
+}
+
+impl Drop for Hydration {
+    fn drop(&mut self) {
+        if let Some(controller) = &self.controller {
+            controller.abort();
+        }
+        if let Some(observer) = &self.observer {
+            observer.disconnect();
+        }
+        if let (Some(window), Some(timer)) =
+            (web_sys::window(), self.hide_timer.borrow_mut().take())
+        {
+            window.clear_timeout_with_handle(timer);
+        }
+        if let Some(popover) = &self.popover {
+            popover.remove();
+        }
+    }
+}
+
+impl Hydration {
+    /// Call `callback` on `event` at `target` until the hydration is
+    /// dropped.
+    fn listen(
+        &mut self,
+        target: &web_sys::EventTarget,
+        event: &str,
+        callback: impl FnMut(Event) + 'static,
+    ) {
+        let Some(controller) = &self.controller else {
+            return;
+        };
+        let callback = Closure::<dyn FnMut(Event)>::new(callback);
+        let options = AddEventListenerOptions::new();
+        options.set_signal(&controller.signal());
+        let _ = target.add_event_listener_with_callback_and_add_event_listener_options(
+            event,
+            callback.as_ref().unchecked_ref(),
+            &options,
+        );
+        self.listeners.push(callback);
+    }
+}
+
+/// Attach copy buttons, heading anchors, lazy images and footnote popovers
+/// to the content in `root`.
+pub fn hydrate(root: &Element) -> Hydration {
+    let mut hydration = Hydration::default();
+    let (Some(window), Some(document)) = (web_sys::window(), root.owner_document()) else {
+        return hydration;
+    };
+    hydration.controller = AbortController::new().ok();
+
+    add_copy_buttons(&mut hydration, &window, &document, root);
+    add_heading_anchors(&document, root);
+    observe_lazy_images(&mut hydration, root);
+    add_footnote_popovers(&mut hydration, &window, &document, root);
+    hydration
+}
+
+/// Elements in `root` matching `selector`.
+fn select(root: &Element, selector: &str) -> Vec<Element> {
+    let Ok(nodes) = root.query_selector_all(selector) else {
+        return Vec::new();
+    };
+    (0..nodes.length())
+        .filter_map(|i| nodes.get(i)?.dyn_into::<Element>().ok())
+        .collect()
+}
+
+/// A "Copy" button in each code block, copying its code.
+fn add_copy_buttons(
+    hydration: &mut Hydration,
+    window: &Window,
+    document: &Document,
+    root: &Element,
+) {
+    for pre in select(root, "pre") {
+        let Some(code) = pre.query_selector("code").ok().flatten() else {
+            continue;
+        };
+        if pre
+            .query_selector(".typstify-copy-button")
+            .ok()
+            .flatten()
+            .is_some()
+        {
+            continue;
+        }
+        let Ok(button) = document.create_element("button") else {
+            continue;
+        };
+        let _ = button.set_attribute("type", "button");
+        let _ = button.set_attribute("class", "typstify-copy-button");
+        let _ = button.set_attribute("aria-label", "Copy code");
+        button.set_text_content(Some("Copy"));
+        let _ = pre.class_list().add_1("typstify-has-copy");
+        let _ = pre.append_child(&button);
+
+        let window = window.clone();
+        let label = button.clone();
+        hydration.listen(&button, "click", move |_| {
+            let text = code.text_content().unwrap_or_default();
+            let _ = window.navigator().clipboard().write_text(&text);
+            label.set_text_content(Some("Copied"));
+            let reset = label.clone();
+            let _ = window.set_timeout_with_callback_and_timeout_and_arguments_0(
+                Closure::once_into_js(move || reset.set_text_content(Some("Copy"))).unchecked_ref(),
+                COPIED_LABEL_MS,
+            );
+        });
+    }
+}
+
+/// A "#" link to each heading, giving headings without an id the slug of
+/// their text.
+fn add_heading_anchors(document: &Document, root: &Element) {
+    for heading in select(root, "h2, h3, h4, h5, h6") {
+        if heading
+            .query_selector(".typstify-heading-anchor")
+            .ok()
+            .flatten()
+            .is_some()
+        {
+            continue;
+        }
+        let mut id = heading.id();
+        if id.is_empty() {
+            id = slugify(&heading.text_content().unwrap_or_default());
+            if id.is_empty() {
+                continue;
+            }
+            heading.set_id(&id);
+        }
+        let Ok(anchor) = document.create_element("a") else {
+            continue;
+        };
+        let _ = anchor.set_attribute("class", "typstify-heading-anchor");
+        let _ = anchor.set_attribute("href", &format!("#{id}"));
+        let _ = anchor.set_attribute("aria-label", "Link to this section");
+        anchor.set_text_content(Some("#"));
+        let _ = heading.append_child(&anchor);
+    }
+}
+
+/// Load each `<img data-src>` when it comes near the viewport.
+fn observe_lazy_images(hydration: &mut Hydration, root: &Element) {
+    let images = select(root, "img[data-src]");
+    if images.is_empty() {
+        return;
+    }
+
+    let callback = Closure::<dyn FnMut(js_sys::Array)>::new(move |entries: js_sys::Array| {
+        for entry in entries.iter() {
+            let Ok(entry) = entry.dyn_into::<IntersectionObserverEntry>() else {
+                continue;
+            };
+            if entry.is_intersecting() {
+                load_image(&entry.target());
+            }
+        }
+    });
+    let options = IntersectionObserverInit::new();
+    options.set_root_margin(LAZY_ROOT_MARGIN);
+    match IntersectionObserver::new_with_options(callback.as_ref().unchecked_ref(), &options) {
+        Ok(observer) => {
+            for image in &images {
+                observer.observe(image);
+            }
+            hydration.observer = Some(observer);
+            hydration.observer_callback = Some(callback);
+        }
+        // No observer support: load them all
+        Err(_) => images.iter().for_each(load_image),
+    }
+}
+
+/// Move the `data-src` of `image` to its `src`.
+fn load_image(image: &Element) {
+    if let Some(src) = image.get_attribute("data-src") {
+        let _ = image.set_attribute("src", &src);
+        let _ = image.remove_attribute("data-src");
+    }
+}
+
+/// A popover showing the footnote of a reference while it is hovered or
+/// focused, or after a tap on touch screens.
+fn add_footnote_popovers(
+    hydration: &mut Hydration,
+    window: &Window,
+    document: &Document,
+    root: &Element,
+) {
+    let refs = select(root, ".footnote-ref a");
+    if refs.is_empty() {
+        return;
+    }
+    let (Ok(popover), Some(body)) = (document.create_element("div"), document.body()) else {
+        return;
+    };
+    let _ = popover.set_attribute("class", "footnote-popover");
+    let _ = popover.set_attribute("role", "tooltip");
+    let Ok(popover) = popover.dyn_into::<HtmlElement>() else {
+        return;
+    };
+    popover.set_hidden(true);
+    let _ = body.append_child(&popover);
+    hydration.popover = Some(popover.clone().into());
+
+    let current: Rc<RefCell<Option<Element>>> = Rc::default();
+    let hide = {
+        let popover = popover.clone();
+        let current = current.clone();
+        move || {
+            popover.set_hidden(true);
+            current.borrow_mut().take();
+        }
+    };
+    let hide_callback = Closure::<dyn FnMut()>::new(hide.clone());
+    let hide_function: js_sys::Function = hide_callback.as_ref().clone().unchecked_into();
+    hydration.hide_callback = Some(hide_callback);
+
+    let timer = hydration.hide_timer.clone();
+    let cancel_hide = {
+        let window = window.clone();
+        move || {
+            if let Some(handle) = timer.borrow_mut().take() {
+                window.clear_timeout_with_handle(handle);
+            }
+        }
+    };
+    let timer = hydration.hide_timer.clone();
+    let schedule_hide = {
+        let window = window.clone();
+        let cancel_hide = cancel_hide.clone();
+        move || {
+            cancel_hide();
+            *timer.borrow_mut() = window
+                .set_timeout_with_callback_and_timeout_and_arguments_0(
+                    &hide_function,
+                    POPOVER_HIDE_DELAY_MS,
+                )
+                .ok();
+        }
+    };
+    let show = {
+        let window = window.clone();
+        let document = document.clone();
+        let popover = popover.clone();
+        let current = current.clone();
+        let cancel_hide = cancel_hide.clone();
+        move |reference: &Element| {
+            let Some(note) = footnote_id(&reference.get_attribute("href").unwrap_or_default())
+                .and_then(|id| document.get_element_by_id(&id))
+            else {
+                return;
+            };
+            cancel_hide();
+
+            let Ok(content) = note
+                .clone_node_with_deep(true)
+                .map(|node| node.unchecked_into::<Element>())
+            else {
+                return;
+            };
+            select(&content, ".footnote-backref")
+                .iter()
+                .for_each(Element::remove);
+            popover.set_inner_html(&content.inner_html());
+            popover.set_hidden(false);
+
+            let rect = reference.get_bounding_client_rect();
+            let width = f64::from(popover.offset_width());
+            let client_width = document
+                .document_element()
+                .map_or(0, |element| element.client_width());
+            let max_left = f64::from(client_width) - width - 8.0;
+            let left = (rect.left() + rect.width() / 2.0 - width / 2.0)
+                .min(max_left)
+                .max(8.0);
+            let style = popover.style();
+            let _ = style.set_property(
+                "left",
+                &format!("{}px", left + window.scroll_x().unwrap_or(0.0)),
+            );
+            let _ = style.set_property(
+                "top",
+                &format!(
+                    "{}px",
+                    rect.bottom() + window.scroll_y().unwrap_or(0.0) + 6.0
+                ),
+            );
+            *current.borrow_mut() = Some(reference.clone());
+        }
+    };
+
+    for reference in refs {
+        let on_show = {
+            let show = show.clone();
+            let reference = reference.clone();
+            move |_: Event| show(&reference)
+        };
+        hydration.listen(&reference, "mouseenter", on_show.clone());
+        hydration.listen(&reference, "focus", on_show);
+        let on_leave = {
+            let schedule_hide = schedule_hide.clone();
+            move |_: Event| schedule_hide()
+        };
+        hydration.listen(&reference, "mouseleave", on_leave.clone());
+        hydration.listen(&reference, "blur", on_leave);
+
+        // On touch devices a tap toggles the popover instead of jumping
+        let on_tap = {
+            let window = window.clone();
+            let show = show.clone();
+            let hide = hide.clone();
+            let current = current.clone();
+            let target = reference.clone();
+            move |event: Event| {
+                let touch = window
+                    .match_media("(hover: none)")
+                    .ok()
+                    .flatten()
+                    .is_some_and(|query| query.matches());
+                if !touch {
+                    return;
+                }
+                event.prevent_default();
+                let is_current = current.borrow().as_ref() == Some(&target);
+                if is_current {
+                    hide();
+                } else {
+                    show(&target);
+                }
+            }
+        };
+        hydration.listen(&reference, "click", on_tap);
+    }
+
+    let on_enter = {
+        let cancel_hide = cancel_hide.clone();
+        move |_: Event| cancel_hide()
+    };
+    hydration.listen(&popover, "mouseenter", on_enter);
+    hydration.listen(&popover, "mouseleave", move |_| schedule_hide());
+
+    let on_key = {
+        let hide = hide.clone();
+        move |event: Event| {
+            if event
+                .dyn_ref::<KeyboardEvent>()
+                .is_some_and(|e| e.key() == "Escape")
+            {
+                hide();
+            }
+        }
+    };
+    hydration.listen(document, "keydown", on_key);
+    let on_click = {
+        let popover = popover.clone();
+        move |event: Event| {
+            let target = event
+                .target()
+                .and_then(|t| t.dyn_into::<web_sys::Node>().ok());
+            let outside = |element: &web_sys::Node| !element.contains(target.as_ref());
+            let is_outside = current
+                .borrow()
+                .as_ref()
+                .is_some_and(|current| outside(&popover) && outside(current));
+            if is_outside {
+                hide();
+            }
+        }
+    };
+    hydration.listen(document, "click", on_click);
+}
+
+/// Element id of the footnote a reference links to (`#fn-1` → `fn-1`).
+fn footnote_id(href: &str) -> Option<String> {
+    let id = href.strip_prefix('#').filter(|id| !id.is_empty())?;
+    Some(
+        js_sys::decode_uri_component(id)
+            .map(String::from)
+            .unwrap_or_else(|_| id.to_string()),
+    )
+}
+
+/// Heading id for `text`, as the parser makes them: lowercase alphanumeric
+/// words joined by hyphens.
+fn slugify(text: &str) -> String {
+    text.to_lowercase()
+        .chars()
+        .filter_map(|c| {
+            if c.is_alphanumeric() {
+                Some(c)
+            } else if c.is_whitespace() || c == '-' || c == '_' {
+                Some('-')
+            } else {
+                None
+            }
+        })
+        .collect::<String>()
+        .split('-')
+        .filter(|s| !s.is_empty())
+        .collect::<Vec<_>>()
+        .join("-")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_slugify() {
+        assert_eq!(slugify("Getting Started"), "getting-started");
+        assert_eq!(slugify("  What's new in 2.0?  "), "whats-new-in-20");
+        assert_eq!(slugify("snake_case -- words"), "snake-case-words");
+        assert_eq!(slugify("中文 标题"), "中文-标题");
+        assert_eq!(slugify("!!!"), "");
+    }
+}
@@ -5,20 +5,27 @@
 //! # Components
 //!
 //! ## Search
-//! - [`SearchBox`] - Text input with debounced search
+//! - [`SearchBox`] - Text input with debounced search and tag/section
+//!   filter suggestions
 //! - [`SearchResults`] - List of search results
 //! - [`SearchModal`] - Modal dialog for search (Cmd/Ctrl+K)
 //! - [`SearchShortcut`] - Global keyboard shortcut handler
 //!
 //! ## Article
-//! - [`Article`] - Renders HTML content with custom CSS/JS
+//! - [`Article`] - Renders HTML content with custom CSS/JS, hydrating code
+//!   blocks, headings, images and footnotes
 //! - [`ArticleMeta`] - Article metadata (date, reading time, tags)
 //! - [`Prose`] - Styled prose wrapper
 //!
 //! ## Navigation
 //! - [`Navigation`] - Main site navigation
 //! - [`TableOfContents`] - Article table of contents
-//! - [`Breadcrumbs`] - Breadcrumb navigation
+//! - [`Breadcrumbs`] - Breadcrumb navigation derived from the generator's
+//!   `sections.json` ([`SiteStructure`])
+//!
+//! ## Archive
+//! - [`ArchiveTimeline`] - Dated pages by year and month, with collapsible
+//!   years, from the `typstify list --json` inventory
 //!
 //! # Example
 //!
@@ -39,10 +46,17 @@
 //! }
 //! ```
 
+pub mod archive;
 pub mod article;
+pub mod hydrate;
 pub mod navigation;
 pub mod search;
 
+pub use archive::{ArchiveMonth, ArchiveTimeline, ArchiveYear, InventoryEntry};
 pub use article::{Article, ArticleData, ArticleMeta, Prose};
-pub use navigation::{Breadcrumbs, NavItem, Navigation, TableOfContents, TocEntry};
-pub use search::{SearchBox, SearchModal, SearchResultItem, SearchResults, SearchShortcut};
+pub use navigation::{
+    Breadcrumbs, NavItem, Navigation, SectionInfo, SiteStructure, TableOfContents, TocEntry,
+};
+pub use search::{
+    SearchBox, SearchModal, SearchResultItem, SearchResults, SearchShortcut, SearchSuggestion,
+};
@@ -1,6 +1,8 @@
 //! Navigation components for site navigation and table of contents.
 //!
-//! Provides Navigation, NavLink, and TableOfContents components.
+//! Provides Navigation, NavLink, TableOfContents and Breadcrumbs components.
+
+use std::collections::BTreeMap;
 
 use leptos::prelude::*;
 use serde::{Deserialize, Serialize};
@@ -47,7 +49,7 @@ impl NavItem {
     }
 }
 
-/// Table of contents entry.
+/// Table of contents entry, as `typstify_core::TocEntry` serializes.
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 pub struct TocEntry {
     /// Heading level (1-6).
@@ -58,6 +60,10 @@ pub struct TocEntry {
 
     /// Anchor ID.
     pub id: String,
+
+    /// Deeper headings under this one.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub children: Vec<TocEntry>,
 }
 
 impl TocEntry {
@@ -67,8 +73,43 @@ impl TocEntry {
             level,
             text: text.into(),
             id: id.into(),
+            children: Vec::new(),
         }
     }
+
+    /// Parse a page's table of contents.
+    pub fn list_from_json(json: &str) -> Result<Vec<Self>, serde_json::Error> {
+        serde_json::from_str(json)
+    }
+
+    /// Nest `entries` (flat or nested) under the closest preceding
+    /// shallower heading, keeping levels `min_depth` to `max_depth`, like
+    /// the generator's `{{ toc }}`.
+    pub fn nest(entries: &[TocEntry], min_depth: u8, max_depth: u8) -> Vec<TocEntry> {
+        fn flatten<'a>(entries: &'a [TocEntry], flat: &mut Vec<&'a TocEntry>) {
+            for entry in entries {
+                flat.push(entry);
+                flatten(&entry.children, flat);
+            }
+        }
+        fn insert(toc: &mut Vec<TocEntry>, entry: TocEntry) {
+            match toc.last_mut() {
+                Some(last) if last.level < entry.level => insert(&mut last.children, entry),
+                _ => toc.push(entry),
+            }
+        }
+
+        let mut flat = Vec::new();
+        flatten(entries, &mut flat);
+        let mut toc = Vec::new();
+        for entry in flat
+            .into_iter()
+            .filter(|entry| (min_depth..=max_depth).contains(&entry.level))
+        {
+            insert(&mut toc, TocEntry::new(entry.level, &entry.text, &entry.id));
+        }
+        toc
+    }
 }
 
 /// Main navigation component.
@@ -159,6 +200,10 @@ fn NavLinkWithChildren(
 }
 
 /// Table of contents component.
+///
+/// Lists the headings of `entries` (a page's flat or nested TOC) from
+/// `min_depth` (1 unless given) to `max_depth` (3 unless given) as nested
+/// lists, matching the generator's `[toc]` defaults.
 #[component]
 pub fn TableOfContents(
     /// TOC entries.
@@ -166,59 +211,165 @@ pub fn TableOfContents(
     /// Currently active heading ID.
     #[prop(default = "".to_string().into())]
     active_id: Signal<String>,
+    /// Shallowest heading level listed.
+    #[prop(optional)]
+    min_depth: Option<u8>,
+    /// Deepest heading level listed.
+    #[prop(optional)]
+    max_depth: Option<u8>,
 ) -> impl IntoView {
+    let min_depth = min_depth.unwrap_or(1);
+    let max_depth = max_depth.unwrap_or(3);
+
     view! {
       <nav class="typstify-toc" aria-label="Table of contents">
         <h2 class="typstify-toc-title">"On this page"</h2>
-        <ul class="typstify-toc-list">
-          <For
-            each=move || entries.get()
-            key=|entry| entry.id.clone()
-            children=move |entry| {
-              let id = entry.id.clone();
-              let is_active = Memo::new(move |_| active_id.get() == id);
-              let indent_class = format!("typstify-toc-level-{}", entry.level);
-              let href = format!("#{}", entry.id);
+        {move || {
+          entries
+            .with(|entries| toc_list(TocEntry::nest(entries, min_depth, max_depth), active_id))
+        }}
+      </nav>
+    }
+}
 
-              view! {
-                <li class=indent_class class:active=is_active>
-                  <a href=href class="typstify-toc-link">
-                    {entry.text.clone()}
-                  </a>
-                </li>
-              }
+/// Nested TOC list of `entries`.
+fn toc_list(entries: Vec<TocEntry>, active_id: Signal<String>) -> AnyView {
+    view! {
+      <ul class="typstify-toc-list">
+        {entries
+          .into_iter()
+          .map(|entry| {
+            let id = entry.id.clone();
+            let is_active = Memo::new(move |_| active_id.get() == id);
+            let indent_class = format!("typstify-toc-level-{}", entry.level);
+            let href = format!("#{}", entry.id);
+            let children = (!entry.children.is_empty())
+              .then(|| toc_list(entry.children, active_id));
+
+            view! {
+              <li class=indent_class class:active=is_active>
+                <a href=href class="typstify-toc-link">
+                  {entry.text}
+                </a>
+                {children}
+              </li>
             }
-          />
+          })
+          .collect_view()}
+      </ul>
+    }
+    .into_any()
+}
 
-        </ul>
-      </nav>
+/// Sections and page titles of one language, as the generator writes them
+/// to `sections.json` (`/zh/sections.json` for other languages).
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+pub struct SiteStructure {
+    /// URL of the home page (e.g. "/blog/").
+    #[serde(default)]
+    pub home: String,
+
+    /// Section path (e.g. "docs/guides") -> section.
+    #[serde(default)]
+    pub sections: BTreeMap<String, SectionInfo>,
+
+    /// Page URL -> page title.
+    #[serde(default)]
+    pub pages: BTreeMap<String, String>,
+}
+
+/// A section in the [`SiteStructure`].
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct SectionInfo {
+    /// Display title.
+    pub title: String,
+
+    /// Section URL.
+    pub url: String,
+}
+
+impl SiteStructure {
+    /// Parse `sections.json`.
+    pub fn from_json(json: &str) -> Result<Self, serde_json::Error> {
+        serde_json::from_str(json)
+    }
+
+    /// Breadcrumb trail from the home page through the sections containing
+    /// `path` to `path` itself, which is the active item.
+    ///
+    /// Pages not in the structure (lists, taxonomy pages) are titled after
+    /// their last URL segment.
+    #[must_use]
+    pub fn trail(&self, path: &str) -> Vec<NavItem> {
+        let home = self.home.trim_end_matches('/');
+        let path = path.trim_end_matches("index.html").trim_end_matches('/');
+        let home_item = NavItem::new("Home", format!("{home}/"));
+
+        let Some(rest) = path.strip_prefix(home).map(|rest| rest.trim_matches('/')) else {
+            return vec![home_item];
+        };
+        if rest.is_empty() {
+            return vec![home_item.with_active(true)];
+        }
+
+        let mut trail = vec![home_item];
+        let segments: Vec<&str> = rest.split('/').collect();
+        for i in 0..segments.len() - 1 {
+            if let Some(section) = self.sections.get(&segments[..=i].join("/")) {
+                trail.push(NavItem::new(section.title.clone(), section.url.clone()));
+            }
+        }
+        let current = match (self.pages.get(path), self.sections.get(rest)) {
+            (Some(title), _) => NavItem::new(title.clone(), path),
+            (None, Some(section)) => NavItem::new(section.title.clone(), section.url.clone()),
+            (None, None) => NavItem::new(segment_title(segments[segments.len() - 1]), path),
+        };
+        trail.push(current.with_active(true));
+        trail
     }
 }
 
+/// Display title for a URL segment: capitalized, with hyphens as spaces.
+fn segment_title(segment: &str) -> String {
+    let name = segment.replace('-', " ");
+    let mut chars = name.chars();
+    match chars.next() {
+        Some(first) => first.to_uppercase().collect::<String>() + chars.as_str(),
+        None => name,
+    }
+}
+
+/// Path of the current page, from the browser location.
+fn current_path() -> String {
+    web_sys::window()
+        .and_then(|window| window.location().pathname().ok())
+        .unwrap_or_else(|| "/".to_string())
+}
+
 /// Breadcrumb navigation component.
+///
+/// Derives the trail from the site structure (`sections.json`) and the
+/// current path, which defaults to the browser location.
 #[component]
 pub fn Breadcrumbs(
-    /// Breadcrumb items (label, url).
-    items: Signal<Vec<(String, String)>>,
+    /// Sections and page titles of the site.
+    structure: Signal<SiteStructure>,
+    /// Path of the current page.
+    #[prop(optional, into)]
+    path: Option<Signal<String>>,
 ) -> impl IntoView {
+    let path = path.unwrap_or_else(|| Signal::derive(current_path));
+
     view! {
       <nav class="typstify-breadcrumbs" aria-label="Breadcrumb">
         <ol class="typstify-breadcrumb-list">
           <For
-            each=move || {
-              let items_vec = items.get();
-              let len = items_vec.len();
-              items_vec
-                .into_iter()
-                .enumerate()
-                .map(move |(i, (label, url))| (i, label, url, i == len - 1))
-                .collect::<Vec<_>>()
-            }
-
-            key=|(i, _, _, _)| *i
-            children=move |(_, label, url, is_last)| {
-              let label_for_fallback = label.clone();
-              let label_for_link = label.clone();
+            each=move || structure.with(|structure| structure.trail(&path.get()))
+            key=|item| item.url.clone()
+            children=move |item| {
+              let is_last = item.active;
+              let label_for_fallback = item.label.clone();
+              let label_for_link = item.label.clone();
               view! {
                 <li class="typstify-breadcrumb-item">
                   <Show
@@ -232,7 +383,7 @@ pub fn Breadcrumbs(
                     }
                   >
 
-                    <a href=url.clone() class="typstify-breadcrumb-link">
+                    <a href=item.url.clone() class="typstify-breadcrumb-link">
                       {label_for_link.clone()}
                     </a>
                     <span class="typstify-breadcrumb-separator" aria-hidden="true">
@@ -285,6 +436,94 @@ mod tests {
         assert_eq!(entry.id, "introduction");
     }
 
+    #[test]
+    fn test_toc_nest() {
+        // As the generator serializes a page's TOC
+        let entries = TocEntry::list_from_json(
+            r#"[{"level":1,"text":"Intro","id":"intro","children":[{"level":2,"text":"Setup","id":"setup"}]},{"level":4,"text":"Deep","id":"deep"},{"level":2,"text":"Usage","id":"usage"}]"#,
+        )
+        .unwrap();
+
+        let toc = TocEntry::nest(&entries, 2, 3);
+        assert_eq!(
+            toc,
+            vec![
+                TocEntry::new(2, "Setup", "setup"),
+                TocEntry::new(2, "Usage", "usage")
+            ]
+        );
+
+        let all = TocEntry::nest(&entries, 1, 6);
+        assert_eq!(all.len(), 1);
+        assert_eq!(
+            all[0]
+                .children
+                .iter()
+                .map(|e| e.id.as_str())
+                .collect::<Vec<_>>(),
+            vec!["setup", "usage"]
+        );
+        assert_eq!(all[0].children[0].children[0].id, "deep");
+    }
+
+    fn structure() -> SiteStructure {
+        SiteStructure::from_json(
+            r#"{
+                "home": "/blog/",
+                "sections": {
+                    "docs": {"title": "Docs", "url": "/blog/docs"},
+                    "docs/guides": {"title": "Guides", "url": "/blog/docs/guides"}
+                },
+                "pages": {"/blog/docs/guides/install": "Installing Typstify"}
+            }"#,
+        )
+        .unwrap()
+    }
+
+    fn labels(trail: &[NavItem]) -> Vec<(&str, &str, bool)> {
+        trail
+            .iter()
+            .map(|item| (item.label.as_str(), item.url.as_str(), item.active))
+            .collect()
+    }
+
+    #[test]
+    fn test_breadcrumb_trail() {
+        let structure = structure();
+        assert_eq!(
+            labels(&structure.trail("/blog/docs/guides/install/")),
+            vec![
+                ("Home", "/blog/", false),
+                ("Docs", "/blog/docs", false),
+                ("Guides", "/blog/docs/guides", false),
+                ("Installing Typstify", "/blog/docs/guides/install", true),
+            ]
+        );
+        assert_eq!(
+            labels(&structure.trail("/blog/docs/guides/index.html")),
+            vec![
+                ("Home", "/blog/", false),
+                ("Docs", "/blog/docs", false),
+                ("Guides", "/blog/docs/guides", true),
+            ]
+        );
+        assert_eq!(
+            labels(&structure.trail("/blog/tags/getting-started")),
+            vec![
+                ("Home", "/blog/", false),
+                ("Getting started", "/blog/tags/getting-started", true),
+            ]
+        );
+        assert_eq!(
+            labels(&structure.trail("/blog/")),
+            vec![("Home", "/blog/", true)]
+        );
+        assert_eq!(
+            labels(&SiteStructure::default().trail("/")),
+            vec![("Home", "/", true)]
+        );
+    }
+
     #[test]
     fn test_nav_item_serialization() {
         let item = NavItem::new("Test", "/test");
@@ -1,6 +1,8 @@
 //! Search components for the Typstify frontend.
 //!
 //! Provides SearchBox, SearchResults, and SearchModal Leptos components.
+//! The search box offers the index's tags and sections as filters
+//! (`tag:rust`) while the query is typed, from the engine's `suggest`.
 
 use leptos::prelude::*;
 use serde::{Deserialize, Serialize};
@@ -23,9 +25,37 @@ pub struct SearchResultItem {
     pub score: f32,
 }
 
+/// A tag or section to filter the search by, as suggested by the search
+/// engine for the word being typed.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct SearchSuggestion {
+    /// `tag` or `section`.
+    pub kind: String,
+
+    /// Tag or section name.
+    pub name: String,
+
+    /// Number of pages with the tag or in the section.
+    #[serde(default)]
+    pub count: usize,
+
+    /// The query with the typed word replaced by the filter.
+    pub query: String,
+}
+
+impl SearchSuggestion {
+    /// Label of the suggestion, such as "Filter by tag".
+    #[must_use]
+    pub fn label(&self) -> String {
+        format!("Filter by {}", self.kind)
+    }
+}
+
 /// Search box input component.
 ///
-/// Provides a text input with debounced search callback.
+/// Provides a text input with debounced search callback. With
+/// `suggestions`, offers them below the input; choosing one replaces the
+/// query with the suggestion's.
 #[component]
 pub fn SearchBox(
     /// Placeholder text for the input.
@@ -36,8 +66,12 @@ pub fn SearchBox(
     /// Whether search is loading.
     #[prop(default = false.into())]
     loading: Signal<bool>,
+    /// Filter suggestions for the word being typed.
+    #[prop(optional, into)]
+    suggestions: Option<Signal<Vec<SearchSuggestion>>>,
 ) -> impl IntoView {
     let input_ref = NodeRef::<leptos::html::Input>::new();
+    let suggestions = suggestions.unwrap_or_else(|| Signal::derive(Vec::new));
 
     // Focus input on mount
     Effect::new(move |_| {
@@ -62,6 +96,35 @@ pub fn SearchBox(
         <Show when=move || loading.get()>
           <span class="typstify-search-spinner" aria-label="Loading"></span>
         </Show>
+        <Show when=move || !suggestions.get().is_empty()>
+          <ul class="typstify-search-suggestions">
+            <For
+              each=move || suggestions.get()
+              key=|suggestion| suggestion.query.clone()
+              children=move |suggestion| {
+                let label = suggestion.label();
+                let next = suggestion.query.clone();
+                view! {
+                  <li class="typstify-search-suggestion" data-kind=suggestion.kind.clone()>
+                    <button
+                      type="button"
+                      on:click=move |_| {
+                        query.set(next.clone());
+                        if let Some(input) = input_ref.get() {
+                          let _ = input.focus();
+                        }
+                      }
+                    >
+                      <span class="typstify-search-suggestion-label">{label}</span>
+                      <span class="typstify-search-suggestion-name">{suggestion.name.clone()}</span>
+                      <span class="typstify-search-suggestion-count">{suggestion.count}</span>
+                    </button>
+                  </li>
+                }
+              }
+            />
+          </ul>
+        </Show>
       </div>
     }
 }
@@ -142,7 +205,12 @@ pub fn SearchModal(
     /// Whether search is loading.
     #[prop(default = false.into())]
     loading: Signal<bool>,
+    /// Filter suggestions for the word being typed.
+    #[prop(optional, into)]
+    suggestions: Option<Signal<Vec<SearchSuggestion>>>,
 ) -> impl IntoView {
+    let suggestions = suggestions.unwrap_or_else(|| Signal::derive(Vec::new));
+
     // Close on Escape key
     let on_keydown = move |ev: web_sys::KeyboardEvent| {
         if ev.key() == "Escape" {
@@ -165,7 +233,7 @@ pub fn SearchModal(
         <div class="typstify-modal-overlay" on:click=on_overlay_click on:keydown=on_keydown>
           <div class="typstify-modal-content" on:click=on_content_click>
             <div class="typstify-modal-header">
-              <SearchBox query=query loading=loading />
+              <SearchBox query=query loading=loading suggestions=suggestions />
               <button
                 class="typstify-modal-close"
                 on:click=move |_| open.set(false)
@@ -247,6 +315,18 @@ mod tests {
         assert!(item.description.is_none());
     }
 
+    #[test]
+    fn test_search_suggestion_from_engine_json() {
+        let suggestion: SearchSuggestion = serde_json::from_str(
+            r#"{"kind":"tag","name":"rust","count":3,"query":"async tag:rust "}"#,
+        )
+        .unwrap();
+
+        assert_eq!(suggestion.name, "rust");
+        assert_eq!(suggestion.query, "async tag:rust ");
+        assert_eq!(suggestion.label(), "Filter by tag");
+    }
+
     #[test]
     fn test_search_result_serialization() {
         let item = SearchResultItem {